@@ -20,6 +20,7 @@ cfg_if! {
 
 struct ClientApiConnectionInner {
     comproc: CommandProcessor,
+    token: Option<String>,
     request_sender: Option<flume::Sender<String>>,
     disconnector: Option<StopSource>,
     disconnect_requested: bool,
@@ -33,10 +34,11 @@ pub struct ClientApiConnection {
 }
 
 impl ClientApiConnection {
-    pub fn new(comproc: CommandProcessor) -> Self {
+    pub fn new(comproc: CommandProcessor, token: Option<String>) -> Self {
         Self {
             inner: Arc::new(Mutex::new(ClientApiConnectionInner {
                 comproc,
+                token,
                 request_sender: None,
                 disconnector: None,
                 disconnect_requested: false,
@@ -109,6 +111,12 @@ impl ClientApiConnection {
             "ValueChange" => {
                 comproc.update_value_change(&update);
             }
+            "InboxChange" => {
+                comproc.update_inbox_change(&update);
+            }
+            "ValueSync" => {
+                comproc.update_value_sync(&update);
+            }
             _ => {
                 comproc.log_message(Level::Error, &format!("unknown update kind: {}", update));
             }
@@ -188,6 +196,12 @@ impl ClientApiConnection {
         // Request initial server state
         let capi = self.clone();
         spawn_detached_local("get initial server state", async move {
+            // Authenticate first if we have a token, the server processes requests in parallel
+            if let Err(e) = capi.server_authenticate().await {
+                error!("failed to authenticate: {}", e);
+                return;
+            }
+
             let mut req = json::JsonValue::new_object();
             req["op"] = "GetState".into();
             let Some(resp) = capi.perform_request(req).await else {
@@ -345,6 +359,25 @@ impl ClientApiConnection {
         Ok(())
     }
 
+    async fn server_authenticate(&self) -> Result<(), String> {
+        trace!("ClientApiConnection::server_authenticate");
+        let Some(token) = self.inner.lock().token.clone() else {
+            return Ok(());
+        };
+        let mut req = json::JsonValue::new_object();
+        req["op"] = "Control".into();
+        req["args"] = json::JsonValue::new_array();
+        req["args"].push("Authenticate").unwrap();
+        req["args"].push(token).unwrap();
+        let Some(resp) = self.perform_request(req).await else {
+            return Err("Cancelled".to_owned());
+        };
+        if resp.has_key("error") {
+            return Err(resp["error"].to_string());
+        }
+        Ok(())
+    }
+
     pub async fn server_shutdown(&self) -> Result<(), String> {
         trace!("ClientApiConnection::server_shutdown");
         let mut req = json::JsonValue::new_object();
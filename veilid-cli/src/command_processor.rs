@@ -504,6 +504,13 @@ Server Debug Commands:
             self.inner().ui_sender.add_node_event(Level::Info, &out);
         }
     }
+    pub fn update_inbox_change(&self, inbox_change: &json::JsonValue) {
+        let out = format!(
+            "Stable inbox {} routes: {:?}",
+            inbox_change["key"], inbox_change["route_ids"]
+        );
+        self.inner().ui_sender.add_node_event(Level::Info, &out);
+    }
     pub fn update_value_change(&self, value_change: &json::JsonValue) {
         let data = json_str_vec_u8(&value_change["value"]["data"]);
         let (datastr, truncated) = Self::print_json_str_vec_u8(&data);
@@ -520,6 +527,20 @@ Server Debug Commands:
         );
         self.inner().ui_sender.add_node_event(Level::Info, &out);
     }
+    pub fn update_value_sync(&self, value_sync: &json::JsonValue) {
+        let out = format!(
+            "Value sync: key={} state={} subkeys={}",
+            value_sync["key"].dump(),
+            value_sync["state"].dump(),
+            value_sync["subkeys"].dump(),
+        );
+        let level = if value_sync["state"] == "Conflict" {
+            Level::Warn
+        } else {
+            Level::Info
+        };
+        self.inner().ui_sender.add_node_event(level, &out);
+    }
 
     pub fn update_log(&self, log: &json::JsonValue) {
         let log_level =
@@ -41,6 +41,9 @@ struct CmdlineArgs {
     /// Address to connect to
     #[arg(long, short = 'a')]
     address: Option<String>,
+    /// Client API token to authenticate with, if the server requires it
+    #[arg(long)]
+    token: Option<String>,
     /// Wait for debugger to attach
     #[arg(long)]
     wait_for_debug: bool,
@@ -311,7 +314,10 @@ fn main() -> Result<(), String> {
 
         // Create client api client side
         info!("Starting API connection");
-        let capi = client_api_connection::ClientApiConnection::new(comproc.clone());
+        let capi = client_api_connection::ClientApiConnection::new(
+            comproc.clone(),
+            args.token.or(settings.token.clone()),
+        );
 
         // Save client api in command processor
         comproc.set_client_api_connection(capi.clone());
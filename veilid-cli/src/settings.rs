@@ -222,6 +222,7 @@ pub struct Settings {
     pub ipc_path: Option<PathBuf>,
     pub enable_network: bool,
     pub address: Option<NamedSocketAddrs>,
+    pub token: Option<String>,
     pub autoconnect: bool,
     pub autoreconnect: bool,
     pub logging: Logging,
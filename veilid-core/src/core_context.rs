@@ -396,6 +396,59 @@ pub async fn api_startup_config(
     Ok(veilid_api)
 }
 
+/// Restore a backup made with [VeilidAPI::export_backup] into a fresh data directory.
+///
+/// Must be called before [api_startup] for the same 'program_name + namespace' combination,
+/// and will fail if that node is running or already has any stored state.
+///
+/// * `update_callback` - called with any log messages produced while restoring.
+/// * `config_callback` - supplies the configuration of the node that is to be started with the restored state.
+/// * `archive` - the backup archive.
+/// * `password` - the password the backup archive was encrypted with.
+#[instrument(level = "trace", target = "core_context", err, skip_all)]
+pub async fn api_restore_backup(
+    update_callback: UpdateCallback,
+    config_callback: ConfigCallback,
+    archive: Vec<u8>,
+    password: String,
+) -> VeilidAPIResult<()> {
+    // Set up config from callback
+    let mut config = VeilidConfig::new();
+    config.setup(config_callback, update_callback)?;
+    let init_key = {
+        let c = config.get();
+        (c.program_name.clone(), c.namespace.clone())
+    };
+
+    // Hold the lock so the node can't be started while we are restoring into it
+    let initialized_lock = INITIALIZED.lock().await;
+    if initialized_lock.contains(&init_key) {
+        apibail_already_initialized!();
+    }
+
+    // Only the stores are needed, and the crypto system is used without initializing it
+    // so that nothing other than the backup gets written to the table store
+    let protected_store = ProtectedStore::new(config.clone());
+    protected_store
+        .init()
+        .await
+        .map_err(VeilidAPIError::generic)?;
+    let table_store = TableStore::new(config.clone(), protected_store.clone());
+    let crypto = Crypto::new(config.clone(), table_store.clone());
+    table_store.set_crypto(crypto);
+    let res = match table_store.init().await {
+        Ok(()) => {
+            let res = table_store.import_backup(&archive, &password).await;
+            table_store.terminate().await;
+            res
+        }
+        Err(e) => Err(VeilidAPIError::generic(e)),
+    };
+    protected_store.terminate().await;
+
+    res
+}
+
 #[instrument(level = "trace", target = "core_context", skip_all)]
 pub(crate) async fn api_shutdown(context: VeilidCoreContext) {
     let mut initialized_lock = INITIALIZED.lock().await;
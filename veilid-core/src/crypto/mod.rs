@@ -11,6 +11,8 @@ pub mod none;
 pub mod tests;
 #[cfg(feature = "enable-crypto-vld0")]
 pub mod vld0;
+#[cfg(feature = "enable-crypto-vld1")]
+pub mod vld1;
 
 pub use blake3digest512::*;
 
@@ -23,6 +25,8 @@ pub use types::*;
 pub use none::*;
 #[cfg(feature = "enable-crypto-vld0")]
 pub use vld0::*;
+#[cfg(feature = "enable-crypto-vld1")]
+pub use vld1::*;
 
 use super::*;
 use core::convert::TryInto;
@@ -34,10 +38,22 @@ use hashlink::LruCache;
 pub type CryptoSystemVersion = Arc<dyn CryptoSystem + Send + Sync>;
 
 cfg_if! {
-    if #[cfg(all(feature = "enable-crypto-none", feature = "enable-crypto-vld0"))] {
+    if #[cfg(all(feature = "enable-crypto-none", feature = "enable-crypto-vld0", feature = "enable-crypto-vld1"))] {
+        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
+        pub const VALID_CRYPTO_KINDS: [CryptoKind; 3] = [CRYPTO_KIND_VLD0, CRYPTO_KIND_VLD1, CRYPTO_KIND_NONE];
+    }
+    else if #[cfg(all(feature = "enable-crypto-vld0", feature = "enable-crypto-vld1"))] {
+        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
+        pub const VALID_CRYPTO_KINDS: [CryptoKind; 2] = [CRYPTO_KIND_VLD0, CRYPTO_KIND_VLD1];
+    }
+    else if #[cfg(all(feature = "enable-crypto-none", feature = "enable-crypto-vld0"))] {
         /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
         pub const VALID_CRYPTO_KINDS: [CryptoKind; 2] = [CRYPTO_KIND_VLD0, CRYPTO_KIND_NONE];
     }
+    else if #[cfg(all(feature = "enable-crypto-none", feature = "enable-crypto-vld1"))] {
+        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
+        pub const VALID_CRYPTO_KINDS: [CryptoKind; 2] = [CRYPTO_KIND_VLD1, CRYPTO_KIND_NONE];
+    }
     else if #[cfg(feature = "enable-crypto-none")] {
         /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
         pub const VALID_CRYPTO_KINDS: [CryptoKind; 1] = [CRYPTO_KIND_NONE];
@@ -46,6 +62,10 @@ cfg_if! {
         /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
         pub const VALID_CRYPTO_KINDS: [CryptoKind; 1] = [CRYPTO_KIND_VLD0];
     }
+    else if #[cfg(feature = "enable-crypto-vld1")] {
+        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
+        pub const VALID_CRYPTO_KINDS: [CryptoKind; 1] = [CRYPTO_KIND_VLD1];
+    }
     else {
         compile_error!("No crypto kinds enabled, specify an enable-crypto- feature");
     }
@@ -74,6 +94,8 @@ struct CryptoInner {
     flush_future: Option<SendPinBoxFuture<()>>,
     #[cfg(feature = "enable-crypto-vld0")]
     crypto_vld0: Option<Arc<dyn CryptoSystem + Send + Sync>>,
+    #[cfg(feature = "enable-crypto-vld1")]
+    crypto_vld1: Option<Arc<dyn CryptoSystem + Send + Sync>>,
     #[cfg(feature = "enable-crypto-none")]
     crypto_none: Option<Arc<dyn CryptoSystem + Send + Sync>>,
 }
@@ -97,6 +119,8 @@ impl Crypto {
             flush_future: None,
             #[cfg(feature = "enable-crypto-vld0")]
             crypto_vld0: None,
+            #[cfg(feature = "enable-crypto-vld1")]
+            crypto_vld1: None,
             #[cfg(feature = "enable-crypto-none")]
             crypto_none: None,
         }
@@ -116,6 +140,11 @@ impl Crypto {
             out.inner.lock().crypto_vld0 = Some(Arc::new(vld0::CryptoSystemVLD0::new(out.clone())));
         }
 
+        #[cfg(feature = "enable-crypto-vld1")]
+        {
+            out.inner.lock().crypto_vld1 = Some(Arc::new(vld1::CryptoSystemVLD1::new(out.clone())));
+        }
+
         #[cfg(feature = "enable-crypto-none")]
         {
             out.inner.lock().crypto_none = Some(Arc::new(none::CryptoSystemNONE::new(out.clone())));
@@ -226,6 +255,8 @@ impl Crypto {
         match kind {
             #[cfg(feature = "enable-crypto-vld0")]
             CRYPTO_KIND_VLD0 => Some(inner.crypto_vld0.clone().unwrap()),
+            #[cfg(feature = "enable-crypto-vld1")]
+            CRYPTO_KIND_VLD1 => Some(inner.crypto_vld1.clone().unwrap()),
             #[cfg(feature = "enable-crypto-none")]
             CRYPTO_KIND_NONE => Some(inner.crypto_none.clone().unwrap()),
             _ => None,
@@ -292,6 +323,11 @@ impl Crypto {
             let kp = vld0_generate_keypair();
             return Ok(TypedKeyPair::new(crypto_kind, kp));
         }
+        #[cfg(feature = "enable-crypto-vld1")]
+        if crypto_kind == CRYPTO_KIND_VLD1 {
+            let kp = vld1_generate_keypair();
+            return Ok(TypedKeyPair::new(crypto_kind, kp));
+        }
         #[cfg(feature = "enable-crypto-none")]
         if crypto_kind == CRYPTO_KIND_NONE {
             let kp = none_generate_keypair();
@@ -224,6 +224,23 @@ pub async fn test_generation(vcrypto: CryptoSystemVersion) {
         .expect_err("should reject long salt");
 }
 
+#[cfg(all(feature = "enable-crypto-vld0", feature = "enable-crypto-vld1"))]
+pub async fn test_cross_kind(crypto: Crypto) {
+    trace!("test_cross_kind");
+
+    let vld0 = crypto.get(CRYPTO_KIND_VLD0).unwrap();
+    let vld1 = crypto.get(CRYPTO_KIND_VLD1).unwrap();
+
+    // A signature from one cryptosystem never verifies with the other
+    for (signer, verifier) in [(vld0.clone(), vld1.clone()), (vld1, vld0)] {
+        let kp = signer.generate_keypair();
+        let sig = signer.sign(&kp.key, &kp.secret, LOREM_IPSUM).unwrap();
+        assert_eq!(signer.verify(&kp.key, LOREM_IPSUM, &sig), Ok(true));
+        assert_ne!(verifier.verify(&kp.key, LOREM_IPSUM, &sig), Ok(true));
+        assert!(!verifier.validate_keypair(&kp.key, &kp.secret));
+    }
+}
+
 pub async fn test_all() {
     let api = crypto_tests_startup().await;
     let crypto = api.crypto().unwrap();
@@ -236,6 +253,8 @@ pub async fn test_all() {
         test_dh(vcrypto.clone()).await;
         test_generation(vcrypto).await;
     }
+    #[cfg(all(feature = "enable-crypto-vld0", feature = "enable-crypto-vld1"))]
+    test_cross_kind(crypto).await;
 
     crypto_tests_shutdown(api.clone()).await;
     assert!(api.is_shutdown());
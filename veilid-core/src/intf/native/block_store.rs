@@ -1,9 +1,20 @@
 use crate::*;
+use keyvaluedb_sqlite::*;
+use std::path::PathBuf;
+
+/// The name of the block store database
+const BLOCK_STORE_TABLE: &str = "block_store";
+/// The column holding block data, keyed by block id
+const COLUMN_BLOCKS: u32 = 0;
+/// The number of columns in the block store database
+const BLOCK_STORE_COLUMN_COUNT: u32 = 1;
 
 struct BlockStoreInner {
-    //
+    db: Option<Database>,
 }
 
+/// Persistent content-addressed storage for blocks.
+/// Blocks are keyed by their hash, which callers must verify before storing.
 #[derive(Clone)]
 pub struct BlockStore {
     config: VeilidConfig,
@@ -12,7 +23,7 @@ pub struct BlockStore {
 
 impl BlockStore {
     fn new_inner() -> BlockStoreInner {
-        BlockStoreInner {}
+        BlockStoreInner { db: None }
     }
     pub fn new(config: VeilidConfig) -> Self {
         Self {
@@ -21,12 +32,95 @@ impl BlockStore {
         }
     }
 
+    fn get_dbpath(&self) -> EyreResult<PathBuf> {
+        let c = self.config.get();
+        let blockstoredir = c.block_store.directory.clone();
+        std::fs::create_dir_all(&blockstoredir)?;
+
+        let dbpath: PathBuf = if c.namespace.is_empty() {
+            [blockstoredir, String::from(BLOCK_STORE_TABLE)]
+                .iter()
+                .collect()
+        } else {
+            [
+                blockstoredir,
+                format!("{}_{}", c.namespace, BLOCK_STORE_TABLE),
+            ]
+            .iter()
+            .collect()
+        };
+        Ok(dbpath)
+    }
+
     pub async fn init(&self) -> EyreResult<()> {
+        let dbpath = self.get_dbpath()?;
+
+        let delete = {
+            let c = self.config.get();
+            c.block_store.delete
+        };
+        if delete && dbpath.exists() {
+            std::fs::remove_file(&dbpath)?;
+            log_stor!(debug "deleted block store at path '{:?}'", dbpath);
+        }
+
         // Ensure permissions are correct
-        // ensure_file_private_owner(&dbpath)?;
+        ensure_file_private_owner(&dbpath).map_err(|e| eyre!("{}", e))?;
+
+        let cfg = DatabaseConfig::with_columns(BLOCK_STORE_COLUMN_COUNT);
+        let db = Database::open(&dbpath, cfg).wrap_err("failed to open block store")?;
+
+        // Ensure permissions are correct
+        ensure_file_private_owner(&dbpath).map_err(|e| eyre!("{}", e))?;
+
+        log_stor!(debug "opened block store at path '{:?}'", dbpath);
 
+        self.inner.lock().db = Some(db);
         Ok(())
     }
 
-    pub async fn terminate(&self) {}
+    pub async fn terminate(&self) {
+        *self.inner.lock() = Self::new_inner();
+    }
+
+    fn get_db(&self) -> VeilidAPIResult<Database> {
+        let inner = self.inner.lock();
+        let Some(db) = inner.db.clone() else {
+            apibail_not_initialized!();
+        };
+        Ok(db)
+    }
+
+    fn block_key(block_id: TypedKey) -> [u8; PUBLIC_KEY_LENGTH + 4] {
+        let mut bytes = [0u8; PUBLIC_KEY_LENGTH + 4];
+        bytes[0..4].copy_from_slice(&block_id.kind.0);
+        bytes[4..PUBLIC_KEY_LENGTH + 4].copy_from_slice(&block_id.value.bytes);
+        bytes
+    }
+
+    /// Load a block's data if it is held locally
+    pub async fn load_block(&self, block_id: TypedKey) -> VeilidAPIResult<Option<Vec<u8>>> {
+        let db = self.get_db()?;
+        db.get(COLUMN_BLOCKS, &Self::block_key(block_id))
+            .await
+            .map_err(VeilidAPIError::from)
+    }
+
+    /// Save a block's data, replacing any existing copy
+    pub async fn save_block(&self, block_id: TypedKey, data: &[u8]) -> VeilidAPIResult<()> {
+        let db = self.get_db()?;
+        let mut dbt = db.transaction();
+        dbt.put(COLUMN_BLOCKS, &Self::block_key(block_id), data);
+        db.write(dbt).await.map_err(VeilidAPIError::generic)
+    }
+
+    /// Delete a block, returning true if it was held locally
+    pub async fn delete_block(&self, block_id: TypedKey) -> VeilidAPIResult<bool> {
+        let db = self.get_db()?;
+        let old = db
+            .delete(COLUMN_BLOCKS, &Self::block_key(block_id))
+            .await
+            .map_err(VeilidAPIError::from)?;
+        Ok(old.is_some())
+    }
 }
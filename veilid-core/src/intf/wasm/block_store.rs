@@ -1,9 +1,19 @@
 use crate::*;
+use keyvaluedb_web::*;
+
+/// The name of the block store database
+const BLOCK_STORE_TABLE: &str = "block_store";
+/// The column holding block data, keyed by block id
+const COLUMN_BLOCKS: u32 = 0;
+/// The number of columns in the block store database
+const BLOCK_STORE_COLUMN_COUNT: u32 = 1;
 
 struct BlockStoreInner {
-    //
+    db: Option<Database>,
 }
 
+/// Persistent content-addressed storage for blocks.
+/// Blocks are keyed by their hash, which callers must verify before storing.
 #[derive(Clone)]
 pub struct BlockStore {
     config: VeilidConfig,
@@ -12,7 +22,7 @@ pub struct BlockStore {
 
 impl BlockStore {
     fn new_inner() -> BlockStoreInner {
-        BlockStoreInner {}
+        BlockStoreInner { db: None }
     }
     pub fn new(config: VeilidConfig) -> Self {
         Self {
@@ -21,9 +31,78 @@ impl BlockStore {
         }
     }
 
+    fn get_namespaced_table_name(&self) -> String {
+        let c = self.config.get();
+        if c.namespace.is_empty() {
+            BLOCK_STORE_TABLE.to_owned()
+        } else {
+            format!("{}_{}", c.namespace, BLOCK_STORE_TABLE)
+        }
+    }
+
     pub async fn init(&self) -> EyreResult<()> {
+        let table_name = self.get_namespaced_table_name();
+
+        let delete = {
+            let c = self.config.get();
+            c.block_store.delete
+        };
+        if delete && is_browser() && Database::delete(&table_name).await.is_ok() {
+            log_stor!(debug "deleted block store '{}'", table_name);
+        }
+
+        let db = Database::open(&table_name, BLOCK_STORE_COLUMN_COUNT, false)
+            .await
+            .map_err(|e| eyre!("failed to open block store: {}", e))?;
+
+        log_stor!(debug "opened block store '{}'", table_name);
+
+        self.inner.lock().db = Some(db);
         Ok(())
     }
 
-    pub async fn terminate(&self) {}
+    pub async fn terminate(&self) {
+        *self.inner.lock() = Self::new_inner();
+    }
+
+    fn get_db(&self) -> VeilidAPIResult<Database> {
+        let inner = self.inner.lock();
+        let Some(db) = inner.db.clone() else {
+            apibail_not_initialized!();
+        };
+        Ok(db)
+    }
+
+    fn block_key(block_id: TypedKey) -> [u8; PUBLIC_KEY_LENGTH + 4] {
+        let mut bytes = [0u8; PUBLIC_KEY_LENGTH + 4];
+        bytes[0..4].copy_from_slice(&block_id.kind.0);
+        bytes[4..PUBLIC_KEY_LENGTH + 4].copy_from_slice(&block_id.value.bytes);
+        bytes
+    }
+
+    /// Load a block's data if it is held locally
+    pub async fn load_block(&self, block_id: TypedKey) -> VeilidAPIResult<Option<Vec<u8>>> {
+        let db = self.get_db()?;
+        db.get(COLUMN_BLOCKS, &Self::block_key(block_id))
+            .await
+            .map_err(VeilidAPIError::from)
+    }
+
+    /// Save a block's data, replacing any existing copy
+    pub async fn save_block(&self, block_id: TypedKey, data: &[u8]) -> VeilidAPIResult<()> {
+        let db = self.get_db()?;
+        let mut dbt = db.transaction();
+        dbt.put(COLUMN_BLOCKS, &Self::block_key(block_id), data);
+        db.write(dbt).await.map_err(VeilidAPIError::generic)
+    }
+
+    /// Delete a block, returning true if it was held locally
+    pub async fn delete_block(&self, block_id: TypedKey) -> VeilidAPIResult<bool> {
+        let db = self.get_db()?;
+        let old = db
+            .delete(COLUMN_BLOCKS, &Self::block_key(block_id))
+            .await
+            .map_err(VeilidAPIError::from)?;
+        Ok(old.is_some())
+    }
 }
@@ -58,7 +58,9 @@ mod veilid_api;
 mod veilid_config;
 mod wasm_helpers;
 
-pub use self::core_context::{api_startup, api_startup_config, api_startup_json, UpdateCallback};
+pub use self::core_context::{
+    api_restore_backup, api_startup, api_startup_config, api_startup_json, UpdateCallback,
+};
 pub use self::logging::{
     ApiTracingLayer, VeilidLayerFilter, DEFAULT_LOG_FACILITIES_ENABLED_LIST,
     DEFAULT_LOG_FACILITIES_IGNORE_LIST, DURATION_LOG_FACILITIES, FLAME_LOG_FACILITIES_IGNORE_LIST,
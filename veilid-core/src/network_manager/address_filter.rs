@@ -270,6 +270,32 @@ impl AddressFilter {
             .or_insert(ts);
     }
 
+    pub fn debug_punishment_list(&self) -> Vec<DebugPunishment> {
+        let inner = self.inner.lock();
+        let to_debug = |target: String, punishment: &Punishment| DebugPunishment {
+            target,
+            reason: format!("{:?}", punishment.reason),
+            timestamp: punishment.timestamp,
+        };
+        inner
+            .punishments_by_ip4
+            .iter()
+            .map(|(k, v)| to_debug(k.to_string(), v))
+            .chain(
+                inner
+                    .punishments_by_ip6_prefix
+                    .iter()
+                    .map(|(k, v)| to_debug(k.to_string(), v)),
+            )
+            .chain(
+                inner
+                    .punishments_by_node_id
+                    .iter()
+                    .map(|(k, v)| to_debug(k.to_string(), v)),
+            )
+            .collect()
+    }
+
     pub fn clear_punishments(&self) {
         let mut inner = self.inner.lock();
         inner.punishments_by_ip4.clear();
@@ -404,6 +404,42 @@ impl ConnectionManager {
         self.on_new_protocol_network_connection(inner, prot_conn)
     }
 
+    /// Called by low-level network when a connection that was negotiated out of band, such as by signaling,
+    /// has been established and must be registered before it can be used
+    pub(super) async fn register_negotiated_connection(
+        &self,
+        prot_conn: ProtocolNetworkConnection,
+    ) -> EyreResult<NetworkResult<ConnectionHandle>> {
+        let Ok(_guard) = self.arc.startup_lock.enter() else {
+            return Ok(NetworkResult::service_unavailable(
+                "connection manager is not started",
+            ));
+        };
+
+        // Async lock on the remote address for atomicity per remote
+        let _lock_guard = self
+            .arc
+            .address_lock_table
+            .lock_tag(prot_conn.flow().remote_address().socket_addr())
+            .await;
+
+        log_net!(
+            "== register_negotiated_connection flow={:?}",
+            prot_conn.flow()
+        );
+
+        // Add to the connection table
+        let mut inner = self.arc.inner.lock();
+        let inner = match &mut *inner {
+            Some(v) => v,
+            None => {
+                bail!("shutting down");
+            }
+        };
+
+        self.on_new_protocol_network_connection(inner, prot_conn)
+    }
+
     ///////////////////////////////////////////////////////////////////////////////////////////////////////
     /// Asynchronous Event Processor
 
@@ -56,6 +56,7 @@ impl ConnectionTable {
                 c.network.protocol.tcp.max_connections as usize,
                 c.network.protocol.ws.max_connections as usize,
                 c.network.protocol.wss.max_connections as usize,
+                c.network.protocol.wrtc.max_connections as usize,
             ]
         };
         Self {
@@ -65,6 +66,7 @@ impl ConnectionTable {
                     LruCache::new_unbounded(),
                     LruCache::new_unbounded(),
                     LruCache::new_unbounded(),
+                    LruCache::new_unbounded(),
                 ],
                 protocol_index_by_id: BTreeMap::new(),
                 id_by_flow: BTreeMap::new(),
@@ -79,6 +81,7 @@ impl ConnectionTable {
             ProtocolType::TCP => 0,
             ProtocolType::WS => 1,
             ProtocolType::WSS => 2,
+            ProtocolType::WRTC => 3,
             ProtocolType::UDP => panic!("not a connection-oriented protocol"),
         }
     }
@@ -88,6 +91,7 @@ impl ConnectionTable {
             0 => ProtocolType::TCP,
             1 => ProtocolType::WS,
             2 => ProtocolType::WSS,
+            3 => ProtocolType::WRTC,
             _ => panic!("not a connection-oriented protocol"),
         }
     }
@@ -122,13 +126,15 @@ impl ConnectionTable {
         let inner = self.inner.lock();
 
         let protocol_type = dial_info.protocol_type();
-        let low_level_protocol_type = protocol_type.low_level_protocol_type();
+        let Some(low_level_protocol_type) = protocol_type.low_level_protocol_type() else {
+            return false;
+        };
 
         // check protocol types
         let mut check_protocol_types = ProtocolTypeSet::empty();
         for check_pt in ProtocolTypeSet::all().iter() {
             if check_pt != protocol_type
-                && check_pt.low_level_protocol_type() == low_level_protocol_type
+                && check_pt.low_level_protocol_type() == Some(low_level_protocol_type)
             {
                 check_protocol_types.insert(check_pt);
             }
@@ -6,14 +6,28 @@ impl NetworkManager {
     pub(crate) async fn handle_boot_request(&self, flow: Flow) -> EyreResult<NetworkResult<()>> {
         let routing_table = self.routing_table();
 
+        // Reply with peers from the routing domain the request came in on
+        let routing_domain = routing_table
+            .routing_domain_for_address(flow.remote_address().address())
+            .unwrap_or(RoutingDomain::PublicInternet)
+            .peer_routing_domain();
+
         // Get a bunch of nodes with the various
-        let bootstrap_nodes = routing_table.find_bootstrap_nodes_filtered(2);
+        let bootstrap_nodes = routing_table.find_bootstrap_nodes_filtered(routing_domain, 2);
 
         // Serialize out peer info
-        let bootstrap_peerinfo: Vec<PeerInfo> = bootstrap_nodes
+        let mut bootstrap_peerinfo: Vec<PeerInfo> = bootstrap_nodes
             .iter()
-            .filter_map(|nr| nr.make_peer_info(RoutingDomain::PublicInternet))
+            .filter_map(|nr| nr.make_peer_info(routing_domain))
             .collect();
+
+        // Private overlays may be too small to have other bootstrap nodes,
+        // so include ourselves so the requester can always find at least one peer
+        if routing_domain == RoutingDomain::PrivateOverlay
+            && routing_table.has_valid_network_class(routing_domain)
+        {
+            bootstrap_peerinfo.insert(0, routing_table.get_own_peer_info(routing_domain));
+        }
         let json_bytes = serialize_json(bootstrap_peerinfo).as_bytes().to_vec();
 
         // Reply with a chunk of signed routing table
@@ -62,6 +62,7 @@ pub const PUBLIC_ADDRESS_INCONSISTENCY_TIMEOUT_US: TimestampDuration =
 pub const PUBLIC_ADDRESS_INCONSISTENCY_PUNISHMENT_TIMEOUT_US: TimestampDuration =
     TimestampDuration::new(3_600_000_000_u64); // 60 minutes
 pub const ADDRESS_FILTER_TASK_INTERVAL_SECS: u32 = 60;
+pub const TCP_HOLE_PUNCH_RETRY_DELAY_MS: u32 = 250;
 pub const BOOT_MAGIC: &[u8; 4] = b"BOOT";
 
 #[derive(Clone, Debug, Default)]
@@ -114,6 +115,8 @@ pub(crate) enum NodeContactMethod {
     SignalReverse(NodeRef, NodeRef),
     /// Request via signal the node negotiate a hole punch (relay, target)
     SignalHolePunch(NodeRef, NodeRef),
+    /// Request via signal the node negotiate a WebRTC connection (relay, target)
+    SignalWebRTC(NodeRef, NodeRef),
     /// Must use an inbound relay to reach the node
     InboundRelay(NodeRef),
     /// Must use outbound relay to reach the node
@@ -803,7 +806,11 @@ impl NetworkManager {
                     .await
                     .wrap_err("rpc failure")
             }
-            SignalInfo::HolePunch { receipt, peer_info } => {
+            SignalInfo::HolePunch {
+                receipt,
+                peer_info,
+                protocol_type,
+            } => {
                 let routing_table = self.routing_table();
                 let rpc = self.rpc_processor();
 
@@ -822,10 +829,10 @@ impl NetworkManager {
                     }
                 };
 
-                // Get the udp direct dialinfo for the hole punch
+                // Get the udp or tcp direct dialinfo for the hole punch
                 let outbound_nrf = routing_table
                     .get_outbound_node_ref_filter(RoutingDomain::PublicInternet)
-                    .with_protocol_type(ProtocolType::UDP);
+                    .with_protocol_type(protocol_type);
                 peer_nr.set_filter(Some(outbound_nrf));
                 let Some(hole_punch_dial_info_detail) = peer_nr.first_filtered_dial_info_detail()
                 else {
@@ -841,16 +848,39 @@ impl NetworkManager {
                     filter.with_address_type(hole_punch_dial_info_detail.dial_info.address_type());
                 peer_nr.set_filter(Some(filter));
 
-                // Do our half of the hole punch by sending an empty packet
-                // Both sides will do this and then the receipt will get sent over the punched hole
-                let unique_flow = network_result_try!(
-                    self.net()
-                        .send_data_to_dial_info(
+                let unique_flow = if protocol_type == ProtocolType::TCP {
+                    // Do our half of the hole punch by connecting to the peer while it connects to us
+                    // The simultaneous open makes the connection the receipt will get sent over
+                    let Some(stop_token) = self.unlocked_inner.startup_lock.stop_token() else {
+                        return Ok(NetworkResult::service_unavailable("network is stopping"));
+                    };
+                    let hole_punch_timeout = TimestampDuration::new_ms(
+                        self.unlocked_inner
+                            .config
+                            .get()
+                            .network
+                            .hole_punch_receipt_time_ms as u64,
+                    );
+                    network_result_try!(
+                        self.connect_tcp_hole_punch(
                             hole_punch_dial_info_detail.dial_info.clone(),
-                            Vec::new(),
+                            hole_punch_timeout,
+                            stop_token,
                         )
                         .await?
-                );
+                    )
+                } else {
+                    // Do our half of the hole punch by sending an empty packet
+                    // Both sides will do this and then the receipt will get sent over the punched hole
+                    network_result_try!(
+                        self.net()
+                            .send_data_to_dial_info(
+                                hole_punch_dial_info_detail.dial_info.clone(),
+                                Vec::new(),
+                            )
+                            .await?
+                    )
+                };
 
                 // XXX: do we need a delay here? or another hole punch packet?
 
@@ -862,6 +892,96 @@ impl NetworkManager {
                     .await
                     .wrap_err("rpc failure")
             }
+            SignalInfo::WebRTCOffer {
+                receipt,
+                sdp,
+                peer_info,
+            } => {
+                let routing_table = self.routing_table();
+                let rpc = self.rpc_processor();
+
+                // Our answer goes back through the relay of the node making the offer
+                let Some(relay_peer_info) = peer_info.signed_node_info().relay_peer_info() else {
+                    return Ok(NetworkResult::invalid_message(
+                        "webrtc offer sender has no relay",
+                    ));
+                };
+
+                // Add the peer info and its relay to our routing table
+                let mut peer_nr = match routing_table.register_node_with_peer_info(
+                    RoutingDomain::PublicInternet,
+                    peer_info,
+                    false,
+                ) {
+                    Ok(nr) => nr,
+                    Err(e) => {
+                        return Ok(NetworkResult::invalid_message(format!(
+                            "unable to register webrtc offer peerinfo: {}",
+                            e
+                        )));
+                    }
+                };
+                let relay_nr = match routing_table.register_node_with_peer_info(
+                    RoutingDomain::PublicInternet,
+                    relay_peer_info,
+                    false,
+                ) {
+                    Ok(nr) => nr,
+                    Err(e) => {
+                        return Ok(NetworkResult::invalid_message(format!(
+                            "unable to register webrtc offer relay peerinfo: {}",
+                            e
+                        )));
+                    }
+                };
+
+                // Make our answer to the offer and signal it back
+                let answer_sdp = network_result_try!(
+                    self.net().accept_webrtc_offer(receipt.clone(), sdp).await?
+                );
+                let res = rpc
+                    .clone()
+                    .rpc_call_signal(
+                        Destination::relay(relay_nr, peer_nr.clone()),
+                        SignalInfo::WebRTCAnswer {
+                            receipt: receipt.clone(),
+                            sdp: answer_sdp,
+                        },
+                    )
+                    .await
+                    .wrap_err("rpc failure");
+                if !matches!(res, Ok(NetworkResult::Value(()))) {
+                    self.net().cancel_webrtc(&receipt);
+                }
+                network_result_try!(res?);
+
+                // Wait for the connection to open
+                let flow = network_result_try!(self.net().connect_webrtc(receipt.clone()).await?);
+
+                // Set the new connection as our 'last connection' to ensure we return the receipt over it
+                peer_nr.set_filter(Some(
+                    NodeRefFilter::new().with_protocol_type(ProtocolType::WRTC),
+                ));
+                peer_nr.set_last_flow(flow, Timestamp::now());
+
+                // Return the receipt over the new connection
+                rpc.rpc_call_return_receipt(Destination::direct(peer_nr), receipt)
+                    .await
+                    .wrap_err("rpc failure")
+            }
+            SignalInfo::WebRTCAnswer { receipt, sdp } => {
+                // Apply the answer to the offer we made, and wait for the connection to open
+                // The receipt will be returned to us over it once it does
+                network_result_try!(
+                    self.net()
+                        .complete_webrtc_offer(receipt.clone(), sdp)
+                        .await?
+                );
+                let flow = network_result_try!(self.net().connect_webrtc(receipt).await?);
+                log_net!(debug "webrtc connection established: {:?}", flow);
+
+                Ok(NetworkResult::value(()))
+            }
         }
     }
 
@@ -1,5 +1,5 @@
 /// Context detection of public dial info for a single protocol and address type
-/// Also performs UPNP/IGD mapping if enabled and possible
+/// Also performs UPNP/IGD or PCP/NAT-PMP mapping if enabled and possible
 use super::*;
 use futures_util::stream::FuturesUnordered;
 
@@ -329,7 +329,7 @@ impl DiscoveryContext {
     #[instrument(level = "trace", skip(self), ret)]
     async fn try_upnp_port_mapping(&self) -> Option<DialInfo> {
         let protocol_type = self.unlocked_inner.protocol_type;
-        let low_level_protocol_type = protocol_type.low_level_protocol_type();
+        let low_level_protocol_type = protocol_type.low_level_protocol_type()?;
         let address_type = self.unlocked_inner.address_type;
         let local_port = self.unlocked_inner.port;
         let external_1 = self.inner.lock().external_1.as_ref().unwrap().clone();
@@ -373,7 +373,7 @@ impl DiscoveryContext {
                 }
 
                 if validate_tries != PORT_MAP_VALIDATE_TRY_COUNT {
-                    log_net!(debug "UPNP port mapping succeeded but port {}/{} is still unreachable.\nretrying\n",
+                    log_net!(debug "Port mapping succeeded but port {}/{} is still unreachable.\nretrying\n",
                     local_port, match low_level_protocol_type {
                         LowLevelProtocolType::UDP => "udp",
                         LowLevelProtocolType::TCP => "tcp",
@@ -389,12 +389,12 @@ impl DiscoveryContext {
                 .unmap_port(
                     low_level_protocol_type,
                     address_type,
-                    external_1.address.port(),
+                    mapped_external_address.port(),
                 )
                 .await;
 
             if tries == PORT_MAP_TRY_COUNT {
-                warn!("UPNP port mapping succeeded but port {}/{} is still unreachable.\nYou may need to add a local firewall allowed port on this machine.\n",
+                warn!("Port mapping succeeded but port {}/{} is still unreachable.\nYou may need to add a local firewall allowed port on this machine.\n",
                     local_port, match low_level_protocol_type {
                         LowLevelProtocolType::UDP => "udp",
                         LowLevelProtocolType::TCP => "tcp",
@@ -1,3 +1,4 @@
+use super::pcp::*;
 use super::*;
 use igd::*;
 use std::net::UdpSocket;
@@ -20,13 +21,21 @@ struct PortMapValue {
     ext_ip: IpAddr,
     mapped_port: u16,
     timestamp: Timestamp,
+    lifetime: TimestampDuration,
     renewal_lifetime: TimestampDuration,
     renewal_attempts: u32,
 }
 
+/// A gateway that can map ports, through UPnP IGD or through PCP/NAT-PMP
+#[derive(Clone)]
+enum PortMapGateway {
+    Upnp(Arc<Gateway>),
+    Pcp(Arc<PcpGateway>),
+}
+
 struct IGDManagerInner {
     local_ip_addrs: BTreeMap<AddressType, IpAddr>,
-    gateways: BTreeMap<IpAddr, Arc<Gateway>>,
+    gateways: BTreeMap<IpAddr, PortMapGateway>,
     port_maps: BTreeMap<PortMapKey, PortMapValue>,
 }
 
@@ -117,11 +126,26 @@ impl IGDManager {
     }
 
     #[instrument(level = "trace", target = "net", skip_all)]
-    fn find_gateway(inner: &mut IGDManagerInner, local_ip: IpAddr) -> Option<Arc<Gateway>> {
+    fn find_gateway(inner: &mut IGDManagerInner, local_ip: IpAddr) -> Option<PortMapGateway> {
         if let Some(gw) = inner.gateways.get(&local_ip) {
             return Some(gw.clone());
         }
 
+        // Most IPv4 routers speak UPnP, but PCP is the standard way to open IPv6 pinholes
+        let gw = match local_ip {
+            IpAddr::V4(_) => {
+                Self::find_upnp_gateway(local_ip).or_else(|| Self::find_pcp_gateway(local_ip))
+            }
+            IpAddr::V6(_) => {
+                Self::find_pcp_gateway(local_ip).or_else(|| Self::find_upnp_gateway(local_ip))
+            }
+        }?;
+        inner.gateways.insert(local_ip, gw.clone());
+        Some(gw)
+    }
+
+    #[instrument(level = "trace", target = "net", skip_all)]
+    fn find_upnp_gateway(local_ip: IpAddr) -> Option<PortMapGateway> {
         let gateway = match local_ip {
             IpAddr::V4(v4) => {
                 let mut opts = SearchOptions::new_v4(UPNP_GATEWAY_DETECT_TIMEOUT_MS as u64);
@@ -151,13 +175,30 @@ impl IGDManager {
                 }
             }
         };
-        let gw = Arc::new(gateway);
-        inner.gateways.insert(local_ip, gw.clone());
-        Some(gw)
+        Some(PortMapGateway::Upnp(Arc::new(gateway)))
     }
 
     #[instrument(level = "trace", target = "net", skip_all)]
-    fn get_gateway(inner: &mut IGDManagerInner, local_ip: IpAddr) -> Option<Arc<Gateway>> {
+    fn find_pcp_gateway(local_ip: IpAddr) -> Option<PortMapGateway> {
+        let Some(server_addr) = find_pcp_server_address(local_ip) else {
+            log_net!(debug "couldn't find default gateway for pcp: local_ip={}", local_ip);
+            return None;
+        };
+
+        match PcpGateway::probe(server_addr, local_ip) {
+            Ok(gw) => {
+                log_net!(debug "found {:?} gateway at {}", gw.version(), server_addr);
+                Some(PortMapGateway::Pcp(Arc::new(gw)))
+            }
+            Err(e) => {
+                log_net!(debug "couldn't find pcp gateway at {}: {}", server_addr, e);
+                None
+            }
+        }
+    }
+
+    #[instrument(level = "trace", target = "net", skip_all)]
+    fn get_gateway(inner: &mut IGDManagerInner, local_ip: IpAddr) -> Option<PortMapGateway> {
         if let Some(gw) = inner.gateways.get(&local_ip) {
             return Some(gw.clone());
         }
@@ -173,6 +214,92 @@ impl IGDManager {
         )
     }
 
+    // Map a local port through the gateway
+    // Renewing an existing mapping asks for the same external address, otherwise any port may be mapped
+    // Returns the external address and the lifetime of the mapping
+    fn gateway_map_port(
+        &self,
+        gw: &PortMapGateway,
+        llpt: LowLevelProtocolType,
+        local_addr: SocketAddr,
+        renew_addr: Option<SocketAddr>,
+    ) -> EyreResult<(SocketAddr, TimestampDuration)> {
+        match gw {
+            PortMapGateway::Upnp(gw) => {
+                let desc = self.get_description(llpt, local_addr.port());
+                let lifetime_secs = (UPNP_MAPPING_LIFETIME_MS + 999) / 1000;
+                if let Some(renew_addr) = renew_addr {
+                    gw.add_port(
+                        convert_llpt(llpt),
+                        renew_addr.port(),
+                        local_addr,
+                        lifetime_secs,
+                        &desc,
+                    )
+                    .map_err(|e| eyre!("upnp failed to renew external port: {}", e))?;
+                    return Ok((renew_addr, UPNP_MAPPING_LIFETIME_US));
+                }
+
+                let ext_ip = gw
+                    .get_external_ip()
+                    .map_err(|e| eyre!("couldn't get external ip from igd: {}", e))?;
+                let mapped_port = gw
+                    .add_any_port(convert_llpt(llpt), local_addr, lifetime_secs, &desc)
+                    .map_err(|e| eyre!("upnp failed to map external port: {}", e))?;
+                Ok((
+                    SocketAddr::new(ext_ip, mapped_port),
+                    UPNP_MAPPING_LIFETIME_US,
+                ))
+            }
+            PortMapGateway::Pcp(gw) => {
+                // Prefer keeping the local port number if the gateway has a choice
+                let suggested_port = renew_addr.map(|a| a.port()).unwrap_or(local_addr.port());
+                let mapping = gw
+                    .add_port(
+                        llpt,
+                        local_addr.port(),
+                        suggested_port,
+                        (UPNP_MAPPING_LIFETIME_MS + 999) / 1000,
+                    )
+                    .map_err(|e| eyre!("{:?} failed to map external port: {}", gw.version(), e))?;
+                if mapping.lifetime_secs == 0 {
+                    bail!("{:?} mapped external port with no lifetime", gw.version());
+                }
+                if let Some(renew_addr) = renew_addr {
+                    if mapping.external_address != renew_addr {
+                        bail!(
+                            "{:?} renewed mapping with a different external address: {} vs {}",
+                            gw.version(),
+                            renew_addr,
+                            mapping.external_address
+                        );
+                    }
+                }
+                Ok((
+                    mapping.external_address,
+                    TimestampDuration::new(mapping.lifetime_secs as u64 * 1_000_000u64),
+                ))
+            }
+        }
+    }
+
+    // Remove a port mapping from the gateway
+    fn gateway_unmap_port(
+        gw: &PortMapGateway,
+        llpt: LowLevelProtocolType,
+        local_port: u16,
+        mapped_port: u16,
+    ) -> EyreResult<()> {
+        match gw {
+            PortMapGateway::Upnp(gw) => gw
+                .remove_port(convert_llpt(llpt), mapped_port)
+                .map_err(|e| eyre!("upnp failed to remove external port: {}", e)),
+            PortMapGateway::Pcp(gw) => gw
+                .remove_port(llpt, local_port)
+                .map_err(|e| eyre!("{:?} failed to remove external port: {}", gw.version(), e)),
+        }
+    }
+
     #[instrument(level = "trace", target = "net", skip_all)]
     pub async fn unmap_port(
         &self,
@@ -207,11 +334,11 @@ impl IGDManager {
                 let gw = Self::find_gateway(&mut inner, local_ip)?;
 
                 // Unmap port
-                match gw.remove_port(convert_llpt(llpt), mapped_port) {
+                match Self::gateway_unmap_port(&gw, llpt, pmk.local_port, mapped_port) {
                     Ok(()) => (),
                     Err(e) => {
-                        // Failed to map external port
-                        log_net!(debug "upnp failed to remove external port: {}", e);
+                        // Failed to unmap external port
+                        log_net!(debug "{}", e);
                         return None;
                     }
                 };
@@ -250,42 +377,37 @@ impl IGDManager {
             // Find gateway
             let gw = Self::find_gateway(&mut inner, local_ip)?;
 
-            // Get external address
-            let ext_ip = match gw.get_external_ip() {
-                Ok(ip) => ip,
+            // Map any port
+            let (ext_addr, lifetime) = match this.gateway_map_port(&gw, llpt, SocketAddr::new(local_ip, local_port), None) {
+                Ok(v) => v,
                 Err(e) => {
-                    log_net!(debug "couldn't get external ip from igd: {}", e);
+                    // Failed to map external port
+                    log_net!(debug "{}", e);
                     return None;
                 }
             };
+            let ext_ip = ext_addr.ip();
+            let mapped_port = ext_addr.port();
 
             // Ensure external IP matches address type
-            if ext_ip.is_ipv4() && at != AddressType::IPV4 {
-                log_net!(debug "mismatched ip address type from igd, wanted v4, got v6");
-                return None;
+            let mismatched = if ext_ip.is_ipv4() && at != AddressType::IPV4 {
+                log_net!(debug "mismatched ip address type from gateway, wanted v4, got v6");
+                true
             } else if ext_ip.is_ipv6() && at != AddressType::IPV6 {
-                log_net!(debug "mismatched ip address type from igd, wanted v6, got v4");
+                log_net!(debug "mismatched ip address type from gateway, wanted v6, got v4");
+                true
+            } else if let Some(expected_external_address) = expected_external_address.filter(|x| *x != ext_ip) {
+                log_net!(debug "gateway external address does not match calculated external address: expected={} vs gateway={}", expected_external_address, ext_ip);
+                true
+            } else {
+                false
+            };
+            if mismatched {
+                // Don't leave behind a mapping we won't use
+                let _ = Self::gateway_unmap_port(&gw, llpt, local_port, mapped_port);
                 return None;
             }
 
-            if let Some(expected_external_address) = expected_external_address {
-                if ext_ip != expected_external_address {
-                    log_net!(debug "gateway external address does not match calculated external address: expected={} vs gateway={}", expected_external_address, ext_ip);
-                    return None;
-                }
-            }
-
-            // Map any port
-            let desc = this.get_description(llpt, local_port);
-            let mapped_port = match gw.add_any_port(convert_llpt(llpt), SocketAddr::new(local_ip, local_port), (UPNP_MAPPING_LIFETIME_MS + 999) / 1000, &desc) {
-                Ok(mapped_port) => mapped_port,
-                Err(e) => {
-                    // Failed to map external port
-                    log_net!(debug "upnp failed to map external port: {}", e);
-                    return None;
-                }
-            };
-
             // Add to mapping list to keep alive
             let timestamp = Timestamp::now();
             inner.port_maps.insert(PortMapKey {
@@ -296,7 +418,8 @@ impl IGDManager {
                 ext_ip,
                 mapped_port,
                 timestamp,
-                renewal_lifetime: ((UPNP_MAPPING_LIFETIME_MS / 2) as u64 * 1000u64).into(),
+                lifetime,
+                renewal_lifetime: lifetime / 2u64,
                 renewal_attempts: 0,
             });
 
@@ -324,9 +447,7 @@ impl IGDManager {
 
             for (k, v) in &inner.port_maps {
                 let mapping_lifetime = now.saturating_sub(v.timestamp);
-                if mapping_lifetime >= UPNP_MAPPING_LIFETIME_US
-                    || v.renewal_attempts >= UPNP_MAPPING_ATTEMPTS
-                {
+                if mapping_lifetime >= v.lifetime || v.renewal_attempts >= UPNP_MAPPING_ATTEMPTS {
                     // Past expiration time or tried N times, do a full renew and fail out if we can't
                     full_renews.push((*k, *v));
                 } else if mapping_lifetime >= v.renewal_lifetime {
@@ -367,31 +488,38 @@ impl IGDManager {
                     };
 
                     // Delete the mapping if it exists, ignore any errors here
-                    let _ = gw.remove_port(convert_llpt(k.llpt), v.mapped_port);
+                    let _ = Self::gateway_unmap_port(&gw, k.llpt, k.local_port, v.mapped_port);
                     inner.port_maps.remove(&k);
 
-                    let desc = this.get_description(k.llpt, k.local_port);
-                    match gw.add_any_port(
-                        convert_llpt(k.llpt),
+                    match this.gateway_map_port(
+                        &gw,
+                        k.llpt,
                         SocketAddr::new(local_ip, k.local_port),
-                        (UPNP_MAPPING_LIFETIME_MS + 999) / 1000,
-                        &desc,
+                        None,
                     ) {
-                        Ok(mapped_port) => {
+                        Ok((ext_addr, lifetime)) if ext_addr.ip() == v.ext_ip => {
                             log_net!(debug "full-renewed mapped port {:?} -> {:?}", v, k);
                             inner.port_maps.insert(
                                 k,
                                 PortMapValue {
                                     ext_ip: v.ext_ip,
-                                    mapped_port,
+                                    mapped_port: ext_addr.port(),
                                     timestamp: Timestamp::now(),
-                                    renewal_lifetime: TimestampDuration::new(
-                                        (UPNP_MAPPING_LIFETIME_MS / 2) as u64 * 1000u64,
-                                    ),
+                                    lifetime,
+                                    renewal_lifetime: lifetime / 2u64,
                                     renewal_attempts: 0,
                                 },
                             );
                         }
+                        Ok((ext_addr, _)) => {
+                            info!(
+                                "full-renewed mapped port {:?} -> {:?} has a new external address: {}",
+                                v, k, ext_addr
+                            );
+
+                            // Must restart network now :(
+                            return Ok(false);
+                        }
                         Err(e) => {
                             info!("failed to full-renew mapped port {:?} -> {:?}: {}", v, k, e);
 
@@ -418,15 +546,13 @@ impl IGDManager {
                         }
                     };
 
-                    let desc = this.get_description(k.llpt, k.local_port);
-                    match gw.add_port(
-                        convert_llpt(k.llpt),
-                        v.mapped_port,
+                    match this.gateway_map_port(
+                        &gw,
+                        k.llpt,
                         SocketAddr::new(local_ip, k.local_port),
-                        (UPNP_MAPPING_LIFETIME_MS + 999) / 1000,
-                        &desc,
+                        Some(SocketAddr::new(v.ext_ip, v.mapped_port)),
                     ) {
-                        Ok(()) => {
+                        Ok((_, lifetime)) => {
                             log_net!("renewed mapped port {:?} -> {:?}", v, k);
 
                             inner.port_maps.insert(
@@ -435,9 +561,8 @@ impl IGDManager {
                                     ext_ip: v.ext_ip,
                                     mapped_port: v.mapped_port,
                                     timestamp: Timestamp::now(),
-                                    renewal_lifetime: ((UPNP_MAPPING_LIFETIME_MS / 2) as u64
-                                        * 1000u64)
-                                        .into(),
+                                    lifetime,
+                                    renewal_lifetime: lifetime / 2u64,
                                     renewal_attempts: 0,
                                 },
                             );
@@ -446,8 +571,7 @@ impl IGDManager {
                             log_net!(debug "failed to renew mapped port {:?} -> {:?}: {}", v, k, e);
 
                             // Get closer to the maximum renewal timeline by a factor of two each time
-                            v.renewal_lifetime =
-                                (v.renewal_lifetime + UPNP_MAPPING_LIFETIME_US) / 2u64;
+                            v.renewal_lifetime = (v.renewal_lifetime + v.lifetime) / 2u64;
                             v.renewal_attempts += 1;
 
                             // Store new value to try again
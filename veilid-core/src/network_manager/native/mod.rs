@@ -3,6 +3,7 @@ mod igd_manager;
 mod network_class_discovery;
 mod network_tcp;
 mod network_udp;
+mod pcp;
 mod protocol;
 mod start_protocols;
 
@@ -32,11 +33,11 @@ pub const PEEK_DETECT_LEN: usize = 64;
 
 cfg_if! {
     if #[cfg(all(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 9;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 10;
     } else if #[cfg(any(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 8;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 9;
     } else  {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 7;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 8;
     }
 }
 pub const PUBLIC_INTERNET_CAPABILITIES: [Capability; PUBLIC_INTERNET_CAPABILITIES_LEN] = [
@@ -48,17 +49,25 @@ pub const PUBLIC_INTERNET_CAPABILITIES: [Capability; PUBLIC_INTERNET_CAPABILITIE
     CAP_VALIDATE_DIAL_INFO,
     CAP_DHT,
     CAP_DHT_WATCH,
+    CAP_DHT_PIN,
     CAP_APPMESSAGE,
     #[cfg(feature = "unstable-blockstore")]
     CAP_BLOCKSTORE,
 ];
 
-#[cfg(feature = "unstable-blockstore")]
-const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 5;
-#[cfg(not(feature = "unstable-blockstore"))]
-const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 4;
+cfg_if! {
+    if #[cfg(all(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
+        const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 6;
+    } else if #[cfg(any(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
+        const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 5;
+    } else  {
+        const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 4;
+    }
+}
 
 pub const LOCAL_NETWORK_CAPABILITIES: [Capability; LOCAL_NETWORK_CAPABILITIES_LEN] = [
+    #[cfg(feature = "unstable-tunnels")]
+    CAP_TUNNEL,
     CAP_RELAY,
     CAP_DHT,
     CAP_DHT_WATCH,
@@ -456,6 +465,9 @@ impl Network {
                         .wrap_err("connect failure")?);
                         network_result_try!(pnc.send(data).await.wrap_err("send failure")?);
                     }
+                    ProtocolType::WRTC => {
+                        bail!("no support for WRTC protocol")
+                    }
                 }
                 // Network accounting
                 self.network_manager()
@@ -538,9 +550,12 @@ impl Network {
                         out.resize(recv_len, 0u8);
                         Ok(NetworkResult::Value(out))
                     }
+                    ProtocolType::WRTC => {
+                        bail!("no support for WRTC protocol")
+                    }
                     ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS => {
                         let pnc = network_result_try!(match dial_info.protocol_type() {
-                            ProtocolType::UDP => unreachable!(),
+                            ProtocolType::UDP | ProtocolType::WRTC => unreachable!(),
                             ProtocolType::TCP => {
                                 let peer_socket_addr = dial_info.to_socket_addr();
                                 RawTcpProtocolHandler::connect(
@@ -717,6 +732,83 @@ impl Network {
         .await
     }
 
+    // Make one attempt at our half of a TCP simultaneous open to a dial info
+    // The connection is made from our listening port so it matches the address the peer is connecting to
+    // Failures are expected until the peer's SYN has opened its NAT, so they are not recorded as dial info failures
+    #[instrument(level = "trace", target = "net", err, skip(self))]
+    pub async fn connect_tcp_hole_punch(
+        &self,
+        dial_info: DialInfo,
+    ) -> EyreResult<NetworkResult<UniqueFlow>> {
+        let _guard = self.unlocked_inner.startup_lock.enter()?;
+
+        if dial_info.protocol_type() != ProtocolType::TCP {
+            bail!("tcp hole punch requires tcp dial info");
+        }
+        let Some(local_address) = self.get_preferred_local_address(&dial_info) else {
+            return Ok(NetworkResult::no_connection_other(
+                "no bound tcp listener for hole punch",
+            ));
+        };
+        let connect_timeout_ms = {
+            let c = self.config.get();
+            c.network.connection_initial_timeout_ms
+        };
+
+        let prot_conn = network_result_try!(RawTcpProtocolHandler::connect(
+            Some(local_address),
+            dial_info.to_socket_addr(),
+            connect_timeout_ms,
+        )
+        .await
+        .wrap_err("connect failure")?);
+
+        let conn = network_result_try!(
+            self.connection_manager()
+                .register_negotiated_connection(prot_conn)
+                .await?
+        );
+
+        Ok(NetworkResult::value(conn.unique_flow()))
+    }
+
+    /////////////////////////////////////////////////////////////////
+    // WebRTC negotiation is only supported on WASM targets
+
+    pub async fn start_webrtc_offer(&self, _receipt: Vec<u8>) -> EyreResult<NetworkResult<String>> {
+        Ok(NetworkResult::service_unavailable(
+            "WRTC is not supported on native targets",
+        ))
+    }
+
+    pub async fn complete_webrtc_offer(
+        &self,
+        _receipt: Vec<u8>,
+        _answer_sdp: String,
+    ) -> EyreResult<NetworkResult<()>> {
+        Ok(NetworkResult::service_unavailable(
+            "WRTC is not supported on native targets",
+        ))
+    }
+
+    pub async fn accept_webrtc_offer(
+        &self,
+        _receipt: Vec<u8>,
+        _offer_sdp: String,
+    ) -> EyreResult<NetworkResult<String>> {
+        Ok(NetworkResult::service_unavailable(
+            "WRTC is not supported on native targets",
+        ))
+    }
+
+    pub async fn connect_webrtc(&self, _receipt: Vec<u8>) -> EyreResult<NetworkResult<Flow>> {
+        Ok(NetworkResult::service_unavailable(
+            "WRTC is not supported on native targets",
+        ))
+    }
+
+    pub fn cancel_webrtc(&self, _receipt: &[u8]) {}
+
     /////////////////////////////////////////////////////////////////
 
     pub async fn startup_internal(&self) -> EyreResult<StartupDisposition> {
@@ -748,6 +840,25 @@ impl Network {
             .routing_table
             .configure_local_network_routing_domain(local_networks);
 
+        // Configure the private overlay routing domain from its configured networks
+        let private_overlay_networks: Vec<(IpAddr, IpAddr)> = {
+            let c = self.config.get();
+            c.network
+                .routing_table
+                .private_overlay
+                .networks
+                .iter()
+                .filter_map(|n| ipaddr_parse_cidr(n))
+                .collect()
+        };
+        let mut family_private_overlay = AddressTypeSet::new();
+        for (network_ip, _) in &private_overlay_networks {
+            family_private_overlay.insert(Address::from_ip_addr(*network_ip).address_type());
+        }
+        self.unlocked_inner
+            .routing_table
+            .configure_private_overlay_routing_domain(private_overlay_networks);
+
         // determine if we have ipv4/ipv6 addresses
         {
             let mut inner = self.inner.lock();
@@ -832,6 +943,8 @@ impl Network {
                         .iter()
                         .copied()
                         .filter(|cap| !c.capabilities.disable.contains(cap))
+                        .filter(|cap| *cap != CAP_DHT_PIN || c.network.dht.pinning.enabled)
+                        .chain(c.capabilities.advertise.iter().copied())
                         .collect::<Vec<Capability>>()
                 };
                 let local_network_capabilities = {
@@ -839,6 +952,7 @@ impl Network {
                         .iter()
                         .copied()
                         .filter(|cap| !c.capabilities.disable.contains(cap))
+                        .chain(c.capabilities.advertise.iter().copied())
                         .collect::<Vec<Capability>>()
                 };
 
@@ -865,11 +979,19 @@ impl Network {
             .unlocked_inner
             .routing_table
             .edit_routing_domain(RoutingDomain::LocalNetwork);
+        let mut editor_private_overlay = self
+            .unlocked_inner
+            .routing_table
+            .edit_routing_domain(RoutingDomain::PrivateOverlay);
 
         // start listeners
         if protocol_config.inbound.contains(ProtocolType::UDP) {
             let res = self
-                .bind_udp_protocol_handlers(&mut editor_public_internet, &mut editor_local_network)
+                .bind_udp_protocol_handlers(
+                    &mut editor_public_internet,
+                    &mut editor_local_network,
+                    &mut editor_private_overlay,
+                )
                 .await;
             if !matches!(res, Ok(StartupDisposition::Success)) {
                 return res;
@@ -877,7 +999,11 @@ impl Network {
         }
         if protocol_config.inbound.contains(ProtocolType::WS) {
             let res = self
-                .start_ws_listeners(&mut editor_public_internet, &mut editor_local_network)
+                .start_ws_listeners(
+                    &mut editor_public_internet,
+                    &mut editor_local_network,
+                    &mut editor_private_overlay,
+                )
                 .await;
             if !matches!(res, Ok(StartupDisposition::Success)) {
                 return res;
@@ -885,7 +1011,11 @@ impl Network {
         }
         if protocol_config.inbound.contains(ProtocolType::WSS) {
             let res = self
-                .start_wss_listeners(&mut editor_public_internet, &mut editor_local_network)
+                .start_wss_listeners(
+                    &mut editor_public_internet,
+                    &mut editor_local_network,
+                    &mut editor_private_overlay,
+                )
                 .await;
             if !matches!(res, Ok(StartupDisposition::Success)) {
                 return res;
@@ -893,7 +1023,11 @@ impl Network {
         }
         if protocol_config.inbound.contains(ProtocolType::TCP) {
             let res = self
-                .start_tcp_listeners(&mut editor_public_internet, &mut editor_local_network)
+                .start_tcp_listeners(
+                    &mut editor_public_internet,
+                    &mut editor_local_network,
+                    &mut editor_private_overlay,
+                )
                 .await;
             if !matches!(res, Ok(StartupDisposition::Success)) {
                 return res;
@@ -910,8 +1044,28 @@ impl Network {
             protocol_config.outbound,
             protocol_config.inbound,
             protocol_config.family_local,
-            protocol_config.local_network_capabilities,
+            protocol_config.local_network_capabilities.clone(),
         );
+
+        // Set up the private overlay if it is configured, we can only be reached over it
+        // if we have an address on one of its networks
+        if !family_private_overlay.is_empty() {
+            editor_private_overlay.setup_network(
+                protocol_config.outbound,
+                protocol_config.inbound,
+                family_private_overlay,
+                protocol_config.local_network_capabilities,
+            );
+            let routing_table = self.routing_table();
+            let has_private_overlay_address =
+                self.get_stable_interface_addresses().iter().any(|addr| {
+                    routing_table.routing_domain_for_address(Address::from_ip_addr(*addr))
+                        == Some(RoutingDomain::PrivateOverlay)
+                });
+            if has_private_overlay_address {
+                editor_private_overlay.set_network_class(Some(NetworkClass::InboundCapable));
+            }
+        }
         let detect_address_changes = {
             let c = self.config.get();
             c.network.detect_address_changes
@@ -926,6 +1080,7 @@ impl Network {
         // commit routing table edits
         editor_public_internet.commit(true).await;
         editor_local_network.commit(true).await;
+        editor_private_overlay.commit(true).await;
 
         Ok(StartupDisposition::Success)
     }
@@ -1009,6 +1164,14 @@ impl Network {
             .commit(true)
             .await;
 
+        routing_table
+            .edit_routing_domain(RoutingDomain::PrivateOverlay)
+            .clear_dial_info_details(None, None)
+            .set_network_class(None)
+            .clear_relay_node()
+            .commit(true)
+            .await;
+
         // Reset state including network class
         *self.inner.lock() = Self::new_inner();
     }
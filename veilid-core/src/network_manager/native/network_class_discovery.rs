@@ -122,8 +122,12 @@ impl Network {
                         continue;
                     }
 
+                    // Protocols without a socket of our own are not discovered
+                    let Some(llpt) = pt.low_level_protocol_type() else {
+                        continue;
+                    };
+
                     if let Some(pla) = inner.preferred_local_addresses.get(&key) {
-                        let llpt = pt.low_level_protocol_type();
                         let itmkey = (at, llpt, pla.port());
                         inbound_protocol_map
                             .entry(itmkey)
@@ -215,12 +219,18 @@ impl Network {
 
                     // Add additional dialinfo for protocols on the same port
                     if let DetectedDialInfo::Detected(did) = &dr.ddi {
-                        let ipmkey = (
-                            did.dial_info.address_type(),
-                            did.dial_info.protocol_type().low_level_protocol_type(),
-                            dr.local_port,
-                        );
-                        if let Some(ipm) = inbound_protocol_map.get(&ipmkey) {
+                        let opt_ipm = did
+                            .dial_info
+                            .protocol_type()
+                            .low_level_protocol_type()
+                            .and_then(|llpt| {
+                                inbound_protocol_map.get(&(
+                                    did.dial_info.address_type(),
+                                    llpt,
+                                    dr.local_port,
+                                ))
+                            });
+                        if let Some(ipm) = opt_ipm {
                             for additional_pt in ipm.iter().skip(1) {
                                 // Make dialinfo for additional protocol type
                                 let additional_ddi = DetectedDialInfo::Detected(DialInfoDetail {
@@ -323,6 +333,9 @@ impl Network {
                 )
                 .unwrap()
             }
+            ProtocolType::WRTC => {
+                panic!("WRTC has no dial info");
+            }
         }
     }
 }
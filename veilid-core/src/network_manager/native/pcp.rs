@@ -0,0 +1,589 @@
+/// Port mapping with PCP (RFC 6887) and its predecessor NAT-PMP (RFC 6886)
+/// PCP can also open firewall pinholes for IPv6, where there is no NAT to map through
+use super::*;
+use std::net::UdpSocket;
+
+pub const PCP_SERVER_PORT: u16 = 5351;
+
+const PCP_VERSION: u8 = 2;
+const NAT_PMP_VERSION: u8 = 0;
+
+const PCP_OPCODE_ANNOUNCE: u8 = 0;
+const PCP_OPCODE_MAP: u8 = 1;
+const PCP_RESPONSE_BIT: u8 = 0x80;
+const PCP_HEADER_LEN: usize = 24;
+const PCP_MAP_LEN: usize = 36;
+const PCP_NONCE_LEN: usize = 12;
+
+const NAT_PMP_OPCODE_EXTERNAL_ADDRESS: u8 = 0;
+const NAT_PMP_OPCODE_MAP_UDP: u8 = 1;
+const NAT_PMP_OPCODE_MAP_TCP: u8 = 2;
+const NAT_PMP_EXTERNAL_ADDRESS_RESPONSE_LEN: usize = 12;
+const NAT_PMP_MAP_RESPONSE_LEN: usize = 16;
+
+// Result code shared by PCP and NAT-PMP when a gateway does not speak the requested version
+const RESULT_SUCCESS: u8 = 0;
+const RESULT_UNSUPP_VERSION: u8 = 1;
+
+// Requests are retransmitted with a doubling timeout, as both protocols run over UDP
+const PCP_INITIAL_TIMEOUT_MS: u64 = 250;
+const PCP_REQUEST_ATTEMPTS: u32 = 3;
+
+const IANA_PROTOCOL_TCP: u8 = 6;
+const IANA_PROTOCOL_UDP: u8 = 17;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PcpVersion {
+    NatPmp,
+    Pcp,
+}
+
+/// A port mapping created by a PCP or NAT-PMP gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PcpMapping {
+    pub external_address: SocketAddr,
+    pub lifetime_secs: u32,
+}
+
+/// A gateway that speaks PCP or NAT-PMP
+#[derive(Debug)]
+pub struct PcpGateway {
+    gateway_addr: SocketAddr,
+    local_ip: IpAddr,
+    version: PcpVersion,
+    // Identifies our mappings to the gateway, so that only we can renew or delete them
+    nonce: [u8; PCP_NONCE_LEN],
+}
+
+fn pcp_result_name(result: u8) -> &'static str {
+    match result {
+        1 => "UNSUPP_VERSION",
+        2 => "NOT_AUTHORIZED",
+        3 => "MALFORMED_REQUEST",
+        4 => "UNSUPP_OPCODE",
+        5 => "UNSUPP_OPTION",
+        6 => "MALFORMED_OPTION",
+        7 => "NETWORK_FAILURE",
+        8 => "NO_RESOURCES",
+        9 => "UNSUPP_PROTOCOL",
+        10 => "USER_EX_QUOTA",
+        11 => "CANNOT_PROVIDE_EXTERNAL",
+        12 => "ADDRESS_MISMATCH",
+        13 => "EXCESSIVE_REMOTE_PEERS",
+        _ => "unknown result",
+    }
+}
+
+fn nat_pmp_result_name(result: u16) -> &'static str {
+    match result {
+        1 => "Unsupported Version",
+        2 => "Not Authorized/Refused",
+        3 => "Network Failure",
+        4 => "Out of resources",
+        5 => "Unsupported opcode",
+        _ => "unknown result",
+    }
+}
+
+fn result_error(version: PcpVersion, result: u16) -> io::Error {
+    let name = match version {
+        PcpVersion::NatPmp => nat_pmp_result_name(result),
+        PcpVersion::Pcp => pcp_result_name(result as u8),
+    };
+    io::Error::new(
+        io::ErrorKind::Other,
+        format!("{:?} gateway returned error {}: {}", version, result, name),
+    )
+}
+
+// PCP carries all addresses as IPv6, with IPv4 addresses in their IPv4-mapped form
+fn to_pcp_address(ip: IpAddr) -> [u8; 16] {
+    match ip {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped().octets(),
+        IpAddr::V6(v6) => v6.octets(),
+    }
+}
+
+fn from_pcp_address(octets: [u8; 16]) -> IpAddr {
+    let v6 = Ipv6Addr::from(octets);
+    match v6.to_ipv4_mapped() {
+        Some(v4) => IpAddr::V4(v4),
+        None => IpAddr::V6(v6),
+    }
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+impl PcpGateway {
+    /// Find out which protocol, if any, the gateway speaks
+    /// PCP is preferred, and NAT-PMP is only used for IPv4 as it can not map IPv6
+    pub fn probe(gateway_addr: SocketAddr, local_ip: IpAddr) -> io::Result<Self> {
+        let mut nonce = [0u8; PCP_NONCE_LEN];
+        random_bytes(&mut nonce);
+        let mut gateway = Self {
+            gateway_addr,
+            local_ip,
+            version: PcpVersion::Pcp,
+            nonce,
+        };
+
+        // An ANNOUNCE request does nothing other than elicit a response from a PCP server
+        let request = gateway.make_pcp_header(PCP_OPCODE_ANNOUNCE, 0);
+        let response = gateway.request(&request, |buf| {
+            buf.len() >= 4
+                && (buf[0] == PCP_VERSION || buf[0] == NAT_PMP_VERSION)
+                && buf[1] == PCP_RESPONSE_BIT | PCP_OPCODE_ANNOUNCE
+        })?;
+
+        if response[0] == PCP_VERSION {
+            let result = response[3];
+            if result != RESULT_SUCCESS {
+                return Err(result_error(PcpVersion::Pcp, result as u16));
+            }
+            return Ok(gateway);
+        }
+
+        // NAT-PMP gateways reject PCP requests with their own version number
+        if read_u16(&response, 2) != RESULT_UNSUPP_VERSION as u16 || !local_ip.is_ipv4() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "gateway does not speak PCP",
+            ));
+        }
+        gateway.version = PcpVersion::NatPmp;
+        gateway.get_nat_pmp_external_ip()?;
+
+        Ok(gateway)
+    }
+
+    pub fn version(&self) -> PcpVersion {
+        self.version
+    }
+
+    /// Map a local port, asking for the same external port if there is a choice
+    /// Returns the external address and the lifetime the gateway granted
+    pub fn add_port(
+        &self,
+        llpt: LowLevelProtocolType,
+        local_port: u16,
+        suggested_external_port: u16,
+        lifetime_secs: u32,
+    ) -> io::Result<PcpMapping> {
+        match self.version {
+            PcpVersion::NatPmp => {
+                self.nat_pmp_map(llpt, local_port, suggested_external_port, lifetime_secs)
+            }
+            PcpVersion::Pcp => {
+                self.pcp_map(llpt, local_port, suggested_external_port, lifetime_secs)
+            }
+        }
+    }
+
+    /// Delete the mapping for a local port
+    pub fn remove_port(&self, llpt: LowLevelProtocolType, local_port: u16) -> io::Result<()> {
+        match self.version {
+            PcpVersion::NatPmp => self.nat_pmp_map(llpt, local_port, 0, 0).map(drop),
+            PcpVersion::Pcp => self.pcp_map(llpt, local_port, 0, 0).map(drop),
+        }
+    }
+
+    fn make_pcp_header(&self, opcode: u8, lifetime_secs: u32) -> Vec<u8> {
+        let mut request = Vec::with_capacity(PCP_HEADER_LEN + PCP_MAP_LEN);
+        request.push(PCP_VERSION);
+        request.push(opcode);
+        request.extend_from_slice(&[0u8; 2]);
+        request.extend_from_slice(&lifetime_secs.to_be_bytes());
+        request.extend_from_slice(&to_pcp_address(self.local_ip));
+        request
+    }
+
+    fn pcp_map(
+        &self,
+        llpt: LowLevelProtocolType,
+        local_port: u16,
+        suggested_external_port: u16,
+        lifetime_secs: u32,
+    ) -> io::Result<PcpMapping> {
+        let protocol = match llpt {
+            LowLevelProtocolType::UDP => IANA_PROTOCOL_UDP,
+            LowLevelProtocolType::TCP => IANA_PROTOCOL_TCP,
+        };
+        // Any external address of the same family as our own
+        let suggested_external_ip = match self.local_ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+
+        let mut request = self.make_pcp_header(PCP_OPCODE_MAP, lifetime_secs);
+        request.extend_from_slice(&self.nonce);
+        request.push(protocol);
+        request.extend_from_slice(&[0u8; 3]);
+        request.extend_from_slice(&local_port.to_be_bytes());
+        request.extend_from_slice(&suggested_external_port.to_be_bytes());
+        request.extend_from_slice(&to_pcp_address(suggested_external_ip));
+
+        let response = self.request(&request, |buf| {
+            buf.len() >= PCP_HEADER_LEN + PCP_MAP_LEN
+                && buf[0] == PCP_VERSION
+                && buf[1] == PCP_RESPONSE_BIT | PCP_OPCODE_MAP
+                && buf[PCP_HEADER_LEN..PCP_HEADER_LEN + PCP_NONCE_LEN] == self.nonce
+                && buf[PCP_HEADER_LEN + 12] == protocol
+                && read_u16(buf, PCP_HEADER_LEN + 16) == local_port
+        })?;
+
+        let result = response[3];
+        if result != RESULT_SUCCESS {
+            return Err(result_error(PcpVersion::Pcp, result as u16));
+        }
+
+        let mut external_ip = [0u8; 16];
+        external_ip.copy_from_slice(&response[PCP_HEADER_LEN + 20..PCP_HEADER_LEN + 36]);
+        Ok(PcpMapping {
+            external_address: SocketAddr::new(
+                from_pcp_address(external_ip),
+                read_u16(&response, PCP_HEADER_LEN + 18),
+            ),
+            lifetime_secs: read_u32(&response, 4),
+        })
+    }
+
+    fn get_nat_pmp_external_ip(&self) -> io::Result<IpAddr> {
+        let request = [NAT_PMP_VERSION, NAT_PMP_OPCODE_EXTERNAL_ADDRESS];
+        let response = self.request(&request, |buf| {
+            buf.len() >= NAT_PMP_EXTERNAL_ADDRESS_RESPONSE_LEN
+                && buf[0] == NAT_PMP_VERSION
+                && buf[1] == PCP_RESPONSE_BIT | NAT_PMP_OPCODE_EXTERNAL_ADDRESS
+        })?;
+
+        let result = read_u16(&response, 2);
+        if result != RESULT_SUCCESS as u16 {
+            return Err(result_error(PcpVersion::NatPmp, result));
+        }
+
+        Ok(IpAddr::V4(Ipv4Addr::new(
+            response[8],
+            response[9],
+            response[10],
+            response[11],
+        )))
+    }
+
+    fn nat_pmp_map(
+        &self,
+        llpt: LowLevelProtocolType,
+        local_port: u16,
+        suggested_external_port: u16,
+        lifetime_secs: u32,
+    ) -> io::Result<PcpMapping> {
+        let opcode = match llpt {
+            LowLevelProtocolType::UDP => NAT_PMP_OPCODE_MAP_UDP,
+            LowLevelProtocolType::TCP => NAT_PMP_OPCODE_MAP_TCP,
+        };
+
+        let mut request = Vec::with_capacity(12);
+        request.push(NAT_PMP_VERSION);
+        request.push(opcode);
+        request.extend_from_slice(&[0u8; 2]);
+        request.extend_from_slice(&local_port.to_be_bytes());
+        request.extend_from_slice(&suggested_external_port.to_be_bytes());
+        request.extend_from_slice(&lifetime_secs.to_be_bytes());
+
+        let response = self.request(&request, |buf| {
+            buf.len() >= NAT_PMP_MAP_RESPONSE_LEN
+                && buf[0] == NAT_PMP_VERSION
+                && buf[1] == PCP_RESPONSE_BIT | opcode
+                && read_u16(buf, 8) == local_port
+        })?;
+
+        let result = read_u16(&response, 2);
+        if result != RESULT_SUCCESS as u16 {
+            return Err(result_error(PcpVersion::NatPmp, result));
+        }
+
+        // NAT-PMP map responses do not include the external address
+        let external_ip = if lifetime_secs == 0 {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        } else {
+            self.get_nat_pmp_external_ip()?
+        };
+
+        Ok(PcpMapping {
+            external_address: SocketAddr::new(external_ip, read_u16(&response, 10)),
+            lifetime_secs: read_u32(&response, 12),
+        })
+    }
+
+    // Send a request to the gateway and wait for the response that answers it
+    fn request<F>(&self, request: &[u8], is_response: F) -> io::Result<Vec<u8>>
+    where
+        F: Fn(&[u8]) -> bool,
+    {
+        let socket = UdpSocket::bind(SocketAddr::new(self.local_ip, 0))?;
+        socket.connect(self.gateway_addr)?;
+
+        let mut buf = [0u8; 1100];
+        let mut timeout_ms = PCP_INITIAL_TIMEOUT_MS;
+        for _ in 0..PCP_REQUEST_ATTEMPTS {
+            socket.send(request)?;
+
+            let deadline = std::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+            loop {
+                let now = std::time::Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                socket.set_read_timeout(Some(deadline - now))?;
+                match socket.recv(&mut buf) {
+                    Ok(len) => {
+                        if is_response(&buf[0..len]) {
+                            return Ok(buf[0..len].to_vec());
+                        }
+                        // Ignore anything that isn't the answer to this request
+                    }
+                    Err(e)
+                        if e.kind() == io::ErrorKind::WouldBlock
+                            || e.kind() == io::ErrorKind::TimedOut =>
+                    {
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            timeout_ms *= 2;
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "no response from gateway",
+        ))
+    }
+}
+
+/// Find the address of the PCP or NAT-PMP server for a local address, which is the default gateway
+pub fn find_pcp_server_address(local_ip: IpAddr) -> Option<SocketAddr> {
+    cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android"))] {
+            let server_addr = match local_ip {
+                IpAddr::V4(_) => read_default_gateway_v4(),
+                IpAddr::V6(_) => read_default_gateway_v6(),
+            };
+        } else {
+            // Without access to the routing table, assume the gateway has the first address
+            // of the local /24 subnet, as most home routers do. There is no such convention for IPv6.
+            let server_addr = match local_ip {
+                IpAddr::V4(v4) => {
+                    let o = v4.octets();
+                    Some(SocketAddr::new(
+                        IpAddr::V4(Ipv4Addr::new(o[0], o[1], o[2], 1)),
+                        PCP_SERVER_PORT,
+                    ))
+                }
+                IpAddr::V6(_) => None,
+            };
+        }
+    }
+    server_addr
+}
+
+cfg_if! {
+    if #[cfg(any(target_os = "linux", target_os = "android"))] {
+        // Read the gateway of the lowest metric IPv4 default route from /proc/net/route
+        // Columns are: Iface Destination Gateway Flags RefCnt Use Metric ...
+        fn read_default_gateway_v4() -> Option<SocketAddr> {
+            let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+            routes
+                .lines()
+                .skip(1)
+                .filter_map(|line| {
+                    let cols: Vec<&str> = line.split_whitespace().collect();
+                    if cols.len() < 7 || cols[1] != "00000000" {
+                        return None;
+                    }
+                    // Addresses are the hex of the in-memory network order value, so in host byte order
+                    let gateway = u32::from_str_radix(cols[2], 16).ok()?;
+                    if gateway == 0 {
+                        return None;
+                    }
+                    let metric = cols[6].parse::<u32>().ok()?;
+                    let gateway = Ipv4Addr::from(gateway.to_ne_bytes());
+                    Some((metric, gateway))
+                })
+                .min()
+                .map(|(_, gateway)| SocketAddr::new(IpAddr::V4(gateway), PCP_SERVER_PORT))
+        }
+
+        // Read the next hop of the lowest metric IPv6 default route from /proc/net/ipv6_route
+        // Columns are: Destination DestPrefixLen Source SourcePrefixLen NextHop Metric RefCnt Use Flags Iface
+        fn read_default_gateway_v6() -> Option<SocketAddr> {
+            let routes = std::fs::read_to_string("/proc/net/ipv6_route").ok()?;
+            routes
+                .lines()
+                .filter_map(|line| {
+                    let cols: Vec<&str> = line.split_whitespace().collect();
+                    if cols.len() < 10 || cols[0] != "00000000000000000000000000000000" || cols[1] != "00" {
+                        return None;
+                    }
+                    let next_hop = Ipv6Addr::from(u128::from_str_radix(cols[4], 16).ok()?);
+                    if next_hop.is_unspecified() {
+                        return None;
+                    }
+                    let metric = u32::from_str_radix(cols[5], 16).ok()?;
+                    // The next hop is usually link-local, so it needs the interface as its scope
+                    let ifname = std::ffi::CString::new(cols[9]).ok()?;
+                    let scope_id = unsafe { libc::if_nametoindex(ifname.as_ptr()) };
+                    Some((metric, next_hop, scope_id))
+                })
+                .min()
+                .map(|(_, next_hop, scope_id)| {
+                    SocketAddr::V6(SocketAddrV6::new(next_hop, PCP_SERVER_PORT, 0, scope_id))
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOCK_EXTERNAL_IP: Ipv4Addr = Ipv4Addr::new(203, 0, 113, 5);
+    const MOCK_ANY_PORT: u16 = 40000;
+
+    // Run a mock gateway on localhost that answers up to `count` requests
+    fn mock_gateway<F>(count: usize, answer: F) -> (SocketAddr, std::thread::JoinHandle<()>)
+    where
+        F: Fn(&[u8]) -> Vec<u8> + Send + 'static,
+    {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        let jh = std::thread::spawn(move || {
+            let mut buf = [0u8; 1100];
+            for _ in 0..count {
+                let Ok((len, from)) = socket.recv_from(&mut buf) else {
+                    return;
+                };
+                socket.send_to(&answer(&buf[0..len]), from).unwrap();
+            }
+        });
+        (addr, jh)
+    }
+
+    fn mock_pcp_answer(request: &[u8], map_result: u8) -> Vec<u8> {
+        assert_eq!(request[0], PCP_VERSION);
+        assert_eq!(
+            request[8..24],
+            to_pcp_address(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        );
+
+        let mut response = vec![0u8; PCP_HEADER_LEN];
+        response[0] = PCP_VERSION;
+        response[1] = PCP_RESPONSE_BIT | request[1];
+        response[4..8].copy_from_slice(&request[4..8]);
+        if request[1] == PCP_OPCODE_MAP {
+            response[3] = map_result;
+            let mut map = request[PCP_HEADER_LEN..PCP_HEADER_LEN + PCP_MAP_LEN].to_vec();
+            if read_u16(&map, 18) == 0 && read_u32(request, 4) != 0 {
+                map[18..20].copy_from_slice(&MOCK_ANY_PORT.to_be_bytes());
+            }
+            map[20..36].copy_from_slice(&to_pcp_address(IpAddr::V4(MOCK_EXTERNAL_IP)));
+            response.extend_from_slice(&map);
+        }
+        response
+    }
+
+    fn mock_nat_pmp_answer(request: &[u8]) -> Vec<u8> {
+        let mut response = vec![NAT_PMP_VERSION, PCP_RESPONSE_BIT | request[1], 0, 0];
+        response.extend_from_slice(&1234u32.to_be_bytes());
+        if request[0] != NAT_PMP_VERSION {
+            response[3] = RESULT_UNSUPP_VERSION;
+        } else if request[1] == NAT_PMP_OPCODE_EXTERNAL_ADDRESS {
+            response.extend_from_slice(&MOCK_EXTERNAL_IP.octets());
+        } else {
+            // Internal port, mapped external port, and lifetime
+            response.extend_from_slice(&request[4..6]);
+            if read_u16(request, 6) == 0 && read_u32(request, 8) != 0 {
+                response.extend_from_slice(&MOCK_ANY_PORT.to_be_bytes());
+            } else {
+                response.extend_from_slice(&request[6..8]);
+            }
+            response.extend_from_slice(&request[8..12]);
+        }
+        response
+    }
+
+    #[test]
+    fn test_pcp_map() {
+        // Probe, map, map any port, unmap
+        let (addr, jh) = mock_gateway(4, |req| mock_pcp_answer(req, RESULT_SUCCESS));
+
+        let gw = PcpGateway::probe(addr, IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        assert_eq!(gw.version(), PcpVersion::Pcp);
+
+        let mapping = gw
+            .add_port(LowLevelProtocolType::UDP, 5150, 5150, 120)
+            .unwrap();
+        assert_eq!(
+            mapping,
+            PcpMapping {
+                external_address: SocketAddr::new(IpAddr::V4(MOCK_EXTERNAL_IP), 5150),
+                lifetime_secs: 120,
+            }
+        );
+        let mapping = gw
+            .add_port(LowLevelProtocolType::TCP, 5150, 0, 120)
+            .unwrap();
+        assert_eq!(mapping.external_address.port(), MOCK_ANY_PORT);
+
+        gw.remove_port(LowLevelProtocolType::TCP, 5150).unwrap();
+        jh.join().unwrap();
+    }
+
+    #[test]
+    fn test_pcp_map_error() {
+        let (addr, jh) = mock_gateway(2, |req| mock_pcp_answer(req, 8));
+
+        let gw = PcpGateway::probe(addr, IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        let err = gw
+            .add_port(LowLevelProtocolType::UDP, 5150, 5150, 120)
+            .unwrap_err();
+        assert!(err.to_string().contains("NO_RESOURCES"));
+        jh.join().unwrap();
+    }
+
+    #[test]
+    fn test_nat_pmp_fallback() {
+        // PCP probe, external address, map, external address, unmap
+        let (addr, jh) = mock_gateway(5, mock_nat_pmp_answer);
+
+        let gw = PcpGateway::probe(addr, IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        assert_eq!(gw.version(), PcpVersion::NatPmp);
+
+        let mapping = gw
+            .add_port(LowLevelProtocolType::UDP, 5150, 5150, 120)
+            .unwrap();
+        assert_eq!(
+            mapping,
+            PcpMapping {
+                external_address: SocketAddr::new(IpAddr::V4(MOCK_EXTERNAL_IP), 5150),
+                lifetime_secs: 120,
+            }
+        );
+
+        gw.remove_port(LowLevelProtocolType::UDP, 5150).unwrap();
+        jh.join().unwrap();
+    }
+}
@@ -42,6 +42,9 @@ impl ProtocolNetworkConnection {
             ProtocolType::WS | ProtocolType::WSS => {
                 ws::WebsocketProtocolHandler::connect(local_address, dial_info, timeout_ms).await
             }
+            ProtocolType::WRTC => {
+                panic!("WRTC dial info is not supported on native targets");
+            }
         }
     }
 
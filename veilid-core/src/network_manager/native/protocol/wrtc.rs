@@ -1 +1,2 @@
-
+//! WebRTC connections are only supported on WASM targets, see the wasm protocol module.
+//! Native nodes never advertise WRTC as an outbound protocol, so they are never asked to negotiate one.
@@ -122,6 +122,25 @@ impl Network {
         }
     }
 
+    // Register interface dial info in the private overlay if its address is on an overlay network,
+    // otherwise in the local network
+    fn register_interface_dial_info(
+        &self,
+        editor_local_network: &mut RoutingDomainEditor,
+        editor_private_overlay: &mut RoutingDomainEditor,
+        dial_info: DialInfo,
+    ) -> EyreResult<()> {
+        let routing_domain = self
+            .routing_table()
+            .routing_domain_for_address(dial_info.address());
+        if routing_domain == Some(RoutingDomain::PrivateOverlay) {
+            editor_private_overlay.register_dial_info(dial_info, DialInfoClass::Direct)?;
+        } else {
+            editor_local_network.register_dial_info(dial_info, DialInfoClass::Direct)?;
+        }
+        Ok(())
+    }
+
     // Add local dial info to preferred local address table
     fn add_preferred_local_address(inner: &mut NetworkInner, pa: PeerAddress) {
         let key = (pa.protocol_type(), pa.address_type());
@@ -142,6 +161,7 @@ impl Network {
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
+        editor_private_overlay: &mut RoutingDomainEditor,
     ) -> EyreResult<StartupDisposition> {
         log_net!("UDP: binding protocol handlers");
         let routing_table = self.routing_table();
@@ -211,9 +231,10 @@ impl Network {
                 })();
 
                 if !local_dial_info_list.contains(&pdi) && is_interface_address {
-                    editor_local_network.register_dial_info(
+                    self.register_interface_dial_info(
+                        editor_local_network,
+                        editor_private_overlay,
                         DialInfo::udp_from_socketaddr(pdi_addr),
-                        DialInfoClass::Direct,
                     )?;
                 }
             }
@@ -232,7 +253,11 @@ impl Network {
             }
 
             // Register interface dial info as well since the address is on the local interface
-            editor_local_network.register_dial_info(di.clone(), DialInfoClass::Direct)?;
+            self.register_interface_dial_info(
+                editor_local_network,
+                editor_private_overlay,
+                di.clone(),
+            )?;
         }
 
         {
@@ -256,6 +281,7 @@ impl Network {
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
+        editor_private_overlay: &mut RoutingDomainEditor,
     ) -> EyreResult<StartupDisposition> {
         log_net!("WS: binding protocol handlers");
         let routing_table = self.routing_table();
@@ -325,7 +351,11 @@ impl Network {
                 if !registered_addresses.contains(&gsa.ip())
                     && self.is_stable_interface_address(gsa.ip())
                 {
-                    editor_local_network.register_dial_info(pdi, DialInfoClass::Direct)?;
+                    self.register_interface_dial_info(
+                        editor_local_network,
+                        editor_private_overlay,
+                        pdi,
+                    )?;
                 }
 
                 registered_addresses.insert(gsa.ip());
@@ -353,7 +383,11 @@ impl Network {
             }
 
             // Register local dial info
-            editor_local_network.register_dial_info(local_di, DialInfoClass::Direct)?;
+            self.register_interface_dial_info(
+                editor_local_network,
+                editor_private_overlay,
+                local_di,
+            )?;
         }
 
         let mut inner = self.inner.lock();
@@ -372,6 +406,7 @@ impl Network {
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
+        editor_private_overlay: &mut RoutingDomainEditor,
     ) -> EyreResult<StartupDisposition> {
         log_net!("WSS: binding protocol handlers");
 
@@ -447,7 +482,11 @@ impl Network {
                 if !registered_addresses.contains(&gsa.ip())
                     && self.is_stable_interface_address(gsa.ip())
                 {
-                    editor_local_network.register_dial_info(pdi, DialInfoClass::Direct)?;
+                    self.register_interface_dial_info(
+                        editor_local_network,
+                        editor_private_overlay,
+                        pdi,
+                    )?;
                 }
 
                 registered_addresses.insert(gsa.ip());
@@ -472,6 +511,7 @@ impl Network {
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
+        editor_private_overlay: &mut RoutingDomainEditor,
     ) -> EyreResult<StartupDisposition> {
         log_net!("TCP: binding protocol handlers");
 
@@ -537,7 +577,11 @@ impl Network {
 
                 // See if this public address is also a local interface address
                 if self.is_stable_interface_address(pdi_addr.ip()) {
-                    editor_local_network.register_dial_info(pdi, DialInfoClass::Direct)?;
+                    self.register_interface_dial_info(
+                        editor_local_network,
+                        editor_private_overlay,
+                        pdi,
+                    )?;
                 }
             }
         }
@@ -554,7 +598,11 @@ impl Network {
                 static_public = true;
             }
             // Register interface dial info
-            editor_local_network.register_dial_info(di.clone(), DialInfoClass::Direct)?;
+            self.register_interface_dial_info(
+                editor_local_network,
+                editor_private_overlay,
+                di.clone(),
+            )?;
             registered_addresses.insert(socket_address.ip_addr());
         }
 
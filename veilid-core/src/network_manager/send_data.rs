@@ -1,4 +1,5 @@
 use super::*;
+use futures_util::future::{select, Either};
 use stop_token::future::FutureExt as _;
 
 impl NetworkManager {
@@ -155,6 +156,19 @@ impl NetworkManager {
                             network_result_try!(nres)
                         }
                     }
+                    NodeContactMethod::SignalWebRTC(relay_nr, target_node_ref) => {
+                        let nres =
+                            this.send_data_ncm_signal_webrtc(relay_nr.clone(), target_node_ref.clone(), data.clone())
+                                .await?;
+                        if matches!(nres, NetworkResult::Timeout) {
+                            // Failed to negotiate, fallback to inbound relay
+                            log_network_result!(debug "WebRTC connection failed to {}, falling back to inbound relay via {}", target_node_ref, relay_nr);
+                            network_result_try!(this.try_possibly_relayed_contact_method(NodeContactMethod::InboundRelay(relay_nr), destination_node_ref, data).await?)
+                        } else {
+                            log_network_result!(debug "WebRTC connection successful to {} via {}", target_node_ref, relay_nr);
+                            network_result_try!(nres)
+                        }
+                    }
                     NodeContactMethod::Existing => {
                         network_result_try!(
                             this.send_data_ncm_existing(target_node_ref, data).await?
@@ -330,6 +344,49 @@ impl NetworkManager {
         }))
     }
 
+    /// Send data using NodeContactMethod::SignalWebRTC
+    #[instrument(level = "trace", target = "net", skip_all, err)]
+    async fn send_data_ncm_signal_webrtc(
+        &self,
+        relay_nr: NodeRef,
+        target_node_ref: NodeRef,
+        data: Vec<u8>,
+    ) -> EyreResult<NetworkResult<SendDataMethod>> {
+        // First try to send data to the last socket we've seen this peer on
+        let data = if let Some(flow) = target_node_ref.last_flow() {
+            match self.net().send_data_to_existing_flow(flow, data).await? {
+                SendDataToExistingFlowResult::Sent(unique_flow) => {
+                    // Update timestamp for this last connection since we just sent to it
+                    target_node_ref.set_last_flow(flow, Timestamp::now());
+
+                    return Ok(NetworkResult::value(SendDataMethod {
+                        contact_method: NodeContactMethod::Existing,
+                        opt_relayed_contact_method: None,
+                        unique_flow,
+                    }));
+                }
+                SendDataToExistingFlowResult::NotSent(data) => {
+                    // Couldn't send data to existing connection
+                    // so pass the data back out
+                    data
+                }
+            }
+        } else {
+            // No last connection
+            data
+        };
+
+        let unique_flow = network_result_try!(
+            self.do_webrtc_connect(relay_nr.clone(), target_node_ref.clone(), data)
+                .await?
+        );
+        Ok(NetworkResult::value(SendDataMethod {
+            contact_method: NodeContactMethod::SignalWebRTC(relay_nr, target_node_ref),
+            opt_relayed_contact_method: None,
+            unique_flow,
+        }))
+    }
+
     /// Send data using NodeContactMethod::Direct
     #[instrument(level = "trace", target = "net", skip_all, err)]
     async fn send_data_ncm_direct(
@@ -518,7 +575,7 @@ impl NetworkManager {
                     target_node_ref.filtered_clone(NodeRefFilter::from(dial_info_filter));
                 NodeContactMethod::SignalReverse(relay_nr, target_node_ref)
             }
-            ContactMethod::SignalHolePunch(relay_key, target_key) => {
+            ContactMethod::SignalHolePunch(relay_key, target_key, protocol_type) => {
                 let mut relay_nr = routing_table
                     .lookup_and_filter_noderef(relay_key, routing_domain.into(), dial_info_filter)?
                     .ok_or_else(|| {
@@ -533,15 +590,35 @@ impl NetworkManager {
                 }
                 relay_nr.set_sequencing(sequencing);
 
-                // if any other protocol were possible here we could update this and do_hole_punch
-                // but tcp hole punch is very very unreliable it seems
-                let udp_target_node_ref = target_node_ref.filtered_clone(
+                // Hole punch with the protocol both nodes can reach each other with
+                let hole_punch_target_node_ref = target_node_ref.filtered_clone(
                     NodeRefFilter::new()
                         .with_dial_info_filter(dial_info_filter)
-                        .with_protocol_type(ProtocolType::UDP),
+                        .with_protocol_type(protocol_type),
                 );
 
-                NodeContactMethod::SignalHolePunch(relay_nr, udp_target_node_ref)
+                NodeContactMethod::SignalHolePunch(relay_nr, hole_punch_target_node_ref)
+            }
+            ContactMethod::SignalWebRTC(relay_key, target_key) => {
+                let mut relay_nr = routing_table
+                    .lookup_and_filter_noderef(relay_key, routing_domain.into(), dial_info_filter)?
+                    .ok_or_else(|| {
+                        eyre!(
+                            "couldn't look up relay for signal webrtc: {} with filter {:?}",
+                            relay_key,
+                            dial_info_filter
+                        )
+                    })?;
+                if !target_node_ref.node_ids().contains(&target_key) {
+                    bail!("signalwebrtc target noderef didn't match target key: {:?} != {} for relay {}", target_node_ref, target_key, relay_key );
+                }
+                relay_nr.set_sequencing(sequencing);
+                let target_node_ref = target_node_ref.filtered_clone(
+                    NodeRefFilter::new()
+                        .with_dial_info_filter(dial_info_filter)
+                        .with_protocol_type(ProtocolType::WRTC),
+                );
+                NodeContactMethod::SignalWebRTC(relay_nr, target_node_ref)
             }
             ContactMethod::InboundRelay(relay_key) => {
                 let mut relay_nr = routing_table
@@ -703,12 +780,14 @@ impl NetworkManager {
             return Ok(NetworkResult::service_unavailable("network is stopping"));
         };
 
-        // Ensure we are filtered down to UDP (the only hole punch protocol supported today)
-        assert!(target_nr
-            .filter_ref()
-            .map(|nrf| nrf.dial_info_filter.protocol_type_set
-                == ProtocolTypeSet::only(ProtocolType::UDP))
-            .unwrap_or_default());
+        // Ensure we are filtered down to UDP or TCP (the only hole punch protocols supported today)
+        let Some(protocol_type) = target_nr.filter_ref().and_then(|nrf| {
+            [ProtocolType::UDP, ProtocolType::TCP]
+                .into_iter()
+                .find(|pt| nrf.dial_info_filter.protocol_type_set == ProtocolTypeSet::only(*pt))
+        }) else {
+            bail!("hole punch target must be filtered to udp or tcp");
+        };
 
         // Build a return receipt for the signal
         let receipt_timeout = TimestampDuration::new_ms(
@@ -737,37 +816,65 @@ impl NetworkManager {
         // Get our peer info
         let peer_info = self.routing_table().get_own_peer_info(routing_domain);
 
-        // Get the udp direct dialinfo for the hole punch
+        // Get the direct dialinfo for the hole punch
         let hole_punch_did = target_nr
             .first_filtered_dial_info_detail()
             .ok_or_else(|| eyre!("No hole punch capable dialinfo found for node"))?;
 
-        // Do our half of the hole punch by sending an empty packet
+        // Do our half of a udp hole punch by sending an empty packet
         // Both sides will do this and then the receipt will get sent over the punched hole
         // Don't bother storing the returned flow as the 'last flow' because the other side of the hole
         // punch should come through and create a real 'last connection' for us if this succeeds
-        network_result_try!(
-            self.net()
-                .send_data_to_dial_info(hole_punch_did.dial_info, Vec::new())
-                .await?
-        );
+        if protocol_type == ProtocolType::UDP {
+            network_result_try!(
+                self.net()
+                    .send_data_to_dial_info(hole_punch_did.dial_info.clone(), Vec::new())
+                    .await?
+            );
+        }
 
         // Issue the signal
         let rpc = self.rpc_processor();
         network_result_try!(rpc
             .rpc_call_signal(
                 Destination::relay(relay_nr, target_nr.clone()),
-                SignalInfo::HolePunch { receipt, peer_info },
+                SignalInfo::HolePunch {
+                    receipt,
+                    peer_info,
+                    protocol_type,
+                },
             )
             .await
             .wrap_err("failed to send signal")?);
 
-        // Wait for the return receipt
-        let inbound_nr = match eventual_value
-            .timeout_at(stop_token)
-            .in_current_span()
-            .await
-        {
+        // Do our half of a tcp hole punch by connecting to the target while it connects to us
+        // The simultaneous open makes the connection the receipt will get sent over
+        let tcp_hole_punch_fut = async {
+            if protocol_type == ProtocolType::TCP {
+                if let Err(e) = self
+                    .connect_tcp_hole_punch(
+                        hole_punch_did.dial_info.clone(),
+                        receipt_timeout,
+                        stop_token.clone(),
+                    )
+                    .await
+                {
+                    log_net!(debug "tcp hole punch to {} failed: {}", hole_punch_did.dial_info, e);
+                }
+            }
+        };
+
+        // Wait for the return receipt, stopping the tcp hole punch if it comes back first
+        let receipt_fut = Box::pin(
+            eventual_value
+                .timeout_at(stop_token.clone())
+                .in_current_span(),
+        );
+        let receipt_result = match select(receipt_fut, Box::pin(tcp_hole_punch_fut)).await {
+            Either::Left((res, _)) => res,
+            Either::Right((_, receipt_fut)) => receipt_fut.await,
+        };
+        let inbound_nr = match receipt_result {
             Err(_) => {
                 return Ok(NetworkResult::service_unavailable("network is stopping"));
             }
@@ -822,4 +929,163 @@ impl NetworkManager {
             )));
         }
     }
+
+    /// Do our half of a TCP hole punch by repeatedly connecting to the peer from our listening port
+    /// The peer does the same towards us, and once both sides' SYNs have passed through their NATs
+    /// the simultaneous open completes and the connection is registered with the connection manager
+    #[instrument(level = "trace", target = "net", skip_all, err)]
+    pub(super) async fn connect_tcp_hole_punch(
+        &self,
+        dial_info: DialInfo,
+        timeout: TimestampDuration,
+        stop_token: StopToken,
+    ) -> EyreResult<NetworkResult<UniqueFlow>> {
+        let deadline = Timestamp::now() + timeout;
+        loop {
+            let res = match self
+                .net()
+                .connect_tcp_hole_punch(dial_info.clone())
+                .timeout_at(stop_token.clone())
+                .await
+            {
+                Err(_) => {
+                    return Ok(NetworkResult::service_unavailable("network is stopping"));
+                }
+                Ok(v) => v?,
+            };
+            if res.is_value() || Timestamp::now() >= deadline {
+                return Ok(res);
+            }
+            log_net!("tcp hole punch attempt to {} failed: {}", dial_info, res);
+            sleep(TCP_HOLE_PUNCH_RETRY_DELAY_MS).await;
+        }
+    }
+
+    /// Send a WebRTC offer signal and wait for the return receipt over the negotiated connection
+    /// Then send the data across the new connection
+    /// Only usable for PublicInternet routing domain
+    #[instrument(level = "trace", target = "net", skip_all, err)]
+    async fn do_webrtc_connect(
+        &self,
+        relay_nr: NodeRef,
+        target_nr: NodeRef,
+        data: Vec<u8>,
+    ) -> EyreResult<NetworkResult<UniqueFlow>> {
+        // Detect if network is stopping so we can break out of this
+        let Some(stop_token) = self.unlocked_inner.startup_lock.stop_token() else {
+            return Ok(NetworkResult::service_unavailable("network is stopping"));
+        };
+
+        // Build a return receipt for the signal
+        // Both sides gather their ICE candidates before the connection can be made, so allow for that too
+        let receipt_timeout = {
+            let c = self.unlocked_inner.config.get();
+            TimestampDuration::new_ms(
+                c.network.reverse_connection_receipt_time_ms as u64
+                    + 2 * c.network.connection_initial_timeout_ms as u64,
+            )
+        };
+        let (receipt, eventual_value) = self.generate_single_shot_receipt(receipt_timeout, [])?;
+
+        // Get target routing domain
+        let Some(routing_domain) = target_nr.best_routing_domain() else {
+            return Ok(NetworkResult::no_connection_other(
+                "No routing domain for target for webrtc connect",
+            ));
+        };
+
+        // Ensure we have a valid network class so our peer info is useful
+        if !self.routing_table().has_valid_network_class(routing_domain) {
+            return Ok(NetworkResult::no_connection_other(
+                "Network class not yet valid for webrtc connect",
+            ));
+        };
+
+        // Get our peer info
+        let peer_info = self.routing_table().get_own_peer_info(routing_domain);
+
+        // Make our offer, which will wait for the answer to be signaled back
+        let sdp = network_result_try!(self.net().start_webrtc_offer(receipt.clone()).await?);
+
+        // Issue the signal
+        let rpc = self.rpc_processor();
+        let res = rpc
+            .rpc_call_signal(
+                Destination::relay(relay_nr, target_nr.clone()),
+                SignalInfo::WebRTCOffer {
+                    receipt: receipt.clone(),
+                    sdp,
+                    peer_info,
+                },
+            )
+            .await
+            .wrap_err("failed to send signal");
+        if !matches!(res, Ok(NetworkResult::Value(()))) {
+            self.net().cancel_webrtc(&receipt);
+        }
+        network_result_try!(res?);
+
+        // Wait for the return receipt
+        let inbound_nr = match eventual_value
+            .timeout_at(stop_token)
+            .in_current_span()
+            .await
+        {
+            Err(_) => {
+                self.net().cancel_webrtc(&receipt);
+                return Ok(NetworkResult::service_unavailable("network is stopping"));
+            }
+            Ok(v) => {
+                let receipt_event = v.take_value().unwrap();
+                match receipt_event {
+                    ReceiptEvent::ReturnedPrivate { private_route: _ }
+                    | ReceiptEvent::ReturnedOutOfBand
+                    | ReceiptEvent::ReturnedSafety => {
+                        return Ok(NetworkResult::invalid_message(
+                            "webrtc connect receipt should be returned in-band",
+                        ));
+                    }
+                    ReceiptEvent::ReturnedInBand { inbound_noderef } => inbound_noderef,
+                    ReceiptEvent::Expired => {
+                        self.net().cancel_webrtc(&receipt);
+                        return Ok(NetworkResult::timeout());
+                    }
+                    ReceiptEvent::Cancelled => {
+                        self.net().cancel_webrtc(&receipt);
+                        return Ok(NetworkResult::no_connection_other(format!(
+                            "webrtc connect receipt cancelled from {}",
+                            target_nr
+                        )));
+                    }
+                }
+            }
+        };
+
+        // We expect the inbound noderef to be the same as the target noderef
+        // if they aren't the same, we should error on this and figure out what then hell is up
+        if !target_nr.same_entry(&inbound_nr) {
+            bail!(
+                "unexpected noderef mismatch on webrtc connect {}, expected {}",
+                inbound_nr,
+                target_nr
+            );
+        }
+
+        // And now use the existing connection to send over
+        if let Some(flow) = inbound_nr.last_flow() {
+            match self.net().send_data_to_existing_flow(flow, data).await? {
+                SendDataToExistingFlowResult::Sent(unique_flow) => {
+                    Ok(NetworkResult::value(unique_flow))
+                }
+                SendDataToExistingFlowResult::NotSent(_) => Ok(NetworkResult::no_connection_other(
+                    "unable to send over webrtc connection",
+                )),
+            }
+        } else {
+            Ok(NetworkResult::no_connection_other(format!(
+                "webrtc connection dropped from {}",
+                target_nr
+            )))
+        }
+    }
 }
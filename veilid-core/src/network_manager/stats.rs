@@ -75,6 +75,11 @@ impl NetworkManager {
         inner.stats.clone()
     }
 
+    pub fn get_self_transfer_stats(&self) -> TransferStatsDownUp {
+        let inner = self.inner.lock();
+        inner.stats.self_stats.transfer_stats.clone()
+    }
+
     pub fn get_veilid_state(&self) -> Box<VeilidStateNetwork> {
         if !self.network_is_started() {
             return Box::new(VeilidStateNetwork {
@@ -84,7 +89,6 @@ impl NetworkManager {
                 peers: Vec::new(),
             });
         }
-        let routing_table = self.routing_table();
 
         let (bps_down, bps_up) = {
             let inner = self.inner.lock();
@@ -98,24 +102,28 @@ impl NetworkManager {
             started: true,
             bps_down,
             bps_up,
-            peers: {
-                let mut out = Vec::new();
-                for (k, v) in routing_table.get_recent_peers() {
-                    if let Ok(Some(nr)) = routing_table.lookup_node_ref(k) {
-                        let peer_stats = nr.peer_stats();
-                        let peer = PeerTableData {
-                            node_ids: nr.node_ids().iter().copied().collect(),
-                            peer_address: v.last_connection.remote().to_string(),
-                            peer_stats,
-                        };
-                        out.push(peer);
-                    }
-                }
-                out
-            },
+            peers: self.get_recent_peer_table_data(),
         })
     }
 
+    pub fn get_recent_peer_table_data(&self) -> Vec<PeerTableData> {
+        let routing_table = self.routing_table();
+
+        let mut out = Vec::new();
+        for (k, v) in routing_table.get_recent_peers() {
+            if let Ok(Some(nr)) = routing_table.lookup_node_ref(k) {
+                let peer_stats = nr.peer_stats();
+                let peer = PeerTableData {
+                    node_ids: nr.node_ids().iter().copied().collect(),
+                    peer_address: v.last_connection.remote().to_string(),
+                    peer_stats,
+                };
+                out.push(peer);
+            }
+        }
+        out
+    }
+
     pub(super) fn send_network_update(&self) {
         let update_cb = self.unlocked_inner.update_callback.read().clone();
         if update_cb.is_none() {
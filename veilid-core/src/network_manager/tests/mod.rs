@@ -1,4 +1,5 @@
 pub mod test_connection_table;
+pub mod test_protocol_type;
 pub mod test_signed_node_info;
 
 use super::*;
@@ -0,0 +1,75 @@
+use super::*;
+
+pub async fn test_sort_order() {
+    // Dial info protocols keep their established preference order
+    for (pt, unordered, ordered) in [
+        (ProtocolType::UDP, 0, 3),
+        (ProtocolType::TCP, 1, 0),
+        (ProtocolType::WS, 2, 1),
+        (ProtocolType::WSS, 3, 2),
+        (ProtocolType::WRTC, 4, 4),
+    ] {
+        assert_eq!(pt.sort_order(Sequencing::NoPreference), unordered);
+        assert_eq!(pt.sort_order(Sequencing::PreferOrdered), ordered);
+        assert_eq!(pt.sort_order(Sequencing::EnsureOrdered), ordered);
+    }
+
+    // WRTC is only ever the last resort
+    let mut pts = vec![
+        ProtocolType::WRTC,
+        ProtocolType::UDP,
+        ProtocolType::WSS,
+        ProtocolType::TCP,
+        ProtocolType::WS,
+    ];
+    pts.sort_by(|a, b| ProtocolType::ordered_sequencing_sort(*a, *b));
+    assert_eq!(
+        pts,
+        vec![
+            ProtocolType::TCP,
+            ProtocolType::WS,
+            ProtocolType::WSS,
+            ProtocolType::UDP,
+            ProtocolType::WRTC,
+        ]
+    );
+}
+
+pub async fn test_low_level_protocol_type() {
+    assert_eq!(
+        ProtocolType::UDP.low_level_protocol_type(),
+        Some(LowLevelProtocolType::UDP)
+    );
+    for pt in [ProtocolType::TCP, ProtocolType::WS, ProtocolType::WSS] {
+        assert_eq!(
+            pt.low_level_protocol_type(),
+            Some(LowLevelProtocolType::TCP)
+        );
+    }
+    // WRTC never shares a socket with UDP
+    assert_eq!(ProtocolType::WRTC.low_level_protocol_type(), None);
+}
+
+pub async fn test_ordered() {
+    assert!(!ProtocolType::UDP.is_ordered());
+    for pt in ProtocolType::all_ordered_set() {
+        assert!(pt.is_ordered());
+    }
+    assert!(ProtocolType::all_ordered_set().contains(ProtocolType::WRTC));
+    assert!(!ProtocolType::all_ordered_set().contains(ProtocolType::UDP));
+}
+
+pub async fn test_parse_display() {
+    for pt in ProtocolTypeSet::all() {
+        assert_eq!(ProtocolType::from_str(&pt.to_string()).unwrap(), pt);
+    }
+    assert_eq!(ProtocolType::from_str("wrtc").unwrap(), ProtocolType::WRTC);
+    assert!(ProtocolType::from_str("webrtc").is_err());
+}
+
+pub async fn test_all() {
+    test_sort_order().await;
+    test_low_level_protocol_type().await;
+    test_ordered().await;
+    test_parse_display().await;
+}
@@ -3,6 +3,8 @@ use super::*;
 
 // Keep member order appropriate for sorting < preference
 // Must match DialInfo order
+// WRTC has no DialInfo as it is only ever established via signaling,
+// and is only supported on WASM targets where the browser provides the WebRTC stack
 #[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Debug, PartialOrd, Ord, Hash, EnumSetType, Serialize, Deserialize)]
 #[enumset(repr = "u8")]
@@ -11,19 +13,25 @@ pub enum ProtocolType {
     TCP = 1,
     WS = 2,
     WSS = 3,
+    WRTC = 4,
 }
 
 impl ProtocolType {
     pub fn is_ordered(&self) -> bool {
         matches!(
             self,
-            ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS
+            ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS | ProtocolType::WRTC
         )
     }
-    pub fn low_level_protocol_type(&self) -> LowLevelProtocolType {
+    /// The socket protocol this protocol type runs over on our own ports.
+    /// WRTC runs inside the browser's WebRTC stack and has no socket of ours to map or share.
+    pub fn low_level_protocol_type(&self) -> Option<LowLevelProtocolType> {
         match self {
-            ProtocolType::UDP => LowLevelProtocolType::UDP,
-            ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS => LowLevelProtocolType::TCP,
+            ProtocolType::UDP => Some(LowLevelProtocolType::UDP),
+            ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS => {
+                Some(LowLevelProtocolType::TCP)
+            }
+            ProtocolType::WRTC => None,
         }
     }
     pub fn sort_order(&self, sequencing: Sequencing) -> usize {
@@ -56,10 +64,12 @@ impl ProtocolType {
                     3
                 }
             }
+            // Only reachable via signaling, so always the last resort
+            ProtocolType::WRTC => 4,
         }
     }
     pub fn all_ordered_set() -> ProtocolTypeSet {
-        ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS
+        ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS | ProtocolType::WRTC
     }
 
     pub fn ordered_sequencing_sort(a: Self, b: Self) -> core::cmp::Ordering {
@@ -82,6 +92,7 @@ impl fmt::Display for ProtocolType {
             ProtocolType::TCP => write!(f, "TCP"),
             ProtocolType::WS => write!(f, "WS"),
             ProtocolType::WSS => write!(f, "WSS"),
+            ProtocolType::WRTC => write!(f, "WRTC"),
         }
     }
 }
@@ -94,6 +105,7 @@ impl FromStr for ProtocolType {
             "TCP" => Ok(ProtocolType::TCP),
             "WS" => Ok(ProtocolType::WS),
             "WSS" => Ok(ProtocolType::WSS),
+            "WRTC" => Ok(ProtocolType::WRTC),
             _ => Err(VeilidAPIError::parse_error(
                 "ProtocolType::from_str failed",
                 s,
@@ -1,14 +1,19 @@
 use super::*;
 
+/// The largest session description that can be signaled for a WebRTC connection
+pub const MAX_SIGNAL_SDP_LEN: usize = 8192;
+
 /// Parameter for Signal operation
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SignalInfo {
-    /// UDP Hole Punch Request
+    /// UDP or TCP Hole Punch Request
     HolePunch {
         /// /// Receipt to be returned after the hole punch
         receipt: Vec<u8>,
         /// Sender's peer info
         peer_info: PeerInfo,
+        /// Protocol to hole punch with, UDP or TCP
+        protocol_type: ProtocolType,
     },
     /// Reverse Connection Request
     ReverseConnect {
@@ -17,19 +22,43 @@ pub enum SignalInfo {
         /// Sender's peer info
         peer_info: PeerInfo,
     },
-    // XXX: WebRTC
+    /// WebRTC Connection Offer
+    WebRTCOffer {
+        /// Receipt to be returned over the WebRTC connection
+        receipt: Vec<u8>,
+        /// Sender's session description
+        sdp: String,
+        /// Sender's peer info
+        peer_info: PeerInfo,
+    },
+    /// WebRTC Connection Answer
+    WebRTCAnswer {
+        /// Receipt from the offer being answered
+        receipt: Vec<u8>,
+        /// Answerer's session description
+        sdp: String,
+    },
 }
 
 impl SignalInfo {
     pub fn validate(&self, crypto: Crypto) -> Result<(), RPCError> {
         match self {
-            SignalInfo::HolePunch { receipt, peer_info } => {
+            SignalInfo::HolePunch {
+                receipt,
+                peer_info,
+                protocol_type,
+            } => {
                 if receipt.len() < MIN_RECEIPT_SIZE {
                     return Err(RPCError::protocol("SignalInfo HolePunch receipt too short"));
                 }
                 if receipt.len() > MAX_RECEIPT_SIZE {
                     return Err(RPCError::protocol("SignalInfo HolePunch receipt too long"));
                 }
+                if !matches!(protocol_type, ProtocolType::UDP | ProtocolType::TCP) {
+                    return Err(RPCError::protocol(
+                        "SignalInfo HolePunch protocol must be udp or tcp",
+                    ));
+                }
                 peer_info.validate(crypto).map_err(RPCError::protocol)
             }
             SignalInfo::ReverseConnect { receipt, peer_info } => {
@@ -45,6 +74,42 @@ impl SignalInfo {
                 }
                 peer_info.validate(crypto).map_err(RPCError::protocol)
             }
+            SignalInfo::WebRTCOffer {
+                receipt,
+                sdp,
+                peer_info,
+            } => {
+                if receipt.len() < MIN_RECEIPT_SIZE {
+                    return Err(RPCError::protocol(
+                        "SignalInfo WebRTCOffer receipt too short",
+                    ));
+                }
+                if receipt.len() > MAX_RECEIPT_SIZE {
+                    return Err(RPCError::protocol(
+                        "SignalInfo WebRTCOffer receipt too long",
+                    ));
+                }
+                if sdp.len() > MAX_SIGNAL_SDP_LEN {
+                    return Err(RPCError::protocol("SignalInfo WebRTCOffer sdp too long"));
+                }
+                peer_info.validate(crypto).map_err(RPCError::protocol)
+            }
+            SignalInfo::WebRTCAnswer { receipt, sdp } => {
+                if receipt.len() < MIN_RECEIPT_SIZE {
+                    return Err(RPCError::protocol(
+                        "SignalInfo WebRTCAnswer receipt too short",
+                    ));
+                }
+                if receipt.len() > MAX_RECEIPT_SIZE {
+                    return Err(RPCError::protocol(
+                        "SignalInfo WebRTCAnswer receipt too long",
+                    ));
+                }
+                if sdp.len() > MAX_SIGNAL_SDP_LEN {
+                    return Err(RPCError::protocol("SignalInfo WebRTCAnswer sdp too long"));
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -4,6 +4,7 @@ use super::*;
 
 use crate::routing_table::*;
 use connection_manager::*;
+use protocol::wrtc::{WebRTCPendingConnection, WebRTCProtocolHandler};
 use protocol::ws::WebsocketProtocolHandler;
 pub use protocol::*;
 use std::io;
@@ -54,6 +55,8 @@ pub const MAX_CAPABILITIES: usize = 64;
 struct NetworkInner {
     network_needs_restart: bool,
     protocol_config: ProtocolConfig,
+    /// WebRTC connections being negotiated by signaling, keyed by the signal's receipt
+    pending_webrtc: HashMap<Vec<u8>, Arc<WebRTCPendingConnection>>,
 }
 
 struct NetworkUnlockedInner {
@@ -78,6 +81,7 @@ impl Network {
         NetworkInner {
             network_needs_restart: false,
             protocol_config: Default::default(),
+            pending_webrtc: HashMap::new(),
         }
     }
 
@@ -171,6 +175,9 @@ impl Network {
                 ProtocolType::TCP => {
                     bail!("no support for TCP protocol")
                 }
+                ProtocolType::WRTC => {
+                    bail!("no support for WRTC dial info")
+                }
                 ProtocolType::WS | ProtocolType::WSS => {
                     let pnc = network_result_try!(WebsocketProtocolHandler::connect(
                         &dial_info, timeout_ms
@@ -226,10 +233,14 @@ impl Network {
                 ProtocolType::TCP => {
                     bail!("no support for TCP protocol")
                 }
+                ProtocolType::WRTC => {
+                    bail!("no support for WRTC dial info")
+                }
                 ProtocolType::WS | ProtocolType::WSS => {
                     let pnc = network_result_try!(match dial_info.protocol_type() {
                         ProtocolType::UDP => unreachable!(),
                         ProtocolType::TCP => unreachable!(),
+                        ProtocolType::WRTC => unreachable!(),
                         ProtocolType::WS | ProtocolType::WSS => {
                             WebsocketProtocolHandler::connect(&dial_info, connect_timeout_ms)
                                 .await
@@ -322,6 +333,9 @@ impl Network {
             if dial_info.protocol_type() == ProtocolType::TCP {
                 bail!("no support for TCP protocol");
             }
+            if dial_info.protocol_type() == ProtocolType::WRTC {
+                bail!("no support for WRTC dial info");
+            }
 
             // Handle connection-oriented protocols
             let conn = network_result_try!(
@@ -347,6 +361,161 @@ impl Network {
         .await
     }
 
+    pub async fn connect_tcp_hole_punch(
+        &self,
+        _dial_info: DialInfo,
+    ) -> EyreResult<NetworkResult<UniqueFlow>> {
+        Ok(NetworkResult::service_unavailable(
+            "TCP is not supported on WASM targets",
+        ))
+    }
+
+    /////////////////////////////////////////////////////////////////
+    // WebRTC negotiation
+
+    fn webrtc_parameters(&self) -> (Vec<String>, u32) {
+        let c = self.config.get();
+        (
+            c.network.protocol.wrtc.ice_servers.clone(),
+            c.network.connection_initial_timeout_ms,
+        )
+    }
+
+    // Create a WebRTC offer to signal to a remote node, returning its session description
+    #[instrument(level = "trace", target = "net", err, skip(self, receipt))]
+    pub async fn start_webrtc_offer(&self, receipt: Vec<u8>) -> EyreResult<NetworkResult<String>> {
+        let _guard = self.unlocked_inner.startup_lock.enter()?;
+
+        if !self
+            .inner
+            .lock()
+            .protocol_config
+            .outbound
+            .contains(ProtocolType::WRTC)
+        {
+            return Ok(NetworkResult::service_unavailable("WRTC is not enabled"));
+        }
+
+        let (ice_servers, timeout_ms) = self.webrtc_parameters();
+        let pending = network_result_try!(WebRTCProtocolHandler::create_offer(
+            &ice_servers,
+            timeout_ms
+        )
+        .await
+        .wrap_err("create offer failure")?);
+        let sdp = pending.local_sdp();
+
+        self.inner
+            .lock()
+            .pending_webrtc
+            .insert(receipt, Arc::new(pending));
+
+        Ok(NetworkResult::value(sdp))
+    }
+
+    // Apply the answer a remote node signaled back for one of our WebRTC offers
+    #[instrument(level = "trace", target = "net", err, skip(self, receipt))]
+    pub async fn complete_webrtc_offer(
+        &self,
+        receipt: Vec<u8>,
+        answer_sdp: String,
+    ) -> EyreResult<NetworkResult<()>> {
+        let _guard = self.unlocked_inner.startup_lock.enter()?;
+
+        let Some(pending) = self.inner.lock().pending_webrtc.get(&receipt).cloned() else {
+            return Ok(NetworkResult::invalid_message(
+                "no pending webrtc offer for answer",
+            ));
+        };
+        if let Err(e) = pending.set_remote_answer(answer_sdp).await {
+            self.cancel_webrtc(&receipt);
+            return Ok(NetworkResult::invalid_message(format!(
+                "invalid webrtc answer: {}",
+                e
+            )));
+        }
+        Ok(NetworkResult::value(()))
+    }
+
+    // Answer a WebRTC offer signaled by a remote node, returning the session description to signal back
+    #[instrument(level = "trace", target = "net", err, skip(self, receipt))]
+    pub async fn accept_webrtc_offer(
+        &self,
+        receipt: Vec<u8>,
+        offer_sdp: String,
+    ) -> EyreResult<NetworkResult<String>> {
+        let _guard = self.unlocked_inner.startup_lock.enter()?;
+
+        if !self
+            .inner
+            .lock()
+            .protocol_config
+            .outbound
+            .contains(ProtocolType::WRTC)
+        {
+            return Ok(NetworkResult::service_unavailable("WRTC is not enabled"));
+        }
+
+        let (ice_servers, timeout_ms) = self.webrtc_parameters();
+        let pending =
+            match WebRTCProtocolHandler::create_answer(&ice_servers, offer_sdp, timeout_ms).await {
+                Ok(v) => network_result_try!(v),
+                Err(e) => {
+                    return Ok(NetworkResult::invalid_message(format!(
+                        "invalid webrtc offer: {}",
+                        e
+                    )));
+                }
+            };
+        let sdp = pending.local_sdp();
+
+        self.inner
+            .lock()
+            .pending_webrtc
+            .insert(receipt, Arc::new(pending));
+
+        Ok(NetworkResult::value(sdp))
+    }
+
+    // Wait for a negotiated WebRTC connection to open and add it to the connection table
+    #[instrument(level = "trace", target = "net", err, skip(self, receipt))]
+    pub async fn connect_webrtc(&self, receipt: Vec<u8>) -> EyreResult<NetworkResult<Flow>> {
+        let _guard = self.unlocked_inner.startup_lock.enter()?;
+
+        let Some(pending) = self.inner.lock().pending_webrtc.remove(&receipt) else {
+            return Ok(NetworkResult::no_connection_other(
+                "no pending webrtc connection",
+            ));
+        };
+
+        let (_, timeout_ms) = self.webrtc_parameters();
+        let prot_conn = network_result_try!(pending
+            .connect(timeout_ms)
+            .await
+            .wrap_err("connect failure")?);
+
+        if self
+            .network_manager()
+            .address_filter()
+            .is_ip_addr_punished(prot_conn.flow().remote_address().address().ip_addr())
+        {
+            return Ok(NetworkResult::no_connection_other("punished"));
+        }
+
+        let conn = network_result_try!(
+            self.connection_manager()
+                .register_negotiated_connection(prot_conn)
+                .await?
+        );
+
+        Ok(NetworkResult::value(conn.flow()))
+    }
+
+    // Abandon a WebRTC negotiation
+    pub fn cancel_webrtc(&self, receipt: &[u8]) {
+        self.inner.lock().pending_webrtc.remove(receipt);
+    }
+
     /////////////////////////////////////////////////////////////////
 
     pub async fn startup_internal(&self) -> EyreResult<StartupDisposition> {
@@ -363,6 +532,9 @@ impl Network {
             if c.network.protocol.wss.connect {
                 outbound.insert(ProtocolType::WSS);
             }
+            if c.network.protocol.wrtc.connect {
+                outbound.insert(ProtocolType::WRTC);
+            }
 
             let supported_address_types: AddressTypeSet = if is_ipv6_supported() {
                 AddressType::IPV4 | AddressType::IPV6
@@ -378,6 +550,7 @@ impl Network {
                     .iter()
                     .copied()
                     .filter(|cap| !c.capabilities.disable.contains(cap))
+                    .chain(c.capabilities.advertise.iter().copied())
                     .collect::<Vec<Capability>>()
             };
 
@@ -9,7 +9,7 @@ pub(in crate::network_manager) enum ProtocolNetworkConnection {
     #[allow(dead_code)]
     //Dummy(DummyNetworkConnection),
     Ws(ws::WebsocketNetworkConnection),
-    //WebRTC(wrtc::WebRTCNetworkConnection),
+    WebRTC(wrtc::WebRTCNetworkConnection),
 }
 
 impl ProtocolNetworkConnection {
@@ -32,6 +32,9 @@ impl ProtocolNetworkConnection {
             ProtocolType::WS | ProtocolType::WSS => {
                 ws::WebsocketProtocolHandler::connect(dial_info, timeout_ms).await
             }
+            ProtocolType::WRTC => {
+                panic!("WRTC connections are negotiated by signaling, not dial info");
+            }
         }
     }
 
@@ -39,18 +42,21 @@ impl ProtocolNetworkConnection {
         match self {
             //            Self::Dummy(d) => d.flow(),
             Self::Ws(w) => w.flow(),
+            Self::WebRTC(w) => w.flow(),
         }
     }
     pub async fn close(&self) -> io::Result<NetworkResult<()>> {
         match self {
             //            Self::Dummy(d) => d.close(),
             Self::Ws(w) => w.close().await,
+            Self::WebRTC(w) => w.close().await,
         }
     }
     pub async fn send(&self, message: Vec<u8>) -> io::Result<NetworkResult<()>> {
         match self {
             //            Self::Dummy(d) => d.send(message),
             Self::Ws(w) => w.send(message).await,
+            Self::WebRTC(w) => w.send(message).await,
         }
     }
 
@@ -58,6 +64,7 @@ impl ProtocolNetworkConnection {
         match self {
             //            Self::Dummy(d) => d.recv(),
             Self::Ws(w) => w.recv().await,
+            Self::WebRTC(w) => w.recv().await,
         }
     }
 }
@@ -1 +1,460 @@
+use super::*;
+use send_wrapper::*;
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    MessageEvent, RtcConfiguration, RtcDataChannel, RtcDataChannelEvent, RtcDataChannelInit,
+    RtcDataChannelState, RtcDataChannelType, RtcIceGatheringState, RtcIceServer, RtcPeerConnection,
+    RtcSdpType, RtcSessionDescriptionInit,
+};
 
+/// The label of the data channel used for veilid traffic
+const DATA_CHANNEL_LABEL: &str = "veilid";
+
+type JsClosure = Closure<dyn FnMut(JsValue)>;
+type JsClosures = Rc<RefCell<Vec<JsClosure>>>;
+
+fn to_io(err: JsValue) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("{:?}", err))
+}
+
+enum DataChannelEvent {
+    Open(SendWrapper<RtcDataChannel>),
+    Message(Vec<u8>),
+    Closed,
+}
+
+/// Pick the remote address of a WebRTC peer out of its session description.
+/// Server reflexive candidates are preferred as they are the peer's public address.
+/// Host candidates obfuscated with mDNS are skipped as they can not be resolved here.
+fn remote_socket_addr_from_sdp(sdp: &str) -> Option<SocketAddr> {
+    let mut best: Option<(usize, SocketAddr)> = None;
+    for line in sdp.lines() {
+        let Some(candidate) = line.trim().strip_prefix("a=candidate:") else {
+            continue;
+        };
+        let parts: Vec<&str> = candidate.split_whitespace().collect();
+        if parts.len() < 8 || !parts[2].eq_ignore_ascii_case("udp") || parts[6] != "typ" {
+            continue;
+        }
+        let Ok(ip_addr) = IpAddr::from_str(parts[4]) else {
+            continue;
+        };
+        let Ok(port) = u16::from_str(parts[5]) else {
+            continue;
+        };
+        let preference = match parts[7] {
+            "srflx" => 0,
+            "prflx" => 1,
+            "host" => 2,
+            "relay" => 3,
+            _ => continue,
+        };
+        if best.map(|(p, _)| preference < p).unwrap_or(true) {
+            best = Some((preference, SocketAddr::new(ip_addr, port)));
+        }
+    }
+    best.map(|(_, sa)| sa)
+}
+
+struct WebRTCNetworkConnectionInner {
+    peer_connection: SendWrapper<RtcPeerConnection>,
+    data_channel: SendWrapper<RtcDataChannel>,
+    receiver: flume::Receiver<DataChannelEvent>,
+    closures: SendWrapper<JsClosures>,
+}
+
+impl Drop for WebRTCNetworkConnectionInner {
+    fn drop(&mut self) {
+        self.data_channel.close();
+        self.peer_connection.close();
+        // Event handlers may hold references to the closure list, so break the cycle
+        self.closures.borrow_mut().clear();
+    }
+}
+
+#[derive(Clone)]
+pub struct WebRTCNetworkConnection {
+    flow: Flow,
+    inner: Arc<WebRTCNetworkConnectionInner>,
+}
+
+impl fmt::Debug for WebRTCNetworkConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", core::any::type_name::<Self>())
+    }
+}
+
+impl WebRTCNetworkConnection {
+    pub fn flow(&self) -> Flow {
+        self.flow
+    }
+
+    #[cfg_attr(
+        feature = "verbose-tracing",
+        instrument(level = "trace", err, skip(self))
+    )]
+    pub async fn close(&self) -> io::Result<NetworkResult<()>> {
+        self.inner.data_channel.close();
+        self.inner.peer_connection.close();
+        Ok(NetworkResult::value(()))
+    }
+
+    #[instrument(level = "trace", target="protocol", err, skip(self, message), fields(network_result, message.len = message.len()))]
+    pub async fn send(&self, message: Vec<u8>) -> io::Result<NetworkResult<()>> {
+        if message.len() > MAX_MESSAGE_SIZE {
+            bail_io_error_other!("sending too large WRTC message");
+        }
+        if self.inner.data_channel.ready_state() != RtcDataChannelState::Open {
+            return Ok(NetworkResult::no_connection(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "WRTC data channel not open",
+            )));
+        }
+        let out = self
+            .inner
+            .data_channel
+            .send_with_u8_array(&message)
+            .map_err(to_io)
+            .into_network_result()?;
+
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("network_result", &tracing::field::display(&out));
+        Ok(out)
+    }
+
+    #[instrument(level = "trace", target="protocol", err, skip(self), fields(network_result, ret.len))]
+    pub async fn recv(&self) -> io::Result<NetworkResult<Vec<u8>>> {
+        let out = match self.inner.receiver.recv_async().await {
+            Ok(DataChannelEvent::Message(v)) => {
+                if v.len() > MAX_MESSAGE_SIZE {
+                    return Ok(NetworkResult::invalid_message("too large wrtc message"));
+                }
+                NetworkResult::Value(v)
+            }
+            Ok(DataChannelEvent::Open(_)) => NetworkResult::no_connection_other(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "Unexpected WRTC data channel event",
+            )),
+            Ok(DataChannelEvent::Closed) | Err(_) => {
+                return Ok(NetworkResult::no_connection(io::Error::new(
+                    io::ErrorKind::ConnectionReset,
+                    "WRTC data channel closed",
+                )));
+            }
+        };
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("network_result", &tracing::field::display(&out));
+        Ok(out)
+    }
+}
+
+///////////////////////////////////////////////////////////
+
+/// A WebRTC peer connection that is still being negotiated via signaling
+pub(in crate::network_manager) struct WebRTCPendingConnection {
+    peer_connection: SendWrapper<RtcPeerConnection>,
+    local_sdp: String,
+    remote_sdp: Mutex<Option<String>>,
+    receiver: flume::Receiver<DataChannelEvent>,
+    closures: Mutex<Option<SendWrapper<JsClosures>>>,
+}
+
+impl fmt::Debug for WebRTCPendingConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", core::any::type_name::<Self>())
+    }
+}
+
+impl WebRTCPendingConnection {
+    /// The session description to signal to the remote peer
+    pub fn local_sdp(&self) -> String {
+        self.local_sdp.clone()
+    }
+
+    /// Apply the answer the remote peer signaled back for our offer
+    #[instrument(level = "trace", target = "protocol", err, skip(self))]
+    pub async fn set_remote_answer(&self, answer_sdp: String) -> io::Result<()> {
+        let mut desc = RtcSessionDescriptionInit::new(RtcSdpType::Answer);
+        desc.sdp(&answer_sdp);
+        SendWrapper::new(JsFuture::from(
+            self.peer_connection.set_remote_description(&desc),
+        ))
+        .await
+        .map_err(to_io)?;
+        *self.remote_sdp.lock() = Some(answer_sdp);
+        Ok(())
+    }
+
+    /// Wait for the data channel to open and produce a connection from it
+    #[instrument(level = "trace", target = "protocol", ret, err, skip(self))]
+    pub async fn connect(
+        &self,
+        timeout_ms: u32,
+    ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        let receiver = self.receiver.clone();
+        let fut = SendWrapper::new(timeout(timeout_ms, async move {
+            loop {
+                match receiver.recv_async().await {
+                    Ok(DataChannelEvent::Open(dc)) => return Ok(dc),
+                    Ok(DataChannelEvent::Message(_)) => {}
+                    Ok(DataChannelEvent::Closed) | Err(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::ConnectionRefused,
+                            "WRTC data channel closed before opening",
+                        ))
+                    }
+                }
+            }
+        }));
+        let data_channel = network_result_try!(network_result_try!(fut
+            .await
+            .into_network_result())
+        .into_network_result()?);
+
+        // Get the remote address from what the peer told us about itself
+        let Some(remote_sdp) = self.remote_sdp.lock().clone() else {
+            bail_io_error_other!("WRTC remote description was never set");
+        };
+        let Some(remote_socket_addr) = remote_socket_addr_from_sdp(&remote_sdp) else {
+            return Ok(NetworkResult::no_connection_other(
+                "no usable address in WRTC remote description",
+            ));
+        };
+        let flow = Flow::new_no_local(PeerAddress::new(
+            SocketAddress::from_socket_addr(remote_socket_addr),
+            ProtocolType::WRTC,
+        ));
+
+        let Some(closures) = self.closures.lock().take() else {
+            bail_io_error_other!("WRTC connection already made");
+        };
+
+        Ok(NetworkResult::value(ProtocolNetworkConnection::WebRTC(
+            WebRTCNetworkConnection {
+                flow,
+                inner: Arc::new(WebRTCNetworkConnectionInner {
+                    peer_connection: SendWrapper::new((*self.peer_connection).clone()),
+                    data_channel,
+                    receiver: self.receiver.clone(),
+                    closures,
+                }),
+            },
+        )))
+    }
+}
+
+impl Drop for WebRTCPendingConnection {
+    fn drop(&mut self) {
+        // If we never made a connection out of this, tear it down
+        if let Some(closures) = self.closures.lock().take() {
+            self.peer_connection.close();
+            closures.borrow_mut().clear();
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////
+
+pub(in crate::network_manager) struct WebRTCProtocolHandler {}
+
+impl WebRTCProtocolHandler {
+    fn new_peer_connection(ice_servers: &[String]) -> io::Result<RtcPeerConnection> {
+        let urls = js_sys::Array::new();
+        for url in ice_servers {
+            urls.push(&JsValue::from_str(url));
+        }
+        let servers = js_sys::Array::new();
+        if urls.length() != 0 {
+            let mut ice_server = RtcIceServer::new();
+            ice_server.urls(&urls);
+            servers.push(&ice_server);
+        }
+        let mut config = RtcConfiguration::new();
+        config.ice_servers(&servers);
+        RtcPeerConnection::new_with_configuration(&config).map_err(to_io)
+    }
+
+    /// Forward data channel events to the pending connection's event channel
+    fn wire_data_channel(
+        data_channel: &RtcDataChannel,
+        sender: &flume::Sender<DataChannelEvent>,
+        closures: &JsClosures,
+    ) {
+        let mut closures = closures.borrow_mut();
+        data_channel.set_binary_type(RtcDataChannelType::Arraybuffer);
+
+        let onopen = {
+            let sender = sender.clone();
+            let data_channel = data_channel.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |_: JsValue| {
+                let _ = sender.try_send(DataChannelEvent::Open(SendWrapper::new(
+                    data_channel.clone(),
+                )));
+            })
+        };
+        data_channel.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+        closures.push(onopen);
+
+        let onmessage = {
+            let sender = sender.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |ev: JsValue| {
+                let Ok(ev) = ev.dyn_into::<MessageEvent>() else {
+                    return;
+                };
+                let data = js_sys::Uint8Array::new(&ev.data()).to_vec();
+                let _ = sender.try_send(DataChannelEvent::Message(data));
+            })
+        };
+        data_channel.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        closures.push(onmessage);
+
+        let onclose = {
+            let sender = sender.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |_: JsValue| {
+                let _ = sender.try_send(DataChannelEvent::Closed);
+            })
+        };
+        data_channel.set_onclose(Some(onclose.as_ref().unchecked_ref()));
+        data_channel.set_onerror(Some(onclose.as_ref().unchecked_ref()));
+        closures.push(onclose);
+    }
+
+    /// Set the local description and wait for ICE gathering to complete, so the
+    /// session description we signal contains all of our candidates
+    async fn set_local_description_and_gather(
+        peer_connection: &RtcPeerConnection,
+        description: JsValue,
+        timeout_ms: u32,
+        closures: &JsClosures,
+    ) -> io::Result<NetworkResult<String>> {
+        let (gathered_tx, gathered_rx) = flume::bounded::<()>(1);
+        let ongatheringchange = {
+            let peer_connection = peer_connection.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |_: JsValue| {
+                if peer_connection.ice_gathering_state() == RtcIceGatheringState::Complete {
+                    let _ = gathered_tx.try_send(());
+                }
+            })
+        };
+        peer_connection
+            .set_onicegatheringstatechange(Some(ongatheringchange.as_ref().unchecked_ref()));
+        closures.borrow_mut().push(ongatheringchange);
+
+        let desc: RtcSessionDescriptionInit = description.unchecked_into();
+        JsFuture::from(peer_connection.set_local_description(&desc))
+            .await
+            .map_err(to_io)?;
+
+        if peer_connection.ice_gathering_state() != RtcIceGatheringState::Complete {
+            network_result_try!(timeout(timeout_ms, gathered_rx.recv_async())
+                .await
+                .into_network_result())
+            .map_err(to_io_error_other)?;
+        }
+
+        let Some(local_description) = peer_connection.local_description() else {
+            bail_io_error_other!("WRTC local description missing after gathering");
+        };
+        Ok(NetworkResult::value(local_description.sdp()))
+    }
+
+    /// Create a peer connection with a data channel and an offer to signal to the remote peer
+    #[instrument(level = "trace", target = "protocol", ret, err)]
+    pub async fn create_offer(
+        ice_servers: &[String],
+        timeout_ms: u32,
+    ) -> io::Result<NetworkResult<WebRTCPendingConnection>> {
+        let fut = SendWrapper::new(async move {
+            let peer_connection = Self::new_peer_connection(ice_servers)?;
+            let (sender, receiver) = flume::unbounded();
+            let closures: JsClosures = Rc::new(RefCell::new(Vec::new()));
+
+            let mut dc_init = RtcDataChannelInit::new();
+            dc_init.ordered(true);
+            let data_channel = peer_connection
+                .create_data_channel_with_data_channel_dict(DATA_CHANNEL_LABEL, &dc_init);
+            Self::wire_data_channel(&data_channel, &sender, &closures);
+
+            let offer = JsFuture::from(peer_connection.create_offer())
+                .await
+                .map_err(to_io)?;
+            let local_sdp = network_result_try!(
+                Self::set_local_description_and_gather(
+                    &peer_connection,
+                    offer,
+                    timeout_ms,
+                    &closures
+                )
+                .await?
+            );
+
+            Ok(NetworkResult::value(WebRTCPendingConnection {
+                peer_connection: SendWrapper::new(peer_connection),
+                local_sdp,
+                remote_sdp: Mutex::new(None),
+                receiver,
+                closures: Mutex::new(Some(SendWrapper::new(closures))),
+            }))
+        });
+        fut.await
+    }
+
+    /// Create a peer connection from a remote peer's offer, and an answer to signal back to it
+    #[instrument(level = "trace", target = "protocol", ret, err)]
+    pub async fn create_answer(
+        ice_servers: &[String],
+        offer_sdp: String,
+        timeout_ms: u32,
+    ) -> io::Result<NetworkResult<WebRTCPendingConnection>> {
+        let fut = SendWrapper::new(async move {
+            let peer_connection = Self::new_peer_connection(ice_servers)?;
+            let (sender, receiver) = flume::unbounded();
+            let closures: JsClosures = Rc::new(RefCell::new(Vec::new()));
+
+            // The offerer creates the data channel, so wire it up when it arrives
+            let ondatachannel = {
+                let sender = sender.clone();
+                let closures = closures.clone();
+                Closure::<dyn FnMut(JsValue)>::new(move |ev: JsValue| {
+                    let Ok(ev) = ev.dyn_into::<RtcDataChannelEvent>() else {
+                        return;
+                    };
+                    Self::wire_data_channel(&ev.channel(), &sender, &closures);
+                })
+            };
+            peer_connection.set_ondatachannel(Some(ondatachannel.as_ref().unchecked_ref()));
+            closures.borrow_mut().push(ondatachannel);
+
+            let mut desc = RtcSessionDescriptionInit::new(RtcSdpType::Offer);
+            desc.sdp(&offer_sdp);
+            JsFuture::from(peer_connection.set_remote_description(&desc))
+                .await
+                .map_err(to_io)?;
+
+            let answer = JsFuture::from(peer_connection.create_answer())
+                .await
+                .map_err(to_io)?;
+            let local_sdp = network_result_try!(
+                Self::set_local_description_and_gather(
+                    &peer_connection,
+                    answer,
+                    timeout_ms,
+                    &closures
+                )
+                .await?
+            );
+
+            Ok(NetworkResult::value(WebRTCPendingConnection {
+                peer_connection: SendWrapper::new(peer_connection),
+                local_sdp,
+                remote_sdp: Mutex::new(Some(offer_sdp)),
+                receiver,
+                closures: Mutex::new(Some(SendWrapper::new(closures))),
+            }))
+        });
+        fut.await
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+/// Network prefix used to group IPv4 addresses the ASN table does not cover
+const UNMAPPED_IPV4_PREFIX_LENGTH: u32 = 16;
+/// Network prefix used to group IPv6 addresses the ASN table does not cover
+const UNMAPPED_IPV6_PREFIX_LENGTH: u32 = 32;
+
+/// Parse an ASN table entry of the form '<network>/<prefix length> <asn>' into a (network, netmask, asn) triple
+/// The ASN may optionally be written with an 'AS' prefix, as in '192.0.2.0/24 AS64496'
+pub fn parse_asn_table_entry(entry: &str) -> Option<(IpAddr, IpAddr, u32)> {
+    let mut parts = entry.split_whitespace();
+    let (network, netmask) = ipaddr_parse_cidr(parts.next()?)?;
+    let asn = parts.next()?;
+    let asn = asn
+        .strip_prefix("AS")
+        .or_else(|| asn.strip_prefix("as"))
+        .unwrap_or(asn);
+    let asn = u32::from_str(asn).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((network, netmask, asn))
+}
+
+/// The network an address belongs to, for keeping route hops apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum NetworkGroup {
+    /// The autonomous system announcing the address
+    Asn(u32),
+    /// The coarse network block of an address with no known autonomous system
+    Block(IpAddr),
+}
+
+/// Maps addresses to the autonomous system announcing them
+/// Configured by 'network.routing_table.asn_table'
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AsnTable {
+    /// Networks and their autonomous system, grouped by netmask
+    /// Longer netmasks sort after shorter ones, so the longest matching prefix is found by searching in reverse
+    networks: BTreeMap<IpAddr, HashMap<IpAddr, u32>>,
+}
+
+impl AsnTable {
+    /// Build the table from its config entries, skipping invalid ones
+    pub fn new(entries: &[String]) -> Self {
+        let mut networks = BTreeMap::<IpAddr, HashMap<IpAddr, u32>>::new();
+        for entry in entries {
+            let Some((network, netmask, asn)) = parse_asn_table_entry(entry) else {
+                log_rtab!(warn "invalid asn table entry: {}", entry);
+                continue;
+            };
+            networks.entry(netmask).or_default().insert(network, asn);
+        }
+        Self { networks }
+    }
+
+    /// Look up the autonomous system announcing an address, using the longest matching prefix
+    pub fn lookup(&self, addr: IpAddr) -> Option<u32> {
+        for (netmask, networks) in self.networks.iter().rev() {
+            if netmask.is_ipv4() != addr.is_ipv4() {
+                continue;
+            }
+            if let Some(asn) = networks.get(&ipaddr_apply_netmask(addr, *netmask)) {
+                return Some(*asn);
+            }
+        }
+        None
+    }
+
+    /// Get the network an address belongs to
+    /// Addresses with no known autonomous system are grouped by a coarse network prefix instead
+    pub fn network_group(&self, addr: IpAddr) -> NetworkGroup {
+        if let Some(asn) = self.lookup(addr) {
+            return NetworkGroup::Asn(asn);
+        }
+        let netmask = match addr {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(
+                u32::MAX << (32 - UNMAPPED_IPV4_PREFIX_LENGTH),
+            )),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(
+                u128::MAX << (128 - UNMAPPED_IPV6_PREFIX_LENGTH),
+            )),
+        };
+        NetworkGroup::Block(ipaddr_apply_netmask(addr, netmask))
+    }
+
+    /// Get the networks of all of a node's dial info addresses
+    pub fn node_network_groups(&self, node_info: &NodeInfo) -> BTreeSet<NetworkGroup> {
+        node_info
+            .dial_info_detail_list()
+            .iter()
+            .map(|did| self.network_group(did.dial_info.to_socket_addr().ip()))
+            .collect()
+    }
+}
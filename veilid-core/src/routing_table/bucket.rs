@@ -5,7 +5,7 @@ use core::sync::atomic::Ordering;
 /// Stores map of public keys to entries, which may be in multiple routing tables per crypto kind
 /// Keeps entries at a particular 'dht distance' from this cryptokind's node id
 /// Helps to keep managed lists at particular distances so we can evict nodes by priority
-/// where the priority comes from liveness and age of the entry (older is better)
+/// where the priority comes from the bucket retention policy (liveness, age, reliability and reputation of the entry)
 pub struct Bucket {
     /// Map of keys to entries for this bucket
     entries: BTreeMap<PublicKey, Arc<BucketEntry>>,
@@ -107,8 +107,15 @@ impl Bucket {
         self.entries.iter()
     }
 
+    /// Kick entries from the bucket until it is down to 'bucket_depth' entries
+    /// Entries are kicked in the order decided by the retention policy, and entries that are
+    /// referenced, exempt, or protected by the policy are kept.
+    /// At most 'bucket_depth' entries are protected, those the policy would keep longest, so a
+    /// bucket full of protected entries is still kicked down to its depth.
+    /// A bucket depth of zero empties the bucket of all unreferenced entries, including protected ones.
     pub(super) fn kick(
         &mut self,
+        policy: &dyn BucketRetentionPolicy,
         bucket_depth: usize,
         exempt_peers: &BTreeSet<PublicKey>,
     ) -> Option<BTreeSet<PublicKey>> {
@@ -120,28 +127,41 @@ impl Bucket {
             return None;
         }
 
-        // Try to purge the newest entries that overflow the bucket
+        // Try to purge the lowest retention entries that overflow the bucket
         let mut dead_node_ids: BTreeSet<PublicKey> = BTreeSet::new();
         let mut extra_entries = bucket_len - bucket_depth;
 
         // Get the sorted list of entries by their kick order
-        let mut sorted_entries: Vec<(PublicKey, Arc<BucketEntry>)> =
-            self.entries.iter().map(|(k, v)| (*k, v.clone())).collect();
         let cur_ts = Timestamp::now();
+        let mut sorted_entries: Vec<(PublicKey, Arc<BucketEntry>, bool)> = self
+            .entries
+            .iter()
+            .map(|(k, v)| {
+                let protected = v.with_inner(|e| policy.is_protected(e, cur_ts));
+                (*k, v.clone(), protected)
+            })
+            .collect();
         sorted_entries.sort_by(|a, b| -> core::cmp::Ordering {
             if a.0 == b.0 {
                 return core::cmp::Ordering::Equal;
             }
             a.1.with_inner(|ea| {
                 b.1.with_inner(|eb| {
-                    let astate = ea.state(cur_ts).ordering();
-                    let bstate = eb.state(cur_ts).ordering();
-                    // first kick punished nodes, then dead nodes, then unreliable nodes
-                    if astate < bstate {
-                        return core::cmp::Ordering::Less;
-                    }
-                    if astate > bstate {
-                        return core::cmp::Ordering::Greater;
+                    // first kick punished nodes, then dead nodes
+                    let astate = ea.state(cur_ts);
+                    let bstate = eb.state(cur_ts);
+                    if !astate.is_alive() || !bstate.is_alive() {
+                        let ord = astate.ordering().cmp(&bstate.ordering());
+                        if ord != core::cmp::Ordering::Equal {
+                            return ord;
+                        }
+                    } else {
+                        // then kick by retention score, lowest scores are kicked first
+                        let ascore = policy.retention_score(ea, cur_ts);
+                        let bscore = policy.retention_score(eb, cur_ts);
+                        if ascore != bscore {
+                            return ascore.cmp(&bscore);
+                        }
                     }
                     // then kick by time added, most recent nodes are kicked first
                     let ata = ea.peer_stats().time_added;
@@ -151,11 +171,30 @@ impl Bucket {
             })
         });
 
+        // Cap the protected entries at the bucket depth, keeping the last ones in kick order
+        let mut protected_count = 0usize;
+        for entry in sorted_entries.iter_mut().rev() {
+            if entry.2 {
+                if protected_count < bucket_depth {
+                    protected_count += 1;
+                } else {
+                    entry.2 = false;
+                }
+            }
+        }
+
         for entry in sorted_entries {
             // If we're not evicting more entries, exit, noting this may be the newest entry
             if extra_entries == 0 {
                 break;
             }
+
+            // if this entry is protected by the retention policy, don't drop it
+            // and don't count it against the entries we are trying to evict,
+            // there are always enough unprotected entries left to evict as protection is capped
+            if entry.2 {
+                continue;
+            }
             extra_entries -= 1;
 
             // if this entry has references we can't drop it yet
@@ -57,6 +57,17 @@ pub(crate) enum BucketEntryState {
     Reliable,
 }
 
+impl From<BucketEntryState> for DebugEntryState {
+    fn from(value: BucketEntryState) -> Self {
+        match value {
+            BucketEntryState::Punished => DebugEntryState::Punished,
+            BucketEntryState::Dead => DebugEntryState::Dead,
+            BucketEntryState::Unreliable => DebugEntryState::Unreliable,
+            BucketEntryState::Reliable => DebugEntryState::Reliable,
+        }
+    }
+}
+
 impl BucketEntryState {
     pub fn is_alive(&self) -> bool {
         match self {
@@ -112,6 +123,17 @@ pub(crate) struct BucketEntryLocalNetwork {
     node_status: Option<NodeStatus>,
 }
 
+/// Bucket entry information specific to the PrivateOverlay RoutingDomain
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct BucketEntryPrivateOverlay {
+    /// The PrivateOverlay node info
+    signed_node_info: Option<Box<SignedNodeInfo>>,
+    /// The last node info timestamp of ours that this entry has seen
+    last_seen_our_node_info_ts: Timestamp,
+    /// Last known node status
+    node_status: Option<NodeStatus>,
+}
+
 /// The data associated with each bucket entry
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct BucketEntryInner {
@@ -134,6 +156,9 @@ pub(crate) struct BucketEntryInner {
     public_internet: BucketEntryPublicInternet,
     /// The node info for this entry on the localnetwork routing domain
     local_network: BucketEntryLocalNetwork,
+    /// The node info for this entry on the privateoverlay routing domain
+    #[serde(default)]
+    private_overlay: BucketEntryPrivateOverlay,
     /// Statistics gathered for the peer
     peer_stats: PeerStats,
     /// The accounting for the latency statistics
@@ -307,6 +332,7 @@ impl BucketEntryInner {
         // Get the correct signed_node_info for the chosen routing domain
         let opt_current_sni = match routing_domain {
             RoutingDomain::LocalNetwork => &mut self.local_network.signed_node_info,
+            RoutingDomain::PrivateOverlay => &mut self.private_overlay.signed_node_info,
             RoutingDomain::PublicInternet => &mut self.public_internet.signed_node_info,
         };
         *opt_current_sni = None;
@@ -320,6 +346,7 @@ impl BucketEntryInner {
         // Get the correct signed_node_info for the chosen routing domain
         let opt_current_sni = match routing_domain {
             RoutingDomain::LocalNetwork => &mut self.local_network.signed_node_info,
+            RoutingDomain::PrivateOverlay => &mut self.private_overlay.signed_node_info,
             RoutingDomain::PublicInternet => &mut self.public_internet.signed_node_info,
         };
 
@@ -376,6 +403,7 @@ impl BucketEntryInner {
             // Get the correct signed_node_info for the chosen routing domain
             let opt_current_sni = match routing_domain {
                 RoutingDomain::LocalNetwork => &self.local_network.signed_node_info,
+                RoutingDomain::PrivateOverlay => &self.private_overlay.signed_node_info,
                 RoutingDomain::PublicInternet => &self.public_internet.signed_node_info,
             };
             if opt_current_sni.is_some() {
@@ -403,6 +431,7 @@ impl BucketEntryInner {
     pub fn node_info(&self, routing_domain: RoutingDomain) -> Option<&NodeInfo> {
         let opt_current_sni = match routing_domain {
             RoutingDomain::LocalNetwork => &self.local_network.signed_node_info,
+            RoutingDomain::PrivateOverlay => &self.private_overlay.signed_node_info,
             RoutingDomain::PublicInternet => &self.public_internet.signed_node_info,
         };
         opt_current_sni.as_ref().map(|s| s.node_info())
@@ -411,6 +440,7 @@ impl BucketEntryInner {
     pub fn signed_node_info(&self, routing_domain: RoutingDomain) -> Option<&SignedNodeInfo> {
         let opt_current_sni = match routing_domain {
             RoutingDomain::LocalNetwork => &self.local_network.signed_node_info,
+            RoutingDomain::PrivateOverlay => &self.private_overlay.signed_node_info,
             RoutingDomain::PublicInternet => &self.public_internet.signed_node_info,
         };
         opt_current_sni.as_ref().map(|s| s.as_ref())
@@ -419,6 +449,7 @@ impl BucketEntryInner {
     pub fn make_peer_info(&self, routing_domain: RoutingDomain) -> Option<PeerInfo> {
         let opt_current_sni = match routing_domain {
             RoutingDomain::LocalNetwork => &self.local_network.signed_node_info,
+            RoutingDomain::PrivateOverlay => &self.private_overlay.signed_node_info,
             RoutingDomain::PublicInternet => &self.public_internet.signed_node_info,
         };
         // Peer info includes all node ids, even unvalidated ones
@@ -437,6 +468,7 @@ impl BucketEntryInner {
         for routing_domain in routing_domain_set {
             let opt_current_sni = match routing_domain {
                 RoutingDomain::LocalNetwork => &self.local_network.signed_node_info,
+                RoutingDomain::PrivateOverlay => &self.private_overlay.signed_node_info,
                 RoutingDomain::PublicInternet => &self.public_internet.signed_node_info,
             };
             if opt_current_sni.is_some() {
@@ -633,6 +665,9 @@ impl BucketEntryInner {
             RoutingDomain::LocalNetwork => {
                 self.local_network.node_status = Some(status);
             }
+            RoutingDomain::PrivateOverlay => {
+                self.private_overlay.node_status = Some(status);
+            }
             RoutingDomain::PublicInternet => {
                 self.public_internet.node_status = Some(status);
             }
@@ -641,6 +676,7 @@ impl BucketEntryInner {
     pub fn node_status(&self, routing_domain: RoutingDomain) -> Option<NodeStatus> {
         match routing_domain {
             RoutingDomain::LocalNetwork => self.local_network.node_status.as_ref().cloned(),
+            RoutingDomain::PrivateOverlay => self.private_overlay.node_status.as_ref().cloned(),
             RoutingDomain::PublicInternet => self.public_internet.node_status.as_ref().cloned(),
         }
     }
@@ -650,6 +686,9 @@ impl BucketEntryInner {
             RoutingDomain::LocalNetwork => {
                 self.local_network.last_seen_our_node_info_ts = seen_ts;
             }
+            RoutingDomain::PrivateOverlay => {
+                self.private_overlay.last_seen_our_node_info_ts = seen_ts;
+            }
             RoutingDomain::PublicInternet => {
                 self.public_internet.last_seen_our_node_info_ts = seen_ts;
             }
@@ -665,6 +704,9 @@ impl BucketEntryInner {
             RoutingDomain::LocalNetwork => {
                 our_node_info_ts == self.local_network.last_seen_our_node_info_ts
             }
+            RoutingDomain::PrivateOverlay => {
+                our_node_info_ts == self.private_overlay.last_seen_our_node_info_ts
+            }
             RoutingDomain::PublicInternet => {
                 our_node_info_ts == self.public_internet.last_seen_our_node_info_ts
             }
@@ -949,6 +991,11 @@ impl BucketEntry {
                 signed_node_info: None,
                 node_status: None,
             },
+            private_overlay: BucketEntryPrivateOverlay {
+                last_seen_our_node_info_ts: Timestamp::new(0u64),
+                signed_node_info: None,
+                node_status: None,
+            },
             peer_stats: PeerStats {
                 time_added: now,
                 rpc_stats: RPCStats::default(),
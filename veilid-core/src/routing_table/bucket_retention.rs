@@ -0,0 +1,90 @@
+use super::*;
+
+/// The maximum value of each component of a weighted retention score
+const RETENTION_SCORE_SCALE: u64 = 1000;
+
+/// Decides how many entries each bucket holds and which entries a full bucket keeps
+pub(crate) trait BucketRetentionPolicy: fmt::Debug + Send + Sync {
+    /// The number of entries a bucket should be kicked down to
+    fn bucket_depth(&self, bucket_index: BucketIndex) -> usize;
+
+    /// If an entry must never be kicked, even if the bucket is over its depth
+    fn is_protected(&self, entry: &BucketEntryInner, cur_ts: Timestamp) -> bool;
+
+    /// How strongly an entry should be kept, entries with lower scores are kicked first
+    /// Punished and dead entries are always kicked before any others, regardless of score
+    fn retention_score(&self, entry: &BucketEntryInner, cur_ts: Timestamp) -> u64;
+}
+
+/// Retention policy that weighs the age, reliability and reputation of entries
+/// Configured by 'network.routing_table.bucket_retention'
+#[derive(Debug, Clone)]
+pub(crate) struct WeightedBucketRetentionPolicy {
+    config: VeilidConfigBucketRetention,
+}
+
+impl WeightedBucketRetentionPolicy {
+    pub fn new(config: VeilidConfigBucketRetention) -> Self {
+        Self { config }
+    }
+
+    /// Scale a duration to the score range, saturating at the long-lived age
+    fn duration_score(&self, duration: TimestampDuration) -> u64 {
+        let long_lived_age_us = (self.config.long_lived_age_ms as u64 * 1000u64).max(1);
+        duration.as_u64().min(long_lived_age_us) * RETENTION_SCORE_SCALE / long_lived_age_us
+    }
+
+    /// Time since the entry was added to the routing table
+    fn age_score(&self, entry: &BucketEntryInner, cur_ts: Timestamp) -> u64 {
+        self.duration_score(cur_ts.saturating_sub(entry.peer_stats().time_added))
+    }
+
+    /// Time the entry has been seen consecutively, if it is currently reliable
+    fn reliability_score(&self, entry: &BucketEntryInner, cur_ts: Timestamp) -> u64 {
+        if entry.state(cur_ts) != BucketEntryState::Reliable {
+            return 0;
+        }
+        match entry.peer_stats().rpc_stats.first_consecutive_seen_ts {
+            Some(ts) => self.duration_score(cur_ts.saturating_sub(ts)),
+            None => 0,
+        }
+    }
+
+    /// Fraction of recent communication with the entry that succeeded
+    fn reputation_score(&self, entry: &BucketEntryInner) -> u64 {
+        let rpc_stats = &entry.peer_stats().rpc_stats;
+        let good = rpc_stats.messages_rcvd as u64;
+        let bad = rpc_stats.recent_lost_answers as u64 + rpc_stats.failed_to_send as u64;
+        if good + bad == 0 {
+            // Nothing is known about this entry yet
+            return RETENTION_SCORE_SCALE / 2;
+        }
+        good * RETENTION_SCORE_SCALE / (good + bad)
+    }
+}
+
+impl BucketRetentionPolicy for WeightedBucketRetentionPolicy {
+    fn bucket_depth(&self, bucket_index: BucketIndex) -> usize {
+        // Buckets further from our node id cover more of the keyspace and hold more entries
+        let max_bucket_depth = self.config.max_bucket_depth as usize;
+        let min_bucket_depth = self.config.min_bucket_depth as usize;
+        max_bucket_depth
+            .checked_shr(bucket_index.1 as u32)
+            .unwrap_or_default()
+            .max(min_bucket_depth)
+    }
+
+    fn is_protected(&self, entry: &BucketEntryInner, cur_ts: Timestamp) -> bool {
+        if !self.config.protect_long_lived || entry.state(cur_ts) != BucketEntryState::Reliable {
+            return false;
+        }
+        cur_ts.saturating_sub(entry.peer_stats().time_added)
+            >= TimestampDuration::new(self.config.long_lived_age_ms as u64 * 1000u64)
+    }
+
+    fn retention_score(&self, entry: &BucketEntryInner, cur_ts: Timestamp) -> u64 {
+        self.config.age_weight as u64 * self.age_score(entry, cur_ts)
+            + self.config.reliability_weight as u64 * self.reliability_score(entry, cur_ts)
+            + self.config.reputation_weight as u64 * self.reputation_score(entry)
+    }
+}
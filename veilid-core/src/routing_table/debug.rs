@@ -81,6 +81,7 @@ impl RoutingTable {
     pub(crate) fn debug_info_dialinfo(&self) -> String {
         let ldis = self.dial_info_details(RoutingDomain::LocalNetwork);
         let gdis = self.dial_info_details(RoutingDomain::PublicInternet);
+        let odis = self.dial_info_details(RoutingDomain::PrivateOverlay);
         let mut out = String::new();
 
         out += "Local Network Dial Info Details:\n";
@@ -91,6 +92,10 @@ impl RoutingTable {
         for (n, gdi) in gdis.iter().enumerate() {
             out += &format!("  {:>2}: {:?}\n", n, gdi);
         }
+        out += "Private Overlay Dial Info Details:\n";
+        for (n, odi) in odis.iter().enumerate() {
+            out += &format!("  {:>2}: {:?}\n", n, odi);
+        }
         out
     }
 
@@ -210,6 +215,46 @@ impl RoutingTable {
         out
     }
 
+    pub(crate) fn debug_entry_list(
+        &self,
+        min_state: BucketEntryState,
+        capabilities: Vec<FourCC>,
+    ) -> Vec<DebugEntry> {
+        let inner = self.inner.read();
+        let inner = &*inner;
+        let cur_ts = Timestamp::now();
+
+        let mut out = Vec::new();
+        for ck in &VALID_CRYPTO_KINDS {
+            for (b, bucket) in inner.buckets[ck].iter().enumerate() {
+                for (k, v) in bucket.entries() {
+                    let Some(entry) = v.with(inner, |_rti, e| {
+                        let state = e.state(cur_ts);
+                        if state < min_state
+                            || !e.has_all_capabilities(RoutingDomain::PublicInternet, &capabilities)
+                        {
+                            return None;
+                        }
+                        Some(DebugEntry {
+                            node_id: TypedKey::new(*ck, *k),
+                            bucket_index: b as u32,
+                            state: state.into(),
+                            state_reason: format!("{:?}", e.state_reason(cur_ts)),
+                            latency: e.peer_stats().latency.clone(),
+                            capabilities: e
+                                .node_info(RoutingDomain::PublicInternet)
+                                .map(|ni| ni.capabilities().to_vec()),
+                        })
+                    }) else {
+                        continue;
+                    };
+                    out.push(entry);
+                }
+            }
+        }
+        out
+    }
+
     pub(crate) fn debug_info_entry(&self, node_ref: NodeRef) -> String {
         let cur_ts = Timestamp::now();
 
@@ -7,10 +7,11 @@ impl RoutingTable {
     #[instrument(level = "trace", target = "rtab", skip_all)]
     pub fn find_preferred_closest_peers(
         &self,
+        routing_domain: RoutingDomain,
         key: TypedKey,
         capabilities: &[Capability],
     ) -> NetworkResult<Vec<PeerInfo>> {
-        if !self.has_valid_network_class(RoutingDomain::PublicInternet) {
+        if !self.has_valid_network_class(routing_domain) {
             // Our own node info is not yet available, drop this request.
             return NetworkResult::service_unavailable(
                 "Not finding closest peers because our network class is still invalid",
@@ -21,21 +22,17 @@ impl RoutingTable {
         }
 
         // find N nodes closest to the target node in our routing table
-        let own_peer_info = self.get_own_peer_info(RoutingDomain::PublicInternet);
+        let own_peer_info = self.get_own_peer_info(routing_domain);
         let filter = Box::new(
             |rti: &RoutingTableInner, opt_entry: Option<Arc<BucketEntry>>| {
-                // Ensure only things that are valid/signed in the PublicInternet domain are returned
-                if !rti.filter_has_valid_signed_node_info(
-                    RoutingDomain::PublicInternet,
-                    true,
-                    opt_entry.clone(),
-                ) {
+                // Ensure only things that are valid/signed in the routing domain are returned
+                if !rti.filter_has_valid_signed_node_info(routing_domain, true, opt_entry.clone()) {
                     return false;
                 }
                 // Ensure capabilities are met
                 match opt_entry {
                     Some(entry) => entry.with(rti, |_rti, e| {
-                        e.has_all_capabilities(RoutingDomain::PublicInternet, capabilities)
+                        e.has_all_capabilities(routing_domain, capabilities)
                     }),
                     None => own_peer_info
                         .signed_node_info()
@@ -56,9 +53,7 @@ impl RoutingTable {
             key,
             filters,
             // transform
-            |rti, entry| {
-                rti.transform_to_peer_info(RoutingDomain::PublicInternet, &own_peer_info, entry)
-            },
+            |rti, entry| rti.transform_to_peer_info(routing_domain, &own_peer_info, entry),
         ) {
             Ok(v) => v,
             Err(e) => {
@@ -1,5 +1,7 @@
+mod asn_table;
 mod bucket;
 mod bucket_entry;
+mod bucket_retention;
 mod debug;
 mod find_peers;
 mod node_ref;
@@ -24,7 +26,9 @@ use crate::rpc_processor::*;
 use bucket::*;
 use hashlink::LruCache;
 
+pub(crate) use asn_table::*;
 pub(crate) use bucket_entry::*;
+pub(crate) use bucket_retention::*;
 pub(crate) use node_ref::*;
 pub(crate) use node_ref_filter::*;
 pub(crate) use privacy::*;
@@ -41,6 +45,10 @@ pub use types::*;
 /// How many nodes in our routing table we require for a functional PublicInternet RoutingDomain
 pub const MIN_PUBLIC_INTERNET_ROUTING_DOMAIN_NODE_COUNT: usize = 4;
 
+/// How many nodes in our routing table we require for a functional PrivateOverlay RoutingDomain
+/// Overlays may be very small, so any single peer is enough to find the rest with
+pub const MIN_PRIVATE_OVERLAY_ROUTING_DOMAIN_NODE_COUNT: usize = 1;
+
 /// How frequently we tick the relay management routine
 pub const RELAY_MANAGEMENT_INTERVAL_SECS: u32 = 1;
 
@@ -107,6 +115,10 @@ pub(crate) struct RoutingTableUnlockedInner {
     node_id_secret: TypedSecretGroup,
     /// Buckets to kick on our next kick task
     kick_queue: Mutex<BTreeSet<BucketIndex>>,
+    /// The policy deciding which entries are kept when buckets are kicked
+    bucket_retention_policy: Arc<dyn BucketRetentionPolicy>,
+    /// The autonomous systems of address ranges, for keeping route hops apart
+    asn_table: Arc<AsnTable>,
     /// Background process for computing statistics
     rolling_transfers_task: TickTask<EyreReport>,
     /// Background process to purge dead routing table entries when necessary
@@ -145,6 +157,14 @@ impl RoutingTableUnlockedInner {
         f(&self.config.get())
     }
 
+    pub fn bucket_retention_policy(&self) -> Arc<dyn BucketRetentionPolicy> {
+        self.bucket_retention_policy.clone()
+    }
+
+    pub fn asn_table(&self) -> Arc<AsnTable> {
+        self.asn_table.clone()
+    }
+
     pub fn node_id(&self, kind: CryptoKind) -> TypedKey {
         self.node_id.get(kind).unwrap()
     }
@@ -221,6 +241,10 @@ impl RoutingTable {
             node_id: c.network.routing_table.node_id.clone(),
             node_id_secret: c.network.routing_table.node_id_secret.clone(),
             kick_queue: Mutex::new(BTreeSet::default()),
+            bucket_retention_policy: Arc::new(WeightedBucketRetentionPolicy::new(
+                c.network.routing_table.bucket_retention.clone(),
+            )),
+            asn_table: Arc::new(AsnTable::new(&c.network.routing_table.asn_table)),
             rolling_transfers_task: TickTask::new(
                 "rolling_transfers_task",
                 ROLLING_TRANSFERS_INTERVAL_SECS,
@@ -508,6 +532,18 @@ impl RoutingTable {
             .configure_local_network_routing_domain(local_networks);
     }
 
+    /// Set up the private overlay routing domain with the configured overlay networks
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn configure_private_overlay_routing_domain(
+        &self,
+        overlay_networks: Vec<(IpAddr, IpAddr)>,
+    ) {
+        log_net!(debug "configure_private_overlay_routing_domain: {:#?}", overlay_networks);
+        self.inner
+            .write()
+            .configure_private_overlay_routing_domain(overlay_networks);
+    }
+
     /////////////////////////////////////
     /// Locked operations
 
@@ -722,6 +758,10 @@ impl RoutingTable {
         self.inner.read().get_routing_table_health()
     }
 
+    pub fn get_bucket_stats(&self) -> Vec<BucketStats> {
+        self.inner.read().get_bucket_stats()
+    }
+
     #[instrument(level = "trace", skip_all)]
     pub fn get_recent_peers(&self) -> Vec<(TypedKey, RecentPeersEntry)> {
         let mut recent_peers = Vec::new();
@@ -790,15 +830,20 @@ impl RoutingTable {
             &DialInfoFilter::all(),
         );
         for did in our_dids {
+            let Some(low_level_protocol_type) =
+                did.dial_info.protocol_type().low_level_protocol_type()
+            else {
+                continue;
+            };
             low_level_protocol_ports.insert((
-                did.dial_info.protocol_type().low_level_protocol_type(),
+                low_level_protocol_type,
                 did.dial_info.address_type(),
                 did.dial_info.socket_address().port(),
             ));
             protocol_to_port.insert(
                 (did.dial_info.protocol_type(), did.dial_info.address_type()),
                 (
-                    did.dial_info.protocol_type().low_level_protocol_type(),
+                    low_level_protocol_type,
                     did.dial_info.socket_address().port(),
                 ),
             );
@@ -877,6 +922,7 @@ impl RoutingTable {
     /// Retrieve up to N of each type of protocol capable nodes for a single crypto kind
     fn find_bootstrap_nodes_filtered_per_crypto_kind(
         &self,
+        routing_domain: RoutingDomain,
         crypto_kind: CryptoKind,
         max_per_type: usize,
     ) -> Vec<NodeRef> {
@@ -896,7 +942,9 @@ impl RoutingTable {
                 let entry = entry.unwrap();
                 entry.with(rti, |_rti, e| {
                     // skip nodes on our local network here
-                    if e.has_node_info(RoutingDomain::LocalNetwork.into()) {
+                    if routing_domain == RoutingDomain::PublicInternet
+                        && e.has_node_info(RoutingDomain::LocalNetwork.into())
+                    {
                         return false;
                     }
 
@@ -940,9 +988,7 @@ impl RoutingTable {
                         keep
                     };
 
-                    e.node_info(RoutingDomain::PublicInternet)
-                        .map(filter)
-                        .unwrap_or(false)
+                    e.node_info(routing_domain).map(filter).unwrap_or(false)
                 })
             },
         ) as RoutingTableEntryFilter;
@@ -959,14 +1005,24 @@ impl RoutingTable {
     }
 
     /// Retrieve up to N of each type of protocol capable nodes for all crypto kinds
-    pub fn find_bootstrap_nodes_filtered(&self, max_per_type: usize) -> Vec<NodeRef> {
-        let mut out =
-            self.find_bootstrap_nodes_filtered_per_crypto_kind(VALID_CRYPTO_KINDS[0], max_per_type);
+    pub fn find_bootstrap_nodes_filtered(
+        &self,
+        routing_domain: RoutingDomain,
+        max_per_type: usize,
+    ) -> Vec<NodeRef> {
+        let mut out = self.find_bootstrap_nodes_filtered_per_crypto_kind(
+            routing_domain,
+            VALID_CRYPTO_KINDS[0],
+            max_per_type,
+        );
 
         // Merge list of nodes so we don't have duplicates
         for crypto_kind in &VALID_CRYPTO_KINDS[1..] {
-            let nrs =
-                self.find_bootstrap_nodes_filtered_per_crypto_kind(*crypto_kind, max_per_type);
+            let nrs = self.find_bootstrap_nodes_filtered_per_crypto_kind(
+                routing_domain,
+                *crypto_kind,
+                max_per_type,
+            );
             'nrloop: for nr in nrs {
                 for nro in &out {
                     if nro.same_entry(&nr) {
@@ -979,6 +1035,32 @@ impl RoutingTable {
         out
     }
 
+    /// Retrieve up to N of the fastest nodes that advertise a capability in the PublicInternet routing domain
+    pub fn find_nodes_with_capability(
+        &self,
+        capability: Capability,
+        node_count: usize,
+    ) -> Vec<NodeRef> {
+        let filter = Box::new(
+            move |rti: &RoutingTableInner, opt_entry: Option<Arc<BucketEntry>>| {
+                let entry = opt_entry.unwrap().clone();
+                entry.with(rti, |_rti, e| {
+                    e.has_all_capabilities(RoutingDomain::PublicInternet, &[capability])
+                })
+            },
+        ) as RoutingTableEntryFilter;
+
+        let filters = VecDeque::from([filter]);
+
+        self.find_preferred_fastest_nodes(
+            node_count,
+            filters,
+            |_rti, entry: Option<Arc<BucketEntry>>| {
+                NodeRef::new(self.clone(), entry.unwrap().clone(), None)
+            },
+        )
+    }
+
     pub fn find_preferred_fastest_nodes<'a, T, O>(
         &self,
         node_count: usize,
@@ -1021,6 +1103,7 @@ impl RoutingTable {
     #[instrument(level = "trace", skip(self, peers))]
     pub fn register_find_node_answer(
         &self,
+        routing_domain: RoutingDomain,
         crypto_kind: CryptoKind,
         peers: Vec<PeerInfo>,
     ) -> Vec<NodeRef> {
@@ -1038,7 +1121,7 @@ impl RoutingTable {
             }
 
             // Register the node if it's new
-            match self.register_node_with_peer_info(RoutingDomain::PublicInternet, p, false) {
+            match self.register_node_with_peer_info(routing_domain, p, false) {
                 Ok(nr) => out.push(nr),
                 Err(e) => {
                     log_rtab!(debug "failed to register node with peer info from find node answer: {}", e);
@@ -1059,6 +1142,16 @@ impl RoutingTable {
     ) -> EyreResult<NetworkResult<Vec<NodeRef>>> {
         let rpc_processor = self.rpc_processor();
 
+        // Peers found are registered in the routing domain the request is sent over
+        let Some(routing_domain) = node_ref
+            .best_routing_domain()
+            .map(|rd| rd.peer_routing_domain())
+        else {
+            return Ok(NetworkResult::no_connection_other(
+                "no routing domain for find node",
+            ));
+        };
+
         let res = network_result_try!(
             rpc_processor
                 .clone()
@@ -1067,9 +1160,11 @@ impl RoutingTable {
         );
 
         // register nodes we'd found
-        Ok(NetworkResult::value(
-            self.register_find_node_answer(node_id.kind, res.answer),
-        ))
+        Ok(NetworkResult::value(self.register_find_node_answer(
+            routing_domain,
+            node_id.kind,
+            res.answer,
+        )))
     }
 
     /// Ask a remote node to list the nodes it has around the current node
@@ -3,6 +3,7 @@ use crate::veilid_api::*;
 
 mod permutation;
 mod remote_private_route_info;
+mod route_quality;
 mod route_set_spec_detail;
 mod route_spec_store_cache;
 mod route_spec_store_content;
@@ -14,7 +15,8 @@ use route_set_spec_detail::*;
 use route_spec_store_cache::*;
 use route_spec_store_content::*;
 
-pub(crate) use route_spec_store_cache::CompiledRoute;
+pub(crate) use route_quality::*;
+pub(crate) use route_spec_store_cache::{CompiledRoute, RouteSpecStoreCache};
 pub(crate) use route_stats::*;
 
 /// The size of the remote private route cache
@@ -25,6 +27,10 @@ const REMOTE_PRIVATE_ROUTE_CACHE_EXPIRY: TimestampDuration = TimestampDuration::
 const ROUTE_MIN_IDLE_TIME_MS: u32 = 30_000;
 /// The size of the compiled route cache
 const COMPILED_ROUTE_CACHE_SIZE: usize = 256;
+/// Latency assumed when scoring a hop we have not measured yet
+const UNMEASURED_HOP_LATENCY_MS: u64 = 1_000;
+/// Route test failures of a hop are forgotten 10 minutes after its last failure
+const HOP_FAILURE_EXPIRY: TimestampDuration = TimestampDuration::new(600_000_000u64);
 
 #[derive(Debug)]
 struct RouteSpecStoreInner {
@@ -177,6 +183,7 @@ impl RouteSpecStore {
         crypto_kinds: &[CryptoKind],
         stability: Stability,
         sequencing: Sequencing,
+        route_quality: &RouteQuality,
         hop_count: usize,
         directions: DirectionSet,
         avoid_nodes: &[TypedKey],
@@ -192,6 +199,7 @@ impl RouteSpecStore {
             crypto_kinds,
             stability,
             sequencing,
+            route_quality,
             hop_count,
             directions,
             avoid_nodes,
@@ -208,6 +216,7 @@ impl RouteSpecStore {
         crypto_kinds: &[CryptoKind],
         stability: Stability,
         sequencing: Sequencing,
+        route_quality: &RouteQuality,
         hop_count: usize,
         directions: DirectionSet,
         avoid_nodes: &[TypedKey],
@@ -221,6 +230,7 @@ impl RouteSpecStore {
             .get()
             .network
             .max_connections_per_ip6_prefix_size as usize;
+        let asn_table = rti.unlocked_inner.asn_table();
 
         if hop_count < 1 {
             apibail_invalid_argument!(
@@ -283,6 +293,15 @@ impl RouteSpecStore {
                         return false;
                     }
 
+                    // Exclude nodes that are measurably slower than the hop latency limit
+                    if route_quality.max_hop_latency.as_u64() != 0 {
+                        if let Some(latency) = &e.peer_stats().latency {
+                            if latency.average > route_quality.max_hop_latency {
+                                return false;
+                            }
+                        }
+                    }
+
                     // Exclude nodes on our local network
                     if e.node_info(RoutingDomain::LocalNetwork).is_some() {
                         return false;
@@ -358,6 +377,15 @@ impl RouteSpecStore {
             },
         ) as RoutingTableEntryFilter;
         let filters = VecDeque::from([filter]);
+
+        // Score a hop by its measured latency and recent route test failures, lower is better
+        let hop_score = |e: &BucketEntryInner| -> u64 {
+            route_hop_score(
+                route_quality,
+                e.peer_stats().latency.as_ref(),
+                inner.cache.get_hop_failure_count(&e.node_ids()),
+            )
+        };
         let compare = |_rti: &RoutingTableInner,
                        entry1: &Option<Arc<BucketEntry>>,
                        entry2: &Option<Arc<BucketEntry>>|
@@ -407,6 +435,15 @@ impl RouteSpecStore {
                 }
             }
 
+            // apply route quality scoring
+            if route_quality.latency_weight != 0 || route_quality.failure_weight != 0 {
+                let cmp_score = entry1
+                    .with_inner(|e1| entry2.with_inner(|e2| hop_score(e1).cmp(&hop_score(e2))));
+                if !matches!(cmp_score, Ordering::Equal) {
+                    return cmp_score;
+                }
+            }
+
             // apply stability preference
             // always prioritize reliable nodes, but sort by oldest or fastest
             entry1.with_inner(|e1| {
@@ -484,6 +521,17 @@ impl RouteSpecStore {
                 }
             }
 
+            // Ensure no two hops, or their relays, share an ipblock or network if we want diversity
+            if route_quality.diverse_ip_blocks || route_quality.diverse_asns {
+                let hops: Vec<&SignedNodeInfo> = permutation
+                    .iter()
+                    .map(|n| nodes_pi.get(*n).unwrap().signed_node_info())
+                    .collect();
+                if !is_route_diverse(route_quality, &asn_table, &hops, ip6_prefix_size) {
+                    return None;
+                }
+            }
+
             // Ensure this route is viable by checking that each node can contact the next one
             let mut can_do_sequenced = true;
             if directions.contains(Direction::Outbound) {
@@ -625,6 +673,7 @@ impl RouteSpecStore {
             directions,
             stability,
             can_do_sequenced,
+            *route_quality,
             automatic,
         );
 
@@ -746,6 +795,7 @@ impl RouteSpecStore {
                 hop_count,
                 stability,
                 sequencing,
+                route_quality: RouteQuality::default(),
             };
             let safety_selection = SafetySelection::Safe(safety_spec);
 
@@ -758,6 +808,8 @@ impl RouteSpecStore {
             )
         };
 
+        let hop_node_ids: Vec<TypedKeyGroup> = hops.iter().map(|h| h.node_ids()).collect();
+
         // Test with double-round trip ping to self
         let rpc_processor = self.unlocked_inner.routing_table.rpc_processor();
         let _res = match rpc_processor.rpc_call_status(dest).await? {
@@ -766,13 +818,22 @@ impl RouteSpecStore {
                 // Did not error, but did not come back, mark the nodes as failed to send, and then return false
                 // This will prevent those node from immediately being included in the next allocated route,
                 // avoiding the same route being constructed to replace this one when it is removed.
-                for hop in hops {
+                for hop in &hops {
                     hop.report_failed_route_test();
                 }
+                self.inner
+                    .lock()
+                    .cache
+                    .record_route_test(&hop_node_ids, false, Timestamp::now());
                 return Ok(false);
             }
         };
 
+        self.inner
+            .lock()
+            .cache
+            .record_route_test(&hop_node_ids, true, Timestamp::now());
+
         Ok(true)
     }
 
@@ -796,6 +857,7 @@ impl RouteSpecStore {
                 hop_count: self.unlocked_inner.default_route_hop_count,
                 stability,
                 sequencing,
+                route_quality: RouteQuality::default(),
             };
 
             let safety_selection = SafetySelection::Safe(safety_spec);
@@ -879,6 +941,7 @@ impl RouteSpecStore {
         max_hop_count: usize,
         stability: Stability,
         sequencing: Sequencing,
+        route_quality: &RouteQuality,
         directions: DirectionSet,
         avoid_nodes: &[TypedKey],
     ) -> Option<RouteId> {
@@ -890,6 +953,7 @@ impl RouteSpecStore {
         // but definitely prefer routes that have been recently tested
         for (id, rssd) in inner.content.iter_details() {
             if rssd.is_sequencing_match(sequencing)
+                && rssd.is_route_quality_match(route_quality)
                 && rssd.hop_count() >= min_hop_count
                 && rssd.hop_count() <= max_hop_count
                 && rssd.get_directions().is_superset(directions)
@@ -1312,6 +1376,7 @@ impl RouteSpecStore {
             safety_spec.hop_count,
             safety_spec.stability,
             safety_spec.sequencing,
+            &safety_spec.route_quality,
             direction,
             avoid_nodes,
         ) {
@@ -1325,6 +1390,7 @@ impl RouteSpecStore {
                 &[crypto_kind],
                 safety_spec.stability,
                 safety_spec.sequencing,
+                &safety_spec.route_quality,
                 safety_spec.hop_count,
                 direction,
                 avoid_nodes,
@@ -1747,6 +1813,9 @@ impl RouteSpecStore {
 
         // Roll transfers for remote private routes
         inner.cache.roll_transfers(last_ts, cur_ts);
+
+        // Forget hop failures that are no longer recent
+        inner.cache.prune_hop_failures(cur_ts);
     }
 
     /// Convert private route list to binary blob
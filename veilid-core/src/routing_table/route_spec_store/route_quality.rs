@@ -0,0 +1,56 @@
+use super::*;
+
+/// Score a hop by its measured latency and recent route test failures, lower is better
+pub(crate) fn route_hop_score(
+    route_quality: &RouteQuality,
+    latency: Option<&LatencyStats>,
+    failures: u32,
+) -> u64 {
+    let latency_ms = latency
+        .map(|l| l.average.as_u64() / 1_000)
+        .unwrap_or(UNMEASURED_HOP_LATENCY_MS);
+    latency_ms
+        .saturating_mul(route_quality.latency_weight as u64)
+        .saturating_add((failures as u64).saturating_mul(route_quality.failure_weight as u64))
+}
+
+/// Check that the hops of a route are as diverse as the route quality asks for
+/// With diverse_ip_blocks, no two hops or relays may share an ipblock
+/// With diverse_asns, no two hops may share an autonomous system, though a hop and its own relay may
+pub(crate) fn is_route_diverse(
+    route_quality: &RouteQuality,
+    asn_table: &AsnTable,
+    hops: &[&SignedNodeInfo],
+    ip6_prefix_size: usize,
+) -> bool {
+    if route_quality.diverse_ip_blocks {
+        let mut seen_node_infos: Vec<&NodeInfo> = Vec::with_capacity(hops.len() * 2);
+        for sni in hops {
+            for ni in core::iter::once(sni.node_info()).chain(sni.relay_info()) {
+                if seen_node_infos
+                    .iter()
+                    .any(|seen| seen.node_is_on_same_ipblock(ni, ip6_prefix_size))
+                {
+                    return false;
+                }
+                seen_node_infos.push(ni);
+            }
+        }
+    }
+
+    if route_quality.diverse_asns {
+        let mut seen_groups: BTreeSet<NetworkGroup> = BTreeSet::new();
+        for sni in hops {
+            let mut hop_groups = asn_table.node_network_groups(sni.node_info());
+            if let Some(rni) = sni.relay_info() {
+                hop_groups.extend(asn_table.node_network_groups(rni));
+            }
+            if !seen_groups.is_disjoint(&hop_groups) {
+                return false;
+            }
+            seen_groups.extend(hop_groups);
+        }
+    }
+
+    true
+}
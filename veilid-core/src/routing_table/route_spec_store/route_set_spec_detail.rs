@@ -27,6 +27,9 @@ pub(crate) struct RouteSetSpecDetail {
     stability: Stability,
     /// Sequencing capability (connection oriented protocols vs datagram)
     can_do_sequenced: bool,
+    /// Hop scoring this route was allocated with
+    #[serde(default)]
+    route_quality: RouteQuality,
     /// Stats
     stats: RouteStats,
     /// Automatically allocated route vs manually allocated route
@@ -41,6 +44,7 @@ impl RouteSetSpecDetail {
         directions: DirectionSet,
         stability: Stability,
         can_do_sequenced: bool,
+        route_quality: RouteQuality,
         automatic: bool,
     ) -> Self {
         Self {
@@ -50,6 +54,7 @@ impl RouteSetSpecDetail {
             directions,
             stability,
             can_do_sequenced,
+            route_quality,
             stats: RouteStats::new(cur_ts),
             automatic,
         }
@@ -109,6 +114,12 @@ impl RouteSetSpecDetail {
             Sequencing::EnsureOrdered => self.can_do_sequenced,
         }
     }
+    pub fn get_route_quality(&self) -> RouteQuality {
+        self.route_quality
+    }
+    pub fn is_route_quality_match(&self, route_quality: &RouteQuality) -> bool {
+        route_quality.is_default() || self.route_quality == *route_quality
+    }
     pub fn contains_nodes(&self, nodes: &[TypedKey]) -> bool {
         for tk in nodes {
             for rsd in self.route_set.values() {
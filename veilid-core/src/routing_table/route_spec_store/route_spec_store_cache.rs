@@ -20,13 +20,15 @@ pub(crate) struct CompiledRoute {
 
 /// Ephemeral data used to help the RouteSpecStore operate efficiently
 #[derive(Debug)]
-pub(super) struct RouteSpecStoreCache {
+pub(crate) struct RouteSpecStoreCache {
     /// How many times nodes have been used
     used_nodes: HashMap<PublicKey, usize>,
     /// How many times nodes have been used at the terminal point of a route
     used_end_nodes: HashMap<PublicKey, usize>,
     /// Route spec hop cache, used to quickly disqualify routes
     hop_cache: HashSet<Vec<u8>>,
+    /// How many route tests in a row nodes have been part of that failed, and when the last one failed
+    hop_failures: HashMap<PublicKey, (u32, Timestamp)>,
     /// Remote private routes we've imported and statistics
     remote_private_route_set_cache: LruCache<RouteId, RemotePrivateRouteInfo>,
     /// Remote private routes indexed by public key
@@ -136,6 +138,43 @@ impl RouteSpecStoreCache {
         })
     }
 
+    /// record the outcome of a route test for every node id of every hop in the route
+    pub fn record_route_test(
+        &mut self,
+        hop_node_ids: &[TypedKeyGroup],
+        success: bool,
+        cur_ts: Timestamp,
+    ) {
+        for node_ids in hop_node_ids {
+            for k in node_ids.iter() {
+                if success {
+                    self.hop_failures.remove(&k.value);
+                } else {
+                    self.hop_failures
+                        .entry(k.value)
+                        .and_modify(|e| *e = (e.0.saturating_add(1), cur_ts))
+                        .or_insert((1, cur_ts));
+                }
+            }
+        }
+    }
+
+    /// get how many route tests in a row a node with a particular node id set has failed
+    pub fn get_hop_failure_count(&self, node_ids: &TypedKeyGroup) -> u32 {
+        node_ids
+            .iter()
+            .filter_map(|k| self.hop_failures.get(&k.value).map(|e| e.0))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// forget the failures of nodes that have not failed a route test recently
+    /// nodes that are no longer tested would otherwise be remembered forever
+    pub fn prune_hop_failures(&mut self, cur_ts: Timestamp) {
+        self.hop_failures
+            .retain(|_k, e| cur_ts.saturating_sub(e.1) < HOP_FAILURE_EXPIRY);
+    }
+
     /// add remote private route to caches
     /// returns a remote private route set id
     fn add_remote_private_route(
@@ -372,6 +411,7 @@ impl Default for RouteSpecStoreCache {
             used_nodes: Default::default(),
             used_end_nodes: Default::default(),
             hop_cache: Default::default(),
+            hop_failures: Default::default(),
             remote_private_route_set_cache: LruCache::new(REMOTE_PRIVATE_ROUTE_CACHE_SIZE),
             remote_private_routes_by_key: HashMap::new(),
             compiled_route_cache: LruCache::new(COMPILED_ROUTE_CACHE_SIZE),
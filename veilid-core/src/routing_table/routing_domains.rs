@@ -1,7 +1,7 @@
 use super::*;
 
 /// Mechanism required to contact another node
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum ContactMethod {
     /// Node is not reachable by any means
     Unreachable,
@@ -11,8 +11,10 @@ pub(crate) enum ContactMethod {
     Direct(DialInfo),
     /// Request via signal the node connect back directly (relay, target)
     SignalReverse(TypedKey, TypedKey),
-    /// Request via signal the node negotiate a hole punch (relay, target)
-    SignalHolePunch(TypedKey, TypedKey),
+    /// Request via signal the node negotiate a hole punch (relay, target, protocol)
+    SignalHolePunch(TypedKey, TypedKey, ProtocolType),
+    /// Request via signal the node negotiate a WebRTC connection (relay, target)
+    SignalWebRTC(TypedKey, TypedKey),
     /// Must use an inbound relay to reach the node
     InboundRelay(TypedKey),
     /// Must use outbound relay to reach the node
@@ -455,6 +457,45 @@ impl RoutingDomainDetail for PublicInternetRoutingDomainDetail {
                                     return ContactMethod::SignalHolePunch(
                                         node_b_relay_id,
                                         node_b_id,
+                                        ProtocolType::UDP,
+                                    );
+                                }
+                            }
+                        }
+
+                        ///////// TCP hole-punch
+
+                        // Does node B have a direct tcp dialinfo node A can reach?
+                        // Simultaneous open needs both NATs to keep the same external port for every destination,
+                        // as address and port restricted NATs do, so that each side can connect from its listening port
+                        let tcp_dial_info_filter = dial_info_filter
+                            .filtered(&DialInfoFilter::all().with_protocol_type(ProtocolType::TCP));
+                        if let Some(target_tcp_did) = first_filtered_dial_info_detail_between_nodes(
+                            node_a,
+                            node_b,
+                            &tcp_dial_info_filter,
+                            sequencing,
+                            dif_sort.clone(),
+                        ) {
+                            // Does node A have a direct tcp dialinfo that node B can reach?
+                            if let Some(reverse_tcp_did) =
+                                first_filtered_dial_info_detail_between_nodes(
+                                    node_b,
+                                    node_a,
+                                    &tcp_dial_info_filter,
+                                    sequencing,
+                                    dif_sort.clone(),
+                                )
+                            {
+                                // Ensure we aren't on the same public IP address (no hairpin nat)
+                                if reverse_tcp_did.dial_info.ip_addr()
+                                    != target_tcp_did.dial_info.ip_addr()
+                                {
+                                    // The target and ourselves have a tcp dialinfo that they can reach
+                                    return ContactMethod::SignalHolePunch(
+                                        node_b_relay_id,
+                                        node_b_id,
+                                        ProtocolType::TCP,
                                     );
                                 }
                             }
@@ -517,6 +558,18 @@ impl RoutingDomainDetail for PublicInternetRoutingDomainDetail {
                     }
                 }
 
+                ///////// WebRTC
+
+                // Neither node has dial info the other can use, but if both can make WebRTC
+                // connections, they can negotiate one through the inbound relay
+                if dial_info_filter
+                    .protocol_type_set
+                    .contains(ProtocolType::WRTC)
+                    && node_b.outbound_protocols().contains(ProtocolType::WRTC)
+                {
+                    return ContactMethod::SignalWebRTC(node_b_relay_id, node_b_id);
+                }
+
                 return ContactMethod::InboundRelay(node_b_relay_id);
             }
         }
@@ -620,3 +673,85 @@ impl RoutingDomainDetail for LocalNetworkRoutingDomainDetail {
         ContactMethod::Unreachable
     }
 }
+
+/// Private Overlay routing domain internals
+#[derive(Debug)]
+pub struct PrivateOverlayRoutingDomainDetail {
+    /// The overlay networks this domain will communicate with
+    overlay_networks: Vec<(IpAddr, IpAddr)>,
+    /// Common implementation for all routing domains
+    common: RoutingDomainDetailCommon,
+}
+
+impl Default for PrivateOverlayRoutingDomainDetail {
+    fn default() -> Self {
+        Self {
+            overlay_networks: Default::default(),
+            common: RoutingDomainDetailCommon::new(RoutingDomain::PrivateOverlay),
+        }
+    }
+}
+
+impl PrivateOverlayRoutingDomainDetail {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn set_overlay_networks(&mut self, mut overlay_networks: Vec<(IpAddr, IpAddr)>) -> bool {
+        overlay_networks.sort();
+        if overlay_networks == self.overlay_networks {
+            return false;
+        }
+        self.overlay_networks = overlay_networks;
+        true
+    }
+}
+
+impl RoutingDomainDetail for PrivateOverlayRoutingDomainDetail {
+    fn common(&self) -> &RoutingDomainDetailCommon {
+        &self.common
+    }
+    fn common_mut(&mut self) -> &mut RoutingDomainDetailCommon {
+        &mut self.common
+    }
+    fn can_contain_address(&self, address: Address) -> bool {
+        let ip = address.ip_addr();
+        for overlaynet in &self.overlay_networks {
+            if ipaddr_in_network(ip, overlaynet.0, overlaynet.1) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn get_contact_method(
+        &self,
+        _rti: &RoutingTableInner,
+        peer_a: &PeerInfo,
+        peer_b: &PeerInfo,
+        dial_info_filter: DialInfoFilter,
+        sequencing: Sequencing,
+        dif_sort: Option<Arc<DialInfoDetailSort>>,
+    ) -> ContactMethod {
+        // Get the nodeinfos for convenience
+        let node_a = peer_a.signed_node_info().node_info();
+        let node_b = peer_b.signed_node_info().node_info();
+
+        // Get the node ids that would be used between these peers
+        let cck = common_crypto_kinds(&peer_a.node_ids().kinds(), &peer_b.node_ids().kinds());
+        let Some(_best_ck) = cck.first().copied() else {
+            // No common crypto kinds between these nodes, can't contact
+            return ContactMethod::Unreachable;
+        };
+
+        // Overlay networks route between all of their members without NAT, so only direct contact is used
+        if let Some(target_did) = first_filtered_dial_info_detail_between_nodes(
+            node_a,
+            node_b,
+            &dial_info_filter,
+            sequencing,
+            dif_sort,
+        ) {
+            return ContactMethod::Direct(target_did.dial_info);
+        }
+
+        ContactMethod::Unreachable
+    }
+}
@@ -20,6 +20,8 @@ pub(crate) struct RoutingTableInner {
     pub(super) public_internet_routing_domain: PublicInternetRoutingDomainDetail,
     /// The dial info we use on the local network
     pub(super) local_network_routing_domain: LocalNetworkRoutingDomainDetail,
+    /// The dial info we use on the private overlay network
+    pub(super) private_overlay_routing_domain: PrivateOverlayRoutingDomainDetail,
     /// Interim accounting mechanism for this node's RPC latency to any other node
     pub(super) self_latency_stats_accounting: LatencyStatsAccounting,
     /// Interim accounting mechanism for the total bandwidth to/from this node
@@ -44,6 +46,7 @@ impl RoutingTableInner {
             buckets: BTreeMap::new(),
             public_internet_routing_domain: PublicInternetRoutingDomainDetail::default(),
             local_network_routing_domain: LocalNetworkRoutingDomainDetail::default(),
+            private_overlay_routing_domain: PrivateOverlayRoutingDomainDetail::default(),
             all_entries: PtrWeakHashSet::new(),
             live_entry_count: BTreeMap::new(),
             self_latency_stats_accounting: LatencyStatsAccounting::new(),
@@ -69,14 +72,31 @@ impl RoutingTableInner {
 
     pub fn routing_domain_for_address(&self, address: Address) -> Option<RoutingDomain> {
         for rd in RoutingDomain::all() {
-            let can_contain = self.with_routing_domain(rd, |rdd| rdd.can_contain_address(address));
-            if can_contain {
+            if self.routing_domain_can_contain_address(rd, address) {
                 return Some(rd);
             }
         }
         None
     }
 
+    /// Addresses on the private overlay networks belong only to the PrivateOverlay routing domain,
+    /// even where the rules of another routing domain would also accept them, so that overlay
+    /// addressing is never published in the LocalNetwork or PublicInternet routing domains
+    pub(super) fn routing_domain_can_contain_address(
+        &self,
+        domain: RoutingDomain,
+        address: Address,
+    ) -> bool {
+        if domain != RoutingDomain::PrivateOverlay
+            && self
+                .private_overlay_routing_domain
+                .can_contain_address(address)
+        {
+            return false;
+        }
+        self.with_routing_domain(domain, |rd| rd.can_contain_address(address))
+    }
+
     pub fn with_routing_domain<F, R>(&self, domain: RoutingDomain, f: F) -> R
     where
         F: FnOnce(&dyn RoutingDomainDetail) -> R,
@@ -84,6 +104,7 @@ impl RoutingTableInner {
         match domain {
             RoutingDomain::PublicInternet => f(&self.public_internet_routing_domain),
             RoutingDomain::LocalNetwork => f(&self.local_network_routing_domain),
+            RoutingDomain::PrivateOverlay => f(&self.private_overlay_routing_domain),
         }
     }
 
@@ -94,6 +115,7 @@ impl RoutingTableInner {
         match domain {
             RoutingDomain::PublicInternet => f(&mut self.public_internet_routing_domain),
             RoutingDomain::LocalNetwork => f(&mut self.local_network_routing_domain),
+            RoutingDomain::PrivateOverlay => f(&mut self.private_overlay_routing_domain),
         }
     }
 
@@ -163,8 +185,7 @@ impl RoutingTableInner {
 
     pub fn ensure_dial_info_is_valid(&self, domain: RoutingDomain, dial_info: &DialInfo) -> bool {
         let address = dial_info.socket_address().address();
-        let can_contain_address =
-            self.with_routing_domain(domain, |rd| rd.can_contain_address(address));
+        let can_contain_address = self.routing_domain_can_contain_address(domain, address);
 
         if !can_contain_address {
             log_rtab!(debug "can not add dial info to this routing domain");
@@ -300,20 +321,6 @@ impl RoutingTableInner {
             .with_dial_info_filter(dif)
     }
 
-    fn bucket_depth(bucket_index: BucketIndex) -> usize {
-        match bucket_index.1 {
-            0 => 256,
-            1 => 128,
-            2 => 64,
-            3 => 32,
-            4 => 16,
-            5 => 8,
-            6 => 4,
-            7 => 2,
-            _ => 1,
-        }
-    }
-
     pub fn init_buckets(&mut self) {
         // Size the buckets (one per bit), one bucket set per crypto kind
         self.buckets.clear();
@@ -351,6 +358,30 @@ impl RoutingTableInner {
         }
     }
 
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn configure_private_overlay_routing_domain(
+        &mut self,
+        overlay_networks: Vec<(IpAddr, IpAddr)>,
+    ) {
+        log_net!(debug "configure_private_overlay_routing_domain: {:#?}", overlay_networks);
+
+        let changed = self
+            .private_overlay_routing_domain
+            .set_overlay_networks(overlay_networks);
+
+        // If the overlay networks have changed, nuke the existing overlay node info and let the overlay bootstrap again
+        if changed {
+            let cur_ts = Timestamp::now();
+            self.with_entries_mut(cur_ts, BucketEntryState::Dead, |rti, e| {
+                e.with_mut(rti, |_rti, e| {
+                    e.clear_signed_node_info(RoutingDomain::PrivateOverlay);
+                    e.reset_updated_since_last_network_change();
+                });
+                Option::<()>::None
+            });
+        }
+    }
+
     /// Attempt to empty the routing table
     /// should only be performed when there are no node_refs (detached)
     pub fn purge_buckets(&mut self) {
@@ -359,9 +390,10 @@ impl RoutingTableInner {
             self.bucket_entry_count()
         );
         let closest_nodes = BTreeSet::new();
+        let policy = self.unlocked_inner.bucket_retention_policy();
         for ck in VALID_CRYPTO_KINDS {
             for bucket in self.buckets.get_mut(&ck).unwrap().iter_mut() {
-                bucket.kick(0, &closest_nodes);
+                bucket.kick(&*policy, 0, &closest_nodes);
             }
         }
         self.all_entries.remove_expired();
@@ -398,10 +430,11 @@ impl RoutingTableInner {
     /// Attempt to settle buckets and remove entries down to the desired number
     /// which may not be possible due extant NodeRefs
     pub fn kick_bucket(&mut self, bucket_index: BucketIndex, exempt_peers: &BTreeSet<PublicKey>) {
+        let policy = self.unlocked_inner.bucket_retention_policy();
+        let bucket_depth = policy.bucket_depth(bucket_index);
         let bucket = self.get_bucket_mut(bucket_index);
-        let bucket_depth = Self::bucket_depth(bucket_index);
 
-        if let Some(_dead_node_ids) = bucket.kick(bucket_depth, exempt_peers) {
+        if let Some(_dead_node_ids) = bucket.kick(&*policy, bucket_depth, exempt_peers) {
             // Remove expired entries
             self.all_entries.remove_expired();
 
@@ -930,6 +963,41 @@ impl RoutingTableInner {
         }
     }
 
+    pub fn get_bucket_stats(&self) -> Vec<BucketStats> {
+        let cur_ts = Timestamp::now();
+        let mut out = Vec::new();
+        for ck in VALID_CRYPTO_KINDS {
+            for (index, bucket) in self.buckets[&ck].iter().enumerate() {
+                let mut bucket_stats = BucketStats {
+                    kind: ck,
+                    index: index as u32,
+                    reliable: 0,
+                    unreliable: 0,
+                    dead: 0,
+                    punished: 0,
+                };
+                for (_k, entry) in bucket.entries() {
+                    match entry.with_inner(|e| e.state(cur_ts)) {
+                        BucketEntryState::Reliable => {
+                            bucket_stats.reliable += 1;
+                        }
+                        BucketEntryState::Unreliable => {
+                            bucket_stats.unreliable += 1;
+                        }
+                        BucketEntryState::Dead => {
+                            bucket_stats.dead += 1;
+                        }
+                        BucketEntryState::Punished => {
+                            bucket_stats.punished += 1;
+                        }
+                    }
+                }
+                out.push(bucket_stats);
+            }
+        }
+        out
+    }
+
     pub fn touch_recent_peer(&mut self, node_id: TypedKey, last_connection: Flow) {
         self.recent_peers
             .insert(node_id, RecentPeersEntry { last_connection });
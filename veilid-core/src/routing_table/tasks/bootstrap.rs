@@ -256,17 +256,19 @@ impl RoutingTable {
     //#[instrument(level = "trace", skip(self), err)]
     pub(crate) fn bootstrap_with_peer(
         self,
+        routing_domain: RoutingDomain,
         crypto_kinds: Vec<CryptoKind>,
         pi: PeerInfo,
         unord: &FuturesUnordered<SendPinBoxFuture<()>>,
     ) {
         log_rtab!(
-            "--- bootstrapping {} with {:?}",
+            "--- bootstrapping {} in {:?} with {:?}",
             pi.node_ids(),
+            routing_domain,
             pi.signed_node_info().node_info().dial_info_detail_list()
         );
 
-        let nr = match self.register_node_with_peer_info(RoutingDomain::PublicInternet, pi, true) {
+        let nr = match self.register_node_with_peer_info(routing_domain, pi, true) {
             Ok(nr) => nr,
             Err(e) => {
                 log_rtab!(error "failed to register bootstrap peer info: {}", e);
@@ -305,7 +307,7 @@ impl RoutingTable {
                     let _ = routing_table.find_target(crypto_kind, nr.clone(), vec![]).await;
 
                     // Ensure we got the signed peer info
-                    if !nr.signed_node_info_has_valid_signature(RoutingDomain::PublicInternet) {
+                    if !nr.signed_node_info_has_valid_signature(routing_domain) {
                         log_rtab!(warn "bootstrap server is not responding");
                         log_rtab!(debug "bootstrap server is not responding for dialinfo: {}", bsdi);
 
@@ -324,13 +326,14 @@ impl RoutingTable {
     #[instrument(level = "trace", skip(self), err)]
     pub(crate) async fn bootstrap_with_peer_list(
         self,
+        routing_domain: RoutingDomain,
         peers: Vec<PeerInfo>,
         stop_token: StopToken,
     ) -> EyreResult<()> {
         log_rtab!(debug "  bootstrapped peers: {:?}", &peers);
 
         // Get crypto kinds to bootstrap
-        let crypto_kinds = self.get_bootstrap_crypto_kinds();
+        let crypto_kinds = self.get_bootstrap_crypto_kinds(routing_domain);
 
         log_rtab!(debug "  bootstrapped crypto kinds: {:?}", &crypto_kinds);
 
@@ -338,7 +341,7 @@ impl RoutingTable {
         let mut unord = FuturesUnordered::<SendPinBoxFuture<()>>::new();
         for peer in peers {
             self.clone()
-                .bootstrap_with_peer(crypto_kinds.clone(), peer, &unord);
+                .bootstrap_with_peer(routing_domain, crypto_kinds.clone(), peer, &unord);
         }
 
         // Wait for all bootstrap operations to complete before we complete the singlefuture
@@ -347,14 +350,21 @@ impl RoutingTable {
     }
 
     // Get counts by crypto kind and figure out which crypto kinds need bootstrapping
-    fn get_bootstrap_crypto_kinds(&self) -> Vec<CryptoKind> {
+    pub(crate) fn get_bootstrap_crypto_kinds(
+        &self,
+        routing_domain: RoutingDomain,
+    ) -> Vec<CryptoKind> {
+        let min_bootstrap_peers = match routing_domain {
+            RoutingDomain::PrivateOverlay => MIN_PRIVATE_OVERLAY_ROUTING_DOMAIN_NODE_COUNT,
+            RoutingDomain::LocalNetwork | RoutingDomain::PublicInternet => MIN_BOOTSTRAP_PEERS,
+        };
         let entry_count = self.inner.read().cached_entry_counts();
         let mut crypto_kinds = Vec::new();
         for crypto_kind in VALID_CRYPTO_KINDS {
             // Do we need to bootstrap this crypto kind?
-            let eckey = (RoutingDomain::PublicInternet, crypto_kind);
+            let eckey = (routing_domain, crypto_kind);
             let cnt = entry_count.get(&eckey).copied().unwrap_or_default();
-            if cnt < MIN_BOOTSTRAP_PEERS {
+            if cnt < min_bootstrap_peers {
                 crypto_kinds.push(crypto_kind);
             }
         }
@@ -363,16 +373,58 @@ impl RoutingTable {
 
     #[instrument(level = "trace", skip(self), err)]
     pub(crate) async fn bootstrap_task_routine(self, stop_token: StopToken) -> EyreResult<()> {
-        let bootstrap = self
-            .unlocked_inner
-            .with_config(|c| c.network.routing_table.bootstrap.clone());
+        let (bootstrap, private_overlay_bootstrap) = self.unlocked_inner.with_config(|c| {
+            (
+                c.network.routing_table.bootstrap.clone(),
+                c.network.routing_table.private_overlay.bootstrap.clone(),
+            )
+        });
+
+        // The public internet bootstraps from its own list
+        if !self
+            .get_bootstrap_crypto_kinds(RoutingDomain::PublicInternet)
+            .is_empty()
+        {
+            self.clone()
+                .bootstrap_routing_domain(
+                    RoutingDomain::PublicInternet,
+                    bootstrap,
+                    stop_token.clone(),
+                )
+                .await?;
+        }
 
+        // The private overlay bootstraps only from its own list, if it is configured
+        if self.has_valid_network_class(RoutingDomain::PrivateOverlay)
+            && !self
+                .get_bootstrap_crypto_kinds(RoutingDomain::PrivateOverlay)
+                .is_empty()
+        {
+            self.clone()
+                .bootstrap_routing_domain(
+                    RoutingDomain::PrivateOverlay,
+                    private_overlay_bootstrap,
+                    stop_token,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self), err)]
+    async fn bootstrap_routing_domain(
+        self,
+        routing_domain: RoutingDomain,
+        bootstrap: Vec<String>,
+        stop_token: StopToken,
+    ) -> EyreResult<()> {
         // Don't bother if bootstraps aren't configured
         if bootstrap.is_empty() {
             return Ok(());
         }
 
-        log_rtab!(debug "--- bootstrap_task");
+        log_rtab!(debug "--- bootstrap_task {:?}", routing_domain);
 
         // See if we are specifying a direct dialinfo for bootstrap, if so use the direct mechanism
         let mut bootstrap_dialinfos = Vec::<DialInfo>::new();
@@ -430,7 +482,7 @@ impl RoutingTable {
         };
 
         self.clone()
-            .bootstrap_with_peer_list(peers, stop_token)
+            .bootstrap_with_peer_list(routing_domain, peers, stop_token)
             .await
     }
 }
@@ -1,11 +1,5 @@
 use super::*;
 
-/// How many 'reliable' nodes closest to our own node id to keep
-const KEEP_N_CLOSEST_RELIABLE_PEERS_COUNT: usize = 16;
-
-/// How many 'unreliable' nodes closest to our own node id to keep
-const KEEP_N_CLOSEST_UNRELIABLE_PEERS_COUNT: usize = 8;
-
 impl RoutingTable {
     // Kick the queued buckets in the routing table to free dead nodes if necessary
     // Attempts to keep the size of the routing table down to the bucket depth
@@ -20,6 +14,21 @@ impl RoutingTable {
             core::mem::take(&mut *self.unlocked_inner.kick_queue.lock())
                 .into_iter()
                 .collect();
+
+        // How many 'reliable' and 'unreliable' nodes closest to our own node id to keep
+        let (keep_closest_reliable_count, keep_closest_unreliable_count) = self.with_config(|c| {
+            (
+                c.network
+                    .routing_table
+                    .bucket_retention
+                    .keep_closest_reliable as usize,
+                c.network
+                    .routing_table
+                    .bucket_retention
+                    .keep_closest_unreliable as usize,
+            )
+        });
+
         let mut inner = self.inner.write();
 
         // Get our exempt nodes for each crypto kind
@@ -59,21 +68,21 @@ impl RoutingTable {
                         }
                         BucketEntryState::Unreliable => {
                             // Add to closest unreliable nodes list
-                            if closest_unreliable_count < KEEP_N_CLOSEST_UNRELIABLE_PEERS_COUNT {
+                            if closest_unreliable_count < keep_closest_unreliable_count {
                                 closest_peers.insert(*key);
                                 closest_unreliable_count += 1;
                             }
                         }
                         BucketEntryState::Reliable => {
                             // Add to closest reliable nodes list
-                            if closest_reliable_count < KEEP_N_CLOSEST_RELIABLE_PEERS_COUNT {
+                            if closest_reliable_count < keep_closest_reliable_count {
                                 closest_peers.insert(*key);
                                 closest_reliable_count += 1;
                             }
                         }
                     }
-                    if closest_unreliable_count == KEEP_N_CLOSEST_UNRELIABLE_PEERS_COUNT
-                        && closest_reliable_count == KEEP_N_CLOSEST_RELIABLE_PEERS_COUNT
+                    if closest_unreliable_count == keep_closest_unreliable_count
+                        && closest_reliable_count == keep_closest_reliable_count
                     {
                         break 'outer;
                     }
@@ -157,7 +157,17 @@ impl RoutingTable {
                 needs_peer_minimum_refresh = true;
             }
         }
-        if needs_bootstrap {
+
+        // The private overlay bootstraps separately if it is configured and has too few peers
+        let needs_private_overlay_bootstrap = self
+            .has_valid_network_class(RoutingDomain::PrivateOverlay)
+            && VALID_CRYPTO_KINDS.iter().any(|ck| {
+                let eckey = (RoutingDomain::PrivateOverlay, *ck);
+                let cnt = entry_counts.get(&eckey).copied().unwrap_or_default();
+                cnt < MIN_PRIVATE_OVERLAY_ROUTING_DOMAIN_NODE_COUNT
+            });
+
+        if needs_bootstrap || needs_private_overlay_bootstrap {
             self.unlocked_inner.bootstrap_task.tick().await?;
         }
         if needs_peer_minimum_refresh {
@@ -202,18 +202,19 @@ impl RoutingTable {
         Ok(())
     }
 
-    // Ping each node in the LocalNetwork routing domain if they
-    // need to be pinged to determine their reliability
+    // Ping each node in a directly reachable routing domain (LocalNetwork or PrivateOverlay)
+    // if they need to be pinged to determine their reliability
     #[instrument(level = "trace", skip(self, futurequeue), err)]
-    async fn ping_validator_local_network(
+    async fn ping_validator_direct_routing_domain(
         &self,
+        routing_domain: RoutingDomain,
         cur_ts: Timestamp,
         futurequeue: &mut VecDeque<PingValidatorFuture>,
     ) -> EyreResult<()> {
         let rpc = self.rpc_processor();
 
-        // Get all nodes needing pings in the LocalNetwork routing domain
-        let node_refs = self.get_nodes_needing_ping(RoutingDomain::LocalNetwork, cur_ts);
+        // Get all nodes needing pings in the routing domain
+        let node_refs = self.get_nodes_needing_ping(routing_domain, cur_ts);
 
         // For all nodes needing pings, figure out how many and over what protocols
         for nr in node_refs {
@@ -244,8 +245,20 @@ impl RoutingTable {
             .await?;
 
         // LocalNetwork
-        self.ping_validator_local_network(cur_ts, &mut futurequeue)
-            .await?;
+        self.ping_validator_direct_routing_domain(
+            RoutingDomain::LocalNetwork,
+            cur_ts,
+            &mut futurequeue,
+        )
+        .await?;
+
+        // PrivateOverlay
+        self.ping_validator_direct_routing_domain(
+            RoutingDomain::PrivateOverlay,
+            cur_ts,
+            &mut futurequeue,
+        )
+        .await?;
 
         // Wait for ping futures to complete in parallel
         let mut unord = FuturesUnordered::new();
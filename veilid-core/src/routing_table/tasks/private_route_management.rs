@@ -186,14 +186,19 @@ impl RoutingTable {
                 .await?;
         }
 
-        // Ensure we have a minimum of N allocated local, unpublished routes with the default number of hops and all our supported crypto kinds
-        let default_route_hop_count =
-            self.with_config(|c| c.network.rpc.default_route_hop_count as usize);
+        // Ensure we have a minimum of N allocated local, unpublished routes with the default number of hops and route quality, and all our supported crypto kinds
+        let (default_route_hop_count, default_route_quality) = self.with_config(|c| {
+            (
+                c.network.rpc.default_route_hop_count as usize,
+                c.network.rpc.default_route_quality,
+            )
+        });
         let mut local_unpublished_route_count = 0usize;
         let rss = self.route_spec_store();
         rss.list_allocated_routes(|_k, v| {
             if !v.is_published()
                 && v.hop_count() == default_route_hop_count
+                && v.get_route_quality() == default_route_quality
                 && v.get_route_set_keys().kinds() == VALID_CRYPTO_KINDS
             {
                 local_unpublished_route_count += 1;
@@ -215,6 +220,7 @@ impl RoutingTable {
                     &VALID_CRYPTO_KINDS,
                     Stability::Reliable,
                     Sequencing::PreferOrdered,
+                    &default_route_quality,
                     default_route_hop_count,
                     DirectionSet::all(),
                     &[],
@@ -1,5 +1,10 @@
 use super::*;
 
+pub mod test_bucket;
+pub mod test_contact_method;
+pub mod test_find_capability;
+pub mod test_private_overlay;
+pub mod test_route_quality;
 pub mod test_serialize_routing_table;
 
 pub(crate) fn mock_routing_table() -> routing_table::RoutingTable {
@@ -26,3 +31,61 @@ pub(crate) fn mock_routing_table() -> routing_table::RoutingTable {
     );
     RoutingTable::new(network_manager)
 }
+
+/// The node id of mock node `n`
+pub(crate) fn mock_node_id(n: u8) -> TypedKey {
+    TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([n; 32]))
+}
+
+/// The node ids of mock node `n`
+pub(crate) fn mock_node_ids(n: u8) -> TypedKeyGroup {
+    TypedKeyGroup::from(mock_node_id(n))
+}
+
+/// UDP dial info for a socket address
+pub(crate) fn mock_udp(class: DialInfoClass, addr: &str) -> DialInfoDetail {
+    DialInfoDetail {
+        class,
+        dial_info: DialInfo::udp_from_socketaddr(SocketAddr::from_str(addr).unwrap()),
+    }
+}
+
+/// TCP dial info for a socket address
+pub(crate) fn mock_tcp(class: DialInfoClass, addr: &str) -> DialInfoDetail {
+    DialInfoDetail {
+        class,
+        dial_info: DialInfo::tcp_from_socketaddr(SocketAddr::from_str(addr).unwrap()),
+    }
+}
+
+/// Node info with the public internet capabilities, reachable through the given dial info
+pub(crate) fn mock_node_info(
+    network_class: NetworkClass,
+    dial_info_detail_list: Vec<DialInfoDetail>,
+) -> NodeInfo {
+    NodeInfo::new(
+        network_class,
+        ProtocolTypeSet::all(),
+        AddressTypeSet::all(),
+        vec![0],
+        vec![CRYPTO_KIND_VLD0],
+        PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+        dial_info_detail_list,
+    )
+}
+
+/// An unsigned peer info for mock node `n` that is not behind a relay
+pub(crate) fn mock_peer_info(
+    n: u8,
+    network_class: NetworkClass,
+    dial_info_detail_list: Vec<DialInfoDetail>,
+) -> PeerInfo {
+    PeerInfo::new(
+        mock_node_ids(n),
+        SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+            mock_node_info(network_class, dial_info_detail_list),
+            Timestamp::new(0),
+            vec![],
+        )),
+    )
+}
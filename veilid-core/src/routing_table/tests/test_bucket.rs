@@ -0,0 +1,177 @@
+use super::*;
+
+const TWO_MINUTES: TimestampDuration = TimestampDuration::new(120_000_000u64);
+
+fn mock_policy(long_lived_age_ms: u32, protect_long_lived: bool) -> WeightedBucketRetentionPolicy {
+    WeightedBucketRetentionPolicy::new(VeilidConfigBucketRetention {
+        max_bucket_depth: 256,
+        min_bucket_depth: 4,
+        age_weight: 1,
+        reliability_weight: 1,
+        reputation_weight: 1,
+        long_lived_age_ms,
+        protect_long_lived,
+        ..Default::default()
+    })
+}
+
+/// An entry that has answered us consecutively for long enough to be reliable
+fn make_reliable(entry: &Arc<BucketEntry>, cur_ts: Timestamp) {
+    entry.with_mut_inner(|e| e.question_rcvd(cur_ts - TWO_MINUTES, ByteCount::new(0)));
+}
+
+/// An entry we have failed to reach often enough to be dead
+fn make_dead(entry: &Arc<BucketEntry>, cur_ts: Timestamp) {
+    entry.with_mut_inner(|e| {
+        for _ in 0..3 {
+            e.failed_to_send(cur_ts, false);
+        }
+    });
+}
+
+pub async fn test_bucket_depth() {
+    let policy = mock_policy(60_000, false);
+
+    // Depth halves with each bucket closer to our node id, down to the minimum
+    assert_eq!(policy.bucket_depth((CRYPTO_KIND_VLD0, 0)), 256);
+    assert_eq!(policy.bucket_depth((CRYPTO_KIND_VLD0, 1)), 128);
+    assert_eq!(policy.bucket_depth((CRYPTO_KIND_VLD0, 6)), 4);
+    assert_eq!(policy.bucket_depth((CRYPTO_KIND_VLD0, 7)), 4);
+    assert_eq!(policy.bucket_depth((CRYPTO_KIND_VLD0, 255)), 4);
+}
+
+pub async fn test_retention_score() {
+    let policy = mock_policy(60_000, false);
+    let cur_ts = Timestamp::now();
+
+    let mut bucket = Bucket::new(CRYPTO_KIND_VLD0);
+    let unknown = bucket.add_new_entry(mock_node_id(1).value);
+    let reliable = bucket.add_new_entry(mock_node_id(2).value);
+    make_reliable(&reliable, cur_ts);
+    let failing = bucket.add_new_entry(mock_node_id(3).value);
+    failing.with_mut_inner(|e| e.failed_to_send(cur_ts, false));
+
+    let unknown_score = unknown.with_inner(|e| policy.retention_score(e, cur_ts));
+    let reliable_score = reliable.with_inner(|e| policy.retention_score(e, cur_ts));
+    let failing_score = failing.with_inner(|e| policy.retention_score(e, cur_ts));
+
+    // Reliability and a good reputation are kept over entries we know nothing about,
+    // which are kept over entries we failed to reach
+    assert!(reliable_score > unknown_score);
+    assert!(unknown_score > failing_score);
+
+    // Age counts up to the long-lived age and no further
+    let later_score = unknown.with_inner(|e| policy.retention_score(e, cur_ts + TWO_MINUTES));
+    let much_later_score =
+        unknown.with_inner(|e| policy.retention_score(e, cur_ts + TWO_MINUTES + TWO_MINUTES));
+    assert!(later_score > unknown_score);
+    assert_eq!(later_score, much_later_score);
+}
+
+pub async fn test_is_protected() {
+    let cur_ts = Timestamp::now();
+
+    let mut bucket = Bucket::new(CRYPTO_KIND_VLD0);
+    let reliable = bucket.add_new_entry(mock_node_id(1).value);
+    make_reliable(&reliable, cur_ts);
+    let unreliable = bucket.add_new_entry(mock_node_id(2).value);
+
+    // Only long-lived reliable entries are protected, and only if the policy says so
+    let policy = mock_policy(60_000, true);
+    assert!(!reliable.with_inner(|e| policy.is_protected(e, cur_ts)));
+    assert!(reliable.with_inner(|e| policy.is_protected(e, cur_ts + TWO_MINUTES)));
+    assert!(!unreliable.with_inner(|e| policy.is_protected(e, cur_ts + TWO_MINUTES)));
+
+    let policy = mock_policy(60_000, false);
+    assert!(!reliable.with_inner(|e| policy.is_protected(e, cur_ts + TWO_MINUTES)));
+}
+
+pub async fn test_kick_order() {
+    let policy = mock_policy(60_000, false);
+    let cur_ts = Timestamp::now();
+
+    let mut bucket = Bucket::new(CRYPTO_KIND_VLD0);
+    for n in 1..=5 {
+        bucket.add_new_entry(mock_node_id(n).value);
+    }
+    bucket
+        .entry(&mock_node_id(1).value)
+        .unwrap()
+        .with_mut_inner(|e| e.set_punished(Some(PunishmentReason::FailedToDecodeOperation)));
+    make_dead(&bucket.entry(&mock_node_id(2).value).unwrap(), cur_ts);
+    make_reliable(&bucket.entry(&mock_node_id(4).value).unwrap(), cur_ts);
+    make_reliable(&bucket.entry(&mock_node_id(5).value).unwrap(), cur_ts);
+
+    // Nothing to kick if the bucket isn't over its depth
+    assert_eq!(bucket.kick(&policy, 5, &BTreeSet::new()), None);
+
+    // Punished and dead entries go first
+    assert_eq!(
+        bucket.kick(&policy, 3, &BTreeSet::new()),
+        Some(BTreeSet::from([
+            mock_node_id(1).value,
+            mock_node_id(2).value
+        ]))
+    );
+
+    // Then the lowest retention score
+    assert_eq!(
+        bucket.kick(&policy, 2, &BTreeSet::new()),
+        Some(BTreeSet::from([mock_node_id(3).value]))
+    );
+
+    // Exempt and referenced entries are kept, even if that leaves the bucket over its depth
+    let referenced = bucket.entry(&mock_node_id(5).value).unwrap();
+    referenced
+        .ref_count
+        .fetch_add(1, core::sync::atomic::Ordering::AcqRel);
+    assert_eq!(
+        bucket.kick(&policy, 0, &BTreeSet::from([mock_node_id(4).value])),
+        None
+    );
+    referenced
+        .ref_count
+        .fetch_sub(1, core::sync::atomic::Ordering::AcqRel);
+    assert_eq!(
+        bucket.kick(&policy, 0, &BTreeSet::new()),
+        Some(BTreeSet::from([
+            mock_node_id(4).value,
+            mock_node_id(5).value
+        ]))
+    );
+}
+
+pub async fn test_kick_protected_capped() {
+    // Every reliable entry is long-lived as soon as it is added
+    let policy = mock_policy(0, true);
+    let cur_ts = Timestamp::now();
+
+    let mut bucket = Bucket::new(CRYPTO_KIND_VLD0);
+    for n in 1..=6 {
+        let entry = bucket.add_new_entry(mock_node_id(n).value);
+        make_reliable(&entry, cur_ts);
+    }
+
+    // A bucket full of protected entries is still kicked down to its depth
+    let kicked = bucket.kick(&policy, 2, &BTreeSet::new()).unwrap();
+    assert_eq!(kicked.len(), 4);
+    assert_eq!(bucket.entries().count(), 2);
+    for (k, _) in bucket.entries() {
+        assert!(!kicked.contains(k));
+    }
+
+    // The protected entries that were kept are still kept ahead of newcomers
+    bucket.add_new_entry(mock_node_id(7).value);
+    assert_eq!(
+        bucket.kick(&policy, 2, &BTreeSet::new()),
+        Some(BTreeSet::from([mock_node_id(7).value]))
+    );
+}
+
+pub async fn test_all() {
+    test_bucket_depth().await;
+    test_retention_score().await;
+    test_is_protected().await;
+    test_kick_order().await;
+    test_kick_protected_capped().await;
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+const RELAY: u8 = 0xFF;
+
+// A node behind an inbound relay, which forwards signals to it
+fn mock_relayed_peer(n: u8, relay: u8, dids: Vec<DialInfoDetail>) -> PeerInfo {
+    PeerInfo::new(
+        mock_node_ids(n),
+        SignedNodeInfo::Relayed(SignedRelayedNodeInfo::new(
+            mock_node_info(NetworkClass::InboundCapable, dids),
+            mock_node_ids(relay),
+            SignedDirectNodeInfo::new(
+                mock_node_info(
+                    NetworkClass::InboundCapable,
+                    vec![
+                        mock_udp(DialInfoClass::Direct, "192.0.2.1:5150"),
+                        mock_tcp(DialInfoClass::Direct, "192.0.2.1:5150"),
+                    ],
+                ),
+                Timestamp::new(0),
+                vec![],
+            ),
+            Timestamp::new(0),
+            vec![],
+        )),
+    )
+}
+
+fn contact_method(
+    routing_table: &RoutingTable,
+    peer_a: &PeerInfo,
+    peer_b: &PeerInfo,
+) -> ContactMethod {
+    routing_table.get_contact_method(
+        RoutingDomain::PublicInternet,
+        peer_a,
+        peer_b,
+        DialInfoFilter::all(),
+        Sequencing::NoPreference,
+        None,
+    )
+}
+
+pub async fn test_restricted_nat_contact_method(routing_table: RoutingTable) {
+    use DialInfoClass::{AddressRestrictedNAT, Direct, PortRestrictedNAT};
+
+    let cm = |peer_a: &PeerInfo, peer_b: &PeerInfo| contact_method(&routing_table, peer_a, peer_b);
+    let hole_punch = |protocol_type| {
+        ContactMethod::SignalHolePunch(mock_node_id(RELAY), mock_node_id(2), protocol_type)
+    };
+
+    let b_udp_tcp = mock_relayed_peer(
+        2,
+        RELAY,
+        vec![
+            mock_udp(PortRestrictedNAT, "203.0.113.2:5150"),
+            mock_tcp(PortRestrictedNAT, "203.0.113.2:5150"),
+        ],
+    );
+    let b_tcp = mock_relayed_peer(
+        2,
+        RELAY,
+        vec![mock_tcp(AddressRestrictedNAT, "203.0.113.2:5150")],
+    );
+    let b_udp = mock_relayed_peer(
+        2,
+        RELAY,
+        vec![mock_udp(PortRestrictedNAT, "203.0.113.2:5150")],
+    );
+
+    let a_udp_tcp = mock_peer_info(
+        1,
+        NetworkClass::InboundCapable,
+        vec![
+            mock_udp(AddressRestrictedNAT, "198.51.100.1:5150"),
+            mock_tcp(AddressRestrictedNAT, "198.51.100.1:5150"),
+        ],
+    );
+    let a_tcp = mock_peer_info(
+        1,
+        NetworkClass::InboundCapable,
+        vec![mock_tcp(PortRestrictedNAT, "198.51.100.1:5150")],
+    );
+
+    // UDP hole punching is preferred when both nodes have restricted udp dial info
+    assert_eq!(cm(&a_udp_tcp, &b_udp_tcp), hole_punch(ProtocolType::UDP));
+
+    // TCP hole punching is used when there is no udp dial info in common
+    assert_eq!(cm(&a_tcp, &b_udp_tcp), hole_punch(ProtocolType::TCP));
+    assert_eq!(cm(&a_udp_tcp, &b_tcp), hole_punch(ProtocolType::TCP));
+    assert_eq!(cm(&a_tcp, &b_tcp), hole_punch(ProtocolType::TCP));
+
+    // Without a protocol in common, the relay is used
+    assert_eq!(
+        cm(&a_tcp, &b_udp),
+        ContactMethod::InboundRelay(mock_node_id(RELAY))
+    );
+
+    // Nodes that can't receive inbound connections can't hole punch
+    let a_outbound = mock_peer_info(
+        1,
+        NetworkClass::OutboundOnly,
+        vec![mock_tcp(PortRestrictedNAT, "198.51.100.1:5150")],
+    );
+    assert_eq!(
+        cm(&a_outbound, &b_tcp),
+        ContactMethod::InboundRelay(mock_node_id(RELAY))
+    );
+
+    // A node with direct dial info is connected back to rather than hole punched
+    let a_direct = mock_peer_info(
+        1,
+        NetworkClass::InboundCapable,
+        vec![mock_tcp(Direct, "198.51.100.1:5150")],
+    );
+    assert_eq!(
+        cm(&a_direct, &b_tcp),
+        ContactMethod::SignalReverse(mock_node_id(RELAY), mock_node_id(2))
+    );
+
+    // The relay itself already has a connection to the node
+    let relay = mock_peer_info(
+        RELAY,
+        NetworkClass::InboundCapable,
+        vec![mock_tcp(PortRestrictedNAT, "198.51.100.1:5150")],
+    );
+    assert_eq!(cm(&relay, &b_tcp), ContactMethod::Existing);
+}
+
+pub async fn test_all() {
+    let routing_table = mock_routing_table();
+    test_restricted_nat_contact_method(routing_table).await;
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+const CAP_CHAT: Capability = FourCC(*b"CHAT");
+const CAP_FILE: Capability = FourCC(*b"FILE");
+
+// An unsigned peer that advertises application capabilities alongside the usual ones
+fn mock_app_peer(n: u8, addr: &str, app_capabilities: &[Capability]) -> PeerInfo {
+    let mut capabilities = PUBLIC_INTERNET_CAPABILITIES.to_vec();
+    capabilities.extend_from_slice(app_capabilities);
+    PeerInfo::new(
+        mock_node_ids(n),
+        SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+            NodeInfo::new(
+                NetworkClass::InboundCapable,
+                ProtocolTypeSet::all(),
+                AddressTypeSet::all(),
+                vec![0],
+                vec![CRYPTO_KIND_VLD0],
+                capabilities,
+                vec![mock_udp(DialInfoClass::Direct, addr)],
+            ),
+            Timestamp::new(0),
+            vec![],
+        )),
+    )
+}
+
+fn node_numbers(node_ids: &[TypedKey]) -> BTreeSet<u8> {
+    node_ids.iter().map(|nid| nid.value.bytes[0]).collect()
+}
+
+pub async fn test_find_nodes_with_capability(api: VeilidAPI) {
+    let routing_table = api.routing_table().unwrap();
+
+    // Peers 1 to 5 chat, peer 6 only shares files, and peer 7 advertises nothing of its own
+    let register = |rd: RoutingDomain, peer_info: PeerInfo| {
+        routing_table
+            .register_node_with_peer_info(rd, peer_info, true)
+            .unwrap();
+    };
+    for n in 1..=5 {
+        register(
+            RoutingDomain::PublicInternet,
+            mock_app_peer(n, &format!("44.0.0.{}:5150", n), &[CAP_CHAT]),
+        );
+    }
+    register(
+        RoutingDomain::PublicInternet,
+        mock_app_peer(6, "44.0.0.6:5150", &[CAP_FILE]),
+    );
+    register(
+        RoutingDomain::PublicInternet,
+        mock_app_peer(7, "44.0.0.7:5150", &[]),
+    );
+
+    // Peer 8 chats only on the local network
+    register(
+        RoutingDomain::LocalNetwork,
+        mock_app_peer(8, "192.168.0.8:5150", &[CAP_CHAT]),
+    );
+
+    // Only peers advertising the capability in the PublicInternet routing domain are found
+    let find = |capability: Capability, count: u32| {
+        node_numbers(&api.find_peers_with_capability(capability, count).unwrap())
+    };
+    assert_eq!(find(CAP_CHAT, 10), BTreeSet::from([1, 2, 3, 4, 5]));
+    assert_eq!(find(CAP_FILE, 10), BTreeSet::from([6]));
+    assert!(find(FourCC(*b"NONE"), 10).is_empty());
+
+    // No more than the requested number of peers are returned
+    let some_chat = find(CAP_CHAT, 3);
+    assert_eq!(some_chat.len(), 3);
+    assert!(some_chat.is_subset(&BTreeSet::from([1, 2, 3, 4, 5])));
+    assert!(find(CAP_CHAT, 0).is_empty());
+
+    let nrs = routing_table.find_nodes_with_capability(CAP_CHAT, 2);
+    assert_eq!(nrs.len(), 2);
+    assert!(nrs.iter().all(|nr| nr
+        .node_info(RoutingDomain::PublicInternet)
+        .map(|ni| ni.has_capability(CAP_CHAT))
+        .unwrap_or(false)));
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    test_find_nodes_with_capability(api.clone()).await;
+
+    api.shutdown().await;
+}
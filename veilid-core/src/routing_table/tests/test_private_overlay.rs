@@ -0,0 +1,221 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+fn cidr(network: &str) -> (IpAddr, IpAddr) {
+    ipaddr_parse_cidr(network).unwrap()
+}
+
+fn address(addr: &str) -> Address {
+    Address::from_ip_addr(IpAddr::from_str(addr).unwrap())
+}
+
+// A bootstrap-capable peer with a single direct dial info
+// Its signature is never checked here, it only has to be present
+fn mock_signed_peer(n: u8, addr: &str) -> PeerInfo {
+    PeerInfo::new(
+        mock_node_ids(n),
+        SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+            mock_node_info(
+                NetworkClass::InboundCapable,
+                vec![mock_udp(DialInfoClass::Direct, addr)],
+            ),
+            Timestamp::new(0),
+            vec![TypedSignature::new(
+                CRYPTO_KIND_VLD0,
+                Signature::new([n; 64]),
+            )],
+        )),
+    )
+}
+
+// Route overlay traffic through a wireguard network inside the lan, and a yggdrasil network that uses global addresses
+fn configure_overlay(routing_table: &RoutingTable) {
+    routing_table.configure_local_network_routing_domain(vec![cidr("10.0.0.0/8")]);
+    routing_table
+        .configure_private_overlay_routing_domain(vec![cidr("10.8.0.0/16"), cidr("200::/7")]);
+}
+
+pub async fn test_overlay_address_separation() {
+    let routing_table = mock_routing_table();
+    configure_overlay(&routing_table);
+    let inner = routing_table.inner.read();
+
+    let can_contain =
+        |rd: RoutingDomain, addr: &str| inner.routing_domain_can_contain_address(rd, address(addr));
+
+    // Overlay addresses belong only to the overlay, even where another domain's rules would accept them
+    for overlay_addr in ["10.8.1.1", "201:abcd::1"] {
+        assert!(can_contain(RoutingDomain::PrivateOverlay, overlay_addr));
+        assert!(!can_contain(RoutingDomain::LocalNetwork, overlay_addr));
+        assert!(!can_contain(RoutingDomain::PublicInternet, overlay_addr));
+        assert_eq!(
+            inner.routing_domain_for_address(address(overlay_addr)),
+            Some(RoutingDomain::PrivateOverlay)
+        );
+    }
+    assert!(address("201:abcd::1").is_global());
+
+    // Other addresses are left to their own domains
+    assert!(can_contain(RoutingDomain::LocalNetwork, "10.1.1.1"));
+    assert!(!can_contain(RoutingDomain::PrivateOverlay, "10.1.1.1"));
+    assert!(can_contain(RoutingDomain::PublicInternet, "44.0.0.1"));
+    assert!(!can_contain(RoutingDomain::PrivateOverlay, "44.0.0.1"));
+
+    // Node info with overlay dial info is only valid in the overlay
+    let overlay_peer = mock_signed_peer(1, "[201:abcd::1]:5150");
+    for rd in RoutingDomain::all() {
+        assert_eq!(
+            inner.signed_node_info_is_valid_in_routing_domain(rd, overlay_peer.signed_node_info()),
+            rd == RoutingDomain::PrivateOverlay
+        );
+    }
+}
+
+pub async fn test_peer_routing_domain() {
+    // FindNode over the local network exchanges public internet peers, the overlay keeps its own
+    assert_eq!(
+        RoutingDomain::PublicInternet.peer_routing_domain(),
+        RoutingDomain::PublicInternet
+    );
+    assert_eq!(
+        RoutingDomain::LocalNetwork.peer_routing_domain(),
+        RoutingDomain::PublicInternet
+    );
+    assert_eq!(
+        RoutingDomain::PrivateOverlay.peer_routing_domain(),
+        RoutingDomain::PrivateOverlay
+    );
+}
+
+fn closest_peers(routing_table: &RoutingTable, rd: RoutingDomain) -> Vec<PeerInfo> {
+    match routing_table.find_preferred_closest_peers(rd, mock_node_id(0), &[]) {
+        NetworkResult::Value(v) => v,
+        res => panic!("no closest peers: {:?}", res),
+    }
+}
+
+pub async fn test_find_node_separation(routing_table: RoutingTable) {
+    let overlay_peer = mock_signed_peer(1, "[201:abcd::1]:5150");
+    let wireguard_peer = mock_signed_peer(2, "10.8.0.2:5150");
+    let public_peer = mock_signed_peer(3, "44.0.0.3:5150");
+
+    // Peers are only registered in the domain their addresses belong to
+    assert!(routing_table
+        .register_node_with_peer_info(RoutingDomain::PublicInternet, overlay_peer.clone(), false)
+        .is_err());
+    assert!(routing_table
+        .register_node_with_peer_info(RoutingDomain::LocalNetwork, wireguard_peer.clone(), false)
+        .is_err());
+    assert!(routing_table
+        .register_node_with_peer_info(RoutingDomain::PrivateOverlay, public_peer.clone(), false)
+        .is_err());
+
+    // FindNode answers are registered in the domain they were asked over
+    assert!(routing_table
+        .register_find_node_answer(
+            RoutingDomain::PublicInternet,
+            CRYPTO_KIND_VLD0,
+            vec![overlay_peer.clone(), wireguard_peer.clone()],
+        )
+        .is_empty());
+    assert_eq!(
+        routing_table
+            .register_find_node_answer(
+                RoutingDomain::PrivateOverlay,
+                CRYPTO_KIND_VLD0,
+                vec![overlay_peer, wireguard_peer],
+            )
+            .len(),
+        2
+    );
+    assert_eq!(
+        routing_table
+            .register_find_node_answer(
+                RoutingDomain::PublicInternet,
+                CRYPTO_KIND_VLD0,
+                vec![public_peer],
+            )
+            .len(),
+        1
+    );
+
+    // FindNode questions are answered only with peers from the domain they were asked over
+    let has_peer = |peers: &[PeerInfo], n: u8| {
+        peers
+            .iter()
+            .any(|p| p.node_ids().contains(&mock_node_id(n)))
+    };
+    let has_overlay_address = |peers: &[PeerInfo]| {
+        let inner = routing_table.inner.read();
+        peers.iter().any(|p| {
+            p.signed_node_info()
+                .node_info()
+                .dial_info_detail_list()
+                .iter()
+                .any(|did| {
+                    inner.routing_domain_for_address(did.dial_info.address())
+                        == Some(RoutingDomain::PrivateOverlay)
+                })
+        })
+    };
+
+    let public_peers = closest_peers(&routing_table, RoutingDomain::PublicInternet);
+    assert!(has_peer(&public_peers, 3));
+    assert!(!has_peer(&public_peers, 1));
+    assert!(!has_peer(&public_peers, 2));
+    assert!(!has_overlay_address(&public_peers));
+
+    let overlay_peers = closest_peers(&routing_table, RoutingDomain::PrivateOverlay);
+    assert!(has_peer(&overlay_peers, 1));
+    assert!(has_peer(&overlay_peers, 2));
+    assert!(!has_peer(&overlay_peers, 3));
+}
+
+pub async fn test_overlay_bootstrap(routing_table: RoutingTable) {
+    // The overlay needs far fewer peers than the public internet, and counts only its own
+    routing_table.inner.write().refresh_cached_entry_counts();
+    assert!(!routing_table
+        .get_bootstrap_crypto_kinds(RoutingDomain::PrivateOverlay)
+        .contains(&CRYPTO_KIND_VLD0));
+
+    // Bootstrap nodes are only taken from the domain being bootstrapped
+    let is_node = |nr: &NodeRef, n: u8| nr.node_ids().contains(&mock_node_id(n));
+    let overlay_nodes =
+        routing_table.find_bootstrap_nodes_filtered(RoutingDomain::PrivateOverlay, 2);
+    assert!(overlay_nodes.iter().any(|nr| is_node(nr, 1)));
+    assert!(overlay_nodes.iter().any(|nr| is_node(nr, 2)));
+    assert!(!overlay_nodes.iter().any(|nr| is_node(nr, 3)));
+
+    let public_nodes =
+        routing_table.find_bootstrap_nodes_filtered(RoutingDomain::PublicInternet, 2);
+    assert!(!public_nodes.iter().any(|nr| is_node(nr, 1)));
+    assert!(!public_nodes.iter().any(|nr| is_node(nr, 2)));
+}
+
+pub async fn test_all() {
+    test_overlay_address_separation().await;
+    test_peer_routing_domain().await;
+
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let routing_table = api.routing_table().unwrap();
+
+    // Answering FindNode needs our own node info to be valid in the domain
+    configure_overlay(&routing_table);
+    {
+        let mut inner = routing_table.inner.write();
+        for rd in [RoutingDomain::PublicInternet, RoutingDomain::PrivateOverlay] {
+            inner.with_routing_domain_mut(rd, |rdd| {
+                rdd.common_mut()
+                    .set_network_class(Some(NetworkClass::InboundCapable))
+            });
+        }
+    }
+
+    test_find_node_separation(routing_table.clone()).await;
+    test_overlay_bootstrap(routing_table).await;
+
+    api.shutdown().await;
+}
@@ -0,0 +1,214 @@
+use super::*;
+
+const ONE_MINUTE: TimestampDuration = TimestampDuration::new(60_000_000u64);
+const TEN_MINUTES: TimestampDuration = TimestampDuration::new(600_000_000u64);
+
+fn ip(addr: &str) -> IpAddr {
+    IpAddr::from_str(addr).unwrap()
+}
+
+fn mock_direct_node_info(addr: &str) -> NodeInfo {
+    mock_node_info(
+        NetworkClass::InboundCapable,
+        vec![mock_udp(DialInfoClass::Direct, addr)],
+    )
+}
+
+fn mock_direct_hop(addr: &str) -> SignedNodeInfo {
+    SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+        mock_direct_node_info(addr),
+        Timestamp::new(0),
+        vec![],
+    ))
+}
+
+fn mock_relayed_hop(addr: &str, relay_addr: &str) -> SignedNodeInfo {
+    SignedNodeInfo::Relayed(SignedRelayedNodeInfo::new(
+        mock_direct_node_info(addr),
+        mock_node_ids(0xFF),
+        SignedDirectNodeInfo::new(mock_direct_node_info(relay_addr), Timestamp::new(0), vec![]),
+        Timestamp::new(0),
+        vec![],
+    ))
+}
+
+pub async fn test_hop_score() {
+    let fast = LatencyStats {
+        average: TimestampDuration::new_ms(10u64),
+        ..Default::default()
+    };
+    let slow = LatencyStats {
+        average: TimestampDuration::new_ms(200u64),
+        ..Default::default()
+    };
+
+    // The default route quality scores every hop the same
+    let route_quality = RouteQuality::default();
+    assert_eq!(route_hop_score(&route_quality, Some(&fast), 0), 0);
+    assert_eq!(route_hop_score(&route_quality, Some(&slow), 5), 0);
+
+    // Latency is scored per millisecond, and hops we have not measured are assumed to be slow
+    let route_quality = RouteQuality {
+        latency_weight: 2,
+        ..Default::default()
+    };
+    assert_eq!(route_hop_score(&route_quality, Some(&fast), 0), 20);
+    assert_eq!(route_hop_score(&route_quality, Some(&slow), 0), 400);
+    assert_eq!(route_hop_score(&route_quality, None, 0), 2_000);
+
+    // Failures are scored per failed route test, and can outweigh latency
+    let route_quality = RouteQuality {
+        latency_weight: 1,
+        failure_weight: 100,
+        ..Default::default()
+    };
+    assert_eq!(route_hop_score(&route_quality, Some(&fast), 3), 310);
+    assert!(
+        route_hop_score(&route_quality, Some(&fast), 2)
+            > route_hop_score(&route_quality, Some(&slow), 0)
+    );
+
+    // Scores saturate rather than overflow
+    let route_quality = RouteQuality {
+        latency_weight: u32::MAX,
+        failure_weight: u32::MAX,
+        ..Default::default()
+    };
+    assert_eq!(route_hop_score(&route_quality, None, u32::MAX), u64::MAX);
+}
+
+pub async fn test_hop_failures() {
+    let mut cache = RouteSpecStoreCache::default();
+    let cur_ts = Timestamp::now();
+    let a = mock_node_ids(1);
+    let b = mock_node_ids(2);
+
+    // Every hop of a failed route test counts a failure
+    cache.record_route_test(&[a.clone(), b.clone()], false, cur_ts);
+    cache.record_route_test(&[a.clone()], false, cur_ts + ONE_MINUTE);
+    assert_eq!(cache.get_hop_failure_count(&a), 2);
+    assert_eq!(cache.get_hop_failure_count(&b), 1);
+
+    // A successful route test clears the failures
+    cache.record_route_test(&[b.clone()], true, cur_ts + ONE_MINUTE);
+    assert_eq!(cache.get_hop_failure_count(&b), 0);
+
+    // Failures are forgotten once the last one is no longer recent
+    cache.record_route_test(&[b.clone()], false, cur_ts);
+    cache.prune_hop_failures(cur_ts + TEN_MINUTES);
+    assert_eq!(cache.get_hop_failure_count(&a), 2);
+    assert_eq!(cache.get_hop_failure_count(&b), 0);
+    cache.prune_hop_failures(cur_ts + ONE_MINUTE + TEN_MINUTES);
+    assert_eq!(cache.get_hop_failure_count(&a), 0);
+}
+
+pub async fn test_asn_table() {
+    // Entries are '<network>/<prefix length> <asn>', optionally with an 'AS' prefix
+    assert!(parse_asn_table_entry("192.0.2.0/24 64496").is_some());
+    assert!(parse_asn_table_entry(" 2001:db8::/32   AS64497 ").is_some());
+    assert!(parse_asn_table_entry("192.0.2.0/24").is_none());
+    assert!(parse_asn_table_entry("192.0.2.0 64496").is_none());
+    assert!(parse_asn_table_entry("192.0.2.0/33 64496").is_none());
+    assert!(parse_asn_table_entry("192.0.2.0/24 ASX").is_none());
+    assert!(parse_asn_table_entry("192.0.2.0/24 64496 64497").is_none());
+
+    let asn_table = AsnTable::new(&[
+        "198.51.0.0/16 64496".to_string(),
+        "198.51.100.0/24 64497".to_string(),
+        "2001:db8::/32 64498".to_string(),
+        "bogus".to_string(),
+    ]);
+
+    // The longest matching prefix wins, and address families never match each other
+    assert_eq!(asn_table.lookup(ip("198.51.100.7")), Some(64497));
+    assert_eq!(asn_table.lookup(ip("198.51.7.7")), Some(64496));
+    assert_eq!(asn_table.lookup(ip("2001:db8:1::1")), Some(64498));
+    assert_eq!(asn_table.lookup(ip("203.0.113.1")), None);
+    assert_eq!(asn_table.lookup(ip("::ffff:198.51.100.7")), None);
+
+    // Addresses with no known autonomous system are grouped by a coarse network instead
+    assert_eq!(
+        asn_table.network_group(ip("198.51.100.7")),
+        NetworkGroup::Asn(64497)
+    );
+    assert_eq!(
+        asn_table.network_group(ip("203.0.113.1")),
+        NetworkGroup::Block(ip("203.0.0.0"))
+    );
+    assert_eq!(
+        asn_table.network_group(ip("2001:db9:1::1")),
+        NetworkGroup::Block(ip("2001:db9::"))
+    );
+}
+
+pub async fn test_route_diversity() {
+    let asn_table = AsnTable::new(&[
+        "198.51.100.0/24 64496".to_string(),
+        "203.0.113.0/24 64496".to_string(),
+        "192.0.2.0/24 64497".to_string(),
+    ]);
+    let ip6_prefix_size = 56;
+    let any = RouteQuality::default();
+    let diverse_ip_blocks = RouteQuality {
+        diverse_ip_blocks: true,
+        ..Default::default()
+    };
+    let diverse_asns = RouteQuality {
+        diverse_asns: true,
+        ..Default::default()
+    };
+    let is_diverse = |route_quality: &RouteQuality, hops: &[&SignedNodeInfo]| {
+        is_route_diverse(route_quality, &asn_table, hops, ip6_prefix_size)
+    };
+
+    let as1 = mock_direct_hop("198.51.100.1:5150");
+    let as1_same_host = mock_direct_hop("198.51.100.1:5151");
+    let as1_other_network = mock_direct_hop("203.0.113.1:5150");
+    let as2 = mock_direct_hop("192.0.2.1:5150");
+    let unmapped = mock_direct_hop("100.100.1.1:5150");
+    let unmapped_same_block = mock_direct_hop("100.100.2.2:5150");
+    let unmapped_other_block = mock_direct_hop("100.101.1.1:5150");
+
+    // Without diversity any hops can be combined
+    assert!(is_diverse(&any, &[&as1, &as1_same_host]));
+
+    // IP block diversity keeps hops on distinct addresses, but not distinct autonomous systems
+    assert!(!is_diverse(&diverse_ip_blocks, &[&as1, &as1_same_host]));
+    assert!(is_diverse(&diverse_ip_blocks, &[&as1, &as1_other_network]));
+
+    // ASN diversity keeps hops in distinct autonomous systems, even across their networks
+    assert!(!is_diverse(&diverse_asns, &[&as1, &as1_other_network]));
+    assert!(is_diverse(&diverse_asns, &[&as1, &as2]));
+    assert!(!is_diverse(&diverse_asns, &[&as2, &as1, &as1_same_host]));
+
+    // Unmapped addresses are kept apart by their coarse network
+    assert!(!is_diverse(
+        &diverse_asns,
+        &[&unmapped, &unmapped_same_block]
+    ));
+    assert!(is_diverse(
+        &diverse_asns,
+        &[&unmapped, &unmapped_other_block]
+    ));
+
+    // A hop may share an autonomous system with its own relay, but relays count against the other hops
+    let relayed_in_as2 = mock_relayed_hop("192.0.2.7:5150", "192.0.2.8:5150");
+    let relayed_via_as1 = mock_relayed_hop("100.102.1.1:5150", "198.51.100.9:5150");
+    assert!(is_diverse(&diverse_asns, &[&relayed_in_as2, &as1]));
+    assert!(!is_diverse(&diverse_asns, &[&relayed_in_as2, &as2]));
+    assert!(!is_diverse(&diverse_asns, &[&relayed_via_as1, &as1]));
+    assert!(is_diverse(&diverse_ip_blocks, &[&relayed_via_as1, &as1]));
+
+    let relayed_via_as1_host = mock_relayed_hop("100.103.1.1:5150", "198.51.100.1:5152");
+    assert!(!is_diverse(
+        &diverse_ip_blocks,
+        &[&relayed_via_as1_host, &as1]
+    ));
+}
+
+pub async fn test_all() {
+    test_hop_score().await;
+    test_hop_failures().await;
+    test_asn_table().await;
+    test_route_diversity().await;
+}
@@ -2,19 +2,33 @@ use super::*;
 
 pub type Capability = FourCC;
 pub const CAP_ROUTE: Capability = FourCC(*b"ROUT");
-#[cfg(feature = "unstable-tunnels")]
 pub const CAP_TUNNEL: Capability = FourCC(*b"TUNL");
 pub const CAP_SIGNAL: Capability = FourCC(*b"SGNL");
 pub const CAP_RELAY: Capability = FourCC(*b"RLAY");
 pub const CAP_VALIDATE_DIAL_INFO: Capability = FourCC(*b"DIAL");
 pub const CAP_DHT: Capability = FourCC(*b"DHTV");
 pub const CAP_DHT_WATCH: Capability = FourCC(*b"DHTW");
+pub const CAP_DHT_PIN: Capability = FourCC(*b"DHTP");
 pub const CAP_APPMESSAGE: Capability = FourCC(*b"APPM");
-#[cfg(feature = "unstable-blockstore")]
 pub const CAP_BLOCKSTORE: Capability = FourCC(*b"BLOC");
 
 pub const DISTANCE_METRIC_CAPABILITIES: &[Capability] = &[CAP_DHT, CAP_DHT_WATCH];
 
+/// Capabilities defined by Veilid itself, whether or not they are enabled in this build.
+/// Applications may not advertise these as their own.
+pub const RESERVED_CAPABILITIES: &[Capability] = &[
+    CAP_ROUTE,
+    CAP_TUNNEL,
+    CAP_SIGNAL,
+    CAP_RELAY,
+    CAP_VALIDATE_DIAL_INFO,
+    CAP_DHT,
+    CAP_DHT_WATCH,
+    CAP_DHT_PIN,
+    CAP_APPMESSAGE,
+    CAP_BLOCKSTORE,
+];
+
 #[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct NodeInfo {
     network_class: NetworkClass,
@@ -9,14 +9,29 @@ use super::*;
 pub enum RoutingDomain {
     LocalNetwork = 0,
     PublicInternet = 1,
+    PrivateOverlay = 2,
 }
 impl RoutingDomain {
     pub const fn count() -> usize {
-        2
+        3
     }
     pub const fn all() -> [RoutingDomain; RoutingDomain::count()] {
         // Routing domain here is listed in order of preference, keep in order
-        [RoutingDomain::LocalNetwork, RoutingDomain::PublicInternet]
+        [
+            RoutingDomain::LocalNetwork,
+            RoutingDomain::PublicInternet,
+            RoutingDomain::PrivateOverlay,
+        ]
+    }
+    /// The routing domain whose peers are exchanged by FindNode over this routing domain
+    /// Only the PrivateOverlay keeps its own set of peers, the LocalNetwork shares the PublicInternet's
+    pub const fn peer_routing_domain(&self) -> RoutingDomain {
+        match self {
+            RoutingDomain::LocalNetwork | RoutingDomain::PublicInternet => {
+                RoutingDomain::PublicInternet
+            }
+            RoutingDomain::PrivateOverlay => RoutingDomain::PrivateOverlay,
+        }
     }
 }
 pub type RoutingDomainSet = EnumSet<RoutingDomain>;
@@ -26,9 +26,16 @@ mod socket_address;
 mod tunnel;
 mod typed_key;
 mod typed_signature;
+mod value_subkey_range_set;
 
+#[cfg(feature = "unstable-blockstore")]
+pub(crate) use operations::MAX_FIND_BLOCK_A_DATA_LEN;
 pub(crate) use operations::MAX_INSPECT_VALUE_A_SEQS_LEN;
+#[cfg(feature = "unstable-tunnels")]
+pub(crate) use operations::MAX_TUNNEL_DATA_LEN;
 pub(in crate::rpc_processor) use operations::*;
+pub(crate) use operations::{MAX_GET_VALUES_A_DATA_LEN, MAX_GET_VALUES_Q_SUBKEYS_LEN};
+pub(crate) use operations::{MAX_SET_VALUES_Q_DATA_LEN, MAX_SET_VALUES_Q_VALUES_LEN};
 
 pub(crate) use address::*;
 pub(crate) use address_type_set::*;
@@ -57,6 +64,7 @@ pub use socket_address::*;
 pub use tunnel::*;
 pub use typed_key::*;
 pub use typed_signature::*;
+pub use value_subkey_range_set::*;
 
 use super::*;
 
@@ -66,6 +74,9 @@ pub(in crate::rpc_processor) enum QuestionContext {
     GetValue(ValidateGetValueContext),
     SetValue(ValidateSetValueContext),
     InspectValue(ValidateInspectValueContext),
+    GetValues(ValidateGetValuesContext),
+    SetValues(ValidateSetValuesContext),
+    AppCallStream(AppCallStreamContext),
 }
 
 #[derive(Clone)]
@@ -49,6 +49,9 @@ pub(in crate::rpc_processor) enum RPCAnswerDetail {
     CompleteTunnelA(Box<RPCOperationCompleteTunnelA>),
     #[cfg(feature = "unstable-tunnels")]
     CancelTunnelA(Box<RPCOperationCancelTunnelA>),
+    GetValuesA(Box<RPCOperationGetValuesA>),
+    SetValuesA(Box<RPCOperationSetValuesA>),
+    PinValueA(Box<RPCOperationPinValueA>),
 }
 
 impl RPCAnswerDetail {
@@ -72,6 +75,9 @@ impl RPCAnswerDetail {
             RPCAnswerDetail::CompleteTunnelA(_) => "CompleteTunnelA",
             #[cfg(feature = "unstable-tunnels")]
             RPCAnswerDetail::CancelTunnelA(_) => "CancelTunnelA",
+            RPCAnswerDetail::GetValuesA(_) => "GetValuesA",
+            RPCAnswerDetail::SetValuesA(_) => "SetValuesA",
+            RPCAnswerDetail::PinValueA(_) => "PinValueA",
         }
     }
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -93,6 +99,9 @@ impl RPCAnswerDetail {
             RPCAnswerDetail::CompleteTunnelA(r) => r.validate(validate_context),
             #[cfg(feature = "unstable-tunnels")]
             RPCAnswerDetail::CancelTunnelA(r) => r.validate(validate_context),
+            RPCAnswerDetail::GetValuesA(r) => r.validate(validate_context),
+            RPCAnswerDetail::SetValuesA(r) => r.validate(validate_context),
+            RPCAnswerDetail::PinValueA(r) => r.validate(validate_context),
         }
     }
     pub fn decode(
@@ -135,6 +144,11 @@ impl RPCAnswerDetail {
                 let out = RPCOperationInspectValueA::decode(&op_reader)?;
                 RPCAnswerDetail::InspectValueA(Box::new(out))
             }
+            #[cfg(not(feature = "unstable-blockstore"))]
+            veilid_capnp::answer::detail::SupplyBlockA(_)
+            | veilid_capnp::answer::detail::FindBlockA(_) => {
+                return Err(RPCError::ignore("blockstore is not enabled"));
+            }
             #[cfg(feature = "unstable-blockstore")]
             veilid_capnp::answer::detail::SupplyBlockA(r) => {
                 let op_reader = r.map_err(RPCError::protocol)?;
@@ -147,6 +161,12 @@ impl RPCAnswerDetail {
                 let out = RPCOperationFindBlockA::decode(&op_reader)?;
                 RPCAnswerDetail::FindBlockA(Box::new(out))
             }
+            #[cfg(not(feature = "unstable-tunnels"))]
+            veilid_capnp::answer::detail::StartTunnelA(_)
+            | veilid_capnp::answer::detail::CompleteTunnelA(_)
+            | veilid_capnp::answer::detail::CancelTunnelA(_) => {
+                return Err(RPCError::ignore("tunnels are not enabled"));
+            }
             #[cfg(feature = "unstable-tunnels")]
             veilid_capnp::answer::detail::StartTunnelA(r) => {
                 let op_reader = r.map_err(RPCError::protocol)?;
@@ -165,6 +185,21 @@ impl RPCAnswerDetail {
                 let out = RPCOperationCancelTunnelA::decode(&op_reader)?;
                 RPCAnswerDetail::CancelTunnelA(Box::new(out))
             }
+            veilid_capnp::answer::detail::GetValuesA(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationGetValuesA::decode(&op_reader)?;
+                RPCAnswerDetail::GetValuesA(Box::new(out))
+            }
+            veilid_capnp::answer::detail::SetValuesA(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationSetValuesA::decode(&op_reader)?;
+                RPCAnswerDetail::SetValuesA(Box::new(out))
+            }
+            veilid_capnp::answer::detail::PinValueA(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationPinValueA::decode(&op_reader)?;
+                RPCAnswerDetail::PinValueA(Box::new(out))
+            }
         };
         Ok(out)
     }
@@ -202,6 +237,9 @@ impl RPCAnswerDetail {
             RPCAnswerDetail::CancelTunnelA(d) => {
                 d.encode(&mut builder.reborrow().init_cancel_tunnel_a())
             }
+            RPCAnswerDetail::GetValuesA(d) => d.encode(&mut builder.reborrow().init_get_values_a()),
+            RPCAnswerDetail::SetValuesA(d) => d.encode(&mut builder.reborrow().init_set_values_a()),
+            RPCAnswerDetail::PinValueA(d) => d.encode(&mut builder.reborrow().init_pin_value_a()),
         }
     }
 }
@@ -1,13 +1,19 @@
 mod answer;
 mod operation;
 mod operation_app_call;
+mod operation_app_call_cancel;
+mod operation_app_call_chunk;
+mod operation_app_call_credit;
 mod operation_app_message;
 mod operation_find_node;
 mod operation_get_value;
+mod operation_get_values;
 mod operation_inspect_value;
+mod operation_pin_value;
 mod operation_return_receipt;
 mod operation_route;
 mod operation_set_value;
+mod operation_set_values;
 mod operation_signal;
 mod operation_status;
 
@@ -29,17 +35,25 @@ mod operation_cancel_tunnel;
 mod operation_complete_tunnel;
 #[cfg(feature = "unstable-tunnels")]
 mod operation_start_tunnel;
+#[cfg(feature = "unstable-tunnels")]
+mod operation_tunnel_data;
 
 pub(in crate::rpc_processor) use answer::*;
 pub(in crate::rpc_processor) use operation::*;
 pub(in crate::rpc_processor) use operation_app_call::*;
+pub(in crate::rpc_processor) use operation_app_call_cancel::*;
+pub(in crate::rpc_processor) use operation_app_call_chunk::*;
+pub(in crate::rpc_processor) use operation_app_call_credit::*;
 pub(in crate::rpc_processor) use operation_app_message::*;
 pub(in crate::rpc_processor) use operation_find_node::*;
 pub(in crate::rpc_processor) use operation_get_value::*;
+pub(in crate::rpc_processor) use operation_get_values::*;
 pub(in crate::rpc_processor) use operation_inspect_value::*;
+pub(in crate::rpc_processor) use operation_pin_value::*;
 pub(in crate::rpc_processor) use operation_return_receipt::*;
 pub(in crate::rpc_processor) use operation_route::*;
 pub(in crate::rpc_processor) use operation_set_value::*;
+pub(in crate::rpc_processor) use operation_set_values::*;
 pub(in crate::rpc_processor) use operation_signal::*;
 pub(in crate::rpc_processor) use operation_status::*;
 pub(in crate::rpc_processor) use operation_validate_dial_info::*;
@@ -60,7 +74,15 @@ pub(in crate::rpc_processor) use operation_cancel_tunnel::*;
 pub(in crate::rpc_processor) use operation_complete_tunnel::*;
 #[cfg(feature = "unstable-tunnels")]
 pub(in crate::rpc_processor) use operation_start_tunnel::*;
+#[cfg(feature = "unstable-tunnels")]
+pub(in crate::rpc_processor) use operation_tunnel_data::*;
 
 use super::*;
 
+#[cfg(feature = "unstable-blockstore")]
+pub(crate) use operation_find_block::MAX_FIND_BLOCK_A_DATA_LEN;
+pub(crate) use operation_get_values::{MAX_GET_VALUES_A_DATA_LEN, MAX_GET_VALUES_Q_SUBKEYS_LEN};
 pub(crate) use operation_inspect_value::MAX_INSPECT_VALUE_A_SEQS_LEN;
+pub(crate) use operation_set_values::{MAX_SET_VALUES_Q_DATA_LEN, MAX_SET_VALUES_Q_VALUES_LEN};
+#[cfg(feature = "unstable-tunnels")]
+pub(crate) use operation_tunnel_data::MAX_TUNNEL_DATA_LEN;
@@ -6,14 +6,15 @@ const MAX_APP_CALL_A_MESSAGE_LEN: usize = 32768;
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationAppCallQ {
     message: Vec<u8>,
+    stream: bool,
 }
 
 impl RPCOperationAppCallQ {
-    pub fn new(message: Vec<u8>) -> Result<Self, RPCError> {
+    pub fn new(message: Vec<u8>, stream: bool) -> Result<Self, RPCError> {
         if message.len() > MAX_APP_CALL_Q_MESSAGE_LEN {
             return Err(RPCError::protocol("AppCallQ message too long to set"));
         }
-        Ok(Self { message })
+        Ok(Self { message, stream })
     }
     pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
         Ok(())
@@ -23,8 +24,8 @@ impl RPCOperationAppCallQ {
     //     &self.message
     // }
 
-    pub fn destructure(self) -> Vec<u8> {
-        self.message
+    pub fn destructure(self) -> (Vec<u8>, bool) {
+        (self.message, self.stream)
     }
 
     pub fn decode(reader: &veilid_capnp::operation_app_call_q::Reader) -> Result<Self, RPCError> {
@@ -34,6 +35,7 @@ impl RPCOperationAppCallQ {
         }
         Ok(Self {
             message: mr.to_vec(),
+            stream: reader.get_stream(),
         })
     }
     pub fn encode(
@@ -41,6 +43,7 @@ impl RPCOperationAppCallQ {
         builder: &mut veilid_capnp::operation_app_call_q::Builder,
     ) -> Result<(), RPCError> {
         builder.set_message(&self.message);
+        builder.set_stream(self.stream);
         Ok(())
     }
 }
@@ -50,14 +53,18 @@ impl RPCOperationAppCallQ {
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationAppCallA {
     message: Vec<u8>,
+    chunk_count: u64,
 }
 
 impl RPCOperationAppCallA {
-    pub fn new(message: Vec<u8>) -> Result<Self, RPCError> {
+    pub fn new(message: Vec<u8>, chunk_count: u64) -> Result<Self, RPCError> {
         if message.len() > MAX_APP_CALL_A_MESSAGE_LEN {
             return Err(RPCError::protocol("AppCallA message too long to set"));
         }
-        Ok(Self { message })
+        Ok(Self {
+            message,
+            chunk_count,
+        })
     }
 
     pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -68,8 +75,8 @@ impl RPCOperationAppCallA {
     //     &self.message
     // }
 
-    pub fn destructure(self) -> Vec<u8> {
-        self.message
+    pub fn destructure(self) -> (Vec<u8>, u64) {
+        (self.message, self.chunk_count)
     }
 
     pub fn decode(reader: &veilid_capnp::operation_app_call_a::Reader) -> Result<Self, RPCError> {
@@ -79,6 +86,7 @@ impl RPCOperationAppCallA {
         }
         Ok(Self {
             message: mr.to_vec(),
+            chunk_count: reader.get_chunk_count(),
         })
     }
     pub fn encode(
@@ -86,6 +94,7 @@ impl RPCOperationAppCallA {
         builder: &mut veilid_capnp::operation_app_call_a::Builder,
     ) -> Result<(), RPCError> {
         builder.set_message(&self.message);
+        builder.set_chunk_count(self.chunk_count);
         Ok(())
     }
 }
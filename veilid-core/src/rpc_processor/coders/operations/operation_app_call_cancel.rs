@@ -0,0 +1,34 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationAppCallCancel {
+    call_id: OperationId,
+}
+
+impl RPCOperationAppCallCancel {
+    pub fn new(call_id: OperationId) -> Self {
+        Self { call_id }
+    }
+
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    pub fn destructure(self) -> OperationId {
+        self.call_id
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_app_call_cancel::Reader,
+    ) -> Result<Self, RPCError> {
+        let call_id = OperationId::new(reader.get_call_id());
+        Ok(Self { call_id })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_app_call_cancel::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_call_id(self.call_id.as_u64());
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+const MAX_APP_CALL_CHUNK_MESSAGE_LEN: usize = 32768;
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationAppCallChunk {
+    call_id: OperationId,
+    seq: u64,
+    message: Vec<u8>,
+}
+
+impl RPCOperationAppCallChunk {
+    pub fn new(call_id: OperationId, seq: u64, message: Vec<u8>) -> Result<Self, RPCError> {
+        if message.len() > MAX_APP_CALL_CHUNK_MESSAGE_LEN {
+            return Err(RPCError::protocol("AppCallChunk message too long to set"));
+        }
+        Ok(Self {
+            call_id,
+            seq,
+            message,
+        })
+    }
+
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (OperationId, u64, Vec<u8>) {
+        (self.call_id, self.seq, self.message)
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_app_call_chunk::Reader,
+    ) -> Result<Self, RPCError> {
+        let call_id = OperationId::new(reader.get_call_id());
+        let seq = reader.get_seq();
+        let mr = reader.get_message().map_err(RPCError::protocol)?;
+        if mr.len() > MAX_APP_CALL_CHUNK_MESSAGE_LEN {
+            return Err(RPCError::protocol("AppCallChunk message too long to set"));
+        }
+        Ok(Self {
+            call_id,
+            seq,
+            message: mr.to_vec(),
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_app_call_chunk::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_call_id(self.call_id.as_u64());
+        builder.set_seq(self.seq);
+        builder.set_message(&self.message);
+        Ok(())
+    }
+}
+
+/// Where the reply chunks of a streaming app call are delivered while its question is waiting for an answer
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct AppCallStreamContext {
+    pub peer: AppCallStreamPeer,
+    pub chunk_sender: flume::Sender<(u64, Vec<u8>)>,
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationAppCallCredit {
+    call_id: OperationId,
+    credit: u64,
+}
+
+impl RPCOperationAppCallCredit {
+    pub fn new(call_id: OperationId, credit: u64) -> Self {
+        Self { call_id, credit }
+    }
+
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (OperationId, u64) {
+        (self.call_id, self.credit)
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_app_call_credit::Reader,
+    ) -> Result<Self, RPCError> {
+        let call_id = OperationId::new(reader.get_call_id());
+        let credit = reader.get_credit();
+        Ok(Self { call_id, credit })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_app_call_credit::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_call_id(self.call_id.as_u64());
+        builder.set_credit(self.credit);
+        Ok(())
+    }
+}
@@ -1,17 +1,18 @@
 use super::*;
 
-const MAX_FIND_BLOCK_A_DATA_LEN: usize = 32768;
+pub(crate) const MAX_FIND_BLOCK_A_DATA_LEN: usize = 32768;
 const MAX_FIND_BLOCK_A_SUPPLIERS_LEN: usize = 10;
-const MAX_FIND_BLOCK_A_PEERS_LEN: usize = 10;
+pub(in crate::rpc_processor) const MAX_FIND_BLOCK_A_PEERS_LEN: usize = 10;
 
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationFindBlockQ {
     block_id: TypedKey,
+    offset: u32,
 }
 
 impl RPCOperationFindBlockQ {
-    pub fn new(block_id: TypedKey) -> Self {
-        Self { block_id }
+    pub fn new(block_id: TypedKey, offset: u32) -> Self {
+        Self { block_id, offset }
     }
     pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
         Ok(())
@@ -20,9 +21,12 @@ impl RPCOperationFindBlockQ {
     pub fn block_id(&self) -> TypedKey {
         self.block_id
     }
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
 
-    pub fn destructure(self) -> TypedKey {
-        self.block_id
+    pub fn destructure(self) -> (TypedKey, u32) {
+        (self.block_id, self.offset)
     }
 
     pub fn decode(
@@ -30,8 +34,9 @@ impl RPCOperationFindBlockQ {
     ) -> Result<RPCOperationFindBlockQ, RPCError> {
         let bi_reader = reader.get_block_id().map_err(RPCError::protocol)?;
         let block_id = decode_typed_key(&bi_reader)?;
+        let offset = reader.get_offset();
 
-        Ok(Self { block_id })
+        Ok(Self { block_id, offset })
     }
     pub fn encode(
         &self,
@@ -39,6 +44,7 @@ impl RPCOperationFindBlockQ {
     ) -> Result<(), RPCError> {
         let mut bi_builder = builder.reborrow().init_block_id();
         encode_typed_key(&self.block_id, &mut bi_builder);
+        builder.set_offset(self.offset);
 
         Ok(())
     }
@@ -49,6 +55,7 @@ impl RPCOperationFindBlockQ {
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationFindBlockA {
     data: Vec<u8>,
+    size: u32,
     suppliers: Vec<PeerInfo>,
     peers: Vec<PeerInfo>,
 }
@@ -56,12 +63,16 @@ pub(in crate::rpc_processor) struct RPCOperationFindBlockA {
 impl RPCOperationFindBlockA {
     pub fn new(
         data: Vec<u8>,
+        size: u32,
         suppliers: Vec<PeerInfo>,
         peers: Vec<PeerInfo>,
     ) -> Result<Self, RPCError> {
         if data.len() > MAX_FIND_BLOCK_A_DATA_LEN {
             return Err(RPCError::protocol("find block data length too long"));
         }
+        if data.len() > size as usize {
+            return Err(RPCError::protocol("find block data longer than block size"));
+        }
         if suppliers.len() > MAX_FIND_BLOCK_A_SUPPLIERS_LEN {
             return Err(RPCError::protocol("find block suppliers length too long"));
         }
@@ -71,6 +82,7 @@ impl RPCOperationFindBlockA {
 
         Ok(Self {
             data,
+            size,
             suppliers,
             peers,
         })
@@ -84,6 +96,9 @@ impl RPCOperationFindBlockA {
     pub fn data(&self) -> &[u8] {
         &self.data
     }
+    pub fn size(&self) -> u32 {
+        self.size
+    }
     pub fn suppliers(&self) -> &[PeerInfo] {
         &self.suppliers
     }
@@ -91,14 +106,18 @@ impl RPCOperationFindBlockA {
         &self.peers
     }
 
-    pub fn destructure(self) -> (Vec<u8>, Vec<PeerInfo>, Vec<PeerInfo>) {
-        (self.data, self.suppliers, self.peers)
+    pub fn destructure(self) -> (Vec<u8>, u32, Vec<PeerInfo>, Vec<PeerInfo>) {
+        (self.data, self.size, self.suppliers, self.peers)
     }
     pub fn decode(reader: &veilid_capnp::operation_find_block_a::Reader) -> Result<Self, RPCError> {
         let data = reader.get_data().map_err(RPCError::protocol)?;
         if data.len() > MAX_FIND_BLOCK_A_DATA_LEN {
             return Err(RPCError::protocol("find block data length too long"));
         }
+        let size = reader.get_size();
+        if data.len() > size as usize {
+            return Err(RPCError::protocol("find block data longer than block size"));
+        }
 
         let suppliers_reader = reader.get_suppliers().map_err(RPCError::protocol)?;
         if suppliers_reader.len() as usize > MAX_FIND_BLOCK_A_SUPPLIERS_LEN {
@@ -134,6 +153,7 @@ impl RPCOperationFindBlockA {
 
         Ok(Self {
             data: data.to_vec(),
+            size,
             suppliers,
             peers,
         })
@@ -144,6 +164,7 @@ impl RPCOperationFindBlockA {
         builder: &mut veilid_capnp::operation_find_block_a::Builder,
     ) -> Result<(), RPCError> {
         builder.set_data(&self.data);
+        builder.set_size(self.size);
 
         let mut suppliers_builder = builder.reborrow().init_suppliers(
             self.suppliers
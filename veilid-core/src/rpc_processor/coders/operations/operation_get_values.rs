@@ -0,0 +1,306 @@
+use super::*;
+use crate::storage_manager::{SignedValueData, SignedValueDescriptor};
+
+const MAX_GET_VALUES_Q_SUBKEY_RANGES_LEN: usize = 512;
+pub(crate) const MAX_GET_VALUES_Q_SUBKEYS_LEN: usize = 512;
+pub(crate) const MAX_GET_VALUES_A_DATA_LEN: usize = 32768;
+const MAX_GET_VALUES_A_PEERS_LEN: usize = 20;
+
+#[derive(Clone)]
+pub(in crate::rpc_processor) struct ValidateGetValuesContext {
+    pub last_descriptor: Option<SignedValueDescriptor>,
+    pub subkeys: ValueSubkeyRangeSet,
+    pub vcrypto: CryptoSystemVersion,
+}
+
+impl fmt::Debug for ValidateGetValuesContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidateGetValuesContext")
+            .field("last_descriptor", &self.last_descriptor)
+            .field("subkeys", &self.subkeys)
+            .field("vcrypto", &self.vcrypto.kind().to_string())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationGetValuesQ {
+    key: TypedKey,
+    subkeys: ValueSubkeyRangeSet,
+    want_descriptor: bool,
+}
+
+impl RPCOperationGetValuesQ {
+    pub fn new(
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        want_descriptor: bool,
+    ) -> Result<Self, RPCError> {
+        #[allow(clippy::unnecessary_cast)]
+        if subkeys.len() as u64 > MAX_GET_VALUES_Q_SUBKEYS_LEN as u64 {
+            return Err(RPCError::protocol("encoded GetValuesQ too many subkeys"));
+        }
+        Ok(Self {
+            key,
+            subkeys,
+            want_descriptor,
+        })
+    }
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (TypedKey, ValueSubkeyRangeSet, bool) {
+        (self.key, self.subkeys, self.want_descriptor)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_get_values_q::Reader) -> Result<Self, RPCError> {
+        let k_reader = reader.reborrow().get_key().map_err(RPCError::protocol)?;
+        let key = decode_typed_key(&k_reader)?;
+        let sk_reader = reader.get_subkeys().map_err(RPCError::protocol)?;
+        if sk_reader.len() as usize > MAX_GET_VALUES_Q_SUBKEY_RANGES_LEN {
+            return Err(RPCError::protocol("GetValuesQ too many subkey ranges"));
+        }
+        let subkeys = decode_value_subkey_range_set(&sk_reader)?;
+        #[allow(clippy::unnecessary_cast)]
+        if subkeys.len() as u64 > MAX_GET_VALUES_Q_SUBKEYS_LEN as u64 {
+            return Err(RPCError::protocol("decoded GetValuesQ too many subkeys"));
+        }
+
+        let want_descriptor = reader.reborrow().get_want_descriptor();
+        Ok(Self {
+            key,
+            subkeys,
+            want_descriptor,
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_get_values_q::Builder,
+    ) -> Result<(), RPCError> {
+        let mut k_builder = builder.reborrow().init_key();
+        encode_typed_key(&self.key, &mut k_builder);
+
+        let mut sk_builder = builder.reborrow().init_subkeys(
+            self.subkeys
+                .ranges_len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid subkey range list length"))?,
+        );
+        encode_value_subkey_range_set(&self.subkeys, &mut sk_builder)?;
+        builder.set_want_descriptor(self.want_descriptor);
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationGetValuesA {
+    values: Vec<(ValueSubkey, SignedValueData)>,
+    truncated: bool,
+    peers: Vec<PeerInfo>,
+    descriptor: Option<SignedValueDescriptor>,
+}
+
+impl RPCOperationGetValuesA {
+    pub fn new(
+        values: Vec<(ValueSubkey, SignedValueData)>,
+        truncated: bool,
+        peers: Vec<PeerInfo>,
+        descriptor: Option<SignedValueDescriptor>,
+    ) -> Result<Self, RPCError> {
+        if values.len() > MAX_GET_VALUES_Q_SUBKEYS_LEN {
+            return Err(RPCError::protocol(
+                "encoded GetValuesA values length too long",
+            ));
+        }
+        if peers.len() > MAX_GET_VALUES_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "encoded GetValuesA peers length too long",
+            ));
+        }
+        Ok(Self {
+            values,
+            truncated,
+            peers,
+            descriptor,
+        })
+    }
+
+    pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        let question_context = validate_context
+            .question_context
+            .as_ref()
+            .expect("GetValuesA requires question context");
+        let QuestionContext::GetValues(get_values_context) = question_context else {
+            panic!("Wrong context type for GetValuesA");
+        };
+
+        // Validate descriptor
+        if let Some(descriptor) = &self.descriptor {
+            // Ensure the descriptor itself validates
+            descriptor
+                .validate(get_values_context.vcrypto.clone())
+                .map_err(RPCError::protocol)?;
+
+            // Ensure descriptor matches last one
+            if let Some(last_descriptor) = &get_values_context.last_descriptor {
+                if descriptor.cmp_no_sig(last_descriptor) != cmp::Ordering::Equal {
+                    return Err(RPCError::protocol(
+                        "GetValues descriptor does not match last descriptor",
+                    ));
+                }
+            }
+        }
+
+        // A truncated answer must make progress
+        if self.truncated && self.values.is_empty() {
+            return Err(RPCError::protocol("GetValues truncated with no values"));
+        }
+
+        // Ensure the values validate
+        let mut last_subkey: Option<ValueSubkey> = None;
+        for (subkey, value) in &self.values {
+            // Values must be for requested subkeys in ascending order
+            if !get_values_context.subkeys.contains(*subkey) {
+                return Err(RPCError::protocol("GetValues value for unrequested subkey"));
+            }
+            if let Some(last_subkey) = last_subkey {
+                if *subkey <= last_subkey {
+                    return Err(RPCError::protocol("GetValues values out of order"));
+                }
+            }
+            last_subkey = Some(*subkey);
+
+            // Get descriptor to validate with
+            let Some(descriptor) = self
+                .descriptor
+                .as_ref()
+                .or(get_values_context.last_descriptor.as_ref())
+            else {
+                return Err(RPCError::protocol(
+                    "no last descriptor, requires a descriptor",
+                ));
+            };
+
+            // And the signed value data
+            if !value
+                .validate(
+                    descriptor.owner(),
+                    *subkey,
+                    get_values_context.vcrypto.clone(),
+                )
+                .map_err(RPCError::protocol)?
+            {
+                return Err(RPCError::protocol("signed value data did not validate"));
+            }
+        }
+
+        PeerInfo::validate_vec(&mut self.peers, validate_context.crypto.clone());
+        Ok(())
+    }
+
+    pub fn destructure(
+        self,
+    ) -> (
+        Vec<(ValueSubkey, SignedValueData)>,
+        bool,
+        Vec<PeerInfo>,
+        Option<SignedValueDescriptor>,
+    ) {
+        (self.values, self.truncated, self.peers, self.descriptor)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_get_values_a::Reader) -> Result<Self, RPCError> {
+        let values_reader = reader.get_values().map_err(RPCError::protocol)?;
+        if values_reader.len() as usize > MAX_GET_VALUES_Q_SUBKEYS_LEN {
+            return Err(RPCError::protocol(
+                "decoded GetValuesA values length too long",
+            ));
+        }
+        let mut values = Vec::<(ValueSubkey, SignedValueData)>::with_capacity(
+            values_reader
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("too many values"))?,
+        );
+        for v in values_reader.iter() {
+            let subkey = v.get_subkey();
+            let value_reader = v.get_value().map_err(RPCError::protocol)?;
+            let value = decode_signed_value_data(&value_reader)?;
+            values.push((subkey, value));
+        }
+
+        let truncated = reader.get_truncated();
+
+        let peers_reader = reader.get_peers().map_err(RPCError::protocol)?;
+        if peers_reader.len() as usize > MAX_GET_VALUES_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "decoded GetValuesA peers length too long",
+            ));
+        }
+        let mut peers = Vec::<PeerInfo>::with_capacity(
+            peers_reader
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("too many peers"))?,
+        );
+        for p in peers_reader.iter() {
+            let peer_info = decode_peer_info(&p)?;
+            peers.push(peer_info);
+        }
+
+        let descriptor = if reader.has_descriptor() {
+            let d_reader = reader.get_descriptor().map_err(RPCError::protocol)?;
+            let descriptor = decode_signed_value_descriptor(&d_reader)?;
+            Some(descriptor)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            values,
+            truncated,
+            peers,
+            descriptor,
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_get_values_a::Builder,
+    ) -> Result<(), RPCError> {
+        let mut values_builder = builder.reborrow().init_values(
+            self.values
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid values list length"))?,
+        );
+        for (i, (subkey, value)) in self.values.iter().enumerate() {
+            let mut sv_builder = values_builder.reborrow().get(i as u32);
+            sv_builder.set_subkey(*subkey);
+            let mut v_builder = sv_builder.init_value();
+            encode_signed_value_data(value, &mut v_builder)?;
+        }
+
+        builder.set_truncated(self.truncated);
+
+        let mut peers_builder = builder.reborrow().init_peers(
+            self.peers
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid peers list length"))?,
+        );
+        for (i, peer) in self.peers.iter().enumerate() {
+            let mut pi_builder = peers_builder.reborrow().get(i as u32);
+            encode_peer_info(peer, &mut pi_builder)?;
+        }
+
+        if let Some(descriptor) = &self.descriptor {
+            let mut d_builder = builder.reborrow().init_descriptor();
+            encode_signed_value_descriptor(descriptor, &mut d_builder)?;
+        }
+
+        Ok(())
+    }
+}
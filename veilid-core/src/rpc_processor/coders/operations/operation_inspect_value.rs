@@ -67,21 +67,7 @@ impl RPCOperationInspectValueQ {
         if sk_reader.len() as usize > MAX_INSPECT_VALUE_Q_SUBKEY_RANGES_LEN {
             return Err(RPCError::protocol("InspectValueQ too many subkey ranges"));
         }
-        let mut subkeys = ValueSubkeyRangeSet::new();
-        for skr in sk_reader.iter() {
-            let vskr = (skr.get_start(), skr.get_end());
-            if vskr.0 > vskr.1 {
-                return Err(RPCError::protocol("invalid subkey range"));
-            }
-            if let Some(lvskr) = subkeys.last() {
-                if lvskr >= vskr.0 {
-                    return Err(RPCError::protocol(
-                        "subkey range out of order or not merged",
-                    ));
-                }
-            }
-            subkeys.ranges_insert(vskr.0..=vskr.1);
-        }
+        let subkeys = decode_value_subkey_range_set(&sk_reader)?;
 
         let want_descriptor = reader.reborrow().get_want_descriptor();
         Ok(Self {
@@ -103,11 +89,7 @@ impl RPCOperationInspectValueQ {
                 .try_into()
                 .map_err(RPCError::map_internal("invalid subkey range list length"))?,
         );
-        for (i, skr) in self.subkeys.ranges().enumerate() {
-            let mut skr_builder = sk_builder.reborrow().get(i as u32);
-            skr_builder.set_start(*skr.start());
-            skr_builder.set_end(*skr.end());
-        }
+        encode_value_subkey_range_set(&self.subkeys, &mut sk_builder)?;
         builder.set_want_descriptor(self.want_descriptor);
         Ok(())
     }
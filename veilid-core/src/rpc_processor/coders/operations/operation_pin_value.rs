@@ -0,0 +1,200 @@
+use super::*;
+use crate::storage_manager::SignedValueDescriptor;
+
+const MAX_PIN_VALUE_A_PEERS_LEN: usize = 20;
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationPinValueQ {
+    key: TypedKey,
+    descriptor: SignedValueDescriptor,
+    timestamp: u64,
+    expiration: u64,
+    signature: Signature,
+}
+
+impl RPCOperationPinValueQ {
+    pub fn new(
+        key: TypedKey,
+        descriptor: SignedValueDescriptor,
+        timestamp: u64,
+        expiration: u64,
+        owner: KeyPair,
+        vcrypto: CryptoSystemVersion,
+    ) -> Result<Self, RPCError> {
+        if owner.key != *descriptor.owner() {
+            return Err(RPCError::internal("PinValueQ must be signed by the owner"));
+        }
+
+        let signature_data = Self::make_signature_data(&key, timestamp, expiration);
+        let signature = vcrypto
+            .sign(&owner.key, &owner.secret, &signature_data)
+            .map_err(RPCError::protocol)?;
+
+        Ok(Self {
+            key,
+            descriptor,
+            timestamp,
+            expiration,
+            signature,
+        })
+    }
+
+    // signature covers: key, timestamp, expiration, using owner key
+    fn make_signature_data(key: &TypedKey, timestamp: u64, expiration: u64) -> Vec<u8> {
+        let mut sig_data = Vec::with_capacity(PUBLIC_KEY_LENGTH + 4 + 8 + 8);
+        sig_data.extend_from_slice(&key.kind.0);
+        sig_data.extend_from_slice(&key.value.bytes);
+        sig_data.extend_from_slice(&timestamp.to_le_bytes());
+        sig_data.extend_from_slice(&expiration.to_le_bytes());
+        sig_data
+    }
+
+    pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        let Some(vcrypto) = validate_context.crypto.get(self.key.kind) else {
+            return Err(RPCError::protocol("unsupported cryptosystem"));
+        };
+
+        // Ensure the descriptor itself validates
+        self.descriptor
+            .validate(vcrypto.clone())
+            .map_err(RPCError::protocol)?;
+
+        // Only the owner of the record may pin it
+        let sig_data = Self::make_signature_data(&self.key, self.timestamp, self.expiration);
+        if !vcrypto
+            .verify(self.descriptor.owner(), &sig_data, &self.signature)
+            .map_err(RPCError::protocol)?
+        {
+            return Err(RPCError::protocol("failed to validate owner signature"));
+        }
+
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (TypedKey, SignedValueDescriptor, u64, u64) {
+        (self.key, self.descriptor, self.timestamp, self.expiration)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_pin_value_q::Reader) -> Result<Self, RPCError> {
+        let k_reader = reader.get_key().map_err(RPCError::protocol)?;
+        let key = decode_typed_key(&k_reader)?;
+
+        let d_reader = reader.get_descriptor().map_err(RPCError::protocol)?;
+        let descriptor = decode_signed_value_descriptor(&d_reader)?;
+
+        let timestamp = reader.get_timestamp();
+        let expiration = reader.get_expiration();
+
+        let s_reader = reader.get_signature().map_err(RPCError::protocol)?;
+        let signature = decode_signature512(&s_reader);
+
+        Ok(Self {
+            key,
+            descriptor,
+            timestamp,
+            expiration,
+            signature,
+        })
+    }
+
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_pin_value_q::Builder,
+    ) -> Result<(), RPCError> {
+        let mut k_builder = builder.reborrow().init_key();
+        encode_typed_key(&self.key, &mut k_builder);
+
+        let mut d_builder = builder.reborrow().init_descriptor();
+        encode_signed_value_descriptor(&self.descriptor, &mut d_builder)?;
+
+        builder.set_timestamp(self.timestamp);
+        builder.set_expiration(self.expiration);
+
+        let mut s_builder = builder.reborrow().init_signature();
+        encode_signature512(&self.signature, &mut s_builder);
+
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationPinValueA {
+    accepted: bool,
+    expiration: u64,
+    peers: Vec<PeerInfo>,
+}
+
+impl RPCOperationPinValueA {
+    pub fn new(accepted: bool, expiration: u64, peers: Vec<PeerInfo>) -> Result<Self, RPCError> {
+        if peers.len() > MAX_PIN_VALUE_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "encoded PinValueA peers length too long",
+            ));
+        }
+        Ok(Self {
+            accepted,
+            expiration,
+            peers,
+        })
+    }
+
+    pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        PeerInfo::validate_vec(&mut self.peers, validate_context.crypto.clone());
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (bool, u64, Vec<PeerInfo>) {
+        (self.accepted, self.expiration, self.peers)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_pin_value_a::Reader) -> Result<Self, RPCError> {
+        let accepted = reader.get_accepted();
+        let expiration = reader.get_expiration();
+
+        let peers_reader = reader.get_peers().map_err(RPCError::protocol)?;
+        if peers_reader.len() as usize > MAX_PIN_VALUE_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "decoded PinValueA peers length too long",
+            ));
+        }
+        let mut peers = Vec::<PeerInfo>::with_capacity(
+            peers_reader
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("too many peers"))?,
+        );
+        for p in peers_reader.iter() {
+            let peer_info = decode_peer_info(&p)?;
+            peers.push(peer_info);
+        }
+
+        Ok(Self {
+            accepted,
+            expiration,
+            peers,
+        })
+    }
+
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_pin_value_a::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_accepted(self.accepted);
+        builder.set_expiration(self.expiration);
+
+        let mut peers_builder = builder.reborrow().init_peers(
+            self.peers
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid peers list length"))?,
+        );
+        for (i, peer) in self.peers.iter().enumerate() {
+            let mut pi_builder = peers_builder.reborrow().get(i as u32);
+            encode_peer_info(peer, &mut pi_builder)?;
+        }
+
+        Ok(())
+    }
+}
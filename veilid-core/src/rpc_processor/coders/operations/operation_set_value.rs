@@ -26,6 +26,7 @@ pub(in crate::rpc_processor) struct RPCOperationSetValueQ {
     subkey: ValueSubkey,
     value: SignedValueData,
     descriptor: Option<SignedValueDescriptor>,
+    expect_seq: bool,
 }
 
 impl RPCOperationSetValueQ {
@@ -34,12 +35,14 @@ impl RPCOperationSetValueQ {
         subkey: ValueSubkey,
         value: SignedValueData,
         descriptor: Option<SignedValueDescriptor>,
+        expect_seq: bool,
     ) -> Self {
         Self {
             key,
             subkey,
             value,
             descriptor,
+            expect_seq,
         }
     }
     pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -61,6 +64,10 @@ impl RPCOperationSetValueQ {
     // pub fn descriptor(&self) -> Option<&SignedValueDescriptor> {
     //     self.descriptor.as_ref()
     // }
+
+    // pub fn expect_seq(&self) -> bool {
+    //     self.expect_seq
+    // }
     pub fn destructure(
         self,
     ) -> (
@@ -68,8 +75,15 @@ impl RPCOperationSetValueQ {
         ValueSubkey,
         SignedValueData,
         Option<SignedValueDescriptor>,
+        bool,
     ) {
-        (self.key, self.subkey, self.value, self.descriptor)
+        (
+            self.key,
+            self.subkey,
+            self.value,
+            self.descriptor,
+            self.expect_seq,
+        )
     }
 
     pub fn decode(reader: &veilid_capnp::operation_set_value_q::Reader) -> Result<Self, RPCError> {
@@ -85,11 +99,13 @@ impl RPCOperationSetValueQ {
         } else {
             None
         };
+        let expect_seq = reader.get_expect_seq();
         Ok(Self {
             key,
             subkey,
             value,
             descriptor,
+            expect_seq,
         })
     }
     pub fn encode(
@@ -105,6 +121,7 @@ impl RPCOperationSetValueQ {
             let mut d_builder = builder.reborrow().init_descriptor();
             encode_signed_value_descriptor(descriptor, &mut d_builder)?;
         }
+        builder.set_expect_seq(self.expect_seq);
         Ok(())
     }
 }
@@ -0,0 +1,250 @@
+use super::*;
+use crate::storage_manager::{SignedValueData, SignedValueDescriptor};
+
+pub(crate) const MAX_SET_VALUES_Q_VALUES_LEN: usize = 512;
+pub(crate) const MAX_SET_VALUES_Q_DATA_LEN: usize = 32768;
+const MAX_SET_VALUES_A_PEERS_LEN: usize = 20;
+
+#[derive(Clone)]
+pub(in crate::rpc_processor) struct ValidateSetValuesContext {
+    pub values_len: usize,
+}
+
+impl fmt::Debug for ValidateSetValuesContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidateSetValuesContext")
+            .field("values_len", &self.values_len)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationSetValuesQ {
+    key: TypedKey,
+    values: Vec<(ValueSubkey, SignedValueData)>,
+    descriptor: Option<SignedValueDescriptor>,
+}
+
+impl RPCOperationSetValuesQ {
+    pub fn new(
+        key: TypedKey,
+        values: Vec<(ValueSubkey, SignedValueData)>,
+        descriptor: Option<SignedValueDescriptor>,
+    ) -> Result<Self, RPCError> {
+        if values.len() > MAX_SET_VALUES_Q_VALUES_LEN {
+            return Err(RPCError::protocol(
+                "encoded SetValuesQ values length too long",
+            ));
+        }
+        Ok(Self {
+            key,
+            values,
+            descriptor,
+        })
+    }
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        // Values must be in ascending subkey order
+        let mut last_subkey: Option<ValueSubkey> = None;
+        for (subkey, _value) in &self.values {
+            if let Some(last_subkey) = last_subkey {
+                if *subkey <= last_subkey {
+                    return Err(RPCError::protocol("SetValuesQ values out of order"));
+                }
+            }
+            last_subkey = Some(*subkey);
+        }
+        Ok(())
+    }
+
+    pub fn destructure(
+        self,
+    ) -> (
+        TypedKey,
+        Vec<(ValueSubkey, SignedValueData)>,
+        Option<SignedValueDescriptor>,
+    ) {
+        (self.key, self.values, self.descriptor)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_set_values_q::Reader) -> Result<Self, RPCError> {
+        let k_reader = reader.get_key().map_err(RPCError::protocol)?;
+        let key = decode_typed_key(&k_reader)?;
+
+        let values_reader = reader.get_values().map_err(RPCError::protocol)?;
+        if values_reader.len() as usize > MAX_SET_VALUES_Q_VALUES_LEN {
+            return Err(RPCError::protocol(
+                "decoded SetValuesQ values length too long",
+            ));
+        }
+        let mut values = Vec::<(ValueSubkey, SignedValueData)>::with_capacity(
+            values_reader
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("too many values"))?,
+        );
+        for v in values_reader.iter() {
+            let subkey = v.get_subkey();
+            let value_reader = v.get_value().map_err(RPCError::protocol)?;
+            let value = decode_signed_value_data(&value_reader)?;
+            values.push((subkey, value));
+        }
+
+        let descriptor = if reader.has_descriptor() {
+            let d_reader = reader.get_descriptor().map_err(RPCError::protocol)?;
+            let descriptor = decode_signed_value_descriptor(&d_reader)?;
+            Some(descriptor)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            key,
+            values,
+            descriptor,
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_set_values_q::Builder,
+    ) -> Result<(), RPCError> {
+        let mut k_builder = builder.reborrow().init_key();
+        encode_typed_key(&self.key, &mut k_builder);
+
+        let mut values_builder = builder.reborrow().init_values(
+            self.values
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid values list length"))?,
+        );
+        for (i, (subkey, value)) in self.values.iter().enumerate() {
+            let mut sv_builder = values_builder.reborrow().get(i as u32);
+            sv_builder.set_subkey(*subkey);
+            let mut v_builder = sv_builder.init_value();
+            encode_signed_value_data(value, &mut v_builder)?;
+        }
+
+        if let Some(descriptor) = &self.descriptor {
+            let mut d_builder = builder.reborrow().init_descriptor();
+            encode_signed_value_descriptor(descriptor, &mut d_builder)?;
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationSetValuesA {
+    set: bool,
+    seqs: Vec<ValueSeqNum>,
+    peers: Vec<PeerInfo>,
+}
+
+impl RPCOperationSetValuesA {
+    pub fn new(set: bool, seqs: Vec<ValueSeqNum>, peers: Vec<PeerInfo>) -> Result<Self, RPCError> {
+        if seqs.len() > MAX_SET_VALUES_Q_VALUES_LEN {
+            return Err(RPCError::protocol(
+                "encoded SetValuesA seqs length too long",
+            ));
+        }
+        if peers.len() > MAX_SET_VALUES_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "encoded SetValuesA peers length too long",
+            ));
+        }
+        Ok(Self { set, seqs, peers })
+    }
+
+    pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        let question_context = validate_context
+            .question_context
+            .as_ref()
+            .expect("SetValuesA requires question context");
+        let QuestionContext::SetValues(set_values_context) = question_context else {
+            panic!("Wrong context type for SetValuesA");
+        };
+
+        // A node that accepted the set reports a sequence number for every value sent
+        let expected_len = if self.set {
+            set_values_context.values_len
+        } else {
+            0
+        };
+        if self.seqs.len() != expected_len {
+            return Err(RPCError::protocol(format!(
+                "SetValues seqs length does not match values sent: {} != {}",
+                self.seqs.len(),
+                expected_len
+            )));
+        }
+
+        PeerInfo::validate_vec(&mut self.peers, validate_context.crypto.clone());
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (bool, Vec<ValueSeqNum>, Vec<PeerInfo>) {
+        (self.set, self.seqs, self.peers)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_set_values_a::Reader) -> Result<Self, RPCError> {
+        let set = reader.get_set();
+
+        let seqs_reader = reader.get_seqs().map_err(RPCError::protocol)?;
+        if seqs_reader.len() as usize > MAX_SET_VALUES_Q_VALUES_LEN {
+            return Err(RPCError::protocol(
+                "decoded SetValuesA seqs length too long",
+            ));
+        }
+        let Some(seqs) = seqs_reader.as_slice().map(|s| s.to_vec()) else {
+            return Err(RPCError::protocol("invalid decoded SetValuesA seqs"));
+        };
+
+        let peers_reader = reader.get_peers().map_err(RPCError::protocol)?;
+        if peers_reader.len() as usize > MAX_SET_VALUES_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "decoded SetValuesA peers length too long",
+            ));
+        }
+        let mut peers = Vec::<PeerInfo>::with_capacity(
+            peers_reader
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("too many peers"))?,
+        );
+        for p in peers_reader.iter() {
+            let peer_info = decode_peer_info(&p)?;
+            peers.push(peer_info);
+        }
+
+        Ok(Self { set, seqs, peers })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_set_values_a::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_set(self.set);
+
+        let mut seqs_builder = builder.reborrow().init_seqs(
+            self.seqs
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid seqs list length"))?,
+        );
+        for (i, seq) in self.seqs.iter().enumerate() {
+            seqs_builder.set(i as u32, *seq);
+        }
+
+        let mut peers_builder = builder.reborrow().init_peers(
+            self.peers
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid peers list length"))?,
+        );
+        for (i, peer) in self.peers.iter().enumerate() {
+            let mut pi_builder = peers_builder.reborrow().get(i as u32);
+            encode_peer_info(peer, &mut pi_builder)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-const MAX_SUPPLY_BLOCK_A_PEERS_LEN: usize = 20;
+pub(in crate::rpc_processor) const MAX_SUPPLY_BLOCK_A_PEERS_LEN: usize = 20;
 
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationSupplyBlockQ {
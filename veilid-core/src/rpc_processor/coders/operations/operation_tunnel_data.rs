@@ -0,0 +1,50 @@
+use super::*;
+
+pub(crate) const MAX_TUNNEL_DATA_LEN: usize = 32768;
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationTunnelData {
+    id: TunnelId,
+    seq: u64,
+    data: Vec<u8>,
+}
+
+impl RPCOperationTunnelData {
+    pub fn new(id: TunnelId, seq: u64, data: Vec<u8>) -> Result<Self, RPCError> {
+        if data.len() > MAX_TUNNEL_DATA_LEN {
+            return Err(RPCError::protocol("TunnelData data too long to set"));
+        }
+        Ok(Self { id, seq, data })
+    }
+
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    pub fn destructure(self) -> (TunnelId, u64, Vec<u8>) {
+        (self.id, self.seq, self.data)
+    }
+
+    pub fn decode(reader: &veilid_capnp::operation_tunnel_data::Reader) -> Result<Self, RPCError> {
+        let id = TunnelId::new(reader.get_id());
+        let seq = reader.get_seq();
+        let dr = reader.get_data().map_err(RPCError::protocol)?;
+        if dr.len() > MAX_TUNNEL_DATA_LEN {
+            return Err(RPCError::protocol("TunnelData data too long to set"));
+        }
+        Ok(Self {
+            id,
+            seq,
+            data: dr.to_vec(),
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_tunnel_data::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_id(self.id.as_u64());
+        builder.set_seq(self.seq);
+        builder.set_data(&self.data);
+        Ok(())
+    }
+}
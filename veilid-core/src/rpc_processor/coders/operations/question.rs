@@ -61,6 +61,9 @@ pub(in crate::rpc_processor) enum RPCQuestionDetail {
     CompleteTunnelQ(Box<RPCOperationCompleteTunnelQ>),
     #[cfg(feature = "unstable-tunnels")]
     CancelTunnelQ(Box<RPCOperationCancelTunnelQ>),
+    GetValuesQ(Box<RPCOperationGetValuesQ>),
+    SetValuesQ(Box<RPCOperationSetValuesQ>),
+    PinValueQ(Box<RPCOperationPinValueQ>),
 }
 
 impl RPCQuestionDetail {
@@ -84,6 +87,9 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::CompleteTunnelQ(_) => "CompleteTunnelQ",
             #[cfg(feature = "unstable-tunnels")]
             RPCQuestionDetail::CancelTunnelQ(_) => "CancelTunnelQ",
+            RPCQuestionDetail::GetValuesQ(_) => "GetValuesQ",
+            RPCQuestionDetail::SetValuesQ(_) => "SetValuesQ",
+            RPCQuestionDetail::PinValueQ(_) => "PinValueQ",
         }
     }
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -105,6 +111,9 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::CompleteTunnelQ(r) => r.validate(validate_context),
             #[cfg(feature = "unstable-tunnels")]
             RPCQuestionDetail::CancelTunnelQ(r) => r.validate(validate_context),
+            RPCQuestionDetail::GetValuesQ(r) => r.validate(validate_context),
+            RPCQuestionDetail::SetValuesQ(r) => r.validate(validate_context),
+            RPCQuestionDetail::PinValueQ(r) => r.validate(validate_context),
         }
     }
 
@@ -148,6 +157,11 @@ impl RPCQuestionDetail {
                 let out = RPCOperationInspectValueQ::decode(&op_reader)?;
                 RPCQuestionDetail::InspectValueQ(Box::new(out))
             }
+            #[cfg(not(feature = "unstable-blockstore"))]
+            veilid_capnp::question::detail::SupplyBlockQ(_)
+            | veilid_capnp::question::detail::FindBlockQ(_) => {
+                return Err(RPCError::ignore("blockstore is not enabled"));
+            }
             #[cfg(feature = "unstable-blockstore")]
             veilid_capnp::question::detail::SupplyBlockQ(r) => {
                 let op_reader = r.map_err(RPCError::protocol)?;
@@ -160,6 +174,12 @@ impl RPCQuestionDetail {
                 let out = RPCOperationFindBlockQ::decode(&op_reader)?;
                 RPCQuestionDetail::FindBlockQ(Box::new(out))
             }
+            #[cfg(not(feature = "unstable-tunnels"))]
+            veilid_capnp::question::detail::StartTunnelQ(_)
+            | veilid_capnp::question::detail::CompleteTunnelQ(_)
+            | veilid_capnp::question::detail::CancelTunnelQ(_) => {
+                return Err(RPCError::ignore("tunnels are not enabled"));
+            }
             #[cfg(feature = "unstable-tunnels")]
             veilid_capnp::question::detail::StartTunnelQ(r) => {
                 let op_reader = r.map_err(RPCError::protocol)?;
@@ -178,6 +198,21 @@ impl RPCQuestionDetail {
                 let out = RPCOperationCancelTunnelQ::decode(&op_reader)?;
                 RPCQuestionDetail::CancelTunnelQ(Box::new(out))
             }
+            veilid_capnp::question::detail::GetValuesQ(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationGetValuesQ::decode(&op_reader)?;
+                RPCQuestionDetail::GetValuesQ(Box::new(out))
+            }
+            veilid_capnp::question::detail::SetValuesQ(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationSetValuesQ::decode(&op_reader)?;
+                RPCQuestionDetail::SetValuesQ(Box::new(out))
+            }
+            veilid_capnp::question::detail::PinValueQ(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationPinValueQ::decode(&op_reader)?;
+                RPCQuestionDetail::PinValueQ(Box::new(out))
+            }
         };
         Ok(out)
     }
@@ -217,6 +252,13 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::CancelTunnelQ(d) => {
                 d.encode(&mut builder.reborrow().init_cancel_tunnel_q())
             }
+            RPCQuestionDetail::GetValuesQ(d) => {
+                d.encode(&mut builder.reborrow().init_get_values_q())
+            }
+            RPCQuestionDetail::SetValuesQ(d) => {
+                d.encode(&mut builder.reborrow().init_set_values_q())
+            }
+            RPCQuestionDetail::PinValueQ(d) => d.encode(&mut builder.reborrow().init_pin_value_q()),
         }
     }
 }
@@ -41,6 +41,11 @@ pub(in crate::rpc_processor) enum RPCStatementDetail {
     Signal(Box<RPCOperationSignal>),
     ReturnReceipt(Box<RPCOperationReturnReceipt>),
     AppMessage(Box<RPCOperationAppMessage>),
+    #[cfg(feature = "unstable-tunnels")]
+    TunnelData(Box<RPCOperationTunnelData>),
+    AppCallChunk(Box<RPCOperationAppCallChunk>),
+    AppCallCancel(Box<RPCOperationAppCallCancel>),
+    AppCallCredit(Box<RPCOperationAppCallCredit>),
 }
 
 impl RPCStatementDetail {
@@ -53,6 +58,11 @@ impl RPCStatementDetail {
             RPCStatementDetail::Signal(_) => "Signal",
             RPCStatementDetail::ReturnReceipt(_) => "ReturnReceipt",
             RPCStatementDetail::AppMessage(_) => "AppMessage",
+            #[cfg(feature = "unstable-tunnels")]
+            RPCStatementDetail::TunnelData(_) => "TunnelData",
+            RPCStatementDetail::AppCallChunk(_) => "AppCallChunk",
+            RPCStatementDetail::AppCallCancel(_) => "AppCallCancel",
+            RPCStatementDetail::AppCallCredit(_) => "AppCallCredit",
         }
     }
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -63,6 +73,11 @@ impl RPCStatementDetail {
             RPCStatementDetail::Signal(r) => r.validate(validate_context),
             RPCStatementDetail::ReturnReceipt(r) => r.validate(validate_context),
             RPCStatementDetail::AppMessage(r) => r.validate(validate_context),
+            #[cfg(feature = "unstable-tunnels")]
+            RPCStatementDetail::TunnelData(r) => r.validate(validate_context),
+            RPCStatementDetail::AppCallChunk(r) => r.validate(validate_context),
+            RPCStatementDetail::AppCallCancel(r) => r.validate(validate_context),
+            RPCStatementDetail::AppCallCredit(r) => r.validate(validate_context),
         }
     }
     pub fn decode(
@@ -100,6 +115,31 @@ impl RPCStatementDetail {
                 let out = RPCOperationAppMessage::decode(&op_reader)?;
                 RPCStatementDetail::AppMessage(Box::new(out))
             }
+            #[cfg(not(feature = "unstable-tunnels"))]
+            veilid_capnp::statement::detail::TunnelData(_) => {
+                return Err(RPCError::ignore("tunnels are not enabled"));
+            }
+            #[cfg(feature = "unstable-tunnels")]
+            veilid_capnp::statement::detail::TunnelData(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationTunnelData::decode(&op_reader)?;
+                RPCStatementDetail::TunnelData(Box::new(out))
+            }
+            veilid_capnp::statement::detail::AppCallChunk(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationAppCallChunk::decode(&op_reader)?;
+                RPCStatementDetail::AppCallChunk(Box::new(out))
+            }
+            veilid_capnp::statement::detail::AppCallCancel(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationAppCallCancel::decode(&op_reader)?;
+                RPCStatementDetail::AppCallCancel(Box::new(out))
+            }
+            veilid_capnp::statement::detail::AppCallCredit(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationAppCallCredit::decode(&op_reader)?;
+                RPCStatementDetail::AppCallCredit(Box::new(out))
+            }
         };
         Ok(out)
     }
@@ -122,6 +162,19 @@ impl RPCStatementDetail {
             RPCStatementDetail::AppMessage(d) => {
                 d.encode(&mut builder.reborrow().init_app_message())
             }
+            #[cfg(feature = "unstable-tunnels")]
+            RPCStatementDetail::TunnelData(d) => {
+                d.encode(&mut builder.reborrow().init_tunnel_data())
+            }
+            RPCStatementDetail::AppCallChunk(d) => {
+                d.encode(&mut builder.reborrow().init_app_call_chunk())
+            }
+            RPCStatementDetail::AppCallCancel(d) => {
+                d.encode(&mut builder.reborrow().init_app_call_cancel())
+            }
+            RPCStatementDetail::AppCallCredit(d) => {
+                d.encode(&mut builder.reborrow().init_app_call_credit())
+            }
         }
     }
 }
@@ -8,6 +8,7 @@ pub fn encode_protocol_type_set(
     builder.set_tcp(protocol_type_set.contains(ProtocolType::TCP));
     builder.set_ws(protocol_type_set.contains(ProtocolType::WS));
     builder.set_wss(protocol_type_set.contains(ProtocolType::WSS));
+    builder.set_wrtc(protocol_type_set.contains(ProtocolType::WRTC));
 
     Ok(())
 }
@@ -28,5 +29,8 @@ pub fn decode_protocol_type_set(
     if reader.reborrow().get_wss() {
         out.insert(ProtocolType::WSS);
     }
+    if reader.reborrow().get_wrtc() {
+        out.insert(ProtocolType::WRTC);
+    }
     Ok(out)
 }
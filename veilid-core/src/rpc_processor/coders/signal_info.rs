@@ -5,8 +5,21 @@ pub fn encode_signal_info(
     builder: &mut veilid_capnp::operation_signal::Builder,
 ) -> Result<(), RPCError> {
     match signal_info {
-        SignalInfo::HolePunch { receipt, peer_info } => {
+        SignalInfo::HolePunch {
+            receipt,
+            peer_info,
+            protocol_type,
+        } => {
             let mut hp_builder = builder.reborrow().init_hole_punch();
+            hp_builder.set_protocol_kind(match protocol_type {
+                ProtocolType::UDP => veilid_capnp::ProtocolKind::Udp,
+                ProtocolType::TCP => veilid_capnp::ProtocolKind::Tcp,
+                _ => {
+                    return Err(RPCError::protocol(
+                        "invalid hole punch protocol in encode_signal_info",
+                    ))
+                }
+            });
             let r_builder = hp_builder
                 .reborrow()
                 .init_receipt(receipt.len().try_into().map_err(RPCError::map_protocol(
@@ -27,6 +40,42 @@ pub fn encode_signal_info(
             let mut pi_builder = rc_builder.init_peer_info();
             encode_peer_info(peer_info, &mut pi_builder)?;
         }
+        SignalInfo::WebRTCOffer {
+            receipt,
+            sdp,
+            peer_info,
+        } => {
+            let mut wo_builder = builder.reborrow().init_webrtc_offer();
+            let r_builder = wo_builder
+                .reborrow()
+                .init_receipt(receipt.len().try_into().map_err(RPCError::map_protocol(
+                    "invalid receipt length in encode_signal_info",
+                ))?);
+            r_builder.copy_from_slice(receipt);
+            let mut sdp_builder = wo_builder.reborrow().init_sdp(
+                sdp.len()
+                    .try_into()
+                    .map_err(RPCError::map_protocol("sdp too long"))?,
+            );
+            sdp_builder.push_str(sdp.as_str());
+            let mut pi_builder = wo_builder.init_peer_info();
+            encode_peer_info(peer_info, &mut pi_builder)?;
+        }
+        SignalInfo::WebRTCAnswer { receipt, sdp } => {
+            let mut wa_builder = builder.reborrow().init_webrtc_answer();
+            let r_builder = wa_builder
+                .reborrow()
+                .init_receipt(receipt.len().try_into().map_err(RPCError::map_protocol(
+                    "invalid receipt length in encode_signal_info",
+                ))?);
+            r_builder.copy_from_slice(receipt);
+            let mut sdp_builder = wa_builder.init_sdp(
+                sdp.len()
+                    .try_into()
+                    .map_err(RPCError::map_protocol("sdp too long"))?,
+            );
+            sdp_builder.push_str(sdp.as_str());
+        }
     }
 
     Ok(())
@@ -53,8 +102,23 @@ pub fn decode_signal_info(
                     "invalid peer info in hole punch signal info",
                 ))?;
                 let peer_info = decode_peer_info(&pi_reader)?;
+                let protocol_type = match r.get_protocol_kind().map_err(RPCError::map_protocol(
+                    "invalid protocol in hole punch signal info",
+                ))? {
+                    veilid_capnp::ProtocolKind::Udp => ProtocolType::UDP,
+                    veilid_capnp::ProtocolKind::Tcp => ProtocolType::TCP,
+                    _ => {
+                        return Err(RPCError::protocol(
+                            "unsupported protocol in hole punch signal info",
+                        ))
+                    }
+                };
 
-                SignalInfo::HolePunch { receipt, peer_info }
+                SignalInfo::HolePunch {
+                    receipt,
+                    peer_info,
+                    protocol_type,
+                }
             }
             veilid_capnp::operation_signal::ReverseConnect(r) => {
                 // Extract reverse connect reader
@@ -72,6 +136,52 @@ pub fn decode_signal_info(
 
                 SignalInfo::ReverseConnect { receipt, peer_info }
             }
+            veilid_capnp::operation_signal::WebrtcOffer(r) => {
+                // Extract webrtc offer reader
+                let r = r.map_err(RPCError::protocol)?;
+                let receipt = r
+                    .get_receipt()
+                    .map_err(RPCError::map_protocol(
+                        "invalid receipt in webrtc offer signal info",
+                    ))?
+                    .to_vec();
+                let sdp = r
+                    .get_sdp()
+                    .map_err(RPCError::map_protocol(
+                        "invalid sdp in webrtc offer signal info",
+                    ))?
+                    .to_string()
+                    .map_err(RPCError::map_protocol("invalid sdp string"))?;
+                let pi_reader = r.get_peer_info().map_err(RPCError::map_protocol(
+                    "invalid peer info in webrtc offer signal info",
+                ))?;
+                let peer_info = decode_peer_info(&pi_reader)?;
+
+                SignalInfo::WebRTCOffer {
+                    receipt,
+                    sdp,
+                    peer_info,
+                }
+            }
+            veilid_capnp::operation_signal::WebrtcAnswer(r) => {
+                // Extract webrtc answer reader
+                let r = r.map_err(RPCError::protocol)?;
+                let receipt = r
+                    .get_receipt()
+                    .map_err(RPCError::map_protocol(
+                        "invalid receipt in webrtc answer signal info",
+                    ))?
+                    .to_vec();
+                let sdp = r
+                    .get_sdp()
+                    .map_err(RPCError::map_protocol(
+                        "invalid sdp in webrtc answer signal info",
+                    ))?
+                    .to_string()
+                    .map_err(RPCError::map_protocol("invalid sdp string"))?;
+
+                SignalInfo::WebRTCAnswer { receipt, sdp }
+            }
         },
     )
 }
@@ -0,0 +1,34 @@
+use super::*;
+
+pub fn encode_value_subkey_range_set(
+    subkeys: &ValueSubkeyRangeSet,
+    builder: &mut capnp::struct_list::Builder<veilid_capnp::subkey_range::Owned>,
+) -> Result<(), RPCError> {
+    for (i, skr) in subkeys.ranges().enumerate() {
+        let mut skr_builder = builder.reborrow().get(i as u32);
+        skr_builder.set_start(*skr.start());
+        skr_builder.set_end(*skr.end());
+    }
+    Ok(())
+}
+
+pub fn decode_value_subkey_range_set(
+    reader: &capnp::struct_list::Reader<veilid_capnp::subkey_range::Owned>,
+) -> Result<ValueSubkeyRangeSet, RPCError> {
+    let mut subkeys = ValueSubkeyRangeSet::new();
+    for skr in reader.iter() {
+        let vskr = (skr.get_start(), skr.get_end());
+        if vskr.0 > vskr.1 {
+            return Err(RPCError::protocol("invalid subkey range"));
+        }
+        if let Some(lvskr) = subkeys.last() {
+            if lvskr >= vskr.0 {
+                return Err(RPCError::protocol(
+                    "subkey range out of order or not merged",
+                ));
+            }
+        }
+        subkeys.ranges_insert(vskr.0..=vskr.1);
+    }
+    Ok(subkeys)
+}
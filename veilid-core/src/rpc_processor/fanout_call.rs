@@ -214,9 +214,11 @@ where
 
                     // Call succeeded
                     // Register the returned nodes and add them to the fanout queue in sorted order
-                    let new_nodes = self
-                        .routing_table
-                        .register_find_node_answer(self.crypto_kind, filtered_v);
+                    let new_nodes = self.routing_table.register_find_node_answer(
+                        RoutingDomain::PublicInternet,
+                        self.crypto_kind,
+                        filtered_v,
+                    );
                     self.clone().add_to_fanout_queue(&new_nodes);
                 }
                 #[allow(unused_variables)]
@@ -8,16 +8,21 @@ mod rpc_app_message;
 mod rpc_error;
 mod rpc_find_node;
 mod rpc_get_value;
+mod rpc_get_values;
 mod rpc_inspect_value;
+mod rpc_pin_value;
 mod rpc_return_receipt;
 mod rpc_route;
 mod rpc_set_value;
+mod rpc_set_values;
 mod rpc_signal;
 mod rpc_status;
 mod rpc_validate_dial_info;
 mod rpc_value_changed;
 mod rpc_watch_value;
 
+pub mod tests;
+
 #[cfg(feature = "unstable-blockstore")]
 mod rpc_find_block;
 #[cfg(feature = "unstable-blockstore")]
@@ -29,6 +34,10 @@ mod rpc_cancel_tunnel;
 mod rpc_complete_tunnel;
 #[cfg(feature = "unstable-tunnels")]
 mod rpc_start_tunnel;
+#[cfg(feature = "unstable-tunnels")]
+mod rpc_tunnel_data;
+#[cfg(feature = "unstable-tunnels")]
+mod tunnel_table;
 
 pub(crate) use coders::*;
 pub(crate) use destination::*;
@@ -36,6 +45,8 @@ pub(crate) use fanout_call::*;
 pub(crate) use operation_waiter::*;
 pub(crate) use rpc_error::*;
 pub(crate) use rpc_status::*;
+#[cfg(feature = "unstable-tunnels")]
+pub(crate) use tunnel_table::*;
 
 use super::*;
 
@@ -44,6 +55,7 @@ use fanout_queue::*;
 use futures_util::StreamExt;
 use network_manager::*;
 use routing_table::*;
+use rpc_app_call::*;
 use stop_token::future::FutureExt;
 use storage_manager::*;
 
@@ -298,7 +310,10 @@ struct RPCProcessorUnlockedInner {
     validate_dial_info_receipt_time_ms: u32,
     update_callback: UpdateCallback,
     waiting_rpc_table: OperationWaiter<RPCMessage, Option<QuestionContext>>,
-    waiting_app_call_table: OperationWaiter<Vec<u8>, ()>,
+    waiting_app_call_table: OperationWaiter<Vec<u8>, Option<AppCallReplyStream>>,
+    app_call_stream_table: Mutex<HashMap<OperationId, AppCallStreamContext>>,
+    #[cfg(feature = "unstable-tunnels")]
+    tunnel_table: TunnelTable,
     startup_lock: StartupLock,
 }
 
@@ -353,6 +368,9 @@ impl RPCProcessor {
             update_callback,
             waiting_rpc_table: OperationWaiter::new(),
             waiting_app_call_table: OperationWaiter::new(),
+            app_call_stream_table: Mutex::new(HashMap::new()),
+            #[cfg(feature = "unstable-tunnels")]
+            tunnel_table: TunnelTable::new(),
             startup_lock: StartupLock::new(),
         }
     }
@@ -381,6 +399,11 @@ impl RPCProcessor {
         self.storage_manager.clone()
     }
 
+    #[cfg(feature = "unstable-tunnels")]
+    pub fn tunnel_table(&self) -> TunnelTable {
+        self.unlocked_inner.tunnel_table.clone()
+    }
+
     //////////////////////////////////////////////////////////////////////
 
     #[instrument(level = "debug", skip_all, err)]
@@ -1679,6 +1702,9 @@ impl RPCProcessor {
                 RPCQuestionDetail::CompleteTunnelQ(_) => self.process_complete_tunnel_q(msg).await,
                 #[cfg(feature = "unstable-tunnels")]
                 RPCQuestionDetail::CancelTunnelQ(_) => self.process_cancel_tunnel_q(msg).await,
+                RPCQuestionDetail::GetValuesQ(_) => self.process_get_values_q(msg).await,
+                RPCQuestionDetail::SetValuesQ(_) => self.process_set_values_q(msg).await,
+                RPCQuestionDetail::PinValueQ(_) => self.process_pin_value_q(msg).await,
             },
             RPCOperationKind::Statement(s) => match s.detail() {
                 RPCStatementDetail::ValidateDialInfo(_) => {
@@ -1689,6 +1715,11 @@ impl RPCProcessor {
                 RPCStatementDetail::Signal(_) => self.process_signal(msg).await,
                 RPCStatementDetail::ReturnReceipt(_) => self.process_return_receipt(msg).await,
                 RPCStatementDetail::AppMessage(_) => self.process_app_message(msg).await,
+                #[cfg(feature = "unstable-tunnels")]
+                RPCStatementDetail::TunnelData(_) => self.process_tunnel_data(msg).await,
+                RPCStatementDetail::AppCallChunk(_) => self.process_app_call_chunk(msg).await,
+                RPCStatementDetail::AppCallCancel(_) => self.process_app_call_cancel(msg).await,
+                RPCStatementDetail::AppCallCredit(_) => self.process_app_call_credit(msg).await,
             },
             RPCOperationKind::Answer(_) => {
                 let op_id = msg.operation.op_id();
@@ -1,4 +1,5 @@
 use super::*;
+use futures_util::future::{select, Either};
 
 #[derive(Debug)]
 pub struct OperationWaitHandle<T, C>
@@ -9,6 +10,7 @@ where
     waiter: OperationWaiter<T, C>,
     op_id: OperationId,
     result_receiver: Option<flume::Receiver<(Span, T)>>,
+    progress_receiver: flume::Receiver<()>,
 }
 
 impl<T, C> OperationWaitHandle<T, C>
@@ -42,6 +44,7 @@ where
     context: C,
     timestamp: Timestamp,
     result_sender: flume::Sender<(Span, T)>,
+    progress_sender: flume::Sender<()>,
 }
 
 #[derive(Debug)]
@@ -91,10 +94,12 @@ where
     pub fn add_op_waiter(&self, op_id: OperationId, context: C) -> OperationWaitHandle<T, C> {
         let mut inner = self.inner.lock();
         let (result_sender, result_receiver) = flume::bounded(1);
+        let (progress_sender, progress_receiver) = flume::bounded(1);
         let waiting_op = OperationWaitingOp {
             context,
             timestamp: Timestamp::now(),
             result_sender,
+            progress_sender,
         };
         if inner.waiting_op_table.insert(op_id, waiting_op).is_some() {
             error!(
@@ -107,6 +112,7 @@ where
             waiter: self.clone(),
             op_id,
             result_receiver: Some(result_receiver),
+            progress_receiver,
         }
     }
 
@@ -134,9 +140,23 @@ where
         Ok(waiting_op.context.clone())
     }
 
+    /// Restart the timeout of a waiting operation that is still making progress
+    pub fn report_op_progress(&self, op_id: OperationId) -> Result<(), RPCError> {
+        let inner = self.inner.lock();
+        let Some(waiting_op) = inner.waiting_op_table.get(&op_id) else {
+            return Err(RPCError::ignore(format!(
+                "Missing operation id reporting op progress: id={}",
+                op_id
+            )));
+        };
+        // If the channel is full, the waiter has not yet seen the progress already reported
+        let _ = waiting_op.progress_sender.try_send(());
+        Ok(())
+    }
+
     /// Remove wait for op
     #[instrument(level = "trace", target = "rpc", skip_all)]
-    fn cancel_op_waiter(&self, op_id: OperationId) {
+    pub fn cancel_op_waiter(&self, op_id: OperationId) {
         let mut inner = self.inner.lock();
         inner.waiting_op_table.remove(&op_id);
     }
@@ -173,11 +193,20 @@ where
         // After this, we must manually cancel since the cancel on handle drop is disabled
         let result_receiver = handle.result_receiver.take().unwrap();
 
-        let result_fut = result_receiver.recv_async().in_current_span();
+        let mut result_fut = Box::pin(result_receiver.recv_async().in_current_span());
 
-        // wait for eventualvalue
+        // wait for eventualvalue, restarting the timeout whenever progress is reported
         let start_ts = Timestamp::now();
-        let res = timeout(timeout_ms, result_fut).await.into_timeout_or();
+        let res = loop {
+            let progress_fut = Box::pin(handle.progress_receiver.recv_async());
+            match timeout(timeout_ms, select(result_fut, progress_fut)).await {
+                Err(_) => break TimeoutOr::Timeout,
+                Ok(Either::Left((res, _))) => break TimeoutOr::Value(res),
+                Ok(Either::Right((_, fut))) => {
+                    result_fut = fut;
+                }
+            }
+        };
 
         match res {
             TimeoutOr::Timeout => {
@@ -1,4 +1,177 @@
 use super::*;
+use futures_util::future::{select, Either};
+
+/// Maximum number of reply chunks of a streaming app call held for reordering or delivery
+const MAX_APP_CALL_STREAM_PENDING_CHUNKS: usize = 256;
+
+/// Number of reply chunks of a streaming app call that may be sent ahead of the caller's application
+/// Both sides start with this much credit, and the caller grants more as its application takes chunks
+pub(in crate::rpc_processor) const APP_CALL_STREAM_WINDOW: u64 = 64;
+
+/// Who the reply chunks or the cancel of a streaming app call must come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::rpc_processor) enum AppCallStreamPeer {
+    /// Sent directly by one of these node ids
+    Node(TypedKeyGroup),
+    /// Sent over this remote safety route
+    SafetyRoute(PublicKey),
+    /// Received over this private route of ours
+    PrivateRoute(PublicKey),
+}
+
+impl AppCallStreamPeer {
+    /// The peer that sent a streaming app call question, which is the only one allowed to cancel it
+    fn from_question(msg: &RPCMessage) -> Self {
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(d) => {
+                Self::Node(TypedKeyGroup::from(d.envelope.get_sender_typed_id()))
+            }
+            RPCMessageHeaderDetail::SafetyRouted(s) => Self::SafetyRoute(s.remote_safety_route),
+            RPCMessageHeaderDetail::PrivateRouted(p) => Self::SafetyRoute(p.remote_safety_route),
+        }
+    }
+
+    /// The peer that answers a streaming app call, which is the only one allowed to send its reply chunks
+    fn from_destination(dest: &Destination, respond_to: &RespondTo) -> Option<Self> {
+        match respond_to {
+            RespondTo::Sender => dest.node().map(|nr| Self::Node(nr.node_ids())),
+            RespondTo::PrivateRoute(pr) => Some(Self::PrivateRoute(pr.public_key.value)),
+        }
+    }
+
+    /// Check that a message came from this peer
+    pub fn sent(&self, header: &RPCMessageHeader) -> bool {
+        match (self, &header.detail) {
+            (Self::Node(node_ids), RPCMessageHeaderDetail::Direct(d)) => {
+                node_ids.contains(&d.envelope.get_sender_typed_id())
+            }
+            (Self::SafetyRoute(key), RPCMessageHeaderDetail::SafetyRouted(s)) => {
+                s.remote_safety_route == *key
+            }
+            (Self::SafetyRoute(key), RPCMessageHeaderDetail::PrivateRouted(p)) => {
+                p.remote_safety_route == *key
+            }
+            (Self::PrivateRoute(key), RPCMessageHeaderDetail::PrivateRouted(p)) => {
+                p.private_route == *key
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AppCallReplyStreamState {
+    next_seq: u64,
+    credit: u64,
+    credit_receiver: flume::Receiver<Option<u64>>,
+    finished: bool,
+}
+
+/// Where the reply chunks of a streaming app call are sent, and how many have been sent so far
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct AppCallReplyStream {
+    dest: Destination,
+    peer: AppCallStreamPeer,
+    /// Credit granted by the caller, or None once the caller has cancelled
+    credit_sender: flume::Sender<Option<u64>>,
+    state: Arc<AsyncMutex<AppCallReplyStreamState>>,
+}
+
+impl AppCallReplyStream {
+    fn new(dest: Destination, peer: AppCallStreamPeer) -> Self {
+        let (credit_sender, credit_receiver) = flume::unbounded();
+        Self {
+            dest,
+            peer,
+            credit_sender,
+            state: Arc::new(AsyncMutex::new(AppCallReplyStreamState {
+                next_seq: 0,
+                credit: APP_CALL_STREAM_WINDOW,
+                credit_receiver,
+                finished: false,
+            })),
+        }
+    }
+
+    /// Allow reply chunks up to a new credit to be sent
+    fn grant_credit(&self, credit: u64) {
+        let _ = self.credit_sender.send(Some(credit));
+    }
+
+    /// Stop any reply chunk that is waiting for credit
+    fn cancel(&self) {
+        let _ = self.credit_sender.send(None);
+    }
+
+    /// Stop accepting reply chunks and return how many were sent
+    async fn finish(&self) -> u64 {
+        let mut state = self.state.lock().await;
+        state.finished = true;
+        state.next_seq
+    }
+}
+
+/// Puts the reply chunks of a streaming app call back in order before they are delivered,
+/// and keeps track of how far ahead of the application the sender is allowed to get
+pub(in crate::rpc_processor) struct AppCallStreamReorder {
+    next_seq: u64,
+    granted: u64,
+    pending: BTreeMap<u64, Vec<u8>>,
+}
+
+impl AppCallStreamReorder {
+    pub fn new() -> Self {
+        Self {
+            next_seq: 0,
+            granted: APP_CALL_STREAM_WINDOW,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// The number of chunks delivered so far
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Accept a reply chunk and deliver every chunk that is now in sequence
+    /// Waits for room in `chunk_sender` for each chunk, so chunks sent beyond the granted credit are rejected
+    pub async fn deliver(
+        &mut self,
+        seq: u64,
+        chunk: Vec<u8>,
+        chunk_sender: &flume::Sender<Vec<u8>>,
+    ) -> NetworkResult<()> {
+        if seq < self.next_seq {
+            // Duplicate chunk, drop it
+            return NetworkResult::value(());
+        }
+        if seq >= self.granted {
+            return NetworkResult::invalid_message(format!(
+                "app call stream chunk sent without credit: granted {} got seq {}",
+                self.granted, seq
+            ));
+        }
+        self.pending.insert(seq, chunk);
+
+        while let Some(chunk) = self.pending.remove(&self.next_seq) {
+            if chunk_sender.send_async(chunk).await.is_err() {
+                return NetworkResult::service_unavailable("app call stream cancelled");
+            }
+            self.next_seq += 1;
+        }
+        NetworkResult::value(())
+    }
+
+    /// Take the credit to grant the sender, once enough of its window has been delivered to be worth a statement
+    pub fn take_credit(&mut self) -> Option<u64> {
+        let credit = self.next_seq + APP_CALL_STREAM_WINDOW;
+        if credit - self.granted < APP_CALL_STREAM_WINDOW / 2 {
+            return None;
+        }
+        self.granted = credit;
+        Some(credit)
+    }
+}
 
 impl RPCProcessor {
     // Sends a high level app request and wait for response
@@ -17,7 +190,7 @@ impl RPCProcessor {
 
         let debug_string = format!("AppCall(message(len)={}) => {}", message.len(), dest);
 
-        let app_call_q = RPCOperationAppCallQ::new(message)?;
+        let app_call_q = RPCOperationAppCallQ::new(message, false)?;
         let question = RPCQuestion::new(
             network_result_try!(self.get_destination_respond_to(&dest)?),
             RPCQuestionDetail::AppCallQ(Box::new(app_call_q)),
@@ -45,7 +218,7 @@ impl RPCProcessor {
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
         };
 
-        let a_message = app_call_a.destructure();
+        let (a_message, _chunk_count) = app_call_a.destructure();
 
         #[cfg(feature = "verbose-tracing")]
         tracing::Span::current().record("ret.latency", latency.as_u64());
@@ -58,6 +231,253 @@ impl RPCProcessor {
         )))
     }
 
+    // Sends a high level app request that may be answered with a stream of reply chunks
+    // Chunks are delivered in order to `chunk_sender`, followed by the answer's message if it is not empty
+    // The remote node is granted credit to send more chunks as they are taken from `chunk_sender`
+    // The call is cancelled on the remote node if `stop_token` fires or `chunk_sender` is dropped
+    // Can be sent via all methods including relays and routes
+    #[instrument(level = "trace", target = "rpc", skip(self, message, chunk_sender, stop_token), fields(message.len = message.len()), err)]
+    pub async fn rpc_call_app_call_stream(
+        self,
+        dest: Destination,
+        message: Vec<u8>,
+        chunk_sender: flume::Sender<Vec<u8>>,
+        stop_token: StopToken,
+    ) -> RPCNetworkResult<()> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        let debug_string = format!("AppCallStream(message(len)={}) => {}", message.len(), dest);
+
+        let app_call_q = RPCOperationAppCallQ::new(message, true)?;
+        let respond_to = network_result_try!(self.get_destination_respond_to(&dest)?);
+
+        // Only the node answering the call may send its reply chunks
+        let Some(peer) = AppCallStreamPeer::from_destination(&dest, &respond_to) else {
+            return Ok(NetworkResult::invalid_message(
+                "app call stream can not identify the answering node",
+            ));
+        };
+        let question = RPCQuestion::new(
+            respond_to,
+            RPCQuestionDetail::AppCallQ(Box::new(app_call_q)),
+        );
+
+        // Reply chunks are found through the question context until the stream is registered below
+        let (stream_sender, stream_receiver) = flume::bounded(MAX_APP_CALL_STREAM_PENDING_CHUNKS);
+        let stream_context = AppCallStreamContext {
+            peer,
+            chunk_sender: stream_sender,
+        };
+        let question_context = QuestionContext::AppCallStream(stream_context.clone());
+
+        // Send the app call question
+        let waitable_reply = network_result_try!(
+            self.question(dest.clone(), question, Some(question_context))
+                .await?
+        );
+        let call_id = waitable_reply.handle.id();
+
+        // Register the stream so reply chunks that arrive after the answer are still delivered
+        self.unlocked_inner
+            .app_call_stream_table
+            .lock()
+            .insert(call_id, stream_context);
+
+        let res = self
+            .recv_app_call_stream(
+                &dest,
+                waitable_reply,
+                debug_string,
+                stream_receiver,
+                chunk_sender,
+                stop_token,
+            )
+            .await;
+
+        self.unlocked_inner
+            .app_call_stream_table
+            .lock()
+            .remove(&call_id);
+
+        // Tell the remote node to stop if the stream did not finish
+        if !matches!(res, Ok(NetworkResult::Value(()))) {
+            // A cancelled stream may have stopped waiting for the answer part way through
+            self.unlocked_inner
+                .waiting_rpc_table
+                .cancel_op_waiter(call_id);
+
+            match self.clone().rpc_call_app_call_cancel(dest, call_id).await {
+                Ok(NetworkResult::Value(())) => {}
+                Ok(v) => {
+                    log_rpc!(debug "Remote app call cancel failed: {:?}", v);
+                }
+                Err(e) => {
+                    log_rpc!(debug "Remote app call cancel failed: {}", e);
+                }
+            }
+        }
+
+        res
+    }
+
+    async fn recv_app_call_stream(
+        &self,
+        dest: &Destination,
+        waitable_reply: WaitableReply,
+        debug_string: String,
+        stream_receiver: flume::Receiver<(u64, Vec<u8>)>,
+        chunk_sender: flume::Sender<Vec<u8>>,
+        stop_token: StopToken,
+    ) -> RPCNetworkResult<()> {
+        let call_id = waitable_reply.handle.id();
+        let mut reorder = AppCallStreamReorder::new();
+
+        // Deliver reply chunks while waiting for the answer, each of which restarts the answer timeout
+        let mut reply_fut = Box::pin(self.wait_for_reply(waitable_reply, debug_string));
+        let (msg, _latency) = loop {
+            let chunk_fut = Box::pin(stream_receiver.recv_async());
+            match select(reply_fut, chunk_fut)
+                .timeout_at(stop_token.clone())
+                .await
+            {
+                Err(_) => {
+                    return Ok(NetworkResult::service_unavailable(
+                        "app call stream cancelled",
+                    ));
+                }
+                Ok(Either::Left((res, _))) => match res? {
+                    TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+                    TimeoutOr::Value(v) => break v,
+                },
+                Ok(Either::Right((res, fut))) => {
+                    reply_fut = fut;
+                    let (seq, chunk) = res.map_err(RPCError::internal)?;
+                    let Ok(res) = reorder
+                        .deliver(seq, chunk, &chunk_sender)
+                        .timeout_at(stop_token.clone())
+                        .await
+                    else {
+                        return Ok(NetworkResult::service_unavailable(
+                            "app call stream cancelled",
+                        ));
+                    };
+                    network_result_try!(res);
+
+                    // Let the remote node send more once the application has taken enough of the window
+                    if let Some(credit) = reorder.take_credit() {
+                        network_result_try!(
+                            self.clone()
+                                .rpc_call_app_call_credit(dest.clone(), call_id, credit)
+                                .await?
+                        );
+                    }
+                }
+            }
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let app_call_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::AppCallA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not an appcall answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+        let (a_message, chunk_count) = app_call_a.destructure();
+
+        // Reply chunks may still be in flight behind the answer
+        let timeout_ms =
+            us_to_ms(self.unlocked_inner.timeout_us.as_u64()).map_err(RPCError::internal)?;
+        while reorder.next_seq() < chunk_count {
+            match timeout(timeout_ms, stream_receiver.recv_async())
+                .timeout_at(stop_token.clone())
+                .await
+            {
+                Err(_) => {
+                    return Ok(NetworkResult::service_unavailable(
+                        "app call stream cancelled",
+                    ));
+                }
+                Ok(Err(_)) => {
+                    log_rpc!(debug "App call stream missing chunks (id={}): got {} of {}", call_id, reorder.next_seq(), chunk_count);
+                    return Ok(NetworkResult::Timeout);
+                }
+                Ok(Ok(res)) => {
+                    let (seq, chunk) = res.map_err(RPCError::internal)?;
+                    let Ok(res) = reorder
+                        .deliver(seq, chunk, &chunk_sender)
+                        .timeout_at(stop_token.clone())
+                        .await
+                    else {
+                        return Ok(NetworkResult::service_unavailable(
+                            "app call stream cancelled",
+                        ));
+                    };
+                    network_result_try!(res);
+                }
+            }
+        }
+
+        // The answer's message is the last chunk of the stream, and needs no credit
+        if !a_message.is_empty()
+            && !matches!(
+                chunk_sender
+                    .send_async(a_message)
+                    .timeout_at(stop_token)
+                    .await,
+                Ok(Ok(()))
+            )
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "app call stream cancelled",
+            ));
+        }
+
+        Ok(NetworkResult::value(()))
+    }
+
+    // Tells the remote node to stop sending reply chunks for a streaming app call
+    #[instrument(level = "trace", target = "rpc", skip(self), err)]
+    pub async fn rpc_call_app_call_cancel(
+        self,
+        dest: Destination,
+        call_id: OperationId,
+    ) -> RPCNetworkResult<()> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        let app_call_cancel = RPCOperationAppCallCancel::new(call_id);
+        let statement =
+            RPCStatement::new(RPCStatementDetail::AppCallCancel(Box::new(app_call_cancel)));
+
+        // Send the app call cancel
+        self.statement(dest, statement).await
+    }
+
+    // Lets the remote node send reply chunks of a streaming app call up to a new credit
+    #[instrument(level = "trace", target = "rpc", skip(self), err)]
+    async fn rpc_call_app_call_credit(
+        self,
+        dest: Destination,
+        call_id: OperationId,
+        credit: u64,
+    ) -> RPCNetworkResult<()> {
+        let app_call_credit = RPCOperationAppCallCredit::new(call_id, credit);
+        let statement =
+            RPCStatement::new(RPCStatementDetail::AppCallCredit(Box::new(app_call_credit)));
+
+        // Send the app call credit
+        self.statement(dest, statement).await
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
@@ -113,24 +533,52 @@ impl RPCProcessor {
             .as_ref()
             .map(|nr| nr.node_ids().get(crypto_kind).unwrap());
 
+        // Reply chunks of a streaming app call go where the answer will
+        let (message_q, stream) = app_call_q.destructure();
+        let opt_reply_stream = if stream {
+            let dest = network_result_try!(self.get_respond_to_destination(&msg));
+            Some(AppCallReplyStream::new(
+                dest,
+                AppCallStreamPeer::from_question(&msg),
+            ))
+        } else {
+            None
+        };
+
         // Register a waiter for this app call
         let handle = self
             .unlocked_inner
             .waiting_app_call_table
-            .add_op_waiter(op_id, ());
+            .add_op_waiter(op_id, opt_reply_stream.clone());
 
         // Pass the call up through the update callback
-        let message_q = app_call_q.destructure();
         (self.unlocked_inner.update_callback)(VeilidUpdate::AppCall(Box::new(VeilidAppCall::new(
-            sender, route_id, message_q, op_id,
+            sender, route_id, message_q, op_id, stream,
         ))));
 
         // Wait for an app call answer to come back from the app
-        let res = self
+        // Each reply chunk sent by the app restarts the timeout
+        let res = match self
             .unlocked_inner
             .waiting_app_call_table
             .wait_for_op(handle, self.unlocked_inner.timeout_us)
-            .await?;
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                let Some(reply_stream) = opt_reply_stream else {
+                    return Err(e);
+                };
+                // The caller cancelled the streaming app call
+                reply_stream.finish().await;
+                log_rpc!(debug "App call cancelled for id {}: {}", op_id, e);
+                return Ok(NetworkResult::value(()));
+            }
+        };
+        let chunk_count = match &opt_reply_stream {
+            Some(reply_stream) => reply_stream.finish().await,
+            None => 0,
+        };
         let (message_a, _latency) = match res {
             TimeoutOr::Timeout => {
                 // No message sent on timeout, but this isn't an error
@@ -141,7 +589,7 @@ impl RPCProcessor {
         };
 
         // Return the appcall answer
-        let app_call_a = RPCOperationAppCallA::new(message_a)?;
+        let app_call_a = RPCOperationAppCallA::new(message_a, chunk_count)?;
 
         // Send status answer
         self.answer(
@@ -164,4 +612,232 @@ impl RPCProcessor {
             .complete_op_waiter(call_id, message)
             .map_err(RPCError::ignore)
     }
+
+    /// Exposed to API for apps to send reply chunks on streaming app calls before their final answer
+    #[instrument(level = "trace", target = "rpc", skip_all)]
+    pub async fn app_call_reply_chunk(
+        &self,
+        call_id: OperationId,
+        message: Vec<u8>,
+    ) -> RPCNetworkResult<()> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+        let Some(reply_stream) = self
+            .unlocked_inner
+            .waiting_app_call_table
+            .get_op_context(call_id)?
+        else {
+            return Err(RPCError::invalid_format(format!(
+                "app call is not streaming: id={}",
+                call_id
+            )));
+        };
+
+        // Hold the stream state while sending so chunks go out in sequence and the answer can not overtake them
+        let mut state = reply_stream.state.lock().await;
+        if state.finished {
+            return Err(RPCError::ignore(format!(
+                "app call is already finished: id={}",
+                call_id
+            )));
+        }
+
+        // Wait for the caller to grant credit for this chunk
+        let timeout_ms =
+            us_to_ms(self.unlocked_inner.timeout_us.as_u64()).map_err(RPCError::internal)?;
+        while state.next_seq >= state.credit {
+            let credit_receiver = state.credit_receiver.clone();
+            match timeout(timeout_ms, credit_receiver.recv_async()).await {
+                Ok(Ok(Some(credit))) => {
+                    state.credit = state.credit.max(credit);
+                }
+                Ok(Ok(None)) | Ok(Err(_)) => {
+                    return Err(RPCError::ignore(format!(
+                        "app call was cancelled: id={}",
+                        call_id
+                    )));
+                }
+                Err(_) => {
+                    return Ok(NetworkResult::timeout());
+                }
+            }
+        }
+
+        let app_call_chunk = RPCOperationAppCallChunk::new(call_id, state.next_seq, message)?;
+        let statement =
+            RPCStatement::new(RPCStatementDetail::AppCallChunk(Box::new(app_call_chunk)));
+        network_result_try!(self.statement(reply_stream.dest.clone(), statement).await?);
+        state.next_seq += 1;
+
+        // Keep waiting for the app's final reply
+        let _ = self
+            .unlocked_inner
+            .waiting_app_call_table
+            .report_op_progress(call_id);
+
+        Ok(NetworkResult::value(()))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_app_call_chunk(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Get the statement
+        let (_, _, _, kind) = msg.operation.destructure();
+        let app_call_chunk = match kind {
+            RPCOperationKind::Statement(s) => match s.destructure() {
+                RPCStatementDetail::AppCallChunk(s) => s,
+                _ => panic!("not an app call chunk statement"),
+            },
+            _ => panic!("not a statement"),
+        };
+        let (call_id, seq, message) = app_call_chunk.destructure();
+
+        // Find the streaming app call this chunk belongs to
+        let opt_stream_context = self
+            .unlocked_inner
+            .app_call_stream_table
+            .lock()
+            .get(&call_id)
+            .cloned();
+        let stream_context = match opt_stream_context {
+            Some(v) => v,
+            None => match self
+                .unlocked_inner
+                .waiting_rpc_table
+                .get_op_context(call_id)
+            {
+                Ok(Some(QuestionContext::AppCallStream(ctx))) => ctx,
+                _ => {
+                    return Ok(NetworkResult::invalid_message(format!(
+                        "no streaming app call for id {}",
+                        call_id
+                    )));
+                }
+            },
+        };
+
+        // Only the node answering the call may send its chunks
+        if !stream_context.peer.sent(&msg.header) {
+            return Ok(NetworkResult::invalid_message(format!(
+                "app call stream chunk not from the answering node for id {}",
+                call_id
+            )));
+        }
+
+        if stream_context
+            .chunk_sender
+            .try_send((seq, message))
+            .is_err()
+        {
+            return Ok(NetworkResult::invalid_message(format!(
+                "app call stream chunk queue full for id {}",
+                call_id
+            )));
+        }
+
+        // Each chunk restarts the answer timeout
+        let _ = self
+            .unlocked_inner
+            .waiting_rpc_table
+            .report_op_progress(call_id);
+
+        Ok(NetworkResult::value(()))
+    }
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_app_call_cancel(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_APPMESSAGE)
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "app call is not available",
+            ));
+        }
+
+        // Get the statement
+        let (_, _, _, kind) = msg.operation.destructure();
+        let app_call_cancel = match kind {
+            RPCOperationKind::Statement(s) => match s.destructure() {
+                RPCStatementDetail::AppCallCancel(s) => s,
+                _ => panic!("not an app call cancel statement"),
+            },
+            _ => panic!("not a statement"),
+        };
+        let call_id = app_call_cancel.destructure();
+
+        // Only streaming app calls can be cancelled
+        let Ok(Some(reply_stream)) = self
+            .unlocked_inner
+            .waiting_app_call_table
+            .get_op_context(call_id)
+        else {
+            return Ok(NetworkResult::invalid_message(format!(
+                "no streaming app call to cancel for id {}",
+                call_id
+            )));
+        };
+
+        // Only the node that made the call may cancel it
+        if !reply_stream.peer.sent(&msg.header) {
+            return Ok(NetworkResult::invalid_message(format!(
+                "app call cancel not from the calling node for id {}",
+                call_id
+            )));
+        }
+
+        // Stop waiting for the app, which makes any further replies from it fail
+        self.unlocked_inner
+            .waiting_app_call_table
+            .cancel_op_waiter(call_id);
+        reply_stream.cancel();
+
+        Ok(NetworkResult::value(()))
+    }
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_app_call_credit(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Get the statement
+        let (_, _, _, kind) = msg.operation.destructure();
+        let app_call_credit = match kind {
+            RPCOperationKind::Statement(s) => match s.destructure() {
+                RPCStatementDetail::AppCallCredit(s) => s,
+                _ => panic!("not an app call credit statement"),
+            },
+            _ => panic!("not a statement"),
+        };
+        let (call_id, credit) = app_call_credit.destructure();
+
+        // Find the streaming app call this credit is for
+        let Ok(Some(reply_stream)) = self
+            .unlocked_inner
+            .waiting_app_call_table
+            .get_op_context(call_id)
+        else {
+            return Ok(NetworkResult::invalid_message(format!(
+                "no streaming app call to credit for id {}",
+                call_id
+            )));
+        };
+
+        // Only the node that made the call may grant credit
+        if !reply_stream.peer.sent(&msg.header) {
+            return Ok(NetworkResult::invalid_message(format!(
+                "app call credit not from the calling node for id {}",
+                call_id
+            )));
+        }
+
+        reply_stream.grant_credit(credit);
+
+        Ok(NetworkResult::value(()))
+    }
 }
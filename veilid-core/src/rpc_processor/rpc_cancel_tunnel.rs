@@ -1,27 +1,127 @@
 use super::*;
 
 impl RPCProcessor {
+    /// Send CancelTunnelQ RPC request, receive CancelTunnelA answer
+    /// Tears down the remote end of a tunnel this node is an endpoint of
+    /// Can be sent via relays, but not routes, as tunnel endpoints are node ids
+    #[instrument(level = "trace", target = "rpc", skip(self), ret, err)]
+    pub async fn rpc_call_cancel_tunnel(
+        self,
+        dest: Destination,
+        id: TunnelId,
+    ) -> RPCNetworkResult<Answer<Result<TunnelId, TunnelError>>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        if matches!(
+            dest,
+            Destination::PrivateRoute {
+                private_route: _,
+                safety_selection: _
+            }
+        ) {
+            return Err(RPCError::internal(
+                "Never send cancel tunnel requests over private routes",
+            ));
+        }
+
+        let debug_string = format!("CancelTunnel(id={}) => {}", id, dest);
+
+        let cancel_tunnel_q = RPCOperationCancelTunnelQ::new(id);
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::CancelTunnelQ(Box::new(cancel_tunnel_q)),
+        );
+
+        // Send the cancel tunnel question
+        let waitable_reply = network_result_try!(self.question(dest, question, None).await?);
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let cancel_tunnel_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::CancelTunnelA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a cancel tunnel answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let out = match *cancel_tunnel_a {
+            RPCOperationCancelTunnelA::Tunnel(t) => Ok(t),
+            RPCOperationCancelTunnelA::Error(e) => Err(e),
+        };
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            out,
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
     pub(crate) async fn process_cancel_tunnel_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
         // Ignore if disabled
-        #[cfg(feature = "unstable-tunnels")]
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_TUNNEL)
         {
-            let routing_table = self.routing_table();
-            {
-                if let Some(opi) = routing_table.get_own_peer_info(msg.header.routing_domain()) {
-                    if !opi
-                        .signed_node_info()
-                        .node_info()
-                        .has_capability(CAP_TUNNEL)
-                    {
-                        return Ok(NetworkResult::service_unavailable(
-                            "tunnel is not available",
-                        ));
-                    }
-                }
-            }
+            return Ok(NetworkResult::service_unavailable(
+                "tunnel is not available",
+            ));
         }
 
-        Err(RPCError::unimplemented("process_cancel_tunnel_q"))
+        // Tunnel endpoints are node ids, so tunnels can not be built over routes
+        if !matches!(msg.header.detail, RPCMessageHeaderDetail::Direct(_)) {
+            return Ok(NetworkResult::invalid_message(
+                "cancel tunnel must be direct",
+            ));
+        }
+        let sender_node_id = msg.header.direct_sender_node_id();
+
+        // Get the question
+        let (_, _, _, kind) = msg.operation.clone().destructure();
+        let cancel_tunnel_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::CancelTunnelQ(q)) => q,
+                _ => panic!("not a cancel tunnel question"),
+            },
+            _ => panic!("not a question"),
+        };
+        let id = cancel_tunnel_q.destructure();
+
+        // Only the remote endpoint of a tunnel may cancel it
+        let cancel_tunnel_a = match self
+            .unlocked_inner
+            .tunnel_table
+            .remove(id, Some(sender_node_id))
+        {
+            Ok(_) => RPCOperationCancelTunnelA::new_tunnel(id),
+            Err(e) => RPCOperationCancelTunnelA::new_error(e),
+        };
+
+        // Send cancel tunnel answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::CancelTunnelA(Box::new(cancel_tunnel_a))),
+        )
+        .await
     }
 }
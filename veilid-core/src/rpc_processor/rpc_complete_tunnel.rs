@@ -1,26 +1,153 @@
 use super::*;
 
 impl RPCProcessor {
+    /// Send CompleteTunnelQ RPC request, receive CompleteTunnelA answer
+    /// Completes a partial tunnel previously reserved with StartTunnelQ, supplying this node's endpoint
+    /// Can be sent via relays, but not routes, as tunnel endpoints are node ids
+    #[instrument(level = "trace", target = "rpc", skip(self), ret, err)]
+    pub async fn rpc_call_complete_tunnel(
+        self,
+        dest: Destination,
+        id: TunnelId,
+        local_mode: TunnelMode,
+        depth: u8,
+        endpoint: TunnelEndpoint,
+    ) -> RPCNetworkResult<Answer<Result<FullTunnel, TunnelError>>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        if matches!(
+            dest,
+            Destination::PrivateRoute {
+                private_route: _,
+                safety_selection: _
+            }
+        ) {
+            return Err(RPCError::internal(
+                "Never send complete tunnel requests over private routes",
+            ));
+        }
+
+        let debug_string = format!(
+            "CompleteTunnel(id={}, local_mode={:?}, depth={}) => {}",
+            id, local_mode, depth, dest
+        );
+
+        let complete_tunnel_q = RPCOperationCompleteTunnelQ::new(id, local_mode, depth, endpoint);
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::CompleteTunnelQ(Box::new(complete_tunnel_q)),
+        );
+
+        // Send the complete tunnel question
+        let waitable_reply = network_result_try!(self.question(dest, question, None).await?);
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let complete_tunnel_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::CompleteTunnelA(a) => a,
+                _ => {
+                    return Ok(NetworkResult::invalid_message(
+                        "not a complete tunnel answer",
+                    ))
+                }
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let out = match *complete_tunnel_a {
+            RPCOperationCompleteTunnelA::Tunnel(t) => {
+                if t.id != id {
+                    return Ok(NetworkResult::invalid_message(
+                        "complete tunnel answer has wrong tunnel id",
+                    ));
+                }
+                Ok(t)
+            }
+            RPCOperationCompleteTunnelA::Error(e) => Err(e),
+        };
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            out,
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
     pub(crate) async fn process_complete_tunnel_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
         // Ignore if disabled
-        #[cfg(feature = "unstable-tunnels")]
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_TUNNEL)
         {
-            let routing_table = self.routing_table();
-            {
-                if let Some(opi) = routing_table.get_own_peer_info(msg.header.routing_domain()) {
-                    if !opi
-                        .signed_node_info()
-                        .node_info()
-                        .has_capability(CAP_TUNNEL)
-                    {
-                        return Ok(NetworkResult::service_unavailable(
-                            "tunnel is not available",
-                        ));
-                    }
-                }
-            }
+            return Ok(NetworkResult::service_unavailable(
+                "tunnel is not available",
+            ));
         }
-        Err(RPCError::unimplemented("process_complete_tunnel_q"))
+
+        // Tunnel endpoints are node ids, so tunnels can not be built over routes
+        if !matches!(msg.header.detail, RPCMessageHeaderDetail::Direct(_)) {
+            return Ok(NetworkResult::invalid_message(
+                "complete tunnel must be direct",
+            ));
+        }
+        let sender_node_id = msg.header.direct_sender_node_id();
+
+        // Get the question
+        let (_, _, _, kind) = msg.operation.clone().destructure();
+        let complete_tunnel_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::CompleteTunnelQ(q)) => q,
+                _ => panic!("not a complete tunnel question"),
+            },
+            _ => panic!("not a question"),
+        };
+        let (id, remote_mode, depth, endpoint) = complete_tunnel_q.destructure();
+
+        // Turn the reserved partial tunnel into a full tunnel to the sender
+        let complete_tunnel_a = if depth != 0 || endpoint.mode != remote_mode {
+            RPCOperationCompleteTunnelA::new_error(TunnelError::RejectedMode)
+        } else if let Some(sender_nr) = msg.opt_sender_nr.clone() {
+            match self.unlocked_inner.tunnel_table.complete_partial(
+                id,
+                sender_nr,
+                sender_node_id,
+                endpoint,
+            ) {
+                Ok(full_tunnel) => RPCOperationCompleteTunnelA::new_tunnel(full_tunnel),
+                Err(e) => RPCOperationCompleteTunnelA::new_error(e),
+            }
+        } else {
+            RPCOperationCompleteTunnelA::new_error(TunnelError::NoEndpoint)
+        };
+
+        // Send complete tunnel answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::CompleteTunnelA(Box::new(
+                complete_tunnel_a,
+            ))),
+        )
+        .await
     }
 }
@@ -1,22 +1,203 @@
 use super::*;
 
+#[derive(Clone, Debug)]
+pub struct FindBlockAnswer {
+    pub data: Vec<u8>,
+    pub size: u32,
+    pub suppliers: Vec<PeerInfo>,
+    pub peers: Vec<PeerInfo>,
+}
+
 impl RPCProcessor {
+    /// Sends a find block request and wait for response
+    /// Can be sent via all methods including relays
+    /// Safety routes may be used, but never private routes.
+    /// Returns the block data starting at 'offset' if the node holds the block,
+    /// along with any suppliers it knows of and peers closer to the block id.
+    #[instrument(level = "trace", target = "rpc", skip(self), ret, err)]
+    pub async fn rpc_call_find_block(
+        self,
+        dest: Destination,
+        block_id: TypedKey,
+        offset: u32,
+    ) -> RPCNetworkResult<Answer<FindBlockAnswer>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        // and get the target noderef so we can validate the response
+        let Some(target) = dest.node() else {
+            return Err(RPCError::internal(
+                "Never send find block requests over private routes",
+            ));
+        };
+
+        // Get the target node id
+        let Some(vcrypto) = self.crypto.get(block_id.kind) else {
+            return Err(RPCError::internal("unsupported cryptosystem"));
+        };
+        let Some(target_node_id) = target.node_ids().get(block_id.kind) else {
+            return Err(RPCError::internal("No node id for crypto kind"));
+        };
+
+        let debug_string = format!("OUT ==> FindBlockQ({} @{}) => {}", block_id, offset, dest);
+
+        // Send the find block question
+        let find_block_q = RPCOperationFindBlockQ::new(block_id, offset);
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::FindBlockQ(Box::new(find_block_q)),
+        );
+
+        log_rpc!(debug "{}", debug_string);
+
+        let waitable_reply =
+            network_result_try!(self.question(dest.clone(), question, None).await?);
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let find_block_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::FindBlockA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a find block answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let (data, size, suppliers, peers) = find_block_a.destructure();
+
+        log_rpc!(debug "OUT <== FindBlockA({} @{} len={} size={} suppliers={} peers={}) <= {}",
+            block_id,
+            offset,
+            data.len(),
+            size,
+            suppliers.len(),
+            peers.len(),
+            dest
+        );
+
+        // Validate the data returned fits in the block
+        if offset as usize + data.len() > size as usize {
+            return Ok(NetworkResult::invalid_message(
+                "find block data past end of block",
+            ));
+        }
+
+        // Validate peers returned are, in fact, closer to the block id than the node we sent this to
+        let valid =
+            match RoutingTable::verify_peers_closer(vcrypto, target_node_id, block_id, &peers) {
+                Ok(v) => v,
+                Err(e) => {
+                    return Ok(NetworkResult::invalid_message(format!(
+                        "missing cryptosystem in peers node ids: {}",
+                        e
+                    )));
+                }
+            };
+        if !valid {
+            return Ok(NetworkResult::invalid_message("non-closer peers returned"));
+        }
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            FindBlockAnswer {
+                data,
+                size,
+                suppliers,
+                peers,
+            },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
     pub(crate) async fn process_find_block_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ensure this never came over a private route, safety route is okay though
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(_) | RPCMessageHeaderDetail::SafetyRouted(_) => {}
+            RPCMessageHeaderDetail::PrivateRouted(_) => {
+                return Ok(NetworkResult::invalid_message(
+                    "not processing find block request over private route",
+                ))
+            }
+        }
+
         // Ignore if disabled
-        #[cfg(feature = "unstable-blockstore")]
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_BLOCKSTORE)
         {
-            let routing_table = self.routing_table();
-            {
-                if let Some(opi) = routing_table.get_own_peer_info(detail.routing_domain) {
-                    if !opi.signed_node_info().node_info().can_blockstore() {
-                        return Ok(NetworkResult::service_unavailable(
-                            "block store is not available",
-                        ));
-                    }
-                }
-            }
+            return Ok(NetworkResult::service_unavailable(
+                "block store is not available",
+            ));
         }
-        Err(RPCError::unimplemented("process_find_block_q"))
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let find_block_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::FindBlockQ(q)) => q,
+                _ => panic!("not a find block question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let (block_id, offset) = find_block_q.destructure();
+
+        // Get the nodes that we know about that are closer to the the block id than our own node
+        let mut closer_to_key_peers = network_result_try!(
+            routing_table.find_preferred_peers_closer_to_key(block_id, vec![CAP_BLOCKSTORE])
+        );
+        closer_to_key_peers.truncate(MAX_FIND_BLOCK_A_PEERS_LEN);
+
+        // See if we have this block ourselves, or know who does
+        let storage_manager = self.storage_manager();
+        let find_block_result = network_result_try!(storage_manager
+            .inbound_find_block(block_id, offset)
+            .await
+            .map_err(RPCError::internal)?);
+
+        log_rpc!(debug "IN ===> FindBlockA({} @{} len={} size={} suppliers={} peers={}) ==> {}",
+            block_id,
+            offset,
+            find_block_result.data.len(),
+            find_block_result.size,
+            find_block_result.suppliers.len(),
+            closer_to_key_peers.len(),
+            msg.header.direct_sender_node_id()
+        );
+
+        // Make FindBlock answer
+        let find_block_a = RPCOperationFindBlockA::new(
+            find_block_result.data,
+            find_block_result.size,
+            find_block_result.suppliers,
+            closer_to_key_peers,
+        )?;
+
+        // Send FindBlock answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::FindBlockA(Box::new(find_block_a))),
+        )
+        .await
     }
 }
@@ -55,6 +55,9 @@ impl RPCProcessor {
             TimeoutOr::Value(v) => v,
         };
 
+        // Peers are exchanged in the routing domain the answer came in on
+        let routing_domain = msg.header.routing_domain().peer_routing_domain();
+
         // Get the right answer type
         let (_, _, _, kind) = msg.operation.destructure();
         let find_node_a = match kind {
@@ -69,11 +72,7 @@ impl RPCProcessor {
         let peers = find_node_a.destructure();
 
         for peer_info in &peers {
-            if !self.verify_node_info(
-                RoutingDomain::PublicInternet,
-                peer_info.signed_node_info(),
-                &capabilities,
-            ) {
+            if !self.verify_node_info(routing_domain, peer_info.signed_node_info(), &capabilities) {
                 return Ok(NetworkResult::invalid_message(
                     "find_node response does not meet peer criteria",
                 ));
@@ -113,9 +112,14 @@ impl RPCProcessor {
         let (node_id, capabilities) = find_node_q.destructure();
 
         // Get a chunk of the routing table near the requested node id
+        // from the routing domain the question came in on
+        let routing_domain = msg.header.routing_domain().peer_routing_domain();
         let routing_table = self.routing_table();
-        let closest_nodes =
-            network_result_try!(routing_table.find_preferred_closest_peers(node_id, &capabilities));
+        let closest_nodes = network_result_try!(routing_table.find_preferred_closest_peers(
+            routing_domain,
+            node_id,
+            &capabilities
+        ));
 
         // Make FindNode answer
         let find_node_a = RPCOperationFindNodeA::new(closest_nodes)?;
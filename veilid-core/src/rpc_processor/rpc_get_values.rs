@@ -0,0 +1,283 @@
+use super::*;
+use crate::storage_manager::{SignedValueData, SignedValueDescriptor};
+
+#[derive(Clone, Debug)]
+pub struct GetValuesAnswer {
+    pub values: Vec<(ValueSubkey, SignedValueData)>,
+    pub truncated: bool,
+    pub peers: Vec<PeerInfo>,
+    pub descriptor: Option<SignedValueDescriptor>,
+}
+
+impl RPCProcessor {
+    /// Sends a batched get value request for a set of subkeys and wait for response
+    /// Can be sent via all methods including relays
+    /// Safety routes may be used, but never private routes.
+    /// Because this leaks information about the identity of the node itself,
+    /// replying to this request received over a private route will leak
+    /// the identity of the node and defeat the private route.
+    /// The values returned may be truncated to fit in a single answer, in which case
+    /// the subkeys after the last value returned should be requested again.
+    #[instrument(level = "trace", target = "rpc", skip(self, last_descriptor),
+            fields(ret.values.len,
+                ret.truncated,
+                ret.peers.len,
+                ret.latency
+            ),err)]
+    pub async fn rpc_call_get_values(
+        self,
+        dest: Destination,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        last_descriptor: Option<SignedValueDescriptor>,
+    ) -> RPCNetworkResult<Answer<GetValuesAnswer>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        // and get the target noderef so we can validate the response
+        let Some(target) = dest.node() else {
+            return Err(RPCError::internal(
+                "Never send get values requests over private routes",
+            ));
+        };
+
+        // Get the target node id
+        let Some(vcrypto) = self.crypto.get(key.kind) else {
+            return Err(RPCError::internal("unsupported cryptosystem"));
+        };
+        let Some(target_node_id) = target.node_ids().get(key.kind) else {
+            return Err(RPCError::internal("No node id for crypto kind"));
+        };
+
+        let debug_string = format!(
+            "OUT ==> GetValuesQ({} #{}{}) => {}",
+            key,
+            &subkeys,
+            if last_descriptor.is_some() {
+                " +lastdesc"
+            } else {
+                ""
+            },
+            dest
+        );
+
+        // Send the getvalues question
+        let get_values_q =
+            RPCOperationGetValuesQ::new(key, subkeys.clone(), last_descriptor.is_none())?;
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::GetValuesQ(Box::new(get_values_q)),
+        );
+
+        let question_context = QuestionContext::GetValues(ValidateGetValuesContext {
+            last_descriptor,
+            subkeys,
+            vcrypto: vcrypto.clone(),
+        });
+
+        log_dht!(debug "{}", debug_string);
+
+        let waitable_reply = network_result_try!(
+            self.question(dest.clone(), question, Some(question_context))
+                .await?
+        );
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let get_values_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::GetValuesA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a getvalues answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let (values, truncated, peers, descriptor) = get_values_a.destructure();
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "OUT <== GetValuesA({} values={}{}{} peers={}) <= {}",
+                key,
+                values.len(),
+                if truncated { " +truncated" } else { "" },
+                if descriptor.is_some() { " +desc" } else { "" },
+                peers.len(),
+                dest,
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+
+            let peer_ids: Vec<String> = peers
+                .iter()
+                .filter_map(|p| p.node_ids().get(key.kind).map(|k| k.to_string()))
+                .collect();
+            log_dht!(debug "Peers: {:#?}", peer_ids);
+        }
+
+        // Validate peers returned are, in fact, closer to the key than the node we sent this to
+        let valid = match RoutingTable::verify_peers_closer(vcrypto, target_node_id, key, &peers) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(NetworkResult::invalid_message(format!(
+                    "missing cryptosystem in peers node ids: {}",
+                    e
+                )));
+            }
+        };
+        if !valid {
+            return Ok(NetworkResult::invalid_message("non-closer peers returned"));
+        }
+
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.latency", latency.as_u64());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.values.len", values.len());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.truncated", truncated);
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.peers.len", peers.len());
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            GetValuesAnswer {
+                values,
+                truncated,
+                peers,
+                descriptor,
+            },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_get_values_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ensure this never came over a private route, safety route is okay though
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(_) | RPCMessageHeaderDetail::SafetyRouted(_) => {}
+            RPCMessageHeaderDetail::PrivateRouted(_) => {
+                return Ok(NetworkResult::invalid_message(
+                    "not processing get values request over private route",
+                ))
+            }
+        }
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi.signed_node_info().node_info().has_capability(CAP_DHT) {
+            return Ok(NetworkResult::service_unavailable("dht is not available"));
+        }
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let get_values_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::GetValuesQ(q)) => q,
+                _ => panic!("not a getvalues question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let (key, subkeys, want_descriptor) = get_values_q.destructure();
+
+        // Get the nodes that we know about that are closer to the the key than our own node
+        let routing_table = self.routing_table();
+        let closer_to_key_peers = network_result_try!(
+            routing_table.find_preferred_peers_closer_to_key(key, vec![CAP_DHT])
+        );
+
+        if debug_target_enabled!("dht") {
+            let debug_string = format!(
+                "IN <=== GetValuesQ({} #{}{}) <== {}",
+                key,
+                subkeys,
+                if want_descriptor { " +wantdesc" } else { "" },
+                msg.header.direct_sender_node_id()
+            );
+
+            log_dht!(debug "{}", debug_string);
+        }
+
+        // See if we would have accepted this as a set
+        let set_value_count = {
+            let c = self.config.get();
+            c.network.dht.set_value_count as usize
+        };
+        let (get_result_values, get_result_descriptor) =
+            if closer_to_key_peers.len() >= set_value_count {
+                // Not close enough
+                (Vec::new(), None)
+            } else {
+                // Close enough, lets get them
+
+                // See if we have this record ourselves
+                let storage_manager = self.storage_manager();
+                let get_result = network_result_try!(storage_manager
+                    .inbound_get_values(key, subkeys, want_descriptor)
+                    .await
+                    .map_err(RPCError::internal)?);
+                (get_result.values, get_result.opt_descriptor)
+            };
+
+        // Return as many values as fit in one answer, always returning at least one
+        let mut values = Vec::with_capacity(get_result_values.len());
+        let mut truncated = false;
+        let mut data_len = 0usize;
+        for (subkey, value) in get_result_values {
+            let value_len = value.value_data().data().len();
+            if !values.is_empty() && data_len + value_len > MAX_GET_VALUES_A_DATA_LEN {
+                truncated = true;
+                break;
+            }
+            data_len += value_len;
+            values.push((subkey, (*value).clone()));
+        }
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "IN ===> GetValuesA({} values={}{}{} peers={}) ==> {}",
+                key,
+                values.len(),
+                if truncated { " +truncated" } else { "" },
+                if get_result_descriptor.is_some() {
+                    " +desc"
+                } else {
+                    ""
+                },
+                closer_to_key_peers.len(),
+                msg.header.direct_sender_node_id()
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+        }
+
+        // Make GetValues answer
+        let get_values_a = RPCOperationGetValuesA::new(
+            values,
+            truncated,
+            closer_to_key_peers,
+            get_result_descriptor.map(|x| (*x).clone()),
+        )?;
+
+        // Send GetValues answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::GetValuesA(Box::new(get_values_a))),
+        )
+        .await
+    }
+}
@@ -0,0 +1,251 @@
+use super::*;
+use crate::storage_manager::SignedValueDescriptor;
+
+#[derive(Clone, Debug)]
+pub struct PinValueAnswer {
+    pub accepted: bool,
+    pub expiration: Timestamp,
+    pub peers: Vec<PeerInfo>,
+}
+
+impl RPCProcessor {
+    /// Sends a pin value request and wait for response
+    /// Can be sent via all methods including relays
+    /// Safety routes may be used, but never private routes.
+    /// Because this leaks information about the identity of the node itself,
+    /// replying to this request received over a private route will leak
+    /// the identity of the node and defeat the private route.
+    /// The request is signed by the record owner, and asks a pinning service node to retain
+    /// the record and keep it replicated until the expiration. An expiration of zero unpins.
+    /// If the node accepted the pin, the answer has the expiration the node granted.
+    #[instrument(level = "trace", target = "rpc", skip(self, descriptor, owner),
+        fields(ret.accepted,
+            ret.expiration,
+            ret.peers.len,
+            ret.latency
+        ), err)]
+    pub async fn rpc_call_pin_value(
+        self,
+        dest: Destination,
+        key: TypedKey,
+        descriptor: SignedValueDescriptor,
+        timestamp: Timestamp,
+        expiration: Timestamp,
+        owner: KeyPair,
+    ) -> RPCNetworkResult<Answer<PinValueAnswer>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        // and get the target noderef so we can validate the response
+        let Some(target) = dest.node() else {
+            return Err(RPCError::internal(
+                "Never send pin value requests over private routes",
+            ));
+        };
+
+        // Get the target node id
+        let Some(vcrypto) = self.crypto.get(key.kind) else {
+            return Err(RPCError::internal("unsupported cryptosystem"));
+        };
+        let Some(target_node_id) = target.node_ids().get(key.kind) else {
+            return Err(RPCError::internal("No node id for crypto kind"));
+        };
+
+        let debug_string = format!(
+            "OUT ==> PinValueQ({} ts={} exp={}) => {}",
+            key, timestamp, expiration, dest
+        );
+
+        // Send the pinvalue question
+        let pin_value_q = RPCOperationPinValueQ::new(
+            key,
+            descriptor,
+            timestamp.as_u64(),
+            expiration.as_u64(),
+            owner,
+            vcrypto.clone(),
+        )?;
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::PinValueQ(Box::new(pin_value_q)),
+        );
+
+        log_dht!(debug "{}", debug_string);
+
+        let waitable_reply =
+            network_result_try!(self.question(dest.clone(), question, None).await?);
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let pin_value_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::PinValueA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a pinvalue answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let (accepted, expiration, peers) = pin_value_a.destructure();
+        let expiration = Timestamp::new(expiration);
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "OUT <== PinValueA({}{} exp={} peers={}) <= {}",
+                key,
+                if accepted { " +accepted" } else { "" },
+                expiration,
+                peers.len(),
+                dest,
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+
+            let peer_ids: Vec<String> = peers
+                .iter()
+                .filter_map(|p| p.node_ids().get(key.kind).map(|k| k.to_string()))
+                .collect();
+            log_dht!(debug "Peers: {:#?}", peer_ids);
+        }
+
+        // Validate peers returned are, in fact, closer to the key than the node we sent this to
+        let valid = match RoutingTable::verify_peers_closer(vcrypto, target_node_id, key, &peers) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(NetworkResult::invalid_message(format!(
+                    "missing cryptosystem in peers node ids: {}",
+                    e
+                )));
+            }
+        };
+        if !valid {
+            return Ok(NetworkResult::invalid_message("non-closer peers returned"));
+        }
+
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.latency", latency.as_u64());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.accepted", accepted);
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.expiration", expiration.as_u64());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.peers.len", peers.len());
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            PinValueAnswer {
+                accepted,
+                expiration,
+                peers,
+            },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_pin_value_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_DHT_PIN)
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "dht pinning is not available",
+            ));
+        }
+
+        // Ensure this never came over a private route, safety route is okay though
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(_) | RPCMessageHeaderDetail::SafetyRouted(_) => {}
+            RPCMessageHeaderDetail::PrivateRouted(_) => {
+                return Ok(NetworkResult::invalid_message(
+                    "not processing pin value request over private route",
+                ))
+            }
+        }
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let pin_value_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::PinValueQ(q)) => q,
+                _ => panic!("not a pinvalue question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let (key, descriptor, timestamp, expiration) = pin_value_q.destructure();
+
+        // Get the pinning nodes that we know about that are closer to the the key than our own node
+        let closer_to_key_peers = network_result_try!(
+            routing_table.find_preferred_peers_closer_to_key(key, vec![CAP_DHT_PIN])
+        );
+
+        let debug_string = format!(
+            "IN <=== PinValueQ({} ts={} exp={}) <== {}",
+            key,
+            timestamp,
+            expiration,
+            msg.header.direct_sender_node_id()
+        );
+
+        log_rpc!(debug "{}", debug_string);
+
+        // Apply the pin, creating a new record if necessary
+        let storage_manager = self.storage_manager();
+        let opt_expiration = network_result_try!(storage_manager
+            .inbound_pin_value(
+                key,
+                Arc::new(descriptor),
+                Timestamp::new(timestamp),
+                Timestamp::new(expiration),
+            )
+            .await
+            .map_err(RPCError::internal)?);
+        let (accepted, expiration) = match opt_expiration {
+            Some(exp) => (true, exp.as_u64()),
+            None => (false, 0),
+        };
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "IN ===> PinValueA({}{} exp={} peers={}) ==> {}",
+                key,
+                if accepted { " +accepted" } else { "" },
+                expiration,
+                closer_to_key_peers.len(),
+                msg.header.direct_sender_node_id(),
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+        }
+
+        // Make PinValue answer
+        let pin_value_a = RPCOperationPinValueA::new(accepted, expiration, closer_to_key_peers)?;
+
+        // Send PinValue answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::PinValueA(Box::new(pin_value_a))),
+        )
+        .await
+    }
+}
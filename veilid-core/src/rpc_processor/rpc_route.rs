@@ -183,6 +183,7 @@ impl RPCProcessor {
                         hop_count: rssd.hop_count(),
                         stability: rssd.get_stability(),
                         sequencing: routed_operation.sequencing(),
+                        route_quality: rssd.get_route_quality(),
                     },
                 )
             },
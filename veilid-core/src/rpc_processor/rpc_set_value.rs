@@ -33,6 +33,7 @@ impl RPCProcessor {
         value: SignedValueData,
         descriptor: SignedValueDescriptor,
         send_descriptor: bool,
+        expect_seq: bool,
     ) -> RPCNetworkResult<Answer<SetValueAnswer>> {
         let _guard = self
             .unlocked_inner
@@ -57,12 +58,13 @@ impl RPCProcessor {
         };
 
         let debug_string = format!(
-            "OUT ==> SetValueQ({} #{} len={} writer={}{}) => {}",
+            "OUT ==> SetValueQ({} #{} len={} writer={}{}{}) => {}",
             key,
             subkey,
             value.value_data().data().len(),
             value.value_data().writer(),
             if send_descriptor { " +senddesc" } else { "" },
+            if expect_seq { " +expectseq" } else { "" },
             dest
         );
 
@@ -76,6 +78,7 @@ impl RPCProcessor {
             } else {
                 None
             },
+            expect_seq,
         );
         let question = RPCQuestion::new(
             network_result_try!(self.get_destination_respond_to(&dest)?),
@@ -219,7 +222,7 @@ impl RPCProcessor {
         };
 
         // Destructure
-        let (key, subkey, value, descriptor) = set_value_q.destructure();
+        let (key, subkey, value, descriptor, expect_seq) = set_value_q.destructure();
 
         // Get target for ValueChanged notifications
         let dest = network_result_try!(self.get_respond_to_destination(&msg));
@@ -232,13 +235,14 @@ impl RPCProcessor {
         );
 
         let debug_string = format!(
-            "IN <=== SetValueQ({} #{} len={} seq={} writer={}{}) <== {}",
+            "IN <=== SetValueQ({} #{} len={} seq={} writer={}{}{}) <== {}",
             key,
             subkey,
             value.value_data().data().len(),
             value.value_data().seq(),
             value.value_data().writer(),
             if descriptor.is_some() { " +desc" } else { "" },
+            if expect_seq { " +expectseq" } else { "" },
             msg.header.direct_sender_node_id()
         );
 
@@ -249,7 +253,13 @@ impl RPCProcessor {
             let c = self.config.get();
             c.network.dht.set_value_count as usize
         };
-        let (set, new_value) = if closer_to_key_peers.len() >= set_value_count {
+        // Records pinned to this node are stored here regardless of distance
+        let pinned = self
+            .storage_manager()
+            .is_pinned_record(key)
+            .await
+            .map_err(RPCError::internal)?;
+        let (set, new_value) = if closer_to_key_peers.len() >= set_value_count && !pinned {
             // Not close enough
             (false, None)
         } else {
@@ -263,7 +273,8 @@ impl RPCProcessor {
                     subkey,
                     Arc::new(value),
                     descriptor.map(Arc::new),
-                    target
+                    target,
+                    expect_seq,
                 )
                 .await
                 .map_err(RPCError::internal)?);
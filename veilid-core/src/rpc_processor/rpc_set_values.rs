@@ -0,0 +1,272 @@
+use super::*;
+use crate::storage_manager::{SignedValueData, SignedValueDescriptor};
+
+#[derive(Clone, Debug)]
+pub struct SetValuesAnswer {
+    pub set: bool,
+    pub seqs: Vec<ValueSeqNum>,
+    pub peers: Vec<PeerInfo>,
+}
+
+impl RPCProcessor {
+    /// Sends a batched set value request for a set of subkeys and wait for response
+    /// Can be sent via all methods including relays
+    /// Safety routes may be used, but never private routes.
+    /// Because this leaks information about the identity of the node itself,
+    /// replying to this request received over a private route will leak
+    /// the identity of the node and defeat the private route.
+    /// If the node accepted the set, the answer has the sequence number the node has
+    /// for each value sent, which is higher than the one sent if the node kept a newer value.
+    #[instrument(level = "trace", target = "rpc", skip(self, values, descriptor),
+        fields(values.len = values.len(),
+            ret.set,
+            ret.peers.len,
+            ret.latency
+        ), err)]
+    pub async fn rpc_call_set_values(
+        self,
+        dest: Destination,
+        key: TypedKey,
+        values: Vec<(ValueSubkey, SignedValueData)>,
+        descriptor: SignedValueDescriptor,
+        send_descriptor: bool,
+    ) -> RPCNetworkResult<Answer<SetValuesAnswer>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        // and get the target noderef so we can validate the response
+        let Some(target) = dest.node() else {
+            return Err(RPCError::internal(
+                "Never send set values requests over private routes",
+            ));
+        };
+
+        // Get the target node id
+        let Some(vcrypto) = self.crypto.get(key.kind) else {
+            return Err(RPCError::internal("unsupported cryptosystem"));
+        };
+        let Some(target_node_id) = target.node_ids().get(key.kind) else {
+            return Err(RPCError::internal("No node id for crypto kind"));
+        };
+
+        let debug_string = format!(
+            "OUT ==> SetValuesQ({} values={}{}) => {}",
+            key,
+            values.len(),
+            if send_descriptor { " +senddesc" } else { "" },
+            dest
+        );
+
+        // Send the setvalues question
+        let values_len = values.len();
+        let set_values_q = RPCOperationSetValuesQ::new(
+            key,
+            values,
+            if send_descriptor {
+                Some(descriptor)
+            } else {
+                None
+            },
+        )?;
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::SetValuesQ(Box::new(set_values_q)),
+        );
+        let question_context = QuestionContext::SetValues(ValidateSetValuesContext { values_len });
+
+        if debug_target_enabled!("dht") {
+            log_dht!(debug "{}", debug_string);
+        }
+
+        let waitable_reply = network_result_try!(
+            self.question(dest.clone(), question, Some(question_context))
+                .await?
+        );
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let set_values_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::SetValuesA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a setvalues answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let (set, seqs, peers) = set_values_a.destructure();
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "OUT <== SetValuesA({}{} peers={}) <= {} seqs:\n{}",
+                key,
+                if set { " +set" } else { "" },
+                peers.len(),
+                dest,
+                debug_seqs(&seqs)
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+
+            let peer_ids: Vec<String> = peers
+                .iter()
+                .filter_map(|p| p.node_ids().get(key.kind).map(|k| k.to_string()))
+                .collect();
+            log_dht!(debug "Peers: {:#?}", peer_ids);
+        }
+
+        // Validate peers returned are, in fact, closer to the key than the node we sent this to
+        let valid = match RoutingTable::verify_peers_closer(vcrypto, target_node_id, key, &peers) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(NetworkResult::invalid_message(format!(
+                    "missing cryptosystem in peers node ids: {}",
+                    e
+                )));
+            }
+        };
+        if !valid {
+            return Ok(NetworkResult::invalid_message("non-closer peers returned"));
+        }
+
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.latency", latency.as_u64());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.set", set);
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.peers.len", peers.len());
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            SetValuesAnswer { set, seqs, peers },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_set_values_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let rss = routing_table.route_spec_store();
+
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi.signed_node_info().node_info().has_capability(CAP_DHT) {
+            return Ok(NetworkResult::service_unavailable("dht is not available"));
+        }
+
+        // Ensure this never came over a private route, safety route is okay though
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(_) | RPCMessageHeaderDetail::SafetyRouted(_) => {}
+            RPCMessageHeaderDetail::PrivateRouted(_) => {
+                return Ok(NetworkResult::invalid_message(
+                    "not processing set values request over private route",
+                ))
+            }
+        }
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let set_values_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::SetValuesQ(q)) => q,
+                _ => panic!("not a setvalues question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let (key, values, descriptor) = set_values_q.destructure();
+
+        // Get target for ValueChanged notifications
+        let dest = network_result_try!(self.get_respond_to_destination(&msg));
+        let target = dest.get_target(rss)?;
+
+        // Get the nodes that we know about that are closer to the the key than our own node
+        let routing_table = self.routing_table();
+        let closer_to_key_peers = network_result_try!(
+            routing_table.find_preferred_peers_closer_to_key(key, vec![CAP_DHT])
+        );
+
+        let debug_string = format!(
+            "IN <=== SetValuesQ({} values={}{}) <== {}",
+            key,
+            values.len(),
+            if descriptor.is_some() { " +desc" } else { "" },
+            msg.header.direct_sender_node_id()
+        );
+
+        log_rpc!(debug "{}", debug_string);
+
+        // If there are less than 'set_value_count' peers that are closer, then store here too
+        let set_value_count = {
+            let c = self.config.get();
+            c.network.dht.set_value_count as usize
+        };
+        // Records pinned to this node are stored here regardless of distance
+        let pinned = self
+            .storage_manager()
+            .is_pinned_record(key)
+            .await
+            .map_err(RPCError::internal)?;
+        let (set, seqs) = if closer_to_key_peers.len() >= set_value_count && !pinned {
+            // Not close enough
+            (false, Vec::new())
+        } else {
+            // Close enough, lets set them
+
+            // Save the subkeys, creating a new record if necessary
+            let storage_manager = self.storage_manager();
+            let seqs = network_result_try!(storage_manager
+                .inbound_set_values(
+                    key,
+                    values
+                        .into_iter()
+                        .map(|(subkey, value)| (subkey, Arc::new(value)))
+                        .collect(),
+                    descriptor.map(Arc::new),
+                    target,
+                )
+                .await
+                .map_err(RPCError::internal)?);
+
+            (true, seqs)
+        };
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "IN ===> SetValuesA({}{} peers={}) ==> {} seqs:\n{}",
+                key,
+                if set { " +set" } else { "" },
+                closer_to_key_peers.len(),
+                msg.header.direct_sender_node_id(),
+                debug_seqs(&seqs)
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+        }
+
+        // Make SetValues answer
+        let set_values_a = RPCOperationSetValuesA::new(set, seqs, closer_to_key_peers)?;
+
+        // Send SetValues answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::SetValuesA(Box::new(set_values_a))),
+        )
+        .await
+    }
+}
@@ -1,26 +1,136 @@
 use super::*;
 
 impl RPCProcessor {
+    /// Send StartTunnelQ RPC request, receive StartTunnelA answer
+    /// Reserves a partial tunnel started by the destination node so that only this node may complete it
+    /// Can be sent via relays, but not routes, as tunnel endpoints are node ids
+    #[instrument(level = "trace", target = "rpc", skip(self), ret, err)]
+    pub async fn rpc_call_start_tunnel(
+        self,
+        dest: Destination,
+        id: TunnelId,
+        local_mode: TunnelMode,
+        depth: u8,
+    ) -> RPCNetworkResult<Answer<Result<PartialTunnel, TunnelError>>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        if matches!(
+            dest,
+            Destination::PrivateRoute {
+                private_route: _,
+                safety_selection: _
+            }
+        ) {
+            return Err(RPCError::internal(
+                "Never send start tunnel requests over private routes",
+            ));
+        }
+
+        let debug_string = format!(
+            "StartTunnel(id={}, local_mode={:?}, depth={}) => {}",
+            id, local_mode, depth, dest
+        );
+
+        let start_tunnel_q = RPCOperationStartTunnelQ::new(id, local_mode, depth);
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::StartTunnelQ(Box::new(start_tunnel_q)),
+        );
+
+        // Send the start tunnel question
+        let waitable_reply = network_result_try!(self.question(dest, question, None).await?);
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let start_tunnel_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::StartTunnelA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a start tunnel answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let out = match *start_tunnel_a {
+            RPCOperationStartTunnelA::Partial(p) => Ok(p),
+            RPCOperationStartTunnelA::Error(e) => Err(e),
+        };
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            out,
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
     pub(crate) async fn process_start_tunnel_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
         // Ignore if disabled
-        #[cfg(feature = "unstable-tunnels")]
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_TUNNEL)
         {
-            let routing_table = self.routing_table();
+            return Ok(NetworkResult::service_unavailable(
+                "tunnel is not available",
+            ));
+        }
+
+        // Tunnel endpoints are node ids, so tunnels can not be built over routes
+        if !matches!(msg.header.detail, RPCMessageHeaderDetail::Direct(_)) {
+            return Ok(NetworkResult::invalid_message(
+                "start tunnel must be direct",
+            ));
+        }
+        let sender_node_id = msg.header.direct_sender_node_id();
+
+        // Get the question
+        let (_, _, _, kind) = msg.operation.clone().destructure();
+        let start_tunnel_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::StartTunnelQ(q)) => q,
+                _ => panic!("not a start tunnel question"),
+            },
+            _ => panic!("not a question"),
+        };
+        let (id, remote_mode, depth) = start_tunnel_q.destructure();
+
+        // Reserve the partial tunnel for the sender
+        let start_tunnel_a = if depth != 0 {
+            RPCOperationStartTunnelA::new_error(TunnelError::RejectedMode)
+        } else {
+            match self
+                .unlocked_inner
+                .tunnel_table
+                .reserve_partial(id, remote_mode, sender_node_id)
             {
-                if let Some(opi) = routing_table.get_own_peer_info(msg.header.routing_domain()) {
-                    if !opi
-                        .signed_node_info()
-                        .node_info()
-                        .has_capability(CAP_TUNNEL)
-                    {
-                        return Ok(NetworkResult::service_unavailable(
-                            "tunnel is not available",
-                        ));
-                    }
-                }
+                Ok(partial_tunnel) => RPCOperationStartTunnelA::new_partial(partial_tunnel),
+                Err(e) => RPCOperationStartTunnelA::new_error(e),
             }
-        }
-        Err(RPCError::unimplemented("process_start_tunnel_q"))
+        };
+
+        // Send start tunnel answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::StartTunnelA(Box::new(start_tunnel_a))),
+        )
+        .await
     }
 }
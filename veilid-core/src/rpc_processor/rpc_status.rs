@@ -135,6 +135,9 @@ impl RPCProcessor {
                                         target,
                                     )
                                 }
+                                RoutingDomain::PrivateOverlay => {
+                                    // Overlay addresses are configured, not discovered
+                                }
                             }
                         };
                         opt_sender_info = Some(sender_info.clone());
@@ -1,22 +1,211 @@
 use super::*;
 
+#[derive(Clone, Debug)]
+pub struct SupplyBlockAnswer {
+    pub expiration: Timestamp,
+    pub peers: Vec<PeerInfo>,
+}
+
 impl RPCProcessor {
+    /// Sends a supply block request and wait for response
+    /// Announces that this node can supply a block to a node close to the block id
+    /// Can be sent via relays, but not routes, as the supplier is identified by its node id
+    /// Returns when the supplier entry must be refreshed, or zero if it was not accepted,
+    /// along with peers closer to the block id if it was not accepted.
+    #[instrument(level = "trace", target = "rpc", skip(self), ret, err)]
+    pub async fn rpc_call_supply_block(
+        self,
+        dest: Destination,
+        block_id: TypedKey,
+    ) -> RPCNetworkResult<Answer<SupplyBlockAnswer>> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        // and get the target noderef so we can validate the response
+        let Some(target) = dest.node() else {
+            return Err(RPCError::internal(
+                "Never send supply block requests over private routes",
+            ));
+        };
+        // Ensure we are not sending over a safety route, as the supplier must be identifiable
+        if matches!(dest.get_safety_selection(), SafetySelection::Safe(_)) {
+            return Err(RPCError::internal(
+                "Never send supply block requests over safety routes",
+            ));
+        }
+
+        // Get the target node id
+        let Some(vcrypto) = self.crypto.get(block_id.kind) else {
+            return Err(RPCError::internal("unsupported cryptosystem"));
+        };
+        let Some(target_node_id) = target.node_ids().get(block_id.kind) else {
+            return Err(RPCError::internal("No node id for crypto kind"));
+        };
+
+        let debug_string = format!("OUT ==> SupplyBlockQ({}) => {}", block_id, dest);
+
+        // Send the supply block question
+        let supply_block_q = RPCOperationSupplyBlockQ::new(block_id);
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::SupplyBlockQ(Box::new(supply_block_q)),
+        );
+
+        log_rpc!(debug "{}", debug_string);
+
+        let waitable_reply =
+            network_result_try!(self.question(dest.clone(), question, None).await?);
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let supply_block_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::SupplyBlockA(a) => a,
+                _ => return Ok(NetworkResult::invalid_message("not a supply block answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let (expiration, peers) = supply_block_a.destructure();
+
+        log_rpc!(debug "OUT <== SupplyBlockA({} exp={} peers={}) <= {}",
+            block_id,
+            expiration,
+            peers.len(),
+            dest
+        );
+
+        // Validate peers returned are, in fact, closer to the block id than the node we sent this to
+        let valid =
+            match RoutingTable::verify_peers_closer(vcrypto, target_node_id, block_id, &peers) {
+                Ok(v) => v,
+                Err(e) => {
+                    return Ok(NetworkResult::invalid_message(format!(
+                        "missing cryptosystem in peers node ids: {}",
+                        e
+                    )));
+                }
+            };
+        if !valid {
+            return Ok(NetworkResult::invalid_message("non-closer peers returned"));
+        }
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            SupplyBlockAnswer {
+                expiration: Timestamp::new(expiration),
+                peers,
+            },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
     pub(crate) async fn process_supply_block_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Suppliers are identified by their node id, so this must come directly from the supplier
+        if !matches!(msg.header.detail, RPCMessageHeaderDetail::Direct(_)) {
+            return Ok(NetworkResult::invalid_message(
+                "supply block must be direct",
+            ));
+        }
+
         // Ignore if disabled
-        #[cfg(feature = "unstable-blockstore")]
+        let routing_table = self.routing_table();
+        let routing_domain = msg.header.routing_domain();
+        let opi = routing_table.get_own_peer_info(routing_domain);
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_BLOCKSTORE)
         {
-            let routing_table = self.routing_table();
-            {
-                if let Some(opi) = routing_table.get_own_peer_info(detail.routing_domain) {
-                    if !opi.signed_node_info().node_info().can_blockstore() {
-                        return Ok(NetworkResult::service_unavailable(
-                            "block store is not available",
-                        ));
-                    }
-                }
-            }
+            return Ok(NetworkResult::service_unavailable(
+                "block store is not available",
+            ));
         }
-        Err(RPCError::unimplemented("process_supply_block_q"))
+
+        // Get the supplier's peer info so we can hand it out to nodes looking for the block
+        let Some(supplier_peer_info) = msg
+            .opt_sender_nr
+            .as_ref()
+            .and_then(|nr| nr.make_peer_info(routing_domain))
+        else {
+            return Ok(NetworkResult::invalid_message(
+                "supply block sender has no peer info",
+            ));
+        };
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let supply_block_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::SupplyBlockQ(q)) => q,
+                _ => panic!("not a supply block question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let block_id = supply_block_q.destructure();
+
+        // Get the nodes that we know about that are closer to the the block id than our own node
+        let mut closer_to_key_peers = network_result_try!(
+            routing_table.find_preferred_peers_closer_to_key(block_id, vec![CAP_BLOCKSTORE])
+        );
+        closer_to_key_peers.truncate(MAX_SUPPLY_BLOCK_A_PEERS_LEN);
+
+        // Only accept suppliers for blocks we are close to, the same as we would for a dht value
+        let set_value_count = {
+            let c = self.config.get();
+            c.network.dht.set_value_count as usize
+        };
+        let opt_expiration = if closer_to_key_peers.len() >= set_value_count {
+            // Not close enough
+            None
+        } else {
+            // Close enough, remember the supplier
+            let storage_manager = self.storage_manager();
+            storage_manager
+                .inbound_supply_block(block_id, supplier_peer_info)
+                .await
+                .map_err(RPCError::internal)?
+        };
+
+        // Closer peers are only returned if the supplier was not accepted
+        let (expiration, peers) = match opt_expiration {
+            Some(expiration) => (expiration.as_u64(), vec![]),
+            None => (0u64, closer_to_key_peers),
+        };
+
+        log_rpc!(debug "IN ===> SupplyBlockA({} exp={} peers={}) ==> {}",
+            block_id,
+            expiration,
+            peers.len(),
+            msg.header.direct_sender_node_id()
+        );
+
+        // Make SupplyBlock answer
+        let supply_block_a = RPCOperationSupplyBlockA::new(expiration, peers)?;
+
+        // Send SupplyBlock answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::SupplyBlockA(Box::new(supply_block_a))),
+        )
+        .await
     }
 }
@@ -0,0 +1,88 @@
+use super::*;
+
+impl RPCProcessor {
+    // Sends a chunk of a tunnel's byte stream to the remote endpoint
+    // Can be sent via relays, but not routes, as tunnel endpoints are node ids
+    #[instrument(level = "trace", target = "rpc", skip(self, data), fields(data.len = data.len()), err)]
+    pub async fn rpc_call_tunnel_data(
+        self,
+        dest: Destination,
+        id: TunnelId,
+        seq: u64,
+        data: Vec<u8>,
+    ) -> RPCNetworkResult<()> {
+        let _guard = self
+            .unlocked_inner
+            .startup_lock
+            .enter()
+            .map_err(RPCError::map_try_again("not started up"))?;
+
+        // Ensure destination never has a private route
+        if matches!(
+            dest,
+            Destination::PrivateRoute {
+                private_route: _,
+                safety_selection: _
+            }
+        ) {
+            return Err(RPCError::internal(
+                "Never send tunnel data over private routes",
+            ));
+        }
+
+        let tunnel_data = RPCOperationTunnelData::new(id, seq, data)?;
+        let statement = RPCStatement::new(RPCStatementDetail::TunnelData(Box::new(tunnel_data)));
+
+        // Send the tunnel data
+        self.statement(dest, statement).await
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[instrument(level = "trace", target = "rpc", skip(self, msg), fields(msg.operation.op_id), ret, err)]
+    pub(crate) async fn process_tunnel_data(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capability(CAP_TUNNEL)
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "tunnel is not available",
+            ));
+        }
+
+        // Tunnel endpoints are node ids, so tunnel data can not arrive over routes
+        if !matches!(msg.header.detail, RPCMessageHeaderDetail::Direct(_)) {
+            return Ok(NetworkResult::invalid_message("tunnel data must be direct"));
+        }
+        let sender_node_id = msg.header.direct_sender_node_id();
+
+        // Get the statement
+        let (_, _, _, kind) = msg.operation.destructure();
+        let tunnel_data = match kind {
+            RPCOperationKind::Statement(s) => match s.destructure() {
+                RPCStatementDetail::TunnelData(s) => s,
+                _ => panic!("not a tunnel data statement"),
+            },
+            _ => panic!("not a statement"),
+        };
+        let (id, seq, data) = tunnel_data.destructure();
+
+        // Pass the data to the tunnel's receive queue
+        if let Err(e) = self
+            .unlocked_inner
+            .tunnel_table
+            .receive(id, sender_node_id, seq, data)
+        {
+            return Ok(NetworkResult::invalid_message(format!(
+                "tunnel data rejected for id {}: {:?}",
+                id, e
+            )));
+        }
+
+        Ok(NetworkResult::value(()))
+    }
+}
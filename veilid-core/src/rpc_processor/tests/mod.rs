@@ -0,0 +1,42 @@
+use super::*;
+
+/// Encode a value into a capnp message and decode it back again, as it would travel over the wire
+macro_rules! coder_round_trip {
+    ($capnp_type:ident, $value:expr, $encode:expr, $decode:expr) => {{
+        let mut message = ::capnp::message::Builder::new_default();
+        {
+            let mut builder = message.init_root::<veilid_capnp::$capnp_type::Builder>();
+            ($encode)(&$value, &mut builder).unwrap();
+        }
+        let data = RPCMessageData::new(builder_to_vec(message).unwrap());
+        let reader = data.get_reader().unwrap();
+        let op_reader = reader
+            .get_root::<veilid_capnp::$capnp_type::Reader>()
+            .unwrap();
+        ($decode)(&op_reader).unwrap()
+    }};
+}
+
+pub mod test_app_call_stream;
+pub mod test_coders;
+pub mod test_pin_value;
+#[cfg(feature = "unstable-tunnels")]
+pub mod test_tunnel_table;
+
+/// Add a node to the routing table as if we had heard from it, so tests have a NodeRef to work with
+pub(crate) fn mock_node_ref(routing_table: &RoutingTable, n: u8) -> (TypedKey, NodeRef) {
+    let node_id = routing_table::tests::mock_node_id(n);
+    let flow = Flow::new_no_local(PeerAddress::new(
+        SocketAddress::new(Address::IPV4(Ipv4Addr::new(192, 0, 2, n)), 5150),
+        ProtocolType::UDP,
+    ));
+    let nr = routing_table
+        .register_node_with_existing_connection(node_id, flow, Timestamp::now())
+        .unwrap();
+    (node_id, nr)
+}
+
+/// An unsigned peer info, good enough to round trip through the coders
+pub(crate) fn mock_peer_info(n: u8) -> PeerInfo {
+    routing_table::tests::mock_peer_info(n, NetworkClass::InboundCapable, vec![])
+}
@@ -0,0 +1,237 @@
+use super::*;
+
+async fn deliver(
+    reorder: &mut AppCallStreamReorder,
+    seq: u64,
+    chunk_sender: &flume::Sender<Vec<u8>>,
+) -> NetworkResult<()> {
+    reorder.deliver(seq, vec![seq as u8], chunk_sender).await
+}
+
+fn drain(receiver: &flume::Receiver<Vec<u8>>) -> Vec<Vec<u8>> {
+    let mut out = Vec::new();
+    while let Ok(data) = receiver.try_recv() {
+        out.push(data);
+    }
+    out
+}
+
+fn mock_header(detail: RPCMessageHeaderDetail) -> RPCMessageHeader {
+    RPCMessageHeader {
+        timestamp: Timestamp::now(),
+        body_len: ByteCount::new(0),
+        detail,
+    }
+}
+
+fn mock_direct_detail(routing_table: &RoutingTable, n: u8) -> RPCMessageHeaderDetailDirect {
+    let (node_id, peer_noderef) = mock_node_ref(routing_table, n);
+    RPCMessageHeaderDetailDirect {
+        envelope: Envelope::new(
+            0,
+            CRYPTO_KIND_VLD0,
+            Timestamp::now(),
+            Nonce::new([0u8; 24]),
+            node_id.value,
+            CryptoKey::new([0u8; 32]),
+        ),
+        flow: Flow::new_no_local(PeerAddress::new(
+            SocketAddress::new(Address::IPV4(Ipv4Addr::new(192, 0, 2, n)), 5150),
+            ProtocolType::UDP,
+        )),
+        peer_noderef,
+        routing_domain: RoutingDomain::PublicInternet,
+    }
+}
+
+pub async fn test_reorder() {
+    let (chunk_sender, chunk_receiver) = flume::bounded(16);
+    let mut reorder = AppCallStreamReorder::new();
+
+    // Chunks are held until the ones before them arrive
+    assert!(matches!(
+        deliver(&mut reorder, 2, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    assert!(matches!(
+        deliver(&mut reorder, 1, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    assert!(drain(&chunk_receiver).is_empty());
+    assert!(matches!(
+        deliver(&mut reorder, 0, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    assert_eq!(drain(&chunk_receiver), vec![vec![0], vec![1], vec![2]]);
+    assert_eq!(reorder.next_seq(), 3);
+
+    // Duplicates are dropped
+    assert!(matches!(
+        deliver(&mut reorder, 1, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    assert!(matches!(
+        deliver(&mut reorder, 3, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    assert_eq!(drain(&chunk_receiver), vec![vec![3]]);
+}
+
+pub async fn test_backpressure() {
+    let (chunk_sender, chunk_receiver) = flume::bounded(APP_CALL_STREAM_WINDOW as usize);
+    let mut reorder = AppCallStreamReorder::new();
+
+    // The sender starts with a window of credit, and chunks beyond it are rejected
+    assert!(matches!(
+        deliver(&mut reorder, APP_CALL_STREAM_WINDOW, &chunk_sender).await,
+        NetworkResult::InvalidMessage(_)
+    ));
+    assert!(matches!(
+        deliver(&mut reorder, APP_CALL_STREAM_WINDOW - 1, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+
+    // Credit is granted once half of the window has been delivered
+    for seq in 0..(APP_CALL_STREAM_WINDOW / 2 - 1) {
+        assert!(matches!(
+            deliver(&mut reorder, seq, &chunk_sender).await,
+            NetworkResult::Value(())
+        ));
+    }
+    assert_eq!(reorder.take_credit(), None);
+    assert!(matches!(
+        deliver(&mut reorder, APP_CALL_STREAM_WINDOW / 2 - 1, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    assert_eq!(
+        reorder.take_credit(),
+        Some(APP_CALL_STREAM_WINDOW / 2 + APP_CALL_STREAM_WINDOW)
+    );
+    assert_eq!(reorder.take_credit(), None);
+    assert!(matches!(
+        deliver(&mut reorder, APP_CALL_STREAM_WINDOW, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    drain(&chunk_receiver);
+
+    // Delivery waits for the application to make room rather than dropping chunks
+    let (chunk_sender, chunk_receiver) = flume::bounded(1);
+    let mut reorder = AppCallStreamReorder::new();
+    assert!(matches!(
+        deliver(&mut reorder, 0, &chunk_sender).await,
+        NetworkResult::Value(())
+    ));
+    let deliver_fut = async {
+        let res = deliver(&mut reorder, 1, &chunk_sender).await;
+        (res, Timestamp::now())
+    };
+    let recv_fut = async {
+        sleep(100).await;
+        let recv_ts = Timestamp::now();
+        (chunk_receiver.recv_async().await.unwrap(), recv_ts)
+    };
+    let ((res, delivered_ts), (chunk, recv_ts)) =
+        futures_util::future::join(deliver_fut, recv_fut).await;
+    assert!(matches!(res, NetworkResult::Value(())));
+    assert_eq!(chunk, vec![0]);
+    assert!(delivered_ts >= recv_ts);
+    assert_eq!(drain(&chunk_receiver), vec![vec![1]]);
+
+    // More credit waits until the application has taken half of the window again
+    assert_eq!(reorder.next_seq(), 2);
+    assert_eq!(reorder.take_credit(), None);
+}
+
+pub async fn test_cancel() {
+    // Dropping the receiving end cancels delivery
+    let (chunk_sender, chunk_receiver) = flume::bounded(1);
+    let mut reorder = AppCallStreamReorder::new();
+    drop(chunk_receiver);
+    assert!(matches!(
+        deliver(&mut reorder, 0, &chunk_sender).await,
+        NetworkResult::ServiceUnavailable(_)
+    ));
+
+    // Cancel and credit statements carry the call they are for
+    let call_id = OperationId::new(12345);
+    let cancel = RPCOperationAppCallCancel::new(call_id);
+    let cancel2: RPCOperationAppCallCancel = coder_round_trip!(
+        operation_app_call_cancel,
+        cancel,
+        RPCOperationAppCallCancel::encode,
+        RPCOperationAppCallCancel::decode
+    );
+    assert_eq!(cancel2.destructure(), call_id);
+
+    let credit = RPCOperationAppCallCredit::new(call_id, 96);
+    let credit2: RPCOperationAppCallCredit = coder_round_trip!(
+        operation_app_call_credit,
+        credit,
+        RPCOperationAppCallCredit::encode,
+        RPCOperationAppCallCredit::decode
+    );
+    assert_eq!(credit2.destructure(), (call_id, 96));
+}
+
+pub async fn test_stream_peer() {
+    let routing_table = routing_table::tests::mock_routing_table();
+    routing_table.init().await.unwrap();
+
+    let direct_1 = mock_direct_detail(&routing_table, 1);
+    let direct_2 = mock_direct_detail(&routing_table, 2);
+    let safety_route = CryptoKey::new([3u8; 32]);
+    let private_route = CryptoKey::new([4u8; 32]);
+
+    let from_1 = mock_header(RPCMessageHeaderDetail::Direct(direct_1.clone()));
+    let from_2 = mock_header(RPCMessageHeaderDetail::Direct(direct_2.clone()));
+    let safety_routed = mock_header(RPCMessageHeaderDetail::SafetyRouted(
+        RPCMessageHeaderDetailSafetyRouted {
+            direct: direct_1.clone(),
+            remote_safety_route: safety_route,
+            sequencing: Sequencing::NoPreference,
+        },
+    ));
+    let private_routed = mock_header(RPCMessageHeaderDetail::PrivateRouted(
+        RPCMessageHeaderDetailPrivateRouted {
+            direct: direct_2.clone(),
+            remote_safety_route: safety_route,
+            private_route,
+            safety_spec: SafetySpec {
+                preferred_route: None,
+                hop_count: 1,
+                stability: Stability::LowLatency,
+                sequencing: Sequencing::NoPreference,
+                route_quality: RouteQuality::default(),
+            },
+        },
+    ));
+
+    // A direct peer must send directly from its own node id, even if it relays other routed messages
+    let peer = AppCallStreamPeer::Node(direct_1.peer_noderef.node_ids());
+    assert!(peer.sent(&from_1));
+    assert!(!peer.sent(&from_2));
+    assert!(!peer.sent(&safety_routed));
+
+    // A routed caller must use the safety route it made the call over
+    let peer = AppCallStreamPeer::SafetyRoute(safety_route);
+    assert!(peer.sent(&safety_routed));
+    assert!(peer.sent(&private_routed));
+    assert!(!peer.sent(&from_1));
+    let other_peer = AppCallStreamPeer::SafetyRoute(private_route);
+    assert!(!other_peer.sent(&safety_routed));
+
+    // A routed answer must come in over the private route we asked it to reply to
+    let peer = AppCallStreamPeer::PrivateRoute(private_route);
+    assert!(peer.sent(&private_routed));
+    assert!(!peer.sent(&safety_routed));
+    assert!(!peer.sent(&from_2));
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_all() {
+    test_reorder().await;
+    test_backpressure().await;
+    test_cancel().await;
+    test_stream_peer().await;
+}
@@ -0,0 +1,298 @@
+use super::*;
+use crate::storage_manager::{SignedValueData, SignedValueDescriptor};
+
+#[cfg(feature = "unstable-blockstore")]
+pub async fn test_find_block_round_trip() {
+    let block_id = TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([7u8; 32]));
+
+    let q = RPCOperationFindBlockQ::new(block_id, 12345);
+    let q2: RPCOperationFindBlockQ = coder_round_trip!(
+        operation_find_block_q,
+        q,
+        RPCOperationFindBlockQ::encode,
+        RPCOperationFindBlockQ::decode
+    );
+    assert_eq!(q2.block_id(), block_id);
+    assert_eq!(q2.offset(), 12345);
+
+    let a = RPCOperationFindBlockA::new(
+        vec![1, 2, 3, 4],
+        100,
+        vec![mock_peer_info(1)],
+        vec![mock_peer_info(2), mock_peer_info(3)],
+    )
+    .unwrap();
+    let a2: RPCOperationFindBlockA = coder_round_trip!(
+        operation_find_block_a,
+        a,
+        RPCOperationFindBlockA::encode,
+        RPCOperationFindBlockA::decode
+    );
+    assert_eq!(a2.data(), &[1, 2, 3, 4]);
+    assert_eq!(a2.size(), 100);
+    assert_eq!(a2.suppliers(), &[mock_peer_info(1)]);
+    assert_eq!(a2.peers(), &[mock_peer_info(2), mock_peer_info(3)]);
+
+    // An empty answer means the block wasn't found here
+    let a = RPCOperationFindBlockA::new(vec![], 0, vec![], vec![]).unwrap();
+    let a2: RPCOperationFindBlockA = coder_round_trip!(
+        operation_find_block_a,
+        a,
+        RPCOperationFindBlockA::encode,
+        RPCOperationFindBlockA::decode
+    );
+    assert!(a2.data().is_empty());
+    assert_eq!(a2.size(), 0);
+    assert!(a2.suppliers().is_empty());
+    assert!(a2.peers().is_empty());
+
+    // Data can't be longer than the block it came from
+    assert!(RPCOperationFindBlockA::new(vec![0; 10], 5, vec![], vec![]).is_err());
+}
+
+#[cfg(feature = "unstable-blockstore")]
+pub async fn test_supply_block_round_trip() {
+    let block_id = TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([9u8; 32]));
+
+    let q = RPCOperationSupplyBlockQ::new(block_id);
+    let q2: RPCOperationSupplyBlockQ = coder_round_trip!(
+        operation_supply_block_q,
+        q,
+        RPCOperationSupplyBlockQ::encode,
+        RPCOperationSupplyBlockQ::decode
+    );
+    assert_eq!(*q2.block_id(), block_id);
+
+    let a = RPCOperationSupplyBlockA::new(1_000_000, vec![mock_peer_info(4)]).unwrap();
+    let a2: RPCOperationSupplyBlockA = coder_round_trip!(
+        operation_supply_block_a,
+        a,
+        RPCOperationSupplyBlockA::encode,
+        RPCOperationSupplyBlockA::decode
+    );
+    assert_eq!(a2.expiration(), 1_000_000);
+    assert_eq!(a2.peers(), &[mock_peer_info(4)]);
+}
+
+pub async fn test_protocol_type_set_round_trip() {
+    for pts in [
+        ProtocolTypeSet::empty(),
+        ProtocolTypeSet::all(),
+        ProtocolType::all_ordered_set(),
+        ProtocolType::UDP | ProtocolType::WRTC,
+    ] {
+        let pts2 = coder_round_trip!(
+            protocol_type_set,
+            pts,
+            encode_protocol_type_set,
+            decode_protocol_type_set
+        );
+        assert_eq!(pts2, pts);
+    }
+}
+
+pub async fn test_signal_info_webrtc_round_trip() {
+    let receipt = vec![3u8; MIN_RECEIPT_SIZE];
+
+    let offer = SignalInfo::WebRTCOffer {
+        receipt: receipt.clone(),
+        sdp: "v=0\r\no=- 0 0 IN IP4 127.0.0.1\r\n".to_owned(),
+        peer_info: mock_peer_info(5),
+    };
+    let SignalInfo::WebRTCOffer {
+        receipt: receipt2,
+        sdp,
+        peer_info,
+    } = coder_round_trip!(
+        operation_signal,
+        offer,
+        encode_signal_info,
+        decode_signal_info
+    )
+    else {
+        panic!("wrong signal info kind");
+    };
+    assert_eq!(receipt2, receipt);
+    assert_eq!(sdp, "v=0\r\no=- 0 0 IN IP4 127.0.0.1\r\n");
+    assert_eq!(peer_info, mock_peer_info(5));
+
+    let answer = SignalInfo::WebRTCAnswer {
+        receipt: receipt.clone(),
+        sdp: "v=0\r\n".to_owned(),
+    };
+    let SignalInfo::WebRTCAnswer {
+        receipt: receipt2,
+        sdp,
+    } = coder_round_trip!(
+        operation_signal,
+        answer,
+        encode_signal_info,
+        decode_signal_info
+    )
+    else {
+        panic!("wrong signal info kind");
+    };
+    assert_eq!(receipt2, receipt);
+    assert_eq!(sdp, "v=0\r\n");
+}
+
+pub async fn test_signal_info_hole_punch_round_trip() {
+    let receipt = vec![4u8; MIN_RECEIPT_SIZE];
+
+    for protocol_type in [ProtocolType::UDP, ProtocolType::TCP] {
+        let hole_punch = SignalInfo::HolePunch {
+            receipt: receipt.clone(),
+            peer_info: mock_peer_info(6),
+            protocol_type,
+        };
+        let SignalInfo::HolePunch {
+            receipt: receipt2,
+            peer_info,
+            protocol_type: protocol_type2,
+        } = coder_round_trip!(
+            operation_signal,
+            hole_punch,
+            encode_signal_info,
+            decode_signal_info
+        )
+        else {
+            panic!("wrong signal info kind");
+        };
+        assert_eq!(receipt2, receipt);
+        assert_eq!(peer_info, mock_peer_info(6));
+        assert_eq!(protocol_type2, protocol_type);
+    }
+
+    // Only udp and tcp can be hole punched
+    let hole_punch = SignalInfo::HolePunch {
+        receipt,
+        peer_info: mock_peer_info(6),
+        protocol_type: ProtocolType::WS,
+    };
+    let mut message = ::capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::operation_signal::Builder>();
+    assert!(encode_signal_info(&hole_punch, &mut builder).is_err());
+}
+
+fn mock_signed_value_data(seq: ValueSeqNum, data: &[u8]) -> SignedValueData {
+    SignedValueData::new(
+        ValueData::new_with_seq(seq, data.to_vec(), CryptoKey::new([1u8; 32])).unwrap(),
+        Signature::new([2u8; 64]),
+    )
+}
+
+fn mock_signed_value_descriptor() -> SignedValueDescriptor {
+    SignedValueDescriptor::new(
+        CryptoKey::new([1u8; 32]),
+        DHTSchema::dflt(4).unwrap().compile(),
+        Signature::new([3u8; 64]),
+    )
+}
+
+pub async fn test_get_values_round_trip() {
+    let key = TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([5u8; 32]));
+
+    let mut subkeys = ValueSubkeyRangeSet::single_range(0, 3);
+    subkeys.insert(10);
+    let q = RPCOperationGetValuesQ::new(key, subkeys.clone(), true).unwrap();
+    let q2: RPCOperationGetValuesQ = coder_round_trip!(
+        operation_get_values_q,
+        q,
+        RPCOperationGetValuesQ::encode,
+        RPCOperationGetValuesQ::decode
+    );
+    assert_eq!(q2.destructure(), (key, subkeys, true));
+
+    // Too many subkeys can't be asked for at once
+    let too_many =
+        ValueSubkeyRangeSet::single_range(0, MAX_GET_VALUES_Q_SUBKEYS_LEN as ValueSubkey);
+    assert!(RPCOperationGetValuesQ::new(key, too_many, false).is_err());
+
+    let values = vec![
+        (0, mock_signed_value_data(0, b"zero")),
+        (2, mock_signed_value_data(7, b"two")),
+    ];
+    let a = RPCOperationGetValuesA::new(
+        values.clone(),
+        true,
+        vec![mock_peer_info(1)],
+        Some(mock_signed_value_descriptor()),
+    )
+    .unwrap();
+    let a2: RPCOperationGetValuesA = coder_round_trip!(
+        operation_get_values_a,
+        a,
+        RPCOperationGetValuesA::encode,
+        RPCOperationGetValuesA::decode
+    );
+    let (values2, truncated, peers, descriptor) = a2.destructure();
+    assert_eq!(values2, values);
+    assert!(truncated);
+    assert_eq!(peers, vec![mock_peer_info(1)]);
+    assert_eq!(descriptor, Some(mock_signed_value_descriptor()));
+
+    // An answer with nothing in it stays empty
+    let a = RPCOperationGetValuesA::new(vec![], false, vec![], None).unwrap();
+    let a2: RPCOperationGetValuesA = coder_round_trip!(
+        operation_get_values_a,
+        a,
+        RPCOperationGetValuesA::encode,
+        RPCOperationGetValuesA::decode
+    );
+    let (values2, truncated, peers, descriptor) = a2.destructure();
+    assert!(values2.is_empty());
+    assert!(!truncated);
+    assert!(peers.is_empty());
+    assert!(descriptor.is_none());
+}
+
+pub async fn test_set_values_round_trip() {
+    let key = TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([6u8; 32]));
+
+    let values = vec![
+        (1, mock_signed_value_data(3, b"one")),
+        (3, mock_signed_value_data(0, b"three")),
+    ];
+    let q = RPCOperationSetValuesQ::new(key, values.clone(), Some(mock_signed_value_descriptor()))
+        .unwrap();
+    let q2: RPCOperationSetValuesQ = coder_round_trip!(
+        operation_set_values_q,
+        q,
+        RPCOperationSetValuesQ::encode,
+        RPCOperationSetValuesQ::decode
+    );
+    let (key2, values2, descriptor) = q2.destructure();
+    assert_eq!(key2, key);
+    assert_eq!(values2, values);
+    assert_eq!(descriptor, Some(mock_signed_value_descriptor()));
+
+    // Too many values can't be set at once
+    let too_many = vec![(0, mock_signed_value_data(0, b"")); MAX_SET_VALUES_Q_VALUES_LEN + 1];
+    assert!(RPCOperationSetValuesQ::new(key, too_many, None).is_err());
+
+    let a = RPCOperationSetValuesA::new(false, vec![4, ValueSeqNum::MAX], vec![mock_peer_info(2)])
+        .unwrap();
+    let a2: RPCOperationSetValuesA = coder_round_trip!(
+        operation_set_values_a,
+        a,
+        RPCOperationSetValuesA::encode,
+        RPCOperationSetValuesA::decode
+    );
+    assert_eq!(
+        a2.destructure(),
+        (false, vec![4, ValueSeqNum::MAX], vec![mock_peer_info(2)])
+    );
+}
+
+pub async fn test_all() {
+    test_protocol_type_set_round_trip().await;
+    test_signal_info_webrtc_round_trip().await;
+    test_signal_info_hole_punch_round_trip().await;
+    test_get_values_round_trip().await;
+    test_set_values_round_trip().await;
+    #[cfg(feature = "unstable-blockstore")]
+    {
+        test_find_block_round_trip().await;
+        test_supply_block_round_trip().await;
+    }
+}
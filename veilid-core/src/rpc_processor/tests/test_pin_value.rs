@@ -0,0 +1,109 @@
+use super::*;
+use crate::storage_manager::SignedValueDescriptor;
+use crate::tests::common::test_veilid_config::*;
+
+/// Encode a pin value question, change it on the wire, and decode it again
+fn tampered(
+    q: &RPCOperationPinValueQ,
+    tamper: impl FnOnce(&mut veilid_capnp::operation_pin_value_q::Builder),
+) -> RPCOperationPinValueQ {
+    let mut message = ::capnp::message::Builder::new_default();
+    {
+        let mut builder = message.init_root::<veilid_capnp::operation_pin_value_q::Builder>();
+        q.encode(&mut builder).unwrap();
+        tamper(&mut builder);
+    }
+    let data = RPCMessageData::new(builder_to_vec(message).unwrap());
+    let reader = data.get_reader().unwrap();
+    let op_reader = reader
+        .get_root::<veilid_capnp::operation_pin_value_q::Reader>()
+        .unwrap();
+    RPCOperationPinValueQ::decode(&op_reader).unwrap()
+}
+
+fn mock_descriptor(vcrypto: CryptoSystemVersion, owner: &KeyPair) -> SignedValueDescriptor {
+    SignedValueDescriptor::make_signature(
+        owner.key,
+        DHTSchema::dflt(1).unwrap().compile(),
+        vcrypto,
+        owner.secret,
+    )
+    .unwrap()
+}
+
+pub async fn test_pin_value_signature(api: VeilidAPI) {
+    let crypto = api.crypto().unwrap();
+    let vcrypto = crypto.best();
+    let validate_context = RPCValidateContext {
+        crypto: crypto.clone(),
+        question_context: None,
+    };
+
+    let owner = vcrypto.generate_keypair();
+    let other = vcrypto.generate_keypair();
+    let key = TypedKey::new(vcrypto.kind(), vcrypto.generate_hash(&owner.key.bytes));
+    let descriptor = mock_descriptor(vcrypto.clone(), &owner);
+    let q = RPCOperationPinValueQ::new(key, descriptor.clone(), 1000, 2000, owner, vcrypto.clone())
+        .unwrap();
+
+    // A pin signed by the owner is accepted
+    assert!(tampered(&q, |_| {}).validate(&validate_context).is_ok());
+
+    // Only the owner can sign a pin
+    assert!(RPCOperationPinValueQ::new(
+        key,
+        descriptor.clone(),
+        1000,
+        2000,
+        other,
+        vcrypto.clone(),
+    )
+    .is_err());
+
+    // Changing the expiration or timestamp breaks the signature
+    assert!(tampered(&q, |b| b.set_expiration(u64::MAX))
+        .validate(&validate_context)
+        .is_err());
+    assert!(tampered(&q, |b| b.set_timestamp(1001))
+        .validate(&validate_context)
+        .is_err());
+
+    // A pin signed by someone else is rejected, even with their own valid descriptor
+    let other_q = RPCOperationPinValueQ::new(
+        key,
+        mock_descriptor(vcrypto.clone(), &other),
+        1000,
+        2000,
+        other,
+        vcrypto.clone(),
+    )
+    .unwrap();
+    assert!(tampered(&other_q, |b| {
+        encode_signed_value_descriptor(&descriptor, &mut b.reborrow().init_descriptor()).unwrap()
+    })
+    .validate(&validate_context)
+    .is_err());
+
+    // A descriptor not signed by its owner is rejected
+    let unsigned = SignedValueDescriptor::new(
+        owner.key,
+        DHTSchema::dflt(1).unwrap().compile(),
+        Signature::default(),
+    );
+    assert!(tampered(&q, |b| {
+        encode_signed_value_descriptor(&unsigned, &mut b.reborrow().init_descriptor()).unwrap()
+    })
+    .validate(&validate_context)
+    .is_err());
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    test_pin_value_signature(api.clone()).await;
+
+    api.shutdown().await;
+}
@@ -0,0 +1,217 @@
+use super::*;
+
+fn raw_endpoint() -> TunnelEndpoint {
+    TunnelEndpoint {
+        mode: TunnelMode::Raw,
+        description: String::new(),
+    }
+}
+
+fn turn_endpoint() -> TunnelEndpoint {
+    TunnelEndpoint {
+        mode: TunnelMode::Turn,
+        description: String::new(),
+    }
+}
+
+fn drain(receiver: &flume::Receiver<Vec<u8>>) -> Vec<Vec<u8>> {
+    let mut out = Vec::new();
+    while let Ok(data) = receiver.try_recv() {
+        out.push(data);
+    }
+    out
+}
+
+pub async fn test_partial_tunnel_state_machine() {
+    let routing_table = routing_table::tests::mock_routing_table();
+    routing_table.init().await.unwrap();
+    let (node_a, nr_a) = mock_node_ref(&routing_table, 1);
+    let (node_b, nr_b) = mock_node_ref(&routing_table, 2);
+
+    let tt = TunnelTable::new();
+    let partial = tt.new_partial(raw_endpoint()).unwrap();
+
+    // Mode must match
+    assert_eq!(
+        tt.reserve_partial(partial.id, TunnelMode::Turn, node_a),
+        Err(TunnelError::RejectedMode)
+    );
+    // Unknown ids are rejected
+    assert_eq!(
+        tt.reserve_partial(
+            TunnelId::new(partial.id.as_u64() ^ 1),
+            TunnelMode::Raw,
+            node_a
+        ),
+        Err(TunnelError::BadId)
+    );
+
+    // First reservation wins, and may be repeated by the same node
+    assert_eq!(
+        tt.reserve_partial(partial.id, TunnelMode::Raw, node_a)
+            .unwrap(),
+        partial
+    );
+    assert_eq!(
+        tt.reserve_partial(partial.id, TunnelMode::Raw, node_a)
+            .unwrap(),
+        partial
+    );
+    assert_eq!(
+        tt.reserve_partial(partial.id, TunnelMode::Raw, node_b),
+        Err(TunnelError::BadId)
+    );
+
+    // Only the reserving node can complete or cancel it
+    assert!(matches!(
+        tt.complete_partial(partial.id, nr_b, node_b, raw_endpoint()),
+        Err(TunnelError::BadId)
+    ));
+    assert!(matches!(
+        tt.complete_partial(partial.id, nr_a.clone(), node_a, turn_endpoint()),
+        Err(TunnelError::RejectedMode)
+    ));
+    assert!(matches!(
+        tt.remove(partial.id, Some(node_b)),
+        Err(TunnelError::BadId)
+    ));
+
+    let full = tt
+        .complete_partial(partial.id, nr_a, node_a, raw_endpoint())
+        .unwrap();
+    assert_eq!(full.id, partial.id);
+    assert_eq!(full.local, raw_endpoint());
+    assert_eq!(full.remote, raw_endpoint());
+
+    // A full tunnel can't be reserved or completed again
+    assert_eq!(
+        tt.reserve_partial(partial.id, TunnelMode::Raw, node_a),
+        Err(TunnelError::BadId)
+    );
+
+    let removed = tt.remove(partial.id, Some(node_a)).unwrap().unwrap();
+    assert!(removed.node_ids().contains(&node_a));
+    assert!(matches!(
+        tt.remove(partial.id, None),
+        Err(TunnelError::BadId)
+    ));
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_full_tunnel_send_sequencing() {
+    let routing_table = routing_table::tests::mock_routing_table();
+    routing_table.init().await.unwrap();
+    let (_node_a, nr_a) = mock_node_ref(&routing_table, 1);
+
+    let tt = TunnelTable::new();
+    let id = TunnelId::new(1);
+    tt.add_full(id, raw_endpoint(), nr_a.clone(), raw_endpoint())
+        .unwrap();
+    assert!(matches!(
+        tt.add_full(id, raw_endpoint(), nr_a, raw_endpoint()),
+        Err(TunnelError::BadId)
+    ));
+
+    // Partial tunnels have no endpoint to send to
+    let partial = tt.new_partial(raw_endpoint()).unwrap();
+    assert!(matches!(
+        tt.begin_send(partial.id, 1).await,
+        Err(TunnelError::NoEndpoint)
+    ));
+
+    let first_seq = {
+        let send = tt.begin_send(id, 3).await.unwrap();
+        send.first_seq
+    };
+    assert_eq!(first_seq, 0);
+    let send = tt.begin_send(id, 2).await.unwrap();
+    assert_eq!(send.first_seq, 3);
+    drop(send);
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_receive_in_order() {
+    let routing_table = routing_table::tests::mock_routing_table();
+    routing_table.init().await.unwrap();
+    let (node_a, nr_a) = mock_node_ref(&routing_table, 1);
+    let (node_b, _nr_b) = mock_node_ref(&routing_table, 2);
+
+    let tt = TunnelTable::new();
+    let id = TunnelId::new(1);
+    tt.add_full(id, raw_endpoint(), nr_a, raw_endpoint())
+        .unwrap();
+    let receiver = tt.receiver(id).unwrap();
+
+    // Only the remote node may send into the tunnel
+    assert_eq!(tt.receive(id, node_b, 0, vec![0]), Err(TunnelError::BadId));
+
+    for seq in 0..4u64 {
+        tt.receive(id, node_a, seq, vec![seq as u8]).unwrap();
+    }
+    // Duplicates are dropped
+    tt.receive(id, node_a, 1, vec![1]).unwrap();
+
+    assert_eq!(drain(&receiver), vec![vec![0], vec![1], vec![2], vec![3]]);
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_receive_out_of_order() {
+    let routing_table = routing_table::tests::mock_routing_table();
+    routing_table.init().await.unwrap();
+    let (node_a, nr_a) = mock_node_ref(&routing_table, 1);
+
+    let tt = TunnelTable::new();
+    let id = TunnelId::new(1);
+    tt.add_full(id, raw_endpoint(), nr_a, raw_endpoint())
+        .unwrap();
+    let receiver = tt.receiver(id).unwrap();
+
+    // Nothing is delivered until the gap is filled
+    tt.receive(id, node_a, 2, vec![2]).unwrap();
+    tt.receive(id, node_a, 1, vec![1]).unwrap();
+    tt.receive(id, node_a, 2, vec![2]).unwrap();
+    assert!(drain(&receiver).is_empty());
+
+    tt.receive(id, node_a, 0, vec![0]).unwrap();
+    assert_eq!(drain(&receiver), vec![vec![0], vec![1], vec![2]]);
+
+    tt.receive(id, node_a, 4, vec![4]).unwrap();
+    tt.receive(id, node_a, 3, vec![3]).unwrap();
+    assert_eq!(drain(&receiver), vec![vec![3], vec![4]]);
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_receive_reorder_window_exceeded() {
+    let routing_table = routing_table::tests::mock_routing_table();
+    routing_table.init().await.unwrap();
+    let (node_a, nr_a) = mock_node_ref(&routing_table, 1);
+
+    let tt = TunnelTable::new();
+    let id = TunnelId::new(1);
+    tt.add_full(id, raw_endpoint(), nr_a, raw_endpoint())
+        .unwrap();
+
+    tt.receive(id, node_a, 0, vec![0]).unwrap();
+
+    // A chunk too far ahead of a missing one breaks the stream and closes the tunnel
+    assert_eq!(
+        tt.receive(id, node_a, 1_000_000, vec![0]),
+        Err(TunnelError::NoEndpoint)
+    );
+    assert!(tt.receiver(id).is_none());
+    assert_eq!(tt.receive(id, node_a, 1, vec![1]), Err(TunnelError::BadId));
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_all() {
+    test_partial_tunnel_state_machine().await;
+    test_full_tunnel_send_sequencing().await;
+    test_receive_in_order().await;
+    test_receive_out_of_order().await;
+    test_receive_reorder_window_exceeded().await;
+}
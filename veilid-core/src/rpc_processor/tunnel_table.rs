@@ -0,0 +1,377 @@
+use super::*;
+
+/// How long a partial tunnel waits to be completed before it is discarded
+const PARTIAL_TUNNEL_TIMEOUT_US: u64 = 60_000_000u64;
+/// How long a full tunnel may go without sending or receiving data before it is discarded
+const FULL_TUNNEL_IDLE_TIMEOUT_US: u64 = 300_000_000u64;
+/// Maximum number of partial and full tunnels held at once
+const MAX_TUNNEL_COUNT: usize = 64;
+/// Maximum number of received chunks buffered for the application per tunnel
+const MAX_TUNNEL_RECV_QUEUE_LEN: usize = 256;
+/// How far ahead of the next expected chunk an out-of-order chunk may arrive and still be held for reordering
+const MAX_TUNNEL_REORDER_WINDOW: u64 = 64;
+
+#[derive(Debug)]
+enum TunnelStateKind {
+    /// Started locally and waiting for a remote node to complete it.
+    /// Once a remote node has started completion, it is the only node allowed to complete it.
+    Partial { reserved_by: Option<TypedKey> },
+    /// Completed and carrying data to and from the remote node
+    Full { remote_nr: NodeRef },
+}
+
+#[derive(Debug)]
+struct TunnelState {
+    local: TunnelEndpoint,
+    kind: TunnelStateKind,
+    last_activity_ts: Timestamp,
+    next_send_seq: u64,
+    next_recv_seq: u64,
+    recv_reorder: BTreeMap<u64, Vec<u8>>,
+    send_lock: Arc<AsyncMutex<()>>,
+    recv_sender: flume::Sender<Vec<u8>>,
+    recv_receiver: flume::Receiver<Vec<u8>>,
+}
+
+impl TunnelState {
+    fn new(local: TunnelEndpoint, kind: TunnelStateKind, cur_ts: Timestamp) -> Self {
+        let (recv_sender, recv_receiver) = flume::bounded(MAX_TUNNEL_RECV_QUEUE_LEN);
+        Self {
+            local,
+            kind,
+            last_activity_ts: cur_ts,
+            next_send_seq: 0,
+            next_recv_seq: 0,
+            recv_reorder: BTreeMap::new(),
+            send_lock: Arc::new(AsyncMutex::new(())),
+            recv_sender,
+            recv_receiver,
+        }
+    }
+
+    fn timeout(&self) -> TimestampDuration {
+        match self.kind {
+            TunnelStateKind::Partial { .. } => TimestampDuration::new(PARTIAL_TUNNEL_TIMEOUT_US),
+            TunnelStateKind::Full { .. } => TimestampDuration::new(FULL_TUNNEL_IDLE_TIMEOUT_US),
+        }
+    }
+
+    fn is_expired(&self, cur_ts: Timestamp) -> bool {
+        cur_ts.saturating_sub(self.last_activity_ts).as_u64() >= self.timeout().as_u64()
+    }
+
+    fn remote_node_ids(&self) -> Option<TypedKeyGroup> {
+        match &self.kind {
+            TunnelStateKind::Partial { .. } => None,
+            TunnelStateKind::Full { remote_nr } => Some(remote_nr.node_ids()),
+        }
+    }
+
+    /// Accept a received chunk, holding it until every chunk before it has arrived.
+    /// Chunks that arrive too far ahead of the stream, or that would overflow the
+    /// application's receive queue, break the stream.
+    fn accept_chunk(&mut self, seq: u64, data: Vec<u8>) -> Result<(), TunnelError> {
+        if seq < self.next_recv_seq {
+            // Duplicate chunk, drop it
+            return Ok(());
+        }
+        if seq - self.next_recv_seq >= MAX_TUNNEL_REORDER_WINDOW {
+            return Err(TunnelError::NoEndpoint);
+        }
+        self.recv_reorder.entry(seq).or_insert(data);
+
+        // Deliver everything that is now contiguous
+        while let Some(data) = self.recv_reorder.remove(&self.next_recv_seq) {
+            if self.recv_sender.try_send(data).is_err() {
+                return Err(TunnelError::NoCapacity);
+            }
+            self.next_recv_seq += 1;
+        }
+        Ok(())
+    }
+}
+
+/// A tunnel send that has been sequenced and may now be transmitted to the remote node
+pub(crate) struct TunnelSend {
+    pub remote_nr: NodeRef,
+    pub first_seq: u64,
+    pub _send_guard: AsyncMutexGuardArc<()>,
+}
+
+#[derive(Debug, Default)]
+struct TunnelTableInner {
+    tunnels: HashMap<TunnelId, TunnelState>,
+}
+
+/// Table of the tunnels this node is an endpoint of
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TunnelTable {
+    inner: Arc<Mutex<TunnelTableInner>>,
+}
+
+impl TunnelTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn purge_expired(inner: &mut TunnelTableInner, cur_ts: Timestamp) {
+        inner.tunnels.retain(|id, t| {
+            if t.is_expired(cur_ts) {
+                log_rpc!(debug "Tunnel expired: id = {}", id);
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Start a new partial tunnel with a locally allocated id
+    pub fn new_partial(&self, local: TunnelEndpoint) -> Result<PartialTunnel, TunnelError> {
+        let cur_ts = Timestamp::now();
+        let mut inner = self.inner.lock();
+        Self::purge_expired(&mut inner, cur_ts);
+
+        if inner.tunnels.len() >= MAX_TUNNEL_COUNT {
+            return Err(TunnelError::NoCapacity);
+        }
+
+        let id = loop {
+            let id = TunnelId::new(get_random_u64());
+            if !inner.tunnels.contains_key(&id) {
+                break id;
+            }
+        };
+
+        let state = TunnelState::new(
+            local.clone(),
+            TunnelStateKind::Partial { reserved_by: None },
+            cur_ts,
+        );
+        let timeout = state.timeout();
+        inner.tunnels.insert(id, state);
+
+        Ok(PartialTunnel { id, timeout, local })
+    }
+
+    /// Reserve a partial tunnel for completion by a specific remote node
+    pub fn reserve_partial(
+        &self,
+        id: TunnelId,
+        remote_mode: TunnelMode,
+        remote_node_id: TypedKey,
+    ) -> Result<PartialTunnel, TunnelError> {
+        let cur_ts = Timestamp::now();
+        let mut inner = self.inner.lock();
+        Self::purge_expired(&mut inner, cur_ts);
+
+        let Some(state) = inner.tunnels.get_mut(&id) else {
+            return Err(TunnelError::BadId);
+        };
+        let TunnelStateKind::Partial { reserved_by } = &mut state.kind else {
+            return Err(TunnelError::BadId);
+        };
+        if let Some(reserved_by) = reserved_by {
+            if *reserved_by != remote_node_id {
+                return Err(TunnelError::BadId);
+            }
+        }
+        if state.local.mode != remote_mode {
+            return Err(TunnelError::RejectedMode);
+        }
+        *reserved_by = Some(remote_node_id);
+
+        Ok(PartialTunnel {
+            id,
+            timeout: state.timeout(),
+            local: state.local.clone(),
+        })
+    }
+
+    /// Complete a reserved partial tunnel, turning it into a full tunnel
+    pub fn complete_partial(
+        &self,
+        id: TunnelId,
+        remote_nr: NodeRef,
+        remote_node_id: TypedKey,
+        remote: TunnelEndpoint,
+    ) -> Result<FullTunnel, TunnelError> {
+        let cur_ts = Timestamp::now();
+        let mut inner = self.inner.lock();
+        Self::purge_expired(&mut inner, cur_ts);
+
+        let Some(state) = inner.tunnels.get_mut(&id) else {
+            return Err(TunnelError::BadId);
+        };
+        let TunnelStateKind::Partial {
+            reserved_by: Some(reserved_by),
+        } = &state.kind
+        else {
+            return Err(TunnelError::BadId);
+        };
+        if *reserved_by != remote_node_id {
+            return Err(TunnelError::BadId);
+        }
+        if state.local.mode != remote.mode {
+            return Err(TunnelError::RejectedMode);
+        }
+
+        state.kind = TunnelStateKind::Full { remote_nr };
+        state.last_activity_ts = cur_ts;
+
+        Ok(FullTunnel {
+            id,
+            timeout: state.timeout(),
+            local: state.local.clone(),
+            remote,
+        })
+    }
+
+    /// Add a full tunnel that was completed by the remote node
+    pub fn add_full(
+        &self,
+        id: TunnelId,
+        local: TunnelEndpoint,
+        remote_nr: NodeRef,
+        remote: TunnelEndpoint,
+    ) -> Result<FullTunnel, TunnelError> {
+        let cur_ts = Timestamp::now();
+        let mut inner = self.inner.lock();
+        Self::purge_expired(&mut inner, cur_ts);
+
+        if inner.tunnels.len() >= MAX_TUNNEL_COUNT {
+            return Err(TunnelError::NoCapacity);
+        }
+        if inner.tunnels.contains_key(&id) {
+            return Err(TunnelError::BadId);
+        }
+
+        let state = TunnelState::new(local.clone(), TunnelStateKind::Full { remote_nr }, cur_ts);
+        let timeout = state.timeout();
+        inner.tunnels.insert(id, state);
+
+        Ok(FullTunnel {
+            id,
+            timeout,
+            local,
+            remote,
+        })
+    }
+
+    /// Remove a tunnel, returning the remote node if it was a full tunnel.
+    /// If a remote node id is specified, the tunnel must belong to that node.
+    pub fn remove(
+        &self,
+        id: TunnelId,
+        opt_remote_node_id: Option<TypedKey>,
+    ) -> Result<Option<NodeRef>, TunnelError> {
+        let mut inner = self.inner.lock();
+
+        let Some(state) = inner.tunnels.get(&id) else {
+            return Err(TunnelError::BadId);
+        };
+        if let Some(remote_node_id) = opt_remote_node_id {
+            let is_remote = match &state.kind {
+                TunnelStateKind::Partial { reserved_by } => {
+                    reserved_by.as_ref() == Some(&remote_node_id)
+                }
+                TunnelStateKind::Full { remote_nr } => {
+                    remote_nr.node_ids().contains(&remote_node_id)
+                }
+            };
+            if !is_remote {
+                return Err(TunnelError::BadId);
+            }
+        }
+
+        let state = inner.tunnels.remove(&id).unwrap();
+        match state.kind {
+            TunnelStateKind::Partial { .. } => Ok(None),
+            TunnelStateKind::Full { remote_nr } => Ok(Some(remote_nr)),
+        }
+    }
+
+    /// Sequence a send of 'chunk_count' chunks over a full tunnel.
+    /// The returned guard must be held until all of the chunks have been sent.
+    pub async fn begin_send(
+        &self,
+        id: TunnelId,
+        chunk_count: u64,
+    ) -> Result<TunnelSend, TunnelError> {
+        let send_lock = {
+            let inner = self.inner.lock();
+            let Some(state) = inner.tunnels.get(&id) else {
+                return Err(TunnelError::BadId);
+            };
+            state.send_lock.clone()
+        };
+        let send_guard = asyncmutex_lock_arc!(&send_lock);
+
+        let cur_ts = Timestamp::now();
+        let mut inner = self.inner.lock();
+        Self::purge_expired(&mut inner, cur_ts);
+
+        let Some(state) = inner.tunnels.get_mut(&id) else {
+            return Err(TunnelError::BadId);
+        };
+        let TunnelStateKind::Full { remote_nr } = &state.kind else {
+            return Err(TunnelError::NoEndpoint);
+        };
+        let remote_nr = remote_nr.clone();
+        let first_seq = state.next_send_seq;
+        state.next_send_seq += chunk_count;
+        state.last_activity_ts = cur_ts;
+
+        Ok(TunnelSend {
+            remote_nr,
+            first_seq,
+            _send_guard: send_guard,
+        })
+    }
+
+    /// Accept a chunk of data received over a full tunnel.
+    /// Chunks may arrive out of order within a bounded window and are delivered in sequence.
+    /// A chunk that is missing for longer than the window breaks the stream and closes the tunnel.
+    pub fn receive(
+        &self,
+        id: TunnelId,
+        remote_node_id: TypedKey,
+        seq: u64,
+        data: Vec<u8>,
+    ) -> Result<(), TunnelError> {
+        let cur_ts = Timestamp::now();
+        let mut inner = self.inner.lock();
+        Self::purge_expired(&mut inner, cur_ts);
+
+        let Some(state) = inner.tunnels.get_mut(&id) else {
+            return Err(TunnelError::BadId);
+        };
+        if !state
+            .remote_node_ids()
+            .map(|ids| ids.contains(&remote_node_id))
+            .unwrap_or_default()
+        {
+            return Err(TunnelError::BadId);
+        }
+
+        if let Err(e) = state.accept_chunk(seq, data) {
+            match e {
+                TunnelError::NoCapacity => {
+                    log_rpc!(debug "Tunnel receive queue full: id = {}", id);
+                }
+                _ => {
+                    log_rpc!(debug "Tunnel stream broken: id = {}, expected seq {} got {}", id, state.next_recv_seq, seq);
+                }
+            }
+            inner.tunnels.remove(&id);
+            return Err(e);
+        }
+        state.last_activity_ts = cur_ts;
+
+        Ok(())
+    }
+
+    /// Get the receiver for a tunnel's incoming byte stream
+    pub fn receiver(&self, id: TunnelId) -> Option<flume::Receiver<Vec<u8>>> {
+        let inner = self.inner.lock();
+        inner.tunnels.get(&id).map(|s| s.recv_receiver.clone())
+    }
+}
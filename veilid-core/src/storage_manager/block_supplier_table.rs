@@ -0,0 +1,69 @@
+use super::*;
+
+/// A node that has announced it can supply a block
+#[derive(Debug, Clone)]
+struct BlockSupplier {
+    peer_info: PeerInfo,
+    expiration: Timestamp,
+}
+
+/// Suppliers that have announced blocks to this node, because this node is close to the block ids
+#[derive(Debug, Default)]
+pub(super) struct BlockSupplierTable {
+    suppliers: HashMap<TypedKey, Vec<BlockSupplier>>,
+}
+
+impl BlockSupplierTable {
+    fn purge_expired(&mut self, cur_ts: Timestamp) {
+        self.suppliers.retain(|_, v| {
+            v.retain(|s| s.expiration > cur_ts);
+            !v.is_empty()
+        });
+    }
+
+    /// Add or refresh a supplier for a block, returning when the supplier entry expires.
+    /// Returns None if there is no room for another block's suppliers.
+    pub fn add_supplier(
+        &mut self,
+        block_id: TypedKey,
+        peer_info: PeerInfo,
+        cur_ts: Timestamp,
+    ) -> Option<Timestamp> {
+        self.purge_expired(cur_ts);
+
+        if !self.suppliers.contains_key(&block_id)
+            && self.suppliers.len() >= MAX_BLOCK_SUPPLIER_RECORDS
+        {
+            return None;
+        }
+
+        let expiration = cur_ts + TimestampDuration::new(BLOCK_SUPPLIER_EXPIRATION_US);
+        let suppliers = self.suppliers.entry(block_id).or_default();
+
+        // Replace any existing entry for the same node
+        suppliers.retain(|s| !s.peer_info.node_ids().contains_any(peer_info.node_ids()));
+
+        // Make room by dropping the supplier closest to expiring
+        if suppliers.len() >= MAX_BLOCK_SUPPLIERS {
+            suppliers.sort_by_key(|s| s.expiration);
+            suppliers.remove(0);
+        }
+
+        suppliers.push(BlockSupplier {
+            peer_info,
+            expiration,
+        });
+
+        Some(expiration)
+    }
+
+    /// Get the unexpired suppliers for a block
+    pub fn get_suppliers(&mut self, block_id: TypedKey, cur_ts: Timestamp) -> Vec<PeerInfo> {
+        self.purge_expired(cur_ts);
+
+        self.suppliers
+            .get(&block_id)
+            .map(|v| v.iter().map(|s| s.peer_info.clone()).collect())
+            .unwrap_or_default()
+    }
+}
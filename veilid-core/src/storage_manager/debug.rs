@@ -15,6 +15,53 @@ impl StorageManager {
         };
         remote_record_store.debug_records()
     }
+    pub(crate) async fn debug_local_record_list(
+        &self,
+    ) -> VeilidAPIResult<(RecordStoreStats, Vec<DebugRecord>)> {
+        let inner = self.inner.lock().await;
+        let Some(local_record_store) = &inner.local_record_store else {
+            apibail_not_initialized!();
+        };
+        Ok((
+            local_record_store.stats(),
+            local_record_store.debug_record_list(),
+        ))
+    }
+    pub(crate) async fn debug_remote_record_list(
+        &self,
+    ) -> VeilidAPIResult<(RecordStoreStats, Vec<DebugRecord>)> {
+        let inner = self.inner.lock().await;
+        let Some(remote_record_store) = &inner.remote_record_store else {
+            apibail_not_initialized!();
+        };
+        Ok((
+            remote_record_store.stats(),
+            remote_record_store.debug_record_list(),
+        ))
+    }
+    pub(crate) async fn debug_opened_record_list(&self) -> Vec<DebugOpenedRecord> {
+        let inner = self.inner.lock().await;
+        inner
+            .opened_records
+            .iter()
+            .map(|(k, v)| DebugOpenedRecord {
+                key: *k,
+                writer: v.writer().map(|w| w.key),
+                watched_subkeys: v.active_watch().map(|w| w.subkeys),
+            })
+            .collect()
+    }
+    pub(crate) async fn debug_offline_record_list(&self) -> Vec<DebugOfflineRecord> {
+        let inner = self.inner.lock().await;
+        inner
+            .offline_subkey_writes
+            .iter()
+            .map(|(k, v)| DebugOfflineRecord {
+                key: *k,
+                subkeys: v.subkeys.clone(),
+            })
+            .collect()
+    }
     pub(crate) async fn debug_opened_records(&self) -> String {
         let inner = self.inner.lock().await;
         let mut out = "[\n".to_owned();
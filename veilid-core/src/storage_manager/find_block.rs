@@ -0,0 +1,299 @@
+use super::*;
+
+/// The context of the outbound_find_block operation
+struct OutboundFindBlockContext {
+    /// The block data if a node has returned it in its entirety
+    pub data: Option<Vec<u8>>,
+    /// The suppliers of the block that have been found so far
+    pub suppliers: Vec<PeerInfo>,
+}
+
+/// The result of the inbound_find_block operation
+#[derive(Clone, Debug)]
+pub(crate) struct InboundFindBlockResult {
+    /// The block data starting at the requested offset, if we have the block
+    pub data: Vec<u8>,
+    /// The total size of the block, or zero if we don't have the block
+    pub size: u32,
+    /// The suppliers of the block that we know of
+    pub suppliers: Vec<PeerInfo>,
+}
+
+impl StorageManager {
+    /// Perform a 'find block' query on the network
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub(super) async fn outbound_find_block(
+        &self,
+        rpc_processor: RPCProcessor,
+        block_id: TypedKey,
+        safety_selection: SafetySelection,
+        known_suppliers: Vec<PeerInfo>,
+    ) -> VeilidAPIResult<Option<Vec<u8>>> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Blocks are located with the same parameters as 'GetValue'
+        let (key_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.get_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.get_value_timeout_ms)),
+            )
+        };
+
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(block_id.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        // Make do-find-block answer context
+        let context = Arc::new(Mutex::new(OutboundFindBlockContext {
+            data: None,
+            suppliers: known_suppliers,
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = {
+            let context = context.clone();
+            let rpc_processor = rpc_processor.clone();
+            let vcrypto = vcrypto.clone();
+            move |next_node: NodeRef| {
+                let context = context.clone();
+                let rpc_processor = rpc_processor.clone();
+                let vcrypto = vcrypto.clone();
+                async move {
+                    let fba = network_result_try!(
+                        rpc_processor
+                            .clone()
+                            .rpc_call_find_block(
+                                Destination::direct(next_node.clone())
+                                    .with_safety(safety_selection),
+                                block_id,
+                                0,
+                            )
+                            .await?
+                    );
+
+                    if fba.answer.size != 0 {
+                        // This node has the block, so download the rest of it
+                        let data = network_result_try!(
+                            Self::download_block(
+                                rpc_processor,
+                                vcrypto,
+                                next_node,
+                                safety_selection,
+                                block_id,
+                                fba.answer.data,
+                                fba.answer.size,
+                            )
+                            .await?
+                        );
+                        context.lock().data = Some(data);
+                    } else {
+                        // Keep any suppliers we haven't seen yet
+                        let mut ctx = context.lock();
+                        for supplier in fba.answer.suppliers {
+                            if !ctx
+                                .suppliers
+                                .iter()
+                                .any(|s| s.node_ids().contains_any(supplier.node_ids()))
+                            {
+                                ctx.suppliers.push(supplier);
+                            }
+                        }
+                    }
+
+                    // Return peers if we have some
+                    log_network_result!(debug "FindBlock fanout call returned peers {}", fba.answer.peers.len());
+
+                    Ok(NetworkResult::value(fba.answer.peers))
+                }
+                .instrument(tracing::trace_span!("outbound_find_block fanout routine"))
+            }
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = {
+            let context = context.clone();
+            move |_closest_nodes: &[NodeRef]| {
+                // If we have the block, return done
+                if context.lock().data.is_some() {
+                    return Some(());
+                }
+                None
+            }
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            block_id,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(vec![CAP_BLOCKSTORE]),
+            call_routine,
+            check_done,
+        );
+
+        let kind = match fanout_call.run(vec![]).await {
+            // If we don't finish in the timeout
+            TimeoutOr::Timeout => FanoutResultKind::Timeout,
+            // If we found the block
+            TimeoutOr::Value(Ok(Some(()))) => FanoutResultKind::Finished,
+            // If we ran out of nodes before finding the block
+            TimeoutOr::Value(Ok(None)) => FanoutResultKind::Exhausted,
+            // Failed
+            TimeoutOr::Value(Err(e)) => {
+                // If we finished with an error, return that
+                log_stor!(debug "FindBlock Fanout Error: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let (opt_data, suppliers) = {
+            let mut ctx = context.lock();
+            (ctx.data.take(), core::mem::take(&mut ctx.suppliers))
+        };
+        log_network_result!(debug "FindBlock Fanout: {:?} found={} suppliers={}", kind, opt_data.is_some(), suppliers.len());
+
+        if opt_data.is_some() {
+            return Ok(opt_data);
+        }
+
+        // Download the block from one of its suppliers
+        let supplier_nodes = routing_table.register_find_node_answer(
+            RoutingDomain::PublicInternet,
+            block_id.kind,
+            suppliers,
+        );
+        for supplier_node in supplier_nodes {
+            let res = rpc_processor
+                .clone()
+                .rpc_call_find_block(
+                    Destination::direct(supplier_node.clone()).with_safety(safety_selection),
+                    block_id,
+                    0,
+                )
+                .await;
+            let fba = match res {
+                Ok(NetworkResult::Value(v)) => v,
+                Ok(x) => {
+                    log_network_result!(debug "FindBlock supplier {} failed: {:?}", supplier_node, x);
+                    continue;
+                }
+                Err(e) => {
+                    log_stor!(debug "FindBlock supplier {} error: {}", supplier_node, e);
+                    continue;
+                }
+            };
+            if fba.answer.size == 0 {
+                // Supplier no longer has the block
+                continue;
+            }
+
+            let res = Self::download_block(
+                rpc_processor.clone(),
+                vcrypto.clone(),
+                supplier_node.clone(),
+                safety_selection,
+                block_id,
+                fba.answer.data,
+                fba.answer.size,
+            )
+            .await;
+            match res {
+                Ok(NetworkResult::Value(data)) => return Ok(Some(data)),
+                Ok(x) => {
+                    log_network_result!(debug "FindBlock download from {} failed: {:?}", supplier_node, x);
+                }
+                Err(e) => {
+                    log_stor!(debug "FindBlock download from {} error: {}", supplier_node, e);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Download the remainder of a block from a node that has it, and validate its hash
+    async fn download_block(
+        rpc_processor: RPCProcessor,
+        vcrypto: CryptoSystemVersion,
+        node: NodeRef,
+        safety_selection: SafetySelection,
+        block_id: TypedKey,
+        first_data: Vec<u8>,
+        size: u32,
+    ) -> RPCNetworkResult<Vec<u8>> {
+        let size = size as usize;
+        if size > MAX_BLOCK_SIZE {
+            return Ok(NetworkResult::invalid_message("block is too large"));
+        }
+
+        let mut data = first_data;
+        data.reserve(size - data.len());
+        while data.len() < size {
+            let fba = network_result_try!(
+                rpc_processor
+                    .clone()
+                    .rpc_call_find_block(
+                        Destination::direct(node.clone()).with_safety(safety_selection),
+                        block_id,
+                        data.len() as u32,
+                    )
+                    .await?
+            );
+            if fba.answer.size as usize != size || fba.answer.data.is_empty() {
+                return Ok(NetworkResult::invalid_message(
+                    "block changed during download",
+                ));
+            }
+            data.extend_from_slice(&fba.answer.data);
+        }
+
+        // Blocks are content-addressed, so the data must hash to the block id
+        if !vcrypto.validate_hash(&data, &block_id.value) {
+            return Ok(NetworkResult::invalid_message(
+                "block failed hash validation",
+            ));
+        }
+
+        Ok(NetworkResult::value(data))
+    }
+
+    /// Handle a received 'Find Block' query
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn inbound_find_block(
+        &self,
+        block_id: TypedKey,
+        offset: u32,
+    ) -> VeilidAPIResult<NetworkResult<InboundFindBlockResult>> {
+        // See if we have the block ourselves
+        if let Some(block) = self.unlocked_inner.block_store.load_block(block_id).await? {
+            let offset = offset as usize;
+            if offset > block.len() {
+                return Ok(NetworkResult::invalid_message(
+                    "find block offset past end of block",
+                ));
+            }
+            let end = usize::min(offset + MAX_FIND_BLOCK_A_DATA_LEN, block.len());
+            return Ok(NetworkResult::value(InboundFindBlockResult {
+                data: block[offset..end].to_vec(),
+                size: block.len() as u32,
+                suppliers: vec![],
+            }));
+        }
+
+        // Otherwise return the suppliers we know of
+        let mut inner = self.lock().await?;
+        let suppliers = inner
+            .block_suppliers
+            .get_suppliers(block_id, Timestamp::now());
+
+        Ok(NetworkResult::value(InboundFindBlockResult {
+            data: vec![],
+            size: 0,
+            suppliers,
+        }))
+    }
+}
@@ -368,6 +368,7 @@ impl StorageManager {
                     subkey,
                     get_result_value.clone(),
                     WatchUpdateMode::UpdateAll,
+                    None,
                 )
                 .await?;
         }
@@ -0,0 +1,300 @@
+use super::*;
+
+/// Info tracked per subkey
+struct SubkeyValueCount {
+    /// The latest value of the subkey, may be the value passed in
+    pub value: Option<Arc<SignedValueData>>,
+    /// The nodes that have returned the value so far (up to the consensus count)
+    pub value_nodes: Vec<NodeRef>,
+}
+
+/// The context of the outbound_get_values operation
+struct OutboundGetValuesContext {
+    /// The latest values and result counts so far, one per subkey
+    pub valuecounts: Vec<SubkeyValueCount>,
+}
+
+/// The result of the outbound_get_values operation
+pub(super) struct OutboundGetValuesResult {
+    /// Fanout results for each subkey
+    pub fanout_results: Vec<FanoutResult>,
+    /// The latest value found for each subkey
+    pub values: Vec<Option<Arc<SignedValueData>>>,
+}
+
+/// The result of the inbound_get_values operation
+pub(crate) struct InboundGetValuesResult {
+    /// The values of the requested subkeys that are set, in ascending subkey order
+    pub values: Vec<(ValueSubkey, Arc<SignedValueData>)>,
+    /// The descriptor if one was requested
+    pub opt_descriptor: Option<Arc<SignedValueDescriptor>>,
+}
+
+impl StorageManager {
+    /// Perform a batched 'get value' query for a set of subkeys on the network
+    /// Each node visited by the fanout is asked for all of the subkeys at once
+    #[instrument(level = "trace", target = "dht", skip_all, err)]
+    pub(super) async fn outbound_get_values(
+        &self,
+        rpc_processor: RPCProcessor,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        safety_selection: SafetySelection,
+        descriptor: Arc<SignedValueDescriptor>,
+        local_values: Vec<Option<Arc<SignedValueData>>>,
+    ) -> VeilidAPIResult<OutboundGetValuesResult> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Get the DHT parameters for 'GetValue'
+        let (key_count, consensus_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.get_value_count as usize,
+                c.network.dht.get_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.get_value_timeout_ms)),
+            )
+        };
+
+        // Get the nodes we know are caching this value to seed the fanout
+        let init_fanout_queue = {
+            let inner = self.inner.lock().await;
+            inner.get_value_nodes(key)?.unwrap_or_default()
+        };
+
+        // Parse the schema
+        let schema = descriptor.schema()?;
+
+        // Make do-get-values answer context
+        let context = Arc::new(Mutex::new(OutboundGetValuesContext {
+            valuecounts: local_values
+                .into_iter()
+                .map(|value| SubkeyValueCount {
+                    value,
+                    value_nodes: vec![],
+                })
+                .collect(),
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = |next_node: NodeRef| {
+            let rpc_processor = rpc_processor.clone();
+            let context = context.clone();
+            let descriptor = descriptor.clone();
+            let schema = schema.clone();
+            let subkeys = subkeys.clone();
+            async move {
+                // Ask for the remaining subkeys until the node has returned all it has
+                let mut remaining_subkeys = subkeys.clone();
+                loop {
+                    let gva = network_result_try!(
+                        rpc_processor
+                            .clone()
+                            .rpc_call_get_values(
+                                Destination::direct(next_node.clone())
+                                    .with_safety(safety_selection),
+                                key,
+                                remaining_subkeys.clone(),
+                                Some((*descriptor).clone()),
+                            )
+                            .await?
+                    );
+                    let answer = gva.answer;
+                    let opt_last_subkey = answer.values.last().map(|x| x.0);
+
+                    log_dht!(debug "GetValues got values back: len={}", answer.values.len());
+
+                    {
+                        let mut ctx = context.lock();
+                        for (subkey, value) in answer.values {
+                            let Some(idx) = subkeys.idx_of_subkey(subkey) else {
+                                return Ok(NetworkResult::invalid_message(
+                                    "GetValues returned unrequested subkey",
+                                ));
+                            };
+
+                            // Validate with schema
+                            if !schema.check_subkey_value_data(
+                                descriptor.owner(),
+                                subkey,
+                                value.value_data(),
+                            ) {
+                                // Validation failed, ignore these values
+                                // Move to the next node
+                                return Ok(NetworkResult::invalid_message(format!(
+                                    "Schema validation failed on subkey {}",
+                                    subkey
+                                )));
+                            }
+
+                            let valuecount = &mut ctx.valuecounts[idx];
+
+                            // If we have a prior value, see if this is a newer sequence number
+                            if let Some(prior_value) = &valuecount.value {
+                                let prior_seq = prior_value.value_data().seq();
+                                let new_seq = value.value_data().seq();
+
+                                if new_seq == prior_seq {
+                                    // If sequence number is the same, the data should be the same
+                                    if prior_value.value_data() != value.value_data() {
+                                        // Move to the next node
+                                        return Ok(NetworkResult::invalid_message(
+                                            "value data mismatch",
+                                        ));
+                                    }
+                                    // Increase the consensus count for the existing value
+                                    valuecount.value_nodes.push(next_node.clone());
+                                } else if new_seq > prior_seq {
+                                    // If the sequence number is greater, start over with the new value
+                                    valuecount.value = Some(Arc::new(value));
+                                    // One node has shown us this value so far
+                                    valuecount.value_nodes = vec![next_node.clone()];
+                                } else {
+                                    // If the sequence number is older, ignore it
+                                }
+                            } else {
+                                // If we have no prior value, keep it
+                                valuecount.value = Some(Arc::new(value));
+                                // One node has shown us this value so far
+                                valuecount.value_nodes = vec![next_node.clone()];
+                            }
+                        }
+                    }
+
+                    // If the answer was truncated, ask again for the subkeys after the last one returned
+                    let (true, Some(last_subkey)) = (answer.truncated, opt_last_subkey) else {
+                        // Return peers if we have some
+                        log_network_result!(debug "GetValues fanout call returned peers {}", answer.peers.len());
+
+                        return Ok(NetworkResult::value(answer.peers));
+                    };
+                    remaining_subkeys = remaining_subkeys
+                        .difference(&ValueSubkeyRangeSet::single_range(0, last_subkey));
+                    if remaining_subkeys.is_empty() {
+                        return Ok(NetworkResult::value(answer.peers));
+                    }
+                }
+            }
+            .instrument(tracing::trace_span!("outbound_get_values fanout routine"))
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = |_closest_nodes: &[NodeRef]| {
+            // If we have reached sufficient consensus on all subkeys, return done
+            let ctx = context.lock();
+            let has_consensus = ctx
+                .valuecounts
+                .iter()
+                .all(|vc| vc.value.is_some() && vc.value_nodes.len() >= consensus_count);
+            if !ctx.valuecounts.is_empty() && has_consensus {
+                return Some(());
+            }
+            None
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            key,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(vec![CAP_DHT]),
+            call_routine,
+            check_done,
+        );
+
+        let kind = match fanout_call.run(init_fanout_queue).await {
+            // If we don't finish in the timeout (too much time passed checking for consensus)
+            TimeoutOr::Timeout => FanoutResultKind::Timeout,
+            // If we finished with or without consensus (enough nodes returning the same value)
+            TimeoutOr::Value(Ok(Some(()))) => FanoutResultKind::Finished,
+            // If we ran out of nodes before getting consensus)
+            TimeoutOr::Value(Ok(None)) => FanoutResultKind::Exhausted,
+            // Failed
+            TimeoutOr::Value(Err(e)) => {
+                // If we finished with an error, return that
+                log_dht!(debug "GetValues Fanout Error: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let ctx = context.lock();
+        let mut fanout_results = vec![];
+        for vc in &ctx.valuecounts {
+            let has_consensus = vc.value_nodes.len() >= consensus_count;
+            let fanout_result = FanoutResult {
+                kind: if has_consensus {
+                    FanoutResultKind::Finished
+                } else {
+                    kind
+                },
+                value_nodes: vc.value_nodes.clone(),
+            };
+            fanout_results.push(fanout_result);
+        }
+
+        log_network_result!(debug "GetValues Fanout ({:?}):\n{}", kind, debug_fanout_results(&fanout_results));
+
+        Ok(OutboundGetValuesResult {
+            fanout_results,
+            values: ctx.valuecounts.iter().map(|vc| vc.value.clone()).collect(),
+        })
+    }
+
+    /// Handle a received batched 'Get Value' query
+    #[instrument(level = "trace", target = "dht", skip_all)]
+    pub async fn inbound_get_values(
+        &self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        want_descriptor: bool,
+    ) -> VeilidAPIResult<NetworkResult<InboundGetValuesResult>> {
+        let mut inner = self.lock().await?;
+
+        // See if this is a remote or local record
+        // If this is local, it must have a descriptor already
+        let local_get_result = inner.handle_get_local_value(key, 0, true).await?;
+        let (is_local, opt_descriptor) = if local_get_result.opt_descriptor.is_some() {
+            (true, local_get_result.opt_descriptor)
+        } else {
+            let remote_get_result = inner.handle_get_remote_value(key, 0, true).await?;
+            (false, remote_get_result.opt_descriptor)
+        };
+
+        // If we don't have the record, we have nothing to return
+        let Some(descriptor) = opt_descriptor else {
+            return Ok(NetworkResult::value(InboundGetValuesResult {
+                values: vec![],
+                opt_descriptor: None,
+            }));
+        };
+        let Ok(schema) = descriptor.schema() else {
+            return Ok(NetworkResult::invalid_message("invalid schema"));
+        };
+
+        // Only return the subkeys that are in the schema
+        let subkeys = schema.truncate_subkeys(&subkeys, Some(MAX_GET_VALUES_Q_SUBKEYS_LEN));
+
+        let mut values = Vec::new();
+        for subkey in subkeys.iter() {
+            let get_result = if is_local {
+                inner.handle_get_local_value(key, subkey, false).await?
+            } else {
+                inner.handle_get_remote_value(key, subkey, false).await?
+            };
+            if let Some(value) = get_result.opt_value {
+                values.push((subkey, value));
+            }
+        }
+
+        Ok(NetworkResult::value(InboundGetValuesResult {
+            values,
+            opt_descriptor: if want_descriptor {
+                Some(descriptor)
+            } else {
+                None
+            },
+        }))
+    }
+}
@@ -1,20 +1,37 @@
+#[cfg(feature = "unstable-blockstore")]
+mod block_supplier_table;
 mod debug;
+#[cfg(feature = "unstable-blockstore")]
+mod find_block;
 mod get_value;
+mod get_values;
 mod inspect_value;
+mod pin_value;
 mod record_store;
 mod set_value;
+mod set_values;
 mod storage_manager_inner;
+mod stream_value;
+#[cfg(feature = "unstable-blockstore")]
+mod supply_block;
 mod tasks;
 mod types;
 mod watch_value;
 
+pub mod tests;
+
 use super::*;
+#[cfg(feature = "unstable-blockstore")]
+use block_supplier_table::*;
 use network_manager::*;
 use record_store::*;
 use routing_table::*;
 use rpc_processor::*;
 use storage_manager_inner::*;
 
+#[cfg(feature = "unstable-blockstore")]
+pub(crate) use find_block::InboundFindBlockResult;
+pub(crate) use get_values::InboundGetValuesResult;
 pub use record_store::{WatchParameters, WatchResult};
 pub use types::*;
 
@@ -22,6 +39,18 @@ pub use types::*;
 const MAX_SUBKEY_SIZE: usize = ValueData::MAX_LEN;
 /// The maximum total size of all subkeys of a record
 const MAX_RECORD_DATA_SIZE: usize = 1_048_576;
+/// The maximum size of a single block
+#[cfg(feature = "unstable-blockstore")]
+const MAX_BLOCK_SIZE: usize = 1_048_576;
+/// How long a node keeps a block supplier entry before the supplier must refresh it
+#[cfg(feature = "unstable-blockstore")]
+const BLOCK_SUPPLIER_EXPIRATION_US: u64 = 3_600_000_000u64;
+/// The maximum number of suppliers remembered for a single block
+#[cfg(feature = "unstable-blockstore")]
+const MAX_BLOCK_SUPPLIERS: usize = 10;
+/// The maximum number of blocks to remember suppliers for
+#[cfg(feature = "unstable-blockstore")]
+const MAX_BLOCK_SUPPLIER_RECORDS: usize = 4096;
 /// Frequency to flush record stores to disk
 const FLUSH_RECORD_STORES_INTERVAL_SECS: u32 = 1;
 /// Frequency to check for offline subkeys writes to send to the network
@@ -32,6 +61,8 @@ const SEND_VALUE_CHANGES_INTERVAL_SECS: u32 = 1;
 const CHECK_ACTIVE_WATCHES_INTERVAL_SECS: u32 = 1;
 /// Frequency to check for expired server-side watched records
 const CHECK_WATCHED_RECORDS_INTERVAL_SECS: u32 = 1;
+/// Frequency to re-replicate the records this node has committed to retain for their owners
+const REPUBLISH_PINNED_RECORDS_INTERVAL_SECS: u32 = 600;
 
 #[derive(Debug, Clone)]
 /// A single 'value changed' message to send
@@ -57,6 +88,7 @@ struct StorageManagerUnlockedInner {
     send_value_changes_task: TickTask<EyreReport>,
     check_active_watches_task: TickTask<EyreReport>,
     check_watched_records_task: TickTask<EyreReport>,
+    republish_pinned_records_task: TickTask<EyreReport>,
 
     // Anonymous watch keys
     anonymous_watch_keys: TypedKeyPairGroup,
@@ -109,6 +141,10 @@ impl StorageManager {
                 "check_watched_records_task",
                 CHECK_WATCHED_RECORDS_INTERVAL_SECS,
             ),
+            republish_pinned_records_task: TickTask::new(
+                "republish_pinned_records_task",
+                REPUBLISH_PINNED_RECORDS_INTERVAL_SECS,
+            ),
 
             anonymous_watch_keys,
         }
@@ -231,6 +267,23 @@ impl StorageManager {
             .collect()
     }
 
+    /// Get the usage of the local and remote record stores, if they are initialized
+    pub async fn get_record_store_stats(
+        &self,
+    ) -> (Option<RecordStoreStats>, Option<RecordStoreStats>) {
+        let inner = self.inner.lock().await;
+        (
+            inner.local_record_store.as_ref().map(|rs| rs.stats()),
+            inner.remote_record_store.as_ref().map(|rs| rs.stats()),
+        )
+    }
+
+    /// Write any pending changes to the record stores and metadata out to the table store
+    pub async fn flush(&self) -> VeilidAPIResult<()> {
+        let mut inner = self.lock().await?;
+        inner.flush().await.map_err(VeilidAPIError::internal)
+    }
+
     /// Create a local record from scratch with a new owner key, open it, and return the opened descriptor
     #[instrument(level = "trace", target = "stor", skip_all)]
     pub async fn create_record(
@@ -495,6 +548,10 @@ impl StorageManager {
     }
 
     /// Set the value of a subkey on an opened local record
+    ///
+    /// If `opt_expected_seq` is specified, the value is only set if the last known sequence number
+    /// of the subkey is `expected_seq`, or `ValueSeqNum::MAX` if the subkey is expected to be unset.
+    /// A mismatch, either locally or from the network, returns `VeilidAPIError::SeqMismatch`.
     #[instrument(level = "trace", target = "stor", skip_all)]
     pub async fn set_value(
         &self,
@@ -502,6 +559,7 @@ impl StorageManager {
         subkey: ValueSubkey,
         data: Vec<u8>,
         writer: Option<KeyPair>,
+        opt_expected_seq: Option<ValueSeqNum>,
     ) -> VeilidAPIResult<Option<ValueData>> {
         let mut inner = self.lock().await?;
 
@@ -537,6 +595,18 @@ impl StorageManager {
         };
         let schema = descriptor.schema()?;
 
+        // Check the expected sequence number against the last known local value
+        if let Some(expected_seq) = opt_expected_seq {
+            let last_seq = last_get_result
+                .opt_value
+                .as_ref()
+                .map(|v| v.value_data().seq())
+                .unwrap_or(ValueSeqNum::MAX);
+            if last_seq != expected_seq {
+                apibail_seq_mismatch!(expected_seq, last_seq);
+            }
+        }
+
         // Make new subkey data
         let value_data = if let Some(last_signed_value_data) = last_get_result.opt_value {
             if last_signed_value_data.value_data().data() == data
@@ -567,16 +637,25 @@ impl StorageManager {
             writer.secret,
         )?);
 
-        // Write the value locally first
-        log_stor!(debug "Writing subkey locally: {}:{} len={}", key, subkey, signed_value_data.value_data().data().len() );
-        inner
-            .handle_set_local_value(
-                key,
-                subkey,
-                signed_value_data.clone(),
-                WatchUpdateMode::NoUpdate,
-            )
-            .await?;
+        // A conditional write can not be checked against the network while offline
+        if opt_expected_seq.is_some() && Self::online_ready_inner(&inner).is_none() {
+            apibail_try_again!("offline, try again later");
+        }
+
+        // Write the value locally first, unless it is conditional,
+        // in which case it is only stored once the network has accepted it
+        if opt_expected_seq.is_none() {
+            log_stor!(debug "Writing subkey locally: {}:{} len={}", key, subkey, signed_value_data.value_data().data().len() );
+            inner
+                .handle_set_local_value(
+                    key,
+                    subkey,
+                    signed_value_data.clone(),
+                    WatchUpdateMode::NoUpdate,
+                    None,
+                )
+                .await?;
+        }
 
         // Get rpc processor and drop mutex so we don't block while getting the value from the network
         let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
@@ -600,14 +679,17 @@ impl StorageManager {
                 safety_selection,
                 signed_value_data.clone(),
                 descriptor,
+                opt_expected_seq.is_some(),
             )
             .await
         {
             Ok(v) => v,
             Err(e) => {
-                // Failed to write, try again later
-                let mut inner = self.lock().await?;
-                inner.add_offline_subkey_write(key, subkey, safety_selection);
+                // Failed to write, try again later, unless the write was conditional
+                if opt_expected_seq.is_none() {
+                    let mut inner = self.lock().await?;
+                    inner.add_offline_subkey_write(key, subkey, safety_selection);
+                }
                 return Err(e);
             }
         };
@@ -626,6 +708,7 @@ impl StorageManager {
                 subkey,
                 signed_value_data.value_data().clone(),
                 safety_selection,
+                opt_expected_seq,
                 result,
             )
             .await?;
@@ -641,12 +724,390 @@ impl StorageManager {
                 out.clone()
                     .unwrap_or_else(|| signed_value_data.value_data().clone()),
                 safety_selection,
+                opt_expected_seq,
+            );
+        }
+
+        // A newer value on the network means the precondition failed
+        if let (Some(expected_seq), Some(newer)) = (opt_expected_seq, &out) {
+            apibail_seq_mismatch!(expected_seq, newer.seq());
+        }
+
+        Ok(out)
+    }
+
+    /// Get the values of a set of subkeys from an opened local record
+    ///
+    /// Subkeys missing locally are fetched from the network in a single fanout, asking each node
+    /// for all of the subkeys at once. Returns one value per subkey, in ascending subkey order,
+    /// which is `None` if the subkey has never been set.
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn get_values(
+        &self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Vec<Option<ValueData>>> {
+        #[allow(clippy::unnecessary_cast)]
+        if subkeys.len() as u64 > MAX_GET_VALUES_Q_SUBKEYS_LEN as u64 {
+            apibail_invalid_argument!("too many subkeys", "subkeys", subkeys);
+        }
+
+        let mut inner = self.lock().await?;
+        let safety_selection = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            opened_record.safety_selection()
+        };
+
+        // Get the descriptor and ensure all the subkeys are in the schema
+        let Some(descriptor) = inner
+            .handle_get_local_value(key, 0, true)
+            .await?
+            .opt_descriptor
+        else {
+            apibail_generic!("must have a descriptor");
+        };
+        let schema = descriptor.schema()?;
+        if let Some(last_subkey) = subkeys.last() {
+            if last_subkey > schema.max_subkey() {
+                apibail_invalid_argument!("subkey out of range", "subkeys", subkeys);
+            }
+        }
+
+        // See which of the requested subkeys are in our local record store
+        let mut local_values = Vec::new();
+        for subkey in subkeys.iter() {
+            let last_get_result = inner.handle_get_local_value(key, subkey, false).await?;
+            local_values.push(last_get_result.opt_value);
+        }
+
+        // Return the existing values if we have all of them unless we are forcing a refresh
+        let have_all = local_values.iter().all(|v| v.is_some());
+        if !force_refresh && have_all {
+            return Ok(local_values
+                .into_iter()
+                .map(|v| v.map(|v| v.value_data().clone()))
+                .collect());
+        }
+
+        // Refresh if we can
+
+        // Get rpc processor and drop mutex so we don't block while getting the values from the network
+        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+            // Return the existing values if we have all of them if we aren't online
+            if have_all {
+                return Ok(local_values
+                    .into_iter()
+                    .map(|v| v.map(|v| v.value_data().clone()))
+                    .collect());
+            }
+            apibail_try_again!("offline, try again later");
+        };
+
+        // Drop the lock for network access
+        drop(inner);
+
+        // Use the safety selection we opened the record with
+        let result = self
+            .outbound_get_values(
+                rpc_processor,
+                key,
+                subkeys.clone(),
+                safety_selection,
+                descriptor,
+                local_values.clone(),
+            )
+            .await?;
+
+        // Regain the lock after network access
+        let mut inner = self.lock().await?;
+
+        // Keep the list of nodes that returned a value for later reference
+        inner.process_fanout_results(key, subkeys.iter().zip(result.fanout_results.iter()), false);
+
+        // Record the newer values and send updates since they are different than what we had
+        let mut out = Vec::with_capacity(result.values.len());
+        for ((subkey, local_value), value) in subkeys
+            .iter()
+            .zip(local_values.iter())
+            .zip(result.values.into_iter())
+        {
+            if let Some(value) = &value {
+                let is_newer = match local_value {
+                    Some(local_value) => value.value_data().seq() > local_value.value_data().seq(),
+                    None => true,
+                };
+                if is_newer {
+                    inner
+                        .handle_set_local_value(
+                            key,
+                            subkey,
+                            value.clone(),
+                            WatchUpdateMode::UpdateAll,
+                            None,
+                        )
+                        .await?;
+                }
+            }
+            out.push(value.map(|v| v.value_data().clone()));
+        }
+
+        Ok(out)
+    }
+
+    /// Set the values of a set of subkeys on an opened local record
+    ///
+    /// The values must be in ascending subkey order. All of the values are written locally first
+    /// and then sent to the network in a single fanout, sending each node all of the values at once.
+    /// Returns one entry per value, which is `None` if the value was set, or the newer value that
+    /// the network holds for that subkey if it was not.
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn set_values(
+        &self,
+        key: TypedKey,
+        values: Vec<(ValueSubkey, Vec<u8>)>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<Vec<Option<ValueData>>> {
+        if values.len() > MAX_GET_VALUES_Q_SUBKEYS_LEN {
+            apibail_invalid_argument!("too many values", "values.len()", values.len());
+        }
+        if values.windows(2).any(|w| w[0].0 >= w[1].0) {
+            apibail_invalid_argument!(
+                "values must be in ascending subkey order",
+                "values",
+                values
+                    .iter()
+                    .map(|v| v.0.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+        }
+
+        let mut inner = self.lock().await?;
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let (safety_selection, opt_writer) = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            (
+                opened_record.safety_selection(),
+                opened_record.writer().cloned(),
+            )
+        };
+
+        // Use the specified writer, or if not specified, the default writer when the record was opened
+        let opt_writer = writer.or(opt_writer);
+
+        // If we don't have a writer then we can't write
+        let Some(writer) = opt_writer else {
+            apibail_generic!("value is not writable");
+        };
+
+        // Get the descriptor and schema for the key
+        let Some(descriptor) = inner
+            .handle_get_local_value(key, 0, true)
+            .await?
+            .opt_descriptor
+        else {
+            apibail_generic!("must have a descriptor");
+        };
+        let schema = descriptor.schema()?;
+
+        // Make the new subkey data, skipping subkeys that are not changing
+        let mut signed_values = Vec::with_capacity(values.len());
+        for (subkey, data) in values.iter().cloned() {
+            let last_get_result = inner.handle_get_local_value(key, subkey, false).await?;
+            let value_data = if let Some(last_signed_value_data) = last_get_result.opt_value {
+                if last_signed_value_data.value_data().data() == data
+                    && last_signed_value_data.value_data().writer() == &writer.key
+                {
+                    // Data and writer is the same, nothing is changing
+                    continue;
+                }
+                let seq = last_signed_value_data.value_data().seq();
+                ValueData::new_with_seq(seq + 1, data, writer.key)?
+            } else {
+                ValueData::new(data, writer.key)?
+            };
+
+            // Validate with schema
+            if !schema.check_subkey_value_data(descriptor.owner(), subkey, &value_data) {
+                // Validation failed, ignore these values
+                apibail_generic!("failed schema validation");
+            }
+
+            // Sign the new value data with the writer
+            let signed_value_data = Arc::new(SignedValueData::make_signature(
+                value_data,
+                descriptor.owner(),
+                subkey,
+                vcrypto.clone(),
+                writer.secret,
+            )?);
+            signed_values.push((subkey, signed_value_data));
+        }
+
+        // Write the values locally first
+        for (subkey, signed_value_data) in &signed_values {
+            log_stor!(debug "Writing subkey locally: {}:{} len={}", key, subkey, signed_value_data.value_data().data().len() );
+            inner
+                .handle_set_local_value(
+                    key,
+                    *subkey,
+                    signed_value_data.clone(),
+                    WatchUpdateMode::NoUpdate,
+                    None,
+                )
+                .await?;
+        }
+
+        // If nothing changed, no network activity needs to happen
+        let mut out = vec![None; values.len()];
+        if signed_values.is_empty() {
+            return Ok(out);
+        }
+
+        // Get rpc processor and drop mutex so we don't block while getting the value from the network
+        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+            for (subkey, _) in &signed_values {
+                log_stor!(debug "Writing subkey offline: {}:{}", key, subkey);
+                // Add to offline writes to flush
+                inner.add_offline_subkey_write(key, *subkey, safety_selection);
+            }
+            return Ok(out);
+        };
+
+        // Drop the lock for network access
+        drop(inner);
+
+        log_stor!(debug "Writing subkeys to the network: {} values={}", key, signed_values.len());
+
+        // Use the safety selection we opened the record with
+        let result = match self
+            .outbound_set_values(
+                rpc_processor,
+                key,
+                safety_selection,
+                signed_values.clone(),
+                descriptor,
+            )
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                // Failed to write, try again later
+                let mut inner = self.lock().await?;
+                for (subkey, _) in &signed_values {
+                    inner.add_offline_subkey_write(key, *subkey, safety_selection);
+                }
+                return Err(e);
+            }
+        };
+
+        {
+            // Regain the lock after network access
+            let mut inner = self.lock().await?;
+
+            // Report on fanout results offline
+            for ((subkey, _), fanout_result) in signed_values.iter().zip(&result.fanout_results) {
+                let was_offline = self.check_fanout_set_offline(key, *subkey, fanout_result);
+                if was_offline {
+                    // Failed to write, try again later
+                    inner.add_offline_subkey_write(key, *subkey, safety_selection);
+                }
+            }
+
+            // Keep the list of nodes that returned a value for later reference
+            inner.process_fanout_results(
+                key,
+                signed_values
+                    .iter()
+                    .map(|x| x.0)
+                    .zip(result.fanout_results.iter()),
+                true,
             );
         }
 
+        // Fetch the newer values the network holds for the subkeys that were not set
+        if !result.newer_subkeys.is_empty() {
+            let newer_values = self
+                .get_values(key, result.newer_subkeys.clone(), true)
+                .await?;
+            for (subkey, newer_value) in result.newer_subkeys.iter().zip(newer_values) {
+                if let Some(idx) = values.iter().position(|v| v.0 == subkey) {
+                    out[idx] = newer_value;
+                }
+            }
+        }
+
         Ok(out)
     }
 
+    /// Ask pinning service nodes on the network to retain and re-replicate a record until the expiration
+    /// The record must be open with the owner keypair as its writer. An expiration of zero unpins the record.
+    /// Returns the earliest expiration granted by the nodes that accepted the pin, or zero if none did
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn pin_record(
+        &self,
+        key: TypedKey,
+        expiration: Timestamp,
+    ) -> VeilidAPIResult<Timestamp> {
+        if expiration.as_u64() != 0 && expiration <= Timestamp::now() {
+            apibail_invalid_argument!("expiration is in the past", "expiration", expiration);
+        }
+
+        let mut inner = self.lock().await?;
+
+        // Get the safety selection and the writer we opened this record with
+        let (safety_selection, opt_writer) = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            (
+                opened_record.safety_selection(),
+                opened_record.writer().cloned(),
+            )
+        };
+
+        // Get the descriptor and the values to replicate
+        let Some(record_values) = inner.get_local_record_values(key).await? else {
+            apibail_generic!("must have a descriptor");
+        };
+
+        // Only the owner can pin a record
+        let Some(owner) = opt_writer.filter(|w| w.key == *record_values.descriptor.owner()) else {
+            apibail_generic!("record must be opened with the owner as the writer");
+        };
+
+        // Get rpc processor and drop mutex so we don't block while pinning the record on the network
+        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+            apibail_try_again!("offline, try again later");
+        };
+        drop(inner);
+
+        // Use the safety selection we opened the record with
+        let result = self
+            .outbound_pin_value(
+                rpc_processor,
+                key,
+                safety_selection,
+                record_values.values,
+                record_values.descriptor,
+                owner,
+                expiration,
+            )
+            .await?;
+
+        Ok(result.expiration)
+    }
+
     /// Create,update or cancel an outbound watch to a DHT value
     #[instrument(level = "trace", target = "stor", skip_all)]
     pub async fn watch_values(
@@ -829,6 +1290,29 @@ impl StorageManager {
         Ok(true)
     }
 
+    /// Get the status of the subkeys of a DHT record that were written offline
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn get_record_sync_status(
+        &self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<DHTRecordSyncStatus> {
+        let inner = self.lock().await?;
+        Ok(inner.get_record_sync_status(key))
+    }
+
+    /// Acknowledge conflicts in the subkeys of a DHT record that were written offline
+    /// If the subkey range is empty, all conflicts for the record are cleared
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn clear_record_conflicts(
+        &self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+    ) -> VeilidAPIResult<()> {
+        let mut inner = self.lock().await?;
+        inner.clear_offline_subkey_write_conflicts(key, subkeys);
+        Ok(())
+    }
+
     /// Inspect an opened DHT record for its subkey sequence numbers
     #[instrument(level = "trace", target = "stor", skip_all)]
     pub async fn inspect_record(
@@ -952,6 +1436,99 @@ impl StorageManager {
         ))
     }
 
+    /// Store a block locally, returning its block id
+    #[cfg(feature = "unstable-blockstore")]
+    #[instrument(level = "trace", target = "stor", skip(self, data), err)]
+    pub async fn store_block(&self, kind: CryptoKind, data: Vec<u8>) -> VeilidAPIResult<TypedKey> {
+        if data.is_empty() || data.len() > MAX_BLOCK_SIZE {
+            apibail_invalid_argument!("invalid block size", "data", data.len());
+        }
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        // Blocks are content-addressed by their hash
+        let block_id = TypedKey::new(kind, vcrypto.generate_hash(&data));
+        self.unlocked_inner
+            .block_store
+            .save_block(block_id, &data)
+            .await?;
+
+        Ok(block_id)
+    }
+
+    /// Get a block's data, from the local block store if we have it, or from the network if we don't
+    #[cfg(feature = "unstable-blockstore")]
+    #[instrument(level = "trace", target = "stor", skip(self), err)]
+    pub async fn find_block(
+        &self,
+        block_id: TypedKey,
+        safety_selection: SafetySelection,
+    ) -> VeilidAPIResult<Vec<u8>> {
+        // See if we have the block locally
+        if let Some(data) = self.unlocked_inner.block_store.load_block(block_id).await? {
+            return Ok(data);
+        }
+
+        let mut inner = self.lock().await?;
+
+        // Seed the search with any suppliers we know of ourselves
+        let known_suppliers = inner
+            .block_suppliers
+            .get_suppliers(block_id, Timestamp::now());
+
+        // Get rpc processor and drop mutex so we don't block while finding the block on the network
+        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+            apibail_try_again!("offline, try again later");
+        };
+
+        // Drop the lock for network access
+        drop(inner);
+
+        let Some(data) = self
+            .outbound_find_block(rpc_processor, block_id, safety_selection, known_suppliers)
+            .await?
+        else {
+            apibail_key_not_found!(block_id);
+        };
+
+        // Keep the block so we can serve it ourselves
+        self.unlocked_inner
+            .block_store
+            .save_block(block_id, &data)
+            .await?;
+
+        Ok(data)
+    }
+
+    /// Announce to the network that this node can supply a block it holds locally.
+    /// Supplier entries expire, so this must be repeated to keep supplying the block.
+    #[cfg(feature = "unstable-blockstore")]
+    #[instrument(level = "trace", target = "stor", skip(self), err)]
+    pub async fn supply_block(&self, block_id: TypedKey) -> VeilidAPIResult<bool> {
+        // We can only supply blocks we have
+        if self
+            .unlocked_inner
+            .block_store
+            .load_block(block_id)
+            .await?
+            .is_none()
+        {
+            apibail_key_not_found!(block_id);
+        }
+
+        // Get rpc processor and drop mutex so we don't block while announcing the block on the network
+        let rpc_processor = {
+            let inner = self.lock().await?;
+            let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+                apibail_try_again!("offline, try again later");
+            };
+            rpc_processor
+        };
+
+        self.outbound_supply_block(rpc_processor, block_id).await
+    }
+
     // Send single value change out to the network
     #[instrument(level = "trace", target = "stor", skip(self), err)]
     async fn send_value_change(&self, vc: ValueChangedInfo) -> VeilidAPIResult<()> {
@@ -0,0 +1,215 @@
+use super::*;
+
+/// The context of the outbound_pin_value operation
+struct OutboundPinValueContext {
+    /// The nodes that have accepted the pin so far (up to the consensus count)
+    pub pin_nodes: Vec<NodeRef>,
+    /// The earliest expiration granted by the nodes that accepted the pin
+    pub expiration: Option<Timestamp>,
+}
+
+/// The result of the outbound_pin_value operation
+pub(super) struct OutboundPinValueResult {
+    /// Fanout result, with the nodes that accepted the pin
+    pub fanout_result: FanoutResult,
+    /// The earliest expiration granted by the nodes that accepted the pin, zero if none did
+    pub expiration: Timestamp,
+}
+
+impl StorageManager {
+    /// Perform a 'pin value' query on the network, asking pinning service nodes to retain a record
+    /// Nodes that accept the pin are sent all of the record's values so they can keep it replicated
+    #[instrument(level = "trace", target = "dht", skip_all, err)]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn outbound_pin_value(
+        &self,
+        rpc_processor: RPCProcessor,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+        values: Vec<(ValueSubkey, Arc<SignedValueData>)>,
+        descriptor: Arc<SignedValueDescriptor>,
+        owner: KeyPair,
+        expiration: Timestamp,
+    ) -> VeilidAPIResult<OutboundPinValueResult> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Get the DHT parameters for 'PinValue', which uses the 'SetValue' parameters
+        let (key_count, consensus_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.set_value_count as usize,
+                c.network.dht.set_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.set_value_timeout_ms)),
+            )
+        };
+
+        // All nodes see the same request timestamp, so retries are ordered after this request
+        let timestamp = Timestamp::now();
+
+        // Split the values into pages that fit in a message once, for all of the pinning nodes
+        let pages = Arc::new(make_set_values_pages(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (subkey, value))| (idx, subkey, value)),
+        ));
+
+        // Make do-pin-value answer context
+        let context = Arc::new(Mutex::new(OutboundPinValueContext {
+            pin_nodes: vec![],
+            expiration: None,
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = |next_node: NodeRef| {
+            let rpc_processor = rpc_processor.clone();
+            let context = context.clone();
+            let descriptor = descriptor.clone();
+            let pages = pages.clone();
+            async move {
+                let dest = Destination::direct(next_node.clone()).with_safety(safety_selection);
+
+                // send across the wire
+                let pva = network_result_try!(
+                    rpc_processor
+                        .clone()
+                        .rpc_call_pin_value(
+                            dest.clone(),
+                            key,
+                            (*descriptor).clone(),
+                            timestamp,
+                            expiration,
+                            owner,
+                        )
+                        .await?
+                );
+
+                if !pva.answer.accepted {
+                    // Return peers if we have some
+                    log_network_result!(debug "PinValue not accepted, fanout call returned peers {}", pva.answer.peers.len());
+                    return Ok(NetworkResult::value(pva.answer.peers));
+                }
+
+                // Give the pinning node the values so it can replicate the record
+                if expiration.as_u64() != 0 {
+                    for page in pages.iter() {
+                        let sva = network_result_try!(
+                            rpc_processor
+                                .clone()
+                                .rpc_call_set_values(
+                                    dest.clone(),
+                                    key,
+                                    page.iter()
+                                        .map(|(_, subkey, value)| (*subkey, (**value).clone()))
+                                        .collect(),
+                                    (*descriptor).clone(),
+                                    true,
+                                )
+                                .await?
+                        );
+                        if !sva.answer.set {
+                            // Skip this node because it accepted the pin but would not store the record
+                            return Ok(NetworkResult::invalid_message(
+                                "pinned record values not stored",
+                            ));
+                        }
+                    }
+                }
+
+                let mut ctx = context.lock();
+                ctx.pin_nodes.push(next_node);
+                ctx.expiration = Some(
+                    ctx.expiration
+                        .map(|e| e.min(pva.answer.expiration))
+                        .unwrap_or(pva.answer.expiration),
+                );
+
+                // Return peers if we have some
+                log_network_result!(debug "PinValue fanout call returned peers {}", pva.answer.peers.len());
+
+                Ok(NetworkResult::value(pva.answer.peers))
+            }
+            .instrument(tracing::trace_span!("outbound_pin_value fanout routine"))
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = |_closest_nodes: &[NodeRef]| {
+            // If we have reached sufficient consensus, return done
+            let ctx = context.lock();
+            if ctx.pin_nodes.len() >= consensus_count {
+                return Some(());
+            }
+            None
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            key,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(vec![CAP_DHT_PIN]),
+            call_routine,
+            check_done,
+        );
+
+        let kind = match fanout_call.run(vec![]).await {
+            // If we don't finish in the timeout (too much time passed checking for consensus)
+            TimeoutOr::Timeout => FanoutResultKind::Timeout,
+            // If we finished with consensus (enough nodes accepting the pin)
+            TimeoutOr::Value(Ok(Some(()))) => FanoutResultKind::Finished,
+            // If we ran out of nodes before getting consensus)
+            TimeoutOr::Value(Ok(None)) => FanoutResultKind::Exhausted,
+            // Failed
+            TimeoutOr::Value(Err(e)) => {
+                // If we finished with an error, return that
+                log_dht!(debug "PinValue fanout error: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let ctx = context.lock();
+        let fanout_result = FanoutResult {
+            kind,
+            value_nodes: ctx.pin_nodes.clone(),
+        };
+        log_network_result!(debug "PinValue Fanout: {:?}", fanout_result);
+
+        Ok(OutboundPinValueResult {
+            fanout_result,
+            expiration: ctx.expiration.unwrap_or_default(),
+        })
+    }
+
+    /// Handle a received 'Pin Value' query
+    /// Returns the expiration granted if the pin was accepted, or None if it was not
+    #[instrument(level = "trace", target = "dht", skip_all)]
+    pub async fn inbound_pin_value(
+        &self,
+        key: TypedKey,
+        descriptor: Arc<SignedValueDescriptor>,
+        timestamp: Timestamp,
+        expiration: Timestamp,
+    ) -> VeilidAPIResult<NetworkResult<Option<Timestamp>>> {
+        let mut inner = self.lock().await?;
+        inner
+            .handle_pin_remote_record(
+                key,
+                descriptor,
+                RecordPin {
+                    timestamp,
+                    expiration,
+                },
+            )
+            .await
+    }
+
+    /// Check if this node has committed to retaining a record for its owner
+    #[instrument(level = "trace", target = "dht", skip_all, err)]
+    pub async fn is_pinned_record(&self, key: TypedKey) -> VeilidAPIResult<bool> {
+        let inner = self.lock().await?;
+        inner.is_pinned_remote_record(key)
+    }
+}
@@ -1,7 +1,8 @@
 /// RecordStore
 /// Keeps an LRU cache of dht keys and their associated subkey valuedata.
 /// Instances of this store are used for 'local' (persistent) and 'remote' (ephemeral) dht key storage.
-/// This store does not perform any validation on the schema, and all ValueRecordData passed in must have been previously validated.
+/// This store does not perform any validation on the schema, and all ValueRecordData passed in must have been previously validated,
+/// with the exception of schema rules that depend on the previous subkey value, such as the ALOG append-only log chain.
 /// Uses an in-memory store for the records, backed by the TableStore. Subkey data is LRU cached and rotated out by a limits policy,
 /// and backed to the TableStore for persistence.
 mod inspect_cache;
@@ -11,6 +12,7 @@ mod local_record_detail;
 mod opened_record;
 mod record;
 mod record_data;
+mod record_pin;
 mod record_store_limits;
 mod remote_record_detail;
 mod watch;
@@ -22,6 +24,7 @@ pub(super) use local_record_detail::*;
 pub(super) use opened_record::*;
 pub(super) use record::*;
 pub(super) use record_data::*;
+pub(super) use record_pin::*;
 pub(super) use record_store_limits::*;
 pub(super) use remote_record_detail::*;
 pub(super) use watch::*;
@@ -50,6 +53,7 @@ where
     D: fmt::Debug + Clone + Serialize + for<'d> Deserialize<'d>,
 {
     table_store: TableStore,
+    crypto: Crypto,
     name: String,
     limits: RecordStoreLimits,
 
@@ -103,7 +107,12 @@ impl<D> RecordStore<D>
 where
     D: fmt::Debug + Clone + Serialize + for<'d> Deserialize<'d>,
 {
-    pub fn new(table_store: TableStore, name: &str, limits: RecordStoreLimits) -> Self {
+    pub fn new(
+        table_store: TableStore,
+        crypto: Crypto,
+        name: &str,
+        limits: RecordStoreLimits,
+    ) -> Self {
         let subkey_cache_size = limits.subkey_cache_size;
         let limit_subkey_cache_total_size = limits
             .max_subkey_cache_memory_mb
@@ -114,6 +123,7 @@ where
 
         Self {
             table_store,
+            crypto,
             name: name.to_owned(),
             limits,
             record_table: None,
@@ -182,18 +192,20 @@ where
             }
 
             // add to index and ensure we deduplicate in the case of an error
-            if let Some(v) = self.record_index.insert_with_callback(ri.0, ri.1, |k, v| {
-                // If the configuration change, we only want to keep the 'limits.max_records' records
+            let (opt_duplicate, evicted) = self.insert_record_index(ri.0, ri.1);
+            if let Some(v) = opt_duplicate {
+                // This shouldn't happen, but deduplicate anyway
+                log_stor!(warn "duplicate record in table: {:?}", ri.0);
                 dead_records.push(DeadRecord {
-                    key: k,
+                    key: ri.0,
                     record: v,
                     in_total_storage: true,
                 });
-            }) {
-                // This shouldn't happen, but deduplicate anyway
-                log_stor!(warn "duplicate record in table: {:?}", ri.0);
+            }
+            // If the configuration change, we only want to keep the 'limits.max_records' records
+            for (k, v) in evicted {
                 dead_records.push(DeadRecord {
-                    key: ri.0,
+                    key: k,
                     record: v,
                     in_total_storage: true,
                 });
@@ -208,6 +220,43 @@ where
         Ok(())
     }
 
+    /// Insert a record into the LRU record index
+    /// Pinned records that would be evicted are moved back to the front of the LRU
+    /// so that unpinned records are evicted in their place. Only when every record
+    /// in the index is pinned will a pinned record be evicted.
+    /// Pins this store no longer allows, such as after a configuration change, do not protect a record.
+    /// Returns the duplicate record if one was replaced, and the evicted records
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    fn insert_record_index(
+        &mut self,
+        key: RecordTableKey,
+        record: Record<D>,
+    ) -> (Option<Record<D>>, Vec<(RecordTableKey, Record<D>)>) {
+        let cur_ts = Timestamp::now();
+
+        let mut evicted = Vec::new();
+        let opt_duplicate = self
+            .record_index
+            .insert_with_callback(key, record, |k, v| evicted.push((k, v)));
+
+        let mut dead = Vec::new();
+        let mut reinsert_budget = self.record_index.len();
+        while let Some((k, v)) = evicted.pop() {
+            if reinsert_budget == 0
+                || !v.is_pinned(cur_ts)
+                || !self.is_pin_allowed(k.key, v.owner())
+            {
+                dead.push((k, v));
+                continue;
+            }
+            reinsert_budget -= 1;
+            self.record_index
+                .insert_with_callback(k, v, |ek, ev| evicted.push((ek, ev)));
+        }
+
+        (opt_duplicate, dead)
+    }
+
     #[instrument(level = "trace", target = "stor", skip_all)]
     fn add_dead_record(&mut self, key: RecordTableKey, record: Record<D>) {
         self.dead_records.push(DeadRecord {
@@ -404,10 +453,8 @@ where
         self.total_storage_space.commit().unwrap();
 
         // Save to record index
-        let mut dead_records = Vec::new();
-        if let Some(v) = self.record_index.insert_with_callback(rtk, record, |k, v| {
-            dead_records.push((k, v));
-        }) {
+        let (opt_duplicate, dead_records) = self.insert_record_index(rtk, record);
+        if let Some(v) = opt_duplicate {
             // Shouldn't happen but log it
             log_stor!(warn "new duplicate record in table: {:?}", rtk);
             self.add_dead_record(rtk, v);
@@ -514,6 +561,68 @@ where
         out
     }
 
+    /// Get the keys and pins of all the records that have an unexpired pin this store allows
+    pub(super) fn pinned_records(&self, cur_ts: Timestamp) -> Vec<(TypedKey, RecordPin)> {
+        self.record_index
+            .iter()
+            .filter(|(rtk, record)| self.is_pin_allowed(rtk.key, record.owner()))
+            .filter_map(|(rtk, record)| {
+                record
+                    .pin()
+                    .filter(|p| !p.is_expired(cur_ts))
+                    .map(|p| (rtk.key, p))
+            })
+            .collect()
+    }
+
+    /// Check if this store accepts pins on the records of an owner
+    fn is_pin_allowed(&self, key: TypedKey, owner: &PublicKey) -> bool {
+        if self.limits.max_pinned_records == 0 {
+            return false;
+        }
+        match &self.limits.pin_allowed_owners {
+            Some(owners) => owners.contains(&TypedKey::new(key.kind, *owner)),
+            None => true,
+        }
+    }
+
+    /// Check a pin request against the pinning limits, returning the pin to apply if it is accepted
+    /// The expiration is shortened to the longest this store allows, and a record's own pin
+    /// does not count against the limits when it is renewed
+    pub(super) fn accept_pin(
+        &self,
+        key: TypedKey,
+        owner: &PublicKey,
+        pin: RecordPin,
+        cur_ts: Timestamp,
+    ) -> Option<RecordPin> {
+        if !self.is_pin_allowed(key, owner) {
+            return None;
+        }
+
+        let mut pinned_count = 0usize;
+        let mut owner_pinned_count = 0usize;
+        for (rtk, record) in self.record_index.iter() {
+            if rtk.key == key || !record.is_pinned(cur_ts) {
+                continue;
+            }
+            pinned_count += 1;
+            if rtk.key.kind == key.kind && record.owner() == owner {
+                owner_pinned_count += 1;
+            }
+        }
+        if pinned_count >= self.limits.max_pinned_records
+            || owner_pinned_count >= self.limits.max_pinned_records_per_owner
+        {
+            return None;
+        }
+
+        Some(RecordPin {
+            timestamp: pin.timestamp,
+            expiration: pin.expiration.min(cur_ts + self.limits.max_pin_expiration),
+        })
+    }
+
     #[instrument(level = "trace", target = "stor", skip_all, err)]
     pub async fn get_subkey(
         &mut self,
@@ -694,6 +803,9 @@ where
         }
     }
 
+    /// Set a subkey value
+    /// If an expected sequence number is given, the previous value of the subkey must have that
+    /// sequence number, or the subkey must be unset if it is ValueSeqNum::MAX, otherwise a SeqMismatch error is returned
     #[instrument(level = "trace", target = "stor", skip_all, err)]
     pub async fn set_subkey(
         &mut self,
@@ -701,6 +813,7 @@ where
         subkey: ValueSubkey,
         signed_value_data: Arc<SignedValueData>,
         watch_update_mode: WatchUpdateMode,
+        opt_expected_seq: Option<ValueSeqNum>,
     ) -> VeilidAPIResult<()> {
         // Check size limit for data
         if signed_value_data.value_data().data().len() > self.limits.max_subkey_size {
@@ -712,9 +825,15 @@ where
         }
 
         // Get record subkey count and total size of all record subkey data exclusive of structures
-        let Some((subkey_count, prior_record_data_size)) = self.with_record(key, |record| {
-            (record.subkey_count(), record.record_data_size())
-        }) else {
+        let Some((subkey_count, prior_record_data_size, schema)) =
+            self.with_record(key, |record| {
+                (
+                    record.subkey_count(),
+                    record.record_data_size(),
+                    record.schema(),
+                )
+            })
+        else {
             apibail_invalid_argument!("no record at this key", "key", key);
         };
 
@@ -730,6 +849,7 @@ where
 
         // Get the previous subkey and ensure we aren't going over the record size limit
         let mut prior_subkey_size = 0usize;
+        let mut opt_prior_record_data = None;
 
         // If subkey exists in subkey cache, use that
         let stk = SubkeyTableKey { key, subkey };
@@ -737,6 +857,7 @@ where
 
         if let Some(record_data) = self.subkey_cache.peek(&stk) {
             prior_subkey_size = record_data.data_size();
+            opt_prior_record_data = Some(record_data.clone());
         } else {
             // If not in cache, try to pull from table store
             if let Some(record_data) = subkey_table
@@ -745,9 +866,35 @@ where
                 .map_err(VeilidAPIError::internal)?
             {
                 prior_subkey_size = record_data.data_size();
+                opt_prior_record_data = Some(record_data);
+            }
+        }
+
+        // Check the sequence number precondition
+        if let Some(expected_seq) = opt_expected_seq {
+            let actual_seq = opt_prior_record_data
+                .as_ref()
+                .map(|x| x.signed_value_data().value_data().seq())
+                .unwrap_or(ValueSeqNum::MAX);
+            if actual_seq != expected_seq {
+                apibail_seq_mismatch!(expected_seq, actual_seq);
             }
         }
 
+        // Check the new value is a permitted update of the previous value, or a permitted first value
+        let Some(vcrypto) = self.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+        if !schema.check_subkey_value_update(
+            vcrypto,
+            opt_prior_record_data
+                .as_ref()
+                .map(|x| x.signed_value_data().value_data()),
+            signed_value_data.value_data(),
+        ) {
+            apibail_generic!("subkey value does not extend the previous value");
+        }
+
         // Make new record data
         let subkey_record_data = RecordData::new(signed_value_data);
 
@@ -1259,6 +1406,29 @@ where
         reclaimed
     }
 
+    pub fn stats(&self) -> RecordStoreStats {
+        RecordStoreStats {
+            record_count: self.record_index.len() as u32,
+            subkey_cache_count: self.subkey_cache.len() as u32,
+            subkey_cache_total_size: ByteCount::new(self.subkey_cache_total_size.get() as u64),
+            total_storage_space: ByteCount::new(self.total_storage_space.get()),
+            dead_record_count: self.dead_records.len() as u32,
+        }
+    }
+
+    pub fn debug_record_list(&self) -> Vec<DebugRecord> {
+        let cur_ts = Timestamp::now();
+        self.record_index
+            .iter()
+            .map(|(rik, rec)| DebugRecord {
+                key: rik.key,
+                age: cur_ts.saturating_sub(rec.last_touched()),
+                data_size: ByteCount::new(rec.record_data_size() as u64),
+                stored_subkeys: rec.stored_subkeys().clone(),
+            })
+            .collect()
+    }
+
     pub fn debug_records(&self) -> String {
         // Dump fields in an abbreviated way
         let mut out = String::new();
@@ -10,6 +10,8 @@ where
     stored_subkeys: ValueSubkeyRangeSet,
     last_touched_ts: Timestamp,
     record_data_size: usize,
+    #[serde(default)]
+    pin: Option<RecordPin>,
     detail: D,
 }
 
@@ -30,6 +32,7 @@ where
             stored_subkeys: ValueSubkeyRangeSet::new(),
             last_touched_ts: cur_ts,
             record_data_size: 0,
+            pin: None,
             detail,
         })
     }
@@ -68,6 +71,16 @@ where
         self.record_data_size
     }
 
+    pub fn pin(&self) -> Option<RecordPin> {
+        self.pin
+    }
+    pub fn set_pin(&mut self, pin: Option<RecordPin>) {
+        self.pin = pin;
+    }
+    pub fn is_pinned(&self, cur_ts: Timestamp) -> bool {
+        self.pin.map(|p| !p.is_expired(cur_ts)).unwrap_or_default()
+    }
+
     pub fn schema(&self) -> DHTSchema {
         // unwrap is safe here because descriptor is immutable and set in new()
         self.descriptor.schema().unwrap()
@@ -0,0 +1,16 @@
+use super::*;
+
+/// An owner-signed request for this node to retain a record and keep it replicated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(in crate::storage_manager) struct RecordPin {
+    /// The owner's timestamp for the pin request, newer requests replace older ones
+    pub timestamp: Timestamp,
+    /// When this node stops retaining the record
+    pub expiration: Timestamp,
+}
+
+impl RecordPin {
+    pub fn is_expired(&self, cur_ts: Timestamp) -> bool {
+        self.expiration <= cur_ts
+    }
+}
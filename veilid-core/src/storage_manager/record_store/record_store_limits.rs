@@ -1,7 +1,7 @@
 use super::*;
 
 /// Configuration for the record store
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct RecordStoreLimits {
     /// Number of subkeys to keep in the memory cache
     pub subkey_cache_size: usize,
@@ -23,4 +23,12 @@ pub struct RecordStoreLimits {
     pub max_watch_expiration: TimestampDuration,
     /// Min expiration duration per watch
     pub min_watch_expiration: TimestampDuration,
+    /// Max number of pinned records, zero if pins are not accepted
+    pub max_pinned_records: usize,
+    /// Max number of pinned records per record owner
+    pub max_pinned_records_per_owner: usize,
+    /// Max expiration duration per pin
+    pub max_pin_expiration: TimestampDuration,
+    /// Owners whose records may be pinned, or None to allow any owner
+    pub pin_allowed_owners: Option<HashSet<TypedKey>>,
 }
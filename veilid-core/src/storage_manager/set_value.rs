@@ -12,6 +12,8 @@ struct OutboundSetValueContext {
     pub schema: DHTSchema,
     /// If we should send a partial update with the current context
     pub send_partial_update: bool,
+    /// If nodes should only set the value if it directly follows their current value
+    pub expect_seq: bool,
 }
 
 /// The result of the outbound_set_value operation
@@ -34,6 +36,7 @@ impl StorageManager {
         safety_selection: SafetySelection,
         value: Arc<SignedValueData>,
         descriptor: Arc<SignedValueDescriptor>,
+        expect_seq: bool,
     ) -> VeilidAPIResult<flume::Receiver<VeilidAPIResult<OutboundSetValueResult>>> {
         let routing_table = rpc_processor.routing_table();
 
@@ -65,6 +68,7 @@ impl StorageManager {
             missed_since_last_set: 0,
             schema,
             send_partial_update: false,
+            expect_seq,
         }));
 
         // Routine to call to generate fanout
@@ -80,9 +84,9 @@ impl StorageManager {
                     let send_descriptor = true; // xxx check if next_node needs the descriptor or not
 
                     // get most recent value to send
-                    let value = {
+                    let (value, expect_seq) = {
                         let ctx = context.lock();
-                        ctx.value.clone()
+                        (ctx.value.clone(), ctx.expect_seq)
                     };
 
                     // send across the wire
@@ -97,6 +101,7 @@ impl StorageManager {
                                 (*value).clone(),
                                 (*descriptor).clone(),
                                 send_descriptor,
+                                expect_seq,
                             )
                             .await?
                     );
@@ -160,6 +165,14 @@ impl StorageManager {
                     let prior_seq = ctx.value.value_data().seq();
                     let new_seq = value.value_data().seq();
                     if new_seq < prior_seq {
+                        // A node that did not take a conditional set returns its current value,
+                        // which may be older than the value we are setting
+                        if expect_seq {
+                            ctx.missed_since_last_set += 1;
+                            log_network_result!(debug "SetValue seq mismatch: {}, fanout call returned peers {}", ctx.missed_since_last_set, sva.answer.peers.len());
+                            return Ok(NetworkResult::value(sva.answer.peers));
+                        }
+
                         // If the sequence number is older node should have not returned a value here.
                         // Skip this node and its closer list because it is misbehaving
                         // Ignore this value and pretend we never saw this node
@@ -169,6 +182,8 @@ impl StorageManager {
                     // If the sequence number is greater or equal, keep it
                     // even if the sequence number is the same, accept all conflicts in an attempt to resolve them
                     ctx.value = Arc::new(value);
+                    // The newer value is not ours, so it is sent unconditionally from now on
+                    ctx.expect_seq = false;
                     // One node has shown us this value so far
                     ctx.value_nodes = vec![next_node];
                     ctx.missed_since_last_set = 0;
@@ -288,6 +303,7 @@ impl StorageManager {
         subkey: ValueSubkey,
         last_value_data: ValueData,
         safety_selection: SafetySelection,
+        opt_expected_seq: Option<ValueSeqNum>,
     ) {
         let this = self.clone();
         let last_value_data = Arc::new(Mutex::new(last_value_data));
@@ -307,7 +323,7 @@ impl StorageManager {
                         };
                         let is_partial = result.fanout_result.kind.is_partial();
                         let lvd = last_value_data.lock().clone();
-                        let value_data = match this.process_outbound_set_value_result(key, subkey, lvd, safety_selection, result).await {
+                        let value_data = match this.process_outbound_set_value_result(key, subkey, lvd, safety_selection, opt_expected_seq, result).await {
                             Ok(Some(v)) => v,
                             Ok(None) => {
                                 return is_partial;
@@ -347,6 +363,10 @@ impl StorageManager {
         );
     }
 
+    /// Process a result from an outbound set value fanout
+    /// Returns Some(newer value) if the network kept a different value than the one we set
+    /// Conditional writes are never queued for offline replay, as the replay would not check the
+    /// expected sequence number again, and are only stored locally once a node has accepted them
     #[instrument(level = "trace", target = "stor", skip_all, err)]
     pub(super) async fn process_outbound_set_value_result(
         &self,
@@ -354,6 +374,7 @@ impl StorageManager {
         subkey: ValueSubkey,
         last_value_data: ValueData,
         safety_selection: SafetySelection,
+        opt_expected_seq: Option<ValueSeqNum>,
         result: set_value::OutboundSetValueResult,
     ) -> Result<Option<ValueData>, VeilidAPIError> {
         // Regain the lock after network access
@@ -361,7 +382,7 @@ impl StorageManager {
 
         // Report on fanout result offline
         let was_offline = self.check_fanout_set_offline(key, subkey, &result.fanout_result);
-        if was_offline {
+        if was_offline && opt_expected_seq.is_none() {
             // Failed to write, try again later
             inner.add_offline_subkey_write(key, subkey, safety_selection);
         }
@@ -378,12 +399,43 @@ impl StorageManager {
                     subkey,
                     result.signed_value_data.clone(),
                     WatchUpdateMode::UpdateAll,
+                    None,
                 )
                 .await?;
 
             return Ok(Some(result.signed_value_data.value_data().clone()));
         }
 
+        if let Some(expected_seq) = opt_expected_seq {
+            // A conditional write that no node accepted is dropped rather than replayed later
+            if result.fanout_result.value_nodes.is_empty() {
+                apibail_try_again!("conditional write was not accepted by any node");
+            }
+
+            // Store the accepted value locally, unless a previous result already did
+            let last_get_result = inner.handle_get_local_value(key, subkey, false).await?;
+            let already_set = last_get_result
+                .opt_value
+                .as_ref()
+                .map(|v| v.value_data() == result.signed_value_data.value_data())
+                .unwrap_or_default();
+            if !already_set {
+                if let Err(e) = inner
+                    .handle_set_local_value(
+                        key,
+                        subkey,
+                        result.signed_value_data.clone(),
+                        WatchUpdateMode::NoUpdate,
+                        Some(expected_seq),
+                    )
+                    .await
+                {
+                    // A newer value was stored locally while we were on the network, keep it
+                    log_stor!(debug "Conditional write superseded locally: {}:{} {}", key, subkey, e);
+                }
+            }
+        }
+
         // If the original value was set, return None
         Ok(None)
     }
@@ -391,6 +443,7 @@ impl StorageManager {
     /// Handle a received 'Set Value' query
     /// Returns a None if the value passed in was set
     /// Returns a Some(current value) if the value was older and the current value was kept
+    /// If expect_seq is set, the value is only set if it directly follows the current value
     #[instrument(level = "trace", target = "dht", skip_all)]
     pub async fn inbound_set_value(
         &self,
@@ -399,6 +452,7 @@ impl StorageManager {
         value: Arc<SignedValueData>,
         descriptor: Option<Arc<SignedValueDescriptor>>,
         target: Target,
+        expect_seq: bool,
     ) -> VeilidAPIResult<NetworkResult<Option<Arc<SignedValueData>>>> {
         let mut inner = self.lock().await?;
 
@@ -469,10 +523,27 @@ impl StorageManager {
             return Ok(NetworkResult::invalid_message("failed schema validation"));
         }
 
+        // If the set is conditional, the value must directly follow the one we have
+        // Nodes that do not have a value yet accept it
+        let opt_expected_seq = if expect_seq {
+            last_get_result
+                .opt_value
+                .as_ref()
+                .map(|_| value.value_data().seq().wrapping_sub(1))
+        } else {
+            None
+        };
+
         // Do the set and return no new value
         let res = if is_local {
             inner
-                .handle_set_local_value(key, subkey, value, WatchUpdateMode::ExcludeTarget(target))
+                .handle_set_local_value(
+                    key,
+                    subkey,
+                    value,
+                    WatchUpdateMode::ExcludeTarget(target),
+                    opt_expected_seq,
+                )
                 .await
         } else {
             inner
@@ -482,11 +553,16 @@ impl StorageManager {
                     value,
                     actual_descriptor,
                     WatchUpdateMode::ExcludeTarget(target),
+                    opt_expected_seq,
                 )
                 .await
         };
         match res {
             Ok(()) => {}
+            Err(VeilidAPIError::SeqMismatch { .. }) => {
+                // Conditional set was not taken, return what we have
+                return Ok(NetworkResult::value(last_get_result.opt_value));
+            }
             Err(VeilidAPIError::Internal { message }) => {
                 apibail_internal!(message);
             }
@@ -0,0 +1,294 @@
+use super::*;
+
+/// Info tracked per subkey
+struct SubkeySetCount {
+    /// The subkey being set
+    pub subkey: ValueSubkey,
+    /// The value being set
+    pub value: Arc<SignedValueData>,
+    /// The nodes that have set the value so far (up to the consensus count)
+    pub value_nodes: Vec<NodeRef>,
+    /// If a node has reported a newer value than the one being set
+    pub newer: bool,
+}
+
+/// The context of the outbound_set_values operation
+struct OutboundSetValuesContext {
+    /// The values being set and result counts so far, one per subkey
+    pub setcounts: Vec<SubkeySetCount>,
+    /// The number of non-sets since the last set we have received
+    pub missed_since_last_set: usize,
+}
+
+/// The result of the outbound_set_values operation
+pub(super) struct OutboundSetValuesResult {
+    /// Fanout results for each subkey
+    pub fanout_results: Vec<FanoutResult>,
+    /// The subkeys for which a node reported a newer value than the one set
+    pub newer_subkeys: ValueSubkeyRangeSet,
+}
+
+/// A page of values that fits in a single SetValuesQ message, tagged with their index
+pub(super) type SetValuesPage = Vec<(usize, ValueSubkey, Arc<SignedValueData>)>;
+
+/// Split values into as few pages as will fit in SetValuesQ messages
+pub(super) fn make_set_values_pages<I>(values: I) -> Vec<SetValuesPage>
+where
+    I: IntoIterator<Item = (usize, ValueSubkey, Arc<SignedValueData>)>,
+{
+    let mut pages: Vec<SetValuesPage> = vec![];
+    let mut page = vec![];
+    let mut data_len = 0usize;
+    for (idx, subkey, value) in values {
+        let value_len = value.value_data().data().len();
+        if !page.is_empty()
+            && (page.len() == MAX_SET_VALUES_Q_VALUES_LEN
+                || data_len + value_len > MAX_SET_VALUES_Q_DATA_LEN)
+        {
+            pages.push(core::mem::take(&mut page));
+            data_len = 0;
+        }
+        data_len += value_len;
+        page.push((idx, subkey, value));
+    }
+    if !page.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+impl StorageManager {
+    /// Perform a batched 'set value' query for a set of subkeys on the network
+    /// Each node visited by the fanout is sent all of the values at once,
+    /// split into as few requests as will fit in a message
+    #[instrument(level = "trace", target = "dht", skip_all, err)]
+    pub(super) async fn outbound_set_values(
+        &self,
+        rpc_processor: RPCProcessor,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+        values: Vec<(ValueSubkey, Arc<SignedValueData>)>,
+        descriptor: Arc<SignedValueDescriptor>,
+    ) -> VeilidAPIResult<OutboundSetValuesResult> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Get the DHT parameters for 'SetValue'
+        let (key_count, consensus_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.set_value_count as usize,
+                c.network.dht.set_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.set_value_timeout_ms)),
+            )
+        };
+
+        // Get the nodes we know are caching this value to seed the fanout
+        let init_fanout_queue = {
+            let inner = self.inner.lock().await;
+            inner.get_value_nodes(key)?.unwrap_or_default()
+        };
+
+        // Make do-set-values answer context
+        let context = Arc::new(Mutex::new(OutboundSetValuesContext {
+            setcounts: values
+                .into_iter()
+                .map(|(subkey, value)| SubkeySetCount {
+                    subkey,
+                    value,
+                    value_nodes: vec![],
+                    newer: false,
+                })
+                .collect(),
+            missed_since_last_set: 0,
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = |next_node: NodeRef| {
+            let rpc_processor = rpc_processor.clone();
+            let context = context.clone();
+            let descriptor = descriptor.clone();
+            async move {
+                let send_descriptor = true; // xxx check if next_node needs the descriptor or not
+
+                // Split the values that are still being set into pages that fit in a message
+                let pages = {
+                    let ctx = context.lock();
+                    make_set_values_pages(
+                        ctx.setcounts
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, sc)| !sc.newer)
+                            .map(|(idx, sc)| (idx, sc.subkey, sc.value.clone())),
+                    )
+                };
+
+                let mut peers = vec![];
+                for page in pages {
+                    // send across the wire
+                    let sva = network_result_try!(
+                        rpc_processor
+                            .clone()
+                            .rpc_call_set_values(
+                                Destination::direct(next_node.clone())
+                                    .with_safety(safety_selection),
+                                key,
+                                page.iter()
+                                    .map(|(_, subkey, value)| (*subkey, (**value).clone()))
+                                    .collect(),
+                                (*descriptor).clone(),
+                                send_descriptor,
+                            )
+                            .await?
+                    );
+                    peers = sva.answer.peers;
+
+                    // If the node was close enough to possibly set the values
+                    let mut ctx = context.lock();
+                    if !sva.answer.set {
+                        ctx.missed_since_last_set += 1;
+
+                        // Return peers if we have some
+                        log_network_result!(debug "SetValues missed: {}, fanout call returned peers {}", ctx.missed_since_last_set, peers.len());
+                        return Ok(NetworkResult::value(peers));
+                    }
+
+                    for ((idx, subkey, value), seq) in page.iter().zip(sva.answer.seqs.iter()) {
+                        let sc = &mut ctx.setcounts[*idx];
+                        let sent_seq = value.value_data().seq();
+                        if *seq == sent_seq {
+                            // The node has our value, so increase our consensus count
+                            sc.value_nodes.push(next_node.clone());
+                        } else if *seq > sent_seq {
+                            // The node kept a newer value, stop setting this subkey
+                            log_dht!(debug "SetValues got newer seq back: subkey={} seq={}", subkey, seq);
+                            sc.newer = true;
+                        } else {
+                            // If the sequence number is older node should have not returned it here.
+                            // Skip this node and its closer list because it is misbehaving
+                            return Ok(NetworkResult::invalid_message("Sequence number is older"));
+                        }
+                    }
+                    ctx.missed_since_last_set = 0;
+                }
+
+                // Return peers if we have some
+                log_network_result!(debug "SetValues fanout call returned peers {}", peers.len());
+
+                Ok(NetworkResult::value(peers))
+            }
+            .instrument(tracing::trace_span!("outbound_set_values fanout routine"))
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = |_closest_nodes: &[NodeRef]| {
+            let ctx = context.lock();
+
+            // Subkeys with newer values are no longer being set
+            let min_value_nodes = ctx
+                .setcounts
+                .iter()
+                .filter(|sc| !sc.newer)
+                .map(|sc| sc.value_nodes.len())
+                .min();
+            let Some(min_value_nodes) = min_value_nodes else {
+                // Nothing left to set
+                return Some(());
+            };
+
+            // If we have reached sufficient consensus on all subkeys, return done
+            if min_value_nodes >= consensus_count {
+                return Some(());
+            }
+            // If we have missed more than our consensus count since our last set, return done
+            // This keeps the traversal from searching too many nodes when we aren't converging
+            // Only do this if we have gotten at least half our desired sets.
+            if min_value_nodes >= ((consensus_count + 1) / 2)
+                && ctx.missed_since_last_set >= consensus_count
+            {
+                return Some(());
+            }
+            None
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            key,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(vec![CAP_DHT]),
+            call_routine,
+            check_done,
+        );
+
+        let kind = match fanout_call.run(init_fanout_queue).await {
+            // If we don't finish in the timeout (too much time passed checking for consensus)
+            TimeoutOr::Timeout => FanoutResultKind::Timeout,
+            // If we finished with or without consensus (enough nodes returning the same value)
+            TimeoutOr::Value(Ok(Some(()))) => FanoutResultKind::Finished,
+            // If we ran out of nodes before getting consensus)
+            TimeoutOr::Value(Ok(None)) => FanoutResultKind::Exhausted,
+            // Failed
+            TimeoutOr::Value(Err(e)) => {
+                // If we finished with an error, return that
+                log_dht!(debug "SetValues fanout error: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let ctx = context.lock();
+        let mut fanout_results = vec![];
+        let mut newer_subkeys = ValueSubkeyRangeSet::new();
+        for sc in &ctx.setcounts {
+            let has_consensus = sc.value_nodes.len() >= consensus_count;
+            let fanout_result = FanoutResult {
+                kind: if has_consensus || sc.newer {
+                    FanoutResultKind::Finished
+                } else {
+                    kind
+                },
+                value_nodes: sc.value_nodes.clone(),
+            };
+            fanout_results.push(fanout_result);
+            if sc.newer {
+                newer_subkeys.insert(sc.subkey);
+            }
+        }
+
+        log_network_result!(debug "SetValues Fanout ({:?}):\n{}", kind, debug_fanout_results(&fanout_results));
+
+        Ok(OutboundSetValuesResult {
+            fanout_results,
+            newer_subkeys,
+        })
+    }
+
+    /// Handle a received batched 'Set Value' query
+    /// Returns the sequence number now held for each value, which is newer than
+    /// the one sent if the value was older and the current value was kept
+    #[instrument(level = "trace", target = "dht", skip_all)]
+    pub async fn inbound_set_values(
+        &self,
+        key: TypedKey,
+        values: Vec<(ValueSubkey, Arc<SignedValueData>)>,
+        descriptor: Option<Arc<SignedValueDescriptor>>,
+        target: Target,
+    ) -> VeilidAPIResult<NetworkResult<Vec<ValueSeqNum>>> {
+        let mut seqs = Vec::with_capacity(values.len());
+        for (subkey, value) in values {
+            let sent_seq = value.value_data().seq();
+            let opt_current_value = network_result_try!(
+                self.inbound_set_value(key, subkey, value, descriptor.clone(), target, false)
+                    .await?
+            );
+            seqs.push(
+                opt_current_value
+                    .map(|v| v.value_data().seq())
+                    .unwrap_or(sent_seq),
+            );
+        }
+        Ok(NetworkResult::value(seqs))
+    }
+}
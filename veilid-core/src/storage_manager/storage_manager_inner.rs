@@ -2,6 +2,7 @@ use super::*;
 
 const STORAGE_MANAGER_METADATA: &str = "storage_manager_metadata";
 const OFFLINE_SUBKEY_WRITES: &[u8] = b"offline_subkey_writes";
+const OFFLINE_SUBKEY_WRITE_CONFLICTS: &[u8] = b"offline_subkey_write_conflicts";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub(super) struct OfflineSubkeyWrite {
@@ -9,6 +10,43 @@ pub(super) struct OfflineSubkeyWrite {
     pub subkeys: ValueSubkeyRangeSet,
 }
 
+/// The outcome of writing the offline subkey writes of a record to the network
+#[derive(Debug, Clone, Default)]
+pub(super) struct OfflineSubkeyWriteResults {
+    /// Subkeys that no longer need to be written
+    pub written: ValueSubkeyRangeSet,
+    /// Subkeys whose offline write reached the network
+    pub flushed: ValueSubkeyRangeSet,
+    /// Subkeys whose offline write was replaced by a newer value from the network
+    pub conflict: ValueSubkeyRangeSet,
+}
+
+impl OfflineSubkeyWriteResults {
+    /// Drop a subkey that can't be written at all
+    pub fn add_dropped(&mut self, subkey: ValueSubkey) {
+        self.written.insert(subkey);
+    }
+
+    /// Add the result of writing a subkey, whether it only reached too few nodes
+    /// to count as online, and whether the network had a newer value that replaced it
+    pub fn add_result(&mut self, subkey: ValueSubkey, was_offline: bool, replaced: bool) {
+        if replaced {
+            // The local value is now the network's, so there is nothing left to write
+            self.written.insert(subkey);
+            self.conflict.insert(subkey);
+        } else if !was_offline {
+            self.written.insert(subkey);
+            self.flushed.insert(subkey);
+        }
+    }
+}
+
+/// The descriptor and stored subkey values of a record
+pub(super) struct RecordValues {
+    pub descriptor: Arc<SignedValueDescriptor>,
+    pub values: Vec<(ValueSubkey, Arc<SignedValueData>)>,
+}
+
 /// Locked structure for storage manager
 pub(super) struct StorageManagerInner {
     unlocked_inner: Arc<StorageManagerUnlockedInner>,
@@ -22,6 +60,10 @@ pub(super) struct StorageManagerInner {
     pub remote_record_store: Option<RecordStore<RemoteRecordDetail>>,
     /// Record subkeys that have not been pushed to the network because they were written to offline
     pub offline_subkey_writes: HashMap<TypedKey, OfflineSubkeyWrite>,
+    /// Record subkeys written offline that are currently being pushed to the network
+    pub offline_subkey_writes_in_flight: HashMap<TypedKey, OfflineSubkeyWrite>,
+    /// Record subkeys written offline that were replaced by newer values from the network, until cleared
+    pub offline_subkey_write_conflicts: HashMap<TypedKey, ValueSubkeyRangeSet>,
     /// Storage manager metadata that is persistent, including copy of offline subkey writes
     pub metadata_db: Option<TableDB>,
     /// RPC processor if it is available
@@ -34,6 +76,9 @@ pub(super) struct StorageManagerInner {
     pub update_callback: Option<UpdateCallback>,
     /// Deferred result processor
     pub deferred_result_processor: DeferredStreamProcessor,
+    /// Suppliers that have announced blocks to this node
+    #[cfg(feature = "unstable-blockstore")]
+    pub block_suppliers: BlockSupplierTable,
 
     /// The maximum consensus count
     set_consensus_count: usize,
@@ -54,6 +99,10 @@ fn local_limits_from_config(config: VeilidConfig) -> RecordStoreLimits {
             c.network.dht.max_watch_expiration_ms,
         )),
         min_watch_expiration: TimestampDuration::new(ms_to_us(c.network.rpc.timeout_ms)),
+        max_pinned_records: 0,
+        max_pinned_records_per_owner: 0,
+        max_pin_expiration: TimestampDuration::default(),
+        pin_allowed_owners: Some(HashSet::new()),
     }
 }
 
@@ -72,6 +121,28 @@ fn remote_limits_from_config(config: VeilidConfig) -> RecordStoreLimits {
             c.network.dht.max_watch_expiration_ms,
         )),
         min_watch_expiration: TimestampDuration::new(ms_to_us(c.network.rpc.timeout_ms)),
+        max_pinned_records: if c.network.dht.pinning.enabled {
+            c.network.dht.pinning.max_records as usize
+        } else {
+            0
+        },
+        max_pinned_records_per_owner: c.network.dht.pinning.max_records_per_owner as usize,
+        max_pin_expiration: TimestampDuration::new(ms_to_us(
+            c.network.dht.pinning.max_expiration_ms,
+        )),
+        pin_allowed_owners: if c.network.dht.pinning.allow_any_owner {
+            None
+        } else {
+            Some(
+                c.network
+                    .dht
+                    .pinning
+                    .allowed_owners
+                    .iter()
+                    .filter_map(|o| TypedKey::from_str(o).ok())
+                    .collect(),
+            )
+        },
     }
 }
 
@@ -85,12 +156,16 @@ impl StorageManagerInner {
             local_record_store: Default::default(),
             remote_record_store: Default::default(),
             offline_subkey_writes: Default::default(),
+            offline_subkey_writes_in_flight: Default::default(),
+            offline_subkey_write_conflicts: Default::default(),
             metadata_db: Default::default(),
             opt_rpc_processor: Default::default(),
             opt_routing_table: Default::default(),
             tick_future: Default::default(),
             update_callback: None,
             deferred_result_processor: DeferredStreamProcessor::default(),
+            #[cfg(feature = "unstable-blockstore")]
+            block_suppliers: Default::default(),
             set_consensus_count,
         }
     }
@@ -111,6 +186,7 @@ impl StorageManagerInner {
 
         let mut local_record_store = RecordStore::new(
             self.unlocked_inner.table_store.clone(),
+            self.unlocked_inner.crypto.clone(),
             "local",
             local_limits,
         );
@@ -118,6 +194,7 @@ impl StorageManagerInner {
 
         let mut remote_record_store = RecordStore::new(
             self.unlocked_inner.table_store.clone(),
+            self.unlocked_inner.crypto.clone(),
             "remote",
             remote_limits,
         );
@@ -180,21 +257,49 @@ impl StorageManagerInner {
             self.metadata_db = None;
         }
         self.offline_subkey_writes.clear();
+        self.offline_subkey_writes_in_flight.clear();
+        self.offline_subkey_write_conflicts.clear();
 
         // Mark not initialized
         self.initialized = false;
     }
 
-    async fn save_metadata(&mut self) -> EyreResult<()> {
+    pub async fn flush(&mut self) -> EyreResult<()> {
+        if let Some(local_record_store) = &mut self.local_record_store {
+            local_record_store.flush().await?;
+        }
+        if let Some(remote_record_store) = &mut self.remote_record_store {
+            remote_record_store.flush().await?;
+        }
+        self.save_metadata().await
+    }
+
+    pub(super) async fn save_metadata(&mut self) -> EyreResult<()> {
         if let Some(metadata_db) = &self.metadata_db {
+            // Writes that are in flight are not done yet, so save them as still offline
+            let mut offline_subkey_writes = self.offline_subkey_writes.clone();
+            for (key, osw) in &self.offline_subkey_writes_in_flight {
+                offline_subkey_writes
+                    .entry(*key)
+                    .and_modify(|x| {
+                        x.subkeys = x.subkeys.union(&osw.subkeys);
+                    })
+                    .or_insert_with(|| osw.clone());
+            }
+
             let tx = metadata_db.transact();
-            tx.store_json(0, OFFLINE_SUBKEY_WRITES, &self.offline_subkey_writes)?;
+            tx.store_json(0, OFFLINE_SUBKEY_WRITES, &offline_subkey_writes)?;
+            tx.store_json(
+                0,
+                OFFLINE_SUBKEY_WRITE_CONFLICTS,
+                &self.offline_subkey_write_conflicts,
+            )?;
             tx.commit().await.wrap_err("failed to commit")?
         }
         Ok(())
     }
 
-    async fn load_metadata(&mut self) -> EyreResult<()> {
+    pub(super) async fn load_metadata(&mut self) -> EyreResult<()> {
         if let Some(metadata_db) = &self.metadata_db {
             self.offline_subkey_writes = match metadata_db.load_json(0, OFFLINE_SUBKEY_WRITES).await
             {
@@ -205,7 +310,19 @@ impl StorageManagerInner {
                     }
                     Default::default()
                 }
-            }
+            };
+            self.offline_subkey_write_conflicts = match metadata_db
+                .load_json(0, OFFLINE_SUBKEY_WRITE_CONFLICTS)
+                .await
+            {
+                Ok(v) => v.unwrap_or_default(),
+                Err(_) => {
+                    if let Err(e) = metadata_db.delete(0, OFFLINE_SUBKEY_WRITE_CONFLICTS).await {
+                        log_stor!(debug "offline_subkey_write_conflicts format changed, clearing: {}", e);
+                    }
+                    Default::default()
+                }
+            };
         }
         Ok(())
     }
@@ -314,7 +431,7 @@ impl StorageManagerInner {
                 continue;
             };
             local_record_store
-                .set_subkey(key, subkey, subkey_data, WatchUpdateMode::NoUpdate)
+                .set_subkey(key, subkey, subkey_data, WatchUpdateMode::NoUpdate, None)
                 .await?;
         }
 
@@ -445,7 +562,13 @@ impl StorageManagerInner {
         if let Some(signed_value_data) = get_result.opt_value {
             // Write subkey to local store
             local_record_store
-                .set_subkey(key, subkey, signed_value_data, WatchUpdateMode::NoUpdate)
+                .set_subkey(
+                    key,
+                    subkey,
+                    signed_value_data,
+                    WatchUpdateMode::NoUpdate,
+                    None,
+                )
                 .await?;
         }
 
@@ -575,6 +698,7 @@ impl StorageManagerInner {
         subkey: ValueSubkey,
         signed_value_data: Arc<SignedValueData>,
         watch_update_mode: WatchUpdateMode,
+        opt_expected_seq: Option<ValueSeqNum>,
     ) -> VeilidAPIResult<()> {
         // See if it's in the local record store
         let Some(local_record_store) = self.local_record_store.as_mut() else {
@@ -583,7 +707,13 @@ impl StorageManagerInner {
 
         // Write subkey to local store
         local_record_store
-            .set_subkey(key, subkey, signed_value_data, watch_update_mode)
+            .set_subkey(
+                key,
+                subkey,
+                signed_value_data,
+                watch_update_mode,
+                opt_expected_seq,
+            )
             .await?;
 
         Ok(())
@@ -646,6 +776,7 @@ impl StorageManagerInner {
         signed_value_data: Arc<SignedValueData>,
         signed_value_descriptor: Arc<SignedValueDescriptor>,
         watch_update_mode: WatchUpdateMode,
+        opt_expected_seq: Option<ValueSeqNum>,
     ) -> VeilidAPIResult<()> {
         // See if it's in the remote record store
         let Some(remote_record_store) = self.remote_record_store.as_mut() else {
@@ -667,12 +798,123 @@ impl StorageManagerInner {
 
         // Write subkey to remote store
         remote_record_store
-            .set_subkey(key, subkey, signed_value_data, watch_update_mode)
+            .set_subkey(
+                key,
+                subkey,
+                signed_value_data,
+                watch_update_mode,
+                opt_expected_seq,
+            )
             .await?;
 
         Ok(())
     }
 
+    /// Apply an owner-signed pin request to a remote record, creating the record if necessary
+    /// Returns the expiration granted if the pin was accepted, or zero if the record was unpinned
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub(super) async fn handle_pin_remote_record(
+        &mut self,
+        key: TypedKey,
+        signed_value_descriptor: Arc<SignedValueDescriptor>,
+        pin: RecordPin,
+    ) -> VeilidAPIResult<NetworkResult<Option<Timestamp>>> {
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        // Get remote record store
+        let Some(remote_record_store) = self.remote_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+
+        let cur_ts = Timestamp::now();
+
+        // See if we have a remote record already or not
+        let opt_existing = remote_record_store.peek_record(key, |r| (r.descriptor(), r.pin()));
+        if let Some((descriptor, opt_pin)) = &opt_existing {
+            if descriptor.owner() != signed_value_descriptor.owner()
+                || descriptor.schema_data() != signed_value_descriptor.schema_data()
+            {
+                return Ok(NetworkResult::invalid_message(
+                    "descriptor does not match record",
+                ));
+            }
+            if let Some(existing_pin) = opt_pin {
+                // Ignore replayed or out of order pin requests
+                if pin.timestamp <= existing_pin.timestamp {
+                    return Ok(NetworkResult::value(None));
+                }
+            }
+        }
+
+        // An expiration of zero unpins the record, and the pin is kept expired
+        // so that its timestamp still orders any later requests
+        if pin.expiration.as_u64() == 0 {
+            if opt_existing.is_some() {
+                remote_record_store.with_record_mut(key, |r| r.set_pin(Some(pin)));
+            }
+            return Ok(NetworkResult::value(Some(Timestamp::default())));
+        }
+
+        // Only commit storage to allowed owners, and to a limited number of records
+        let Some(pin) =
+            remote_record_store.accept_pin(key, signed_value_descriptor.owner(), pin, cur_ts)
+        else {
+            return Ok(NetworkResult::value(None));
+        };
+
+        if opt_existing.is_some() {
+            remote_record_store.with_record_mut(key, |r| r.set_pin(Some(pin)));
+        } else {
+            // record didn't exist, make it
+            let remote_record_detail = RemoteRecordDetail {};
+            let mut record = Record::<RemoteRecordDetail>::new(
+                cur_ts,
+                signed_value_descriptor,
+                remote_record_detail,
+            )?;
+            if Self::get_key(vcrypto, &record) != key {
+                return Ok(NetworkResult::invalid_message(
+                    "descriptor does not match key",
+                ));
+            }
+            record.set_pin(Some(pin));
+            match remote_record_store.new_record(key, record).await {
+                Ok(()) => {}
+                Err(VeilidAPIError::TryAgain { message }) => {
+                    log_stor!(debug "not pinning record {}: {}", key, message);
+                    return Ok(NetworkResult::value(None));
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(NetworkResult::value(Some(pin.expiration)))
+    }
+
+    /// Get the keys and pins of the remote records this node has committed to retain
+    pub(super) fn get_pinned_remote_records(&self) -> VeilidAPIResult<Vec<(TypedKey, RecordPin)>> {
+        let Some(remote_record_store) = self.remote_record_store.as_ref() else {
+            apibail_not_initialized!();
+        };
+        Ok(remote_record_store.pinned_records(Timestamp::now()))
+    }
+
+    /// Check if a remote record has an unexpired pin
+    pub(super) fn is_pinned_remote_record(&self, key: TypedKey) -> VeilidAPIResult<bool> {
+        let Some(remote_record_store) = self.remote_record_store.as_ref() else {
+            apibail_not_initialized!();
+        };
+        let cur_ts = Timestamp::now();
+        Ok(remote_record_store
+            .peek_record(key, |r| r.is_pinned(cur_ts))
+            .unwrap_or_default())
+    }
+
     #[instrument(level = "trace", target = "stor", skip_all, err)]
     pub(super) async fn handle_inspect_remote_value(
         &mut self,
@@ -698,6 +940,53 @@ impl StorageManagerInner {
         })
     }
 
+    /// Get the descriptor and all of the stored subkey values of a local record
+    pub(super) async fn get_local_record_values(
+        &mut self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<Option<RecordValues>> {
+        let Some(local_record_store) = self.local_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+        Self::get_record_values(local_record_store, key).await
+    }
+
+    /// Get the descriptor and all of the stored subkey values of a remote record
+    pub(super) async fn get_remote_record_values(
+        &mut self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<Option<RecordValues>> {
+        let Some(remote_record_store) = self.remote_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+        Self::get_record_values(remote_record_store, key).await
+    }
+
+    async fn get_record_values<D>(
+        record_store: &mut RecordStore<D>,
+        key: TypedKey,
+    ) -> VeilidAPIResult<Option<RecordValues>>
+    where
+        D: fmt::Debug + Clone + Serialize + for<'d> Deserialize<'d>,
+    {
+        let Some((descriptor, stored_subkeys)) =
+            record_store.peek_record(key, |r| (r.descriptor(), r.stored_subkeys().clone()))
+        else {
+            return Ok(None);
+        };
+        let mut values = vec![];
+        for subkey in stored_subkeys.iter() {
+            if let Some(GetResult {
+                opt_value: Some(value),
+                ..
+            }) = record_store.get_subkey(key, subkey, false).await?
+            {
+                values.push((subkey, value));
+            }
+        }
+        Ok(Some(RecordValues { descriptor, values }))
+    }
+
     /// # DHT Key = Hash(ownerKeyKind) of: [ ownerKeyValue, schema ]
     #[instrument(level = "trace", target = "stor", skip_all)]
     fn get_key<D>(vcrypto: CryptoSystemVersion, record: &Record<D>) -> TypedKey
@@ -730,6 +1019,126 @@ impl StorageManagerInner {
                 safety_selection,
                 subkeys: ValueSubkeyRangeSet::single(subkey),
             });
+
+        self.send_value_sync(
+            key,
+            ValueSyncState::Pending,
+            ValueSubkeyRangeSet::single(subkey),
+        );
+    }
+
+    /// Move the queued offline subkey writes in flight, returning them to be written to the network
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub(super) fn take_offline_subkey_writes(&mut self) -> HashMap<TypedKey, OfflineSubkeyWrite> {
+        let out = core::mem::take(&mut self.offline_subkey_writes);
+        self.offline_subkey_writes_in_flight = out.clone();
+        out
+    }
+
+    /// Record the outcome of writing the offline subkey writes of a record that are in flight
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub(super) fn finish_offline_subkey_writes(
+        &mut self,
+        key: TypedKey,
+        osw: &mut OfflineSubkeyWrite,
+        results: OfflineSubkeyWriteResults,
+    ) {
+        osw.subkeys = osw.subkeys.difference(&results.written);
+        if let Some(in_flight) = self.offline_subkey_writes_in_flight.get_mut(&key) {
+            in_flight.subkeys = osw.subkeys.clone();
+        }
+        if !results.flushed.is_empty() {
+            self.send_value_sync(key, ValueSyncState::Flushed, results.flushed);
+        }
+        if !results.conflict.is_empty() {
+            self.add_offline_subkey_write_conflicts(key, results.conflict);
+        }
+    }
+
+    /// Queue the offline subkey writes that were in flight and not written to the network again
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub(super) fn requeue_offline_subkey_writes(
+        &mut self,
+        offline_subkey_writes: HashMap<TypedKey, OfflineSubkeyWrite>,
+    ) {
+        self.offline_subkey_writes_in_flight.clear();
+        for (key, osw) in offline_subkey_writes {
+            if !osw.subkeys.is_empty() {
+                self.offline_subkey_writes
+                    .entry(key)
+                    .and_modify(|x| {
+                        x.subkeys = x.subkeys.union(&osw.subkeys);
+                    })
+                    .or_insert(osw);
+            }
+        }
+    }
+
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub(super) fn add_offline_subkey_write_conflicts(
+        &mut self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+    ) {
+        self.offline_subkey_write_conflicts
+            .entry(key)
+            .and_modify(|x| {
+                *x = x.union(&subkeys);
+            })
+            .or_insert_with(|| subkeys.clone());
+
+        self.send_value_sync(key, ValueSyncState::Conflict, subkeys);
+    }
+
+    /// Stop reporting conflicts for some subkeys of a record, or all of them if the subkey range is empty
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub(super) fn clear_offline_subkey_write_conflicts(
+        &mut self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+    ) {
+        let Some(conflicts) = self.offline_subkey_write_conflicts.get_mut(&key) else {
+            return;
+        };
+        if !subkeys.is_empty() {
+            *conflicts = conflicts.difference(&subkeys);
+        }
+        if subkeys.is_empty() || conflicts.is_empty() {
+            self.offline_subkey_write_conflicts.remove(&key);
+        }
+    }
+
+    pub(super) fn get_record_sync_status(&self, key: TypedKey) -> DHTRecordSyncStatus {
+        let mut pending_subkeys = self
+            .offline_subkey_writes
+            .get(&key)
+            .map(|o| o.subkeys.clone())
+            .unwrap_or_default();
+        if let Some(osw) = self.offline_subkey_writes_in_flight.get(&key) {
+            pending_subkeys = pending_subkeys.union(&osw.subkeys);
+        }
+        let conflict_subkeys = self
+            .offline_subkey_write_conflicts
+            .get(&key)
+            .cloned()
+            .unwrap_or_default();
+        DHTRecordSyncStatus::new(pending_subkeys, conflict_subkeys)
+    }
+
+    /// Send a ValueSync update through the callback
+    pub(super) fn send_value_sync(
+        &self,
+        key: TypedKey,
+        state: ValueSyncState,
+        subkeys: ValueSubkeyRangeSet,
+    ) {
+        if let Some(update_callback) = &self.update_callback {
+            update_callback(VeilidUpdate::ValueSync(Box::new(VeilidValueSync {
+                key,
+                state,
+                subkeys,
+            })));
+        }
     }
 
     #[instrument(level = "trace", target = "stor", skip_all)]
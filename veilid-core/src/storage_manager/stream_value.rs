@@ -0,0 +1,197 @@
+use super::*;
+
+impl StorageManager {
+    /// Write a payload across the subkeys following a header subkey of an opened local record
+    ///
+    /// Chunks are written before the header, so a reader never sees a header for a stream
+    /// whose chunks have not been written yet. Chunks that are already set to the same data
+    /// are not sent again, so an interrupted write can be resumed by repeating it.
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub async fn set_stream(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        data: Vec<u8>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<DHTStreamHeader> {
+        let (vcrypto, schema) = self.stream_parameters(key, header_subkey).await?;
+
+        let Ok(length) = u32::try_from(data.len()) else {
+            apibail_invalid_argument!("stream is too large", "data.len()", data.len());
+        };
+        let chunk_size = MAX_SUBKEY_SIZE as u32;
+        let chunk_hashes = data
+            .chunks(MAX_SUBKEY_SIZE)
+            .map(|c| vcrypto.generate_hash(c))
+            .collect::<Vec<_>>();
+        let header = DHTStreamHeader::new(length, chunk_size, chunk_hashes)?;
+
+        // Ensure the chunks fit in the schema after the header
+        let last_subkey = header_subkey as u64 + header.chunk_count() as u64;
+        if last_subkey > schema.max_subkey() as u64 {
+            apibail_invalid_argument!(
+                "stream does not fit in record",
+                "header_subkey",
+                header_subkey
+            );
+        }
+
+        // Write the chunks
+        for (n, chunk) in data.chunks(MAX_SUBKEY_SIZE).enumerate() {
+            let subkey = header_subkey + 1 + n as ValueSubkey;
+            log_stor!(debug "Writing stream chunk: {}:{} len={}", key, subkey, chunk.len());
+            if self
+                .set_value(key, subkey, chunk.to_vec(), writer.clone(), None)
+                .await?
+                .is_some()
+            {
+                apibail_try_again!("stream chunk was changed by another writer");
+            }
+        }
+
+        // Write the header last
+        if self
+            .set_value(key, header_subkey, header.compile(), writer, None)
+            .await?
+            .is_some()
+        {
+            apibail_try_again!("stream header was changed by another writer");
+        }
+
+        Ok(header)
+    }
+
+    /// Get the stream header stored at a subkey of an opened local record
+    ///
+    /// Returns `None` if the header subkey has not yet been set.
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub async fn get_stream_header(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Option<DHTStreamHeader>> {
+        let Some(value_data) = self.get_value(key, header_subkey, force_refresh).await? else {
+            return Ok(None);
+        };
+        DHTStreamHeader::try_from(value_data.data()).map(Some)
+    }
+
+    /// Read a range of bytes from a stream described by a header
+    ///
+    /// Every chunk read is checked against the hashes in the header, so a read can be resumed
+    /// at any offset with the same header, and will fail rather than mix data from two
+    /// versions of the stream.
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub async fn read_stream(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        header: &DHTStreamHeader,
+        offset: u32,
+        length: u32,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Vec<u8>> {
+        let (vcrypto, _schema) = self.stream_parameters(key, header_subkey).await?;
+
+        let end = offset as u64 + length as u64;
+        if end > header.length() as u64 {
+            apibail_invalid_argument!("read past end of stream", "length", length);
+        }
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let chunk_size = header.chunk_size();
+        let first_chunk = offset / chunk_size;
+        let last_chunk = ((end - 1) / chunk_size as u64) as u32;
+
+        let mut out = Vec::with_capacity(length as usize);
+        for chunk in first_chunk..=last_chunk {
+            let subkey = header_subkey + 1 + chunk;
+            let chunk_hash = &header.chunk_hashes()[chunk as usize];
+
+            // A locally cached chunk may be from an older version of the stream,
+            // so refresh it from the network before giving up on it
+            let mut opt_value_data = self.get_value(key, subkey, force_refresh).await?;
+            if !force_refresh
+                && opt_value_data
+                    .as_ref()
+                    .map(|v| vcrypto.generate_hash(v.data()))
+                    != Some(*chunk_hash)
+            {
+                opt_value_data = self.get_value(key, subkey, true).await?;
+            }
+            let Some(value_data) = opt_value_data else {
+                apibail_try_again!("stream chunk is not available");
+            };
+            if vcrypto.generate_hash(value_data.data()) != *chunk_hash {
+                apibail_try_again!("stream chunk does not match header");
+            }
+
+            let chunk_start = chunk as u64 * chunk_size as u64;
+            let start = (offset as u64).saturating_sub(chunk_start) as usize;
+            let stop = (end - chunk_start).min(value_data.data_size() as u64) as usize;
+            out.extend_from_slice(&value_data.data()[start..stop]);
+        }
+
+        Ok(out)
+    }
+
+    /// Read an entire stream from an opened local record
+    ///
+    /// Returns `None` if the header subkey has not yet been set.
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub async fn get_stream(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Option<Vec<u8>>> {
+        let Some(header) = self
+            .get_stream_header(key, header_subkey, force_refresh)
+            .await?
+        else {
+            return Ok(None);
+        };
+        self.read_stream(
+            key,
+            header_subkey,
+            &header,
+            0,
+            header.length(),
+            force_refresh,
+        )
+        .await
+        .map(Some)
+    }
+
+    /// Get the cryptosystem and schema of an opened record for stream operations
+    async fn stream_parameters(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+    ) -> VeilidAPIResult<(CryptoSystemVersion, DHTSchema)> {
+        let mut inner = self.lock().await?;
+
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+        if !inner.opened_records.contains_key(&key) {
+            apibail_generic!("record not open");
+        }
+
+        let last_get_result = inner
+            .handle_get_local_value(key, header_subkey, true)
+            .await?;
+        let Some(descriptor) = last_get_result.opt_descriptor else {
+            apibail_generic!("must have a descriptor");
+        };
+        let schema = descriptor.schema()?;
+        if header_subkey > schema.max_subkey() {
+            apibail_invalid_argument!("header subkey out of range", "header_subkey", header_subkey);
+        }
+
+        Ok((vcrypto, schema))
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+/// The context of the outbound_supply_block operation
+struct OutboundSupplyBlockContext {
+    /// The nodes that have accepted us as a supplier so far
+    pub supplier_nodes: Vec<NodeRef>,
+}
+
+impl StorageManager {
+    /// Perform a 'supply block' announcement on the network
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub(super) async fn outbound_supply_block(
+        &self,
+        rpc_processor: RPCProcessor,
+        block_id: TypedKey,
+    ) -> VeilidAPIResult<bool> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Suppliers are announced with the same parameters as 'SetValue'
+        let (key_count, consensus_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.set_value_count as usize,
+                c.network.dht.set_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.set_value_timeout_ms)),
+            )
+        };
+
+        // Make do-supply-block answer context
+        let context = Arc::new(Mutex::new(OutboundSupplyBlockContext {
+            supplier_nodes: vec![],
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = {
+            let context = context.clone();
+            let rpc_processor = rpc_processor.clone();
+            move |next_node: NodeRef| {
+                let context = context.clone();
+                let rpc_processor = rpc_processor.clone();
+                async move {
+                    // Suppliers must be reachable by node id, so this is always sent without a safety route
+                    let sba = network_result_try!(
+                        rpc_processor
+                            .clone()
+                            .rpc_call_supply_block(Destination::direct(next_node.clone()), block_id)
+                            .await?
+                    );
+
+                    if sba.answer.expiration.as_u64() != 0 {
+                        log_stor!(debug "SupplyBlock accepted by {} until {}", next_node, sba.answer.expiration);
+                        context.lock().supplier_nodes.push(next_node);
+                    }
+
+                    // Return peers if we have some
+                    log_network_result!(debug "SupplyBlock fanout call returned peers {}", sba.answer.peers.len());
+
+                    Ok(NetworkResult::value(sba.answer.peers))
+                }
+                .instrument(tracing::trace_span!("outbound_supply_block fanout routine"))
+            }
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = {
+            let context = context.clone();
+            move |_closest_nodes: &[NodeRef]| {
+                // If enough nodes have accepted us as a supplier, return done
+                if context.lock().supplier_nodes.len() >= consensus_count {
+                    return Some(());
+                }
+                None
+            }
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            block_id,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(vec![CAP_BLOCKSTORE]),
+            call_routine,
+            check_done,
+        );
+
+        let kind = match fanout_call.run(vec![]).await {
+            // If we don't finish in the timeout
+            TimeoutOr::Timeout => FanoutResultKind::Timeout,
+            // If enough nodes accepted us as a supplier
+            TimeoutOr::Value(Ok(Some(()))) => FanoutResultKind::Finished,
+            // If we ran out of nodes before enough accepted us as a supplier
+            TimeoutOr::Value(Ok(None)) => FanoutResultKind::Exhausted,
+            // Failed
+            TimeoutOr::Value(Err(e)) => {
+                // If we finished with an error, return that
+                log_stor!(debug "SupplyBlock Fanout Error: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let ctx = context.lock();
+        let fanout_result = FanoutResult {
+            kind,
+            value_nodes: ctx.supplier_nodes.clone(),
+        };
+        log_network_result!(debug "SupplyBlock Fanout: {:?}", fanout_result);
+
+        Ok(!ctx.supplier_nodes.is_empty())
+    }
+
+    /// Handle a received 'Supply Block' announcement
+    #[instrument(level = "trace", target = "stor", skip_all)]
+    pub async fn inbound_supply_block(
+        &self,
+        block_id: TypedKey,
+        supplier: PeerInfo,
+    ) -> VeilidAPIResult<Option<Timestamp>> {
+        let mut inner = self.lock().await?;
+        Ok(inner
+            .block_suppliers
+            .add_supplier(block_id, supplier, Timestamp::now()))
+    }
+}
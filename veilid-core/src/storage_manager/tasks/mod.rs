@@ -2,6 +2,7 @@ pub mod check_active_watches;
 pub mod check_watched_records;
 pub mod flush_record_stores;
 pub mod offline_subkey_writes;
+pub mod republish_pinned_records;
 pub mod send_value_changes;
 
 use super::*;
@@ -78,6 +79,20 @@ impl StorageManager {
                     ))
                 });
         }
+        // Set republish pinned records tick task
+        log_stor!(debug "starting republish pinned records task");
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .republish_pinned_records_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(this.clone().republish_pinned_records_task_routine(
+                        s,
+                        Timestamp::new(l),
+                        Timestamp::new(t),
+                    ))
+                });
+        }
     }
 
     #[instrument(parent = None, level = "trace", target = "stor", name = "StorageManager::tick", skip_all, err)]
@@ -106,6 +121,12 @@ impl StorageManager {
 
             // Send value changed notifications
             self.unlocked_inner.send_value_changes_task.tick().await?;
+
+            // Re-replicate records pinned to this node
+            self.unlocked_inner
+                .republish_pinned_records_task
+                .tick()
+                .await?;
         }
         Ok(())
     }
@@ -128,6 +149,15 @@ impl StorageManager {
         if let Err(e) = self.unlocked_inner.flush_record_stores_task.stop().await {
             warn!("flush_record_stores_task not stopped: {}", e);
         }
+        log_stor!(debug "stopping republish pinned records task");
+        if let Err(e) = self
+            .unlocked_inner
+            .republish_pinned_records_task
+            .stop()
+            .await
+        {
+            warn!("republish_pinned_records_task not stopped: {}", e);
+        }
         log_stor!(debug "stopping offline subkey writes task");
         if let Err(e) = self.unlocked_inner.offline_subkey_writes_task.stop().await {
             warn!("offline_subkey_writes_task not stopped: {}", e);
@@ -12,9 +12,7 @@ impl StorageManager {
     ) -> EyreResult<()> {
         let mut offline_subkey_writes = {
             let mut inner = self.lock().await?;
-            let out = inner.offline_subkey_writes.clone();
-            inner.offline_subkey_writes.clear();
-            out
+            inner.take_offline_subkey_writes()
         };
 
         for (key, osw) in offline_subkey_writes.iter_mut() {
@@ -29,7 +27,7 @@ impl StorageManager {
 
             let mut fanout_results = vec![];
 
-            let mut written_subkeys = ValueSubkeyRangeSet::new();
+            let mut results = OfflineSubkeyWriteResults::default();
             for subkey in osw.subkeys.iter() {
                 let get_result = {
                     let mut inner = self.lock().await?;
@@ -38,19 +36,19 @@ impl StorageManager {
                 let Ok(get_result) = get_result else {
                     log_stor!(debug "Offline subkey write had no subkey result: {}:{}", key, subkey);
                     // drop this one
-                    written_subkeys.insert(subkey);
+                    results.add_dropped(subkey);
                     continue;
                 };
                 let Some(value) = get_result.opt_value else {
                     log_stor!(debug "Offline subkey write had no subkey value: {}:{}", key, subkey);
                     // drop this one
-                    written_subkeys.insert(subkey);
+                    results.add_dropped(subkey);
                     continue;
                 };
                 let Some(descriptor) = get_result.opt_descriptor else {
                     log_stor!(debug "Offline subkey write had no descriptor: {}:{}", key, subkey);
                     // drop this one
-                    written_subkeys.insert(subkey);
+                    results.add_dropped(subkey);
                     continue;
                 };
                 log_stor!(debug "Offline subkey write: {}:{} len={}", key, subkey, value.value_data().data().len());
@@ -62,6 +60,7 @@ impl StorageManager {
                         osw.safety_selection,
                         value.clone(),
                         descriptor,
+                        false,
                     )
                     .await;
                 match osvres {
@@ -81,12 +80,11 @@ impl StorageManager {
                                         subkey,
                                         &result.fanout_result,
                                     );
-                                    if !was_offline {
-                                        written_subkeys.insert(subkey);
-                                    }
 
                                     // Set the new value if it differs from what was asked to set
-                                    if result.signed_value_data.value_data() != value.value_data() {
+                                    let replaced =
+                                        result.signed_value_data.value_data() != value.value_data();
+                                    if replaced {
                                         // Record the newer value and send and update since it is different than what we just set
                                         let mut inner = self.lock().await?;
                                         inner
@@ -95,9 +93,11 @@ impl StorageManager {
                                                 subkey,
                                                 result.signed_value_data.clone(),
                                                 WatchUpdateMode::UpdateAll,
+                                                None,
                                             )
                                             .await?;
                                     }
+                                    results.add_result(subkey, was_offline, replaced);
 
                                     fanout_results.push((subkey, result.fanout_result));
                                     break;
@@ -115,8 +115,6 @@ impl StorageManager {
                 }
             }
 
-            osw.subkeys = osw.subkeys.difference(&written_subkeys);
-
             // Keep the list of nodes that returned a value for later reference
             {
                 let mut inner = self.lock().await?;
@@ -125,22 +123,15 @@ impl StorageManager {
                     fanout_results.iter().map(|x| (x.0, &x.1)),
                     true,
                 );
+
+                // Report on the sync progress for this record
+                inner.finish_offline_subkey_writes(*key, osw, results);
             }
         }
 
         // Add any subkeys back in that were not successfully written
         let mut inner = self.lock().await?;
-        for (key, osw) in offline_subkey_writes {
-            if !osw.subkeys.is_empty() {
-                inner
-                    .offline_subkey_writes
-                    .entry(key)
-                    .and_modify(|x| {
-                        x.subkeys = x.subkeys.union(&osw.subkeys);
-                    })
-                    .or_insert(osw);
-            }
-        }
+        inner.requeue_offline_subkey_writes(offline_subkey_writes);
 
         Ok(())
     }
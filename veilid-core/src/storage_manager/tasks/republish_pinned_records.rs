@@ -0,0 +1,57 @@
+use super::*;
+use futures_util::*;
+
+impl StorageManager {
+    // Re-replicate the records this node has committed to retain for their owners
+    #[instrument(level = "trace", target = "stor", skip_all, err)]
+    pub(crate) async fn republish_pinned_records_task_routine(
+        self,
+        stop_token: StopToken,
+        _last_ts: Timestamp,
+        _cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let pinned_records = {
+            let inner = self.lock().await?;
+            inner.get_pinned_remote_records()?
+        };
+
+        for (key, pin) in pinned_records {
+            if poll!(stop_token.clone()).is_ready() {
+                log_stor!(debug "Republish pinned records cancelled.");
+                break;
+            }
+            let Some(rpc_processor) = self.online_writes_ready().await? else {
+                log_stor!(debug "Republish pinned records stopped for network.");
+                break;
+            };
+
+            let record_values = {
+                let mut inner = self.lock().await?;
+                inner.get_remote_record_values(key).await?
+            };
+            let Some(record_values) = record_values else {
+                // Record was removed since we got the list
+                continue;
+            };
+            if record_values.values.is_empty() {
+                continue;
+            }
+
+            log_stor!(debug "Republishing pinned record: {} values={} expiration={}", key, record_values.values.len(), pin.expiration);
+            if let Err(e) = self
+                .outbound_set_values(
+                    rpc_processor,
+                    key,
+                    SafetySelection::Unsafe(Sequencing::NoPreference),
+                    record_values.values,
+                    record_values.descriptor,
+                )
+                .await
+            {
+                log_stor!(debug "failed to republish pinned record: {} {}", key, e);
+            }
+        }
+
+        Ok(())
+    }
+}
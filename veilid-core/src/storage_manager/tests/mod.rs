@@ -0,0 +1,96 @@
+use super::*;
+
+pub mod test_pin_value;
+pub mod test_record_store;
+pub mod test_set_value;
+pub mod test_value_sync;
+
+use crate::tests::common::test_veilid_config::*;
+
+pub(crate) async fn startup() -> VeilidAPI {
+    trace!("storage_manager tests: starting");
+    let (update_callback, config_callback) = setup_veilid_core();
+    api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed")
+}
+
+pub(crate) async fn shutdown(api: VeilidAPI) {
+    trace!("storage_manager tests: shutting down");
+    api.shutdown().await;
+    trace!("storage_manager tests: finished");
+}
+
+/// Limits generous enough that tests only run into the rules they are testing
+pub(crate) fn mock_record_store_limits() -> RecordStoreLimits {
+    RecordStoreLimits {
+        subkey_cache_size: 16,
+        max_subkey_size: ValueData::MAX_LEN,
+        max_record_total_size: 1_048_576,
+        max_records: None,
+        max_subkey_cache_memory_mb: None,
+        max_storage_space_mb: None,
+        public_watch_limit: 0,
+        member_watch_limit: 0,
+        max_watch_expiration: TimestampDuration::default(),
+        min_watch_expiration: TimestampDuration::default(),
+        max_pinned_records: 0,
+        max_pinned_records_per_owner: 0,
+        max_pin_expiration: TimestampDuration::default(),
+        pin_allowed_owners: None,
+    }
+}
+
+/// Make an empty record store backed by the api's table store
+pub(crate) async fn mock_record_store(
+    api: &VeilidAPI,
+    name: &str,
+) -> RecordStore<RemoteRecordDetail> {
+    mock_record_store_with_limits(api, name, mock_record_store_limits()).await
+}
+
+/// Make an empty record store with specific limits backed by the api's table store
+pub(crate) async fn mock_record_store_with_limits(
+    api: &VeilidAPI,
+    name: &str,
+    limits: RecordStoreLimits,
+) -> RecordStore<RemoteRecordDetail> {
+    let mut record_store = RecordStore::new(
+        api.table_store().unwrap(),
+        api.crypto().unwrap(),
+        name,
+        limits,
+    );
+    record_store.init().await.unwrap();
+    record_store
+}
+
+/// Add a record with the given schema to a record store, returning its key and owner
+pub(crate) async fn mock_record(
+    record_store: &mut RecordStore<RemoteRecordDetail>,
+    vcrypto: CryptoSystemVersion,
+    schema: DHTSchema,
+) -> (TypedKey, KeyPair) {
+    let owner = vcrypto.generate_keypair();
+    let descriptor = Arc::new(SignedValueDescriptor::new(
+        owner.key,
+        schema.compile(),
+        Signature::default(),
+    ));
+    let key = TypedKey::new(vcrypto.kind(), vcrypto.generate_hash(&owner.key.bytes));
+    let record = Record::new(Timestamp::now(), descriptor, RemoteRecordDetail {}).unwrap();
+    record_store.new_record(key, record).await.unwrap();
+    (key, owner)
+}
+
+/// Make subkey value data. Record stores do not check signatures, so it is left unsigned.
+pub(crate) fn mock_value(
+    seq: ValueSeqNum,
+    data: Vec<u8>,
+    writer: PublicKey,
+) -> Arc<SignedValueData> {
+    Arc::new(SignedValueData::new(
+        ValueData::new_with_seq(seq, data, writer).unwrap(),
+        Signature::default(),
+    ))
+}
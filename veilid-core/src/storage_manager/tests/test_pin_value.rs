@@ -0,0 +1,209 @@
+use super::*;
+
+const ONE_HOUR: TimestampDuration = TimestampDuration::new(3_600_000_000u64);
+
+fn mock_pin_limits(
+    max_pinned_records: usize,
+    max_pinned_records_per_owner: usize,
+    pin_allowed_owners: Option<HashSet<TypedKey>>,
+) -> RecordStoreLimits {
+    RecordStoreLimits {
+        max_pinned_records,
+        max_pinned_records_per_owner,
+        max_pin_expiration: ONE_HOUR,
+        pin_allowed_owners,
+        ..mock_record_store_limits()
+    }
+}
+
+fn mock_pin(cur_ts: Timestamp, duration: TimestampDuration) -> RecordPin {
+    RecordPin {
+        timestamp: cur_ts,
+        expiration: cur_ts + duration,
+    }
+}
+
+/// Add a record of a specific owner to a record store, returning its key
+async fn mock_owned_record(
+    record_store: &mut RecordStore<RemoteRecordDetail>,
+    vcrypto: CryptoSystemVersion,
+    owner: PublicKey,
+    n: u8,
+) -> TypedKey {
+    let descriptor = Arc::new(SignedValueDescriptor::new(
+        owner,
+        DHTSchema::dflt(1).unwrap().compile(),
+        Signature::default(),
+    ));
+    let mut hash_data = owner.bytes.to_vec();
+    hash_data.push(n);
+    let key = TypedKey::new(vcrypto.kind(), vcrypto.generate_hash(&hash_data));
+    let record = Record::new(Timestamp::now(), descriptor, RemoteRecordDetail {}).unwrap();
+    record_store.new_record(key, record).await.unwrap();
+    key
+}
+
+/// Pin a record if the record store accepts the pin, as a pinning service would
+fn try_pin(
+    record_store: &mut RecordStore<RemoteRecordDetail>,
+    key: TypedKey,
+    owner: PublicKey,
+    pin: RecordPin,
+    cur_ts: Timestamp,
+) -> Option<RecordPin> {
+    let pin = record_store.accept_pin(key, &owner, pin, cur_ts)?;
+    record_store.with_record_mut(key, |r| r.set_pin(Some(pin)));
+    Some(pin)
+}
+
+pub async fn test_pin_slot_exhaustion(api: VeilidAPI) {
+    let vcrypto = api.crypto().unwrap().best();
+    let mut record_store =
+        mock_record_store_with_limits(&api, "test_pin_slots", mock_pin_limits(3, 2, None)).await;
+    let cur_ts = Timestamp::now();
+    let pin = mock_pin(cur_ts, ONE_HOUR);
+
+    let owner_a = vcrypto.generate_keypair().key;
+    let owner_b = vcrypto.generate_keypair().key;
+    let owner_c = vcrypto.generate_keypair().key;
+    let a1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 1).await;
+    let a2 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 2).await;
+    let a3 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 3).await;
+    let b1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_b, 1).await;
+    let c1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_c, 1).await;
+
+    // One owner can only take some of the slots
+    assert!(try_pin(&mut record_store, a1, owner_a, pin, cur_ts).is_some());
+    assert!(try_pin(&mut record_store, a2, owner_a, pin, cur_ts).is_some());
+    assert!(try_pin(&mut record_store, a3, owner_a, pin, cur_ts).is_none());
+
+    // Renewing a pin does not need another slot
+    let renewed = mock_pin(cur_ts + TimestampDuration::new(1u64), ONE_HOUR);
+    assert_eq!(
+        try_pin(&mut record_store, a1, owner_a, renewed, cur_ts),
+        Some(renewed)
+    );
+
+    // Once every slot is taken, no other owner can pin
+    assert!(try_pin(&mut record_store, b1, owner_b, pin, cur_ts).is_some());
+    assert!(try_pin(&mut record_store, c1, owner_c, pin, cur_ts).is_none());
+    assert_eq!(record_store.pinned_records(cur_ts).len(), 3);
+}
+
+pub async fn test_pin_expiry(api: VeilidAPI) {
+    let vcrypto = api.crypto().unwrap().best();
+    let mut record_store =
+        mock_record_store_with_limits(&api, "test_pin_expiry", mock_pin_limits(1, 1, None)).await;
+    let cur_ts = Timestamp::now();
+
+    let owner_a = vcrypto.generate_keypair().key;
+    let owner_b = vcrypto.generate_keypair().key;
+    let a1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 1).await;
+    let b1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_b, 1).await;
+
+    // Pins are cut short to the longest expiration allowed
+    let pin = try_pin(
+        &mut record_store,
+        a1,
+        owner_a,
+        mock_pin(cur_ts, ONE_HOUR * 10u64),
+        cur_ts,
+    )
+    .unwrap();
+    assert_eq!(pin.expiration, cur_ts + ONE_HOUR);
+    assert!(try_pin(
+        &mut record_store,
+        b1,
+        owner_b,
+        mock_pin(cur_ts, ONE_HOUR),
+        cur_ts
+    )
+    .is_none());
+
+    // An expired pin frees its slot
+    let later_ts = cur_ts + ONE_HOUR * 2u64;
+    assert!(record_store.pinned_records(later_ts).is_empty());
+    assert!(try_pin(
+        &mut record_store,
+        b1,
+        owner_b,
+        mock_pin(later_ts, ONE_HOUR),
+        later_ts
+    )
+    .is_some());
+}
+
+pub async fn test_pin_eviction(api: VeilidAPI) {
+    let vcrypto = api.crypto().unwrap().best();
+    let owner_a = vcrypto.generate_keypair().key;
+    let owner_b = vcrypto.generate_keypair().key;
+    let limits = RecordStoreLimits {
+        max_records: Some(3),
+        ..mock_pin_limits(4, 4, Some([TypedKey::new(vcrypto.kind(), owner_a)].into()))
+    };
+    let mut record_store = mock_record_store_with_limits(&api, "test_pin_eviction", limits).await;
+    let cur_ts = Timestamp::now();
+
+    // Only records with an unexpired pin from an allowed owner are kept over newer records
+    let pinned = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 1).await;
+    let expired = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 2).await;
+    let disallowed = mock_owned_record(&mut record_store, vcrypto.clone(), owner_b, 1).await;
+    record_store.with_record_mut(pinned, |r| r.set_pin(Some(mock_pin(cur_ts, ONE_HOUR))));
+    record_store.with_record_mut(expired, |r| {
+        r.set_pin(Some(RecordPin {
+            timestamp: cur_ts,
+            expiration: cur_ts,
+        }))
+    });
+    record_store.with_record_mut(disallowed, |r| r.set_pin(Some(mock_pin(cur_ts, ONE_HOUR))));
+
+    mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 3).await;
+    mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 4).await;
+
+    assert!(record_store.peek_record(pinned, |_| ()).is_some());
+    assert!(record_store.peek_record(expired, |_| ()).is_none());
+    assert!(record_store.peek_record(disallowed, |_| ()).is_none());
+}
+
+pub async fn test_pin_allowed_owners(api: VeilidAPI) {
+    let vcrypto = api.crypto().unwrap().best();
+    let owner_a = vcrypto.generate_keypair().key;
+    let owner_b = vcrypto.generate_keypair().key;
+    let allowed_owners: HashSet<TypedKey> = [TypedKey::new(vcrypto.kind(), owner_a)].into();
+    let mut record_store = mock_record_store_with_limits(
+        &api,
+        "test_pin_allowed",
+        mock_pin_limits(4, 4, Some(allowed_owners)),
+    )
+    .await;
+    let cur_ts = Timestamp::now();
+    let pin = mock_pin(cur_ts, ONE_HOUR);
+
+    let a1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 1).await;
+    let b1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_b, 1).await;
+
+    // Only allowed owners can pin
+    assert!(try_pin(&mut record_store, b1, owner_b, pin, cur_ts).is_none());
+    assert!(try_pin(&mut record_store, a1, owner_a, pin, cur_ts).is_some());
+
+    // Pins that are no longer allowed are not republished
+    record_store.with_record_mut(b1, |r| r.set_pin(Some(pin)));
+    assert_eq!(record_store.pinned_records(cur_ts), vec![(a1, pin)]);
+
+    // A store that does not accept pins rejects every owner
+    let mut record_store =
+        mock_record_store_with_limits(&api, "test_pin_disabled", mock_pin_limits(0, 4, None)).await;
+    let a1 = mock_owned_record(&mut record_store, vcrypto.clone(), owner_a, 1).await;
+    assert!(try_pin(&mut record_store, a1, owner_a, pin, cur_ts).is_none());
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+
+    test_pin_slot_exhaustion(api.clone()).await;
+    test_pin_expiry(api.clone()).await;
+    test_pin_eviction(api.clone()).await;
+    test_pin_allowed_owners(api.clone()).await;
+
+    shutdown(api).await;
+}
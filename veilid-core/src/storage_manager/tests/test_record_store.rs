@@ -0,0 +1,168 @@
+use super::*;
+
+async fn stored_data(
+    record_store: &mut RecordStore<RemoteRecordDetail>,
+    key: TypedKey,
+    subkey: ValueSubkey,
+) -> Option<Vec<u8>> {
+    record_store
+        .get_subkey(key, subkey, false)
+        .await
+        .unwrap()
+        .and_then(|r| r.opt_value)
+        .map(|v| v.value_data().data().to_vec())
+}
+
+pub async fn test_alog_first_write_checks_chain(api: VeilidAPI) {
+    let vcrypto = api.crypto().unwrap().best();
+    let mut record_store = mock_record_store(&api, "test_alog_first").await;
+    let (key, owner) = mock_record(
+        &mut record_store,
+        vcrypto.clone(),
+        DHTSchema::alog(2, vec![]).unwrap(),
+    )
+    .await;
+
+    // A first value whose first entry is chained to a previous entry that doesn't exist is rejected
+    let mut broken = Vec::new();
+    broken.extend_from_slice(&vcrypto.generate_hash(b"not here").bytes);
+    broken.extend_from_slice(&4u16.to_le_bytes());
+    broken.extend_from_slice(b"fake");
+    assert!(record_store
+        .set_subkey(
+            key,
+            0,
+            mock_value(0, broken, owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .is_err());
+    assert_eq!(stored_data(&mut record_store, key, 0).await, None);
+
+    // A first value whose later entries break the chain is rejected
+    let log = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &[], b"first").unwrap();
+    let mut broken = log.clone();
+    broken.extend_from_slice(&HashDigest::default().bytes);
+    broken.extend_from_slice(&6u16.to_le_bytes());
+    broken.extend_from_slice(b"second");
+    assert!(record_store
+        .set_subkey(
+            key,
+            1,
+            mock_value(0, broken, owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .is_err());
+    assert_eq!(stored_data(&mut record_store, key, 1).await, None);
+
+    // A properly chained first value is accepted
+    let log = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &log, b"second").unwrap();
+    record_store
+        .set_subkey(
+            key,
+            1,
+            mock_value(0, log.clone(), owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored_data(&mut record_store, key, 1).await, Some(log));
+}
+
+pub async fn test_alog_rewrite_rejected(api: VeilidAPI) {
+    let vcrypto = api.crypto().unwrap().best();
+    let mut record_store = mock_record_store(&api, "test_alog_rewrite").await;
+    let (key, owner) = mock_record(
+        &mut record_store,
+        vcrypto.clone(),
+        DHTSchema::alog(1, vec![]).unwrap(),
+    )
+    .await;
+
+    let log1 = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &[], b"one").unwrap();
+    let log2 = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &log1, b"two").unwrap();
+    record_store
+        .set_subkey(
+            key,
+            0,
+            mock_value(0, log2.clone(), owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Appending is allowed
+    let log3 = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &log2, b"three").unwrap();
+    record_store
+        .set_subkey(
+            key,
+            0,
+            mock_value(1, log3.clone(), owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Rewriting history with a validly chained log is not
+    let rewritten1 = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &[], b"uno").unwrap();
+    let rewritten2 = DHTSchemaALOG::append_log_entry(vcrypto.clone(), &rewritten1, b"two").unwrap();
+    let rewritten3 =
+        DHTSchemaALOG::append_log_entry(vcrypto.clone(), &rewritten2, b"three").unwrap();
+    let rewritten4 =
+        DHTSchemaALOG::append_log_entry(vcrypto.clone(), &rewritten3, b"four").unwrap();
+    assert!(record_store
+        .set_subkey(
+            key,
+            0,
+            mock_value(2, rewritten4, owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .is_err());
+
+    // Nor is truncating it
+    assert!(record_store
+        .set_subkey(
+            key,
+            0,
+            mock_value(2, log1, owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .is_err());
+
+    // Nor is appending an entry that isn't chained to the last one
+    let mut broken = log3.clone();
+    broken.extend_from_slice(&vcrypto.generate_hash(b"elsewhere").bytes);
+    broken.extend_from_slice(&4u16.to_le_bytes());
+    broken.extend_from_slice(b"four");
+    assert!(record_store
+        .set_subkey(
+            key,
+            0,
+            mock_value(2, broken, owner.key),
+            WatchUpdateMode::NoUpdate,
+            None,
+        )
+        .await
+        .is_err());
+
+    assert_eq!(stored_data(&mut record_store, key, 0).await, Some(log3));
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+
+    test_alog_first_write_checks_chain(api.clone()).await;
+    test_alog_rewrite_rejected(api.clone()).await;
+
+    shutdown(api).await;
+}
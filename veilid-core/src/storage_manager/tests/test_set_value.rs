@@ -0,0 +1,185 @@
+use super::*;
+
+async fn mock_local_record(storage_manager: &StorageManager) -> (TypedKey, KeyPair) {
+    let descriptor = storage_manager
+        .create_record(
+            best_crypto_kind(),
+            DHTSchema::dflt(2).unwrap(),
+            SafetySelection::Unsafe(Sequencing::NoPreference),
+        )
+        .await
+        .unwrap();
+    let owner = KeyPair::new(*descriptor.owner(), *descriptor.owner_secret().unwrap());
+    (*descriptor.key(), owner)
+}
+
+async fn local_value(
+    storage_manager: &StorageManager,
+    key: TypedKey,
+    subkey: ValueSubkey,
+) -> Option<ValueData> {
+    let mut inner = storage_manager.lock().await.unwrap();
+    inner
+        .handle_get_local_value(key, subkey, false)
+        .await
+        .unwrap()
+        .opt_value
+        .map(|v| v.value_data().clone())
+}
+
+async fn is_queued_offline(storage_manager: &StorageManager, key: TypedKey) -> bool {
+    let inner = storage_manager.lock().await.unwrap();
+    inner.offline_subkey_writes.contains_key(&key)
+}
+
+async fn clear_offline_writes(storage_manager: &StorageManager) {
+    let mut inner = storage_manager.lock().await.unwrap();
+    inner.offline_subkey_writes.clear();
+}
+
+fn mock_set_value_result(
+    kind: FanoutResultKind,
+    signed_value_data: Arc<SignedValueData>,
+) -> set_value::OutboundSetValueResult {
+    set_value::OutboundSetValueResult {
+        fanout_result: FanoutResult {
+            kind,
+            value_nodes: vec![],
+        },
+        signed_value_data,
+    }
+}
+
+pub async fn test_conditional_set_offline(api: VeilidAPI) {
+    let storage_manager = api.storage_manager().unwrap();
+    let (key, _owner) = mock_local_record(&storage_manager).await;
+
+    // A conditional write is rejected while offline, and is neither stored nor queued
+    let res = storage_manager
+        .set_value(key, 0, b"first".to_vec(), None, Some(ValueSeqNum::MAX))
+        .await;
+    assert!(matches!(res, Err(VeilidAPIError::TryAgain { .. })));
+    assert_eq!(local_value(&storage_manager, key, 0).await, None);
+    assert!(!is_queued_offline(&storage_manager, key).await);
+
+    // An unconditional write is stored and queued
+    storage_manager
+        .set_value(key, 0, b"first".to_vec(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        local_value(&storage_manager, key, 0).await.unwrap().data(),
+        b"first"
+    );
+    assert!(is_queued_offline(&storage_manager, key).await);
+    clear_offline_writes(&storage_manager).await;
+
+    // A conditional write based on the current value is still rejected offline
+    let res = storage_manager
+        .set_value(key, 0, b"second".to_vec(), None, Some(0))
+        .await;
+    assert!(matches!(res, Err(VeilidAPIError::TryAgain { .. })));
+    assert_eq!(
+        local_value(&storage_manager, key, 0).await.unwrap().data(),
+        b"first"
+    );
+    assert!(!is_queued_offline(&storage_manager, key).await);
+
+    // A conditional write based on a stale value fails the local check first
+    let res = storage_manager
+        .set_value(key, 0, b"second".to_vec(), None, Some(ValueSeqNum::MAX))
+        .await;
+    assert!(matches!(
+        res,
+        Err(VeilidAPIError::SeqMismatch {
+            expected: ValueSeqNum::MAX,
+            actual: 0
+        })
+    ));
+
+    storage_manager.close_record(key).await.unwrap();
+}
+
+pub async fn test_conditional_set_network_failure(api: VeilidAPI) {
+    let storage_manager = api.storage_manager().unwrap();
+    let (key, owner) = mock_local_record(&storage_manager).await;
+
+    let ours = mock_value(0, b"ours".to_vec(), owner.key);
+
+    // A conditional write that no node took is dropped, not stored or queued for replay
+    let res = storage_manager
+        .process_outbound_set_value_result(
+            key,
+            0,
+            ours.value_data().clone(),
+            SafetySelection::Unsafe(Sequencing::NoPreference),
+            Some(ValueSeqNum::MAX),
+            mock_set_value_result(FanoutResultKind::Timeout, ours.clone()),
+        )
+        .await;
+    assert!(matches!(res, Err(VeilidAPIError::TryAgain { .. })));
+    assert_eq!(local_value(&storage_manager, key, 0).await, None);
+    assert!(!is_queued_offline(&storage_manager, key).await);
+
+    // The same failure for an unconditional write is queued for replay
+    let res = storage_manager
+        .process_outbound_set_value_result(
+            key,
+            0,
+            ours.value_data().clone(),
+            SafetySelection::Unsafe(Sequencing::NoPreference),
+            None,
+            mock_set_value_result(FanoutResultKind::Timeout, ours.clone()),
+        )
+        .await;
+    assert_eq!(res.unwrap(), None);
+    assert!(is_queued_offline(&storage_manager, key).await);
+    clear_offline_writes(&storage_manager).await;
+
+    storage_manager.close_record(key).await.unwrap();
+}
+
+pub async fn test_conditional_set_seq_mismatch(api: VeilidAPI) {
+    let storage_manager = api.storage_manager().unwrap();
+    let (key, owner) = mock_local_record(&storage_manager).await;
+
+    storage_manager
+        .set_value(key, 1, b"base".to_vec(), None, None)
+        .await
+        .unwrap();
+    clear_offline_writes(&storage_manager).await;
+
+    // Our conditional write and a newer one that reached the network first
+    let ours = mock_value(1, b"ours".to_vec(), owner.key);
+    let theirs = mock_value(1, b"theirs".to_vec(), owner.key);
+
+    // The network value replaces the local value and is returned, so the caller reports a mismatch
+    let res = storage_manager
+        .process_outbound_set_value_result(
+            key,
+            1,
+            ours.value_data().clone(),
+            SafetySelection::Unsafe(Sequencing::NoPreference),
+            Some(0),
+            mock_set_value_result(FanoutResultKind::Finished, theirs.clone()),
+        )
+        .await;
+    assert_eq!(res.unwrap().as_ref(), Some(theirs.value_data()));
+    assert_eq!(
+        local_value(&storage_manager, key, 1).await.as_ref(),
+        Some(theirs.value_data())
+    );
+    assert!(!is_queued_offline(&storage_manager, key).await);
+
+    storage_manager.close_record(key).await.unwrap();
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+
+    test_conditional_set_offline(api.clone()).await;
+    test_conditional_set_network_failure(api.clone()).await;
+    test_conditional_set_seq_mismatch(api.clone()).await;
+
+    shutdown(api).await;
+}
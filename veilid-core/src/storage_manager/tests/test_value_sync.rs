@@ -0,0 +1,208 @@
+use super::*;
+
+fn record_key(n: u8) -> TypedKey {
+    TypedKey::new(best_crypto_kind(), CryptoKey::new([n; 32]))
+}
+
+fn subkeys(list: &[ValueSubkey]) -> ValueSubkeyRangeSet {
+    let mut out = ValueSubkeyRangeSet::new();
+    for subkey in list {
+        out.insert(*subkey);
+    }
+    out
+}
+
+fn safety_selection() -> SafetySelection {
+    SafetySelection::Unsafe(Sequencing::NoPreference)
+}
+
+// Collect the ValueSync updates sent by the storage manager instead of passing them to the api
+fn capture_value_syncs(inner: &mut StorageManagerInner) -> Arc<Mutex<Vec<VeilidValueSync>>> {
+    let value_syncs = Arc::new(Mutex::new(Vec::new()));
+    let captured = value_syncs.clone();
+    inner.update_callback = Some(Arc::new(move |update| {
+        if let VeilidUpdate::ValueSync(value_sync) = update {
+            captured.lock().push(*value_sync);
+        }
+    }));
+    value_syncs
+}
+
+fn value_sync(key: TypedKey, state: ValueSyncState, list: &[ValueSubkey]) -> VeilidValueSync {
+    VeilidValueSync {
+        key,
+        state,
+        subkeys: subkeys(list),
+    }
+}
+
+pub async fn test_sync_bookkeeping(api: VeilidAPI) {
+    let storage_manager = api.storage_manager().unwrap();
+    let key = record_key(1);
+
+    // Hold the lock throughout so the offline subkey writes task can't run in between
+    let mut inner = storage_manager.lock().await.unwrap();
+    let update_callback = inner.update_callback.clone();
+    let value_syncs = capture_value_syncs(&mut inner);
+
+    for subkey in 0..5 {
+        inner.add_offline_subkey_write(key, subkey, safety_selection());
+    }
+    let status = inner.get_record_sync_status(key);
+    assert_eq!(status.pending_subkeys(), &subkeys(&[0, 1, 2, 3, 4]));
+    assert!(status.conflict_subkeys().is_empty());
+
+    // Writes in flight are still pending
+    let mut offline_subkey_writes = inner.take_offline_subkey_writes();
+    assert!(inner.offline_subkey_writes.is_empty());
+    assert_eq!(
+        inner.get_record_sync_status(key).pending_subkeys(),
+        &subkeys(&[0, 1, 2, 3, 4])
+    );
+
+    let mut results = OfflineSubkeyWriteResults::default();
+    // Reached the network
+    results.add_result(0, false, false);
+    // Reached too few nodes, so it is tried again
+    results.add_result(2, true, false);
+    // Replaced by a newer value from the network, whether or not enough nodes took ours
+    results.add_result(1, false, true);
+    results.add_result(3, true, true);
+    // Could not be written at all
+    results.add_dropped(4);
+
+    let osw = offline_subkey_writes.get_mut(&key).unwrap();
+    inner.finish_offline_subkey_writes(key, osw, results);
+    let status = inner.get_record_sync_status(key);
+    assert_eq!(status.pending_subkeys(), &subkeys(&[2]));
+    assert_eq!(status.conflict_subkeys(), &subkeys(&[1, 3]));
+
+    // Only the subkeys that were not written are queued again
+    inner.requeue_offline_subkey_writes(offline_subkey_writes);
+    assert!(inner.offline_subkey_writes_in_flight.is_empty());
+    assert_eq!(
+        inner.offline_subkey_writes.get(&key).unwrap().subkeys,
+        subkeys(&[2])
+    );
+    let status = inner.get_record_sync_status(key);
+    assert_eq!(status.pending_subkeys(), &subkeys(&[2]));
+    assert_eq!(status.conflict_subkeys(), &subkeys(&[1, 3]));
+
+    // The next pass flushes the rest, without reporting the replaced subkeys as flushed
+    let mut offline_subkey_writes = inner.take_offline_subkey_writes();
+    let mut results = OfflineSubkeyWriteResults::default();
+    results.add_result(2, false, false);
+    let osw = offline_subkey_writes.get_mut(&key).unwrap();
+    inner.finish_offline_subkey_writes(key, osw, results);
+    inner.requeue_offline_subkey_writes(offline_subkey_writes);
+    assert!(inner.offline_subkey_writes.is_empty());
+    let status = inner.get_record_sync_status(key);
+    assert!(status.pending_subkeys().is_empty());
+    assert_eq!(status.conflict_subkeys(), &subkeys(&[1, 3]));
+
+    let mut expected = (0..5)
+        .map(|subkey| value_sync(key, ValueSyncState::Pending, &[subkey]))
+        .collect::<Vec<_>>();
+    expected.push(value_sync(key, ValueSyncState::Flushed, &[0]));
+    expected.push(value_sync(key, ValueSyncState::Conflict, &[1, 3]));
+    expected.push(value_sync(key, ValueSyncState::Flushed, &[2]));
+    assert_eq!(*value_syncs.lock(), expected);
+
+    inner.clear_offline_subkey_write_conflicts(key, ValueSubkeyRangeSet::new());
+    inner.update_callback = update_callback;
+}
+
+pub async fn test_clear_conflicts(api: VeilidAPI) {
+    let storage_manager = api.storage_manager().unwrap();
+    let key = record_key(2);
+    let other_key = record_key(3);
+
+    let mut inner = storage_manager.lock().await.unwrap();
+    inner.add_offline_subkey_write_conflicts(key, subkeys(&[0, 1, 2, 5]));
+    inner.add_offline_subkey_write_conflicts(key, subkeys(&[7]));
+    inner.add_offline_subkey_write_conflicts(other_key, subkeys(&[0]));
+    assert_eq!(
+        inner.get_record_sync_status(key).conflict_subkeys(),
+        &subkeys(&[0, 1, 2, 5, 7])
+    );
+
+    // Clearing a partial range leaves the other conflicts
+    inner.clear_offline_subkey_write_conflicts(key, subkeys(&[1, 2, 3]));
+    assert_eq!(
+        inner.get_record_sync_status(key).conflict_subkeys(),
+        &subkeys(&[0, 5, 7])
+    );
+
+    // Clearing every remaining subkey forgets the record
+    inner.clear_offline_subkey_write_conflicts(key, subkeys(&[0, 5, 7]));
+    assert!(!inner.offline_subkey_write_conflicts.contains_key(&key));
+
+    // Clearing an empty range clears all of a record's conflicts
+    inner.add_offline_subkey_write_conflicts(key, subkeys(&[4, 6]));
+    inner.clear_offline_subkey_write_conflicts(key, ValueSubkeyRangeSet::new());
+    assert!(!inner.offline_subkey_write_conflicts.contains_key(&key));
+    assert!(inner
+        .get_record_sync_status(key)
+        .conflict_subkeys()
+        .is_empty());
+
+    // Other records are left alone, and records without conflicts are ignored
+    assert_eq!(
+        inner.get_record_sync_status(other_key).conflict_subkeys(),
+        &subkeys(&[0])
+    );
+    inner.clear_offline_subkey_write_conflicts(record_key(4), ValueSubkeyRangeSet::new());
+    inner.clear_offline_subkey_write_conflicts(other_key, ValueSubkeyRangeSet::new());
+    assert!(inner.offline_subkey_write_conflicts.is_empty());
+}
+
+pub async fn test_save_in_flight(api: VeilidAPI) {
+    let storage_manager = api.storage_manager().unwrap();
+    let key = record_key(5);
+    let other_key = record_key(6);
+
+    let mut inner = storage_manager.lock().await.unwrap();
+    inner.add_offline_subkey_write(key, 0, safety_selection());
+    inner.add_offline_subkey_write(key, 1, safety_selection());
+    inner.add_offline_subkey_write(other_key, 0, safety_selection());
+    inner.take_offline_subkey_writes();
+
+    // Written again while the first writes are in flight
+    inner.add_offline_subkey_write(key, 2, safety_selection());
+    inner.add_offline_subkey_write_conflicts(key, subkeys(&[3]));
+
+    // Saving while writes are in flight keeps them pending, along with the newer writes
+    inner.flush().await.unwrap();
+    inner.offline_subkey_writes.clear();
+    inner.offline_subkey_writes_in_flight.clear();
+    inner.offline_subkey_write_conflicts.clear();
+    inner.load_metadata().await.unwrap();
+
+    assert!(inner.offline_subkey_writes_in_flight.is_empty());
+    assert_eq!(
+        inner.offline_subkey_writes.get(&key).unwrap().subkeys,
+        subkeys(&[0, 1, 2])
+    );
+    assert_eq!(
+        inner.offline_subkey_writes.get(&other_key).unwrap().subkeys,
+        subkeys(&[0])
+    );
+    let status = inner.get_record_sync_status(key);
+    assert_eq!(status.pending_subkeys(), &subkeys(&[0, 1, 2]));
+    assert_eq!(status.conflict_subkeys(), &subkeys(&[3]));
+
+    // Leave nothing queued for the offline subkey writes task
+    inner.offline_subkey_writes.clear();
+    inner.offline_subkey_write_conflicts.clear();
+    inner.flush().await.unwrap();
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+
+    test_sync_bookkeeping(api.clone()).await;
+    test_clear_conflicts(api.clone()).await;
+    test_save_in_flight(api.clone()).await;
+
+    shutdown(api).await;
+}
@@ -513,6 +513,7 @@ impl StorageManager {
                             first_subkey,
                             value.clone(),
                             WatchUpdateMode::NoUpdate,
+                            None,
                         )
                         .await?;
                 }
@@ -0,0 +1,164 @@
+use super::*;
+
+// Backup archive format:
+// 0x00 - 0x03: magic 'VBAK'
+// 0x04: version
+// 0x05 - 0x08: crypto kind used to derive the key and encrypt the contents
+// 0x09 - 0x28: password salt
+// 0x29 - 0x40: nonce
+// 0x41 - ...: encrypted and compressed contents, with the header as associated data
+const BACKUP_MAGIC: &[u8; 4] = b"VBAK";
+const BACKUP_VERSION: u8 = 0;
+const BACKUP_SALT_LENGTH: usize = 32;
+const BACKUP_HEADER_LENGTH: usize = 0x41;
+
+/// Tables that only hold caches that are rebuilt automatically, and are left out of backups
+const BACKUP_EXCLUDED_TABLES: &[&str] = &["crypto_caches"];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupTable {
+    name: String,
+    column_count: u32,
+    columns: Vec<Vec<TableDBEntry>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupContents {
+    tables: Vec<BackupTable>,
+}
+
+impl TableStore {
+    // Get the names of the tables in our namespace that belong in a backup, without the namespace prefix
+    fn backup_table_names(&self) -> Vec<String> {
+        let namespace = self.config.get().namespace.clone();
+        let inner = self.inner.lock();
+        let mut names = inner
+            .all_table_names
+            .keys()
+            .filter_map(|name| {
+                if namespace.is_empty() {
+                    (!name.starts_with("_ns_")).then(|| name.clone())
+                } else {
+                    name.strip_prefix(&format!("_ns_{}_", namespace))
+                        .map(|n| n.to_owned())
+                }
+            })
+            .filter(|name| !BACKUP_EXCLUDED_TABLES.contains(&name.as_str()))
+            .collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+
+    /// Export every table in this namespace to a single archive encrypted with a password.
+    ///
+    /// This includes the node identity, the route spec store and the DHT record stores,
+    /// along with any application tables. The archive can be restored into an empty
+    /// table store with [TableStore::import_backup].
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    pub async fn export_backup(&self, password: &str) -> VeilidAPIResult<Vec<u8>> {
+        let crypto = {
+            let inner = self.inner.lock();
+            if inner.all_tables_db.is_none() {
+                apibail_not_initialized!();
+            }
+            inner.crypto.as_ref().unwrap().clone()
+        };
+
+        // Read every column of every table
+        let mut tables = Vec::new();
+        for name in self.backup_table_names() {
+            let mut db = self.open(&name, 1).await?;
+            let column_count = db.get_column_count()?;
+            if column_count > 1 {
+                db = self.open(&name, column_count).await?;
+            }
+            let mut columns = Vec::with_capacity(column_count as usize);
+            for col in 0..column_count {
+                columns.push(db.scan(col, TableDBScan::default()).await?);
+            }
+            tables.push(BackupTable {
+                name,
+                column_count,
+                columns,
+            });
+        }
+        let body = compress_prepend_size(&serialize_json_bytes(BackupContents { tables }));
+
+        // Encrypt with a key derived from the password, authenticating the header too
+        let vcrypto = crypto.best();
+        let mut salt = [0u8; BACKUP_SALT_LENGTH];
+        random_bytes(&mut salt);
+        let nonce = vcrypto.random_nonce();
+        let shared_secret = vcrypto.derive_shared_secret(password.as_bytes(), &salt)?;
+
+        let mut out = Vec::with_capacity(BACKUP_HEADER_LENGTH + body.len());
+        out.extend_from_slice(BACKUP_MAGIC);
+        out.push(BACKUP_VERSION);
+        out.extend_from_slice(&vcrypto.kind().0);
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce.bytes);
+        let ciphertext = vcrypto.encrypt_aead(&body, &nonce, &shared_secret, Some(&out))?;
+        out.extend_from_slice(&ciphertext);
+
+        Ok(out)
+    }
+
+    /// Restore an archive made by [TableStore::export_backup].
+    ///
+    /// The table store must not have any tables in this namespace yet, so restoring
+    /// can never overwrite the state of an existing node.
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    pub async fn import_backup(&self, archive: &[u8], password: &str) -> VeilidAPIResult<()> {
+        let crypto = {
+            let inner = self.inner.lock();
+            if inner.all_tables_db.is_none() {
+                apibail_not_initialized!();
+            }
+            inner.crypto.as_ref().unwrap().clone()
+        };
+        if !self.backup_table_names().is_empty() {
+            apibail_generic!("backups can only be restored into an empty table store");
+        }
+
+        // Parse the header
+        if archive.len() < BACKUP_HEADER_LENGTH || &archive[0x00..0x04] != BACKUP_MAGIC {
+            apibail_parse_error!("not a backup archive", archive.len());
+        }
+        if archive[0x04] != BACKUP_VERSION {
+            apibail_parse_error!("unsupported backup version", archive[0x04]);
+        }
+        let (header, ciphertext) = archive.split_at(BACKUP_HEADER_LENGTH);
+        let kind = CryptoKind::try_from(&header[0x05..0x09])?;
+        let salt = &header[0x09..0x29];
+        let nonce = Nonce::try_from(&header[0x29..0x41])?;
+        let Some(vcrypto) = crypto.get(kind) else {
+            apibail_generic!(format!("unsupported cryptosystem in backup: {}", kind));
+        };
+
+        // Decrypt and unpack the contents
+        let shared_secret = vcrypto.derive_shared_secret(password.as_bytes(), salt)?;
+        let body = vcrypto
+            .decrypt_aead(ciphertext, &nonce, &shared_secret, Some(header))
+            .map_err(|_| VeilidAPIError::generic("incorrect password or corrupt backup"))?;
+        let body = decompress_size_prepended(&body, None)?;
+        let contents: BackupContents = serde_json::from_slice(&body)
+            .map_err(|e| VeilidAPIError::parse_error("invalid backup contents", e))?;
+
+        // Write out every table
+        for table in contents.tables {
+            if table.columns.len() != table.column_count as usize {
+                apibail_parse_error!("backup table has wrong number of columns", table.name);
+            }
+            let db = self.open(&table.name, table.column_count).await?;
+            let tx = db.transact();
+            for (col, entries) in table.columns.iter().enumerate() {
+                for entry in entries {
+                    tx.store(col as u32, &entry.key, &entry.value)?;
+                }
+            }
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+}
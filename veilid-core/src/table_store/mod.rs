@@ -1,5 +1,6 @@
 use super::*;
 
+mod backup;
 mod table_db;
 pub use table_db::*;
 
@@ -17,6 +18,9 @@ use native::*;
 use keyvaluedb::*;
 
 const ALL_TABLE_NAMES: &[u8] = b"all_table_names";
+const REKEY_STATE: &[u8] = b"rekey_state";
+const DEVICE_ENCRYPTION_KEY: &str = "device_encryption_key";
+const PENDING_DEVICE_ENCRYPTION_KEY: &str = "pending_device_encryption_key";
 
 /// Description of column
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -65,9 +69,23 @@ pub struct TableInfo {
     pub columns: Vec<ColumnInfo>,
 }
 
+/// Progress of a device encryption key rotation, persisted alongside the table names
+/// Tables are identified by their internal names, which change when they are re-keyed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RekeyState {
+    /// Tables that are encrypted with the pending key
+    done: HashSet<String>,
+    /// A partially written copy of a table being re-keyed
+    in_progress: Option<String>,
+    /// Tables that have been replaced by their re-keyed copy but not yet deleted
+    pending_delete: Vec<String>,
+}
+
 struct TableStoreInner {
     opened: BTreeMap<String, Weak<TableDBUnlockedInner>>,
     encryption_key: Option<TypedSharedSecret>,
+    rekey_encryption_key: Option<TypedSharedSecret>,
+    rekey_state: Option<RekeyState>,
     all_table_names: HashMap<String, String>,
     all_tables_db: Option<Database>,
     crypto: Option<Crypto>,
@@ -89,6 +107,8 @@ impl TableStore {
         TableStoreInner {
             opened: BTreeMap::new(),
             encryption_key: None,
+            rekey_encryption_key: None,
+            rekey_state: None,
             all_table_names: HashMap::new(),
             all_tables_db: None,
             crypto: None,
@@ -114,18 +134,51 @@ impl TableStore {
 
     // Flush internal control state (must not use crypto)
     async fn flush(&self) {
-        let (all_table_names_value, all_tables_db) = {
+        if let Err(e) = self.try_flush().await {
+            error!("failed to write all tables db: {}", e);
+        }
+    }
+
+    // Atomically write the table names and re-key progress together
+    async fn try_flush(&self) -> VeilidAPIResult<()> {
+        let (all_table_names_value, rekey_state_value, all_tables_db) = {
             let inner = self.inner.lock();
             let all_table_names_value = serialize_json_bytes(&inner.all_table_names);
-            (all_table_names_value, inner.all_tables_db.clone().unwrap())
+            let rekey_state_value = inner.rekey_state.as_ref().map(serialize_json_bytes);
+            (
+                all_table_names_value,
+                rekey_state_value,
+                inner.all_tables_db.clone().unwrap(),
+            )
         };
         let mut dbt = DBTransaction::new();
         dbt.put(0, ALL_TABLE_NAMES, &all_table_names_value);
-        if let Err(e) = all_tables_db.write(dbt).await {
-            error!("failed to write all tables db: {}", e);
+        match rekey_state_value {
+            Some(v) => dbt.put(0, REKEY_STATE, &v),
+            None => dbt.delete(0, REKEY_STATE),
+        }
+        all_tables_db.write(dbt).await.map_err(VeilidAPIError::from)
+    }
+
+    // Get the key a table is encrypted with, which depends on whether it has been re-keyed yet
+    fn table_encryption_key(
+        inner: &TableStoreInner,
+        table_name: &str,
+    ) -> Option<TypedSharedSecret> {
+        match &inner.rekey_state {
+            Some(rekey_state) if rekey_state.done.contains(table_name) => {
+                inner.rekey_encryption_key
+            }
+            _ => inner.encryption_key,
         }
     }
 
+    fn new_real_name() -> String {
+        let mut real_name_bytes = [0u8; 32];
+        random_bytes(&mut real_name_bytes);
+        data_encoding::BASE64URL_NOPAD.encode(&real_name_bytes)
+    }
+
     // Internal naming support
     // Adds rename capability and ensures names of tables are totally unique and valid
 
@@ -155,9 +208,12 @@ impl TableStore {
         }
 
         // If not, make a new low level name mapping
-        let mut real_name_bytes = [0u8; 32];
-        random_bytes(&mut real_name_bytes);
-        let real_name = data_encoding::BASE64URL_NOPAD.encode(&real_name_bytes);
+        let real_name = Self::new_real_name();
+
+        // New tables start out encrypted with the pending key if we are re-keying
+        if let Some(rekey_state) = &mut inner.rekey_state {
+            rekey_state.done.insert(real_name.clone());
+        }
 
         if inner
             .all_table_names
@@ -335,7 +391,7 @@ impl TableStore {
     async fn load_device_encryption_key(&self) -> EyreResult<Option<TypedSharedSecret>> {
         let dek_bytes: Option<Vec<u8>> = self
             .protected_store
-            .load_user_secret("device_encryption_key")
+            .load_user_secret(DEVICE_ENCRYPTION_KEY)
             .await?;
         let Some(dek_bytes) = dek_bytes else {
             log_tstore!(debug "no device encryption key");
@@ -363,7 +419,7 @@ impl TableStore {
             // Remove the device encryption key
             let existed = self
                 .protected_store
-                .remove_user_secret("device_encryption_key")
+                .remove_user_secret(DEVICE_ENCRYPTION_KEY)
                 .await?;
             log_tstore!(debug "removed device encryption key. existed: {}", existed);
             return Ok(());
@@ -401,24 +457,76 @@ impl TableStore {
         // Save the new device encryption key
         let existed = self
             .protected_store
-            .save_user_secret("device_encryption_key", &dek_bytes)
+            .save_user_secret(DEVICE_ENCRYPTION_KEY, &dek_bytes)
             .await?;
         log_tstore!(debug "saving device encryption key. existed: {}", existed);
         Ok(())
     }
 
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    async fn load_pending_device_encryption_key(&self) -> EyreResult<Option<TypedSharedSecret>> {
+        let dek_bytes: Option<Vec<u8>> = self
+            .protected_store
+            .load_user_secret(PENDING_DEVICE_ENCRYPTION_KEY)
+            .await?;
+        let Some(dek_bytes) = dek_bytes else {
+            return Ok(None);
+        };
+
+        // The pending key is protected with the same password as the current key
+        let device_encryption_key_password = {
+            let c = self.config.get();
+            c.protected_store.device_encryption_key_password.clone()
+        };
+
+        Ok(Some(self.maybe_unprotect_device_encryption_key(
+            &dek_bytes,
+            &device_encryption_key_password,
+        )?))
+    }
+
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    async fn save_pending_device_encryption_key(
+        &self,
+        pending_device_encryption_key: Option<TypedSharedSecret>,
+    ) -> EyreResult<()> {
+        let Some(pending_device_encryption_key) = pending_device_encryption_key else {
+            let existed = self
+                .protected_store
+                .remove_user_secret(PENDING_DEVICE_ENCRYPTION_KEY)
+                .await?;
+            log_tstore!(debug "removed pending device encryption key. existed: {}", existed);
+            return Ok(());
+        };
+
+        let device_encryption_key_password = {
+            let c = self.config.get();
+            c.protected_store.device_encryption_key_password.clone()
+        };
+        let dek_bytes = self.maybe_protect_device_encryption_key(
+            pending_device_encryption_key,
+            &device_encryption_key_password,
+        )?;
+        self.protected_store
+            .save_user_secret(PENDING_DEVICE_ENCRYPTION_KEY, &dek_bytes)
+            .await?;
+        Ok(())
+    }
+
     #[instrument(level = "trace", target = "tstore", skip_all)]
     pub(crate) async fn init(&self) -> EyreResult<()> {
         let _async_guard = self.async_lock.lock().await;
 
         // Get device encryption key from protected store
         let mut device_encryption_key = self.load_device_encryption_key().await?;
+        let pending_device_encryption_key = self.load_pending_device_encryption_key().await?;
         let mut device_encryption_key_changed = false;
+        let mut migrate_kind = None;
         if let Some(device_encryption_key) = device_encryption_key {
             // If encryption in current use is not the best encryption, then run table migration
             let best_kind = best_crypto_kind();
             if device_encryption_key.kind != best_kind {
-                // XXX: Run migration. See issue #209
+                migrate_kind = Some(best_kind);
             }
         } else {
             // If we don't have an encryption key yet, then make one with the best cryptography and save it
@@ -444,6 +552,12 @@ impl TableStore {
                 .await?;
         }
 
+        // A pending key from an interrupted rotation must follow the password change
+        if changing_password && pending_device_encryption_key.is_some() {
+            self.save_pending_device_encryption_key(pending_device_encryption_key)
+                .await?;
+        }
+
         // Deserialize all table names
         let all_tables_db = self
             .table_store_driver
@@ -469,10 +583,33 @@ impl TableStore {
             }
         };
 
+        // Pick up any device encryption key rotation that was interrupted
+        let rekey_state = match all_tables_db.get(0, REKEY_STATE).await {
+            Ok(Some(v)) => match deserialize_json_bytes::<RekeyState>(&v) {
+                Ok(rekey_state) => Some(rekey_state),
+                Err(e) => {
+                    error!("could not deserialize rekey state: {}", e);
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                error!("could not get rekey state: {}", e);
+                None
+            }
+        };
+
         {
             let mut inner = self.inner.lock();
             inner.encryption_key = device_encryption_key;
             inner.all_tables_db = Some(all_tables_db);
+            if let Some(pending_device_encryption_key) = pending_device_encryption_key {
+                // If the pending key was already made current, the rotation only needs cleaning up
+                if Some(pending_device_encryption_key) != device_encryption_key {
+                    inner.rekey_encryption_key = Some(pending_device_encryption_key);
+                    inner.rekey_state = Some(rekey_state.unwrap_or_default());
+                }
+            }
         }
 
         let do_delete = {
@@ -484,6 +621,21 @@ impl TableStore {
             self.delete_all().await;
         }
 
+        if pending_device_encryption_key.is_some() {
+            log_tstore!(debug "resuming device encryption key rotation");
+            let remaining = self.rekey_tables().await?;
+            if remaining != 0 {
+                bail!("{} tables could not be re-keyed during init", remaining);
+            }
+        } else if let Some(migrate_kind) = migrate_kind {
+            log_tstore!(debug "migrating device encryption key to {}", migrate_kind);
+            self.start_rekey(migrate_kind).await?;
+            let remaining = self.rekey_tables().await?;
+            if remaining != 0 {
+                bail!("{} tables could not be migrated during init", remaining);
+            }
+        }
+
         Ok(())
     }
 
@@ -503,6 +655,252 @@ impl TableStore {
         inner.all_tables_db = None;
         inner.all_table_names.clear();
         inner.encryption_key = None;
+        inner.rekey_encryption_key = None;
+        inner.rekey_state = None;
+    }
+
+    // Begin rotating to a new device encryption key of the given kind
+    // Must be called with the async lock held
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    async fn start_rekey(&self, kind: CryptoKind) -> VeilidAPIResult<()> {
+        let crypto = self.inner.lock().crypto.as_ref().unwrap().clone();
+        if crypto.get(kind).is_none() {
+            apibail_invalid_argument!("unsupported cryptosystem", "kind", kind);
+        }
+
+        let mut shared_secret = SharedSecret::default();
+        random_bytes(&mut shared_secret.bytes);
+        let pending_device_encryption_key = TypedSharedSecret::new(kind, shared_secret);
+
+        // Save the pending key before anything is encrypted with it
+        self.save_pending_device_encryption_key(Some(pending_device_encryption_key))
+            .await
+            .map_err(VeilidAPIError::internal)?;
+
+        {
+            let mut inner = self.inner.lock();
+            inner.rekey_encryption_key = Some(pending_device_encryption_key);
+            inner.rekey_state = Some(RekeyState::default());
+        }
+        self.try_flush().await
+    }
+
+    // Re-key every table that is not currently open, finishing the rotation if none are left
+    // Returns the number of tables that still need re-keying
+    // Must be called with the async lock held
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    async fn rekey_tables(&self) -> VeilidAPIResult<u32> {
+        // Clean up after an interrupted table copy or deletion
+        let stale_tables = {
+            let inner = self.inner.lock();
+            inner.rekey_state.as_ref().map(|rekey_state| {
+                rekey_state
+                    .in_progress
+                    .iter()
+                    .chain(rekey_state.pending_delete.iter())
+                    .cloned()
+                    .collect::<Vec<String>>()
+            })
+        };
+        let Some(stale_tables) = stale_tables else {
+            // The pending key is already in use, so only the cleanup remains
+            self.finish_rekey().await?;
+            return Ok(0);
+        };
+        if !stale_tables.is_empty() {
+            for table_name in &stale_tables {
+                self.table_store_driver.delete(table_name).await?;
+            }
+            {
+                let mut inner = self.inner.lock();
+                let rekey_state = inner.rekey_state.as_mut().unwrap();
+                rekey_state.in_progress = None;
+                rekey_state.pending_delete.clear();
+            }
+            self.try_flush().await?;
+        }
+
+        // Re-key whatever tables are not open
+        let table_names = {
+            let inner = self.inner.lock();
+            let rekey_state = inner.rekey_state.as_ref().unwrap();
+            inner
+                .all_table_names
+                .values()
+                .filter(|v| !rekey_state.done.contains(*v))
+                .cloned()
+                .collect::<Vec<String>>()
+        };
+        let mut remaining = 0u32;
+        for table_name in table_names {
+            if self.inner.lock().opened.contains_key(&table_name) {
+                log_tstore!(debug "not re-keying open table: {}", table_name);
+                remaining += 1;
+                continue;
+            }
+            self.rekey_table(&table_name).await?;
+        }
+
+        if remaining == 0 {
+            self.finish_rekey().await?;
+        }
+        Ok(remaining)
+    }
+
+    // Copy a table into a new table encrypted with the pending key, then swap it in
+    // Must be called with the async lock held
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    async fn rekey_table(&self, table_name: &str) -> VeilidAPIResult<()> {
+        log_tstore!(debug "re-keying table: {}", table_name);
+
+        // Record the copy's name first so a crash part way through can be cleaned up
+        let new_table_name = Self::new_real_name();
+        let (decrypt_info, encrypt_info) = {
+            let mut inner = self.inner.lock();
+            inner.rekey_state.as_mut().unwrap().in_progress = Some(new_table_name.clone());
+            let crypto = inner.crypto.as_ref().unwrap().clone();
+            (
+                inner
+                    .encryption_key
+                    .map(|k| CryptInfo::new(crypto.clone(), k)),
+                inner
+                    .rekey_encryption_key
+                    .map(|k| CryptInfo::new(crypto.clone(), k)),
+            )
+        };
+        self.try_flush().await?;
+
+        // Open the old table with all of its columns and create the copy to match
+        let mut db = self.table_store_driver.open(table_name, 1).await?;
+        let column_count = db.num_columns().map_err(VeilidAPIError::from)?;
+        if column_count > 1 {
+            drop(db);
+            db = self
+                .table_store_driver
+                .open(table_name, column_count)
+                .await?;
+        }
+        let new_db = self
+            .table_store_driver
+            .open(&new_table_name, column_count)
+            .await?;
+
+        for col in 0..column_count {
+            let mut keys = Vec::new();
+            db.iter_keys(col, None, |k| {
+                keys.push(k.to_vec());
+                Ok(Option::<()>::None)
+            })
+            .await
+            .map_err(VeilidAPIError::from)?;
+
+            let mut dbt = new_db.transaction();
+            for k in keys {
+                let Some(v) = db.get(col, &k).await.map_err(VeilidAPIError::from)? else {
+                    continue;
+                };
+                let key = maybe_decrypt_with(decrypt_info.as_ref(), &k)?;
+                let value = maybe_decrypt_with(decrypt_info.as_ref(), &v)?;
+                dbt.put_owned(
+                    col,
+                    maybe_encrypt_with(encrypt_info.as_ref(), &key, true),
+                    maybe_encrypt_with(encrypt_info.as_ref(), &value, false),
+                );
+            }
+            new_db.write(dbt).await.map_err(VeilidAPIError::from)?;
+        }
+        drop(db);
+        drop(new_db);
+
+        // Point the table's name at the copy, atomically with the re-key progress
+        {
+            let mut inner = self.inner.lock();
+            for v in inner.all_table_names.values_mut() {
+                if v == table_name {
+                    v.clone_from(&new_table_name);
+                }
+            }
+            let rekey_state = inner.rekey_state.as_mut().unwrap();
+            rekey_state.in_progress = None;
+            rekey_state.pending_delete.push(table_name.to_owned());
+            rekey_state.done.insert(new_table_name);
+        }
+        self.try_flush().await?;
+
+        // Now the old table can go
+        self.table_store_driver.delete(table_name).await?;
+        {
+            let mut inner = self.inner.lock();
+            let rekey_state = inner.rekey_state.as_mut().unwrap();
+            rekey_state.pending_delete.retain(|x| x != table_name);
+        }
+        self.try_flush().await
+    }
+
+    // Make the pending key the device encryption key once every table uses it
+    // Must be called with the async lock held
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    async fn finish_rekey(&self) -> VeilidAPIResult<()> {
+        let rekey_encryption_key = self.inner.lock().rekey_encryption_key;
+        if let Some(rekey_encryption_key) = rekey_encryption_key {
+            self.save_device_encryption_key(Some(rekey_encryption_key))
+                .await
+                .map_err(VeilidAPIError::internal)?;
+            let mut inner = self.inner.lock();
+            inner.encryption_key = Some(rekey_encryption_key);
+            inner.rekey_encryption_key = None;
+        }
+        self.inner.lock().rekey_state = None;
+        self.try_flush().await?;
+
+        self.save_pending_device_encryption_key(None)
+            .await
+            .map_err(VeilidAPIError::internal)?;
+        log_tstore!(debug "device encryption key rotation complete");
+        Ok(())
+    }
+
+    /// Rotate the device encryption key, re-encrypting every table with a newly generated key.
+    ///
+    /// The new key is of the given crypto kind, or the best available kind if none is given.
+    /// Changing `device_encryption_key_password` only re-protects the existing key,
+    /// so this is the way to replace the key itself.
+    ///
+    /// Tables that are open can not be re-keyed, so they are skipped and the number of
+    /// tables remaining is returned. Calling this again once they are closed continues the
+    /// same rotation, and any rotation left unfinished is completed the next time the
+    /// table store starts up. Each table is re-keyed into a copy that only replaces the
+    /// original once it is complete, so an interruption at any point loses no data.
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    pub async fn rekey(&self, kind: Option<CryptoKind>) -> VeilidAPIResult<u32> {
+        let _async_guard = self.async_lock.lock().await;
+        // If we aren't initialized yet, bail
+        let pending_kind = {
+            let inner = self.inner.lock();
+            if inner.all_tables_db.is_none() {
+                apibail_not_initialized!();
+            }
+            inner.rekey_encryption_key.map(|k| k.kind)
+        };
+
+        match pending_kind {
+            Some(pending_kind) => {
+                if let Some(kind) = kind {
+                    if kind != pending_kind {
+                        apibail_generic!(format!(
+                            "key rotation to {} is already in progress",
+                            pending_kind
+                        ));
+                    }
+                }
+            }
+            None => {
+                self.start_rekey(kind.unwrap_or_else(best_crypto_kind))
+                    .await?;
+            }
+        }
+
+        self.rekey_tables().await
     }
 
     #[instrument(level = "trace", target = "tstore", skip_all)]
@@ -591,13 +989,14 @@ impl TableStore {
 
         // Wrap low-level Database in TableDB object
         let mut inner = self.inner.lock();
+        let encryption_key = Self::table_encryption_key(&inner, &table_name);
         let table_db = TableDB::new(
             table_name.clone(),
             self.clone(),
             inner.crypto.as_ref().unwrap().clone(),
             db,
-            inner.encryption_key,
-            inner.encryption_key,
+            encryption_key,
+            encryption_key,
             column_count,
         );
 
@@ -10,7 +10,7 @@ cfg_if! {
     }
 }
 
-struct CryptInfo {
+pub(super) struct CryptInfo {
     vcrypto: CryptoSystemVersion,
     key: SharedSecret,
 }
@@ -22,6 +22,142 @@ impl CryptInfo {
     }
 }
 
+/// Encrypt buffer using encrypt key, if any, and prepend nonce to output.
+/// Keyed nonces are unique because keys must be unique.
+/// Normally they must be sequential or random, but the critical.
+/// requirement is that they are different for each encryption
+/// but if the contents are guaranteed to be unique, then a nonce
+/// can be generated from the hash of the contents and the encryption key itself.
+pub(super) fn maybe_encrypt_with(
+    encrypt_info: Option<&CryptInfo>,
+    data: &[u8],
+    keyed_nonce: bool,
+) -> Vec<u8> {
+    let data = compress_prepend_size(data);
+    if let Some(ei) = encrypt_info {
+        let mut out = unsafe { unaligned_u8_vec_uninit(NONCE_LENGTH + data.len()) };
+
+        if keyed_nonce {
+            // Key content nonce
+            let mut noncedata = Vec::with_capacity(data.len() + PUBLIC_KEY_LENGTH);
+            noncedata.extend_from_slice(&data);
+            noncedata.extend_from_slice(&ei.key.bytes);
+            let noncehash = ei.vcrypto.generate_hash(&noncedata);
+            out[0..NONCE_LENGTH].copy_from_slice(&noncehash[0..NONCE_LENGTH])
+        } else {
+            // Random nonce
+            random_bytes(&mut out[0..NONCE_LENGTH]);
+        }
+
+        let (nonce, encout) = out.split_at_mut(NONCE_LENGTH);
+        ei.vcrypto
+            .crypt_b2b_no_auth(&data, encout, (nonce as &[u8]).try_into().unwrap(), &ei.key);
+        out
+    } else {
+        data
+    }
+}
+
+/// Decrypt buffer using decrypt key, if any, with nonce prepended to input
+pub(super) fn maybe_decrypt_with(
+    decrypt_info: Option<&CryptInfo>,
+    data: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    if let Some(di) = decrypt_info {
+        assert!(data.len() >= NONCE_LENGTH);
+        if data.len() == NONCE_LENGTH {
+            return Ok(Vec::new());
+        }
+
+        let mut out = unsafe { unaligned_u8_vec_uninit(data.len() - NONCE_LENGTH) };
+
+        di.vcrypto.crypt_b2b_no_auth(
+            &data[NONCE_LENGTH..],
+            &mut out,
+            (&data[0..NONCE_LENGTH]).try_into().unwrap(),
+            &di.key,
+        );
+        decompress_size_prepended(&out, None)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+    } else {
+        decompress_size_prepended(data, None)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+    }
+}
+
+/// Selects an ordered page of the keys in a TableDB column
+///
+/// Keys are stored compressed and, for protected tables, encrypted with a keyed nonce,
+/// so the database's own key order says nothing about the order of the plaintext keys.
+/// Scans therefore visit every key in the column and order the matches by their
+/// plaintext bytes, holding no more than `limit` keys in memory at a time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TableDBScan {
+    /// Only include keys that start with this prefix
+    #[serde(default, with = "as_human_opt_base64")]
+    #[schemars(with = "Option<String>")]
+    pub prefix: Option<Vec<u8>>,
+    /// Only include keys greater than or equal to this key
+    #[serde(default, with = "as_human_opt_base64")]
+    #[schemars(with = "Option<String>")]
+    pub start: Option<Vec<u8>>,
+    /// Only include keys less than this key
+    #[serde(default, with = "as_human_opt_base64")]
+    #[schemars(with = "Option<String>")]
+    pub end: Option<Vec<u8>>,
+    /// Resume after this key, usually the last key of the previous page
+    #[serde(default, with = "as_human_opt_base64")]
+    #[schemars(with = "Option<String>")]
+    pub after: Option<Vec<u8>>,
+    /// Return keys in descending order instead of ascending order
+    #[serde(default)]
+    pub reverse: bool,
+    /// The maximum number of keys to return, or zero for no limit
+    #[serde(default)]
+    pub limit: u32,
+}
+
+impl TableDBScan {
+    fn matches(&self, key: &[u8]) -> bool {
+        if let Some(prefix) = &self.prefix {
+            if !key.starts_with(prefix) {
+                return false;
+            }
+        }
+        if let Some(start) = &self.start {
+            if key < start.as_slice() {
+                return false;
+            }
+        }
+        if let Some(end) = &self.end {
+            if key >= end.as_slice() {
+                return false;
+            }
+        }
+        if let Some(after) = &self.after {
+            if self.reverse {
+                if key >= after.as_slice() {
+                    return false;
+                }
+            } else if key <= after.as_slice() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A key and its value, as returned by a TableDB scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TableDBEntry {
+    #[serde(with = "as_human_base64")]
+    #[schemars(with = "String")]
+    pub key: Vec<u8>,
+    #[serde(with = "as_human_base64")]
+    #[schemars(with = "String")]
+    pub value: Vec<u8>,
+}
+
 pub struct TableDBUnlockedInner {
     table: String,
     table_store: TableStore,
@@ -122,65 +258,15 @@ impl TableDB {
     }
 
     /// Encrypt buffer using encrypt key and prepend nonce to output.
-    /// Keyed nonces are unique because keys must be unique.
-    /// Normally they must be sequential or random, but the critical.
-    /// requirement is that they are different for each encryption
-    /// but if the contents are guaranteed to be unique, then a nonce
-    /// can be generated from the hash of the contents and the encryption key itself.
     #[instrument(level = "trace", target = "tstore", skip_all)]
     fn maybe_encrypt(&self, data: &[u8], keyed_nonce: bool) -> Vec<u8> {
-        let data = compress_prepend_size(data);
-        if let Some(ei) = &self.unlocked_inner.encrypt_info {
-            let mut out = unsafe { unaligned_u8_vec_uninit(NONCE_LENGTH + data.len()) };
-
-            if keyed_nonce {
-                // Key content nonce
-                let mut noncedata = Vec::with_capacity(data.len() + PUBLIC_KEY_LENGTH);
-                noncedata.extend_from_slice(&data);
-                noncedata.extend_from_slice(&ei.key.bytes);
-                let noncehash = ei.vcrypto.generate_hash(&noncedata);
-                out[0..NONCE_LENGTH].copy_from_slice(&noncehash[0..NONCE_LENGTH])
-            } else {
-                // Random nonce
-                random_bytes(&mut out[0..NONCE_LENGTH]);
-            }
-
-            let (nonce, encout) = out.split_at_mut(NONCE_LENGTH);
-            ei.vcrypto.crypt_b2b_no_auth(
-                &data,
-                encout,
-                (nonce as &[u8]).try_into().unwrap(),
-                &ei.key,
-            );
-            out
-        } else {
-            data
-        }
+        maybe_encrypt_with(self.unlocked_inner.encrypt_info.as_ref(), data, keyed_nonce)
     }
 
     /// Decrypt buffer using decrypt key with nonce prepended to input
     #[instrument(level = "trace", target = "tstore", skip_all)]
     fn maybe_decrypt(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
-        if let Some(di) = &self.unlocked_inner.decrypt_info {
-            assert!(data.len() >= NONCE_LENGTH);
-            if data.len() == NONCE_LENGTH {
-                return Ok(Vec::new());
-            }
-
-            let mut out = unsafe { unaligned_u8_vec_uninit(data.len() - NONCE_LENGTH) };
-
-            di.vcrypto.crypt_b2b_no_auth(
-                &data[NONCE_LENGTH..],
-                &mut out,
-                (&data[0..NONCE_LENGTH]).try_into().unwrap(),
-                &di.key,
-            );
-            decompress_size_prepended(&out, None)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
-        } else {
-            decompress_size_prepended(data, None)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
-        }
+        maybe_decrypt_with(self.unlocked_inner.decrypt_info.as_ref(), data)
     }
 
     /// Get the list of keys in a column of the TableDB
@@ -204,6 +290,59 @@ impl TableDB {
         Ok(out)
     }
 
+    /// Get an ordered page of the keys in a column of the TableDB
+    /// See [TableDBScan] for how keys are selected and ordered
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    pub async fn scan_keys(&self, col: u32, scan: TableDBScan) -> VeilidAPIResult<Vec<Vec<u8>>> {
+        if col >= self.opened_column_count {
+            apibail_generic!(format!(
+                "Column exceeds opened column count {} >= {}",
+                col, self.opened_column_count
+            ));
+        }
+        let db = self.unlocked_inner.database.clone();
+        let limit = scan.limit as usize;
+        let mut out = BTreeSet::new();
+        db.iter_keys(col, None, |k| {
+            let key = self.maybe_decrypt(k)?;
+            if scan.matches(&key) {
+                out.insert(key);
+                // Drop whichever key would sort past the end of the page
+                if limit != 0 && out.len() > limit {
+                    if scan.reverse {
+                        out.pop_first();
+                    } else {
+                        out.pop_last();
+                    }
+                }
+            }
+            Ok(Option::<()>::None)
+        })
+        .await
+        .map_err(VeilidAPIError::from)?;
+
+        if scan.reverse {
+            Ok(out.into_iter().rev().collect())
+        } else {
+            Ok(out.into_iter().collect())
+        }
+    }
+
+    /// Get an ordered page of the keys in a column of the TableDB along with their values
+    /// See [TableDBScan] for how keys are selected and ordered
+    #[instrument(level = "trace", target = "tstore", skip_all)]
+    pub async fn scan(&self, col: u32, scan: TableDBScan) -> VeilidAPIResult<Vec<TableDBEntry>> {
+        let keys = self.scan_keys(col, scan).await?;
+        let mut out = Vec::with_capacity(keys.len());
+        for key in keys {
+            // Skip keys deleted since the scan
+            if let Some(value) = self.load(col, &key).await? {
+                out.push(TableDBEntry { key, value });
+            }
+        }
+        Ok(out)
+    }
+
     /// Get the number of keys in a column of the TableDB
     #[instrument(level = "trace", target = "tstore", skip_all)]
     pub async fn get_key_count(&self, col: u32) -> VeilidAPIResult<u64> {
@@ -162,6 +162,309 @@ pub async fn test_transaction(ts: TableStore) {
     assert_eq!(db.load(0, b"ddd").await, Ok(None));
 }
 
+pub async fn test_scan(ts: TableStore) {
+    trace!("test_scan");
+
+    let _ = ts.delete("test").await;
+    let db = ts.open("test", 2).await.expect("should have opened");
+
+    let tx = db.transact();
+    for key in [b"b2", b"a1", b"b1", b"c1", b"b3", b"a2"] {
+        assert!(tx.store(0, key, key).is_ok());
+    }
+    assert!(tx.store(1, b"b0", b"other").is_ok());
+    assert!(tx.commit().await.is_ok());
+
+    let keys = |v: &[&[u8; 2]]| v.iter().map(|k| k.to_vec()).collect::<Vec<_>>();
+
+    assert_eq!(
+        db.scan_keys(0, TableDBScan::default()).await.unwrap(),
+        keys(&[b"a1", b"a2", b"b1", b"b2", b"b3", b"c1"]),
+        "should scan all keys in order"
+    );
+    assert_eq!(
+        db.scan_keys(
+            0,
+            TableDBScan {
+                prefix: Some(b"b".to_vec()),
+                ..Default::default()
+            }
+        )
+        .await
+        .unwrap(),
+        keys(&[b"b1", b"b2", b"b3"]),
+        "should scan only keys with prefix"
+    );
+    assert_eq!(
+        db.scan_keys(
+            0,
+            TableDBScan {
+                start: Some(b"a2".to_vec()),
+                end: Some(b"b3".to_vec()),
+                reverse: true,
+                ..Default::default()
+            }
+        )
+        .await
+        .unwrap(),
+        keys(&[b"b2", b"b1", b"a2"]),
+        "should scan range in reverse"
+    );
+
+    // Page through the keys in reverse, two at a time
+    let mut scan = TableDBScan {
+        reverse: true,
+        limit: 2,
+        ..Default::default()
+    };
+    let mut pages = Vec::new();
+    loop {
+        let page = db.scan_keys(0, scan.clone()).await.unwrap();
+        if page.is_empty() {
+            break;
+        }
+        scan.after = page.last().cloned();
+        pages.push(page);
+    }
+    assert_eq!(
+        pages,
+        vec![
+            keys(&[b"c1", b"b3"]),
+            keys(&[b"b2", b"b1"]),
+            keys(&[b"a2", b"a1"])
+        ]
+    );
+
+    assert_eq!(
+        db.scan(
+            1,
+            TableDBScan {
+                limit: 1,
+                ..Default::default()
+            }
+        )
+        .await
+        .unwrap(),
+        vec![TableDBEntry {
+            key: b"b0".to_vec(),
+            value: b"other".to_vec()
+        }]
+    );
+    assert!(
+        db.scan_keys(2, TableDBScan::default()).await.is_err(),
+        "can't scan column that doesn't exist"
+    );
+}
+
+pub async fn test_rekey(ts: TableStore) {
+    trace!("test_rekey");
+
+    let _ = ts.delete("test").await;
+    let _ = ts.delete("test2").await;
+    let db = ts.open("test", 2).await.expect("should have opened");
+    let db2 = ts.open("test2", 1).await.expect("should have opened");
+    assert!(db.store(0, b"foo", b"1234").await.is_ok());
+    assert!(db.store(1, b"bar", b"5678").await.is_ok());
+    assert!(db2.store(0, b"baz", b"QWERTY").await.is_ok());
+    let table_name = db.table_name();
+    drop(db);
+
+    // Only the closed table can be re-keyed
+    assert_eq!(ts.rekey(None).await, Ok(1));
+    let db = ts.open("test", 2).await.expect("should have opened");
+    assert_ne!(db.table_name(), table_name, "should have been re-keyed");
+    assert_eq!(db.load(0, b"foo").await, Ok(Some(b"1234".to_vec())));
+    assert_eq!(db.load(1, b"bar").await, Ok(Some(b"5678".to_vec())));
+    assert_eq!(db2.load(0, b"baz").await, Ok(Some(b"QWERTY".to_vec())));
+
+    // New tables use the new key straight away
+    let _ = ts.delete("test3").await;
+    let db3 = ts.open("test3", 1).await.expect("should have opened");
+    assert!(db3.store(0, b"new", b"table").await.is_ok());
+    drop(db3);
+
+    // Finish once the other table is closed
+    drop(db);
+    drop(db2);
+    assert_eq!(ts.rekey(None).await, Ok(0));
+
+    let db = ts.open("test", 2).await.expect("should have opened");
+    let db2 = ts.open("test2", 1).await.expect("should have opened");
+    let db3 = ts.open("test3", 1).await.expect("should have opened");
+    assert_eq!(db.load(0, b"foo").await, Ok(Some(b"1234".to_vec())));
+    assert_eq!(db.load(1, b"bar").await, Ok(Some(b"5678".to_vec())));
+    assert_eq!(db2.load(0, b"baz").await, Ok(Some(b"QWERTY".to_vec())));
+    assert_eq!(db3.load(0, b"new").await, Ok(Some(b"table".to_vec())));
+    drop(db2);
+    drop(db3);
+    let _ = ts.delete("test2").await;
+    let _ = ts.delete("test3").await;
+}
+
+pub async fn test_rekey_resume() {
+    trace!("test_rekey_resume");
+
+    // Start from a fresh data directory, then keep it across restarts
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback.clone(), config_callback.clone())
+        .await
+        .expect("startup failed");
+    let config_callback: ConfigCallback = Arc::new(move |key| match key.as_str() {
+        "table_store.delete" | "protected_store.delete" => Ok(Box::new(false)),
+        _ => config_callback(key),
+    });
+
+    let ts = api.table_store().unwrap();
+    let db = ts.open("test", 2).await.expect("should have opened");
+    let db2 = ts.open("test2", 1).await.expect("should have opened");
+    assert!(db.store(0, b"foo", b"1234").await.is_ok());
+    assert!(db2.store(0, b"baz", b"QWERTY").await.is_ok());
+    let table_name2 = db2.table_name();
+    drop(db);
+
+    // Leave the rotation unfinished with a table still open, as if the node stopped mid-rotation
+    assert_eq!(ts.rekey(None).await, Ok(1));
+    drop(db2);
+    shutdown(api).await;
+
+    // The pending key and rekey state are picked up on the next startup, finishing the rotation
+    let api = api_startup(update_callback.clone(), config_callback.clone())
+        .await
+        .expect("startup failed");
+    let ts = api.table_store().unwrap();
+    let db = ts.open("test", 2).await.expect("should have opened");
+    let db2 = ts.open("test2", 1).await.expect("should have opened");
+    assert_ne!(db2.table_name(), table_name2, "should have been re-keyed");
+    assert_eq!(db.load(0, b"foo").await, Ok(Some(b"1234".to_vec())));
+    assert_eq!(db2.load(0, b"baz").await, Ok(Some(b"QWERTY".to_vec())));
+    drop(db);
+    drop(db2);
+
+    // Nothing is left pending, so a new rotation starts and finishes straight away
+    assert_eq!(ts.rekey(None).await, Ok(0));
+    shutdown(api).await;
+
+    // And the data is still readable after restarting again
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let ts = api.table_store().unwrap();
+    let db = ts.open("test", 2).await.expect("should have opened");
+    assert_eq!(db.load(0, b"foo").await, Ok(Some(b"1234".to_vec())));
+    drop(db);
+    let _ = ts.delete("test").await;
+    let _ = ts.delete("test2").await;
+    shutdown(api).await;
+}
+
+pub async fn test_backup() {
+    trace!("test_backup");
+
+    let api = startup().await;
+    let (node_id, node_id_secret) = {
+        let config = api.config().unwrap();
+        let c = config.get();
+        (
+            c.network.routing_table.node_id.clone(),
+            c.network.routing_table.node_id_secret.clone(),
+        )
+    };
+    let ts = api.table_store().unwrap();
+
+    // Test builds generate a fresh node id on every startup, so store it the way a real node does
+    let config_table = ts
+        .open("__veilid_config", 1)
+        .await
+        .expect("should have opened");
+    for nid in node_id.iter() {
+        let key = format!("node_id_{}", nid.kind);
+        assert!(config_table
+            .store_json(0, key.as_bytes(), nid)
+            .await
+            .is_ok());
+    }
+    for nids in node_id_secret.iter() {
+        let key = format!("node_id_secret_{}", nids.kind);
+        assert!(config_table
+            .store_json(0, key.as_bytes(), nids)
+            .await
+            .is_ok());
+    }
+    drop(config_table);
+
+    let _ = ts.delete("test").await;
+    let db = ts.open("test", 2).await.expect("should have opened");
+    assert!(db.store(0, b"foo", b"1234").await.is_ok());
+    assert!(db.store(1, b"bar", b"5678").await.is_ok());
+    drop(db);
+    let archive = api
+        .export_backup("backup password".to_owned())
+        .await
+        .expect("should have exported");
+    shutdown(api).await;
+
+    // Restoring wipes the stores because of the test config, so this is a fresh data directory
+    let (update_callback, config_callback) = setup_veilid_core();
+    assert!(
+        api_restore_backup(
+            update_callback.clone(),
+            config_callback.clone(),
+            archive.clone(),
+            "wrong password".to_owned(),
+        )
+        .await
+        .is_err(),
+        "should fail with the wrong password"
+    );
+    api_restore_backup(
+        update_callback.clone(),
+        config_callback.clone(),
+        archive,
+        "backup password".to_owned(),
+    )
+    .await
+    .expect("should have restored");
+
+    // Start up on the restored state without wiping it
+    let config_callback: ConfigCallback = Arc::new(move |key| match key.as_str() {
+        "table_store.delete" | "protected_store.delete" => Ok(Box::new(false)),
+        _ => config_callback(key),
+    });
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let ts = api.table_store().unwrap();
+    let config_table = ts
+        .open("__veilid_config", 1)
+        .await
+        .expect("should have opened");
+    for nid in node_id.iter() {
+        let key = format!("node_id_{}", nid.kind);
+        assert_eq!(
+            config_table.load_json::<TypedKey>(0, key.as_bytes()).await,
+            Ok(Some(*nid)),
+            "should have restored the node id"
+        );
+    }
+    for nids in node_id_secret.iter() {
+        let key = format!("node_id_secret_{}", nids.kind);
+        assert_eq!(
+            config_table
+                .load_json::<TypedSecret>(0, key.as_bytes())
+                .await,
+            Ok(Some(*nids)),
+            "should have restored the node id secret"
+        );
+    }
+    drop(config_table);
+    let db = ts.open("test", 2).await.expect("should have opened");
+    assert_eq!(db.load(0, b"foo").await, Ok(Some(b"1234".to_vec())));
+    assert_eq!(db.load(1, b"bar").await, Ok(Some(b"5678".to_vec())));
+    drop(db);
+    let _ = ts.delete("test").await;
+    shutdown(api).await;
+}
+
 pub async fn test_json(vcrypto: CryptoSystemVersion, ts: TableStore) {
     trace!("test_json");
 
@@ -271,9 +574,14 @@ pub async fn test_all() {
         test_delete_open_delete(ts.clone()).await;
         test_store_delete_load(ts.clone()).await;
         test_transaction(ts.clone()).await;
+        test_scan(ts.clone()).await;
+        test_rekey(ts.clone()).await;
         test_json(vcrypto, ts.clone()).await;
         let _ = ts.delete("test").await;
     }
 
     shutdown(api).await;
+
+    test_rekey_resume().await;
+    test_backup().await;
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "unstable-blockstore")]
+pub mod test_block_store;
 pub mod test_dht;
 pub mod test_protected_store;
 pub mod test_veilid_config;
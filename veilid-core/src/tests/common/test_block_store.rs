@@ -0,0 +1,131 @@
+use super::test_veilid_config::*;
+use crate::*;
+
+async fn startup() -> VeilidAPI {
+    trace!("test_block_store: starting");
+    let (update_callback, config_callback) = setup_veilid_core();
+    api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed")
+}
+
+async fn shutdown(api: VeilidAPI) {
+    trace!("test_block_store: shutting down");
+    api.shutdown().await;
+    trace!("test_block_store: finished");
+}
+
+pub async fn test_save_load_delete(bs: BlockStore) {
+    info!("testing block store save, load and delete");
+
+    let block_id = TypedKey::new(best_crypto_kind(), CryptoKey::new([1u8; 32]));
+    let _ = bs.delete_block(block_id).await;
+
+    assert_eq!(bs.load_block(block_id).await.unwrap(), None);
+    assert!(!bs.delete_block(block_id).await.unwrap());
+
+    let d1 = vec![1u8, 2, 3, 4];
+    bs.save_block(block_id, &d1).await.unwrap();
+    assert_eq!(bs.load_block(block_id).await.unwrap(), Some(d1.clone()));
+
+    // Saving again replaces the existing copy
+    let d2 = vec![5u8; 100];
+    bs.save_block(block_id, &d2).await.unwrap();
+    assert_eq!(bs.load_block(block_id).await.unwrap(), Some(d2));
+
+    // Blocks with the same key value but a different crypto kind are separate
+    let other_kind_id = TypedKey::new(FourCC([0, 0, 0, 0]), block_id.value);
+    assert_eq!(bs.load_block(other_kind_id).await.unwrap(), None);
+
+    assert!(bs.delete_block(block_id).await.unwrap());
+    assert_eq!(bs.load_block(block_id).await.unwrap(), None);
+    assert!(!bs.delete_block(block_id).await.unwrap());
+}
+
+pub async fn test_store_find_block(api: VeilidAPI) {
+    info!("testing storing and finding blocks");
+
+    let storage_manager = api.storage_manager().unwrap();
+    let kind = best_crypto_kind();
+
+    // Empty blocks are rejected
+    assert!(matches!(
+        storage_manager.store_block(kind, vec![]).await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+
+    // Blocks are addressed by their hash
+    let data: Vec<u8> = (0..100u8).collect();
+    let block_id = storage_manager
+        .store_block(kind, data.clone())
+        .await
+        .unwrap();
+    let vcrypto = api.crypto().unwrap().get(kind).unwrap();
+    assert_eq!(block_id, TypedKey::new(kind, vcrypto.generate_hash(&data)));
+
+    // A locally held block is found without going to the network
+    assert_eq!(
+        storage_manager
+            .find_block(block_id, SafetySelection::Unsafe(Sequencing::default()))
+            .await
+            .unwrap(),
+        data
+    );
+
+    // Inbound queries are answered from the requested offset
+    let NetworkResult::Value(res) = storage_manager
+        .inbound_find_block(block_id, 40)
+        .await
+        .unwrap()
+    else {
+        panic!("expected block data");
+    };
+    assert_eq!(res.data, data[40..].to_vec());
+    assert_eq!(res.size, 100);
+    assert!(res.suppliers.is_empty());
+
+    assert!(matches!(
+        storage_manager
+            .inbound_find_block(block_id, 101)
+            .await
+            .unwrap(),
+        NetworkResult::InvalidMessage(_)
+    ));
+
+    // Unknown blocks have no data, and can't be fetched while offline
+    let missing_id = TypedKey::new(kind, vcrypto.generate_hash(b"missing"));
+    let NetworkResult::Value(res) = storage_manager
+        .inbound_find_block(missing_id, 0)
+        .await
+        .unwrap()
+    else {
+        panic!("expected empty answer");
+    };
+    assert!(res.data.is_empty());
+    assert_eq!(res.size, 0);
+    assert!(matches!(
+        storage_manager
+            .find_block(missing_id, SafetySelection::Unsafe(Sequencing::default()))
+            .await,
+        Err(VeilidAPIError::TryAgain { .. })
+    ));
+    assert!(matches!(
+        storage_manager.supply_block(missing_id).await,
+        Err(VeilidAPIError::KeyNotFound { .. })
+    ));
+
+    api.block_store()
+        .unwrap()
+        .delete_block(block_id)
+        .await
+        .unwrap();
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+    let bs = api.block_store().unwrap();
+    test_save_load_delete(bs).await;
+    test_store_find_block(api.clone()).await;
+
+    shutdown(api).await;
+}
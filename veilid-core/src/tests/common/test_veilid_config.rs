@@ -182,6 +182,7 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "program_name" => Ok(Box::new(String::from("VeilidCoreTests"))),
         "namespace" => Ok(Box::<String>::default()),
         "capabilities.disable" => Ok(Box::<Vec<FourCC>>::default()),
+        "capabilities.advertise" => Ok(Box::<Vec<FourCC>>::default()),
         "table_store.directory" => Ok(Box::new(get_table_store_path())),
         "table_store.delete" => Ok(Box::new(true)),
         "block_store.directory" => Ok(Box::new(get_block_store_path())),
@@ -218,6 +219,18 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.routing_table.limit_attached_strong" => Ok(Box::new(16u32)),
         "network.routing_table.limit_attached_good" => Ok(Box::new(8u32)),
         "network.routing_table.limit_attached_weak" => Ok(Box::new(4u32)),
+        "network.routing_table.bucket_retention.max_bucket_depth" => Ok(Box::new(256u32)),
+        "network.routing_table.bucket_retention.min_bucket_depth" => Ok(Box::new(1u32)),
+        "network.routing_table.bucket_retention.keep_closest_reliable" => Ok(Box::new(16u32)),
+        "network.routing_table.bucket_retention.keep_closest_unreliable" => Ok(Box::new(8u32)),
+        "network.routing_table.bucket_retention.age_weight" => Ok(Box::new(1u32)),
+        "network.routing_table.bucket_retention.reliability_weight" => Ok(Box::new(1u32)),
+        "network.routing_table.bucket_retention.reputation_weight" => Ok(Box::new(1u32)),
+        "network.routing_table.bucket_retention.long_lived_age_ms" => Ok(Box::new(3_600_000u32)),
+        "network.routing_table.bucket_retention.protect_long_lived" => Ok(Box::new(true)),
+        "network.routing_table.private_overlay.networks" => Ok(Box::new(Vec::<String>::new())),
+        "network.routing_table.private_overlay.bootstrap" => Ok(Box::new(Vec::<String>::new())),
+        "network.routing_table.asn_table" => Ok(Box::new(Vec::<String>::new())),
         "network.rpc.concurrency" => Ok(Box::new(0u32)),
         "network.rpc.queue_size" => Ok(Box::new(1024u32)),
         "network.rpc.max_timestamp_behind_ms" => Ok(Box::new(Some(10_000u32))),
@@ -225,6 +238,7 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.rpc.timeout_ms" => Ok(Box::new(5_000u32)),
         "network.rpc.max_route_hop_count" => Ok(Box::new(4u8)),
         "network.rpc.default_route_hop_count" => Ok(Box::new(1u8)),
+        "network.rpc.default_route_quality" => Ok(Box::new(RouteQuality::default())),
         "network.dht.max_find_node_count" => Ok(Box::new(20u32)),
         "network.dht.resolve_node_timeout_ms" => Ok(Box::new(10_000u32)),
         "network.dht.resolve_node_count" => Ok(Box::new(1u32)),
@@ -247,6 +261,12 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.dht.public_watch_limit" => Ok(Box::new(32u32)),
         "network.dht.member_watch_limit" => Ok(Box::new(8u32)),
         "network.dht.max_watch_expiration_ms" => Ok(Box::new(600_000u32)),
+        "network.dht.pinning.enabled" => Ok(Box::new(false)),
+        "network.dht.pinning.max_records" => Ok(Box::new(32u32)),
+        "network.dht.pinning.max_records_per_owner" => Ok(Box::new(4u32)),
+        "network.dht.pinning.max_expiration_ms" => Ok(Box::new(86_400_000u32)),
+        "network.dht.pinning.allow_any_owner" => Ok(Box::new(false)),
+        "network.dht.pinning.allowed_owners" => Ok(Box::new(Vec::<String>::new())),
         "network.upnp" => Ok(Box::new(false)),
         "network.detect_address_changes" => Ok(Box::new(true)),
         "network.restricted_nat_retries" => Ok(Box::new(0u32)),
@@ -282,6 +302,9 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.protocol.wss.listen_address" => Ok(Box::new("".to_owned())),
         "network.protocol.wss.path" => Ok(Box::new(String::from("ws"))),
         "network.protocol.wss.url" => Ok(Box::new(Option::<String>::None)),
+        "network.protocol.wrtc.connect" => Ok(Box::new(false)),
+        "network.protocol.wrtc.max_connections" => Ok(Box::new(32u32)),
+        "network.protocol.wrtc.ice_servers" => Ok(Box::new(Vec::<String>::new())),
         _ => {
             let err = format!("config key '{}' doesn't exist", key);
             debug!("{}", err);
@@ -316,6 +339,7 @@ pub async fn test_config() {
     assert_eq!(inner.program_name, String::from("VeilidCoreTests"));
     assert_eq!(inner.namespace, String::from(""));
     assert_eq!(inner.capabilities.disable, Vec::<FourCC>::new());
+    assert_eq!(inner.capabilities.advertise, Vec::<FourCC>::new());
     assert_eq!(inner.table_store.directory, get_table_store_path());
     assert!(inner.table_store.delete);
     assert_eq!(inner.block_store.directory, get_block_store_path());
@@ -347,6 +371,10 @@ pub async fn test_config() {
     assert_eq!(inner.network.rpc.timeout_ms, 5_000u32);
     assert_eq!(inner.network.rpc.max_route_hop_count, 4u8);
     assert_eq!(inner.network.rpc.default_route_hop_count, 1u8);
+    assert_eq!(
+        inner.network.rpc.default_route_quality,
+        RouteQuality::default()
+    );
     assert_eq!(inner.network.routing_table.node_id.len(), 0);
     assert_eq!(inner.network.routing_table.node_id_secret.len(), 0);
     #[cfg(not(target_arch = "wasm32"))]
@@ -364,6 +392,86 @@ pub async fn test_config() {
     assert_eq!(inner.network.routing_table.limit_attached_strong, 16u32);
     assert_eq!(inner.network.routing_table.limit_attached_good, 8u32);
     assert_eq!(inner.network.routing_table.limit_attached_weak, 4u32);
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .max_bucket_depth,
+        256u32
+    );
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .min_bucket_depth,
+        1u32
+    );
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .keep_closest_reliable,
+        16u32
+    );
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .keep_closest_unreliable,
+        8u32
+    );
+    assert_eq!(
+        inner.network.routing_table.bucket_retention.age_weight,
+        1u32
+    );
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .reliability_weight,
+        1u32
+    );
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .reputation_weight,
+        1u32
+    );
+    assert_eq!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .long_lived_age_ms,
+        3_600_000u32
+    );
+    assert!(
+        inner
+            .network
+            .routing_table
+            .bucket_retention
+            .protect_long_lived
+    );
+    assert!(inner
+        .network
+        .routing_table
+        .private_overlay
+        .networks
+        .is_empty());
+    assert!(inner
+        .network
+        .routing_table
+        .private_overlay
+        .bootstrap
+        .is_empty());
+    assert!(inner.network.routing_table.asn_table.is_empty());
 
     assert_eq!(inner.network.dht.max_find_node_count, 20u32);
     assert_eq!(inner.network.dht.resolve_node_timeout_ms, 10_000u32);
@@ -381,6 +489,12 @@ pub async fn test_config() {
         inner.network.dht.validate_dial_info_receipt_time_ms,
         2_000u32
     );
+    assert!(!inner.network.dht.pinning.enabled);
+    assert_eq!(inner.network.dht.pinning.max_records, 32u32);
+    assert_eq!(inner.network.dht.pinning.max_records_per_owner, 4u32);
+    assert_eq!(inner.network.dht.pinning.max_expiration_ms, 86_400_000u32);
+    assert!(!inner.network.dht.pinning.allow_any_owner);
+    assert!(inner.network.dht.pinning.allowed_owners.is_empty());
 
     assert!(!inner.network.upnp);
     assert!(inner.network.detect_address_changes);
@@ -419,8 +533,51 @@ pub async fn test_config() {
     assert_eq!(inner.network.protocol.wss.listen_address, "");
     assert_eq!(inner.network.protocol.wss.path, "ws");
     assert_eq!(inner.network.protocol.wss.url, None);
+    assert!(!inner.network.protocol.wrtc.connect);
+    assert_eq!(inner.network.protocol.wrtc.max_connections, 32u32);
+    assert!(inner.network.protocol.wrtc.ice_servers.is_empty());
+}
+
+pub async fn test_config_advertise_capabilities() {
+    let vc = get_config();
+
+    // Application-defined capabilities are accepted
+    vc.with_mut(|c| {
+        c.capabilities.advertise = vec![FourCC(*b"ARCH"), FourCC(*b"INDX")];
+        Ok(())
+    })
+    .expect("should accept application capabilities");
+    assert_eq!(
+        vc.get().capabilities.advertise,
+        vec![FourCC(*b"ARCH"), FourCC(*b"INDX")]
+    );
+
+    // Capabilities defined by Veilid are reserved, even when not enabled in this build
+    for cap in [FourCC(*b"ROUT"), FourCC(*b"APPM"), FourCC(*b"BLOC")] {
+        assert!(vc
+            .with_mut(|c| {
+                c.capabilities.advertise = vec![cap];
+                Ok(())
+            })
+            .is_err());
+    }
+
+    // Duplicates are rejected
+    assert!(vc
+        .with_mut(|c| {
+            c.capabilities.advertise = vec![FourCC(*b"ARCH"), FourCC(*b"ARCH")];
+            Ok(())
+        })
+        .is_err());
+
+    // A failed edit leaves the previous configuration in place
+    assert_eq!(
+        vc.get().capabilities.advertise,
+        vec![FourCC(*b"ARCH"), FourCC(*b"INDX")]
+    );
 }
 
 pub async fn test_all() {
     test_config().await;
+    test_config_advertise_capabilities().await;
 }
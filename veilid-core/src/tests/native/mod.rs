@@ -18,18 +18,58 @@ pub async fn run_all_tests() {
     test_connection_table::test_all().await;
     info!("TEST: test_signed_node_info");
     test_signed_node_info::test_all().await;
+    info!("TEST: test_protocol_type");
+    test_protocol_type::test_all().await;
     info!("TEST: test_table_store");
     test_table_store::test_all().await;
     info!("TEST: test_protected_store");
     test_protected_store::test_all().await;
+    #[cfg(feature = "unstable-blockstore")]
+    {
+        info!("TEST: test_block_store");
+        test_block_store::test_all().await;
+    }
     info!("TEST: test_crypto");
     test_crypto::test_all().await;
     info!("TEST: test_envelope_receipt");
     test_envelope_receipt::test_all().await;
     info!("TEST: veilid_api::tests::test_serialize_json");
     veilid_api::tests::test_serialize_json::test_all().await;
+    info!("TEST: veilid_api::tests::test_debug");
+    veilid_api::tests::test_debug::test_all().await;
+    info!("TEST: veilid_api::tests::test_stable_inbox");
+    veilid_api::tests::test_stable_inbox::test_all().await;
+    info!("TEST: routing_table::test_bucket");
+    routing_table::tests::test_bucket::test_all().await;
+    info!("TEST: routing_table::test_contact_method");
+    routing_table::tests::test_contact_method::test_all().await;
+    info!("TEST: routing_table::test_find_capability");
+    routing_table::tests::test_find_capability::test_all().await;
+    info!("TEST: routing_table::test_private_overlay");
+    routing_table::tests::test_private_overlay::test_all().await;
+    info!("TEST: routing_table::test_route_quality");
+    routing_table::tests::test_route_quality::test_all().await;
     info!("TEST: routing_table::test_serialize_routing_table");
     routing_table::tests::test_serialize_routing_table::test_all().await;
+    info!("TEST: storage_manager::test_pin_value");
+    storage_manager::tests::test_pin_value::test_all().await;
+    info!("TEST: storage_manager::test_record_store");
+    storage_manager::tests::test_record_store::test_all().await;
+    info!("TEST: storage_manager::test_set_value");
+    storage_manager::tests::test_set_value::test_all().await;
+    info!("TEST: storage_manager::test_value_sync");
+    storage_manager::tests::test_value_sync::test_all().await;
+    info!("TEST: rpc_processor::test_app_call_stream");
+    rpc_processor::tests::test_app_call_stream::test_all().await;
+    info!("TEST: rpc_processor::test_coders");
+    rpc_processor::tests::test_coders::test_all().await;
+    info!("TEST: rpc_processor::test_pin_value");
+    rpc_processor::tests::test_pin_value::test_all().await;
+    #[cfg(feature = "unstable-tunnels")]
+    {
+        info!("TEST: rpc_processor::test_tunnel_table");
+        rpc_processor::tests::test_tunnel_table::test_all().await;
+    }
     // info!("TEST: test_dht");
     // test_dht::test_all().await;
 
@@ -119,18 +159,54 @@ cfg_if! {
 
         run_test!(test_signed_node_info);
 
+        run_test!(test_protocol_type);
+
         run_test!(test_table_store);
 
         run_test!(test_protected_store);
 
+        #[cfg(feature = "unstable-blockstore")]
+        run_test!(test_block_store);
+
         run_test!(test_crypto);
 
         run_test!(test_envelope_receipt);
 
         run_test!(veilid_api, test_serialize_json);
 
+        run_test!(veilid_api, test_debug);
+
+        run_test!(veilid_api, test_stable_inbox);
+
+        run_test!(routing_table, test_bucket);
+
+        run_test!(routing_table, test_contact_method);
+
+        run_test!(routing_table, test_find_capability);
+
+        run_test!(routing_table, test_private_overlay);
+
+        run_test!(routing_table, test_route_quality);
+
         run_test!(routing_table, test_serialize_routing_table);
 
+        run_test!(storage_manager, test_pin_value);
+
+        run_test!(storage_manager, test_record_store);
+
+        run_test!(storage_manager, test_set_value);
+
+        run_test!(storage_manager, test_value_sync);
+
+        run_test!(rpc_processor, test_app_call_stream);
+
+        run_test!(rpc_processor, test_coders);
+
+        run_test!(rpc_processor, test_pin_value);
+
+        #[cfg(feature = "unstable-tunnels")]
+        run_test!(rpc_processor, test_tunnel_table);
+
         // run_test!(test_dht);
     }
 }
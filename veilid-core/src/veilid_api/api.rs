@@ -5,6 +5,7 @@ use super::*;
 pub(super) struct VeilidAPIInner {
     context: Option<VeilidCoreContext>,
     pub(super) debug_cache: DebugCache,
+    pub(super) stable_inboxes: HashMap<String, Weak<StableInboxUnlockedInner>>,
 }
 
 impl fmt::Debug for VeilidAPIInner {
@@ -53,6 +54,7 @@ impl VeilidAPI {
                         hashlink::LinkedHashMap::new,
                     ),
                 },
+                stable_inboxes: HashMap::new(),
             })),
         }
     }
@@ -178,6 +180,32 @@ impl VeilidAPI {
         })
     }
 
+    /// Get a snapshot of the statistics kept by this node, for monitoring.
+    pub async fn get_stats(&self) -> VeilidAPIResult<VeilidStats> {
+        let network_manager = self.network_manager()?;
+        let storage_manager = self.storage_manager()?;
+
+        let (local_record_store, remote_record_store) =
+            storage_manager.get_record_store_stats().await;
+
+        if !network_manager.network_is_started() {
+            return Ok(VeilidStats {
+                local_record_store,
+                remote_record_store,
+                ..Default::default()
+            });
+        }
+        let routing_table = network_manager.routing_table();
+
+        Ok(VeilidStats {
+            transfer: network_manager.get_self_transfer_stats(),
+            peers: network_manager.get_recent_peer_table_data(),
+            buckets: routing_table.get_bucket_stats(),
+            local_record_store,
+            remote_record_store,
+        })
+    }
+
     /// Connect to the network.
     #[instrument(target = "veilid_api", level = "debug", skip_all, ret, err)]
     pub async fn attach(&self) -> VeilidAPIResult<()> {
@@ -249,11 +277,37 @@ impl VeilidAPI {
         Err(VeilidAPIError::parse_error("Unable to parse as target", s))
     }
 
+    ////////////////////////////////////////////////////////////////
+    // Peer discovery
+
+    /// Find peers in the routing table that advertise a capability in their PublicInternet node info.
+    ///
+    /// Applications advertise their own capabilities with the `capabilities.advertise` config key, so this
+    /// can be used to discover nodes offering an application-specific service. At most `count` node ids are
+    /// returned, fastest peers first, and each can be used as a [Target::NodeId] in a [RoutingContext].
+    /// Only peers this node already knows about are returned; an empty list does not mean that no
+    /// such peers exist on the network.
+    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    pub fn find_peers_with_capability(
+        &self,
+        capability: FourCC,
+        count: u32,
+    ) -> VeilidAPIResult<Vec<TypedKey>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::find_peers_with_capability(capability: {}, count: {})", capability, count);
+
+        let routing_table = self.routing_table()?;
+        let nrs = routing_table.find_nodes_with_capability(capability, count as usize);
+
+        Ok(nrs.iter().map(|nr| nr.best_node_id()).collect())
+    }
+
     ////////////////////////////////////////////////////////////////
     // Private route allocation
 
     /// Allocate a new private route set with default cryptography and network options.
-    /// Default settings are for [Stability::Reliable] and [Sequencing::PreferOrdered].
+    /// Default settings are for [Stability::Reliable], [Sequencing::PreferOrdered] and the
+    /// route quality in 'network.rpc.default_route_quality'.
     /// Returns a route id and a publishable 'blob' with the route encrypted with each crypto kind.
     /// Those nodes importing the blob will have their choice of which crypto kind to use.
     ///
@@ -261,17 +315,23 @@ impl VeilidAPI {
     /// imported by another Veilid node.
     //#[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
     pub async fn new_private_route(&self) -> VeilidAPIResult<(RouteId, Vec<u8>)> {
+        let default_route_quality = {
+            let config = self.config()?;
+            let c = config.get();
+            c.network.rpc.default_route_quality
+        };
         self.new_custom_private_route(
             &VALID_CRYPTO_KINDS,
             Stability::Reliable,
             Sequencing::PreferOrdered,
+            default_route_quality,
         )
         .await
     }
 
-    /// Allocate a new private route and specify a specific cryptosystem, stability and sequencing preference.
+    /// Allocate a new private route and specify a specific cryptosystem, stability, sequencing preference and route quality.
     /// Faster connections may be possible with [Stability::LowLatency], and [Sequencing::NoPreference] at the
-    /// expense of some loss of messages.
+    /// expense of some loss of messages. The [RouteQuality] chooses how the hops of the route are scored and kept apart.
     /// Returns a route id and a publishable 'blob' with the route encrypted with each crypto kind.
     /// Those nodes importing the blob will have their choice of which crypto kind to use.
     ///
@@ -283,12 +343,14 @@ impl VeilidAPI {
         crypto_kinds: &[CryptoKind],
         stability: Stability,
         sequencing: Sequencing,
+        route_quality: RouteQuality,
     ) -> VeilidAPIResult<(RouteId, Vec<u8>)> {
         event!(target: "veilid_api", Level::DEBUG, 
-            "VeilidAPI::new_custom_private_route(crypto_kinds: {:?}, stability: {:?}, sequencing: {:?})",
+            "VeilidAPI::new_custom_private_route(crypto_kinds: {:?}, stability: {:?}, sequencing: {:?}, route_quality: {:?})",
             crypto_kinds,
             stability,
-            sequencing);
+            sequencing,
+            route_quality);
 
         for kind in crypto_kinds {
             Crypto::validate_crypto_kind(*kind)?;
@@ -305,6 +367,7 @@ impl VeilidAPI {
             crypto_kinds,
             stability,
             sequencing,
+            &route_quality,
             default_route_hop_count,
             DirectionSet::all(),
             &[],
@@ -354,6 +417,56 @@ impl VeilidAPI {
         Ok(())
     }
 
+    ////////////////////////////////////////////////////////////////
+    // Stable Inboxes
+
+    /// Open a named [StableInbox], creating its DHT record the first time the name is used.
+    ///
+    /// * `name` - identifies the inbox on this node. Opening the same name again, even after a restart, publishes to the same DHT record key.
+    /// * `route_count` - how many private routes to keep published at once, from 1 to 8. This is fixed when the inbox is first created.
+    ///
+    /// Only one [StableInbox] can be open with a particular name at a time.
+    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    pub async fn open_stable_inbox(
+        &self,
+        name: String,
+        route_count: u16,
+    ) -> VeilidAPIResult<StableInbox> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::open_stable_inbox(name: {:?}, route_count: {:?})", name, route_count);
+
+        let is_open = |inner: &VeilidAPIInner| {
+            inner
+                .stable_inboxes
+                .get(&name)
+                .map(|w| w.strong_count() > 0)
+                .unwrap_or(false)
+        };
+        if is_open(&self.inner.lock()) {
+            apibail_generic!("stable inbox is already open");
+        }
+
+        let stable_inbox = StableInbox::open(self.clone(), name.clone(), route_count).await?;
+
+        // Check again in case the same name was opened while we were opening this one
+        let already_open = {
+            let mut inner = self.inner.lock();
+            let already_open = is_open(&inner);
+            if !already_open {
+                inner
+                    .stable_inboxes
+                    .insert(name, stable_inbox.registration());
+            }
+            already_open
+        };
+        if already_open {
+            stable_inbox.close().await?;
+            apibail_generic!("stable inbox is already open");
+        }
+
+        Ok(stable_inbox)
+    }
+
     ////////////////////////////////////////////////////////////////
     // App Calls
 
@@ -376,33 +489,338 @@ impl VeilidAPI {
             .map_err(|e| e.into())
     }
 
+    /// Send a reply chunk to a streaming AppCall received over a [VeilidUpdate::AppCall].
+    ///
+    /// Chunks are delivered in order to the remote node's [AppCallStream], and the stream ends when the call is finished with [VeilidAPI::app_call_reply()].
+    /// Each chunk sent restarts the timeout for the final reply. If the remote node cancels the call, sending further chunks or the final reply fails.
+    /// Sending waits while the remote application is too far behind in receiving chunks, and times out if it does not catch up.
+    ///
+    /// * `call_id` - specifies which call to reply to, and it comes from a [VeilidUpdate::AppCall] whose [VeilidAppCall::streaming()] value is true.
+    /// * `message` - is a reply chunk blob of up to 32768 bytes.
+    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    pub async fn app_call_reply_chunk(
+        &self,
+        call_id: OperationId,
+        message: Vec<u8>,
+    ) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::app_call_reply_chunk(call_id: {:?}, message: {:?})", call_id, message);
+
+        let rpc_processor = self.rpc_processor()?;
+        match rpc_processor.app_call_reply_chunk(call_id, message).await {
+            Ok(NetworkResult::Value(())) => Ok(()),
+            Ok(NetworkResult::Timeout) => apibail_timeout!(),
+            Ok(NetworkResult::ServiceUnavailable(e)) => apibail_invalid_target!(e),
+            Ok(NetworkResult::NoConnection(e)) | Ok(NetworkResult::AlreadyExists(e)) => {
+                apibail_no_connection!(e);
+            }
+            Ok(NetworkResult::InvalidMessage(message)) => {
+                apibail_generic!(message);
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////
+    // Backup
+
+    /// Export the node's entire local state to a single archive encrypted with `password`.
+    ///
+    /// This includes the node identity, allocated and imported private routes, the local and remote
+    /// DHT record stores including the owner secrets of records this node created, and any application tables.
+    /// The archive can be restored into a fresh data directory with [api_restore_backup] before starting the node.
+    #[instrument(target = "veilid_api", level = "debug", skip_all, err)]
+    pub async fn export_backup(&self, password: String) -> VeilidAPIResult<Vec<u8>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::export_backup()");
+
+        // Make sure everything held in memory has been written to the table store
+        self.routing_table()?
+            .route_spec_store()
+            .save()
+            .await
+            .map_err(VeilidAPIError::internal)?;
+        self.storage_manager()?.flush().await?;
+
+        let table_store = self.table_store()?;
+        table_store.export_backup(&password).await
+    }
+
     ////////////////////////////////////////////////////////////////
     // Tunnel Building
 
+    /// Start a tunnel that a remote node can complete.
+    ///
+    /// * `endpoint_mode` - the kind of endpoint this node provides. Only [TunnelMode::Raw] is currently supported.
+    /// * `depth` - the number of intermediate nodes in the tunnel. Only direct tunnels with a depth of zero are currently supported.
+    ///
+    /// Returns a [PartialTunnel] that must be passed to the remote node, for example with [RoutingContext::app_call()],
+    /// so that it can call [VeilidAPI::complete_tunnel()] before the partial tunnel times out.
     #[cfg(feature = "unstable-tunnels")]
     #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
     pub async fn start_tunnel(
         &self,
-        _endpoint_mode: TunnelMode,
-        _depth: u8,
+        endpoint_mode: TunnelMode,
+        depth: u8,
     ) -> VeilidAPIResult<PartialTunnel> {
-        panic!("unimplemented");
+        event!(target: "veilid_api", Level::DEBUG,
+            "VeilidAPI::start_tunnel(endpoint_mode: {:?}, depth: {:?})", endpoint_mode, depth);
+
+        Self::validate_tunnel_args(endpoint_mode, depth)?;
+
+        let rpc_processor = self.rpc_processor()?;
+        let local = self.local_tunnel_endpoint(endpoint_mode)?;
+        rpc_processor
+            .tunnel_table()
+            .new_partial(local)
+            .map_err(|e| VeilidAPIError::generic(format!("could not start tunnel: {:?}", e)))
     }
 
+    /// Complete a tunnel started by a remote node with [VeilidAPI::start_tunnel()].
+    ///
+    /// * `endpoint_mode` - the kind of endpoint this node provides, which must match the remote endpoint's mode.
+    /// * `depth` - the number of intermediate nodes in the tunnel, which must match the depth the tunnel was started with.
+    /// * `partial_tunnel` - the partial tunnel that was produced by the remote node.
+    ///
+    /// Returns a [FullTunnel] whose `id` can be used with [VeilidAPI::tunnel_send()] and [VeilidAPI::tunnel_recv()].
     #[cfg(feature = "unstable-tunnels")]
     #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
     pub async fn complete_tunnel(
         &self,
-        _endpoint_mode: TunnelMode,
-        _depth: u8,
-        _partial_tunnel: PartialTunnel,
+        endpoint_mode: TunnelMode,
+        depth: u8,
+        partial_tunnel: PartialTunnel,
     ) -> VeilidAPIResult<FullTunnel> {
-        panic!("unimplemented");
+        event!(target: "veilid_api", Level::DEBUG,
+            "VeilidAPI::complete_tunnel(endpoint_mode: {:?}, depth: {:?}, partial_tunnel: {:?})", endpoint_mode, depth, partial_tunnel);
+
+        Self::validate_tunnel_args(endpoint_mode, depth)?;
+        if partial_tunnel.local.mode != endpoint_mode {
+            apibail_invalid_argument!(
+                "complete_tunnel",
+                "endpoint_mode",
+                format!("{:?}", endpoint_mode)
+            );
+        }
+
+        // The remote endpoint is described by its node id
+        let remote_node_id =
+            TypedKey::from_str(&partial_tunnel.local.description).map_err(|e| {
+                VeilidAPIError::invalid_argument("complete_tunnel", "partial_tunnel", e)
+            })?;
+
+        let rpc_processor = self.rpc_processor()?;
+        let tunnel_table = rpc_processor.tunnel_table();
+        let safety_selection = SafetySelection::Unsafe(Sequencing::EnsureOrdered);
+        let Some(remote_nr) = rpc_processor
+            .resolve_node(remote_node_id, safety_selection)
+            .await?
+        else {
+            apibail_invalid_target!("could not resolve tunnel endpoint node");
+        };
+        let dest = Destination::direct(remote_nr.clone()).with_safety(safety_selection);
+
+        // Reserve the partial tunnel so that only we can complete it
+        let reserve_res = match rpc_processor
+            .clone()
+            .rpc_call_start_tunnel(dest.clone(), partial_tunnel.id, endpoint_mode, depth)
+            .await
+        {
+            Ok(NetworkResult::Value(v)) => v.answer,
+            Ok(NetworkResult::Timeout) => apibail_timeout!(),
+            Ok(NetworkResult::ServiceUnavailable(e)) => apibail_invalid_target!(e),
+            Ok(NetworkResult::NoConnection(e)) | Ok(NetworkResult::AlreadyExists(e)) => {
+                apibail_no_connection!(e);
+            }
+            Ok(NetworkResult::InvalidMessage(message)) => {
+                apibail_generic!(message);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if let Err(e) = reserve_res {
+            apibail_generic!(format!("tunnel was rejected: {:?}", e));
+        }
+
+        // Complete the tunnel with our endpoint
+        let local = self.local_tunnel_endpoint(endpoint_mode)?;
+        let remote_full_tunnel = match rpc_processor
+            .clone()
+            .rpc_call_complete_tunnel(
+                dest.clone(),
+                partial_tunnel.id,
+                endpoint_mode,
+                depth,
+                local.clone(),
+            )
+            .await
+        {
+            Ok(NetworkResult::Value(v)) => v.answer,
+            Ok(NetworkResult::Timeout) => apibail_timeout!(),
+            Ok(NetworkResult::ServiceUnavailable(e)) => apibail_invalid_target!(e),
+            Ok(NetworkResult::NoConnection(e)) | Ok(NetworkResult::AlreadyExists(e)) => {
+                apibail_no_connection!(e);
+            }
+            Ok(NetworkResult::InvalidMessage(message)) => {
+                apibail_generic!(message);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let remote_full_tunnel = match remote_full_tunnel {
+            Ok(v) => v,
+            Err(e) => {
+                apibail_generic!(format!("tunnel was rejected: {:?}", e));
+            }
+        };
+
+        // Keep our end of the tunnel, from our perspective
+        match tunnel_table.add_full(
+            partial_tunnel.id,
+            local,
+            remote_nr,
+            remote_full_tunnel.local,
+        ) {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                // Don't leave the remote end dangling
+                let _ = rpc_processor
+                    .rpc_call_cancel_tunnel(dest, partial_tunnel.id)
+                    .await;
+                apibail_generic!(format!("could not complete tunnel: {:?}", e));
+            }
+        }
+    }
+
+    /// Cancel a partial or full tunnel.
+    ///
+    /// If the tunnel is full, the remote endpoint is told to tear down its end as well.
+    ///
+    /// Returns `false` if the tunnel did not exist.
+    #[cfg(feature = "unstable-tunnels")]
+    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    pub async fn cancel_tunnel(&self, tunnel_id: TunnelId) -> VeilidAPIResult<bool> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "VeilidAPI::cancel_tunnel(tunnel_id: {:?})", tunnel_id);
+
+        let rpc_processor = self.rpc_processor()?;
+        let opt_remote_nr = match rpc_processor.tunnel_table().remove(tunnel_id, None) {
+            Ok(v) => v,
+            Err(_) => return Ok(false),
+        };
+
+        if let Some(remote_nr) = opt_remote_nr {
+            let dest = Destination::direct(remote_nr)
+                .with_safety(SafetySelection::Unsafe(Sequencing::EnsureOrdered));
+            // Our end is gone regardless, so the remote end will time out if this fails
+            match rpc_processor.rpc_call_cancel_tunnel(dest, tunnel_id).await {
+                Ok(NetworkResult::Value(_)) => {}
+                Ok(v) => {
+                    log_rpc!(debug "Remote tunnel cancel failed: {:?}", v);
+                }
+                Err(e) => {
+                    log_rpc!(debug "Remote tunnel cancel failed: {}", e);
+                }
+            }
+        }
+
+        Ok(true)
     }
 
+    /// Send bytes over a full tunnel.
+    ///
+    /// Bytes are delivered to the remote endpoint's [VeilidAPI::tunnel_recv()] in the order they were sent.
+    /// If any part of the data can not be delivered, the tunnel is closed.
+    #[cfg(feature = "unstable-tunnels")]
+    #[instrument(target = "veilid_api", level = "debug", skip(self, data), fields(data.len = data.len()), ret, err)]
+    pub async fn tunnel_send(&self, tunnel_id: TunnelId, data: Vec<u8>) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "VeilidAPI::tunnel_send(tunnel_id: {:?}, data(len): {})", tunnel_id, data.len());
+
+        let rpc_processor = self.rpc_processor()?;
+        let tunnel_table = rpc_processor.tunnel_table();
+
+        let chunks: Vec<&[u8]> = data.chunks(MAX_TUNNEL_DATA_LEN).collect();
+        let tunnel_send = match tunnel_table
+            .begin_send(tunnel_id, chunks.len() as u64)
+            .await
+        {
+            Ok(v) => v,
+            Err(TunnelError::BadId) => {
+                apibail_invalid_argument!("tunnel_send", "tunnel_id", tunnel_id);
+            }
+            Err(e) => {
+                apibail_generic!(format!("could not send over tunnel: {:?}", e));
+            }
+        };
+
+        let dest = Destination::direct(tunnel_send.remote_nr.clone())
+            .with_safety(SafetySelection::Unsafe(Sequencing::EnsureOrdered));
+        for (n, chunk) in chunks.into_iter().enumerate() {
+            let res = rpc_processor
+                .clone()
+                .rpc_call_tunnel_data(
+                    dest.clone(),
+                    tunnel_id,
+                    tunnel_send.first_seq + n as u64,
+                    chunk.to_vec(),
+                )
+                .await;
+            let err = match res {
+                Ok(NetworkResult::Value(())) => continue,
+                Ok(NetworkResult::Timeout) => VeilidAPIError::timeout(),
+                Ok(NetworkResult::ServiceUnavailable(e)) => VeilidAPIError::invalid_target(e),
+                Ok(NetworkResult::NoConnection(e)) | Ok(NetworkResult::AlreadyExists(e)) => {
+                    VeilidAPIError::no_connection(e)
+                }
+                Ok(NetworkResult::InvalidMessage(message)) => VeilidAPIError::generic(message),
+                Err(e) => e.into(),
+            };
+
+            // A chunk was lost, so the stream is broken
+            drop(tunnel_send);
+            let _ = self.cancel_tunnel(tunnel_id).await;
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Receive bytes from a partial or full tunnel.
+    ///
+    /// Waits for the next chunk of bytes sent by the remote endpoint.
+    ///
+    /// Returns `None` once the tunnel has been closed and all received bytes have been consumed.
     #[cfg(feature = "unstable-tunnels")]
     #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
-    pub async fn cancel_tunnel(&self, _tunnel_id: TunnelId) -> VeilidAPIResult<bool> {
-        panic!("unimplemented");
+    pub async fn tunnel_recv(&self, tunnel_id: TunnelId) -> VeilidAPIResult<Option<Vec<u8>>> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "VeilidAPI::tunnel_recv(tunnel_id: {:?})", tunnel_id);
+
+        let rpc_processor = self.rpc_processor()?;
+        let Some(receiver) = rpc_processor.tunnel_table().receiver(tunnel_id) else {
+            apibail_invalid_argument!("tunnel_recv", "tunnel_id", tunnel_id);
+        };
+
+        Ok(receiver.recv_async().await.ok())
+    }
+
+    #[cfg(feature = "unstable-tunnels")]
+    fn validate_tunnel_args(endpoint_mode: TunnelMode, depth: u8) -> VeilidAPIResult<()> {
+        if endpoint_mode != TunnelMode::Raw {
+            apibail_invalid_argument!("tunnel", "endpoint_mode", format!("{:?}", endpoint_mode));
+        }
+        if depth != 0 {
+            apibail_invalid_argument!("tunnel", "depth", depth);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "unstable-tunnels")]
+    fn local_tunnel_endpoint(&self, endpoint_mode: TunnelMode) -> VeilidAPIResult<TunnelEndpoint> {
+        let routing_table = self.routing_table()?;
+        let node_id = routing_table.node_id(best_crypto_kind());
+        Ok(TunnelEndpoint {
+            mode: endpoint_mode,
+            description: node_id.to_string(),
+        })
     }
 }
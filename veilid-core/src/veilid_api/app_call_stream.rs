@@ -0,0 +1,108 @@
+use super::*;
+
+/// Maximum number of reply chunks buffered for the application per streaming app call
+const MAX_APP_CALL_STREAM_RECV_QUEUE_LEN: usize = 256;
+
+struct AppCallStreamInner {
+    /// Cancels the call when dropped
+    stop_source: Option<StopSource>,
+    /// How the call ended, once it has
+    result: Option<VeilidAPIResult<()>>,
+}
+
+/// The reply chunks of a streaming app call started with [RoutingContext::app_call_stream].
+///
+/// Chunks are returned by [AppCallStream::recv] in the order the remote node sent them with
+/// [VeilidAPI::app_call_reply_chunk], followed by the message it finished the call with in
+/// [VeilidAPI::app_call_reply] if that is not empty. The remote node is only allowed to send a
+/// limited number of chunks ahead of those received here, so a slow receiver slows the sender down.
+/// Chunks that are not received quickly enough end the stream with an error.
+///
+/// Calling [AppCallStream::cancel] or dropping the stream tells the remote node to stop sending.
+pub struct AppCallStream {
+    chunk_receiver: flume::Receiver<Vec<u8>>,
+    inner: Arc<Mutex<AppCallStreamInner>>,
+}
+
+impl fmt::Debug for AppCallStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("AppCallStream")
+            .field("pending", &self.chunk_receiver.len())
+            .field("cancelled", &inner.stop_source.is_none())
+            .field("result", &inner.result)
+            .finish()
+    }
+}
+
+impl Drop for AppCallStream {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl AppCallStream {
+    pub(super) fn new(
+        rpc_processor: RPCProcessor,
+        dest: rpc_processor::Destination,
+        message: Vec<u8>,
+    ) -> Self {
+        let (chunk_sender, chunk_receiver) = flume::bounded(MAX_APP_CALL_STREAM_RECV_QUEUE_LEN);
+        let stop_source = StopSource::new();
+        let stop_token = stop_source.token();
+        let inner = Arc::new(Mutex::new(AppCallStreamInner {
+            stop_source: Some(stop_source),
+            result: None,
+        }));
+
+        let task_inner = inner.clone();
+        spawn_detached("app call stream", async move {
+            let res = match rpc_processor
+                .rpc_call_app_call_stream(dest, message, chunk_sender.clone(), stop_token)
+                .await
+            {
+                Ok(NetworkResult::Value(())) => Ok(()),
+                Ok(NetworkResult::Timeout) => Err(VeilidAPIError::timeout()),
+                Ok(NetworkResult::ServiceUnavailable(e)) => Err(VeilidAPIError::invalid_target(e)),
+                Ok(NetworkResult::NoConnection(e)) | Ok(NetworkResult::AlreadyExists(e)) => {
+                    Err(VeilidAPIError::no_connection(e))
+                }
+                Ok(NetworkResult::InvalidMessage(message)) => Err(VeilidAPIError::generic(message)),
+                Err(e) => Err(e.into()),
+            };
+
+            // Record how the call ended before the chunk channel closes, so recv() can report it
+            task_inner.lock().result = Some(res);
+            drop(chunk_sender);
+        });
+
+        Self {
+            chunk_receiver,
+            inner,
+        }
+    }
+
+    /// Wait for the next reply chunk.
+    ///
+    /// Returns `None` once the remote node has finished the call and every chunk has been received,
+    /// or once the stream has been cancelled.
+    pub async fn recv(&self) -> VeilidAPIResult<Option<Vec<u8>>> {
+        if let Ok(chunk) = self.chunk_receiver.recv_async().await {
+            return Ok(Some(chunk));
+        }
+
+        let inner = self.inner.lock();
+        if inner.stop_source.is_none() {
+            return Ok(None);
+        }
+        match &inner.result {
+            Some(Err(e)) => Err(e.clone()),
+            _ => Ok(None),
+        }
+    }
+
+    /// Stop receiving reply chunks and tell the remote node to stop sending them.
+    pub fn cancel(&self) {
+        self.inner.lock().stop_source.take();
+    }
+}
@@ -155,8 +155,12 @@ fn get_dht_schema(text: &str) -> Option<VeilidAPIResult<DHTSchema>> {
 fn get_safety_selection(routing_table: RoutingTable) -> impl Fn(&str) -> Option<SafetySelection> {
     move |text| {
         let rss = routing_table.route_spec_store();
-        let default_route_hop_count =
-            routing_table.with_config(|c| c.network.rpc.default_route_hop_count as usize);
+        let (default_route_hop_count, default_route_quality) = routing_table.with_config(|c| {
+            (
+                c.network.rpc.default_route_hop_count as usize,
+                c.network.rpc.default_route_quality,
+            )
+        });
 
         if !text.is_empty() && &text[0..1] == "-" {
             // Unsafe
@@ -169,6 +173,7 @@ fn get_safety_selection(routing_table: RoutingTable) -> impl Fn(&str) -> Option<
             let mut hop_count = default_route_hop_count;
             let mut stability = Stability::default();
             let mut sequencing = Sequencing::default();
+            let mut route_quality = default_route_quality;
             for x in text.split(',') {
                 let x = x.trim();
                 if let Some(pr) = get_route_id(rss.clone(), true, false)(x) {
@@ -183,12 +188,16 @@ fn get_safety_selection(routing_table: RoutingTable) -> impl Fn(&str) -> Option<
                 if let Some(s) = get_sequencing(x) {
                     sequencing = s;
                 }
+                if let Some(rq) = get_route_quality(route_quality)(x) {
+                    route_quality = rq;
+                }
             }
             let ss = SafetySpec {
                 preferred_route,
                 hop_count,
                 stability,
                 sequencing,
+                route_quality,
             };
             Some(SafetySelection::Safe(ss))
         }
@@ -342,6 +351,8 @@ fn get_protocol_type(text: &str) -> Option<ProtocolType> {
         Some(ProtocolType::WS)
     } else if lctext == "wss" {
         Some(ProtocolType::WSS)
+    } else if lctext == "wrtc" {
+        Some(ProtocolType::WRTC)
     } else {
         None
     }
@@ -368,6 +379,26 @@ fn get_stability(text: &str) -> Option<Stability> {
         None
     }
 }
+fn get_route_quality(route_quality: RouteQuality) -> impl Fn(&str) -> Option<RouteQuality> {
+    move |text| {
+        let mut route_quality = route_quality;
+        let rqtext = text.to_ascii_lowercase();
+        if rqtext == "ipdiv" {
+            route_quality.diverse_ip_blocks = true;
+        } else if rqtext == "asndiv" {
+            route_quality.diverse_asns = true;
+        } else if let Some(n) = rqtext.strip_prefix("lat=") {
+            route_quality.latency_weight = get_number(n)?;
+        } else if let Some(n) = rqtext.strip_prefix("fail=") {
+            route_quality.failure_weight = get_number(n)?;
+        } else if let Some(n) = rqtext.strip_prefix("maxlat=") {
+            route_quality.max_hop_latency = TimestampDuration::new_ms(get_number::<u64>(n)?);
+        } else {
+            return None;
+        }
+        Some(route_quality)
+    }
+}
 fn get_direction_set(text: &str) -> Option<DirectionSet> {
     let dstext = text.to_ascii_lowercase();
     if dstext == "in" {
@@ -397,6 +428,8 @@ fn get_routing_domain(text: &str) -> Option<RoutingDomain> {
         Some(RoutingDomain::PublicInternet)
     } else if "localnetwork".starts_with(&lctext) {
         Some(RoutingDomain::LocalNetwork)
+    } else if "privateoverlay".starts_with(&lctext) {
+        Some(RoutingDomain::PrivateOverlay)
     } else {
         None
     }
@@ -558,7 +591,7 @@ impl VeilidAPI {
         Ok(out)
     }
 
-    async fn debug_entries(&self, args: String) -> VeilidAPIResult<String> {
+    fn get_entries_filter(args: String) -> VeilidAPIResult<(BucketEntryState, Vec<FourCC>)> {
         let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
 
         let mut min_state = BucketEntryState::Unreliable;
@@ -576,6 +609,11 @@ impl VeilidAPI {
                 }
             }
         }
+        Ok((min_state, capabilities))
+    }
+
+    async fn debug_entries(&self, args: String) -> VeilidAPIResult<String> {
+        let (min_state, capabilities) = Self::get_entries_filter(args)?;
 
         // Dump routing table entries
         let routing_table = self.network_manager()?.routing_table();
@@ -1014,15 +1052,18 @@ impl VeilidAPI {
     }
 
     async fn debug_route_allocate(&self, args: Vec<String>) -> VeilidAPIResult<String> {
-        // [ord|*ord] [rel] [<count>] [in|out] [avoid_node_id]
+        // [ord|*ord] [rel] [<count>] [in|out] [<routequality>...] [avoid_node_id]
 
         let netman = self.network_manager()?;
         let routing_table = netman.routing_table();
         let rss = routing_table.route_spec_store();
         let config = self.config().unwrap();
-        let default_route_hop_count = {
+        let (default_route_hop_count, default_route_quality) = {
             let c = config.get();
-            c.network.rpc.default_route_hop_count as usize
+            (
+                c.network.rpc.default_route_hop_count as usize,
+                c.network.rpc.default_route_quality,
+            )
         };
 
         let mut ai = 1;
@@ -1030,6 +1071,7 @@ impl VeilidAPI {
         let mut stability = Stability::default();
         let mut hop_count = default_route_hop_count;
         let mut directions = DirectionSet::all();
+        let mut route_quality = default_route_quality;
 
         while ai < args.len() {
             if let Ok(seq) =
@@ -1048,6 +1090,14 @@ impl VeilidAPI {
                 get_debug_argument_at(&args, ai, "debug_route", "direction_set", get_direction_set)
             {
                 directions = ds;
+            } else if let Ok(rq) = get_debug_argument_at(
+                &args,
+                ai,
+                "debug_route",
+                "route_quality",
+                get_route_quality(route_quality),
+            ) {
+                route_quality = rq;
             } else {
                 return Ok(format!("Invalid argument specified: {}", args[ai]));
             }
@@ -1059,6 +1109,7 @@ impl VeilidAPI {
             &VALID_CRYPTO_KINDS,
             stability,
             sequencing,
+            &route_quality,
             hop_count,
             directions,
             &[],
@@ -1720,6 +1771,45 @@ impl VeilidAPI {
         })
     }
 
+    async fn debug_record_pin(&self, args: Vec<String>) -> VeilidAPIResult<String> {
+        let opt_arg_add = if args.len() >= 2 && get_dht_key_no_safety(&args[1]).is_some() {
+            1
+        } else {
+            0
+        };
+
+        let (key, rc) =
+            self.clone()
+                .get_opened_dht_record_context(&args, "debug_record_pin", "key", 1)?;
+
+        // A zero expiration unpins the record
+        let expiration = get_debug_argument_at(
+            &args,
+            1 + opt_arg_add,
+            "debug_record_pin",
+            "expiration",
+            parse_duration,
+        )
+        .ok()
+        .map(|dur| if dur == 0 { 0 } else { dur + get_timestamp() })
+        .unwrap_or_default();
+
+        // Do a record pin
+        let ts = match rc.pin_dht_record(key, Timestamp::new(expiration)).await {
+            Err(e) => {
+                return Ok(format!("Can't pin DHT record: {}", e));
+            }
+            Ok(v) => v,
+        };
+        if expiration == 0 {
+            return Ok("Unpinned".to_owned());
+        }
+        if ts.as_u64() == 0 {
+            return Ok("Failed to pin record".to_owned());
+        }
+        Ok(format!("Success: expiration={:?}", debug_ts(ts.as_u64())))
+    }
+
     async fn debug_record_inspect(&self, args: Vec<String>) -> VeilidAPIResult<String> {
         let opt_arg_add = if args.len() >= 2 && get_dht_key_no_safety(&args[1]).is_some() {
             1
@@ -1804,6 +1894,8 @@ impl VeilidAPI {
             self.debug_record_cancel(args).await
         } else if command == "inspect" {
             self.debug_record_inspect(args).await
+        } else if command == "pin" {
+            self.debug_record_pin(args).await
         } else {
             Ok(">>> Unknown command\n".to_owned())
         }
@@ -1861,6 +1953,35 @@ impl VeilidAPI {
         Ok(out)
     }
 
+    async fn debug_table_rekey(&self, args: Vec<String>) -> VeilidAPIResult<String> {
+        //
+        let table_store = self.table_store()?;
+        let crypto = self.crypto()?;
+
+        let kind = if args.len() > 1 {
+            let vcrypto = get_debug_argument_at(
+                &args,
+                1,
+                "debug_table_rekey",
+                "kind",
+                get_crypto_system_version(crypto),
+            )?;
+            Some(vcrypto.kind())
+        } else {
+            None
+        };
+
+        let remaining = table_store.rekey(kind).await?;
+        if remaining == 0 {
+            Ok("Table store re-keyed\n".to_owned())
+        } else {
+            Ok(format!(
+                "Table store re-key in progress, {} open table(s) remaining\n",
+                remaining
+            ))
+        }
+    }
+
     async fn debug_table(&self, args: String) -> VeilidAPIResult<String> {
         let args: Vec<String> =
             shell_words::split(&args).map_err(|e| VeilidAPIError::parse_error(e, args))?;
@@ -1871,6 +1992,8 @@ impl VeilidAPI {
             self.debug_table_list(args).await
         } else if command == "info" {
             self.debug_table_info(args).await
+        } else if command == "rekey" {
+            self.debug_table_rekey(args).await
         } else {
             Ok(">>> Unknown command\n".to_owned())
         }
@@ -1934,7 +2057,7 @@ appreply [#id] <data>
 relay <relay> [public|local]
 punish list
        clear
-route allocate [ord|*ord] [rel] [<count>] [in|out]
+route allocate [ord|*ord] [rel] [<count>] [in|out] [<routequality>...]
       release <route>
       publish <route> [full]
       unpublish <route>
@@ -1954,7 +2077,9 @@ record list <local|remote|opened|offline>
        watch [<key>] [<subkeys> [<expiration> [<count>]]]
        cancel [<key>] [<subkeys>]
        inspect [<key>] [<scope> [<subkeys>]]
+       pin [<key>] [<expiration>]
 table list
+      rekey [<cryptokind>]
 --------------------------------------------------------------------
 <key> is: VLD0:GsgXCRPrzSK6oBNgxhNpm-rTYFd02R0ySx6j9vbQBG4
     * also <node>, <relay>, <target>, <route>
@@ -1966,11 +2091,12 @@ table list
     * private: #<id>[+<safety>]
 <safety> is:
     * unsafe: -[ord|*ord]
-    * safe: [route][,ord|*ord][,rel][,<count>]
+    * safe: [route][,ord|*ord][,rel][,<count>][,<routequality>...]
+<routequality> is: lat=<weight>|fail=<weight>|maxlat=<ms>|ipdiv|asndiv
 <modifiers> is: [/<protocoltype>][/<addresstype>][/<routingdomain>]
-<protocoltype> is: udp|tcp|ws|wss
+<protocoltype> is: udp|tcp|ws|wss|wrtc
 <addresstype> is: ipv4|ipv6
-<routingdomain> is: public|local
+<routingdomain> is: public|local|private
 <cryptokind> is: VLD0
 <dhtschema> is: 
     * a single-quoted json dht schema, or 
@@ -2057,6 +2183,165 @@ table list
         res
     }
 
+    ////////////////////////////////////////////////////////////////
+    // Structured debug output
+
+    async fn debug_structured_buckets(&self, _args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let routing_table = self.network_manager()?.routing_table();
+        Ok(VeilidDebugResult::Buckets {
+            buckets: routing_table.get_bucket_stats(),
+        })
+    }
+
+    async fn debug_structured_entries(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let (min_state, capabilities) = Self::get_entries_filter(args)?;
+
+        let routing_table = self.network_manager()?.routing_table();
+        Ok(VeilidDebugResult::Entries {
+            entries: routing_table.debug_entry_list(min_state, capabilities),
+        })
+    }
+
+    async fn debug_structured_config(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let mut args = args.as_str();
+        let mut config = self.config()?;
+        if !args.starts_with("insecure") {
+            config = config.safe_config();
+        } else {
+            args = &args[8..];
+        }
+        let args = args.trim_start();
+        let (arg, rest) = args.split_once(' ').unwrap_or((args, ""));
+
+        // Changing the config has no structured output
+        if !rest.trim_start().is_empty() {
+            apibail_invalid_argument!("debug_structured_config", "new value", rest);
+        }
+
+        let config_json = config.get_key_json(arg, false)?;
+        Ok(VeilidDebugResult::Config {
+            config: serde_json::from_str(&config_json).map_err(VeilidAPIError::internal)?,
+        })
+    }
+
+    async fn debug_structured_route(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
+
+        let command = get_debug_argument_at(&args, 0, "debug_route", "command", get_string)?;
+        if command != "list" {
+            apibail_invalid_argument!("no structured output", "command", command);
+        }
+
+        let rss = self.routing_table()?.route_spec_store();
+        Ok(VeilidDebugResult::Routes {
+            allocated: rss.list_allocated_routes(|k, _| Some(*k)),
+            remote: rss.list_remote_routes(|k, _| Some(*k)),
+        })
+    }
+
+    async fn debug_structured_record(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let args: Vec<String> =
+            shell_words::split(&args).map_err(|e| VeilidAPIError::parse_error(e, args))?;
+
+        let command = get_debug_argument_at(&args, 0, "debug_record", "command", get_string)?;
+        if command != "list" {
+            apibail_invalid_argument!("no structured output", "command", command);
+        }
+
+        let storage_manager = self.storage_manager()?;
+        let scope = get_debug_argument_at(&args, 1, "debug_record_list", "scope", get_string)?;
+        match scope.as_str() {
+            "local" => {
+                let (stats, records) = storage_manager.debug_local_record_list().await?;
+                Ok(VeilidDebugResult::Records { stats, records })
+            }
+            "remote" => {
+                let (stats, records) = storage_manager.debug_remote_record_list().await?;
+                Ok(VeilidDebugResult::Records { stats, records })
+            }
+            "opened" => Ok(VeilidDebugResult::OpenedRecords {
+                records: storage_manager.debug_opened_record_list().await,
+            }),
+            "offline" => Ok(VeilidDebugResult::OfflineRecords {
+                records: storage_manager.debug_offline_record_list().await,
+            }),
+            _ => apibail_invalid_argument!("debug_record_list", "scope", scope),
+        }
+    }
+
+    async fn debug_structured_table(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let args: Vec<String> =
+            shell_words::split(&args).map_err(|e| VeilidAPIError::parse_error(e, args))?;
+
+        let command = get_debug_argument_at(&args, 0, "debug_table", "command", get_string)?;
+
+        let table_store = self.table_store()?;
+        if command == "list" {
+            Ok(VeilidDebugResult::Tables {
+                tables: table_store
+                    .list_all()
+                    .into_iter()
+                    .map(|(name, internal_name)| DebugTable {
+                        name,
+                        internal_name,
+                    })
+                    .collect(),
+            })
+        } else if command == "info" {
+            let table_name =
+                get_debug_argument_at(&args, 1, "debug_table_info", "name", get_string)?;
+            Ok(VeilidDebugResult::TableInfo {
+                info: table_store.info(&table_name).await?,
+            })
+        } else {
+            apibail_invalid_argument!("no structured output", "command", command);
+        }
+    }
+
+    async fn debug_structured_punish(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let args: Vec<String> =
+            shell_words::split(&args).map_err(|e| VeilidAPIError::parse_error(e, args))?;
+
+        let command = get_debug_argument_at(&args, 0, "debug_punish", "command", get_string)?;
+        if command != "list" {
+            apibail_invalid_argument!("no structured output", "command", command);
+        }
+
+        let address_filter = self.network_manager()?.address_filter();
+        Ok(VeilidDebugResult::Punishments {
+            punishments: address_filter.debug_punishment_list(),
+        })
+    }
+
+    /// Execute an 'internal debug command' and return its output in a structured form.
+    /// Only commands that inspect state are supported, with the same arguments as [VeilidAPI::debug]:
+    /// `buckets`, `entries`, `config`, `route list`, `record list`, `table list`, `table info` and `punish list`.
+    pub async fn debug_structured(&self, args: String) -> VeilidAPIResult<VeilidDebugResult> {
+        let args = args.trim_start();
+        let (arg, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let rest = rest.trim_start().to_owned();
+
+        if arg == "buckets" {
+            self.debug_structured_buckets(rest).await
+        } else if arg == "entries" {
+            self.debug_structured_entries(rest).await
+        } else if arg == "config" {
+            self.debug_structured_config(rest).await
+        } else if arg == "route" {
+            self.debug_structured_route(rest).await
+        } else if arg == "record" {
+            self.debug_structured_record(rest).await
+        } else if arg == "table" {
+            self.debug_structured_table(rest).await
+        } else if arg == "punish" {
+            self.debug_structured_punish(rest).await
+        } else {
+            Err(VeilidAPIError::generic(
+                "Unknown or unstructured server debug command",
+            ))
+        }
+    }
+
     fn get_destination(
         self,
         routing_table: RoutingTable,
@@ -80,6 +80,14 @@ macro_rules! apibail_key_not_found {
     };
 }
 
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! apibail_seq_mismatch {
+    ($x:expr, $y:expr) => {
+        return Err(VeilidAPIError::seq_mismatch($x, $y))
+    };
+}
+
 #[allow(unused_macros)]
 #[macro_export]
 macro_rules! apibail_invalid_target {
@@ -129,6 +137,11 @@ pub enum VeilidAPIError {
         #[schemars(with = "String")]
         key: TypedKey,
     },
+    #[error("Seq mismatch: expected {expected}, actual {actual}")]
+    SeqMismatch {
+        expected: ValueSeqNum,
+        actual: ValueSeqNum,
+    },
     #[error("Internal: {message}")]
     Internal { message: String },
     #[error("Unimplemented: {message}")]
@@ -179,6 +192,9 @@ impl VeilidAPIError {
     pub fn key_not_found(key: TypedKey) -> Self {
         Self::KeyNotFound { key }
     }
+    pub fn seq_mismatch(expected: ValueSeqNum, actual: ValueSeqNum) -> Self {
+        Self::SeqMismatch { expected, actual }
+    }
     pub fn internal<T: ToString>(msg: T) -> Self {
         Self::Internal {
             message: msg.to_string(),
@@ -56,6 +56,8 @@ pub enum RequestOp {
         stability: Stability,
         #[serde(default)]
         sequencing: Sequencing,
+        #[serde(default)]
+        route_quality: RouteQuality,
     },
     ImportRemotePrivateRoute {
         #[serde(with = "as_human_base64")]
@@ -73,6 +75,18 @@ pub enum RequestOp {
         #[schemars(with = "String")]
         message: Vec<u8>,
     },
+    AppCallReplyChunk {
+        #[schemars(with = "String")]
+        call_id: OperationId,
+        #[serde(with = "as_human_base64")]
+        #[schemars(with = "String")]
+        message: Vec<u8>,
+    },
+    FindPeersWithCapability {
+        #[schemars(with = "String")]
+        capability: FourCC,
+        count: u32,
+    },
     // Routing Context
     NewRoutingContext,
     RoutingContext(RoutingContextRequest),
@@ -118,6 +132,9 @@ pub enum RequestOp {
     Debug {
         command: String,
     },
+    DebugStructured {
+        command: String,
+    },
     VeilidVersionString,
     VeilidVersion,
     DefaultVeilidConfig,
@@ -172,6 +189,15 @@ pub enum ResponseOp {
         #[serde(flatten)]
         result: ApiResult<()>,
     },
+    AppCallReplyChunk {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
+    FindPeersWithCapability {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<Vec<String>>")]
+        result: ApiResultWithVecString<Vec<TypedKey>>,
+    },
     // Routing Context
     NewRoutingContext {
         #[serde(flatten)]
@@ -223,6 +249,10 @@ pub enum ResponseOp {
         #[serde(flatten)]
         result: ApiResult<String>,
     },
+    DebugStructured {
+        #[serde(flatten)]
+        result: ApiResult<Box<VeilidDebugResult>>,
+    },
     VeilidVersionString {
         value: String,
     },
@@ -338,10 +338,44 @@ impl JsonRequestProcessor {
                 subkey,
                 data,
                 writer,
+                expected_seq,
             } => RoutingContextResponseOp::SetDhtValue {
+                result: to_json_api_result(match expected_seq {
+                    Some(expected_seq) => {
+                        routing_context
+                            .compare_and_set_dht_value(key, subkey, expected_seq, data, writer)
+                            .await
+                    }
+                    None => {
+                        routing_context
+                            .set_dht_value(key, subkey, data, writer)
+                            .await
+                    }
+                }),
+            },
+            RoutingContextRequestOp::GetDhtValues {
+                key,
+                subkeys,
+                force_refresh,
+            } => RoutingContextResponseOp::GetDhtValues {
+                result: to_json_api_result(
+                    routing_context
+                        .get_dht_values(key, subkeys, force_refresh)
+                        .await,
+                ),
+            },
+            RoutingContextRequestOp::SetDhtValues {
+                key,
+                values,
+                writer,
+            } => RoutingContextResponseOp::SetDhtValues {
                 result: to_json_api_result(
                     routing_context
-                        .set_dht_value(key, subkey, data, writer)
+                        .set_dht_values(
+                            key,
+                            values.into_iter().map(|(k, v)| (k, v.value)).collect(),
+                            writer,
+                        )
                         .await,
                 ),
             },
@@ -376,6 +410,83 @@ impl JsonRequestProcessor {
                         .map(Box::new),
                 ),
             },
+            RoutingContextRequestOp::PinDhtRecord { key, expiration } => {
+                RoutingContextResponseOp::PinDhtRecord {
+                    result: to_json_api_result(
+                        routing_context.pin_dht_record(key, expiration).await,
+                    ),
+                }
+            }
+            RoutingContextRequestOp::GetDhtRecordSyncStatus { key } => {
+                RoutingContextResponseOp::GetDhtRecordSyncStatus {
+                    result: to_json_api_result(
+                        routing_context
+                            .get_dht_record_sync_status(key)
+                            .await
+                            .map(Box::new),
+                    ),
+                }
+            }
+            RoutingContextRequestOp::ClearDhtRecordConflicts { key, subkeys } => {
+                RoutingContextResponseOp::ClearDhtRecordConflicts {
+                    result: to_json_api_result(
+                        routing_context
+                            .clear_dht_record_conflicts(key, subkeys)
+                            .await,
+                    ),
+                }
+            }
+            RoutingContextRequestOp::SetDhtStream {
+                key,
+                header_subkey,
+                data,
+                writer,
+            } => RoutingContextResponseOp::SetDhtStream {
+                result: to_json_api_result(
+                    routing_context
+                        .set_dht_stream(key, header_subkey, data, writer)
+                        .await
+                        .map(Box::new),
+                ),
+            },
+            RoutingContextRequestOp::GetDhtStreamHeader {
+                key,
+                header_subkey,
+                force_refresh,
+            } => RoutingContextResponseOp::GetDhtStreamHeader {
+                result: to_json_api_result(
+                    routing_context
+                        .get_dht_stream_header(key, header_subkey, force_refresh)
+                        .await
+                        .map(|o| o.map(Box::new)),
+                ),
+            },
+            RoutingContextRequestOp::ReadDhtStream {
+                key,
+                header_subkey,
+                header,
+                offset,
+                length,
+                force_refresh,
+            } => RoutingContextResponseOp::ReadDhtStream {
+                result: to_json_api_result_with_vec_u8(
+                    routing_context
+                        .read_dht_stream(key, header_subkey, header, offset, length, force_refresh)
+                        .await,
+                ),
+            },
+            RoutingContextRequestOp::GetDhtStream {
+                key,
+                header_subkey,
+                force_refresh,
+            } => RoutingContextResponseOp::GetDhtStream {
+                result: to_json_api_result(
+                    routing_context
+                        .get_dht_stream(key, header_subkey, force_refresh)
+                        .await
+                        .map(|vopt| vopt.map(|v| VecU8 { value: v })),
+                ),
+            },
         };
         RoutingContextResponse {
             rc_id: rcr.rc_id,
@@ -400,6 +511,12 @@ impl JsonRequestProcessor {
             TableDbRequestOp::GetKeys { col } => TableDbResponseOp::GetKeys {
                 result: to_json_api_result_with_vec_vec_u8(table_db.get_keys(col).await),
             },
+            TableDbRequestOp::ScanKeys { col, scan } => TableDbResponseOp::ScanKeys {
+                result: to_json_api_result_with_vec_vec_u8(table_db.scan_keys(col, scan).await),
+            },
+            TableDbRequestOp::Scan { col, scan } => TableDbResponseOp::Scan {
+                result: to_json_api_result(table_db.scan(col, scan).await),
+            },
             TableDbRequestOp::Transact => TableDbResponseOp::Transact {
                 value: self.add_table_db_transaction(table_db.transact()),
             },
@@ -627,10 +744,11 @@ impl JsonRequestProcessor {
                 kinds,
                 stability,
                 sequencing,
+                route_quality,
             } => ResponseOp::NewCustomPrivateRoute {
                 result: to_json_api_result(
                     self.api
-                        .new_custom_private_route(&kinds, stability, sequencing)
+                        .new_custom_private_route(&kinds, stability, sequencing, route_quality)
                         .await
                         .map(|r| NewPrivateRouteResult {
                             route_id: r.0,
@@ -647,6 +765,16 @@ impl JsonRequestProcessor {
             RequestOp::AppCallReply { call_id, message } => ResponseOp::AppCallReply {
                 result: to_json_api_result(self.api.app_call_reply(call_id, message).await),
             },
+            RequestOp::AppCallReplyChunk { call_id, message } => ResponseOp::AppCallReplyChunk {
+                result: to_json_api_result(self.api.app_call_reply_chunk(call_id, message).await),
+            },
+            RequestOp::FindPeersWithCapability { capability, count } => {
+                ResponseOp::FindPeersWithCapability {
+                    result: to_json_api_result_with_vec_string(
+                        self.api.find_peers_with_capability(capability, count),
+                    ),
+                }
+            }
             RequestOp::NewRoutingContext => ResponseOp::NewRoutingContext {
                 result: to_json_api_result(
                     self.api
@@ -821,6 +949,9 @@ impl JsonRequestProcessor {
             RequestOp::Debug { command } => ResponseOp::Debug {
                 result: to_json_api_result(self.api.debug(command).await),
             },
+            RequestOp::DebugStructured { command } => ResponseOp::DebugStructured {
+                result: to_json_api_result(self.api.debug_structured(command).await.map(Box::new)),
+            },
             RequestOp::VeilidVersionString => ResponseOp::VeilidVersionString {
                 value: veilid_version_string(),
             },
@@ -72,6 +72,22 @@ pub enum RoutingContextRequestOp {
         data: Vec<u8>,
         #[schemars(with = "Option<String>")]
         writer: Option<KeyPair>,
+        #[serde(default)]
+        expected_seq: Option<ValueSeqNum>,
+    },
+    GetDhtValues {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        force_refresh: bool,
+    },
+    SetDhtValues {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        #[schemars(with = "Vec<(ValueSubkey, String)>")]
+        values: Vec<(ValueSubkey, VecU8)>,
+        #[schemars(with = "Option<String>")]
+        writer: Option<KeyPair>,
     },
     WatchDhtValues {
         #[schemars(with = "String")]
@@ -91,6 +107,51 @@ pub enum RoutingContextRequestOp {
         subkeys: ValueSubkeyRangeSet,
         scope: DHTReportScope,
     },
+    PinDhtRecord {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        expiration: Timestamp,
+    },
+    GetDhtRecordSyncStatus {
+        #[schemars(with = "String")]
+        key: TypedKey,
+    },
+    ClearDhtRecordConflicts {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+    },
+    SetDhtStream {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        #[serde(with = "as_human_base64")]
+        #[schemars(with = "String")]
+        data: Vec<u8>,
+        #[schemars(with = "Option<String>")]
+        writer: Option<KeyPair>,
+    },
+    GetDhtStreamHeader {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        force_refresh: bool,
+    },
+    ReadDhtStream {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        header: DHTStreamHeader,
+        offset: u32,
+        length: u32,
+        force_refresh: bool,
+    },
+    GetDhtStream {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        force_refresh: bool,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -145,6 +206,14 @@ pub enum RoutingContextResponseOp {
         #[serde(flatten)]
         result: ApiResult<Option<ValueData>>,
     },
+    GetDhtValues {
+        #[serde(flatten)]
+        result: ApiResult<Vec<Option<ValueData>>>,
+    },
+    SetDhtValues {
+        #[serde(flatten)]
+        result: ApiResult<Vec<Option<ValueData>>>,
+    },
     WatchDhtValues {
         #[serde(flatten)]
         result: ApiResult<Timestamp>,
@@ -157,4 +226,34 @@ pub enum RoutingContextResponseOp {
         #[serde(flatten)]
         result: ApiResult<Box<DHTRecordReport>>,
     },
+    PinDhtRecord {
+        #[serde(flatten)]
+        result: ApiResult<Timestamp>,
+    },
+    GetDhtRecordSyncStatus {
+        #[serde(flatten)]
+        result: ApiResult<Box<DHTRecordSyncStatus>>,
+    },
+    ClearDhtRecordConflicts {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
+    SetDhtStream {
+        #[serde(flatten)]
+        result: ApiResult<Box<DHTStreamHeader>>,
+    },
+    GetDhtStreamHeader {
+        #[serde(flatten)]
+        result: ApiResult<Option<Box<DHTStreamHeader>>>,
+    },
+    ReadDhtStream {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<String>")]
+        result: ApiResultWithVecU8,
+    },
+    GetDhtStream {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<Option<String>>")]
+        result: ApiResult<Option<VecU8>>,
+    },
 }
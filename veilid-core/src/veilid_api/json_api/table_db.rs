@@ -22,6 +22,14 @@ pub enum TableDbRequestOp {
     GetKeys {
         col: u32,
     },
+    ScanKeys {
+        col: u32,
+        scan: TableDBScan,
+    },
+    Scan {
+        col: u32,
+        scan: TableDBScan,
+    },
     Transact,
     Store {
         col: u32,
@@ -59,6 +67,15 @@ pub enum TableDbResponseOp {
         #[schemars(with = "ApiResult<Vec<String>>")]
         result: ApiResultWithVecVecU8,
     },
+    ScanKeys {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<Vec<String>>")]
+        result: ApiResultWithVecVecU8,
+    },
+    Scan {
+        #[serde(flatten)]
+        result: ApiResult<Vec<TableDBEntry>>,
+    },
     Transact {
         value: u32,
     },
@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 
 mod api;
+mod app_call_stream;
 mod debug;
 mod error;
 mod routing_context;
 mod serialize_helpers;
+mod stable_inbox;
 mod types;
 
 pub mod json_api;
@@ -12,10 +14,12 @@ pub mod json_api;
 pub mod tests;
 
 pub use api::*;
+pub use app_call_stream::*;
 pub use debug::*;
 pub use error::*;
 pub use routing_context::*;
 pub use serialize_helpers::*;
+pub use stable_inbox::*;
 pub use types::*;
 
 pub use alloc::string::ToString;
@@ -24,14 +28,17 @@ pub use crypto::*;
 #[cfg(feature = "unstable-blockstore")]
 pub use intf::BlockStore;
 pub use intf::ProtectedStore;
-pub use table_store::{TableDB, TableDBTransaction, TableStore};
+pub use table_store::{
+    ColumnInfo, IOStatsInfo, TableDB, TableDBEntry, TableDBScan, TableDBTransaction, TableInfo,
+    TableStore,
+};
 
 use crate::*;
 use attachment_manager::AttachmentManager;
 use core::fmt;
 use core_context::{api_shutdown, VeilidCoreContext};
 use network_manager::NetworkManager;
-use routing_table::{DirectionSet, RouteSpecStore, RoutingTable};
+use routing_table::{DirectionSet, NodeRefBase, RouteSpecStore, RoutingTable};
 use rpc_processor::*;
 use storage_manager::StorageManager;
 
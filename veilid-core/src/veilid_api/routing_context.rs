@@ -57,6 +57,7 @@ impl RoutingContext {
                     hop_count: c.network.rpc.default_route_hop_count as usize,
                     stability: Stability::Reliable,
                     sequencing: Sequencing::PreferOrdered,
+                    route_quality: c.network.rpc.default_route_quality,
                 }),
             }),
         })
@@ -65,7 +66,7 @@ impl RoutingContext {
     /// Turn on sender privacy, enabling the use of safety routes. This is the default and
     /// calling this function is only necessary if you have previously disable safety or used other parameters.
     ///
-    /// Default values for hop count, stability and sequencing preferences are used.
+    /// Default values for hop count, stability, sequencing preferences and route quality are used.
     ///
     /// * Hop count default is dependent on config, but is set to 1 extra hop.
     /// * Stability default is to choose reliable routes, preferring them over low latency.
@@ -85,6 +86,7 @@ impl RoutingContext {
             hop_count: c.network.rpc.default_route_hop_count as usize,
             stability: Stability::Reliable,
             sequencing: Sequencing::PreferOrdered,
+            route_quality: c.network.rpc.default_route_quality,
         }))
     }
 
@@ -118,6 +120,7 @@ impl RoutingContext {
                         hop_count: safety_spec.hop_count,
                         stability: safety_spec.stability,
                         sequencing,
+                        route_quality: safety_spec.route_quality,
                     }),
                 },
             }),
@@ -192,6 +195,34 @@ impl RoutingContext {
         Ok(answer.answer)
     }
 
+    /// App-level bidirectional call that expects a stream of reply chunks to be returned.
+    ///
+    /// The remote node receives a [VeilidUpdate::AppCall] whose [VeilidAppCall::streaming()] value is true, and may send
+    /// any number of reply chunks with [VeilidAPI::app_call_reply_chunk()] before finishing the call with [VeilidAPI::app_call_reply()].
+    /// Nodes that do not support streaming finish the call with a single reply.
+    ///
+    /// * `target` - can be either a direct node id or a private route.
+    /// * `message` - an arbitrary message blob of up to 32768 bytes.
+    ///
+    /// Returns an [AppCallStream] that receives reply chunks of up to 32768 bytes each.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn app_call_stream(
+        &self,
+        target: Target,
+        message: Vec<u8>,
+    ) -> VeilidAPIResult<AppCallStream> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::app_call_stream(self: {:?}, target: {:?}, message: {:?})", self, target, message);
+
+        let rpc_processor = self.api.rpc_processor()?;
+
+        // Get destination
+        let dest = self.get_destination(target).await?;
+
+        // Send app call and receive its reply chunks in the background
+        Ok(AppCallStream::new(rpc_processor, dest, message))
+    }
+
     /// App-level unidirectional message that does not expect any value to be returned.
     ///
     /// Veilid apps may use this for arbitrary message passing.
@@ -348,7 +379,88 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager.set_value(key, subkey, data, writer).await
+        storage_manager
+            .set_value(key, subkey, data, writer, None)
+            .await
+    }
+
+    /// Pushes a changed subkey value to the network, only if the subkey has not changed since it was last seen.
+    /// The DHT record must first by opened via open_dht_record or create_dht_record.
+    ///
+    /// * `expected_seq` is the sequence number of the subkey value this change is based on, or `ValueSeqNum::MAX` if the subkey is expected to be unset.
+    ///
+    /// The writer, if specified, will override the 'default_writer' specified when the record is opened.
+    ///
+    /// Returns `None` if the value was successfully put.
+    /// Returns `VeilidAPIError::SeqMismatch` if the subkey has a different sequence number locally or on the network.
+    /// Returns `VeilidAPIError::TryAgain` if the network is not available, as the sequence number can not be checked while offline.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn compare_and_set_dht_value(
+        &self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        expected_seq: ValueSeqNum,
+        data: Vec<u8>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<Option<ValueData>> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "RoutingContext::compare_and_set_dht_value(self: {:?}, key: {:?}, subkey: {:?}, expected_seq: {:?}, data: {:?}, writer: {:?})", self, key, subkey, expected_seq, data, writer);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .set_value(key, subkey, data, writer, Some(expected_seq))
+            .await
+    }
+
+    /// Gets the latest values of a set of subkeys.
+    ///
+    /// Subkeys that are not available locally are fetched from the network together, asking each node for
+    /// all of the subkeys in a single round, but by setting 'force_refresh' you can force a network data refresh.
+    /// * `subkeys` is the set of subkeys to get. It must not contain more than 512 subkeys.
+    ///
+    /// Returns one entry per subkey in ascending subkey order.
+    /// Each entry is `None` if the value subkey has not yet been set, or `Some(data)` if the value subkey has valid data.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn get_dht_values(
+        &self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Vec<Option<ValueData>>> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "RoutingContext::get_dht_values(self: {:?}, key: {:?}, subkeys: {:?}, force_refresh: {:?})", self, key, subkeys, force_refresh);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .get_values(key, subkeys, force_refresh)
+            .await
+    }
+
+    /// Pushes changed values of a set of subkeys to the network.
+    /// The DHT record must first by opened via open_dht_record or create_dht_record.
+    ///
+    /// All of the values are sent to each node together in a single round.
+    /// * `values` is a list of subkeys and their new data, in ascending subkey order. It must not contain more than 512 values.
+    ///
+    /// The writer, if specified, will override the 'default_writer' specified when the record is opened.
+    ///
+    /// Returns one entry per value in the order given.
+    /// Each entry is `None` if the value was successfully put, or `Some(data)` if the value put was older than the one available on the network.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn set_dht_values(
+        &self,
+        key: TypedKey,
+        values: Vec<(ValueSubkey, Vec<u8>)>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<Vec<Option<ValueData>>> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "RoutingContext::set_dht_values(self: {:?}, key: {:?}, values: {:?}, writer: {:?})", self, key, values, writer);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager.set_values(key, values, writer).await
     }
 
     /// Add or update a watch to a DHT value that informs the user via an VeilidUpdate::ValueChange callback when the record has subkeys change.
@@ -466,18 +578,278 @@ impl RoutingContext {
         storage_manager.inspect_record(key, subkeys, scope).await
     }
 
+    /// Pins a DHT record on the network, asking nodes offering a pinning service to retain the record
+    /// and keep re-replicating its values until the expiration, so that the record survives node churn.
+    ///
+    /// * `key` is the record key to pin. It must first be opened with the record owner keypair as its writer, as the pin request is signed by the owner.
+    /// * `expiration` is the desired timestamp of when the pin should end, in microseconds. A zero value here unpins the record.
+    ///
+    /// Nodes that accept the pin are sent all of the subkey values currently stored locally for the record.
+    /// Each node grants an expiration no later than its `network.dht.pinning.max_expiration_ms` setting, so pins should be renewed.
+    ///
+    /// Returns the earliest expiration granted by the nodes that accepted the pin.
+    /// If the returned timestamp is zero, no node accepted the pin, or the record was unpinned.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn pin_dht_record(
+        &self,
+        key: TypedKey,
+        expiration: Timestamp,
+    ) -> VeilidAPIResult<Timestamp> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::pin_dht_record(self: {:?}, key: {:?}, expiration: {:?})", self, key, expiration);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager.pin_record(key, expiration).await
+    }
+
+    /// Gets the sync status of writes to a DHT record that were made while the network was unavailable.
+    ///
+    /// Subkeys written offline are queued and written to the network once it is available again.
+    /// Progress is also reported as it happens with VeilidUpdate::ValueSync.
+    ///
+    /// Returns a DHTRecordSyncStatus with the subkeys that are still waiting to be written to the network,
+    /// and the subkeys whose queued write was replaced by a newer value from the network.
+    /// The record does not need to be opened to get its sync status.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn get_dht_record_sync_status(
+        &self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<DHTRecordSyncStatus> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::get_dht_record_sync_status(self: {:?}, key: {:?})", self, key);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager.get_record_sync_status(key).await
+    }
+
+    /// Clears conflicts reported for writes to a DHT record that were made while the network was unavailable.
+    ///
+    /// Conflicts remain in the DHTRecordSyncStatus until they are cleared, so that they can be resolved
+    /// by the application, for example by merging the newer value from the network with the value that was lost.
+    /// * `subkeys` is the range of subkeys to clear. If no range is specified, all conflicts for the record are cleared.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn clear_dht_record_conflicts(
+        &self,
+        key: TypedKey,
+        subkeys: ValueSubkeyRangeSet,
+    ) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::clear_dht_record_conflicts(self: {:?}, key: {:?}, subkeys: {:?})", self, key, subkeys);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager.clear_record_conflicts(key, subkeys).await
+    }
+
+    ///////////////////////////////////
+    /// DHT Streams
+
+    /// Writes a payload that may be larger than a single subkey to a DHT record.
+    /// The DHT record must first by opened via open_dht_record or create_dht_record.
+    ///
+    /// The payload is split into chunks written to the subkeys following 'header_subkey', and a
+    /// header describing the chunks is written to 'header_subkey' itself once all the chunks are written.
+    /// All of these subkeys must be writable by the writer, whether the record uses DHTSchemaDFLT or DHTSchemaSMPL.
+    ///
+    /// The writer, if specified, will override the 'default_writer' specified when the record is opened.
+    /// If the write fails partway through, it can be resumed by calling this again with the same data,
+    /// as chunks that were already written are not sent again.
+    ///
+    /// Returns the header for the written stream.
+    #[instrument(target = "veilid_api", level = "debug", skip(data), ret, err)]
+    pub async fn set_dht_stream(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        data: Vec<u8>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<DHTStreamHeader> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::set_dht_stream(self: {:?}, key: {:?}, header_subkey: {:?}, data.len(): {}, writer: {:?})", self, key, header_subkey, data.len(), writer);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .set_stream(key, header_subkey, data, writer)
+            .await
+    }
+
+    /// Gets the header of a stream written with set_dht_stream.
+    ///
+    /// May pull the latest header from the network, but by setting 'force_refresh' you can force a network data refresh.
+    ///
+    /// Returns `None` if the header subkey has not yet been set.
+    /// Returns `Some(header)` if the header subkey contains a valid stream header.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn get_dht_stream_header(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Option<DHTStreamHeader>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::get_dht_stream_header(self: {:?}, key: {:?}, header_subkey: {:?}, force_refresh: {:?})", self, key, header_subkey, force_refresh);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .get_stream_header(key, header_subkey, force_refresh)
+            .await
+    }
+
+    /// Reads a range of bytes from a stream, as described by a header from get_dht_stream_header.
+    ///
+    /// Large streams can be read in pieces, and a failed read can be resumed from any offset, by passing the same header.
+    /// Each chunk is checked against the header, so if the stream is rewritten while it is being read,
+    /// a TryAgain error is returned and the header should be fetched again.
+    ///
+    /// Returns the `length` bytes of the stream starting at `offset`.
+    #[instrument(target = "veilid_api", level = "debug", skip(header), ret, err)]
+    pub async fn read_dht_stream(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        header: DHTStreamHeader,
+        offset: u32,
+        length: u32,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Vec<u8>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::read_dht_stream(self: {:?}, key: {:?}, header_subkey: {:?}, offset: {:?}, length: {:?}, force_refresh: {:?})", self, key, header_subkey, offset, length, force_refresh);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        header.validate()?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .read_stream(key, header_subkey, &header, offset, length, force_refresh)
+            .await
+    }
+
+    /// Gets an entire stream written with set_dht_stream.
+    ///
+    /// May pull the latest data from the network, but by setting 'force_refresh' you can force a network data refresh.
+    ///
+    /// Returns `None` if the header subkey has not yet been set.
+    /// Returns `Some(data)` if the whole stream was read and matched its header.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn get_dht_stream(
+        &self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Option<Vec<u8>>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::get_dht_stream(self: {:?}, key: {:?}, header_subkey: {:?}, force_refresh: {:?})", self, key, header_subkey, force_refresh);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .get_stream(key, header_subkey, force_refresh)
+            .await
+    }
+
+    ///////////////////////////////////
+    /// Stable Inboxes
+
+    /// Resolves the DHT record key of another node's [StableInbox] to a private route that reaches it.
+    ///
+    /// The record is opened, the route blobs published to it are fetched from the network, and the first
+    /// one that can still be imported is returned as a remote private route. The record is closed again
+    /// afterward, so this should not be used on a record that is already open.
+    ///
+    /// Returns `VeilidAPIError::TryAgain` if no routes are currently published to the inbox.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn resolve_stable_inbox(&self, key: TypedKey) -> VeilidAPIResult<RouteId> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::resolve_stable_inbox(self: {:?}, key: {:?})", self, key);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        let descriptor = storage_manager
+            .open_record(key, None, self.unlocked_inner.safety_selection)
+            .await?;
+
+        let res = async {
+            for subkey in 0..=descriptor.schema().max_subkey() {
+                let Some(value) = storage_manager.get_value(key, subkey, true).await? else {
+                    continue;
+                };
+                if value.data().is_empty() {
+                    continue;
+                }
+                match self.api.import_remote_private_route(value.data().to_vec()) {
+                    Ok(route_id) => return Ok(route_id),
+                    Err(e) => {
+                        log_rtab!(debug "skipping stable inbox route in subkey {}: {}", subkey, e);
+                    }
+                }
+            }
+            apibail_try_again!("no routes are published to this stable inbox");
+        }
+        .await;
+
+        storage_manager.close_record(key).await?;
+        res
+    }
+
     ///////////////////////////////////
     /// Block Store
 
+    /// Stores an immutable block of data in the local block store.
+    ///
+    /// Blocks are content-addressed, so the block id is the hash of the data.
+    /// * 'kind' - specify a cryptosystem kind to hash with. Normally you will leave this as None to choose the 'best' cryptosystem available.
+    /// * 'data' - the block data, which may be up to 1MiB in size
+    /// Returns the block id. Use supply_block to make the block available to other nodes.
+    #[cfg(feature = "unstable-blockstore")]
+    #[instrument(target = "veilid_api", level = "debug", skip(data), ret, err)]
+    pub async fn store_block(
+        &self,
+        kind: Option<CryptoKind>,
+        data: Vec<u8>,
+    ) -> VeilidAPIResult<TypedKey> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::store_block(self: {:?}, kind: {:?}, data.len(): {})", self, kind, data.len());
+
+        let kind = kind.unwrap_or(best_crypto_kind());
+        Crypto::validate_crypto_kind(kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager.store_block(kind, data).await
+    }
+
+    /// Finds a block by its block id.
+    ///
+    /// Returns the block from the local block store if it is held locally, otherwise the block is located
+    /// on the network with this routing context's safety selection, validated against its hash, and stored locally.
+    /// Returns a KeyNotFound error if no node could supply the block.
     #[cfg(feature = "unstable-blockstore")]
     #[instrument(target = "veilid_api", level = "debug", ret, err)]
-    pub async fn find_block(&self, _block_id: PublicKey) -> VeilidAPIResult<Vec<u8>> {
-        panic!("unimplemented");
+    pub async fn find_block(&self, block_id: TypedKey) -> VeilidAPIResult<Vec<u8>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::find_block(self: {:?}, block_id: {:?})", self, block_id);
+
+        Crypto::validate_crypto_kind(block_id.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager
+            .find_block(block_id, self.unlocked_inner.safety_selection)
+            .await
     }
 
+    /// Announces to the nodes closest to a block id that this node can supply the block.
+    ///
+    /// The block must be held in the local block store. Supplier announcements identify this node,
+    /// so they are never sent over a safety route, and they expire, so they must be repeated periodically.
+    /// Returns true if at least one node accepted this node as a supplier.
     #[cfg(feature = "unstable-blockstore")]
     #[instrument(target = "veilid_api", level = "debug", ret, err)]
-    pub async fn supply_block(&self, _block_id: PublicKey) -> VeilidAPIResult<bool> {
-        panic!("unimplemented");
+    pub async fn supply_block(&self, block_id: TypedKey) -> VeilidAPIResult<bool> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::supply_block(self: {:?}, block_id: {:?})", self, block_id);
+
+        Crypto::validate_crypto_kind(block_id.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        storage_manager.supply_block(block_id).await
     }
 }
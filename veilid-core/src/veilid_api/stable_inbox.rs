@@ -0,0 +1,491 @@
+use super::*;
+
+/// How often a stable inbox checks on its routes
+const STABLE_INBOX_TICK_INTERVAL_MS: u32 = 10_000;
+/// The most routes a stable inbox can keep published at once
+const MAX_STABLE_INBOX_ROUTES: u16 = 8;
+/// Routes are replaced after 30 minutes, before churn in their hops is likely to break them
+const STABLE_INBOX_ROUTE_LIFETIME: TimestampDuration = TimestampDuration::new(1_800_000_000u64);
+/// Replaced routes are kept for 2 minutes so peers that resolved the inbox recently can still reach us
+const STABLE_INBOX_ROUTE_GRACE: TimestampDuration = TimestampDuration::new(120_000_000u64);
+/// The table that remembers the DHT record of each named stable inbox
+const STABLE_INBOX_TABLE: &str = "stable_inbox";
+
+/// What is kept about a named stable inbox between runs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StableInboxRecord {
+    /// The DHT record the route blobs are published to
+    key: TypedKey,
+    /// The owner of the DHT record
+    writer: KeyPair,
+    /// The number of routes, one per subkey
+    route_count: u16,
+}
+
+/// A route published to one of the stable inbox record's subkeys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct StableInboxRoute {
+    pub route_id: RouteId,
+    pub published_ts: Timestamp,
+}
+
+/// What a tick does with one of the stable inbox record's subkeys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum StableInboxSlotAction {
+    /// The published route is alive, healthy and not too old
+    Keep(StableInboxRoute),
+    /// Nothing is published, so try to publish a route
+    Publish,
+    /// The published route is degraded or too old, so publish a replacement and retire the old
+    /// route, or keep the old route if no replacement can be published
+    Replace(StableInboxRoute),
+    /// The published route died, so publish a replacement, or blank the subkey if none can be published
+    ReplaceDead(StableInboxRoute),
+}
+
+/// The state of a subkey after a tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct StableInboxSlotUpdate {
+    /// The route published to the subkey
+    pub slot: Option<StableInboxRoute>,
+    /// A replaced route to keep until the grace period is over
+    pub retired: Option<RouteId>,
+    /// If the subkey still holds a dead route and must be blanked
+    pub blank_subkey: bool,
+    /// If the routes of the inbox changed
+    pub changed: bool,
+}
+
+impl StableInboxSlotAction {
+    /// Decide what to do with a subkey, given its route if any, whether that route is degraded, or
+    /// None if the route spec store no longer has it
+    pub fn new(
+        opt_route: Option<StableInboxRoute>,
+        opt_degraded: Option<bool>,
+        cur_ts: Timestamp,
+    ) -> Self {
+        let Some(route) = opt_route else {
+            return Self::Publish;
+        };
+        match opt_degraded {
+            None => Self::ReplaceDead(route),
+            Some(true) => Self::Replace(route),
+            Some(false) => {
+                if cur_ts.saturating_sub(route.published_ts) < STABLE_INBOX_ROUTE_LIFETIME {
+                    Self::Keep(route)
+                } else {
+                    Self::Replace(route)
+                }
+            }
+        }
+    }
+
+    /// The state of the subkey after publishing a new route, or failing to
+    pub fn finish(self, opt_new_route: Option<StableInboxRoute>) -> StableInboxSlotUpdate {
+        let mut update = StableInboxSlotUpdate {
+            slot: opt_new_route,
+            retired: None,
+            blank_subkey: false,
+            changed: opt_new_route.is_some(),
+        };
+        match self {
+            Self::Keep(route) => {
+                update.slot = Some(route);
+                update.changed = false;
+            }
+            Self::Publish => {}
+            Self::Replace(route) => {
+                if opt_new_route.is_some() {
+                    update.retired = Some(route.route_id);
+                } else {
+                    update.slot = Some(route);
+                }
+            }
+            Self::ReplaceDead(_) => {
+                update.blank_subkey = opt_new_route.is_none();
+                update.changed = true;
+            }
+        }
+        update
+    }
+}
+
+/// Remove the retired routes whose grace period is over, returning them to be released
+pub(super) fn expire_retired_routes(
+    retired: &mut Vec<(RouteId, Timestamp)>,
+    cur_ts: Timestamp,
+) -> Vec<RouteId> {
+    let mut expired = Vec::new();
+    retired.retain(|(route_id, retired_ts)| {
+        if cur_ts.saturating_sub(*retired_ts) < STABLE_INBOX_ROUTE_GRACE {
+            return true;
+        }
+        expired.push(*route_id);
+        false
+    });
+    expired
+}
+
+pub(super) struct StableInboxUnlockedInner {
+    api: VeilidAPI,
+    routing_context: RoutingContext,
+    name: String,
+    record: StableInboxRecord,
+}
+
+struct StableInboxInner {
+    /// The route published to each subkey, if one could be allocated
+    slots: Vec<Option<StableInboxRoute>>,
+    /// Replaced routes and when they were replaced
+    retired: Vec<(RouteId, Timestamp)>,
+    /// Background route maintenance
+    tick_future: Option<SendPinBoxFuture<()>>,
+    /// Set once the inbox has been closed
+    closed: bool,
+}
+
+impl Drop for StableInboxInner {
+    fn drop(&mut self) {
+        if let Some(tick_future) = self.tick_future.take() {
+            spawn_detached("stable inbox tick stop", tick_future);
+        }
+    }
+}
+
+/// A private route inbox that peers can always reach through the same DHT record key.
+///
+/// The inbox keeps a set of private routes allocated, replaces them when they die or before
+/// they are likely to fail, and publishes the current route blobs to a DHT record owned by this
+/// node, one route per subkey. Peers turn the key into a route with [RoutingContext::resolve_stable_inbox].
+///
+/// Messages sent over the inbox's routes arrive as usual with [VeilidUpdate::AppMessage] and
+/// [VeilidUpdate::AppCall], and a [VeilidUpdate::InboxChange] is sent whenever the routes change.
+///
+/// Open an inbox with [VeilidAPI::open_stable_inbox] and close it with [StableInbox::close] to
+/// release its routes.
+#[derive(Clone)]
+pub struct StableInbox {
+    unlocked_inner: Arc<StableInboxUnlockedInner>,
+    inner: Arc<AsyncMutex<StableInboxInner>>,
+}
+
+impl fmt::Debug for StableInbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StableInbox")
+            .field("name", &self.unlocked_inner.name)
+            .field("key", &self.unlocked_inner.record.key)
+            .finish()
+    }
+}
+
+impl StableInbox {
+    pub(super) async fn open(
+        api: VeilidAPI,
+        name: String,
+        route_count: u16,
+    ) -> VeilidAPIResult<Self> {
+        if route_count == 0 || route_count > MAX_STABLE_INBOX_ROUTES {
+            apibail_invalid_argument!("route count out of range", "route_count", route_count);
+        }
+
+        // Reopen the record for this name, or create it the first time
+        let routing_context = api.routing_context()?;
+        let table_db = api.table_store()?.open(STABLE_INBOX_TABLE, 1).await?;
+        let record = match table_db
+            .load_json::<StableInboxRecord>(0, name.as_bytes())
+            .await?
+        {
+            Some(record) => {
+                if record.route_count != route_count {
+                    apibail_invalid_argument!(
+                        "stable inbox was created with a different route count",
+                        "route_count",
+                        route_count
+                    );
+                }
+                routing_context
+                    .open_dht_record(record.key, Some(record.writer))
+                    .await?;
+                record
+            }
+            None => {
+                let descriptor = routing_context
+                    .create_dht_record(DHTSchema::dflt(route_count)?, None)
+                    .await?;
+                let Some(owner_secret) = descriptor.owner_secret() else {
+                    apibail_internal!("created record has no owner secret");
+                };
+                let record = StableInboxRecord {
+                    key: *descriptor.key(),
+                    writer: KeyPair::new(*descriptor.owner(), *owner_secret),
+                    route_count,
+                };
+                table_db.store_json(0, name.as_bytes(), &record).await?;
+                record
+            }
+        };
+
+        let this = Self {
+            unlocked_inner: Arc::new(StableInboxUnlockedInner {
+                api,
+                routing_context,
+                name,
+                record,
+            }),
+            inner: Arc::new(AsyncMutex::new(StableInboxInner {
+                slots: vec![None; route_count as usize],
+                retired: Vec::new(),
+                tick_future: None,
+                closed: false,
+            })),
+        };
+
+        // Publish what we can right away, the ticker takes care of the rest
+        this.tick().await;
+
+        // Only hold weak references in the ticker so a dropped inbox stops maintaining itself
+        let unlocked_inner = Arc::downgrade(&this.unlocked_inner);
+        let inner = Arc::downgrade(&this.inner);
+        let tick_future =
+            interval(
+                "stable inbox tick",
+                STABLE_INBOX_TICK_INTERVAL_MS,
+                move || {
+                    let opt_this = unlocked_inner.upgrade().zip(inner.upgrade()).map(
+                        |(unlocked_inner, inner)| StableInbox {
+                            unlocked_inner,
+                            inner,
+                        },
+                    );
+                    async move {
+                        if let Some(this) = opt_this {
+                            this.tick().await;
+                        }
+                    }
+                },
+            );
+        this.inner.lock().await.tick_future = Some(tick_future);
+
+        Ok(this)
+    }
+
+    pub(super) fn registration(&self) -> Weak<StableInboxUnlockedInner> {
+        Arc::downgrade(&self.unlocked_inner)
+    }
+
+    /// The name this inbox was opened with.
+    pub fn name(&self) -> &str {
+        &self.unlocked_inner.name
+    }
+
+    /// The DHT record key peers use to reach this inbox.
+    pub fn key(&self) -> TypedKey {
+        self.unlocked_inner.record.key
+    }
+
+    /// Every private route that currently delivers to this inbox.
+    ///
+    /// This includes replaced routes that are kept for a short while after their replacement is published.
+    pub async fn route_ids(&self) -> Vec<RouteId> {
+        let inner = self.inner.lock().await;
+        Self::all_route_ids(&inner)
+    }
+
+    /// Stop maintaining the inbox and release all of its routes.
+    ///
+    /// The DHT record is kept, and opening an inbox with the same name later will publish to it again.
+    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    pub async fn close(&self) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "StableInbox::close(self: {:?})", self);
+
+        let tick_future = {
+            let mut inner = self.inner.lock().await;
+            if inner.closed {
+                return Ok(());
+            }
+            inner.closed = true;
+            inner.tick_future.take()
+        };
+        if let Some(tick_future) = tick_future {
+            tick_future.await;
+        }
+
+        // Let the name be opened again
+        let api = &self.unlocked_inner.api;
+        {
+            let mut api_inner = api.inner.lock();
+            let registration = self.registration();
+            if api_inner
+                .stable_inboxes
+                .get(&self.unlocked_inner.name)
+                .map(|w| w.ptr_eq(&registration))
+                .unwrap_or(false)
+            {
+                api_inner.stable_inboxes.remove(&self.unlocked_inner.name);
+            }
+        }
+
+        let route_ids = {
+            let mut inner = self.inner.lock().await;
+            let route_ids = Self::all_route_ids(&inner);
+            inner.slots.iter_mut().for_each(|slot| *slot = None);
+            inner.retired.clear();
+            route_ids
+        };
+        let rss = api.routing_table()?.route_spec_store();
+        for route_id in route_ids {
+            rss.release_route(route_id);
+        }
+
+        self.unlocked_inner
+            .routing_context
+            .close_dht_record(self.unlocked_inner.record.key)
+            .await
+    }
+
+    ////////////////////////////////////////////////////////////////
+
+    fn all_route_ids(inner: &StableInboxInner) -> Vec<RouteId> {
+        inner
+            .slots
+            .iter()
+            .flatten()
+            .map(|r| r.route_id)
+            .chain(inner.retired.iter().map(|r| r.0))
+            .collect()
+    }
+
+    async fn tick(&self) {
+        if let Err(e) = self.tick_inner().await {
+            log_rtab!(debug "stable inbox tick failed: {}", e);
+        }
+    }
+
+    async fn tick_inner(&self) -> VeilidAPIResult<()> {
+        let inner = &mut *self.inner.lock().await;
+        if inner.closed {
+            return Ok(());
+        }
+        let api = &self.unlocked_inner.api;
+        let rss = api.routing_table()?.route_spec_store();
+        let cur_ts = Timestamp::now();
+
+        // Release replaced routes once peers have had time to pick up their replacements
+        for route_id in expire_retired_routes(&mut inner.retired, cur_ts) {
+            rss.release_route(route_id);
+        }
+
+        let mut changed = false;
+        for subkey in 0..inner.slots.len() {
+            // Keep routes that are alive, healthy and not too old
+            let opt_old_route = inner.slots[subkey];
+            let opt_degraded = opt_old_route.and_then(|old_route| {
+                rss.list_allocated_routes(|id, rssd| {
+                    (*id == old_route.route_id).then(|| {
+                        let stats = rssd.get_stats();
+                        stats.failed_to_send > 0 || stats.questions_lost > 0
+                    })
+                })
+                .pop()
+            });
+            let action = StableInboxSlotAction::new(opt_old_route, opt_degraded, cur_ts);
+            if let StableInboxSlotAction::Keep(_) = action {
+                continue;
+            }
+            if let StableInboxSlotAction::ReplaceDead(old_route) = action {
+                log_rtab!(debug "stable inbox route died: {}", old_route.route_id);
+            }
+
+            // Publish a replacement
+            let opt_new_route = match self.publish_route(&rss, subkey as ValueSubkey).await {
+                Ok(new_route) => Some(new_route),
+                Err(e) => {
+                    log_rtab!(debug "stable inbox route unavailable: {}", e);
+                    None
+                }
+            };
+
+            let update = action.finish(opt_new_route);
+            inner.slots[subkey] = update.slot;
+            if let Some(route_id) = update.retired {
+                inner.retired.push((route_id, cur_ts));
+            }
+            changed |= update.changed;
+            if update.blank_subkey {
+                // Don't leave a dead route published
+                self.unlocked_inner
+                    .routing_context
+                    .set_dht_value(
+                        self.unlocked_inner.record.key,
+                        subkey as ValueSubkey,
+                        vec![],
+                        None,
+                    )
+                    .await?;
+            }
+        }
+
+        if changed {
+            let update_callback = api.routing_table()?.update_callback();
+            update_callback(VeilidUpdate::InboxChange(Box::new(VeilidInboxChange {
+                key: self.unlocked_inner.record.key,
+                route_ids: Self::all_route_ids(inner),
+            })));
+        }
+
+        Ok(())
+    }
+
+    /// Allocate and test a new route, then publish its blob to a subkey
+    async fn publish_route(
+        &self,
+        rss: &RouteSpecStore,
+        subkey: ValueSubkey,
+    ) -> VeilidAPIResult<StableInboxRoute> {
+        let (default_route_hop_count, default_route_quality) = {
+            let config = self.unlocked_inner.api.config()?;
+            let c = config.get();
+            (
+                usize::from(c.network.rpc.default_route_hop_count),
+                c.network.rpc.default_route_quality,
+            )
+        };
+
+        // Allocated as an automatic route so replacing it is not reported as a dead route
+        let route_id = rss.allocate_route(
+            &VALID_CRYPTO_KINDS,
+            Stability::Reliable,
+            Sequencing::PreferOrdered,
+            &default_route_quality,
+            default_route_hop_count,
+            DirectionSet::all(),
+            &[],
+            true,
+        )?;
+
+        let res = async {
+            if !rss.test_route(route_id).await? {
+                apibail_try_again!("allocated route failed to test");
+            }
+            let private_routes = rss.assemble_private_routes(&route_id, Some(true))?;
+            let blob = RouteSpecStore::private_routes_to_blob(&private_routes)?;
+            rss.mark_route_published(&route_id, true)?;
+            self.unlocked_inner
+                .routing_context
+                .set_dht_value(self.unlocked_inner.record.key, subkey, blob, None)
+                .await?;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = res {
+            rss.release_route(route_id);
+            return Err(e);
+        }
+
+        Ok(StableInboxRoute {
+            route_id,
+            published_ts: Timestamp::now(),
+        })
+    }
+}
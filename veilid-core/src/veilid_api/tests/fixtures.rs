@@ -77,6 +77,7 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
         namespace: "Internets".to_string(),
         capabilities: VeilidConfigCapabilities {
             disable: Vec::new(),
+            advertise: Vec::new(),
         },
         protected_store: VeilidConfigProtectedStore {
             allow_insecure_fallback: true,
@@ -114,6 +115,22 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                 limit_attached_strong: 3,
                 limit_attached_good: 4,
                 limit_attached_weak: 5,
+                bucket_retention: VeilidConfigBucketRetention {
+                    max_bucket_depth: 6,
+                    min_bucket_depth: 7,
+                    keep_closest_reliable: 8,
+                    keep_closest_unreliable: 9,
+                    age_weight: 10,
+                    reliability_weight: 11,
+                    reputation_weight: 12,
+                    long_lived_age_ms: 13,
+                    protect_long_lived: false,
+                },
+                private_overlay: VeilidConfigPrivateOverlay {
+                    networks: vec!["100.64.0.0/10".to_string()],
+                    bootstrap: vec!["udp://100.64.0.1:5150".to_string()],
+                },
+                asn_table: vec!["192.0.2.0/24 64496".to_string()],
             },
             rpc: VeilidConfigRPC {
                 concurrency: 5,
@@ -123,6 +140,13 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                 timeout_ms: 3000,
                 max_route_hop_count: 7,
                 default_route_hop_count: 8,
+                default_route_quality: RouteQuality {
+                    latency_weight: 9,
+                    failure_weight: 10,
+                    max_hop_latency: TimestampDuration::new(11),
+                    diverse_ip_blocks: true,
+                    diverse_asns: true,
+                },
             },
             dht: VeilidConfigDHT {
                 max_find_node_count: 1,
@@ -147,6 +171,16 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                 public_watch_limit: 20,
                 member_watch_limit: 21,
                 max_watch_expiration_ms: 22,
+                pinning: VeilidConfigDHTPinning {
+                    enabled: true,
+                    max_records: 23,
+                    max_records_per_owner: 5,
+                    max_expiration_ms: 24,
+                    allow_any_owner: false,
+                    allowed_owners: vec![
+                        "VLD0:7lxDEabK_qgjbe38RtBa3IZLrud84P6NhGP-pRTZzdQ".to_string()
+                    ],
+                },
             },
             upnp: true,
             detect_address_changes: false,
@@ -200,6 +234,11 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                     path: "Curved".to_string(),
                     url: Some("https://veilid.com/wss".to_string()),
                 },
+                wrtc: VeilidConfigWRTC {
+                    connect: true,
+                    max_connections: 11,
+                    ice_servers: vec!["stun:veilid.com:3478".to_string()],
+                },
             },
         },
     }
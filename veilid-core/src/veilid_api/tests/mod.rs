@@ -1,6 +1,10 @@
 mod fixtures;
 #[doc(hidden)]
+pub mod test_debug;
+#[doc(hidden)]
 pub mod test_serialize_json;
+#[doc(hidden)]
+pub mod test_stable_inbox;
 mod test_types;
 mod test_types_dht;
 mod test_types_dht_schema;
@@ -0,0 +1,205 @@
+use crate::tests::common::test_veilid_config::*;
+use crate::*;
+
+async fn startup() -> VeilidAPI {
+    let (update_callback, config_callback) = setup_veilid_core();
+    api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed")
+}
+
+async fn debug_structured(api: &VeilidAPI, args: &str) -> VeilidAPIResult<VeilidDebugResult> {
+    api.debug_structured(args.to_owned()).await
+}
+
+pub async fn test_debug_structured_commands(api: VeilidAPI) {
+    // Unknown commands and commands with only text output are rejected
+    assert!(matches!(
+        debug_structured(&api, "bogus").await,
+        Err(VeilidAPIError::Generic { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "ping").await,
+        Err(VeilidAPIError::Generic { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "").await,
+        Err(VeilidAPIError::Generic { .. })
+    ));
+
+    // Leading whitespace is ignored
+    assert!(matches!(
+        debug_structured(&api, "  buckets").await,
+        Ok(VeilidDebugResult::Buckets { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "entries").await,
+        Ok(VeilidDebugResult::Entries { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "entries dead").await,
+        Ok(VeilidDebugResult::Entries { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "entries not-a-capability").await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+
+    // Subcommands without structured output are rejected
+    assert!(matches!(
+        debug_structured(&api, "route list").await,
+        Ok(VeilidDebugResult::Routes { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "route allocate").await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "route").await,
+        Err(VeilidAPIError::MissingArgument { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "punish list").await,
+        Ok(VeilidDebugResult::Punishments { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "punish clear").await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+}
+
+pub async fn test_debug_structured_config(api: VeilidAPI) {
+    let Ok(VeilidDebugResult::Config { config }) =
+        debug_structured(&api, "config network.rpc.concurrency").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert_eq!(config, serde_json::json!(0));
+
+    // Secrets are only shown when asked for
+    let empty_secrets = serde_json::to_value(TypedSecretGroup::new()).unwrap();
+    let Ok(VeilidDebugResult::Config { config }) =
+        debug_structured(&api, "config network.routing_table.node_id_secret").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert_eq!(config, empty_secrets);
+    let Ok(VeilidDebugResult::Config { config }) =
+        debug_structured(&api, "config insecure network.routing_table.node_id_secret").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert_ne!(config, empty_secrets);
+
+    // The whole config is returned without a key
+    let Ok(VeilidDebugResult::Config { config }) = debug_structured(&api, "config").await else {
+        panic!("wrong debug result");
+    };
+    assert!(config["network"]["rpc"].is_object());
+
+    // Changing the config has no structured output
+    assert!(matches!(
+        debug_structured(&api, "config network.rpc.concurrency 2").await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+    assert_eq!(api.config().unwrap().get().network.rpc.concurrency, 0);
+}
+
+pub async fn test_debug_structured_record(api: VeilidAPI) {
+    let rc = api.routing_context().unwrap();
+    let rec = rc
+        .create_dht_record(DHTSchema::dflt(1).unwrap(), None)
+        .await
+        .unwrap();
+
+    let Ok(VeilidDebugResult::Records { records, .. }) =
+        debug_structured(&api, "record list local").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert!(records.iter().any(|r| r.key == *rec.key()));
+    assert!(matches!(
+        debug_structured(&api, "record list remote").await,
+        Ok(VeilidDebugResult::Records { .. })
+    ));
+    let Ok(VeilidDebugResult::OpenedRecords { records }) =
+        debug_structured(&api, "record list opened").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert!(records.iter().any(|r| r.key == *rec.key()));
+    assert!(matches!(
+        debug_structured(&api, "record list offline").await,
+        Ok(VeilidDebugResult::OfflineRecords { .. })
+    ));
+
+    assert!(matches!(
+        debug_structured(&api, "record list").await,
+        Err(VeilidAPIError::MissingArgument { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "record list bogus").await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+    assert!(matches!(
+        debug_structured(&api, "record get").await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+
+    rc.close_dht_record(*rec.key()).await.unwrap();
+    rc.delete_dht_record(*rec.key()).await.unwrap();
+}
+
+pub async fn test_debug_structured_table(api: VeilidAPI) {
+    let ts = api.table_store().unwrap();
+    let db = ts.open("test_debug", 2).await.unwrap();
+    db.store(1, b"k", b"v").await.unwrap();
+
+    let Ok(VeilidDebugResult::Tables { tables }) = debug_structured(&api, "table list").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert!(tables.iter().any(|t| t.name == "test_debug"));
+
+    let Ok(VeilidDebugResult::TableInfo { info: Some(info) }) =
+        debug_structured(&api, "table info test_debug").await
+    else {
+        panic!("wrong debug result");
+    };
+    assert_eq!(info.columns.len(), 2);
+    assert_eq!(info.columns[1].key_count.as_u64(), 1);
+
+    assert!(matches!(
+        debug_structured(&api, "table info").await,
+        Err(VeilidAPIError::MissingArgument { .. })
+    ));
+
+    drop(db);
+    ts.delete("test_debug").await.unwrap();
+}
+
+pub async fn test_debug_result_shape() {
+    // Results are tagged with their kind
+    let result = VeilidDebugResult::Routes {
+        allocated: vec![],
+        remote: vec![],
+    };
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["kind"], "Routes");
+    assert_eq!(json["allocated"], serde_json::json!([]));
+    assert_eq!(
+        serde_json::from_value::<VeilidDebugResult>(json).unwrap(),
+        result
+    );
+}
+
+pub async fn test_all() {
+    test_debug_result_shape().await;
+
+    let api = startup().await;
+    test_debug_structured_commands(api.clone()).await;
+    test_debug_structured_config(api.clone()).await;
+    test_debug_structured_record(api.clone()).await;
+    test_debug_structured_table(api.clone()).await;
+    api.shutdown().await;
+}
@@ -34,6 +34,8 @@ pub async fn test_all() {
     test_peertabledata().await;
     test_veilidstatenetwork().await;
     test_veilidroutechange().await;
+    test_veilidinboxchange().await;
+    test_veilidvaluesync().await;
     test_veilidstateconfig().await;
     test_veilidvaluechange().await;
     test_veilidupdate().await;
@@ -47,4 +49,5 @@ pub async fn test_all() {
     test_dhtschema().await;
     test_dhtschemasmplmember().await;
     test_dhtschemasmpl().await;
+    test_dhtschemaalog().await;
 }
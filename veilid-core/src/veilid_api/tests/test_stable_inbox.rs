@@ -0,0 +1,144 @@
+use super::super::stable_inbox::*;
+use crate::*;
+
+const MINUTE: u64 = 60_000_000;
+
+fn route_id(n: u8) -> RouteId {
+    CryptoKey::new([n; 32])
+}
+
+fn route(n: u8, published_ts: u64) -> StableInboxRoute {
+    StableInboxRoute {
+        route_id: route_id(n),
+        published_ts: Timestamp::new(published_ts),
+    }
+}
+
+fn slot_update(
+    slot: Option<StableInboxRoute>,
+    retired: Option<RouteId>,
+    blank_subkey: bool,
+    changed: bool,
+) -> StableInboxSlotUpdate {
+    StableInboxSlotUpdate {
+        slot,
+        retired,
+        blank_subkey,
+        changed,
+    }
+}
+
+pub async fn test_slot_action() {
+    let old = route(1, 0);
+    let cur_ts = Timestamp::new(10 * MINUTE);
+
+    // Empty subkeys are published to
+    assert_eq!(
+        StableInboxSlotAction::new(None, None, cur_ts),
+        StableInboxSlotAction::Publish
+    );
+
+    // Healthy routes are kept until they get too old
+    assert_eq!(
+        StableInboxSlotAction::new(Some(old), Some(false), cur_ts),
+        StableInboxSlotAction::Keep(old)
+    );
+    assert_eq!(
+        StableInboxSlotAction::new(Some(old), Some(false), Timestamp::new(29 * MINUTE)),
+        StableInboxSlotAction::Keep(old)
+    );
+    assert_eq!(
+        StableInboxSlotAction::new(Some(old), Some(false), Timestamp::new(30 * MINUTE)),
+        StableInboxSlotAction::Replace(old)
+    );
+
+    // Degraded routes are replaced straight away
+    assert_eq!(
+        StableInboxSlotAction::new(Some(old), Some(true), cur_ts),
+        StableInboxSlotAction::Replace(old)
+    );
+
+    // Routes the route spec store no longer has are dead
+    assert_eq!(
+        StableInboxSlotAction::new(Some(old), None, cur_ts),
+        StableInboxSlotAction::ReplaceDead(old)
+    );
+
+    // A clock that went backwards doesn't replace routes
+    let future = route(2, 20 * MINUTE);
+    assert_eq!(
+        StableInboxSlotAction::new(Some(future), Some(false), cur_ts),
+        StableInboxSlotAction::Keep(future)
+    );
+}
+
+pub async fn test_slot_update() {
+    let old = route(1, 0);
+    let new = route(2, 10 * MINUTE);
+
+    // Kept routes stay as they are
+    assert_eq!(
+        StableInboxSlotAction::Keep(old).finish(None),
+        slot_update(Some(old), None, false, false)
+    );
+
+    // Empty subkeys stay empty if no route can be published
+    assert_eq!(
+        StableInboxSlotAction::Publish.finish(Some(new)),
+        slot_update(Some(new), None, false, true)
+    );
+    assert_eq!(
+        StableInboxSlotAction::Publish.finish(None),
+        slot_update(None, None, false, false)
+    );
+
+    // Replaced routes are retired with a grace period, or kept if there is no replacement yet
+    assert_eq!(
+        StableInboxSlotAction::Replace(old).finish(Some(new)),
+        slot_update(Some(new), Some(old.route_id), false, true)
+    );
+    assert_eq!(
+        StableInboxSlotAction::Replace(old).finish(None),
+        slot_update(Some(old), None, false, false)
+    );
+
+    // Dead routes are never retired, and their subkey is blanked if there is no replacement
+    assert_eq!(
+        StableInboxSlotAction::ReplaceDead(old).finish(Some(new)),
+        slot_update(Some(new), None, false, true)
+    );
+    assert_eq!(
+        StableInboxSlotAction::ReplaceDead(old).finish(None),
+        slot_update(None, None, true, true)
+    );
+}
+
+pub async fn test_expire_retired_routes() {
+    let mut retired = vec![
+        (route_id(1), Timestamp::new(0)),
+        (route_id(2), Timestamp::new(MINUTE)),
+        (route_id(3), Timestamp::new(2 * MINUTE)),
+    ];
+
+    // Retired routes are kept for the grace period
+    assert!(expire_retired_routes(&mut retired, Timestamp::new(MINUTE)).is_empty());
+    assert_eq!(retired.len(), 3);
+
+    assert_eq!(
+        expire_retired_routes(&mut retired, Timestamp::new(3 * MINUTE)),
+        vec![route_id(1), route_id(2)]
+    );
+    assert_eq!(retired, vec![(route_id(3), Timestamp::new(2 * MINUTE))]);
+
+    assert_eq!(
+        expire_retired_routes(&mut retired, Timestamp::new(4 * MINUTE)),
+        vec![route_id(3)]
+    );
+    assert!(retired.is_empty());
+}
+
+pub async fn test_all() {
+    test_slot_action().await;
+    test_slot_update().await;
+    test_expire_retired_routes().await;
+}
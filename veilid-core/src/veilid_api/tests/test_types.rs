@@ -29,6 +29,7 @@ pub async fn test_veilidappcall() {
         Some(fix_cryptokey()),
         b"Well, hello!".to_vec(),
         OperationId::from(123),
+        true,
     );
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
 
@@ -73,6 +74,13 @@ pub async fn test_safetyspec() {
         hop_count: 23,
         stability: Stability::default(),
         sequencing: Sequencing::default(),
+        route_quality: RouteQuality {
+            latency_weight: 2,
+            failure_weight: 500,
+            max_hop_latency: TimestampDuration::new(250_000),
+            diverse_ip_blocks: true,
+            diverse_asns: true,
+        },
     };
     let copy = deserialize_json(&serialize_json(orig)).unwrap();
 
@@ -248,6 +256,27 @@ pub async fn test_veilidroutechange() {
     assert_eq!(orig, copy);
 }
 
+pub async fn test_veilidinboxchange() {
+    let orig = VeilidInboxChange {
+        key: fix_typedkey(),
+        route_ids: vec![fix_cryptokey(), fix_cryptokey()],
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_veilidvaluesync() {
+    let orig = VeilidValueSync {
+        key: fix_typedkey(),
+        state: ValueSyncState::Conflict,
+        subkeys: ValueSubkeyRangeSet::single(3),
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 pub async fn test_veilidstateconfig() {
     let orig = VeilidStateConfig {
         config: fix_veilidconfiginner(),
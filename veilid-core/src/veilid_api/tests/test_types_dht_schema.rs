@@ -65,3 +65,23 @@ pub async fn test_dhtschemasmpl() {
 
     assert_eq!(orig, copy);
 }
+
+// alog
+
+pub async fn test_dhtschemaalog() {
+    let orig = DHTSchemaALOG::new(
+        3,
+        vec![DHTSchemaSMPLMember {
+            m_key: fix_cryptokey(),
+            m_cnt: 4,
+        }],
+    )
+    .unwrap();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+
+    let schema = DHTSchema::ALOG(orig);
+    let compiled = schema.compile();
+    assert_eq!(DHTSchema::try_from(compiled.as_slice()).unwrap(), schema);
+}
@@ -75,6 +75,9 @@ pub struct VeilidAppCall {
     #[serde(with = "as_human_string")]
     #[schemars(with = "String")]
     call_id: OperationId,
+
+    #[serde(default)]
+    streaming: bool,
 }
 
 impl VeilidAppCall {
@@ -83,12 +86,14 @@ impl VeilidAppCall {
         route_id: Option<RouteId>,
         message: Vec<u8>,
         call_id: OperationId,
+        streaming: bool,
     ) -> Self {
         Self {
             sender,
             route_id,
             message,
             call_id,
+            streaming,
         }
     }
 
@@ -111,4 +116,9 @@ impl VeilidAppCall {
     pub fn id(&self) -> OperationId {
         self.call_id
     }
+
+    /// True if the caller accepts reply chunks sent with [VeilidAPI::app_call_reply_chunk] before the final [VeilidAPI::app_call_reply].
+    pub fn streaming(&self) -> bool {
+        self.streaming
+    }
 }
@@ -0,0 +1,139 @@
+use super::*;
+
+/// The liveness state of a routing table entry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum DebugEntryState {
+    Punished,
+    Dead,
+    Unreliable,
+    Reliable,
+}
+
+/// A routing table entry, as listed by the 'entries' debug command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DebugEntry {
+    /// The node id of the entry in the bucket it was found in
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub node_id: TypedKey,
+    /// The index of the bucket the entry was found in
+    pub bucket_index: u32,
+    /// The liveness state of the entry
+    pub state: DebugEntryState,
+    /// Why the entry is in its current state
+    pub state_reason: String,
+    /// Latencies for communications with the entry
+    pub latency: Option<LatencyStats>,
+    /// The capabilities published by the entry, if we have its public internet node info
+    #[schemars(with = "Option<Vec<String>>")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string[] | undefined"))]
+    pub capabilities: Option<Vec<FourCC>>,
+}
+
+/// A record in a record store, as listed by the 'record list local|remote' debug commands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DebugRecord {
+    /// The record key
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub key: TypedKey,
+    /// The time since the record was last accessed
+    pub age: TimestampDuration,
+    /// The size of the record's subkey data
+    pub data_size: ByteCount,
+    /// The subkeys of the record that have data stored
+    pub stored_subkeys: ValueSubkeyRangeSet,
+}
+
+/// A record opened by this node, as listed by the 'record list opened' debug command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DebugOpenedRecord {
+    /// The record key
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub key: TypedKey,
+    /// The public key of the writer the record was opened with, if any
+    #[schemars(with = "Option<String>")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional, type = "string"))]
+    pub writer: Option<PublicKey>,
+    /// The subkeys being watched for changes, if the record has an active watch
+    pub watched_subkeys: Option<ValueSubkeyRangeSet>,
+}
+
+/// A record with writes waiting for the network, as listed by the 'record list offline' debug command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DebugOfflineRecord {
+    /// The record key
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub key: TypedKey,
+    /// The subkeys that have not yet been written to the network
+    pub subkeys: ValueSubkeyRangeSet,
+}
+
+/// A table in the table store, as listed by the 'table list' debug command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DebugTable {
+    /// The name of the table
+    pub name: String,
+    /// The internal name of the table in the database
+    pub internal_name: String,
+}
+
+/// An active punishment, as listed by the 'punish list' debug command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DebugPunishment {
+    /// What is punished: an ipv4 address, an ipv6 prefix, or a node id
+    pub target: String,
+    /// Why it was punished
+    pub reason: String,
+    /// When it was punished
+    pub timestamp: Timestamp,
+}
+
+/// Structured output of a debug command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(into_wasm_abi))]
+#[serde(tag = "kind")]
+pub enum VeilidDebugResult {
+    /// 'buckets'
+    Buckets { buckets: Vec<BucketStats> },
+    /// 'entries [dead|reliable] [<capabilities>]'
+    Entries { entries: Vec<DebugEntry> },
+    /// 'route list'
+    Routes {
+        #[schemars(with = "Vec<String>")]
+        #[cfg_attr(target_arch = "wasm32", tsify(type = "string[]"))]
+        allocated: Vec<RouteId>,
+        #[schemars(with = "Vec<String>")]
+        #[cfg_attr(target_arch = "wasm32", tsify(type = "string[]"))]
+        remote: Vec<RouteId>,
+    },
+    /// 'record list <local|remote>'
+    Records {
+        stats: RecordStoreStats,
+        records: Vec<DebugRecord>,
+    },
+    /// 'record list opened'
+    OpenedRecords { records: Vec<DebugOpenedRecord> },
+    /// 'record list offline'
+    OfflineRecords { records: Vec<DebugOfflineRecord> },
+    /// 'table list'
+    Tables { tables: Vec<DebugTable> },
+    /// 'table info <name>'
+    TableInfo { info: Option<TableInfo> },
+    /// 'punish list'
+    Punishments { punishments: Vec<DebugPunishment> },
+    /// 'config [insecure] [configkey]'
+    Config {
+        #[cfg_attr(target_arch = "wasm32", tsify(type = "any"))]
+        config: serde_json::Value,
+    },
+}
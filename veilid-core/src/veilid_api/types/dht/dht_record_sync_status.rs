@@ -0,0 +1,39 @@
+use super::*;
+
+/// DHT Record Sync Status
+/// Reports on the subkeys of a DHT Record that were written while the network was unavailable
+#[derive(
+    Debug, Default, Clone, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct DHTRecordSyncStatus {
+    /// The subkeys that have been written offline that still need to be flushed to the network
+    pending_subkeys: ValueSubkeyRangeSet,
+    /// The subkeys whose offline write was replaced by a newer value from the network
+    /// These remain reported until cleared with RoutingContext::clear_dht_record_conflicts()
+    conflict_subkeys: ValueSubkeyRangeSet,
+}
+from_impl_to_jsvalue!(DHTRecordSyncStatus);
+
+impl DHTRecordSyncStatus {
+    pub fn new(
+        pending_subkeys: ValueSubkeyRangeSet,
+        conflict_subkeys: ValueSubkeyRangeSet,
+    ) -> Self {
+        Self {
+            pending_subkeys,
+            conflict_subkeys,
+        }
+    }
+
+    pub fn pending_subkeys(&self) -> &ValueSubkeyRangeSet {
+        &self.pending_subkeys
+    }
+    pub fn conflict_subkeys(&self) -> &ValueSubkeyRangeSet {
+        &self.conflict_subkeys
+    }
+}
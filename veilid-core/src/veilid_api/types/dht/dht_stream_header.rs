@@ -0,0 +1,170 @@
+use super::*;
+
+/// DHT Stream Header
+///
+/// Describes a payload that has been spread across a contiguous range of subkeys,
+/// starting at the subkey after the one holding this header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct DHTStreamHeader {
+    /// Total length of the payload in bytes
+    length: u32,
+    /// Length of each chunk subkey in bytes. The last chunk may be shorter.
+    chunk_size: u32,
+    /// Hash of each chunk, in subkey order, using the cryptosystem of the record key
+    #[schemars(with = "Vec<String>")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string[]"))]
+    chunk_hashes: Vec<HashDigest>,
+}
+from_impl_to_jsvalue!(DHTStreamHeader);
+
+impl DHTStreamHeader {
+    pub const FCC: [u8; 4] = *b"STRM";
+    pub const FIXED_SIZE: usize = 12;
+    pub const MAX_CHUNKS: usize = (ValueData::MAX_LEN - Self::FIXED_SIZE) / HASH_DIGEST_LENGTH;
+
+    /// Make a stream header
+    pub fn new(
+        length: u32,
+        chunk_size: u32,
+        chunk_hashes: Vec<HashDigest>,
+    ) -> VeilidAPIResult<Self> {
+        let out = Self {
+            length,
+            chunk_size,
+            chunk_hashes,
+        };
+        out.validate()?;
+        Ok(out)
+    }
+
+    /// Validate the data representation
+    pub fn validate(&self) -> VeilidAPIResult<()> {
+        if self.chunk_size == 0 || self.chunk_size as usize > ValueData::MAX_LEN {
+            apibail_invalid_argument!("invalid chunk size", "chunk_size", self.chunk_size);
+        }
+        let expected_chunks =
+            (self.length as u64 + self.chunk_size as u64 - 1) / self.chunk_size as u64;
+        if self.chunk_hashes.len() as u64 != expected_chunks {
+            apibail_invalid_argument!(
+                "chunk count does not match length",
+                "chunk_hashes.len()",
+                self.chunk_hashes.len()
+            );
+        }
+        if self.chunk_hashes.len() > Self::MAX_CHUNKS {
+            apibail_invalid_argument!("too many chunks", "length", self.length);
+        }
+        Ok(())
+    }
+
+    /// Get the total length of the payload
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Get the length of each chunk
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    /// Get the number of chunk subkeys following the header subkey
+    pub fn chunk_count(&self) -> u32 {
+        self.chunk_hashes.len() as u32
+    }
+
+    /// Get the hash of each chunk
+    pub fn chunk_hashes(&self) -> &[HashDigest] {
+        &self.chunk_hashes
+    }
+
+    /// Get the length of a particular chunk
+    pub fn chunk_len(&self, chunk: u32) -> u32 {
+        let start = chunk.saturating_mul(self.chunk_size);
+        self.length.saturating_sub(start).min(self.chunk_size)
+    }
+
+    /// Build the data representation of the header
+    pub fn compile(&self) -> Vec<u8> {
+        let mut out = Vec::<u8>::with_capacity(
+            Self::FIXED_SIZE + self.chunk_hashes.len() * HASH_DIGEST_LENGTH,
+        );
+        // kind
+        out.extend_from_slice(&Self::FCC);
+        // length
+        out.extend_from_slice(&self.length.to_le_bytes());
+        // chunk_size
+        out.extend_from_slice(&self.chunk_size.to_le_bytes());
+        // chunk_hashes
+        for h in &self.chunk_hashes {
+            out.extend_from_slice(&h.bytes);
+        }
+        out
+    }
+}
+
+impl TryFrom<&[u8]> for DHTStreamHeader {
+    type Error = VeilidAPIError;
+    fn try_from(b: &[u8]) -> Result<Self, Self::Error> {
+        if b.len() < Self::FIXED_SIZE {
+            apibail_generic!("invalid size");
+        }
+        if b[0..4] != Self::FCC {
+            apibail_generic!("wrong fourcc");
+        }
+        if (b.len() - Self::FIXED_SIZE) % HASH_DIGEST_LENGTH != 0 {
+            apibail_generic!("invalid size");
+        }
+
+        let length = u32::from_le_bytes(b[4..8].try_into().map_err(VeilidAPIError::internal)?);
+        let chunk_size = u32::from_le_bytes(b[8..12].try_into().map_err(VeilidAPIError::internal)?);
+        let chunk_hashes = b[Self::FIXED_SIZE..]
+            .chunks_exact(HASH_DIGEST_LENGTH)
+            .map(|h| HashDigest::try_from(h).map_err(VeilidAPIError::internal))
+            .collect::<VeilidAPIResult<Vec<_>>>()?;
+
+        Self::new(length, chunk_size, chunk_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_header_round_trip() {
+        let chunk_size = ValueData::MAX_LEN as u32;
+        let header = DHTStreamHeader::new(
+            chunk_size * 2 + 1,
+            chunk_size,
+            vec![
+                HashDigest::new([1u8; 32]),
+                HashDigest::new([2u8; 32]),
+                HashDigest::new([3u8; 32]),
+            ],
+        )
+        .unwrap();
+        assert_eq!(header.chunk_len(0), chunk_size);
+        assert_eq!(header.chunk_len(2), 1);
+
+        let data = header.compile();
+        assert_eq!(
+            data.len(),
+            DHTStreamHeader::FIXED_SIZE + 3 * HASH_DIGEST_LENGTH
+        );
+        assert_eq!(DHTStreamHeader::try_from(data.as_slice()).unwrap(), header);
+    }
+
+    #[test]
+    fn stream_header_invalid() {
+        // chunk count must match the length
+        assert!(DHTStreamHeader::new(10, 4, vec![HashDigest::new([0u8; 32]); 2]).is_err());
+        assert!(DHTStreamHeader::new(0, 4, vec![]).is_ok());
+        assert!(DHTStreamHeader::new(0, 0, vec![]).is_err());
+        assert!(DHTStreamHeader::try_from(b"DFLT\0\0\0\0\0\0\0\0".as_slice()).is_err());
+    }
+}
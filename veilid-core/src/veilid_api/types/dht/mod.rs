@@ -1,5 +1,7 @@
 mod dht_record_descriptor;
 mod dht_record_report;
+mod dht_record_sync_status;
+mod dht_stream_header;
 mod schema;
 mod value_data;
 mod value_subkey_range_set;
@@ -8,6 +10,8 @@ use super::*;
 
 pub use dht_record_descriptor::*;
 pub use dht_record_report::*;
+pub use dht_record_sync_status::*;
+pub use dht_stream_header::*;
 pub use schema::*;
 pub use value_data::*;
 pub use value_subkey_range_set::*;
@@ -0,0 +1,262 @@
+use super::*;
+
+/// Append-Only Log DHT Schema (ALOG)
+///
+/// Subkeys are allocated to the owner and members as in the SMPL schema, but each subkey
+/// holds a hash-chained log of entries that can only be extended, never rewritten.
+///
+/// A log is a sequence of entries, each encoded as:
+/// `[ prev_hash: HashDigest, len: u16, payload: [u8; len] ]`
+/// where `prev_hash` is the hash of the complete encoding of the previous entry in the subkey,
+/// or all zeroes for the first entry, using the cryptosystem of the record key.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(from_wasm_abi))]
+pub struct DHTSchemaALOG {
+    /// Owner subkey count
+    o_cnt: u16,
+    /// Members
+    members: Vec<DHTSchemaSMPLMember>,
+}
+
+impl DHTSchemaALOG {
+    pub const FCC: [u8; 4] = *b"ALOG";
+    pub const FIXED_SIZE: usize = 6;
+    pub const ENTRY_HEADER_SIZE: usize = HASH_DIGEST_LENGTH + 2;
+
+    /// Make a schema
+    pub fn new(o_cnt: u16, members: Vec<DHTSchemaSMPLMember>) -> VeilidAPIResult<Self> {
+        let out = Self { o_cnt, members };
+        out.validate()?;
+        Ok(out)
+    }
+
+    /// Validate the data representation
+    pub fn validate(&self) -> VeilidAPIResult<()> {
+        let keycount = self
+            .members
+            .iter()
+            .fold(self.o_cnt as usize, |acc, x| acc + (x.m_cnt as usize));
+
+        if keycount == 0 {
+            apibail_invalid_argument!("must have at least one subkey", "keycount", keycount);
+        }
+        if keycount > 65535 {
+            apibail_invalid_argument!("too many subkeys", "keycount", keycount);
+        }
+        Ok(())
+    }
+
+    /// Get the owner subkey count
+    pub fn o_cnt(&self) -> u16 {
+        self.o_cnt
+    }
+
+    /// Get the members of the schema
+    pub fn members(&self) -> &[DHTSchemaSMPLMember] {
+        &self.members
+    }
+
+    /// Build the data representation of the schema
+    pub fn compile(&self) -> Vec<u8> {
+        let mut out = Vec::<u8>::with_capacity(
+            Self::FIXED_SIZE + (self.members.len() * (PUBLIC_KEY_LENGTH + 2)),
+        );
+        // kind
+        out.extend_from_slice(&Self::FCC);
+        // o_cnt
+        out.extend_from_slice(&self.o_cnt.to_le_bytes());
+        // members
+        for m in &self.members {
+            // m_key
+            out.extend_from_slice(&m.m_key.bytes);
+            // m_cnt
+            out.extend_from_slice(&m.m_cnt.to_le_bytes());
+        }
+        out
+    }
+
+    /// Get the maximum subkey this schema allocates
+    pub fn max_subkey(&self) -> ValueSubkey {
+        let subkey_count = self
+            .members
+            .iter()
+            .fold(self.o_cnt as usize, |acc, x| acc + (x.m_cnt as usize));
+        (subkey_count - 1) as ValueSubkey
+    }
+
+    /// Get the data size of this schema beyond the size of the structure itself
+    pub fn data_size(&self) -> usize {
+        self.members.len() * mem::size_of::<DHTSchemaSMPLMember>()
+    }
+
+    /// Check a subkey value data against the schema
+    ///
+    /// This checks the writer and the structure of the log. The hash chain depends on the
+    /// cryptosystem and the previous value, so it is checked by the record store with check_log_append.
+    pub fn check_subkey_value_data(
+        &self,
+        owner: &PublicKey,
+        subkey: ValueSubkey,
+        value_data: &ValueData,
+    ) -> bool {
+        if Self::log_entries(value_data.data()).is_err() {
+            return false;
+        }
+
+        let mut cur_subkey = subkey as usize;
+
+        // Check if subkey is in owner range
+        if cur_subkey < (self.o_cnt as usize) {
+            // Check value data has valid writer
+            if value_data.writer() == owner {
+                return true;
+            }
+            // Wrong writer
+            return false;
+        }
+        cur_subkey -= self.o_cnt as usize;
+
+        // Check all member ranges
+        for m in &self.members {
+            // Check if subkey is in member range
+            if cur_subkey < (m.m_cnt as usize) {
+                // Check value data has valid writer
+                if value_data.writer() == &m.m_key {
+                    return true;
+                }
+                // Wrong writer
+                return false;
+            }
+            cur_subkey -= m.m_cnt as usize;
+        }
+
+        // Subkey out of range
+        false
+    }
+
+    /// Check if a key is a schema member
+    pub fn is_member(&self, key: &PublicKey) -> bool {
+        for m in &self.members {
+            if m.m_key == *key {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Get the payloads of the entries in a log
+    pub fn log_entries(log: &[u8]) -> VeilidAPIResult<Vec<&[u8]>> {
+        Ok(Self::split_log(log)?
+            .into_iter()
+            .map(|(_prev_hash, _entry, payload)| payload)
+            .collect())
+    }
+
+    /// Append an entry to a log, chaining it to the last entry
+    pub fn append_log_entry(
+        vcrypto: CryptoSystemVersion,
+        log: &[u8],
+        payload: &[u8],
+    ) -> VeilidAPIResult<Vec<u8>> {
+        let Ok(len) = u16::try_from(payload.len()) else {
+            apibail_invalid_argument!("log entry too large", "payload.len()", payload.len());
+        };
+        let entries = Self::split_log(log)?;
+        let prev_hash = match entries.last() {
+            Some((_prev_hash, entry, _payload)) => vcrypto.generate_hash(entry),
+            None => HashDigest::default(),
+        };
+
+        let mut out = Vec::with_capacity(log.len() + Self::ENTRY_HEADER_SIZE + payload.len());
+        out.extend_from_slice(log);
+        out.extend_from_slice(&prev_hash.bytes);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(payload);
+        if out.len() > ValueData::MAX_LEN {
+            apibail_invalid_argument!("log is full", "payload.len()", payload.len());
+        }
+        Ok(out)
+    }
+
+    /// Check that every entry in a log is chained to the entry before it
+    pub fn check_log_chain(vcrypto: CryptoSystemVersion, log: &[u8]) -> bool {
+        let Ok(entries) = Self::split_log(log) else {
+            return false;
+        };
+        let mut expected_prev_hash = HashDigest::default();
+        for (prev_hash, entry, _payload) in entries {
+            if prev_hash != expected_prev_hash {
+                return false;
+            }
+            expected_prev_hash = vcrypto.generate_hash(entry);
+        }
+        true
+    }
+
+    /// Check that a new log value only appends entries to an old one
+    pub fn check_log_append(vcrypto: CryptoSystemVersion, old_log: &[u8], new_log: &[u8]) -> bool {
+        new_log.starts_with(old_log) && Self::check_log_chain(vcrypto, new_log)
+    }
+
+    /// Split a log into (prev_hash, entry, payload) for each entry
+    fn split_log(log: &[u8]) -> VeilidAPIResult<Vec<(HashDigest, &[u8], &[u8])>> {
+        let mut out = Vec::new();
+        let mut pos = 0usize;
+        while pos < log.len() {
+            if log.len() - pos < Self::ENTRY_HEADER_SIZE {
+                apibail_generic!("truncated log entry header");
+            }
+            let prev_hash = HashDigest::try_from(&log[pos..pos + HASH_DIGEST_LENGTH])
+                .map_err(VeilidAPIError::internal)?;
+            let len = u16::from_le_bytes(
+                log[pos + HASH_DIGEST_LENGTH..pos + Self::ENTRY_HEADER_SIZE]
+                    .try_into()
+                    .map_err(VeilidAPIError::internal)?,
+            ) as usize;
+            let end = pos + Self::ENTRY_HEADER_SIZE + len;
+            if end > log.len() {
+                apibail_generic!("truncated log entry payload");
+            }
+            out.push((
+                prev_hash,
+                &log[pos..end],
+                &log[pos + Self::ENTRY_HEADER_SIZE..end],
+            ));
+            pos = end;
+        }
+        Ok(out)
+    }
+}
+
+impl TryFrom<&[u8]> for DHTSchemaALOG {
+    type Error = VeilidAPIError;
+    fn try_from(b: &[u8]) -> Result<Self, Self::Error> {
+        if b.len() < Self::FIXED_SIZE {
+            apibail_generic!("invalid size");
+        }
+        if b[0..4] != Self::FCC {
+            apibail_generic!("wrong fourcc");
+        }
+        if (b.len() - Self::FIXED_SIZE) % (PUBLIC_KEY_LENGTH + 2) != 0 {
+            apibail_generic!("invalid member length");
+        }
+
+        let o_cnt = u16::from_le_bytes(b[4..6].try_into().map_err(VeilidAPIError::internal)?);
+
+        let members_len = (b.len() - Self::FIXED_SIZE) / (PUBLIC_KEY_LENGTH + 2);
+        let mut members: Vec<DHTSchemaSMPLMember> = Vec::with_capacity(members_len);
+        for n in 0..members_len {
+            let mstart = Self::FIXED_SIZE + n * (PUBLIC_KEY_LENGTH + 2);
+            let m_key = PublicKey::try_from(&b[mstart..mstart + PUBLIC_KEY_LENGTH])
+                .map_err(VeilidAPIError::internal)?;
+            let m_cnt = u16::from_le_bytes(
+                b[mstart + PUBLIC_KEY_LENGTH..mstart + PUBLIC_KEY_LENGTH + 2]
+                    .try_into()
+                    .map_err(VeilidAPIError::internal)?,
+            );
+            members.push(DHTSchemaSMPLMember { m_key, m_cnt });
+        }
+
+        Self::new(o_cnt, members)
+    }
+}
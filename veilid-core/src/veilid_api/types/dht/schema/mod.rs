@@ -1,8 +1,10 @@
+mod alog;
 mod dflt;
 mod smpl;
 
 use super::*;
 
+pub use alog::*;
 pub use dflt::*;
 pub use smpl::*;
 
@@ -13,6 +15,7 @@ pub use smpl::*;
 pub enum DHTSchema {
     DFLT(DHTSchemaDFLT),
     SMPL(DHTSchemaSMPL),
+    ALOG(DHTSchemaALOG),
 }
 
 impl DHTSchema {
@@ -22,12 +25,16 @@ impl DHTSchema {
     pub fn smpl(o_cnt: u16, members: Vec<DHTSchemaSMPLMember>) -> VeilidAPIResult<DHTSchema> {
         Ok(DHTSchema::SMPL(DHTSchemaSMPL::new(o_cnt, members)?))
     }
+    pub fn alog(o_cnt: u16, members: Vec<DHTSchemaSMPLMember>) -> VeilidAPIResult<DHTSchema> {
+        Ok(DHTSchema::ALOG(DHTSchemaALOG::new(o_cnt, members)?))
+    }
 
     /// Validate the data representation
     pub fn validate(&self) -> VeilidAPIResult<()> {
         match self {
             DHTSchema::DFLT(d) => d.validate(),
             DHTSchema::SMPL(s) => s.validate(),
+            DHTSchema::ALOG(a) => a.validate(),
         }
     }
 
@@ -36,6 +43,7 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.compile(),
             DHTSchema::SMPL(s) => s.compile(),
+            DHTSchema::ALOG(a) => a.compile(),
         }
     }
 
@@ -44,6 +52,7 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.max_subkey(),
             DHTSchema::SMPL(s) => s.max_subkey(),
+            DHTSchema::ALOG(a) => a.max_subkey(),
         }
     }
 
@@ -52,6 +61,7 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.data_size(),
             DHTSchema::SMPL(s) => s.data_size(),
+            DHTSchema::ALOG(a) => a.data_size(),
         }
     }
 
@@ -65,6 +75,28 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.check_subkey_value_data(owner, subkey, value_data),
             DHTSchema::SMPL(s) => s.check_subkey_value_data(owner, subkey, value_data),
+            DHTSchema::ALOG(a) => a.check_subkey_value_data(owner, subkey, value_data),
+        }
+    }
+
+    /// Check that a subkey value data may replace the previous value data for the subkey,
+    /// or be the first value data for the subkey if there is no previous value data
+    pub fn check_subkey_value_update(
+        &self,
+        vcrypto: CryptoSystemVersion,
+        opt_old_value_data: Option<&ValueData>,
+        new_value_data: &ValueData,
+    ) -> bool {
+        match self {
+            DHTSchema::DFLT(_) | DHTSchema::SMPL(_) => true,
+            DHTSchema::ALOG(_) => match opt_old_value_data {
+                Some(old_value_data) => DHTSchemaALOG::check_log_append(
+                    vcrypto,
+                    old_value_data.data(),
+                    new_value_data.data(),
+                ),
+                None => DHTSchemaALOG::check_log_chain(vcrypto, new_value_data.data()),
+            },
         }
     }
 
@@ -73,6 +105,7 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.is_member(key),
             DHTSchema::SMPL(s) => s.is_member(key),
+            DHTSchema::ALOG(a) => a.is_member(key),
         }
     }
 
@@ -119,6 +152,7 @@ impl TryFrom<&[u8]> for DHTSchema {
         match fcc {
             DHTSchemaDFLT::FCC => Ok(DHTSchema::DFLT(DHTSchemaDFLT::try_from(b)?)),
             DHTSchemaSMPL::FCC => Ok(DHTSchema::SMPL(DHTSchemaSMPL::try_from(b)?)),
+            DHTSchemaALOG::FCC => Ok(DHTSchema::ALOG(DHTSchemaALOG::try_from(b)?)),
             _ => {
                 apibail_generic!("unknown fourcc");
             }
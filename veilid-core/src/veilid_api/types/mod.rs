@@ -1,6 +1,7 @@
 #[macro_use]
 mod aligned_u64;
 mod app_message_call;
+mod debug;
 mod dht;
 mod fourcc;
 mod safety;
@@ -16,6 +17,7 @@ use super::*;
 
 pub use aligned_u64::*;
 pub use app_message_call::*;
+pub use debug::*;
 pub use dht::*;
 pub use fourcc::*;
 pub use safety::*;
@@ -43,6 +43,53 @@ impl Default for Stability {
     }
 }
 
+/// Tunables for choosing the hops of a safety route when a new one has to be allocated.
+///
+/// Candidate hops are scored on their measured latency and on the route tests they have
+/// recently failed, and the lowest scoring hops are preferred. The default ignores these
+/// measurements and leaves the ordering of hops to the [Stability] preference.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct RouteQuality {
+    /// Score added for each millisecond of a hop's average measured latency.
+    #[serde(default)]
+    pub latency_weight: u32,
+    /// Score added for each recent route test failure a hop was part of.
+    #[serde(default)]
+    pub failure_weight: u32,
+    /// Hops with a higher average measured latency than this are not used. Zero for no limit.
+    #[serde(default)]
+    pub max_hop_latency: TimestampDuration,
+    /// Only use hops on distinct IP blocks from each other, including their relays.
+    #[serde(default)]
+    pub diverse_ip_blocks: bool,
+    /// Only use hops in distinct autonomous systems from each other, including their relays.
+    /// Autonomous systems are looked up in 'network.routing_table.asn_table', and addresses
+    /// it does not cover are grouped by their /16 (IPv4) or /32 (IPv6) network instead.
+    #[serde(default)]
+    pub diverse_asns: bool,
+}
+
+impl RouteQuality {
+    /// Whether any of the tunables are in use
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// The choice of safety route to include in compiled routes.
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
@@ -91,4 +138,7 @@ pub struct SafetySpec {
     pub stability: Stability,
     /// Prefer connection-oriented sequenced protocols.
     pub sequencing: Sequencing,
+    /// How to score hops if a new safety route has to be allocated.
+    #[serde(default)]
+    pub route_quality: RouteQuality,
 }
@@ -45,3 +45,42 @@ pub struct PeerStats {
     pub latency: Option<LatencyStats>, // latencies for communications with the peer
     pub transfer: TransferStatsDownUp, // Stats for communications with the peer
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct BucketStats {
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub kind: CryptoKind, // the crypto kind of the node ids in this bucket
+    pub index: u32,      // the bucket index, zero is furthest from our node id
+    pub reliable: u32,   // number of entries in the bucket that are reliable
+    pub unreliable: u32, // number of entries in the bucket that are unreliable
+    pub dead: u32,       // number of entries in the bucket that are dead
+    pub punished: u32,   // number of entries in the bucket that are punished
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct RecordStoreStats {
+    pub record_count: u32,                  // number of records in the record index
+    pub subkey_cache_count: u32,            // number of subkeys in the subkey cache
+    pub subkey_cache_total_size: ByteCount, // total size of the subkeys in the subkey cache
+    pub total_storage_space: ByteCount,     // total storage space used by records
+    pub dead_record_count: u32,             // number of records waiting to be purged
+}
+
+/// A snapshot of the statistics kept by this node, for monitoring
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidStats {
+    /// Statistics for all communications of this node
+    pub transfer: TransferStatsDownUp,
+    /// Statistics for the most recently accessed peers
+    pub peers: Vec<PeerTableData>,
+    /// Occupancy of each routing table bucket
+    pub buckets: Vec<BucketStats>,
+    /// Usage of the record store for records we own
+    pub local_record_store: Option<RecordStoreStats>,
+    /// Usage of the record store for records we are storing for others
+    pub remote_record_store: Option<RecordStoreStats>,
+}
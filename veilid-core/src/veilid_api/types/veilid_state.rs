@@ -152,6 +152,45 @@ pub struct VeilidValueChange {
     pub value: Option<ValueData>,
 }
 
+/// Describe a change to the private routes of a stable inbox
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidInboxChange {
+    /// The DHT Record key of the stable inbox
+    #[schemars(with = "String")]
+    pub key: TypedKey,
+    /// Every private route that currently delivers to the stable inbox.
+    /// Replaced routes stay listed for a short while after their replacement is published.
+    #[schemars(with = "Vec<String>")]
+    pub route_ids: Vec<RouteId>,
+}
+
+/// The progress made syncing DHT Record subkeys that were written offline
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum ValueSyncState {
+    /// The subkeys were written locally and are queued to be written to the network
+    Pending,
+    /// The queued subkey writes have reached the network
+    Flushed,
+    /// The network had newer values for the subkeys than the queued writes,
+    /// and the local values have been replaced by the newer values
+    Conflict,
+}
+
+/// Describe progress syncing DHT Record subkeys that were written offline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidValueSync {
+    /// The DHT Record key being synced
+    #[schemars(with = "String")]
+    pub key: TypedKey,
+    /// What happened to the subkeys
+    pub state: ValueSyncState,
+    /// The portion of the DHT Record's subkeys this applies to
+    pub subkeys: ValueSubkeyRangeSet,
+}
+
 /// An update from the veilid-core to the host application describing a change
 /// to the internal state of the Veilid node.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -166,6 +205,8 @@ pub enum VeilidUpdate {
     Config(Box<VeilidStateConfig>),
     RouteChange(Box<VeilidRouteChange>),
     ValueChange(Box<VeilidValueChange>),
+    InboxChange(Box<VeilidInboxChange>),
+    ValueSync(Box<VeilidValueSync>),
     Shutdown,
 }
 from_impl_to_jsvalue!(VeilidUpdate);
@@ -258,9 +258,46 @@ impl Default for VeilidConfigWSS {
     }
 }
 
+/// Enable and configure WebRTC data channels.
+///
+/// WebRTC connections have no dial info and are only established by signaling
+/// through a relay, so there is no 'listen' setting. Only available on WASM targets.
+///
+/// ```yaml
+/// wrtc:
+///     connect: true
+///     max_connections: 32
+///     ice_servers: ['stun:stun.example.com:3478']
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+
+pub struct VeilidConfigWRTC {
+    pub connect: bool,
+    pub max_connections: u32,
+    pub ice_servers: Vec<String>,
+}
+
+impl Default for VeilidConfigWRTC {
+    fn default() -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                let connect = true;
+            } else {
+                let connect = false;
+            }
+        }
+        Self {
+            connect,
+            max_connections: 32,
+            ice_servers: vec![],
+        }
+    }
+}
+
 /// Configure Network Protocols.
 ///
-/// Veilid can communicate over UDP, TCP, and Web Sockets.
+/// Veilid can communicate over UDP, TCP, Web Sockets, and WebRTC.
 ///
 /// All protocols are available by default, and the Veilid node will
 /// sort out which protocol is used for each peer connection.
@@ -273,6 +310,8 @@ pub struct VeilidConfigProtocol {
     pub tcp: VeilidConfigTCP,
     pub ws: VeilidConfigWS,
     pub wss: VeilidConfigWSS,
+    #[serde(default)]
+    pub wrtc: VeilidConfigWRTC,
 }
 
 /// Configure TLS.
@@ -326,6 +365,50 @@ pub fn get_default_ssl_directory(sub_path: &str) -> String {
     }
 }
 
+/// Configure this node as a DHT pinning service.
+///
+/// A pinning service commits storage to records whose owners have asked for them to be pinned.
+/// Pinned records are kept until their pin expires rather than being evicted as other remote
+/// records are, and are periodically republished to the nodes closest to their key so they
+/// survive churn. At most `max_records` records are pinned at once, no more than
+/// `max_records_per_owner` of them for any one owner, and no pin lasts longer than `max_expiration_ms`.
+///
+/// Only the owners listed in `allowed_owners` may pin records, unless `allow_any_owner` is set.
+///
+/// ```yaml
+/// pinning:
+///     enabled: false
+///     max_records: 32
+///     max_records_per_owner: 4
+///     max_expiration_ms: 86400000
+///     allow_any_owner: false
+///     allowed_owners: []
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidConfigDHTPinning {
+    pub enabled: bool,
+    pub max_records: u32,
+    pub max_records_per_owner: u32,
+    pub max_expiration_ms: u32,
+    pub allow_any_owner: bool,
+    /// Typed owner keys, such as 'VLD0:<key>', whose records may be pinned
+    pub allowed_owners: Vec<String>,
+}
+
+impl Default for VeilidConfigDHTPinning {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_records: 32,
+            max_records_per_owner: 4,
+            max_expiration_ms: 86_400_000,
+            allow_any_owner: false,
+            allowed_owners: Vec::new(),
+        }
+    }
+}
+
 /// Configure the Distributed Hash Table (DHT).
 /// Defaults should be used here unless you are absolutely sure you know what you're doing.
 /// If you change the count/fanout/timeout parameters, you may render your node inoperable
@@ -355,6 +438,8 @@ pub struct VeilidConfigDHT {
     pub public_watch_limit: u32,
     pub member_watch_limit: u32,
     pub max_watch_expiration_ms: u32,
+    #[serde(default)]
+    pub pinning: VeilidConfigDHTPinning,
 }
 
 impl Default for VeilidConfigDHT {
@@ -408,6 +493,7 @@ impl Default for VeilidConfigDHT {
             public_watch_limit: 32,
             member_watch_limit: 8,
             max_watch_expiration_ms: 600000,
+            pinning: VeilidConfigDHTPinning::default(),
         }
     }
 }
@@ -426,6 +512,10 @@ pub struct VeilidConfigRPC {
     pub timeout_ms: u32,
     pub max_route_hop_count: u8,
     pub default_route_hop_count: u8,
+    /// How to choose the hops of routes the node allocates for itself, and of private routes
+    /// allocated without asking for a specific route quality.
+    #[serde(default)]
+    pub default_route_quality: RouteQuality,
 }
 
 impl Default for VeilidConfigRPC {
@@ -438,10 +528,80 @@ impl Default for VeilidConfigRPC {
             timeout_ms: 5000,
             max_route_hop_count: 4,
             default_route_hop_count: 1,
+            default_route_quality: RouteQuality::default(),
         }
     }
 }
 
+/// Configure which peers the routing table keeps when a bucket is full.
+///
+/// Each bucket holds up to `max_bucket_depth` peers, halving for each bucket closer
+/// to our own node id, but never less than `min_bucket_depth`. When a bucket overflows,
+/// peers are scored by their age, reliability and reputation, and the lowest scoring
+/// peers are removed first. Reliable peers older than `long_lived_age_ms` can be protected
+/// from removal entirely, as long-lived peers are the main defense against churn and flooding.
+///
+/// ```yaml
+/// bucket_retention:
+///     max_bucket_depth: 256
+///     min_bucket_depth: 1
+///     keep_closest_reliable: 16
+///     keep_closest_unreliable: 8
+///     age_weight: 1
+///     reliability_weight: 1
+///     reputation_weight: 1
+///     long_lived_age_ms: 3600000
+///     protect_long_lived: true
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidConfigBucketRetention {
+    pub max_bucket_depth: u32,
+    pub min_bucket_depth: u32,
+    pub keep_closest_reliable: u32,
+    pub keep_closest_unreliable: u32,
+    pub age_weight: u32,
+    pub reliability_weight: u32,
+    pub reputation_weight: u32,
+    pub long_lived_age_ms: u32,
+    pub protect_long_lived: bool,
+}
+
+impl Default for VeilidConfigBucketRetention {
+    fn default() -> Self {
+        Self {
+            max_bucket_depth: 256,
+            min_bucket_depth: 1,
+            keep_closest_reliable: 16,
+            keep_closest_unreliable: 8,
+            age_weight: 1,
+            reliability_weight: 1,
+            reputation_weight: 1,
+            long_lived_age_ms: 3_600_000,
+            protect_long_lived: true,
+        }
+    }
+}
+
+/// Configure the private overlay routing domain.
+///
+/// Nodes with addresses in one of the `networks` (for example a WireGuard or Tailscale
+/// overlay, or an isolated lab network) peer with each other in their own routing domain.
+/// Overlay addresses are never published to the PublicInternet routing domain, and the
+/// overlay bootstraps only from its own `bootstrap` list. Leave `networks` empty to disable.
+///
+/// ```yaml
+/// private_overlay:
+///     networks: ['100.64.0.0/10', 'fd7a:115c:a1e0::/48']
+///     bootstrap: ['udp://100.64.0.1:5150']
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidConfigPrivateOverlay {
+    pub networks: Vec<String>,
+    pub bootstrap: Vec<String>,
+}
+
 /// Configure the network routing table.
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -457,6 +617,14 @@ pub struct VeilidConfigRoutingTable {
     pub limit_attached_strong: u32,
     pub limit_attached_good: u32,
     pub limit_attached_weak: u32,
+    #[serde(default)]
+    pub bucket_retention: VeilidConfigBucketRetention,
+    #[serde(default)]
+    pub private_overlay: VeilidConfigPrivateOverlay,
+    /// Autonomous system numbers of address ranges, as '<network>/<prefix length> <asn>' lines
+    /// such as '192.0.2.0/24 64496'. Used to keep the hops of a route in distinct autonomous systems.
+    #[serde(default)]
+    pub asn_table: Vec<String>,
     // xxx pub enable_public_internet: bool,
     // xxx pub enable_local_network: bool,
 }
@@ -480,6 +648,9 @@ impl Default for VeilidConfigRoutingTable {
             limit_attached_strong: 16,
             limit_attached_good: 8,
             limit_attached_weak: 4,
+            bucket_retention: VeilidConfigBucketRetention::default(),
+            private_overlay: VeilidConfigPrivateOverlay::default(),
+            asn_table: Vec::new(),
         }
     }
 }
@@ -620,6 +791,10 @@ impl Default for VeilidConfigProtectedStore {
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 pub struct VeilidConfigCapabilities {
     pub disable: Vec<FourCC>,
+    /// Application-defined capabilities to advertise in this node's NodeInfo.
+    /// These must not collide with the capabilities Veilid defines itself.
+    #[serde(default)]
+    pub advertise: Vec<FourCC>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
@@ -818,6 +993,7 @@ impl VeilidConfig {
             get_config!(inner.program_name);
             get_config!(inner.namespace);
             get_config!(inner.capabilities.disable);
+            get_config!(inner.capabilities.advertise);
             get_config!(inner.table_store.directory);
             get_config!(inner.table_store.delete);
             get_config!(inner.block_store.directory);
@@ -846,6 +1022,66 @@ impl VeilidConfig {
             get_config!(inner.network.routing_table.limit_attached_strong);
             get_config!(inner.network.routing_table.limit_attached_good);
             get_config!(inner.network.routing_table.limit_attached_weak);
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .max_bucket_depth
+            );
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .min_bucket_depth
+            );
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .keep_closest_reliable
+            );
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .keep_closest_unreliable
+            );
+            get_config!(inner.network.routing_table.bucket_retention.age_weight);
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .reliability_weight
+            );
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .reputation_weight
+            );
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .long_lived_age_ms
+            );
+            get_config!(
+                inner
+                    .network
+                    .routing_table
+                    .bucket_retention
+                    .protect_long_lived
+            );
+            get_config!(inner.network.routing_table.private_overlay.networks);
+            get_config!(inner.network.routing_table.private_overlay.bootstrap);
+            get_config!(inner.network.routing_table.asn_table);
             get_config!(inner.network.dht.max_find_node_count);
             get_config!(inner.network.dht.resolve_node_timeout_ms);
             get_config!(inner.network.dht.resolve_node_count);
@@ -868,6 +1104,12 @@ impl VeilidConfig {
             get_config!(inner.network.dht.public_watch_limit);
             get_config!(inner.network.dht.member_watch_limit);
             get_config!(inner.network.dht.max_watch_expiration_ms);
+            get_config!(inner.network.dht.pinning.enabled);
+            get_config!(inner.network.dht.pinning.max_records);
+            get_config!(inner.network.dht.pinning.max_records_per_owner);
+            get_config!(inner.network.dht.pinning.max_expiration_ms);
+            get_config!(inner.network.dht.pinning.allow_any_owner);
+            get_config!(inner.network.dht.pinning.allowed_owners);
             get_config!(inner.network.rpc.concurrency);
             get_config!(inner.network.rpc.queue_size);
             get_config!(inner.network.rpc.max_timestamp_behind_ms);
@@ -875,6 +1117,7 @@ impl VeilidConfig {
             get_config!(inner.network.rpc.timeout_ms);
             get_config!(inner.network.rpc.max_route_hop_count);
             get_config!(inner.network.rpc.default_route_hop_count);
+            get_config!(inner.network.rpc.default_route_quality);
             get_config!(inner.network.upnp);
             get_config!(inner.network.detect_address_changes);
             get_config!(inner.network.restricted_nat_retries);
@@ -910,6 +1153,9 @@ impl VeilidConfig {
             get_config!(inner.network.protocol.wss.listen_address);
             get_config!(inner.network.protocol.wss.path);
             get_config!(inner.network.protocol.wss.url);
+            get_config!(inner.network.protocol.wrtc.connect);
+            get_config!(inner.network.protocol.wrtc.max_connections);
+            get_config!(inner.network.protocol.wrtc.ice_servers);
             Ok(())
         })
     }
@@ -1084,9 +1330,37 @@ impl VeilidConfig {
         Ok(())
     }
 
+    fn validate_capabilities(capabilities: &VeilidConfigCapabilities) -> VeilidAPIResult<()> {
+        for (n, cap) in capabilities.advertise.iter().enumerate() {
+            if routing_table::RESERVED_CAPABILITIES.contains(cap) {
+                apibail_generic!(format!(
+                    "capability '{}' is reserved and can not be advertised in config key 'capabilities.advertise'",
+                    cap
+                ));
+            }
+            if capabilities.advertise[..n].contains(cap) {
+                apibail_generic!(format!(
+                    "capability '{}' is listed more than once in config key 'capabilities.advertise'",
+                    cap
+                ));
+            }
+        }
+        if network_manager::PUBLIC_INTERNET_CAPABILITIES.len() + capabilities.advertise.len()
+            > network_manager::MAX_CAPABILITIES
+        {
+            apibail_generic!(format!(
+                "too many capabilities in config key 'capabilities.advertise', at most {} may be advertised",
+                network_manager::MAX_CAPABILITIES
+                    - network_manager::PUBLIC_INTERNET_CAPABILITIES.len()
+            ));
+        }
+        Ok(())
+    }
+
     fn validate(inner: &VeilidConfigInner) -> VeilidAPIResult<()> {
         Self::validate_program_name(&inner.program_name)?;
         Self::validate_namespace(&inner.namespace)?;
+        Self::validate_capabilities(&inner.capabilities)?;
 
         // if inner.network.protocol.udp.enabled {
         //     // Validate UDP settings
@@ -1142,6 +1416,15 @@ impl VeilidConfig {
                 apibail_generic!("WSS path conflicts with HTTP application path in config key 'network.protocol.ws.path'");
             }
         }
+        if inner.network.protocol.wrtc.connect {
+            // Validate WRTC settings
+            if cfg!(not(target_arch = "wasm32")) {
+                apibail_generic!("WRTC is only supported on WASM targets in config key 'network.protocol.wrtc.connect'");
+            }
+            if inner.network.protocol.wrtc.max_connections == 0 {
+                apibail_generic!("WRTC max connections must be > 0 in config key 'network.protocol.wrtc.max_connections'");
+            }
+        }
         if inner.network.application.https.enabled {
             // Validate HTTPS settings
             if inner
@@ -1184,6 +1467,87 @@ impl VeilidConfig {
         if inner.network.rpc.timeout_ms < 1000 {
             apibail_generic!("rpc timeout must be >= 1000 in 'network.rpc.timeout_ms'");
         }
+        if inner
+            .network
+            .routing_table
+            .bucket_retention
+            .min_bucket_depth
+            == 0
+        {
+            apibail_generic!(
+                "min bucket depth must be >= 1 in 'network.routing_table.bucket_retention.min_bucket_depth'"
+            );
+        }
+        if inner
+            .network
+            .routing_table
+            .bucket_retention
+            .min_bucket_depth
+            > inner
+                .network
+                .routing_table
+                .bucket_retention
+                .max_bucket_depth
+        {
+            apibail_generic!(
+                "min bucket depth must be <= max bucket depth in 'network.routing_table.bucket_retention.min_bucket_depth <= network.routing_table.bucket_retention.max_bucket_depth'"
+            );
+        }
+        if inner
+            .network
+            .routing_table
+            .bucket_retention
+            .long_lived_age_ms
+            == 0
+        {
+            apibail_generic!(
+                "long lived age must be > 0 in 'network.routing_table.bucket_retention.long_lived_age_ms'"
+            );
+        }
+        for network in &inner.network.routing_table.private_overlay.networks {
+            if ipaddr_parse_cidr(network).is_none() {
+                apibail_generic!(
+                    "invalid network in 'network.routing_table.private_overlay.networks'"
+                );
+            }
+        }
+        for entry in &inner.network.routing_table.asn_table {
+            if routing_table::parse_asn_table_entry(entry).is_none() {
+                apibail_generic!("invalid entry in 'network.routing_table.asn_table'");
+            }
+        }
+        if inner.network.dht.pinning.enabled {
+            if inner.network.dht.pinning.max_records >= inner.network.dht.remote_max_records {
+                apibail_generic!(
+                    "max pinned records must be < remote max records in 'network.dht.pinning.max_records < network.dht.remote_max_records'"
+                );
+            }
+            if inner.network.dht.pinning.max_records_per_owner == 0
+                || inner.network.dht.pinning.max_records_per_owner
+                    > inner.network.dht.pinning.max_records
+            {
+                apibail_generic!(
+                    "max pinned records per owner must be > 0 and <= max pinned records in 'network.dht.pinning.max_records_per_owner'"
+                );
+            }
+            if inner.network.dht.pinning.max_expiration_ms == 0 {
+                apibail_generic!(
+                    "max pin expiration must be > 0 in 'network.dht.pinning.max_expiration_ms'"
+                );
+            }
+            if !inner.network.dht.pinning.allow_any_owner
+                && inner.network.dht.pinning.allowed_owners.is_empty()
+            {
+                apibail_generic!(
+                    "pinning needs allowed owners in 'network.dht.pinning.allowed_owners' or 'network.dht.pinning.allow_any_owner'"
+                );
+            }
+        }
+        for owner in &inner.network.dht.pinning.allowed_owners {
+            if TypedKey::from_str(owner).is_err() {
+                apibail_generic!("invalid owner in 'network.dht.pinning.allowed_owners'");
+            }
+        }
 
         Ok(())
     }
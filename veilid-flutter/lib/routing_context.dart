@@ -171,6 +171,72 @@ enum Sequencing {
   String toJson() => name.toPascalCase();
 }
 
+//////////////////////////////////////
+/// RouteQuality
+
+/// Tunables for choosing the hops of a safety route when a new one has to be
+/// allocated
+@immutable
+class RouteQuality extends Equatable {
+  const RouteQuality({
+    this.latencyWeight = 0,
+    this.failureWeight = 0,
+    this.maxHopLatency,
+    this.diverseIpBlocks = false,
+    this.diverseAsns = false,
+  });
+
+  factory RouteQuality.fromJson(dynamic jsond) {
+    final json = jsond as Map<String, dynamic>;
+    // Zero means no limit, which is represented as null here
+    final maxHopLatency = json['max_hop_latency'] == null
+        ? null
+        : TimestampDuration.fromJson(json['max_hop_latency']);
+    final hasMaxHopLatency =
+        maxHopLatency != null && maxHopLatency.value != BigInt.zero;
+    return RouteQuality(
+        latencyWeight: (json['latency_weight'] as num?)?.toInt() ?? 0,
+        failureWeight: (json['failure_weight'] as num?)?.toInt() ?? 0,
+        maxHopLatency: hasMaxHopLatency ? maxHopLatency : null,
+        diverseIpBlocks: json['diverse_ip_blocks'] as bool? ?? false,
+        diverseAsns: json['diverse_asns'] as bool? ?? false);
+  }
+
+  /// Score added for each millisecond of a hop's average measured latency
+  final int latencyWeight;
+
+  /// Score added for each recent route test failure a hop was part of
+  final int failureWeight;
+
+  /// Hops with a higher average measured latency than this are not used
+  final TimestampDuration? maxHopLatency;
+
+  /// Only use hops on distinct IP blocks from each other
+  final bool diverseIpBlocks;
+
+  /// Only use hops in distinct autonomous systems from each other
+  final bool diverseAsns;
+
+  @override
+  List<Object?> get props => [
+        latencyWeight,
+        failureWeight,
+        maxHopLatency,
+        diverseIpBlocks,
+        diverseAsns
+      ];
+  @override
+  bool? get stringify => null;
+
+  Map<String, dynamic> toJson() => {
+        'latency_weight': latencyWeight,
+        'failure_weight': failureWeight,
+        'max_hop_latency': maxHopLatency?.toJson() ?? '0',
+        'diverse_ip_blocks': diverseIpBlocks,
+        'diverse_asns': diverseAsns,
+      };
+}
+
 //////////////////////////////////////
 /// SafetySelection
 
@@ -230,6 +296,7 @@ class SafetySpec with _$SafetySpec {
     required Stability stability,
     required Sequencing sequencing,
     String? preferredRoute,
+    @Default(RouteQuality()) RouteQuality routeQuality,
   }) = _SafetySpec;
 
   factory SafetySpec.fromJson(dynamic json) =>
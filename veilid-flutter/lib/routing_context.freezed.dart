@@ -1002,6 +1002,7 @@ mixin _$SafetySpec {
   Stability get stability => throw _privateConstructorUsedError;
   Sequencing get sequencing => throw _privateConstructorUsedError;
   String? get preferredRoute => throw _privateConstructorUsedError;
+  RouteQuality get routeQuality => throw _privateConstructorUsedError;
 
   Map<String, dynamic> toJson() => throw _privateConstructorUsedError;
   @JsonKey(ignore: true)
@@ -1019,7 +1020,8 @@ abstract class $SafetySpecCopyWith<$Res> {
       {int hopCount,
       Stability stability,
       Sequencing sequencing,
-      String? preferredRoute});
+      String? preferredRoute,
+      RouteQuality routeQuality});
 }
 
 /// @nodoc
@@ -1039,6 +1041,7 @@ class _$SafetySpecCopyWithImpl<$Res, $Val extends SafetySpec>
     Object? stability = null,
     Object? sequencing = null,
     Object? preferredRoute = freezed,
+    Object? routeQuality = null,
   }) {
     return _then(_value.copyWith(
       hopCount: null == hopCount
@@ -1057,6 +1060,10 @@ class _$SafetySpecCopyWithImpl<$Res, $Val extends SafetySpec>
           ? _value.preferredRoute
           : preferredRoute // ignore: cast_nullable_to_non_nullable
               as String?,
+      routeQuality: null == routeQuality
+          ? _value.routeQuality
+          : routeQuality // ignore: cast_nullable_to_non_nullable
+              as RouteQuality,
     ) as $Val);
   }
 }
@@ -1073,7 +1080,8 @@ abstract class _$$SafetySpecImplCopyWith<$Res>
       {int hopCount,
       Stability stability,
       Sequencing sequencing,
-      String? preferredRoute});
+      String? preferredRoute,
+      RouteQuality routeQuality});
 }
 
 /// @nodoc
@@ -1091,6 +1099,7 @@ class __$$SafetySpecImplCopyWithImpl<$Res>
     Object? stability = null,
     Object? sequencing = null,
     Object? preferredRoute = freezed,
+    Object? routeQuality = null,
   }) {
     return _then(_$SafetySpecImpl(
       hopCount: null == hopCount
@@ -1109,6 +1118,10 @@ class __$$SafetySpecImplCopyWithImpl<$Res>
           ? _value.preferredRoute
           : preferredRoute // ignore: cast_nullable_to_non_nullable
               as String?,
+      routeQuality: null == routeQuality
+          ? _value.routeQuality
+          : routeQuality // ignore: cast_nullable_to_non_nullable
+              as RouteQuality,
     ));
   }
 }
@@ -1120,7 +1133,8 @@ class _$SafetySpecImpl implements _SafetySpec {
       {required this.hopCount,
       required this.stability,
       required this.sequencing,
-      this.preferredRoute});
+      this.preferredRoute,
+      this.routeQuality = const RouteQuality()});
 
   factory _$SafetySpecImpl.fromJson(Map<String, dynamic> json) =>
       _$$SafetySpecImplFromJson(json);
@@ -1133,10 +1147,13 @@ class _$SafetySpecImpl implements _SafetySpec {
   final Sequencing sequencing;
   @override
   final String? preferredRoute;
+  @override
+  @JsonKey()
+  final RouteQuality routeQuality;
 
   @override
   String toString() {
-    return 'SafetySpec(hopCount: $hopCount, stability: $stability, sequencing: $sequencing, preferredRoute: $preferredRoute)';
+    return 'SafetySpec(hopCount: $hopCount, stability: $stability, sequencing: $sequencing, preferredRoute: $preferredRoute, routeQuality: $routeQuality)';
   }
 
   @override
@@ -1151,13 +1168,15 @@ class _$SafetySpecImpl implements _SafetySpec {
             (identical(other.sequencing, sequencing) ||
                 other.sequencing == sequencing) &&
             (identical(other.preferredRoute, preferredRoute) ||
-                other.preferredRoute == preferredRoute));
+                other.preferredRoute == preferredRoute) &&
+            (identical(other.routeQuality, routeQuality) ||
+                other.routeQuality == routeQuality));
   }
 
   @JsonKey(ignore: true)
   @override
-  int get hashCode =>
-      Object.hash(runtimeType, hopCount, stability, sequencing, preferredRoute);
+  int get hashCode => Object.hash(runtimeType, hopCount, stability,
+      sequencing, preferredRoute, routeQuality);
 
   @JsonKey(ignore: true)
   @override
@@ -1178,7 +1197,8 @@ abstract class _SafetySpec implements SafetySpec {
       {required final int hopCount,
       required final Stability stability,
       required final Sequencing sequencing,
-      final String? preferredRoute}) = _$SafetySpecImpl;
+      final String? preferredRoute,
+      final RouteQuality routeQuality}) = _$SafetySpecImpl;
 
   factory _SafetySpec.fromJson(Map<String, dynamic> json) =
       _$SafetySpecImpl.fromJson;
@@ -1192,6 +1212,8 @@ abstract class _SafetySpec implements SafetySpec {
   @override
   String? get preferredRoute;
   @override
+  RouteQuality get routeQuality;
+  @override
   @JsonKey(ignore: true)
   _$$SafetySpecImplCopyWith<_$SafetySpecImpl> get copyWith =>
       throw _privateConstructorUsedError;
@@ -88,6 +88,9 @@ _$SafetySpecImpl _$$SafetySpecImplFromJson(Map<String, dynamic> json) =>
       stability: Stability.fromJson(json['stability']),
       sequencing: Sequencing.fromJson(json['sequencing']),
       preferredRoute: json['preferred_route'] as String?,
+      routeQuality: json['route_quality'] == null
+          ? const RouteQuality()
+          : RouteQuality.fromJson(json['route_quality']),
     );
 
 Map<String, dynamic> _$$SafetySpecImplToJson(_$SafetySpecImpl instance) =>
@@ -96,6 +99,7 @@ Map<String, dynamic> _$$SafetySpecImplToJson(_$SafetySpecImpl instance) =>
       'stability': instance.stability.toJson(),
       'sequencing': instance.sequencing.toJson(),
       'preferred_route': instance.preferredRoute,
+      'route_quality': instance.routeQuality.toJson(),
     };
 
 _$RouteBlobImpl _$$RouteBlobImplFromJson(Map<String, dynamic> json) =>
@@ -200,4 +200,5 @@ abstract class Veilid {
   VeilidVersion veilidVersion();
   String defaultVeilidConfig();
   Future<String> debug(String command);
+  Future<Map<String, dynamic>> debugStructured(String command);
 }
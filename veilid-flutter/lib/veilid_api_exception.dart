@@ -40,6 +40,11 @@ abstract class VeilidAPIException implements Exception {
         {
           return VeilidAPIExceptionKeyNotFound(json['key']! as String);
         }
+      case 'SeqMismatch':
+        {
+          return VeilidAPIExceptionSeqMismatch(
+              json['expected']! as int, json['actual']! as int);
+        }
       case 'Internal':
         {
           return VeilidAPIExceptionInternal(json['message']! as String);
@@ -164,6 +169,21 @@ class VeilidAPIExceptionKeyNotFound implements VeilidAPIException {
   String toDisplayError() => 'Key not found: $key';
 }
 
+@immutable
+class VeilidAPIExceptionSeqMismatch implements VeilidAPIException {
+  //
+  const VeilidAPIExceptionSeqMismatch(this.expected, this.actual);
+  final int expected;
+  final int actual;
+  @override
+  String toString() =>
+      'VeilidAPIException: SeqMismatch (expected: $expected, actual: $actual)';
+
+  @override
+  String toDisplayError() =>
+      'Seq mismatch: expected $expected, actual $actual';
+}
+
 @immutable
 class VeilidAPIExceptionInternal implements VeilidAPIException {
   //
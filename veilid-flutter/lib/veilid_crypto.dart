@@ -14,6 +14,8 @@ import 'veilid.dart';
 typedef CryptoKind = int;
 const CryptoKind cryptoKindVLD0 =
     $V << 24 | $L << 16 | $D << 8 | $0 << 0; // "VLD0"
+const CryptoKind cryptoKindVLD1 =
+    $V << 24 | $L << 16 | $D << 8 | $1 << 0; // "VLD1"
 const CryptoKind cryptoKindNONE =
     $N << 24 | $O << 16 | $N << 8 | $E << 0; // "NONE"
 
@@ -122,6 +122,10 @@ typedef _DeleteTableDbDart = void Function(int, Pointer<Utf8>);
 typedef _TableDbGetColumnCountDart = int Function(int);
 // fn table_db_get_keys(port: i64, id: u32, col: u32)
 typedef _TableDbGetKeysDart = Pointer<Utf8> Function(int, int, int);
+// fn table_db_scan_keys(port: i64, id: u32, col: u32, scan: FfiStr)
+typedef _TableDbScanKeysDart = void Function(int, int, int, Pointer<Utf8>);
+// fn table_db_scan(port: i64, id: u32, col: u32, scan: FfiStr)
+typedef _TableDbScanDart = void Function(int, int, int, Pointer<Utf8>);
 // fn table_db_store(port: i64, id: u32, col: u32, key: FfiStr, value: FfiStr)
 typedef _TableDbStoreDart = void Function(
     int, int, int, Pointer<Utf8>, Pointer<Utf8>);
@@ -228,6 +232,8 @@ typedef _CryptoCryptNoAuthDart = void Function(
 typedef _NowDart = int Function();
 // fn debug(port: i64, log_level: FfiStr)
 typedef _DebugDart = void Function(int, Pointer<Utf8>);
+// fn debug_structured(port: i64, command: FfiStr)
+typedef _DebugStructuredDart = void Function(int, Pointer<Utf8>);
 // fn shutdown_veilid_core(port: i64)
 typedef _ShutdownVeilidCoreDart = void Function(int);
 // fn veilid_version_string() -> *mut c_char
@@ -903,6 +909,38 @@ class VeilidTableDBFFI extends VeilidTableDB {
         recvPort.first);
   }
 
+  @override
+  Future<List<Uint8List>> scanKeys(int col,
+      [VeilidTableDBScan scan = const VeilidTableDBScan()]) async {
+    _tdb.ensureValid();
+    final nativeScan = jsonEncode(scan).toNativeUtf8();
+
+    final recvPort = ReceivePort('veilid_table_db_scan_keys');
+    final sendPort = recvPort.sendPort;
+
+    _tdb.ffi
+        ._tableDbScanKeys(sendPort.nativePort, _tdb.id!, col, nativeScan);
+
+    return processFutureJson(
+        jsonListConstructor<Uint8List>(base64UrlNoPadDecodeDynamic),
+        recvPort.first);
+  }
+
+  @override
+  Future<List<VeilidTableDBEntry>> scan(int col,
+      [VeilidTableDBScan scan = const VeilidTableDBScan()]) async {
+    _tdb.ensureValid();
+    final nativeScan = jsonEncode(scan).toNativeUtf8();
+
+    final recvPort = ReceivePort('veilid_table_db_scan');
+    final sendPort = recvPort.sendPort;
+
+    _tdb.ffi._tableDbScan(sendPort.nativePort, _tdb.id!, col, nativeScan);
+
+    return processFutureJson(
+        jsonListConstructor(VeilidTableDBEntry.fromJson), recvPort.first);
+  }
+
   @override
   VeilidTableDBTransaction transact() {
     _tdb.ensureValid();
@@ -1349,6 +1387,12 @@ class VeilidFFI extends Veilid {
         _tableDbGetKeys = dylib.lookupFunction<
             Pointer<Utf8> Function(Uint64, Uint32, Uint32),
             _TableDbGetKeysDart>('table_db_get_keys'),
+        _tableDbScanKeys = dylib.lookupFunction<
+            Void Function(Int64, Uint32, Uint32, Pointer<Utf8>),
+            _TableDbScanKeysDart>('table_db_scan_keys'),
+        _tableDbScan = dylib.lookupFunction<
+            Void Function(Int64, Uint32, Uint32, Pointer<Utf8>),
+            _TableDbScanDart>('table_db_scan'),
         _tableDbStore = dylib.lookupFunction<
             Void Function(Int64, Uint32, Uint32, Pointer<Utf8>, Pointer<Utf8>),
             _TableDbStoreDart>('table_db_store'),
@@ -1459,6 +1503,9 @@ class VeilidFFI extends Veilid {
         _now = dylib.lookupFunction<Uint64 Function(), _NowDart>('now'),
         _debug = dylib.lookupFunction<Void Function(Int64, Pointer<Utf8>),
             _DebugDart>('debug'),
+        _debugStructured = dylib.lookupFunction<
+            Void Function(Int64, Pointer<Utf8>),
+            _DebugStructuredDart>('debug_structured'),
         _veilidVersionString = dylib.lookupFunction<Pointer<Utf8> Function(),
             _VeilidVersionStringDart>('veilid_version_string'),
         _veilidVersion = dylib.lookupFunction<VeilidVersionFFI Function(),
@@ -1520,6 +1567,8 @@ class VeilidFFI extends Veilid {
   final _DeleteTableDbDart _deleteTableDb;
   final _TableDbGetColumnCountDart _tableDbGetColumnCount;
   final _TableDbGetKeysDart _tableDbGetKeys;
+  final _TableDbScanKeysDart _tableDbScanKeys;
+  final _TableDbScanDart _tableDbScan;
   final _TableDbStoreDart _tableDbStore;
   final _TableDbLoadDart _tableDbLoad;
   final _TableDbDeleteDart _tableDbDelete;
@@ -1563,6 +1612,7 @@ class VeilidFFI extends Veilid {
 
   final _NowDart _now;
   final _DebugDart _debug;
+  final _DebugStructuredDart _debugStructured;
   final _VeilidVersionStringDart _veilidVersionString;
   final _VeilidVersionDart _veilidVersion;
   final _DefaultVeilidConfigDart _defaultVeilidConfig;
@@ -1607,7 +1657,9 @@ class VeilidFFI extends Veilid {
         sendPort.nativePort, sendStreamPort.nativePort, nativeConfig);
     malloc.free(nativeConfig);
     return processFutureStream(
-        processStreamJson(VeilidUpdate.fromJson, recvStreamPort),
+        processStreamJson(VeilidUpdate.tryFromJson, recvStreamPort)
+            .where((update) => update != null)
+            .cast<VeilidUpdate>(),
         recvPort.first);
   }
 
@@ -1793,6 +1845,16 @@ class VeilidFFI extends Veilid {
     return processFuturePlain(recvPort.first);
   }
 
+  @override
+  Future<Map<String, dynamic>> debugStructured(String command) async {
+    final nativeCommand = command.toNativeUtf8();
+    final recvPort = ReceivePort('debug_structured');
+    final sendPort = recvPort.sendPort;
+    _debugStructured(sendPort.nativePort, nativeCommand);
+    return processFutureJson(
+        (dynamic j) => j as Map<String, dynamic>, recvPort.first);
+  }
+
   @override
   String veilidVersionString() {
     final versionString = _veilidVersionString();
@@ -530,6 +530,24 @@ class VeilidTableDBJS extends VeilidTableDB {
         await js_util.callMethod(wasm, 'table_db_get_keys', [id, col])));
   }
 
+  @override
+  Future<List<Uint8List>> scanKeys(int col,
+      [VeilidTableDBScan scan = const VeilidTableDBScan()]) async {
+    final id = _tdb.requireId();
+    return jsonListConstructor(base64UrlNoPadDecodeDynamic)(jsonDecode(
+        await _wrapApiPromise<String>(js_util.callMethod(
+            wasm, 'table_db_scan_keys', [id, col, jsonEncode(scan)]))));
+  }
+
+  @override
+  Future<List<VeilidTableDBEntry>> scan(int col,
+      [VeilidTableDBScan scan = const VeilidTableDBScan()]) async {
+    final id = _tdb.requireId();
+    return jsonListConstructor(VeilidTableDBEntry.fromJson)(jsonDecode(
+        await _wrapApiPromise<String>(js_util
+            .callMethod(wasm, 'table_db_scan', [id, col, jsonEncode(scan)]))));
+  }
+
   @override
   VeilidTableDBTransaction transact() {
     final id = _tdb.requireId();
@@ -607,8 +625,10 @@ class VeilidJS extends Veilid {
       if (updateJson['kind'] == 'Shutdown') {
         unawaited(streamController.close());
       } else {
-        final update = VeilidUpdate.fromJson(updateJson);
-        streamController.add(update);
+        final update = VeilidUpdate.tryFromJson(updateJson);
+        if (update != null) {
+          streamController.add(update);
+        }
       }
     }
 
@@ -735,6 +755,12 @@ class VeilidJS extends Veilid {
   Future<String> debug(String command) async =>
       _wrapApiPromise(js_util.callMethod(wasm, 'debug', [command]));
 
+  @override
+  Future<Map<String, dynamic>> debugStructured(String command) async =>
+      jsonDecode(await _wrapApiPromise<String>(
+              js_util.callMethod(wasm, 'debug_structured', [command])))
+          as Map<String, dynamic>;
+
   @override
   String veilidVersionString() =>
       js_util.callMethod(wasm, 'veilid_version_string', []);
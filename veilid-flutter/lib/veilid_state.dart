@@ -179,6 +179,19 @@ sealed class VeilidUpdate with _$VeilidUpdate {
 
   factory VeilidUpdate.fromJson(dynamic json) =>
       _$VeilidUpdateFromJson(json as Map<String, dynamic>);
+
+  /// Decode an update, or return null if it is of a kind these bindings
+  /// don't know about, so newer veilid-core updates don't end the stream
+  static VeilidUpdate? tryFromJson(dynamic json) {
+    try {
+      return VeilidUpdate.fromJson(json);
+    } on CheckedFromJsonException catch (e) {
+      if (e.className == 'VeilidUpdate' && e.key == 'kind') {
+        return null;
+      }
+      rethrow;
+    }
+  }
 }
 
 //////////////////////////////////////
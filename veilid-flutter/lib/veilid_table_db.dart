@@ -2,6 +2,64 @@ import 'dart:async';
 import 'dart:convert';
 import 'dart:typed_data';
 
+import 'veilid_encoding.dart';
+
+/////////////////////////////////////
+/// VeilidTableDBScan
+
+/// Selects an ordered page of the keys in a TableDB column
+class VeilidTableDBScan {
+  const VeilidTableDBScan(
+      {this.prefix,
+      this.start,
+      this.end,
+      this.after,
+      this.reverse = false,
+      this.limit = 0});
+
+  /// Only include keys that start with this prefix
+  final Uint8List? prefix;
+
+  /// Only include keys greater than or equal to this key
+  final Uint8List? start;
+
+  /// Only include keys less than this key
+  final Uint8List? end;
+
+  /// Resume after this key, usually the last key of the previous page
+  final Uint8List? after;
+
+  /// Return keys in descending order instead of ascending order
+  final bool reverse;
+
+  /// The maximum number of keys to return, or zero for no limit
+  final int limit;
+
+  Map<String, dynamic> toJson() => {
+        'prefix': _optEncode(prefix),
+        'start': _optEncode(start),
+        'end': _optEncode(end),
+        'after': _optEncode(after),
+        'reverse': reverse,
+        'limit': limit,
+      };
+
+  static String? _optEncode(Uint8List? b) =>
+      b == null ? null : base64UrlNoPadEncode(b);
+}
+
+/// A key and its value, as returned by a TableDB scan
+class VeilidTableDBEntry {
+  const VeilidTableDBEntry({required this.key, required this.value});
+
+  factory VeilidTableDBEntry.fromJson(dynamic json) => VeilidTableDBEntry(
+      key: base64UrlNoPadDecode(json['key'] as String),
+      value: base64UrlNoPadDecode(json['value'] as String));
+
+  final Uint8List key;
+  final Uint8List value;
+}
+
 /////////////////////////////////////
 /// VeilidTableDB
 abstract class VeilidTableDBTransaction {
@@ -26,6 +84,10 @@ abstract class VeilidTableDB {
   void close();
   int getColumnCount();
   Future<List<Uint8List>> getKeys(int col);
+  Future<List<Uint8List>> scanKeys(int col,
+      [VeilidTableDBScan scan = const VeilidTableDBScan()]);
+  Future<List<VeilidTableDBEntry>> scan(int col,
+      [VeilidTableDBScan scan = const VeilidTableDBScan()]);
   VeilidTableDBTransaction transact();
   Future<void> store(int col, Uint8List key, Uint8List value);
   Future<Uint8List?> load(int col, Uint8List key);
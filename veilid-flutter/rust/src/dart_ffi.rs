@@ -895,7 +895,12 @@ pub extern "C" fn new_custom_private_route(port: i64, stability: FfiStr, sequenc
             let veilid_api = get_veilid_api().await?;
 
             let (route_id, blob) = veilid_api
-                .new_custom_private_route(&veilid_core::VALID_CRYPTO_KINDS, stability, sequencing)
+                .new_custom_private_route(
+                    &veilid_core::VALID_CRYPTO_KINDS,
+                    stability,
+                    sequencing,
+                    veilid_core::RouteQuality::default(),
+                )
                 .await?;
 
             let route_blob = VeilidFFIRouteBlob { route_id, blob };
@@ -1063,6 +1068,42 @@ pub extern "C" fn table_db_get_keys(port: i64, id: u32, col: u32) {
     );
 }
 
+#[no_mangle]
+#[instrument(level = "trace", target = "ffi", skip_all)]
+pub extern "C" fn table_db_scan_keys(port: i64, id: u32, col: u32, scan: FfiStr) {
+    let scan: veilid_core::TableDBScan =
+        veilid_core::deserialize_opt_json(scan.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(
+        async move {
+            let table_db = get_table_db(id, "table_db_scan_keys")?;
+
+            let keys = table_db.scan_keys(col, scan).await?;
+            let out: Vec<String> = keys
+                .into_iter()
+                .map(|k| BASE64URL_NOPAD.encode(&k))
+                .collect();
+            APIResult::Ok(out)
+        }
+        .in_current_span(),
+    );
+}
+
+#[no_mangle]
+#[instrument(level = "trace", target = "ffi", skip_all)]
+pub extern "C" fn table_db_scan(port: i64, id: u32, col: u32, scan: FfiStr) {
+    let scan: veilid_core::TableDBScan =
+        veilid_core::deserialize_opt_json(scan.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(
+        async move {
+            let table_db = get_table_db(id, "table_db_scan")?;
+
+            let out = table_db.scan(col, scan).await?;
+            APIResult::Ok(out)
+        }
+        .in_current_span(),
+    );
+}
+
 fn add_table_db_transaction(tdbt: veilid_core::TableDBTransaction) -> u32 {
     let mut next_id: u32 = 1;
     let mut tdbts = TABLE_DB_TRANSACTIONS.lock();
@@ -1986,6 +2027,20 @@ pub extern "C" fn debug(port: i64, command: FfiStr) {
     );
 }
 
+#[no_mangle]
+#[instrument(level = "trace", target = "ffi", skip_all)]
+pub extern "C" fn debug_structured(port: i64, command: FfiStr) {
+    let command = command.into_opt_string().unwrap_or_default();
+    DartIsolateWrapper::new(port).spawn_result_json(
+        async move {
+            let veilid_api = get_veilid_api().await?;
+            let out = veilid_api.debug_structured(command).await?;
+            APIResult::Ok(out)
+        }
+        .in_current_span(),
+    );
+}
+
 #[no_mangle]
 #[instrument(level = "trace", target = "ffi", skip_all)]
 pub extern "C" fn veilid_version_string() -> *mut c_char {
@@ -0,0 +1,38 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:veilid/veilid.dart';
+
+Future<void> testUpdateFromJson() async {
+  final update = VeilidUpdate.tryFromJson({
+    'kind': 'RouteChange',
+    'dead_routes': ['route'],
+    'dead_remote_routes': <String>[],
+  });
+  expect(update, isA<VeilidUpdateRouteChange>());
+  expect((update! as VeilidUpdateRouteChange).deadRoutes, ['route']);
+}
+
+Future<void> testUpdateFromJsonUnknownKind() async {
+  // Offline DHT write progress and stable inbox route changes are reported
+  // with updates the bindings don't decode yet
+  expect(
+      VeilidUpdate.tryFromJson({
+        'kind': 'ValueSync',
+        'key': 'VLD0:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA',
+        'state': 'Flushed',
+        'subkeys': [
+          [0, 1]
+        ],
+      }),
+      isNull);
+  expect(
+      VeilidUpdate.tryFromJson({
+        'kind': 'InboxChange',
+        'key': 'VLD0:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA',
+        'route_ids': ['AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA'],
+      }),
+      isNull);
+
+  // Malformed updates of a known kind are still an error
+  expect(() => VeilidUpdate.tryFromJson({'kind': 'RouteChange'}),
+      throwsA(anything));
+}
@@ -1,6 +1,7 @@
 import 'package:flutter_test/flutter_test.dart';
 import 'test_encoding.dart';
 import 'test_value_subkey_range.dart';
+import 'test_veilid_state.dart';
 
 void main() {
   TestWidgetsFlutterBinding.ensureInitialized();
@@ -18,4 +19,9 @@ void main() {
     test('test ValueSubkeyRange', testValueSubkeyRange);
     test('test List<ValueSubkeyRange>', testValueSubkeyRangeList);
   });
+
+  group('VeilidUpdate', () {
+    test('test VeilidUpdate from json', testUpdateFromJson);
+    test('test VeilidUpdate of unknown kind', testUpdateFromJsonUnknownKind);
+  });
 }
@@ -94,6 +94,60 @@ async def test_set_get_dht_value(api_connection: veilid.VeilidAPI):
         await rc.delete_dht_record(rec.key)
 
 
+@pytest.mark.asyncio
+async def test_compare_and_set_dht_value(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.dflt(1))
+
+        # Expect the subkey to be unset
+        vd = await rc.set_dht_value(rec.key, ValueSubkey(0), b"FIRST", expected_seq=veilid.ValueSeqNum(0xFFFFFFFF))
+        assert vd is None
+
+        # Subkey is no longer unset
+        with pytest.raises(veilid.VeilidAPIErrorSeqMismatch) as exc:
+            await rc.set_dht_value(rec.key, ValueSubkey(0), b"SECOND", expected_seq=veilid.ValueSeqNum(0xFFFFFFFF))
+        assert exc.value.expected == 0xFFFFFFFF
+        assert exc.value.actual == 0
+
+        vd2 = await rc.set_dht_value(rec.key, ValueSubkey(0), b"SECOND", expected_seq=veilid.ValueSeqNum(0))
+        assert vd2 is None
+
+        vd3 = await rc.get_dht_value(rec.key, ValueSubkey(0), False)
+        assert vd3 is not None
+        assert vd3.data == b"SECOND"
+        assert vd3.seq == 1
+
+        await rc.close_dht_record(rec.key)
+        await rc.delete_dht_record(rec.key)
+
+
+@pytest.mark.asyncio
+async def test_set_get_dht_values(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.dflt(4))
+
+        vds = await rc.set_dht_values(
+            rec.key, [(ValueSubkey(0), b"ZERO"), (ValueSubkey(2), b"TWO"), (ValueSubkey(3), b"THREE")]
+        )
+        assert vds == [None, None, None]
+
+        vds2 = await rc.get_dht_values(rec.key, [(ValueSubkey(0), ValueSubkey(3))], False)
+        assert len(vds2) == 4
+        assert vds2[0] is not None and vds2[0].data == b"ZERO"
+        assert vds2[1] is None
+        assert vds2[2] is not None and vds2[2].data == b"TWO"
+        assert vds2[3] is not None and vds2[3].data == b"THREE"
+
+        # Values must be in ascending subkey order
+        with pytest.raises(veilid.VeilidAPIErrorInvalidArgument):
+            await rc.set_dht_values(rec.key, [(ValueSubkey(1), b"ONE"), (ValueSubkey(0), b"ZERO")])
+
+        await rc.close_dht_record(rec.key)
+        await rc.delete_dht_record(rec.key)
+
+
 @pytest.mark.asyncio
 async def test_open_writer_dht_value(api_connection: veilid.VeilidAPI):
     rc = await api_connection.new_routing_context()
@@ -345,6 +399,70 @@ async def test_inspect_dht_record(api_connection: veilid.VeilidAPI):
         await rc.delete_dht_record(rec.key)
 
 
+@pytest.mark.asyncio
+async def test_alog_dht_record_append_only(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.alog(1, []))
+        cs = await api_connection.get_crypto_system(rec.key.kind())
+        async with cs:
+            def make_entry(prev_hash: bytes, payload: bytes) -> bytes:
+                return prev_hash + len(payload).to_bytes(2, "little") + payload
+
+            entry1 = make_entry(bytes(32), b"first")
+            assert await rc.set_dht_value(rec.key, ValueSubkey(0), entry1) is None
+
+            # Appending a chained entry is allowed
+            entry2 = make_entry((await cs.generate_hash(entry1)).to_bytes(), b"second")
+            assert await rc.set_dht_value(rec.key, ValueSubkey(0), entry1 + entry2) is None
+
+            # Rewriting history is not
+            with pytest.raises(veilid.VeilidAPIError):
+                await rc.set_dht_value(rec.key, ValueSubkey(0), make_entry(bytes(32), b"rewritten"))
+
+            # Neither is appending an entry that is not chained to the last one
+            with pytest.raises(veilid.VeilidAPIError):
+                await rc.set_dht_value(
+                    rec.key, ValueSubkey(0), entry1 + entry2 + make_entry(bytes(32), b"third")
+                )
+
+        await rc.close_dht_record(rec.key)
+        await rc.delete_dht_record(rec.key)
+
+
+@pytest.mark.asyncio
+async def test_set_get_dht_stream(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.dflt(8))
+
+        assert await rc.get_dht_stream(rec.key, ValueSubkey(0), False) is None
+
+        data = os.urandom(100000)
+        header = await rc.set_dht_stream(rec.key, ValueSubkey(0), data)
+        assert header.length == len(data)
+        assert len(header.chunk_hashes) == 4
+
+        # Writing the same stream again is a no-op
+        header2 = await rc.set_dht_stream(rec.key, ValueSubkey(0), data)
+        assert header2.chunk_hashes == header.chunk_hashes
+
+        assert await rc.get_dht_stream(rec.key, ValueSubkey(0), False) == data
+
+        # Read a range that spans a chunk boundary
+        header3 = await rc.get_dht_stream_header(rec.key, ValueSubkey(0), False)
+        assert header3 is not None
+        part = await rc.read_dht_stream(rec.key, ValueSubkey(0), header3, 30000, 10000, False)
+        assert part == data[30000:40000]
+
+        # Streams that do not fit in the record are rejected
+        with pytest.raises(veilid.VeilidAPIError):
+            await rc.set_dht_stream(rec.key, ValueSubkey(4), data)
+
+        await rc.close_dht_record(rec.key)
+        await rc.delete_dht_record(rec.key)
+
+
 @pytest.mark.skipif(os.getenv("INTEGRATION") != "1", reason="integration test requires two servers running")
 @pytest.mark.asyncio
 async def test_dht_integration_writer_reader():
@@ -125,3 +125,30 @@ async def test_resize_table_db(api_connection: veilid.VeilidAPI):
     # now delete should succeed
     deleted = await api_connection.delete_table_db(TEST_DB)
     assert deleted
+
+
+@pytest.mark.asyncio
+async def test_scan_table_db(api_connection: veilid.VeilidAPI):
+    # delete test db if it exists
+    await api_connection.delete_table_db(TEST_DB)
+
+    tdb = await api_connection.open_table_db(TEST_DB, 1)
+    async with tdb:
+        for key in [b"b2", b"a1", b"b1", b"c1", b"b3"]:
+            await tdb.store(key, key + b"-value")
+
+        assert await tdb.scan_keys() == [b"a1", b"b1", b"b2", b"b3", b"c1"]
+        assert await tdb.scan_keys(prefix=b"b", reverse=True) == [b"b3", b"b2", b"b1"]
+        assert await tdb.scan_keys(start=b"b1", end=b"b3") == [b"b1", b"b2"]
+
+        # page through the keys two at a time
+        page = await tdb.scan_keys(limit=2)
+        assert page == [b"a1", b"b1"]
+        page = await tdb.scan_keys(after=page[-1], limit=2)
+        assert page == [b"b2", b"b3"]
+
+        assert await tdb.scan(prefix=b"c") == [(b"c1", b"c1-value")]
+
+    # now delete should succeed
+    deleted = await api_connection.delete_table_db(TEST_DB)
+    assert deleted
@@ -75,10 +75,33 @@ class RoutingContext(ABC):
 
     @abstractmethod
     async def set_dht_value(
-        self, key: types.TypedKey, subkey: types.ValueSubkey, data: bytes, writer: Optional[types.KeyPair] = None
+        self,
+        key: types.TypedKey,
+        subkey: types.ValueSubkey,
+        data: bytes,
+        writer: Optional[types.KeyPair] = None,
+        expected_seq: Optional[types.ValueSeqNum] = None,
     ) -> Optional[types.ValueData]:
         pass
 
+    @abstractmethod
+    async def get_dht_values(
+        self,
+        key: types.TypedKey,
+        subkeys: list[tuple[types.ValueSubkey, types.ValueSubkey]],
+        force_refresh: bool = False,
+    ) -> list[Optional[types.ValueData]]:
+        pass
+
+    @abstractmethod
+    async def set_dht_values(
+        self,
+        key: types.TypedKey,
+        values: list[tuple[types.ValueSubkey, bytes]],
+        writer: Optional[types.KeyPair] = None,
+    ) -> list[Optional[types.ValueData]]:
+        pass
+
     @abstractmethod
     async def watch_dht_values(
         self,
@@ -106,6 +129,60 @@ class RoutingContext(ABC):
     ) -> types.DHTRecordReport:
         pass
 
+    @abstractmethod
+    async def pin_dht_record(
+        self, key: types.TypedKey, expiration: types.Timestamp = 0
+    ) -> types.Timestamp:
+        pass
+
+    @abstractmethod
+    async def get_dht_record_sync_status(
+        self, key: types.TypedKey
+    ) -> types.DHTRecordSyncStatus:
+        pass
+
+    @abstractmethod
+    async def clear_dht_record_conflicts(
+        self,
+        key: types.TypedKey,
+        subkeys: list[tuple[types.ValueSubkey, types.ValueSubkey]] = [],
+    ):
+        pass
+
+    @abstractmethod
+    async def set_dht_stream(
+        self,
+        key: types.TypedKey,
+        header_subkey: types.ValueSubkey,
+        data: bytes,
+        writer: Optional[types.KeyPair] = None,
+    ) -> types.DHTStreamHeader:
+        pass
+
+    @abstractmethod
+    async def get_dht_stream_header(
+        self, key: types.TypedKey, header_subkey: types.ValueSubkey, force_refresh: bool = False
+    ) -> Optional[types.DHTStreamHeader]:
+        pass
+
+    @abstractmethod
+    async def read_dht_stream(
+        self,
+        key: types.TypedKey,
+        header_subkey: types.ValueSubkey,
+        header: types.DHTStreamHeader,
+        offset: int,
+        length: int,
+        force_refresh: bool = False,
+    ) -> bytes:
+        pass
+
+    @abstractmethod
+    async def get_dht_stream(
+        self, key: types.TypedKey, header_subkey: types.ValueSubkey, force_refresh: bool = False
+    ) -> Optional[bytes]:
+        pass
+
 
 
 class TableDbTransaction(ABC):
@@ -161,6 +238,32 @@ class TableDb(ABC):
     async def get_keys(self, col: int = 0) -> list[bytes]:
         pass
 
+    @abstractmethod
+    async def scan_keys(
+        self,
+        col: int = 0,
+        prefix: Optional[bytes] = None,
+        start: Optional[bytes] = None,
+        end: Optional[bytes] = None,
+        after: Optional[bytes] = None,
+        reverse: bool = False,
+        limit: int = 0,
+    ) -> list[bytes]:
+        pass
+
+    @abstractmethod
+    async def scan(
+        self,
+        col: int = 0,
+        prefix: Optional[bytes] = None,
+        start: Optional[bytes] = None,
+        end: Optional[bytes] = None,
+        after: Optional[bytes] = None,
+        reverse: bool = False,
+        limit: int = 0,
+    ) -> list[tuple[bytes, bytes]]:
+        pass
+
     @abstractmethod
     async def transact(self) -> TableDbTransaction:
         pass
@@ -325,6 +428,10 @@ class VeilidAPI(ABC):
     async def control(self, args: list[str]) -> str:
         pass
 
+    @abstractmethod
+    async def authenticate(self, token: str):
+        pass
+
     @abstractmethod
     async def get_state(self) -> VeilidState:
         pass
@@ -347,6 +454,7 @@ class VeilidAPI(ABC):
         kinds: list[types.CryptoKind],
         stability: types.Stability,
         sequencing: types.Sequencing,
+        route_quality: types.RouteQuality = types.RouteQuality(),
     ) -> tuple[types.RouteId, bytes]:
         pass
 
@@ -362,6 +470,14 @@ class VeilidAPI(ABC):
     async def app_call_reply(self, call_id: types.OperationId, message: bytes):
         pass
 
+    @abstractmethod
+    async def app_call_reply_chunk(self, call_id: types.OperationId, message: bytes):
+        pass
+
+    @abstractmethod
+    async def find_peers_with_capability(self, capability: str, count: int) -> list[types.TypedKey]:
+        pass
+
     @abstractmethod
     async def new_routing_context(self) -> RoutingContext:
         pass
@@ -409,6 +525,10 @@ class VeilidAPI(ABC):
     async def debug(self, command: str) -> str:
         pass
 
+    @abstractmethod
+    async def debug_structured(self, command: str) -> dict:
+        pass
+
     @abstractmethod
     async def veilid_version_string(self) -> str:
         pass
@@ -2,7 +2,7 @@ from dataclasses import dataclass, fields
 from enum import StrEnum
 from typing import Optional, Self
 
-from .types import TypedKey, TypedSecret, Capability
+from .types import TypedKey, TypedSecret, Capability, RouteQuality
 
 
 class VeilidConfigLogLevel(StrEnum):
@@ -42,6 +42,7 @@ class ConfigBase:
 @dataclass
 class VeilidConfigCapabilities(ConfigBase):
     disable: list[Capability]
+    advertise: list[Capability]
 
 
 @dataclass
@@ -66,6 +67,25 @@ class VeilidConfigBlockStore(ConfigBase):
     delete: bool
 
 
+@dataclass
+class VeilidConfigBucketRetention(ConfigBase):
+    max_bucket_depth: int
+    min_bucket_depth: int
+    keep_closest_reliable: int
+    keep_closest_unreliable: int
+    age_weight: int
+    reliability_weight: int
+    reputation_weight: int
+    long_lived_age_ms: int
+    protect_long_lived: bool
+
+
+@dataclass
+class VeilidConfigPrivateOverlay(ConfigBase):
+    networks: list[str]
+    bootstrap: list[str]
+
+
 @dataclass
 class VeilidConfigRoutingTable(ConfigBase):
     node_id: list[TypedKey]
@@ -76,6 +96,9 @@ class VeilidConfigRoutingTable(ConfigBase):
     limit_attached_strong: int
     limit_attached_good: int
     limit_attached_weak: int
+    bucket_retention: VeilidConfigBucketRetention
+    private_overlay: VeilidConfigPrivateOverlay
+    asn_table: list[str]
 
 
 @dataclass
@@ -87,6 +110,17 @@ class VeilidConfigRPC(ConfigBase):
     timeout_ms: int
     max_route_hop_count: int
     default_route_hop_count: int
+    default_route_quality: RouteQuality
+
+
+@dataclass
+class VeilidConfigDHTPinning(ConfigBase):
+    enabled: bool
+    max_records: int
+    max_records_per_owner: int
+    max_expiration_ms: int
+    allow_any_owner: bool
+    allowed_owners: list[str]
 
 
 @dataclass
@@ -113,6 +147,7 @@ class VeilidConfigDHT(ConfigBase):
     public_watch_limit: int
     member_watch_limit: int
     max_watch_expiration_ms: int
+    pinning: VeilidConfigDHTPinning
 
 @dataclass
 class VeilidConfigTLS(ConfigBase):
@@ -180,12 +215,20 @@ class VeilidConfigWSS(ConfigBase):
     url: Optional[str]
 
 
+@dataclass
+class VeilidConfigWRTC(ConfigBase):
+    connect: bool
+    max_connections: int
+    ice_servers: list[str]
+
+
 @dataclass
 class VeilidConfigProtocol(ConfigBase):
     udp: VeilidConfigUDP
     tcp: VeilidConfigTCP
     ws: VeilidConfigWS
     wss: VeilidConfigWSS
+    wrtc: VeilidConfigWRTC
 
 
 @dataclass
@@ -5,6 +5,7 @@ import sys
 import re
 from collections.abc import Callable
 from functools import cache
+from typing import Optional
 
 import veilid
 from veilid.json_api import _JsonVeilidAPI
@@ -65,6 +66,11 @@ def ipc_info(subindex: int = 0) -> str:
     return ipc_path
 
 
+def server_token() -> Optional[str]:
+    """Return the client api token to authenticate with, if the server requires one."""
+    return os.getenv("VEILID_SERVER_TOKEN")
+
+
 async def api_connector(callback: Callable, subindex: int = 0) -> _JsonVeilidAPI:
     """Return an API connection if possible.
 
@@ -76,10 +82,10 @@ async def api_connector(callback: Callable, subindex: int = 0) -> _JsonVeilidAPI
 
     try:
         if ipc_path_exists(ipc_path):
-            return await veilid.json_api_connect_ipc(ipc_path, callback)
+            api = await veilid.json_api_connect_ipc(ipc_path, callback)
         else:
             hostname, port = server_info(subindex)
-            return await veilid.json_api_connect(hostname, port, callback)
+            api = await veilid.json_api_connect(hostname, port, callback)
     except OSError as exc:
         # This is a little goofy. The underlying Python library handles
         # connection errors in 2 ways, depending on how many connections
@@ -108,3 +114,12 @@ async def api_connector(callback: Callable, subindex: int = 0) -> _JsonVeilidAPI
             raise VeilidConnectionError
 
         raise
+
+    token = server_token()
+    if token is not None:
+        try:
+            await api.authenticate(token)
+        except:
+            await api.release()
+            raise
+    return api
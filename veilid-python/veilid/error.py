@@ -89,6 +89,15 @@ class VeilidAPIErrorKeyNotFound(VeilidAPIError):
     key: str
 
 
+@dataclass
+class VeilidAPIErrorSeqMismatch(VeilidAPIError):
+    """Value sequence number did not match the expected sequence number"""
+
+    label = "Seq mismatch"
+    expected: int
+    actual: int
+
+
 @dataclass
 class VeilidAPIErrorInternal(VeilidAPIError):
     """Veilid experienced an internal failure"""
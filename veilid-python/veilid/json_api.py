@@ -23,8 +23,10 @@ from .types import (
     CryptoKind,
     DHTRecordDescriptor,
     DHTRecordReport,
+    DHTRecordSyncStatus,
     DHTReportScope,
     DHTSchema,
+    DHTStreamHeader,
     HashDigest,
     KeyPair,
     NewPrivateRouteResult,
@@ -32,6 +34,7 @@ from .types import (
     OperationId,
     PublicKey,
     RouteId,
+    RouteQuality,
     SafetySelection,
     SecretKey,
     Sequencing,
@@ -43,6 +46,7 @@ from .types import (
     TypedKeyPair,
     TypedSignature,
     ValueData,
+    ValueSeqNum,
     ValueSubkey,
     VeilidJSONEncoder,
     VeilidVersion,
@@ -307,6 +311,9 @@ class _JsonVeilidAPI(VeilidAPI):
     async def control(self, args: list[str]) -> str:
         return raise_api_result(await self.send_ndjson_request(Operation.CONTROL, args=args))
 
+    async def authenticate(self, token: str):
+        await self.control(["Authenticate", token])
+
     async def get_state(self) -> VeilidState:
         return VeilidState.from_json(
             raise_api_result(await self.send_ndjson_request(Operation.GET_STATE))
@@ -324,7 +331,11 @@ class _JsonVeilidAPI(VeilidAPI):
         ).to_tuple()
 
     async def new_custom_private_route(
-        self, kinds: list[CryptoKind], stability: Stability, sequencing: Sequencing
+        self,
+        kinds: list[CryptoKind],
+        stability: Stability,
+        sequencing: Sequencing,
+        route_quality: RouteQuality = RouteQuality(),
     ) -> tuple[RouteId, bytes]:
         return NewPrivateRouteResult.from_json(
             raise_api_result(
@@ -333,6 +344,7 @@ class _JsonVeilidAPI(VeilidAPI):
                     kinds=kinds,
                     stability=stability,
                     sequencing=sequencing,
+                    route_quality=route_quality,
                 )
             )
         ).to_tuple()
@@ -356,6 +368,25 @@ class _JsonVeilidAPI(VeilidAPI):
             )
         )
 
+    async def app_call_reply_chunk(self, call_id: OperationId, message: bytes):
+        raise_api_result(
+            await self.send_ndjson_request(
+                Operation.APP_CALL_REPLY_CHUNK, call_id=call_id, message=message
+            )
+        )
+
+    async def find_peers_with_capability(self, capability: str, count: int) -> list[TypedKey]:
+        return list(
+            map(
+                lambda x: TypedKey(x),
+                raise_api_result(
+                    await self.send_ndjson_request(
+                        Operation.FIND_PEERS_WITH_CAPABILITY, capability=capability, count=count
+                    )
+                ),
+            )
+        )
+
     async def new_routing_context(self) -> RoutingContext:
         rc_id = raise_api_result(await self.send_ndjson_request(Operation.NEW_ROUTING_CONTEXT))
         return _JsonRoutingContext(self, rc_id)
@@ -433,6 +464,11 @@ class _JsonVeilidAPI(VeilidAPI):
     async def debug(self, command: str) -> str:
         return raise_api_result(await self.send_ndjson_request(Operation.DEBUG, command=command))
 
+    async def debug_structured(self, command: str) -> dict:
+        return raise_api_result(
+            await self.send_ndjson_request(Operation.DEBUG_STRUCTURED, command=command)
+        )
+
     async def veilid_version_string(self) -> str:
         return raise_api_result(await self.send_ndjson_request(Operation.VEILID_VERSION_STRING))
 
@@ -637,7 +673,12 @@ class _JsonRoutingContext(RoutingContext):
         return None if ret is None else ValueData.from_json(ret)
 
     async def set_dht_value(
-        self, key: TypedKey, subkey: ValueSubkey, data: bytes, writer: Optional[KeyPair] = None
+        self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        data: bytes,
+        writer: Optional[KeyPair] = None,
+        expected_seq: Optional[ValueSeqNum] = None,
     ) -> Optional[ValueData]:
         ret = raise_api_result(
             await self.api.send_ndjson_request(
@@ -649,10 +690,49 @@ class _JsonRoutingContext(RoutingContext):
                 subkey=subkey,
                 data=data,
                 writer=writer,
+                expected_seq=expected_seq,
             )
         )
         return None if ret is None else ValueData.from_json(ret)
 
+    async def get_dht_values(
+        self,
+        key: TypedKey,
+        subkeys: list[tuple[ValueSubkey, ValueSubkey]],
+        force_refresh: bool = False,
+    ) -> list[Optional[ValueData]]:
+        ret = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.GET_DHT_VALUES,
+                key=key,
+                subkeys=subkeys,
+                force_refresh=force_refresh,
+            )
+        )
+        return [None if v is None else ValueData.from_json(v) for v in ret]
+
+    async def set_dht_values(
+        self,
+        key: TypedKey,
+        values: list[tuple[ValueSubkey, bytes]],
+        writer: Optional[KeyPair] = None,
+    ) -> list[Optional[ValueData]]:
+        ret = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.SET_DHT_VALUES,
+                key=key,
+                values=values,
+                writer=writer,
+            )
+        )
+        return [None if v is None else ValueData.from_json(v) for v in ret]
+
     async def watch_dht_values(
         self,
         key: TypedKey,
@@ -708,6 +788,131 @@ class _JsonRoutingContext(RoutingContext):
                 )
             )
         )
+
+    async def pin_dht_record(
+        self, key: TypedKey, expiration: Timestamp = 0
+    ) -> Timestamp:
+        return Timestamp(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.PIN_DHT_RECORD,
+                    key=key,
+                    expiration=str(expiration),
+                )
+            )
+        )
+
+    async def get_dht_record_sync_status(self, key: TypedKey) -> DHTRecordSyncStatus:
+        return DHTRecordSyncStatus.from_json(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.GET_DHT_RECORD_SYNC_STATUS,
+                    key=key,
+                )
+            )
+        )
+
+    async def clear_dht_record_conflicts(
+        self,
+        key: TypedKey,
+        subkeys: list[tuple[ValueSubkey, ValueSubkey]] = [],
+    ):
+        raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.CLEAR_DHT_RECORD_CONFLICTS,
+                key=key,
+                subkeys=subkeys,
+            )
+        )
+
+    async def set_dht_stream(
+        self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        data: bytes,
+        writer: Optional[KeyPair] = None,
+    ) -> DHTStreamHeader:
+        return DHTStreamHeader.from_json(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.SET_DHT_STREAM,
+                    key=key,
+                    header_subkey=header_subkey,
+                    data=data,
+                    writer=writer,
+                )
+            )
+        )
+
+    async def get_dht_stream_header(
+        self, key: TypedKey, header_subkey: ValueSubkey, force_refresh: bool = False
+    ) -> Optional[DHTStreamHeader]:
+        ret = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.GET_DHT_STREAM_HEADER,
+                key=key,
+                header_subkey=header_subkey,
+                force_refresh=force_refresh,
+            )
+        )
+        return None if ret is None else DHTStreamHeader.from_json(ret)
+
+    async def read_dht_stream(
+        self,
+        key: TypedKey,
+        header_subkey: ValueSubkey,
+        header: DHTStreamHeader,
+        offset: int,
+        length: int,
+        force_refresh: bool = False,
+    ) -> bytes:
+        return urlsafe_b64decode_no_pad(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.READ_DHT_STREAM,
+                    key=key,
+                    header_subkey=header_subkey,
+                    header=header,
+                    offset=offset,
+                    length=length,
+                    force_refresh=force_refresh,
+                )
+            )
+        )
+
+    async def get_dht_stream(
+        self, key: TypedKey, header_subkey: ValueSubkey, force_refresh: bool = False
+    ) -> Optional[bytes]:
+        ret = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.GET_DHT_STREAM,
+                key=key,
+                header_subkey=header_subkey,
+                force_refresh=force_refresh,
+            )
+        )
+        return None if ret is None else urlsafe_b64decode_no_pad(ret)
         
 
 
@@ -802,6 +1007,24 @@ def validate_db_op(request: dict, response: dict):
         raise ValueError("Response db_op does not match request db_op")
 
 
+def _table_db_scan(
+    prefix: Optional[bytes],
+    start: Optional[bytes],
+    end: Optional[bytes],
+    after: Optional[bytes],
+    reverse: bool,
+    limit: int,
+) -> dict:
+    return {
+        "prefix": prefix,
+        "start": start,
+        "end": end,
+        "after": after,
+        "reverse": reverse,
+        "limit": limit,
+    }
+
+
 class _JsonTableDb(TableDb):
     api: _JsonVeilidAPI
     db_id: int
@@ -862,6 +1085,61 @@ class _JsonTableDb(TableDb):
             )
         )
 
+    async def scan_keys(
+        self,
+        col: int = 0,
+        prefix: Optional[bytes] = None,
+        start: Optional[bytes] = None,
+        end: Optional[bytes] = None,
+        after: Optional[bytes] = None,
+        reverse: bool = False,
+        limit: int = 0,
+    ) -> list[bytes]:
+        return list(
+            map(
+                lambda x: urlsafe_b64decode_no_pad(x),
+                raise_api_result(
+                    await self.api.send_ndjson_request(
+                        Operation.TABLE_DB,
+                        validate=validate_db_op,
+                        db_id=self.db_id,
+                        db_op=TableDbOperation.SCAN_KEYS,
+                        col=col,
+                        scan=_table_db_scan(prefix, start, end, after, reverse, limit),
+                    )
+                ),
+            )
+        )
+
+    async def scan(
+        self,
+        col: int = 0,
+        prefix: Optional[bytes] = None,
+        start: Optional[bytes] = None,
+        end: Optional[bytes] = None,
+        after: Optional[bytes] = None,
+        reverse: bool = False,
+        limit: int = 0,
+    ) -> list[tuple[bytes, bytes]]:
+        return list(
+            map(
+                lambda x: (
+                    urlsafe_b64decode_no_pad(x["key"]),
+                    urlsafe_b64decode_no_pad(x["value"]),
+                ),
+                raise_api_result(
+                    await self.api.send_ndjson_request(
+                        Operation.TABLE_DB,
+                        validate=validate_db_op,
+                        db_id=self.db_id,
+                        db_op=TableDbOperation.SCAN,
+                        col=col,
+                        scan=_table_db_scan(prefix, start, end, after, reverse, limit),
+                    )
+                ),
+            )
+        )
+
     async def transact(self) -> TableDbTransaction:
         tx_id = raise_api_result(
             await self.api.send_ndjson_request(
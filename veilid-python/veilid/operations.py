@@ -12,6 +12,8 @@ class Operation(StrEnum):
     IMPORT_REMOTE_PRIVATE_ROUTE = "ImportRemotePrivateRoute"
     RELEASE_PRIVATE_ROUTE = "ReleasePrivateRoute"
     APP_CALL_REPLY = "AppCallReply"
+    APP_CALL_REPLY_CHUNK = "AppCallReplyChunk"
+    FIND_PEERS_WITH_CAPABILITY = "FindPeersWithCapability"
     NEW_ROUTING_CONTEXT = "NewRoutingContext"
     ROUTING_CONTEXT = "RoutingContext"
     OPEN_TABLE_DB = "OpenTableDb"
@@ -26,6 +28,7 @@ class Operation(StrEnum):
     GENERATE_KEY_PAIR = "GenerateKeyPair"
     NOW = "Now"
     DEBUG = "Debug"
+    DEBUG_STRUCTURED = "DebugStructured"
     VEILID_VERSION_STRING = "VeilidVersionString"
     VEILID_VERSION = "VeilidVersion"
     DEFAULT_VEILID_CONFIG = "DefaultVeilidConfig"
@@ -46,9 +49,18 @@ class RoutingContextOperation(StrEnum):
     DELETE_DHT_RECORD = "DeleteDhtRecord"
     GET_DHT_VALUE = "GetDhtValue"
     SET_DHT_VALUE = "SetDhtValue"
+    GET_DHT_VALUES = "GetDhtValues"
+    SET_DHT_VALUES = "SetDhtValues"
     WATCH_DHT_VALUES = "WatchDhtValues"
     CANCEL_DHT_WATCH = "CancelDhtWatch"
     INSPECT_DHT_RECORD = "InspectDhtRecord"
+    PIN_DHT_RECORD = "PinDhtRecord"
+    GET_DHT_RECORD_SYNC_STATUS = "GetDhtRecordSyncStatus"
+    CLEAR_DHT_RECORD_CONFLICTS = "ClearDhtRecordConflicts"
+    SET_DHT_STREAM = "SetDhtStream"
+    GET_DHT_STREAM_HEADER = "GetDhtStreamHeader"
+    READ_DHT_STREAM = "ReadDhtStream"
+    GET_DHT_STREAM = "GetDhtStream"
 
 
 class TableDbOperation(StrEnum):
@@ -56,6 +68,8 @@ class TableDbOperation(StrEnum):
     RELEASE = "Release"
     GET_COLUMN_COUNT = "GetColumnCount"
     GET_KEYS = "GetKeys"
+    SCAN_KEYS = "ScanKeys"
+    SCAN = "Scan"
     TRANSACT = "Transact"
     STORE = "Store"
     LOAD = "Load"
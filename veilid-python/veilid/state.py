@@ -319,12 +319,14 @@ class VeilidAppCall:
     route_id: Optional[RouteId]
     message: bytes
     call_id: OperationId
+    streaming: bool
 
-    def __init__(self, sender: Optional[TypedKey], route_id: Optional[TypedKey], message: bytes, call_id: OperationId):
+    def __init__(self, sender: Optional[TypedKey], route_id: Optional[TypedKey], message: bytes, call_id: OperationId, streaming: bool):
         self.sender = sender
         self.route_id = route_id
         self.message = message
         self.call_id = call_id
+        self.streaming = streaming
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -334,6 +336,7 @@ class VeilidAppCall:
             None if j["route_id"] is None else RouteId(j["route_id"]),
             urlsafe_b64decode_no_pad(j["message"]),
             OperationId(j["call_id"]),
+            j.get("streaming", False),
         )
 
 
@@ -354,6 +357,23 @@ class VeilidRouteChange:
         )
 
 
+class VeilidInboxChange:
+    key: TypedKey
+    route_ids: list[RouteId]
+
+    def __init__(self, key: TypedKey, route_ids: list[RouteId]):
+        self.key = key
+        self.route_ids = route_ids
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            TypedKey(j["key"]),
+            [RouteId(route) for route in j["route_ids"]],
+        )
+
+
 class VeilidValueChange:
     key: TypedKey
     subkeys: list[tuple[ValueSubkey, ValueSubkey]]
@@ -377,6 +397,32 @@ class VeilidValueChange:
         )
 
 
+class ValueSyncState(StrEnum):
+    PENDING = "Pending"
+    FLUSHED = "Flushed"
+    CONFLICT = "Conflict"
+
+
+class VeilidValueSync:
+    key: TypedKey
+    state: ValueSyncState
+    subkeys: list[tuple[ValueSubkey, ValueSubkey]]
+
+    def __init__(self, key: TypedKey, state: ValueSyncState, subkeys: list[tuple[ValueSubkey, ValueSubkey]]):
+        self.key = key
+        self.state = state
+        self.subkeys = subkeys
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            TypedKey(j["key"]),
+            ValueSyncState(j["state"]),
+            [(p[0], p[1]) for p in j["subkeys"]],
+        )
+
+
 class VeilidUpdateKind(StrEnum):
     LOG = "Log"
     APP_MESSAGE = "AppMessage"
@@ -386,6 +432,8 @@ class VeilidUpdateKind(StrEnum):
     CONFIG = "Config"
     ROUTE_CHANGE = "RouteChange"
     VALUE_CHANGE = "ValueChange"
+    INBOX_CHANGE = "InboxChange"
+    VALUE_SYNC = "ValueSync"
     SHUTDOWN = "Shutdown"
 
 
@@ -398,6 +446,8 @@ VeilidUpdateDetailType = Optional[
     | VeilidStateConfig
     | VeilidRouteChange
     | VeilidValueChange
+    | VeilidInboxChange
+    | VeilidValueSync
 ]
 
 
@@ -435,6 +485,10 @@ class VeilidUpdate:
                 detail = VeilidRouteChange.from_json(j)
             case VeilidUpdateKind.VALUE_CHANGE:
                 detail = VeilidValueChange.from_json(j)
+            case VeilidUpdateKind.INBOX_CHANGE:
+                detail = VeilidInboxChange.from_json(j)
+            case VeilidUpdateKind.VALUE_SYNC:
+                detail = VeilidValueSync.from_json(j)
             case VeilidUpdateKind.SHUTDOWN:
                 detail = None
             case _:
@@ -50,6 +50,7 @@ class VeilidLogLevel(StrEnum):
 class CryptoKind(StrEnum):
     CRYPTO_KIND_NONE = "NONE"
     CRYPTO_KIND_VLD0 = "VLD0"
+    CRYPTO_KIND_VLD1 = "VLD1"
 
 
 class Capability(StrEnum):
@@ -78,6 +79,7 @@ class Sequencing(StrEnum):
 class DHTSchemaKind(StrEnum):
     DFLT = "DFLT"
     SMPL = "SMPL"
+    ALOG = "ALOG"
 
 
 class SafetySelectionKind(StrEnum):
@@ -327,6 +329,10 @@ class DHTSchema:
     def smpl(cls, o_cnt: int, members: list[DHTSchemaSMPLMember]) -> Self:
         return cls(DHTSchemaKind.SMPL, o_cnt=o_cnt, members=members)
 
+    @classmethod
+    def alog(cls, o_cnt: int, members: list[DHTSchemaSMPLMember]) -> Self:
+        return cls(DHTSchemaKind.ALOG, o_cnt=o_cnt, members=members)
+
     @classmethod
     def from_json(cls, j: dict) -> Self:
         if DHTSchemaKind(j["kind"]) == DHTSchemaKind.DFLT:
@@ -336,6 +342,11 @@ class DHTSchema:
                 j["o_cnt"],
                 [DHTSchemaSMPLMember.from_json(member) for member in j["members"]],
             )
+        if DHTSchemaKind(j["kind"]) == DHTSchemaKind.ALOG:
+            return cls.alog(
+                j["o_cnt"],
+                [DHTSchemaSMPLMember.from_json(member) for member in j["members"]],
+            )
         raise Exception("Unknown DHTSchema kind", j["kind"])
 
     def to_json(self) -> dict:
@@ -414,6 +425,62 @@ class DHTRecordReport:
         return self.__dict__
 
 
+class DHTRecordSyncStatus:
+    pending_subkeys: list[tuple[ValueSubkey, ValueSubkey]]
+    conflict_subkeys: list[tuple[ValueSubkey, ValueSubkey]]
+
+    def __init__(
+        self,
+        pending_subkeys: list[tuple[ValueSubkey, ValueSubkey]],
+        conflict_subkeys: list[tuple[ValueSubkey, ValueSubkey]],
+    ):
+        self.pending_subkeys = pending_subkeys
+        self.conflict_subkeys = conflict_subkeys
+
+    def __repr__(self) -> str:
+        return f"<{self.__class__.__name__}(pending_subkeys={self.pending_subkeys!r}, conflict_subkeys={self.conflict_subkeys!r})>"
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            [[p[0], p[1]] for p in j["pending_subkeys"]],
+            [[p[0], p[1]] for p in j["conflict_subkeys"]],
+        )
+
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
+class DHTStreamHeader:
+    length: int
+    chunk_size: int
+    chunk_hashes: list[HashDigest]
+
+    def __init__(
+        self,
+        length: int,
+        chunk_size: int,
+        chunk_hashes: list[HashDigest],
+    ):
+        self.length = length
+        self.chunk_size = chunk_size
+        self.chunk_hashes = chunk_hashes
+
+    def __repr__(self) -> str:
+        return f"<{self.__class__.__name__}(length={self.length!r}, chunk_size={self.chunk_size!r}, chunk_hashes={self.chunk_hashes!r})>"
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            j["length"],
+            j["chunk_size"],
+            [HashDigest(h) for h in j["chunk_hashes"]],
+        )
+
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
 @total_ordering
 class ValueData:
     seq: ValueSeqNum
@@ -466,11 +533,53 @@ class ValueData:
 ####################################################################
 
 
+class RouteQuality:
+    latency_weight: int
+    failure_weight: int
+    max_hop_latency: TimestampDuration
+    diverse_ip_blocks: bool
+    diverse_asns: bool
+
+    def __init__(
+        self,
+        latency_weight: int = 0,
+        failure_weight: int = 0,
+        max_hop_latency: TimestampDuration = TimestampDuration(0),
+        diverse_ip_blocks: bool = False,
+        diverse_asns: bool = False,
+    ):
+        self.latency_weight = latency_weight
+        self.failure_weight = failure_weight
+        self.max_hop_latency = max_hop_latency
+        self.diverse_ip_blocks = diverse_ip_blocks
+        self.diverse_asns = diverse_asns
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            j.get("latency_weight", 0),
+            j.get("failure_weight", 0),
+            TimestampDuration(j.get("max_hop_latency", 0)),
+            j.get("diverse_ip_blocks", False),
+            j.get("diverse_asns", False),
+        )
+
+    def to_json(self) -> dict:
+        return {
+            "latency_weight": self.latency_weight,
+            "failure_weight": self.failure_weight,
+            "max_hop_latency": str(self.max_hop_latency),
+            "diverse_ip_blocks": self.diverse_ip_blocks,
+            "diverse_asns": self.diverse_asns,
+        }
+
+
 class SafetySpec:
     preferred_route: Optional[RouteId]
     hop_count: int
     stability: Stability
     sequencing: Sequencing
+    route_quality: RouteQuality
 
     def __init__(
         self,
@@ -478,11 +587,13 @@ class SafetySpec:
         hop_count: int,
         stability: Stability,
         sequencing: Sequencing,
+        route_quality: Optional[RouteQuality] = None,
     ):
         self.preferred_route = preferred_route
         self.hop_count = hop_count
         self.stability = stability
         self.sequencing = sequencing
+        self.route_quality = route_quality if route_quality is not None else RouteQuality()
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -491,6 +602,7 @@ class SafetySpec:
             j["hop_count"],
             Stability(j["stability"]),
             Sequencing(j["sequencing"]),
+            RouteQuality.from_json(j["route_quality"]) if "route_quality" in j else None,
         )
 
     def to_json(self) -> dict:
@@ -8,6 +8,7 @@
 compile_error!("async-std compilation for windows is currently unsupported");
 
 mod client_api;
+mod metrics;
 mod server;
 mod settings;
 mod tools;
@@ -24,8 +25,9 @@ use server::*;
 use settings::LogLevel;
 use std::collections::HashMap;
 use std::ffi::OsString;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 use tools::*;
 use veilid_core::{TypedKeyGroup, TypedSecretGroup};
 use veilid_logs::*;
@@ -128,6 +130,14 @@ pub struct CmdlineArgs {
     #[arg(long)]
     dump_txt_record: bool,
 
+    /// Export the node identity, private routes, DHT records and tables to a password protected backup file and then quits
+    #[arg(long, value_name = "FILE")]
+    export_backup: Option<OsString>,
+
+    /// Restore a backup file made with --export-backup into an empty data directory and then quits
+    #[arg(long, value_name = "FILE")]
+    import_backup: Option<OsString>,
+
     /// Emits a JSON-Schema for a named type
     #[arg(long, value_name = "schema_name")]
     emit_schema: Option<String>,
@@ -275,7 +285,7 @@ fn main() -> EyreResult<()> {
     if let Some(network_key) = args.network_key {
         settingsrw.core.network.network_key_password = Some(network_key);
     }
-    if args.dump_txt_record {
+    if args.dump_txt_record || args.export_backup.is_some() || args.import_backup.is_some() {
         // Turn off terminal logging so we can be interactive
         settingsrw.logging.terminal.enabled = false;
     }
@@ -396,6 +406,39 @@ fn main() -> EyreResult<()> {
         return Ok(());
     }
 
+    // --- Import Backup ---
+    if let Some(import_backup) = args.import_backup {
+        let archive = std::fs::read(&import_backup).wrap_err("failed to read backup file")?;
+        let password = rpassword::prompt_password("Enter backup password (will not echo): ")
+            .wrap_err("invalid password")?;
+        return block_on(async {
+            // Init combined console/file logger
+            let veilid_logs = VeilidLogs::setup(settings.clone())?;
+
+            let update_callback = Arc::new(|_: veilid_core::VeilidUpdate| {});
+            let config_callback = settings.get_core_config_callback();
+            let res = veilid_core::api_restore_backup(
+                update_callback,
+                config_callback,
+                archive,
+                password,
+            )
+            .await
+            .wrap_err("failed to restore backup");
+
+            drop(veilid_logs);
+            res
+        })
+        .map(|v| {
+            println!("Backup restored successfully");
+            v
+        })
+        .map_err(|e| {
+            println!("Failed to restore backup");
+            e
+        });
+    }
+
     // See if we're just running a quick command
     let (server_mode, success, failure) = if node_id_set {
         (
@@ -405,6 +448,12 @@ fn main() -> EyreResult<()> {
         )
     } else if args.dump_txt_record {
         (ServerMode::DumpTXTRecord, "", "Failed to dump txt record")
+    } else if let Some(export_backup) = args.export_backup {
+        (
+            ServerMode::ExportBackup(PathBuf::from(export_backup)),
+            "Backup exported successfully",
+            "Failed to export backup",
+        )
     } else {
         (ServerMode::Normal, "", "")
     };
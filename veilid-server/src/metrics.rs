@@ -0,0 +1,550 @@
+use crate::tools::*;
+use cfg_if::*;
+use futures_util::{future::join_all, StreamExt};
+use parking_lot::Mutex;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use stop_token::future::FutureExt as _;
+use stop_token::*;
+use tracing::*;
+use veilid_core::tools::*;
+use veilid_core::*;
+use wg::AsyncWaitGroup;
+
+cfg_if! {
+
+    if #[cfg(feature="rt-async-std")] {
+        use futures_util::{AsyncBufReadExt, AsyncWriteExt};
+    } else
+    if #[cfg(feature="rt-tokio")] {
+        use tokio::io::AsyncBufReadExt;
+        use tokio::io::AsyncWriteExt;
+    } else {
+        compile_error!("needs executor implementation");
+    }
+}
+
+/// How long a scraper has to send its request before the connection is dropped
+const METRICS_REQUEST_TIMEOUT_MS: u32 = 5_000;
+/// The longest request head we will read from a scraper
+const MAX_METRICS_REQUEST_HEAD_LEN: usize = 8192;
+/// The only path metrics are served from
+const METRICS_PATH: &str = "/metrics";
+/// Content type for the OpenMetrics text exposition format
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+// --- Metrics Server ---------------------------------
+
+type MetricsAllFuturesJoinHandle = MustJoinHandle<Vec<()>>;
+
+struct MetricsServerInner {
+    veilid_api: veilid_core::VeilidAPI,
+    stop: Option<StopSource>,
+    join_handle: Option<MetricsAllFuturesJoinHandle>,
+}
+
+/// Serves node statistics over HTTP in the OpenMetrics text format
+#[derive(Clone)]
+pub struct MetricsServer {
+    inner: Arc<Mutex<MetricsServerInner>>,
+}
+
+impl MetricsServer {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(veilid_api: veilid_core::VeilidAPI) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MetricsServerInner {
+                veilid_api,
+                stop: Some(StopSource::new()),
+                join_handle: None,
+            })),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub async fn stop(&self) {
+        trace!(target: "metrics", "MetricsServer::stop requested");
+        let jh = {
+            let mut inner = self.inner.lock();
+            if inner.join_handle.is_none() {
+                trace!(target: "metrics", "MetricsServer stop ignored");
+                return;
+            }
+            drop(inner.stop.take());
+            inner.join_handle.take().unwrap()
+        };
+        trace!(target: "metrics", "MetricsServer::stop: waiting for stop");
+        jh.await;
+        trace!(target: "metrics", "MetricsServer::stop: stopped");
+    }
+
+    async fn handle_tcp_incoming(self, bind_addr: SocketAddr) -> std::io::Result<()> {
+        let listener = TcpListener::bind(bind_addr).await?;
+        debug!(target: "metrics", "Metrics listening on: {:?}", bind_addr);
+
+        // Process the incoming accept stream
+        cfg_if! {
+            if #[cfg(feature="rt-async-std")] {
+                let mut incoming_stream = listener.incoming();
+            } else {
+                let mut incoming_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+            }
+        }
+
+        // Make wait group for all incoming connections
+        let awg = AsyncWaitGroup::new();
+
+        let stop_token = self.inner.lock().stop.as_ref().unwrap().token();
+        while let Ok(Some(stream_result)) =
+            incoming_stream.next().timeout_at(stop_token.clone()).await
+        {
+            // Get the stream to process
+            let stream = stream_result?;
+            stream.set_nodelay(true)?;
+
+            // Increment wait group
+            awg.add(1);
+            let t_awg = awg.clone();
+
+            // Process the connection
+            spawn(
+                "metrics handle_tcp_connection",
+                self.clone().handle_tcp_connection(stream, t_awg),
+            )
+            .detach();
+        }
+
+        // Wait for all connections to terminate
+        awg.wait().await;
+
+        Ok(())
+    }
+
+    /// Read the request line and headers, returning the method and path requested
+    async fn read_request_head<R: AsyncBufReadExt + Unpin>(
+        mut reader: R,
+    ) -> std::io::Result<Option<(String, String)>> {
+        let mut request_line = String::new();
+        let mut head_len = 0usize;
+        let mut linebuf = String::new();
+        loop {
+            linebuf.clear();
+            let size = reader.read_line(&mut linebuf).await?;
+            if size == 0 {
+                // Connection closed before the request was complete
+                return Ok(None);
+            }
+            head_len += size;
+            if head_len > MAX_METRICS_REQUEST_HEAD_LEN {
+                return Ok(None);
+            }
+            let line = linebuf.trim_end();
+            if request_line.is_empty() {
+                request_line = line.to_owned();
+            } else if line.is_empty() {
+                // End of headers
+                break;
+            }
+        }
+
+        let mut parts = request_line.split_ascii_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Ok(None);
+        };
+        // Ignore any query string
+        let path = target.split('?').next().unwrap_or_default();
+
+        Ok(Some((method.to_owned(), path.to_owned())))
+    }
+
+    async fn process_request(&self, method: &str, path: &str) -> (&'static str, String) {
+        if path != METRICS_PATH {
+            return ("404 Not Found", "not found\n".to_owned());
+        }
+        if method != "GET" && method != "HEAD" {
+            return ("405 Method Not Allowed", "method not allowed\n".to_owned());
+        }
+
+        let veilid_api = self.inner.lock().veilid_api.clone();
+        match veilid_api.get_stats().await {
+            Ok(stats) => ("200 OK", format_openmetrics(&stats)),
+            Err(e) => {
+                debug!(target: "metrics", "Failed to get stats: {}", e);
+                (
+                    "503 Service Unavailable",
+                    "stats not available\n".to_owned(),
+                )
+            }
+        }
+    }
+
+    pub async fn handle_tcp_connection(self, stream: TcpStream, awg: AsyncWaitGroup) {
+        // Get address of peer
+        let peer_addr = match stream.peer_addr() {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("can't get peer address: {}", e);
+                awg.done();
+                return;
+            }
+        };
+        trace!(target: "metrics", "Accepted Metrics Connection: {:?}", peer_addr);
+
+        // Split into reader and writer halves
+        // with line buffering on the reader
+        cfg_if! {
+            if #[cfg(feature="rt-async-std")] {
+                use futures_util::AsyncReadExt;
+                let (reader, mut writer) = stream.split();
+                let reader = BufReader::new(reader);
+            } else {
+                let (reader, mut writer) = stream.into_split();
+                let reader = BufReader::new(reader);
+            }
+        }
+
+        // Only one request is served per connection
+        let request_head =
+            match timeout(METRICS_REQUEST_TIMEOUT_MS, Self::read_request_head(reader)).await {
+                Ok(Ok(Some(v))) => Some(v),
+                Ok(Ok(None)) | Ok(Err(_)) | Err(_) => None,
+            };
+
+        let (status, body) = match &request_head {
+            Some((method, path)) => self.process_request(method, path).await,
+            None => ("400 Bad Request", "bad request\n".to_owned()),
+        };
+        let content_type = if status.starts_with("200") {
+            OPENMETRICS_CONTENT_TYPE
+        } else {
+            "text/plain; charset=utf-8"
+        };
+        let is_head = matches!(&request_head, Some((method, _)) if method == "HEAD");
+
+        let mut response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        );
+        if !is_head {
+            response += &body;
+        }
+        if let Err(e) = writer.write_all(response.as_bytes()).await {
+            trace!(target: "metrics", "Failed to send metrics response: {}", e);
+        }
+
+        trace!(target: "metrics", "Closed Metrics Connection: {:?}", peer_addr);
+
+        awg.done();
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn run(&self, tcp_bind_addrs: Vec<SocketAddr>) {
+        let mut bind_futures: Vec<SendPinBoxFuture<()>> = Vec::new();
+
+        for addr in tcp_bind_addrs.iter().copied() {
+            let this = self.clone();
+            bind_futures.push(Box::pin(async move {
+                if let Err(e) = this.handle_tcp_incoming(addr).await {
+                    warn!("Not binding metrics to {}: {}", addr, e);
+                }
+            }));
+        }
+
+        let bind_futures_join = join_all(bind_futures);
+        self.inner.lock().join_handle = Some(spawn("metrics bind_futures", bind_futures_join));
+    }
+}
+
+// --- OpenMetrics Formatting ---------------------------------
+
+/// Escape a label value for the OpenMetrics text format
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Convert a duration to the seconds OpenMetrics expects
+fn duration_seconds(duration: TimestampDuration) -> f64 {
+    duration.as_u64() as f64 / 1_000_000.0f64
+}
+
+/// Writes metric families in the OpenMetrics text format
+struct OpenMetricsWriter {
+    out: String,
+}
+
+impl OpenMetricsWriter {
+    fn new() -> Self {
+        Self { out: String::new() }
+    }
+
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+    }
+
+    fn sample<V: std::fmt::Display>(&mut self, name: &str, labels: &[(&str, &str)], value: V) {
+        self.out += name;
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = write!(self.out, "{{{}}}", labels);
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+
+    fn finish(mut self) -> String {
+        self.out += "# EOF\n";
+        self.out
+    }
+}
+
+/// Render a stats snapshot in the OpenMetrics text format
+pub fn format_openmetrics(stats: &VeilidStats) -> String {
+    let mut w = OpenMetricsWriter::new();
+
+    // Node transfer stats
+    let directions = [("down", &stats.transfer.down), ("up", &stats.transfer.up)];
+    w.family(
+        "veilid_transfer_bytes",
+        "counter",
+        "Total bytes transferred by this node.",
+    );
+    for (direction, ts) in directions {
+        w.sample(
+            "veilid_transfer_bytes_total",
+            &[("direction", direction)],
+            ts.total,
+        );
+    }
+    w.family(
+        "veilid_transfer_rate_bytes_per_second",
+        "gauge",
+        "Recent transfer rate of this node.",
+    );
+    for (direction, ts) in directions {
+        for (stat, value) in [
+            ("minimum", ts.minimum),
+            ("average", ts.average),
+            ("maximum", ts.maximum),
+        ] {
+            w.sample(
+                "veilid_transfer_rate_bytes_per_second",
+                &[("direction", direction), ("stat", stat)],
+                value,
+            );
+        }
+    }
+
+    // Recent peer stats
+    w.family(
+        "veilid_peers",
+        "gauge",
+        "Number of recently accessed peers.",
+    );
+    w.sample("veilid_peers", &[], stats.peers.len());
+
+    let peers: Vec<(String, &PeerStats)> = stats
+        .peers
+        .iter()
+        .map(|p| {
+            let node_id = p
+                .node_ids
+                .first()
+                .map(|k| k.to_string())
+                .unwrap_or_default();
+            (node_id, &p.peer_stats)
+        })
+        .collect();
+
+    let rpc_counts: [(&str, &str, fn(&RPCStats) -> u32); 5] = [
+        (
+            "veilid_peer_rpc_messages_sent",
+            "Number of RPC messages sent to the peer.",
+            |s| s.messages_sent,
+        ),
+        (
+            "veilid_peer_rpc_messages_received",
+            "Number of RPC messages received from the peer.",
+            |s| s.messages_rcvd,
+        ),
+        (
+            "veilid_peer_rpc_questions_in_flight",
+            "Number of questions sent to the peer that have not been answered yet.",
+            |s| s.questions_in_flight,
+        ),
+        (
+            "veilid_peer_rpc_recent_lost_answers",
+            "Number of answers from the peer lost since it was last reliable.",
+            |s| s.recent_lost_answers,
+        ),
+        (
+            "veilid_peer_rpc_failed_to_send",
+            "Number of messages to the peer that failed to send since the last success.",
+            |s| s.failed_to_send,
+        ),
+    ];
+    for (name, help, f) in rpc_counts {
+        w.family(name, "gauge", help);
+        for (node_id, ps) in &peers {
+            w.sample(name, &[("node_id", node_id)], f(&ps.rpc_stats));
+        }
+    }
+
+    w.family(
+        "veilid_peer_latency_seconds",
+        "gauge",
+        "Recent round trip latency of communications with the peer.",
+    );
+    for (node_id, ps) in &peers {
+        let Some(latency) = &ps.latency else {
+            continue;
+        };
+        for (stat, value) in [
+            ("fastest", latency.fastest),
+            ("average", latency.average),
+            ("slowest", latency.slowest),
+        ] {
+            w.sample(
+                "veilid_peer_latency_seconds",
+                &[("node_id", node_id), ("stat", stat)],
+                duration_seconds(value),
+            );
+        }
+    }
+
+    w.family(
+        "veilid_peer_transfer_bytes",
+        "counter",
+        "Total bytes transferred with the peer.",
+    );
+    for (node_id, ps) in &peers {
+        for (direction, ts) in [("down", &ps.transfer.down), ("up", &ps.transfer.up)] {
+            w.sample(
+                "veilid_peer_transfer_bytes_total",
+                &[("node_id", node_id), ("direction", direction)],
+                ts.total,
+            );
+        }
+    }
+
+    // Routing table bucket occupancy
+    // Empty buckets are left out, as most of the far buckets are always empty
+    w.family(
+        "veilid_bucket_entries",
+        "gauge",
+        "Number of routing table entries in each bucket, by state.",
+    );
+    for bs in &stats.buckets {
+        let kind = bs.kind.to_string();
+        let index = bs.index.to_string();
+        for (state, count) in [
+            ("reliable", bs.reliable),
+            ("unreliable", bs.unreliable),
+            ("dead", bs.dead),
+            ("punished", bs.punished),
+        ] {
+            if count == 0 {
+                continue;
+            }
+            w.sample(
+                "veilid_bucket_entries",
+                &[("kind", &kind), ("bucket", &index), ("state", state)],
+                count,
+            );
+        }
+    }
+
+    // Record store usage
+    let record_stores = [
+        ("local", &stats.local_record_store),
+        ("remote", &stats.remote_record_store),
+    ];
+    let record_store_gauges: [(&str, &str, fn(&RecordStoreStats) -> u64); 5] = [
+        (
+            "veilid_record_store_records",
+            "Number of records in the record store.",
+            |s| s.record_count as u64,
+        ),
+        (
+            "veilid_record_store_subkey_cache_entries",
+            "Number of subkeys in the record store subkey cache.",
+            |s| s.subkey_cache_count as u64,
+        ),
+        (
+            "veilid_record_store_subkey_cache_bytes",
+            "Size of the subkeys in the record store subkey cache.",
+            |s| s.subkey_cache_total_size.as_u64(),
+        ),
+        (
+            "veilid_record_store_storage_bytes",
+            "Storage space used by records in the record store.",
+            |s| s.total_storage_space.as_u64(),
+        ),
+        (
+            "veilid_record_store_dead_records",
+            "Number of records waiting to be purged from the record store.",
+            |s| s.dead_record_count as u64,
+        ),
+    ];
+    for (name, help, f) in record_store_gauges {
+        w.family(name, "gauge", help);
+        for (store, opt_rss) in record_stores {
+            if let Some(rss) = opt_rss {
+                w.sample(name, &[("store", store)], f(rss));
+            }
+        }
+    }
+
+    w.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_openmetrics() {
+        let stats = VeilidStats {
+            buckets: vec![BucketStats {
+                kind: CRYPTO_KIND_VLD0,
+                index: 3,
+                reliable: 2,
+                unreliable: 0,
+                dead: 1,
+                punished: 0,
+            }],
+            local_record_store: Some(RecordStoreStats {
+                record_count: 4,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let out = format_openmetrics(&stats);
+
+        assert!(out.ends_with("# EOF\n"));
+        assert!(out.contains("veilid_transfer_bytes_total{direction=\"down\"} 0\n"));
+        assert!(out.contains("veilid_peers 0\n"));
+        assert!(out
+            .contains("veilid_bucket_entries{kind=\"VLD0\",bucket=\"3\",state=\"reliable\"} 2\n"));
+        assert!(
+            out.contains("veilid_bucket_entries{kind=\"VLD0\",bucket=\"3\",state=\"dead\"} 1\n")
+        );
+        assert!(!out.contains("state=\"unreliable\""));
+        assert!(out.contains("veilid_record_store_records{store=\"local\"} 4\n"));
+        assert!(!out.contains("store=\"remote\""));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::client_api;
+use crate::metrics;
 use crate::settings::*;
 use crate::tools::*;
 use crate::veilid_logs::*;
@@ -7,16 +8,18 @@ use futures_util::select;
 use futures_util::FutureExt;
 use lazy_static::*;
 use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::*;
 use veilid_core::tools::*;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ServerMode {
     Normal,
     ShutdownImmediate,
     DumpTXTRecord,
+    ExportBackup(PathBuf),
 }
 
 lazy_static! {
@@ -32,6 +35,22 @@ pub fn shutdown() {
     }
 }
 
+async fn export_backup(veilid_api: &veilid_core::VeilidAPI, path: &Path) -> EyreResult<()> {
+    let password = rpassword::prompt_password("Enter backup password (will not echo): ")
+        .wrap_err("invalid password")?;
+    let confirm = rpassword::prompt_password("Confirm backup password (will not echo): ")
+        .wrap_err("invalid password")?;
+    if password != confirm {
+        bail!("Passwords do not match");
+    }
+    let archive = veilid_api
+        .export_backup(password)
+        .await
+        .map_err(|e| eyre!("Exporting backup failed: {:?}", e))?;
+    std::fs::write(path, archive).wrap_err("failed to write backup file")?;
+    Ok(())
+}
+
 //#[instrument(err, skip_all)]
 pub async fn run_veilid_server(
     settings: Settings,
@@ -44,6 +63,12 @@ pub async fn run_veilid_server(
         settings_client_api_network_enabled,
         settings_client_api_ipc_directory,
         settings_client_api_listen_address_addrs,
+        settings_client_api_tls_enabled,
+        settings_client_api_tls_listen_address_addrs,
+        settings_client_api_ws_enabled,
+        settings_client_api_ws_listen_address_addrs,
+        settings_metrics_enabled,
+        settings_metrics_listen_address_addrs,
         subnode_index,
     ) = {
         let settingsr = settings.read();
@@ -54,6 +79,12 @@ pub async fn run_veilid_server(
             settingsr.client_api.network_enabled,
             settingsr.client_api.ipc_directory.clone(),
             settingsr.client_api.listen_address.addrs.clone(),
+            settingsr.client_api.tls_enabled,
+            settingsr.client_api.tls_listen_address.addrs.clone(),
+            settingsr.client_api.ws_enabled,
+            settingsr.client_api.ws_listen_address.addrs.clone(),
+            settingsr.metrics.enabled,
+            settingsr.metrics.listen_address.addrs.clone(),
             settingsr.testing.subnode_index,
         )
     };
@@ -80,7 +111,10 @@ pub async fn run_veilid_server(
         .wrap_err("VeilidCore startup failed")?;
 
     // Start client api if one is requested
-    let capi_enabled = settings_client_api_ipc_enabled || settings_client_api_network_enabled;
+    let capi_enabled = settings_client_api_ipc_enabled
+        || settings_client_api_network_enabled
+        || settings_client_api_tls_enabled
+        || settings_client_api_ws_enabled;
     let mut capi = if capi_enabled && matches!(server_mode, ServerMode::Normal) {
         let some_capi =
             client_api::ClientApi::new(veilid_api.clone(), veilid_logs.clone(), settings.clone());
@@ -95,14 +129,37 @@ pub async fn run_veilid_server(
             } else {
                 vec![]
             },
+            if settings_client_api_tls_enabled {
+                settings_client_api_tls_listen_address_addrs
+            } else {
+                vec![]
+            },
+            if settings_client_api_ws_enabled {
+                settings_client_api_ws_listen_address_addrs
+            } else {
+                vec![]
+            },
         );
         Some(some_capi)
     } else {
         None
     };
 
+    // Start metrics listener if one is requested
+    let metrics = if settings_metrics_enabled && matches!(server_mode, ServerMode::Normal) {
+        let some_metrics = metrics::MetricsServer::new(veilid_api.clone());
+        some_metrics.run(settings_metrics_listen_address_addrs);
+        Some(some_metrics)
+    } else {
+        None
+    };
+
     // Drop rwlock on settings
-    let auto_attach = settings_auto_attach || !matches!(server_mode, ServerMode::Normal);
+    let auto_attach = match server_mode {
+        ServerMode::Normal => settings_auto_attach,
+        ServerMode::ExportBackup(_) => false,
+        _ => true,
+    };
 
     // Process all updates
     let capi2 = capi.clone();
@@ -173,6 +230,14 @@ pub async fn run_veilid_server(
         shutdown();
     }
 
+    // Process export-backup
+    if let ServerMode::ExportBackup(path) = &server_mode {
+        if let Err(e) = export_backup(&veilid_api, path).await {
+            out = Err(e);
+        }
+        shutdown();
+    }
+
     // Process shutdown-immediate
     if matches!(server_mode, ServerMode::ShutdownImmediate) {
         shutdown();
@@ -192,6 +257,11 @@ pub async fn run_veilid_server(
         c.stop().await;
     }
 
+    // Stop the metrics listener if we have one
+    if let Some(m) = metrics {
+        m.stop().await;
+    }
+
     // Shut down Veilid API to release state change sender
     veilid_api.shutdown().await;
 
@@ -41,6 +41,20 @@ client_api:
     ipc_directory: '%IPC_DIRECTORY%'
     network_enabled: false
     listen_address: 'localhost:5959'
+    tls_enabled: false
+    tls_listen_address: 'localhost:5961'
+    ws_enabled: false
+    ws_listen_address: 'localhost:5962'
+    ws_tls: false
+    ws_allowed_origins: []
+    ws_allow_missing_origin: true
+    authentication:
+        ipc_required: false
+        network_required: false
+        clients: []
+metrics:
+    enabled: false
+    listen_address: 'localhost:5960'
 auto_attach: true
 logging:
     system:
@@ -79,6 +93,7 @@ testing:
 core:
     capabilities:
         disable: []
+        advertise: []
     protected_store:
         allow_insecure_fallback: true
         always_use_insecure_storage: true
@@ -113,6 +128,20 @@ core:
             limit_attached_strong: 16
             limit_attached_good: 8
             limit_attached_weak: 4
+            bucket_retention:
+                max_bucket_depth: 256
+                min_bucket_depth: 1
+                keep_closest_reliable: 16
+                keep_closest_unreliable: 8
+                age_weight: 1
+                reliability_weight: 1
+                reputation_weight: 1
+                long_lived_age_ms: 3600000
+                protect_long_lived: true
+            private_overlay:
+                networks: []
+                bootstrap: []
+            asn_table: []
         rpc: 
             concurrency: 0
             queue_size: 1024
@@ -121,6 +150,12 @@ core:
             timeout_ms: 5000
             max_route_hop_count: 4
             default_route_hop_count: 1
+            default_route_quality:
+                latency_weight: 0
+                failure_weight: 0
+                max_hop_latency: '0'
+                diverse_ip_blocks: false
+                diverse_asns: false
         dht:
             max_find_node_count: 20
             resolve_node_timeout_ms: 10000
@@ -144,6 +179,13 @@ core:
             public_watch_limit: 32
             member_watch_limit: 8
             max_watch_expiration_ms: 600000
+            pinning:
+                enabled: false
+                max_records: 32
+                max_records_per_owner: 4
+                max_expiration_ms: 86400000
+                allow_any_owner: false
+                allowed_owners: []
         upnp: true
         detect_address_changes: true
         restricted_nat_retries: 0
@@ -188,6 +230,10 @@ core:
                 listen_address: ':5150'
                 path: 'ws'
                 # url: ''
+            wrtc:
+                connect: false
+                max_connections: 32
+                ice_servers: []
         "#,
     )
     .replace(
@@ -496,12 +542,68 @@ pub struct Otlp {
     pub ignore_log_targets: Vec<String>,
 }
 
+/// Groups of client api requests that can be granted to an authenticated client
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientApiScope {
+    /// Node state, versions, crypto and state updates
+    State,
+    /// Private routes, app_message and app_call
+    AppMessage,
+    /// DHT records and their updates
+    Dht,
+    /// Table store databases
+    TableStore,
+    /// Attach/detach, debug commands and server control
+    Control,
+}
+
+impl ClientApiScope {
+    pub fn all() -> Vec<ClientApiScope> {
+        vec![
+            ClientApiScope::State,
+            ClientApiScope::AppMessage,
+            ClientApiScope::Dht,
+            ClientApiScope::TableStore,
+            ClientApiScope::Control,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClientApiClient {
+    pub name: String,
+    pub token: String,
+    pub scopes: Vec<ClientApiScope>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClientApiAuthentication {
+    pub ipc_required: bool,
+    pub network_required: bool,
+    pub clients: Vec<ClientApiClient>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ClientApi {
     pub ipc_enabled: bool,
     pub ipc_directory: PathBuf,
     pub network_enabled: bool,
     pub listen_address: NamedSocketAddrs,
+    pub tls_enabled: bool,
+    pub tls_listen_address: NamedSocketAddrs,
+    pub ws_enabled: bool,
+    pub ws_listen_address: NamedSocketAddrs,
+    pub ws_tls: bool,
+    pub ws_allowed_origins: Vec<String>,
+    pub ws_allow_missing_origin: bool,
+    pub authentication: ClientApiAuthentication,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Metrics {
+    pub enabled: bool,
+    pub listen_address: NamedSocketAddrs,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -575,12 +677,20 @@ pub struct Wss {
     pub url: Option<ParsedUrl>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Wrtc {
+    pub connect: bool,
+    pub max_connections: u32,
+    pub ice_servers: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Protocol {
     pub udp: Udp,
     pub tcp: Tcp,
     pub ws: Ws,
     pub wss: Wss,
+    pub wrtc: Wrtc,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -599,6 +709,7 @@ pub struct Rpc {
     pub timeout_ms: u32,
     pub max_route_hop_count: u8,
     pub default_route_hop_count: u8,
+    pub default_route_quality: veilid_core::RouteQuality,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -625,6 +736,36 @@ pub struct Dht {
     pub public_watch_limit: u32,
     pub member_watch_limit: u32,
     pub max_watch_expiration_ms: u32,
+    pub pinning: DhtPinning,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DhtPinning {
+    pub enabled: bool,
+    pub max_records: u32,
+    pub max_records_per_owner: u32,
+    pub max_expiration_ms: u32,
+    pub allow_any_owner: bool,
+    pub allowed_owners: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BucketRetention {
+    pub max_bucket_depth: u32,
+    pub min_bucket_depth: u32,
+    pub keep_closest_reliable: u32,
+    pub keep_closest_unreliable: u32,
+    pub age_weight: u32,
+    pub reliability_weight: u32,
+    pub reputation_weight: u32,
+    pub long_lived_age_ms: u32,
+    pub protect_long_lived: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PrivateOverlay {
+    pub networks: Vec<String>,
+    pub bootstrap: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -637,6 +778,9 @@ pub struct RoutingTable {
     pub limit_attached_strong: u32,
     pub limit_attached_good: u32,
     pub limit_attached_weak: u32,
+    pub bucket_retention: BucketRetention,
+    pub private_overlay: PrivateOverlay,
+    pub asn_table: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -692,6 +836,8 @@ pub struct ProtectedStore {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Capabilities {
     pub disable: Vec<String>,
+    #[serde(default)]
+    pub advertise: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -719,6 +865,7 @@ pub struct Daemon {
 pub struct SettingsInner {
     pub daemon: Daemon,
     pub client_api: ClientApi,
+    pub metrics: Metrics,
     pub auto_attach: bool,
     pub logging: Logging,
     pub testing: Testing,
@@ -771,6 +918,17 @@ impl Settings {
             }
         }
 
+        let inner = self.inner.read();
+        let mut tokens = HashSet::new();
+        for client in &inner.client_api.authentication.clients {
+            if client.token.is_empty() {
+                bail!("client api client '{}' has an empty token", client.name);
+            }
+            if !tokens.insert(client.token.as_str()) {
+                bail!("client api client '{}' has a duplicate token", client.name);
+            }
+        }
+
         Ok(())
     }
 
@@ -788,8 +946,13 @@ impl Settings {
             return Ok(());
         }
 
-        // bump client api port
+        // bump client api ports
         settingsrw.client_api.listen_address.offset_port(idx)?;
+        settingsrw.client_api.tls_listen_address.offset_port(idx)?;
+        settingsrw.client_api.ws_listen_address.offset_port(idx)?;
+
+        // bump metrics port
+        settingsrw.metrics.listen_address.offset_port(idx)?;
 
         // bump protocol ports
         settingsrw
@@ -994,6 +1157,18 @@ impl Settings {
         set_config_value!(inner.client_api.ipc_directory, value);
         set_config_value!(inner.client_api.network_enabled, value);
         set_config_value!(inner.client_api.listen_address, value);
+        set_config_value!(inner.client_api.tls_enabled, value);
+        set_config_value!(inner.client_api.tls_listen_address, value);
+        set_config_value!(inner.client_api.ws_enabled, value);
+        set_config_value!(inner.client_api.ws_listen_address, value);
+        set_config_value!(inner.client_api.ws_tls, value);
+        set_config_value!(inner.client_api.ws_allowed_origins, value);
+        set_config_value!(inner.client_api.ws_allow_missing_origin, value);
+        set_config_value!(inner.client_api.authentication.ipc_required, value);
+        set_config_value!(inner.client_api.authentication.network_required, value);
+        set_config_value!(inner.client_api.authentication.clients, value);
+        set_config_value!(inner.metrics.enabled, value);
+        set_config_value!(inner.metrics.listen_address, value);
         set_config_value!(inner.auto_attach, value);
         set_config_value!(inner.logging.system.enabled, value);
         set_config_value!(inner.logging.system.level, value);
@@ -1020,6 +1195,7 @@ impl Settings {
         set_config_value!(inner.logging.console.enabled, value);
         set_config_value!(inner.testing.subnode_index, value);
         set_config_value!(inner.core.capabilities.disable, value);
+        set_config_value!(inner.core.capabilities.advertise, value);
         set_config_value!(inner.core.protected_store.allow_insecure_fallback, value);
         set_config_value!(
             inner.core.protected_store.always_use_insecure_storage,
@@ -1066,6 +1242,91 @@ impl Settings {
         );
         set_config_value!(inner.core.network.routing_table.limit_attached_good, value);
         set_config_value!(inner.core.network.routing_table.limit_attached_weak, value);
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .max_bucket_depth,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .min_bucket_depth,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .keep_closest_reliable,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .keep_closest_unreliable,
+            value
+        );
+        set_config_value!(
+            inner.core.network.routing_table.bucket_retention.age_weight,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .reliability_weight,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .reputation_weight,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .long_lived_age_ms,
+            value
+        );
+        set_config_value!(
+            inner
+                .core
+                .network
+                .routing_table
+                .bucket_retention
+                .protect_long_lived,
+            value
+        );
+        set_config_value!(
+            inner.core.network.routing_table.private_overlay.networks,
+            value
+        );
+        set_config_value!(
+            inner.core.network.routing_table.private_overlay.bootstrap,
+            value
+        );
+        set_config_value!(inner.core.network.routing_table.asn_table, value);
         set_config_value!(inner.core.network.rpc.concurrency, value);
         set_config_value!(inner.core.network.rpc.queue_size, value);
         set_config_value!(inner.core.network.rpc.max_timestamp_behind_ms, value);
@@ -1073,6 +1334,7 @@ impl Settings {
         set_config_value!(inner.core.network.rpc.timeout_ms, value);
         set_config_value!(inner.core.network.rpc.max_route_hop_count, value);
         set_config_value!(inner.core.network.rpc.default_route_hop_count, value);
+        set_config_value!(inner.core.network.rpc.default_route_quality, value);
         set_config_value!(inner.core.network.dht.max_find_node_count, value);
         set_config_value!(inner.core.network.dht.resolve_node_timeout_ms, value);
         set_config_value!(inner.core.network.dht.resolve_node_count, value);
@@ -1104,6 +1366,12 @@ impl Settings {
         set_config_value!(inner.core.network.dht.public_watch_limit, value);
         set_config_value!(inner.core.network.dht.member_watch_limit, value);
         set_config_value!(inner.core.network.dht.max_watch_expiration_ms, value);
+        set_config_value!(inner.core.network.dht.pinning.enabled, value);
+        set_config_value!(inner.core.network.dht.pinning.max_records, value);
+        set_config_value!(inner.core.network.dht.pinning.max_records_per_owner, value);
+        set_config_value!(inner.core.network.dht.pinning.max_expiration_ms, value);
+        set_config_value!(inner.core.network.dht.pinning.allow_any_owner, value);
+        set_config_value!(inner.core.network.dht.pinning.allowed_owners, value);
         set_config_value!(inner.core.network.upnp, value);
         set_config_value!(inner.core.network.detect_address_changes, value);
         set_config_value!(inner.core.network.restricted_nat_retries, value);
@@ -1139,6 +1407,9 @@ impl Settings {
         set_config_value!(inner.core.network.protocol.wss.listen_address, value);
         set_config_value!(inner.core.network.protocol.wss.path, value);
         set_config_value!(inner.core.network.protocol.wss.url, value);
+        set_config_value!(inner.core.network.protocol.wrtc.connect, value);
+        set_config_value!(inner.core.network.protocol.wrtc.max_connections, value);
+        set_config_value!(inner.core.network.protocol.wrtc.ice_servers, value);
         Err(eyre!("settings key not found"))
     }
 
@@ -1162,6 +1433,14 @@ impl Settings {
                     }
                     Ok(Box::new(caps))
                 }
+                "capabilities.advertise" => {
+                    let mut caps = Vec::<FourCC>::new();
+                    for c in &inner.core.capabilities.advertise {
+                        let cap = FourCC::from_str(c.as_str()).map_err(VeilidAPIError::generic)?;
+                        caps.push(cap);
+                    }
+                    Ok(Box::new(caps))
+                }
                 "protected_store.allow_insecure_fallback" => {
                     Ok(Box::new(inner.core.protected_store.allow_insecure_fallback))
                 }
@@ -1259,6 +1538,94 @@ impl Settings {
                 "network.routing_table.limit_attached_weak" => Ok(Box::new(
                     inner.core.network.routing_table.limit_attached_weak,
                 )),
+                "network.routing_table.bucket_retention.max_bucket_depth" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .max_bucket_depth,
+                )),
+                "network.routing_table.bucket_retention.min_bucket_depth" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .min_bucket_depth,
+                )),
+                "network.routing_table.bucket_retention.keep_closest_reliable" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .keep_closest_reliable,
+                )),
+                "network.routing_table.bucket_retention.keep_closest_unreliable" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .keep_closest_unreliable,
+                )),
+                "network.routing_table.bucket_retention.age_weight" => Ok(Box::new(
+                    inner.core.network.routing_table.bucket_retention.age_weight,
+                )),
+                "network.routing_table.bucket_retention.reliability_weight" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .reliability_weight,
+                )),
+                "network.routing_table.bucket_retention.reputation_weight" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .reputation_weight,
+                )),
+                "network.routing_table.bucket_retention.long_lived_age_ms" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .long_lived_age_ms,
+                )),
+                "network.routing_table.bucket_retention.protect_long_lived" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .bucket_retention
+                        .protect_long_lived,
+                )),
+                "network.routing_table.private_overlay.networks" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .private_overlay
+                        .networks
+                        .clone(),
+                )),
+                "network.routing_table.private_overlay.bootstrap" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .routing_table
+                        .private_overlay
+                        .bootstrap
+                        .clone(),
+                )),
+                "network.routing_table.asn_table" => {
+                    Ok(Box::new(inner.core.network.routing_table.asn_table.clone()))
+                }
                 "network.rpc.concurrency" => Ok(Box::new(inner.core.network.rpc.concurrency)),
                 "network.rpc.queue_size" => Ok(Box::new(inner.core.network.rpc.queue_size)),
                 "network.rpc.max_timestamp_behind_ms" => {
@@ -1274,6 +1641,9 @@ impl Settings {
                 "network.rpc.default_route_hop_count" => {
                     Ok(Box::new(inner.core.network.rpc.default_route_hop_count))
                 }
+                "network.rpc.default_route_quality" => {
+                    Ok(Box::new(inner.core.network.rpc.default_route_quality))
+                }
                 "network.dht.max_find_node_count" => {
                     Ok(Box::new(inner.core.network.dht.max_find_node_count))
                 }
@@ -1338,6 +1708,24 @@ impl Settings {
                 "network.dht.max_watch_expiration_ms" => {
                     Ok(Box::new(inner.core.network.dht.max_watch_expiration_ms))
                 }
+                "network.dht.pinning.enabled" => {
+                    Ok(Box::new(inner.core.network.dht.pinning.enabled))
+                }
+                "network.dht.pinning.max_records" => {
+                    Ok(Box::new(inner.core.network.dht.pinning.max_records))
+                }
+                "network.dht.pinning.max_records_per_owner" => Ok(Box::new(
+                    inner.core.network.dht.pinning.max_records_per_owner,
+                )),
+                "network.dht.pinning.max_expiration_ms" => {
+                    Ok(Box::new(inner.core.network.dht.pinning.max_expiration_ms))
+                }
+                "network.dht.pinning.allow_any_owner" => {
+                    Ok(Box::new(inner.core.network.dht.pinning.allow_any_owner))
+                }
+                "network.dht.pinning.allowed_owners" => Ok(Box::new(
+                    inner.core.network.dht.pinning.allowed_owners.clone(),
+                )),
                 "network.upnp" => Ok(Box::new(inner.core.network.upnp)),
                 "network.detect_address_changes" => {
                     Ok(Box::new(inner.core.network.detect_address_changes))
@@ -1523,6 +1911,15 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.urlstring.clone()),
                 )),
+                "network.protocol.wrtc.connect" => {
+                    Ok(Box::new(inner.core.network.protocol.wrtc.connect))
+                }
+                "network.protocol.wrtc.max_connections" => {
+                    Ok(Box::new(inner.core.network.protocol.wrtc.max_connections))
+                }
+                "network.protocol.wrtc.ice_servers" => Ok(Box::new(
+                    inner.core.network.protocol.wrtc.ice_servers.clone(),
+                )),
                 _ => Err(VeilidAPIError::generic(format!(
                     "config key '{}' doesn't exist",
                     key
@@ -1567,6 +1964,30 @@ mod tests {
             s.client_api.listen_address.addrs,
             listen_address_to_socket_addrs("localhost:5959").unwrap()
         );
+        assert!(!s.client_api.tls_enabled);
+        assert_eq!(s.client_api.tls_listen_address.name, "localhost:5961");
+        assert_eq!(
+            s.client_api.tls_listen_address.addrs,
+            listen_address_to_socket_addrs("localhost:5961").unwrap()
+        );
+        assert!(!s.client_api.ws_enabled);
+        assert_eq!(s.client_api.ws_listen_address.name, "localhost:5962");
+        assert_eq!(
+            s.client_api.ws_listen_address.addrs,
+            listen_address_to_socket_addrs("localhost:5962").unwrap()
+        );
+        assert!(!s.client_api.ws_tls);
+        assert!(s.client_api.ws_allowed_origins.is_empty());
+        assert!(s.client_api.ws_allow_missing_origin);
+        assert!(!s.client_api.authentication.ipc_required);
+        assert!(!s.client_api.authentication.network_required);
+        assert!(s.client_api.authentication.clients.is_empty());
+        assert!(!s.metrics.enabled);
+        assert_eq!(s.metrics.listen_address.name, "localhost:5960");
+        assert_eq!(
+            s.metrics.listen_address.addrs,
+            listen_address_to_socket_addrs("localhost:5960").unwrap()
+        );
         assert!(s.auto_attach);
         assert!(!s.logging.system.enabled);
         assert_eq!(s.logging.system.level, LogLevel::Info);
@@ -1633,6 +2054,52 @@ mod tests {
             s.core.network.routing_table.bootstrap,
             vec!["bootstrap.veilid.net".to_owned()]
         );
+        assert_eq!(
+            s.core
+                .network
+                .routing_table
+                .bucket_retention
+                .max_bucket_depth,
+            256u32
+        );
+        assert_eq!(
+            s.core
+                .network
+                .routing_table
+                .bucket_retention
+                .min_bucket_depth,
+            1u32
+        );
+        assert_eq!(
+            s.core
+                .network
+                .routing_table
+                .bucket_retention
+                .long_lived_age_ms,
+            3_600_000u32
+        );
+        assert!(
+            s.core
+                .network
+                .routing_table
+                .bucket_retention
+                .protect_long_lived
+        );
+        assert!(s
+            .core
+            .network
+            .routing_table
+            .private_overlay
+            .networks
+            .is_empty());
+        assert!(s
+            .core
+            .network
+            .routing_table
+            .private_overlay
+            .bootstrap
+            .is_empty());
+        assert!(s.core.network.routing_table.asn_table.is_empty());
         //
         assert_eq!(s.core.network.rpc.concurrency, 0);
         assert_eq!(s.core.network.rpc.queue_size, 1024);
@@ -1641,6 +2108,10 @@ mod tests {
         assert_eq!(s.core.network.rpc.timeout_ms, 5_000u32);
         assert_eq!(s.core.network.rpc.max_route_hop_count, 4);
         assert_eq!(s.core.network.rpc.default_route_hop_count, 1);
+        assert_eq!(
+            s.core.network.rpc.default_route_quality,
+            veilid_core::RouteQuality::default()
+        );
         //
         assert_eq!(s.core.network.dht.max_find_node_count, 20u32);
         assert_eq!(s.core.network.dht.resolve_node_timeout_ms, 10_000u32);
@@ -1661,6 +2132,12 @@ mod tests {
         assert_eq!(s.core.network.dht.public_watch_limit, 32u32);
         assert_eq!(s.core.network.dht.member_watch_limit, 8u32);
         assert_eq!(s.core.network.dht.max_watch_expiration_ms, 600_000u32);
+        assert!(!s.core.network.dht.pinning.enabled);
+        assert_eq!(s.core.network.dht.pinning.max_records, 32u32);
+        assert_eq!(s.core.network.dht.pinning.max_records_per_owner, 4u32);
+        assert_eq!(s.core.network.dht.pinning.max_expiration_ms, 86_400_000u32);
+        assert!(!s.core.network.dht.pinning.allow_any_owner);
+        assert!(s.core.network.dht.pinning.allowed_owners.is_empty());
         //
         assert!(s.core.network.upnp);
         assert!(s.core.network.detect_address_changes);
@@ -1753,5 +2230,9 @@ mod tests {
         );
         assert_eq!(s.core.network.protocol.wss.url, None);
         //
+        assert!(!s.core.network.protocol.wrtc.connect);
+        assert_eq!(s.core.network.protocol.wrtc.max_connections, 32);
+        assert!(s.core.network.protocol.wrtc.ice_servers.is_empty());
+        //
     }
 }
@@ -266,3 +266,29 @@ pub fn ipaddr_in_network(addr: IpAddr, netaddr: IpAddr, netmask: IpAddr) -> bool
     }
     ipaddr_apply_netmask(netaddr, netmask) == ipaddr_apply_netmask(addr, netmask)
 }
+
+/// Parse an address range in CIDR notation such as '10.0.0.0/8' or 'fd00::/8' into a (network, netmask) pair
+pub fn ipaddr_parse_cidr(cidr: &str) -> Option<(IpAddr, IpAddr)> {
+    let (addr, prefix_len) = cidr.trim().split_once('/')?;
+    let addr = IpAddr::from_str(addr.trim()).ok()?;
+    let prefix_len = u32::from_str(prefix_len.trim()).ok()?;
+    let netmask = match addr {
+        IpAddr::V4(_) => {
+            if prefix_len > 32 {
+                return None;
+            }
+            IpAddr::V4(Ipv4Addr::from(
+                u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0),
+            ))
+        }
+        IpAddr::V6(_) => {
+            if prefix_len > 128 {
+                return None;
+            }
+            IpAddr::V6(Ipv6Addr::from(
+                u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0),
+            ))
+        }
+    };
+    Some((ipaddr_apply_netmask(addr, netmask), netmask))
+}
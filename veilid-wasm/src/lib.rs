@@ -654,7 +654,12 @@ pub fn new_custom_private_route(stability: String, sequencing: String) -> Promis
         let veilid_api = get_veilid_api()?;
 
         let (route_id, blob) = veilid_api
-            .new_custom_private_route(&veilid_core::VALID_CRYPTO_KINDS, stability, sequencing)
+            .new_custom_private_route(
+                &veilid_core::VALID_CRYPTO_KINDS,
+                stability,
+                sequencing,
+                veilid_core::RouteQuality::default(),
+            )
             .await?;
 
         let route_blob = VeilidRouteBlob { route_id, blob };
@@ -789,6 +794,32 @@ pub fn table_db_get_keys(id: u32, col: u32) -> Promise {
     })
 }
 
+#[wasm_bindgen()]
+pub fn table_db_scan_keys(id: u32, col: u32, scan: String) -> Promise {
+    let scan: veilid_core::TableDBScan = veilid_core::deserialize_json(&scan).unwrap();
+    wrap_api_future_json(async move {
+        let table_db = get_table_db(id, "table_db_scan_keys")?;
+
+        let keys = table_db.scan_keys(col, scan).await?;
+        let out: Vec<String> = keys
+            .into_iter()
+            .map(|k| data_encoding::BASE64URL_NOPAD.encode(&k))
+            .collect();
+        APIResult::Ok(out)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn table_db_scan(id: u32, col: u32, scan: String) -> Promise {
+    let scan: veilid_core::TableDBScan = veilid_core::deserialize_json(&scan).unwrap();
+    wrap_api_future_json(async move {
+        let table_db = get_table_db(id, "table_db_scan")?;
+
+        let out = table_db.scan(col, scan).await?;
+        APIResult::Ok(out)
+    })
+}
+
 fn add_table_db_transaction(tdbt: veilid_core::TableDBTransaction) -> u32 {
     let mut next_id: u32 = 1;
     let mut tdbts = (*TABLE_DB_TRANSACTIONS).borrow_mut();
@@ -1529,6 +1560,15 @@ pub fn debug(command: String) -> Promise {
     })
 }
 
+#[wasm_bindgen()]
+pub fn debug_structured(command: String) -> Promise {
+    wrap_api_future_json(async move {
+        let veilid_api = get_veilid_api()?;
+        let out = veilid_api.debug_structured(command).await?;
+        APIResult::Ok(out)
+    })
+}
+
 #[wasm_bindgen()]
 pub fn veilid_version_string() -> String {
     veilid_core::veilid_version_string()
@@ -10,7 +10,7 @@ export type CryptoKey = string;
 export type Nonce = string;
 export type Signature = string;
 export type KeyPair = `${PublicKey}:${SecretKey}`;
-export type FourCC = "NONE" | "VLD0" | string;
+export type FourCC = "NONE" | "VLD0" | "VLD1" | string;
 export type CryptoTyped<TCryptoKey extends string> = `${FourCC}:${TCryptoKey}`;
 export type CryptoTypedGroup<TCryptoKey extends string> = Array<CryptoTyped<TCryptoKey>>;
 "#;
@@ -188,6 +188,13 @@ impl VeilidClient {
         APIResult::Ok(out)
     }
 
+    /// Execute an 'internal debug command', returning structured output.
+    pub async fn debugStructured(command: String) -> APIResult<VeilidDebugResult> {
+        let veilid_api = get_veilid_api()?;
+        let out = veilid_api.debug_structured(command).await?;
+        APIResult::Ok(out)
+    }
+
     /// Return the cargo package version of veilid-core, in object format.
     pub fn version() -> VeilidVersion {
         let (major, minor, patch) = veilid_core::veilid_version();
@@ -62,7 +62,12 @@ impl VeilidRoutingContext {
         let veilid_api = get_veilid_api()?;
 
         let (route_id, blob) = veilid_api
-            .new_custom_private_route(&veilid_core::VALID_CRYPTO_KINDS, stability, sequencing)
+            .new_custom_private_route(
+                &veilid_core::VALID_CRYPTO_KINDS,
+                stability,
+                sequencing,
+                veilid_core::RouteQuality::default(),
+            )
             .await?;
 
         let route_blob = VeilidRouteBlob { route_id, blob };
@@ -115,6 +115,89 @@ impl VeilidTableDB {
         APIResult::Ok(out)
     }
 
+    /// Get an ordered page of the keys in a column of the TableDB.
+    ///
+    /// Only keys starting with `prefix`, at or after `start`, and before `end` are included.
+    /// Pass the last key of the previous page as `after` to fetch the next page.
+    /// A `limit` of zero returns all matching keys.
+    ///
+    /// Returns an array of Uint8Array keys.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn scanKeys(
+        &mut self,
+        columnId: u32,
+        prefix: Option<Box<[u8]>>,
+        start: Option<Box<[u8]>>,
+        end: Option<Box<[u8]>>,
+        after: Option<Box<[u8]>>,
+        reverse: bool,
+        limit: u32,
+    ) -> APIResult<Uint8ArrayArray> {
+        self.ensureOpen().await;
+        let table_db = self.getTableDB()?;
+
+        let scan = TableDBScan {
+            prefix: prefix.map(|x| x.into_vec()),
+            start: start.map(|x| x.into_vec()),
+            end: end.map(|x| x.into_vec()),
+            after: after.map(|x| x.into_vec()),
+            reverse,
+            limit,
+        };
+        let keys = table_db.scan_keys(columnId, scan).await?;
+        let out: Vec<Uint8Array> = keys
+            .into_iter()
+            .map(|k| Uint8Array::from(k.as_slice()))
+            .collect();
+
+        let out = into_unchecked_uint8array_array(out);
+
+        APIResult::Ok(out)
+    }
+
+    /// Get an ordered page of the keys in a column of the TableDB along with their values.
+    ///
+    /// Takes the same arguments as `scanKeys`.
+    ///
+    /// Returns an array of `[key, value]` Uint8Array pairs.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn scan(
+        &mut self,
+        columnId: u32,
+        prefix: Option<Box<[u8]>>,
+        start: Option<Box<[u8]>>,
+        end: Option<Box<[u8]>>,
+        after: Option<Box<[u8]>>,
+        reverse: bool,
+        limit: u32,
+    ) -> APIResult<Uint8ArrayPairArray> {
+        self.ensureOpen().await;
+        let table_db = self.getTableDB()?;
+
+        let scan = TableDBScan {
+            prefix: prefix.map(|x| x.into_vec()),
+            start: start.map(|x| x.into_vec()),
+            end: end.map(|x| x.into_vec()),
+            after: after.map(|x| x.into_vec()),
+            reverse,
+            limit,
+        };
+        let entries = table_db.scan(columnId, scan).await?;
+        let out: Vec<(Uint8Array, Uint8Array)> = entries
+            .into_iter()
+            .map(|e| {
+                (
+                    Uint8Array::from(e.key.as_slice()),
+                    Uint8Array::from(e.value.as_slice()),
+                )
+            })
+            .collect();
+
+        let out = into_unchecked_uint8array_pair_array(out);
+
+        APIResult::Ok(out)
+    }
+
     /// Start a TableDB write transaction.
     /// The transaction object must be committed or rolled back before dropping.
     pub async fn createTransaction(&mut self) -> APIResult<VeilidTableDBTransaction> {
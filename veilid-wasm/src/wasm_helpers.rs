@@ -49,6 +49,22 @@ pub(crate) fn into_unchecked_uint8array_array(items: Vec<Uint8Array>) -> Uint8Ar
         .unchecked_into::<Uint8ArrayArray>() // TODO: can I do this a better way?
 }
 
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "[Uint8Array, Uint8Array][]")]
+    pub type Uint8ArrayPairArray;
+}
+/// Convert a `Vec<(Uint8Array, Uint8Array)>` into a `js_sys::Array` with the type of `[Uint8Array, Uint8Array][]`
+pub(crate) fn into_unchecked_uint8array_pair_array(
+    items: Vec<(Uint8Array, Uint8Array)>,
+) -> Uint8ArrayPairArray {
+    items
+        .iter()
+        .map(|(a, b)| js_sys::Array::of2(a, b))
+        .collect::<js_sys::Array>()
+        .unchecked_into::<Uint8ArrayPairArray>()
+}
+
 /// Convert a StringArray (`js_sys::Array` with the type of `string[]`) into `Vec<String>`
 pub(crate) fn into_unchecked_string_vec(items: StringArray) -> Vec<String> {
     items